@@ -1,16 +1,45 @@
 //use anyhow::{anyhow, Result};
 use guest::prelude::*;
 use kubewarden_policy_sdk::wapc_guest as guest;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 mod settings;
 use settings::Settings;
 
 use kubewarden_policy_sdk::{
-    accept_request, mutate_pod_spec_from_request, protocol_version_guest, reject_request,
+    accept_request, mutate_request, protocol_version_guest, reject_request,
     request::ValidationRequest, validate_settings,
 };
 
-use k8s_openapi::api::core::v1 as apicore;
+/// Fields of the Pod spec used by the policy
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    #[serde(default)]
+    containers: Vec<ContainerFields>,
+    init_containers: Option<Vec<ContainerFields>>,
+}
+
+/// Container with its securityContext. The other fields are kept in `other`,
+/// so that the mutation does not drop the fields unknown to k8s-openapi.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct ContainerFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    security_context: Option<SecurityContextFields>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct SecurityContextFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allow_privilege_escalation: Option<bool>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
 
 #[unsafe(no_mangle)]
 pub extern "C" fn wapc_init() {
@@ -21,130 +50,89 @@ pub extern "C" fn wapc_init() {
 
 fn validate(payload: &[u8]) -> CallResult {
     let validation_request = ValidationRequest::<Settings>::new(payload)?;
-    match validation_request.extract_pod_spec_from_object() {
-        Ok(pod_spec) => {
-            if pod_spec.is_none() {
-                return accept_request();
-            }
-            let pod_spec = pod_spec.unwrap();
-            let mut errors = vec![];
-
-            let mutated_init_containers: Option<Vec<apicore::Container>> = match pod_spec
-                .init_containers
-                .clone()
-            {
-                Some(init_containers) => {
-                    if has_allowed_privilege_escalation_container(init_containers.clone()) {
-                        errors.push("one of the init containers has privilege escalation enabled");
-                        None
-                    } else {
-                        patch_containers(
-                            init_containers,
-                            validation_request
-                                .settings
-                                .default_allow_privilege_escalation,
-                        )
-                    }
-                }
-                None => None,
-            };
-            let mutated_containers: Option<Vec<apicore::Container>> =
-                if has_allowed_privilege_escalation_container(pod_spec.containers.clone()) {
-                    errors.push("one of the containers has privilege escalation enabled");
-                    None
-                } else {
-                    patch_containers(
-                        pod_spec.containers.clone(),
-                        validation_request
-                            .settings
-                            .default_allow_privilege_escalation,
-                    )
-                };
-
-            if !errors.is_empty() {
-                return reject_request(Some(errors.join(", ")), None, None, None);
+    let request = &validation_request.request;
+    let mut pod_spec =
+        match partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object) {
+            Ok(Some(pod_spec)) => pod_spec,
+            Ok(None) => return accept_request(),
+            Err(_) => {
+                return reject_request(
+                    Some("Cannot parse validation request".to_string()),
+                    None,
+                    None,
+                    None,
+                );
             }
+        };
 
-            if mutated_containers.is_some() || mutated_init_containers.is_some() {
-                let init_containers =
-                    mutated_init_containers.or_else(|| pod_spec.init_containers.clone());
-                let containers = mutated_containers.unwrap_or_else(|| pod_spec.containers.clone());
-
-                mutate_pod_spec_from_request(
-                    validation_request,
-                    apicore::PodSpec {
-                        init_containers,
-                        containers,
-                        ..pod_spec
-                    },
-                )
-            } else {
-                accept_request()
-            }
-        }
-        Err(_) => reject_request(
-            Some("Cannot parse validation request".to_string()),
-            None,
-            None,
-            None,
-        ),
+    let mut errors = vec![];
+    if has_allowed_privilege_escalation_container(pod_spec.init_containers.iter().flatten()) {
+        errors.push("one of the init containers has privilege escalation enabled");
+    }
+    if has_allowed_privilege_escalation_container(pod_spec.containers.iter()) {
+        errors.push("one of the containers has privilege escalation enabled");
+    }
+    if !errors.is_empty() {
+        return reject_request(Some(errors.join(", ")), None, None, None);
+    }
+
+    let default_allow_privilege_escalation = validation_request
+        .settings
+        .default_allow_privilege_escalation;
+    let mutated_init_containers = patch_containers(
+        pod_spec.init_containers.iter_mut().flatten(),
+        default_allow_privilege_escalation,
+    );
+    let mutated_containers = patch_containers(
+        pod_spec.containers.iter_mut(),
+        default_allow_privilege_escalation,
+    );
+    if mutated_init_containers || mutated_containers {
+        mutate_request(partial_pod_spec::patch(
+            &request.kind.kind,
+            &request.object,
+            &pod_spec,
+        )?)
+    } else {
+        accept_request()
     }
 }
 
-fn has_allowed_privilege_escalation_container(containers: Vec<apicore::Container>) -> bool {
-    containers.into_iter().any(|container| {
-        container.security_context.is_some_and(|security_context| {
-            security_context.allow_privilege_escalation.unwrap_or(false)
-        })
+fn has_allowed_privilege_escalation_container<'a>(
+    mut containers: impl Iterator<Item = &'a ContainerFields>,
+) -> bool {
+    containers.any(|container| {
+        container
+            .security_context
+            .as_ref()
+            .is_some_and(|security_context| {
+                security_context.allow_privilege_escalation.unwrap_or(false)
+            })
     })
 }
 
-fn patch_containers(
-    containers: Vec<apicore::Container>,
+// Disables the privilege escalation of the containers not disabling it.
+// Returns true when a container has been changed.
+fn patch_containers<'a>(
+    containers: impl Iterator<Item = &'a mut ContainerFields>,
     default_allow_privilege_escalation: bool,
-) -> Option<Vec<apicore::Container>> {
+) -> bool {
     if default_allow_privilege_escalation {
         // the default behavior or Kubernetes is to allow privilege escalation
-        return None;
+        return false;
     }
 
     let mut mutations_done = false;
-    let new_containers: Vec<apicore::Container> = containers
-        .iter()
-        .map(|c| {
-            let new_sc = match c.security_context.clone() {
-                Some(sc) => {
-                    if sc.allow_privilege_escalation == Some(false) {
-                        sc
-                    } else {
-                        mutations_done = true;
-                        apicore::SecurityContext {
-                            allow_privilege_escalation: Some(false),
-                            ..sc
-                        }
-                    }
-                }
-                None => {
-                    mutations_done = true;
-                    apicore::SecurityContext {
-                        allow_privilege_escalation: Some(false),
-                        ..Default::default()
-                    }
-                }
-            };
-
-            apicore::Container {
-                security_context: Some(new_sc),
-                ..c.clone()
-            }
-        })
-        .collect();
-
-    if mutations_done {
-        Some(new_containers)
-    } else {
-        None
+    for container in containers {
+        let security_context = container
+            .security_context
+            .get_or_insert_with(SecurityContextFields::default);
+        if security_context.allow_privilege_escalation != Some(false) {
+            security_context.allow_privilege_escalation = Some(false);
+            mutations_done = true;
+        }
     }
+    mutations_done
 }
 
 #[cfg(test)]
@@ -227,7 +215,13 @@ mod tests {
         };
 
         let vr = tc.eval(validate)?;
-        assert!(vr.mutated_object.is_some());
+        let pod = vr.mutated_object.expect("the pod should be mutated");
+        assert_eq!(
+            pod["spec"]["containers"][0]["securityContext"],
+            serde_json::json!({"allowPrivilegeEscalation": false})
+        );
+        // the other fields of the container are left untouched
+        assert!(pod["spec"]["containers"][0]["image"].is_string());
 
         Ok(())
     }
@@ -1,9 +1,9 @@
-use anyhow::{Result, anyhow};
+use anyhow::Result;
 
 use guest::prelude::*;
 use kubewarden_policy_sdk::wapc_guest as guest;
-
-use k8s_openapi::api::core::v1 as apicore;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 extern crate kubewarden_policy_sdk as kubewarden;
 use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
@@ -11,6 +11,25 @@ use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_se
 mod settings;
 use settings::{Ranges, Rule, Settings};
 
+/// Fields of the Pod spec used by the policy
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    security_context: Option<PodSecurityContextFields>,
+}
+
+/// Pod securityContext with its fsGroup. The other fields are kept in
+/// `other`, so that the mutation does not drop the fields unknown to
+/// k8s-openapi.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSecurityContextFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fs_group: Option<i64>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn wapc_init() {
     register_function("validate", validate);
@@ -22,20 +41,18 @@ pub extern "C" fn wapc_init() {
 enum PolicyResponse {
     Accept,
     Reject(String),
-    Mutate(serde_json::Value),
+    Mutate(Value),
 }
 
 fn validate(payload: &[u8]) -> CallResult {
     let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = &validation_request.request;
 
-    let pod = match serde_json::from_value::<apicore::Pod>(validation_request.request.object) {
-        Ok(pod) => pod,
-        Err(_) => return kubewarden::accept_request(),
-    };
-
-    let settings = validation_request.settings;
-
-    match do_validate(pod, settings)? {
+    match do_validate(
+        &request.kind.kind,
+        &request.object,
+        validation_request.settings,
+    )? {
         PolicyResponse::Accept => kubewarden::accept_request(),
         PolicyResponse::Reject(message) => {
             kubewarden::reject_request(Some(message), None, None, None)
@@ -44,48 +61,35 @@ fn validate(payload: &[u8]) -> CallResult {
     }
 }
 
-fn do_validate(pod: apicore::Pod, settings: settings::Settings) -> Result<PolicyResponse> {
-    let pod_spec = pod.spec.ok_or_else(|| anyhow!("invalid pod spec"))?;
+fn do_validate(kind: &str, object: &Value, settings: settings::Settings) -> Result<PolicyResponse> {
+    let Ok(Some(mut pod_spec)) = partial_pod_spec::extract::<PodSpecFields>(kind, object) else {
+        return Ok(PolicyResponse::Accept);
+    };
+    let fs_group = pod_spec
+        .security_context
+        .as_ref()
+        .and_then(|security_context| security_context.fs_group);
 
     match settings.rule {
-        Rule::MustRunAs(ranges) => {
-            let pod_with_defaulted_fs_group = apicore::Pod {
-                spec: Some(apicore::PodSpec {
-                    security_context: Some(apicore::PodSecurityContext {
-                        fs_group: Some(
-                            ranges.ranges.first().unwrap().min, // It is safe to unwrap here because the settings
-                                                                // validation ensure that there is at least one range
-                                                                // in the list
-                        ),
-                        ..apicore::PodSecurityContext::default()
-                    }),
-                    ..pod_spec
-                }),
-                ..pod
-            };
-            if let Some(security_context) = pod_spec.security_context {
-                match security_context.fs_group {
-                    Some(fs_group) => Ok(validate_fs_group(fs_group, ranges)),
-                    None => Ok(PolicyResponse::Mutate(serde_json::to_value(
-                        pod_with_defaulted_fs_group,
-                    )?)),
-                }
-            } else {
-                Ok(PolicyResponse::Mutate(serde_json::to_value(
-                    pod_with_defaulted_fs_group,
+        Rule::MustRunAs(ranges) => match fs_group {
+            Some(fs_group) => Ok(validate_fs_group(fs_group, ranges)),
+            None => {
+                // It is safe to unwrap here because the settings validation
+                // ensure that there is at least one range in the list
+                let default_fs_group = ranges.ranges.first().unwrap().min;
+                pod_spec
+                    .security_context
+                    .get_or_insert_with(PodSecurityContextFields::default)
+                    .fs_group = Some(default_fs_group);
+                Ok(PolicyResponse::Mutate(partial_pod_spec::patch(
+                    kind, object, &pod_spec,
                 )?))
             }
-        }
-        Rule::MayRunAs(ranges) => {
-            if let Some(security_context) = pod_spec.security_context {
-                match security_context.fs_group {
-                    Some(fs_group) => Ok(validate_fs_group(fs_group, ranges)),
-                    None => Ok(PolicyResponse::Accept),
-                }
-            } else {
-                Ok(PolicyResponse::Accept)
-            }
-        }
+        },
+        Rule::MayRunAs(ranges) => match fs_group {
+            Some(fs_group) => Ok(validate_fs_group(fs_group, ranges)),
+            None => Ok(PolicyResponse::Accept),
+        },
         Rule::RunAsAny => Ok(PolicyResponse::Accept),
     }
 }
@@ -106,12 +110,18 @@ fn validate_fs_group(fs_group: i64, ranges: Ranges) -> PolicyResponse {
 mod tests {
     use super::*;
 
+    use k8s_openapi::api::core::v1 as apicore;
+    use serde_json::json;
     use settings::Range;
 
+    fn do_validate_pod(pod: apicore::Pod, settings: Settings) -> Result<PolicyResponse> {
+        do_validate("Pod", &serde_json::to_value(pod)?, settings)
+    }
+
     #[test]
     fn run_as_any_always_accepts() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec::default()),
                     ..apicore::Pod::default()
@@ -129,7 +139,7 @@ mod tests {
     #[test]
     fn may_run_as_accepts_with_empty_security_context() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec::default()),
                     ..apicore::Pod::default()
@@ -152,7 +162,7 @@ mod tests {
     #[test]
     fn may_run_as_accepts_with_empty_fsgroup() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec {
                         security_context: Some(apicore::PodSecurityContext::default()),
@@ -178,7 +188,7 @@ mod tests {
     #[test]
     fn may_run_as_accepts_with_fsgroup_in_range() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec {
                         security_context: Some(apicore::PodSecurityContext {
@@ -207,7 +217,7 @@ mod tests {
     #[test]
     fn may_run_as_accepts_with_fsgroup_in_some_range() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec {
                         security_context: Some(apicore::PodSecurityContext {
@@ -239,7 +249,7 @@ mod tests {
     #[test]
     fn may_run_as_rejects_with_fsgroup_in_no_range() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec {
                         security_context: Some(apicore::PodSecurityContext {
@@ -268,7 +278,7 @@ mod tests {
     #[test]
     fn must_run_as_mutates_with_empty_security_context() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec::default()),
                     ..apicore::Pod::default()
@@ -300,7 +310,7 @@ mod tests {
     #[test]
     fn must_run_as_mutates_with_empty_security_context_and_unordered_ranges() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec::default()),
                     ..apicore::Pod::default()
@@ -338,7 +348,7 @@ mod tests {
     #[test]
     fn must_run_as_mutates_with_empty_fsgroup() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec {
                         security_context: Some(apicore::PodSecurityContext::default()),
@@ -373,7 +383,7 @@ mod tests {
     #[test]
     fn must_run_as_mutates_with_empty_fsgroup_and_unordered_ranges() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec {
                         security_context: Some(apicore::PodSecurityContext::default()),
@@ -414,7 +424,7 @@ mod tests {
     #[test]
     fn must_run_as_accepts_with_fsgroup_in_range() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec {
                         security_context: Some(apicore::PodSecurityContext {
@@ -443,7 +453,7 @@ mod tests {
     #[test]
     fn must_run_as_accepts_with_fsgroup_in_some_range() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec {
                         security_context: Some(apicore::PodSecurityContext {
@@ -475,7 +485,7 @@ mod tests {
     #[test]
     fn must_run_as_rejects_with_fsgroup_in_no_range() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec {
                         security_context: Some(apicore::PodSecurityContext {
@@ -500,4 +510,44 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn must_run_as_keeps_unknown_fields() -> Result<()> {
+        let pod = json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "spec": {
+                "containers": [{"name": "nginx", "image": "nginx", "fieldFromTheFuture": 1}],
+                "securityContext": {"runAsUser": 1000, "fieldFromTheFuture": true},
+                "fieldFromTheFuture": {"enabled": true}
+            }
+        });
+        let settings = Settings {
+            rule: Rule::MustRunAs(Ranges {
+                ranges: vec![Range {
+                    min: 1000,
+                    max: 2000,
+                }],
+            }),
+        };
+
+        assert_eq!(
+            do_validate("Pod", &pod, settings)?,
+            PolicyResponse::Mutate(json!({
+                "apiVersion": "v1",
+                "kind": "Pod",
+                "spec": {
+                    "containers": [{"name": "nginx", "image": "nginx", "fieldFromTheFuture": 1}],
+                    "securityContext": {
+                        "fsGroup": 1000,
+                        "runAsUser": 1000,
+                        "fieldFromTheFuture": true
+                    },
+                    "fieldFromTheFuture": {"enabled": true}
+                }
+            }))
+        );
+
+        Ok(())
+    }
 }
@@ -26,6 +26,20 @@
 	[ $(expr "$output" : '.*The following invalid annotations were found: cc-center.*') -ne 0 ]
 }
 
+@test "reject because annotation matches a denylist glob pattern" {
+	run kwctl run annotated-policy.wasm \
+		-r test_data/ingress.json \
+		--settings-json '{"criteria": "doesNotContainAnyOf" ,"values": ["cc-*", "bar"]}'
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	# request rejected
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*allowed.*false') -ne 0 ]
+	[ $(expr "$output" : '.*The following invalid annotations were found: cc-center.*') -ne 0 ]
+}
+
 @test "reject because a required annotation does not exist" {
 	run kwctl run policy.wasm \
 		-r test_data/ingress.json \
@@ -6,10 +6,11 @@ use criteria_policy_base::{
         accept_request, protocol_version_guest, reject_request, request::ValidationRequest,
         validate_settings, wapc_guest as guest,
     },
+    settings::BaseSettings,
     validate::validate_values,
 };
 use guest::prelude::*;
-use settings::Settings;
+use settings::{Settings, glob_pattern_regex, is_glob_pattern};
 
 mod settings;
 
@@ -24,13 +25,42 @@ fn validate_annotations(
     settings: &Settings,
     resource_annots: &HashSet<String>,
 ) -> Result<(), Vec<String>> {
+    let base_settings = match &settings.0 {
+        BaseSettings::DoesNotContainAnyOf { values } => BaseSettings::DoesNotContainAnyOf {
+            values: denied_annotations(values, resource_annots),
+        },
+        base_settings => base_settings.clone(),
+    };
     validate_values(
-        &settings.0,
+        &base_settings,
         &resource_annots.iter().cloned().collect::<Vec<_>>(),
     )
     .map_err(|e| vec![e.to_string()])
 }
 
+/// Returns the denied annotation names, with the glob patterns replaced by the
+/// annotations of the resource they match
+fn denied_annotations(
+    values: &HashSet<String>,
+    resource_annots: &HashSet<String>,
+) -> HashSet<String> {
+    let patterns: Vec<_> = values
+        .iter()
+        .filter(|value| is_glob_pattern(value))
+        .map(|pattern| glob_pattern_regex(pattern))
+        .collect();
+    values
+        .iter()
+        .filter(|value| !is_glob_pattern(value))
+        .chain(
+            resource_annots
+                .iter()
+                .filter(|annot| patterns.iter().any(|pattern| pattern.is_match(annot))),
+        )
+        .cloned()
+        .collect()
+}
+
 fn get_resource_annotation_keys(
     validation_request: &ValidationRequest<Settings>,
 ) -> HashSet<String> {
@@ -175,4 +205,43 @@ mod tests {
         let result = crate::validate_annotations(&settings.clone(), &annots).is_ok();
         assert_eq!(result, expected);
     }
+
+    #[rstest]
+    #[case::prefix(&["kubectl.kubernetes.io/*"], &["kubectl.kubernetes.io/last-applied-configuration", "cost-center"], false)]
+    #[case::subdomain(&["*.internal.acme.com/*"], &["billing.internal.acme.com/owner"], false)]
+    #[case::no_match(&["*.internal.acme.com/*", "foo"], &["acme.com/owner", "cost-center"], true)]
+    #[case::exact_name(&["kubectl.kubernetes.io/*", "foo"], &["foo"], false)]
+    fn test_validate_annotations_glob_patterns(
+        #[case] values: &[&str],
+        #[case] annots: &[&str],
+        #[case] expected: bool,
+    ) {
+        let settings = Settings(BaseSettings::DoesNotContainAnyOf {
+            values: values.iter().map(|v| v.to_string()).collect(),
+        });
+        assert!(settings.validate().is_ok());
+
+        let annots = annots.iter().map(|a| a.to_string()).collect();
+        assert_eq!(validate_annotations(&settings, &annots).is_ok(), expected);
+    }
+
+    #[test]
+    fn test_validate_annotations_glob_pattern_message() {
+        let settings = Settings(BaseSettings::DoesNotContainAnyOf {
+            values: HashSet::from(["kubectl.kubernetes.io/*".to_string()]),
+        });
+        let annots = HashSet::from([
+            "kubectl.kubernetes.io/last-applied-configuration".to_string(),
+            "cost-center".to_string(),
+        ]);
+
+        let errors =
+            validate_annotations(&settings, &annots).expect_err("the annotation is denied");
+        assert!(
+            errors[0].ends_with(
+                "The following invalid annotations were found: kubectl.kubernetes.io/last-applied-configuration"
+            ),
+            "unexpected error: {errors:?}"
+        );
+    }
 }
@@ -23,6 +23,23 @@ impl Default for Settings {
 // with the subdomain `/`escaped for a Rust literal
 const ANNOTATIONS_NAME_REGEX: &str = r"^([a-z0-9]([-a-z0-9]*[a-z0-9])?(\.[a-z0-9]([-a-z0-9]*[a-z0-9])?)*/)?[A-Za-z0-9]([A-Za-z0-9_.-]*[A-Za-z0-9])?$";
 
+/// Returns true when the annotation name is a glob pattern, like
+/// `kubectl.kubernetes.io/*`
+pub(crate) fn is_glob_pattern(name: &str) -> bool {
+    name.contains('*')
+}
+
+/// Returns the regular expression matching the annotation names of the glob
+/// pattern. `*` matches any sequence of characters, `.` and `/` included.
+pub(crate) fn glob_pattern_regex(pattern: &str) -> Regex {
+    let pattern = pattern
+        .split('*')
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join(".*");
+    Regex::new(&format!("^{pattern}$")).expect("the escaped glob pattern is a valid regex")
+}
+
 impl kubewarden::settings::Validatable for Settings {
     fn validate(&self) -> Result<(), String> {
         // this will fail if the annotations key list is empty
@@ -30,12 +47,23 @@ impl kubewarden::settings::Validatable for Settings {
 
         let annots = self.0.values();
 
+        // The glob patterns are supported only by the denylist
+        if !matches!(self.0, BaseSettings::DoesNotContainAnyOf { .. })
+            && annots.iter().any(|annot| is_glob_pattern(annot))
+        {
+            return Err(
+                "Glob patterns are supported only by the doesNotContainAnyOf criteria".to_string(),
+            );
+        }
+
         // Validate that the annotations names are valid.
         let annotations_name_regex = Regex::new(ANNOTATIONS_NAME_REGEX).unwrap();
         let invalid_annot: Vec<String> = annots
             .iter()
             .filter_map(|annot| {
-                if annotations_name_regex.is_match(annot) {
+                // a glob pattern is valid when the names it matches can be
+                // valid annotation names
+                if annotations_name_regex.is_match(&annot.replace('*', "x")) {
                     return None;
                 }
                 Some(annot.to_string())
@@ -86,4 +114,41 @@ mod tests {
         });
         assert_eq!(settings.validate().is_ok(), is_ok);
     }
+
+    #[rstest]
+    #[case::prefix(vec!["kubectl.kubernetes.io/*"], true)]
+    #[case::subdomains(vec!["*.internal.acme.com/*"], true)]
+    #[case::all(vec!["*"], true)]
+    #[case::invalid_pattern(vec!["example.com/*/*"], false)]
+    fn test_glob_pattern_validation(#[case] variables: Vec<&str>, #[case] is_ok: bool) {
+        let settings = Settings(BaseSettings::DoesNotContainAnyOf {
+            values: variables
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<HashSet<String>>(),
+        });
+        assert_eq!(settings.validate().is_ok(), is_ok);
+    }
+
+    #[test]
+    fn test_glob_pattern_criteria() {
+        let settings = Settings(BaseSettings::DoesNotContainOtherThan {
+            values: HashSet::from(["kubectl.kubernetes.io/*".to_string()]),
+        });
+        assert!(settings.validate().is_err());
+    }
+
+    #[rstest]
+    #[case::prefix(
+        "kubectl.kubernetes.io/*",
+        "kubectl.kubernetes.io/last-applied-configuration",
+        true
+    )]
+    #[case::other_prefix("kubectl.kubernetes.io/*", "kubernetes.io/change-cause", false)]
+    #[case::subdomain("*.internal.acme.com/*", "billing.internal.acme.com/owner", true)]
+    #[case::domain_only("*.internal.acme.com/*", "internal.acme.com/owner", false)]
+    #[case::literal_dot("example.com/*", "exampleXcom/owner", false)]
+    fn test_glob_pattern_regex(#[case] pattern: &str, #[case] name: &str, #[case] matches: bool) {
+        assert_eq!(glob_pattern_regex(pattern).is_match(name), matches);
+    }
 }
@@ -0,0 +1,61 @@
+#!/usr/bin/env bats
+
+@test "Reject a bare Pod" {
+	run kwctl run --request-path test_data/pod_bare.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*user 'developer' cannot create a Pod not owned by a controller.*") -ne 0 ]
+}
+
+@test "Accept a Pod owned by a ReplicaSet" {
+	run kwctl run --request-path test_data/pod_controlled.json --settings-json '{"allowedGroups": []}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a bare ReplicaSet" {
+	run kwctl run --request-path test_data/replicaset_bare.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Accept a ReplicaSet owned by a Deployment" {
+	run kwctl run --request-path test_data/replicaset_controlled.json --settings-json '{"allowedGroups": []}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a bare Pod created by an allowed user" {
+	run kwctl run --request-path test_data/pod_bare.json --settings-json '{"allowedUsers": ["developer"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_bare.json --settings-json '{"allowedUsers": [""]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
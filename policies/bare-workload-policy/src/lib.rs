@@ -0,0 +1,145 @@
+use guest::prelude::*;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use user_allowlist::UserAllowlist;
+use workloads::is_controlled;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+
+    let alternative = match (request.kind.group.as_str(), request.kind.kind.as_str()) {
+        ("", "Pod") => "a Deployment or a Job",
+        ("apps", "ReplicaSet") => "a Deployment",
+        _ => return kubewarden::accept_request(),
+    };
+    let metadata = match request
+        .object
+        .get("metadata")
+        .map(|metadata| serde_json::from_value::<ObjectMeta>(metadata.clone()))
+    {
+        Some(Ok(metadata)) => metadata,
+        _ => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    if is_controlled(&metadata) {
+        return kubewarden::accept_request();
+    }
+    let username = request.user_info.username.unwrap_or_default();
+    let groups = request.user_info.groups.unwrap_or_default();
+    if validation_request
+        .settings
+        .is_user_allowed(&username, &groups)
+    {
+        return kubewarden::accept_request();
+    }
+
+    kubewarden::reject_request(
+        Some(format!(
+            "{kind} '{}': user '{username}' cannot create a {kind} not owned by a controller, use {alternative} instead",
+            request.name,
+            kind = request.kind.kind
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::bare_pod("test_data/pod_bare.json", Settings::default(), false)]
+    #[case::controlled_pod(
+        "test_data/pod_controlled.json",
+        Settings {
+            allowed_groups: [].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::bare_replicaset("test_data/replicaset_bare.json", Settings::default(), false)]
+    #[case::controlled_replicaset(
+        "test_data/replicaset_controlled.json",
+        Settings {
+            allowed_groups: [].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::allowed_user(
+        "test_data/pod_bare.json",
+        Settings {
+            allowed_users: ["developer".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::allowed_group(
+        "test_data/replicaset_bare.json",
+        Settings {
+            allowed_groups: ["system:authenticated".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::bare_pod(
+        "test_data/pod_bare.json",
+        "Pod 'web': user 'developer' cannot create a Pod not owned by a controller, use a Deployment or a Job instead"
+    )]
+    #[case::bare_replicaset(
+        "test_data/replicaset_bare.json",
+        "ReplicaSet 'web': user 'developer' cannot create a ReplicaSet not owned by a controller, use a Deployment instead"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+}
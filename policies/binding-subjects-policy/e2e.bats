@@ -0,0 +1,43 @@
+#!/usr/bin/env bats
+
+@test "Reject a ClusterRoleBinding granting permissions to anonymous users" {
+	run kwctl run --request-path test_data/clusterrolebinding_anonymous.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*ClusterRoleBinding 'public-healthz' has invalid subjects: User 'system:anonymous' is not allowed.*") -ne 0 ]
+}
+
+@test "Reject a RoleBinding referencing a ServiceAccount of another namespace" {
+	run kwctl run --request-path test_data/rolebinding_cross_namespace.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*ServiceAccount 'prometheus' belongs to the namespace 'monitoring' instead of 'team-a'.*") -ne 0 ]
+}
+
+@test "Accept a RoleBinding referencing a ServiceAccount of an allowed namespace" {
+	run kwctl run --request-path test_data/rolebinding_cross_namespace.json --settings-json '{"allowedServiceAccountNamespaces": ["monitoring"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a RoleBinding referencing ServiceAccounts of other namespaces when allowed" {
+	run kwctl run --request-path test_data/rolebinding_cross_namespace.json --settings-json '{"allowCrossNamespaceServiceAccounts": true}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
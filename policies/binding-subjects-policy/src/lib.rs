@@ -0,0 +1,226 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::rbac::v1::{ClusterRoleBinding, RoleBinding, Subject};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+const SERVICE_ACCOUNT_USER_PREFIX: &str = "system:serviceaccount:";
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let (kind, name, namespace, subjects) = match request.kind.kind.as_str() {
+        "RoleBinding" => {
+            let binding = serde_json::from_value::<RoleBinding>(request.object)?;
+            let namespace = binding.metadata.namespace.unwrap_or(request.namespace);
+            (
+                "RoleBinding",
+                binding.metadata.name,
+                Some(namespace),
+                binding.subjects,
+            )
+        }
+        "ClusterRoleBinding" => {
+            let binding = serde_json::from_value::<ClusterRoleBinding>(request.object)?;
+            (
+                "ClusterRoleBinding",
+                binding.metadata.name,
+                None,
+                binding.subjects,
+            )
+        }
+        _ => return kubewarden::accept_request(),
+    };
+
+    let errors = find_invalid_subjects(
+        settings,
+        namespace.as_deref(),
+        &subjects.unwrap_or_default(),
+    );
+    if !errors.is_empty() {
+        return kubewarden::reject_request(
+            Some(format!(
+                "{kind} '{}' has invalid subjects: {}",
+                name.unwrap_or_default(),
+                errors.join("; ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+// Returns a description of the subjects that cannot be used by the binding.
+// The namespace is set only for RoleBindings, the ClusterRoleBindings can
+// reference the ServiceAccounts of all the namespaces.
+fn find_invalid_subjects(
+    settings: &Settings,
+    namespace: Option<&str>,
+    subjects: &[Subject],
+) -> Vec<String> {
+    subjects
+        .iter()
+        .filter_map(|subject| match subject.kind.as_str() {
+            "User" if settings.denied_users.contains(&subject.name) => {
+                Some(format!("User '{}' is not allowed", subject.name))
+            }
+            "Group" if settings.denied_groups.contains(&subject.name) => {
+                Some(format!("Group '{}' is not allowed", subject.name))
+            }
+            // ServiceAccounts can be referenced also as Users, using their
+            // `system:serviceaccount:<namespace>:<name>` username
+            "User" => subject
+                .name
+                .strip_prefix(SERVICE_ACCOUNT_USER_PREFIX)
+                .and_then(|service_account| service_account.split_once(':'))
+                .and_then(|(service_account_namespace, _)| {
+                    validate_service_account_namespace(
+                        settings,
+                        namespace,
+                        service_account_namespace,
+                    )
+                })
+                .map(|error| format!("User '{}' {error}", subject.name)),
+            "ServiceAccount" => subject
+                .namespace
+                .as_deref()
+                .and_then(|service_account_namespace| {
+                    validate_service_account_namespace(
+                        settings,
+                        namespace,
+                        service_account_namespace,
+                    )
+                })
+                .map(|error| format!("ServiceAccount '{}' {error}", subject.name)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn validate_service_account_namespace(
+    settings: &Settings,
+    namespace: Option<&str>,
+    service_account_namespace: &str,
+) -> Option<String> {
+    let namespace = namespace?;
+    if settings.is_service_account_allowed(namespace, service_account_namespace) {
+        None
+    } else {
+        Some(format!(
+            "belongs to the namespace '{service_account_namespace}' instead of '{namespace}'"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn subject(kind: &str, name: &str, namespace: Option<&str>) -> Subject {
+        Subject {
+            kind: kind.to_owned(),
+            name: name.to_owned(),
+            namespace: namespace.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::valid_subjects(
+        Some("team-a"),
+        vec![
+            subject("User", "alice", None),
+            subject("Group", "developers", None),
+            subject("ServiceAccount", "default", Some("team-a")),
+        ],
+        vec![]
+    )]
+    #[case::anonymous(
+        None,
+        vec![subject("User", "system:anonymous", None)],
+        vec!["User 'system:anonymous' is not allowed"]
+    )]
+    #[case::unauthenticated(
+        Some("team-a"),
+        vec![subject("Group", "system:unauthenticated", None)],
+        vec!["Group 'system:unauthenticated' is not allowed"]
+    )]
+    #[case::cross_namespace_service_account(
+        Some("team-a"),
+        vec![subject("ServiceAccount", "default", Some("team-b"))],
+        vec!["ServiceAccount 'default' belongs to the namespace 'team-b' instead of 'team-a'"]
+    )]
+    #[case::cross_namespace_service_account_user(
+        Some("team-a"),
+        vec![subject("User", "system:serviceaccount:team-b:default", None)],
+        vec!["User 'system:serviceaccount:team-b:default' belongs to the namespace 'team-b' instead of 'team-a'"]
+    )]
+    #[case::cluster_role_binding_service_account(
+        None,
+        vec![subject("ServiceAccount", "default", Some("team-b"))],
+        vec![]
+    )]
+    fn invalid_subjects(
+        #[case] namespace: Option<&str>,
+        #[case] subjects: Vec<Subject>,
+        #[case] expected: Vec<&str>,
+    ) {
+        assert_eq!(
+            find_invalid_subjects(&Settings::default(), namespace, &subjects),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::anonymous(
+        "test_data/clusterrolebinding_anonymous.json",
+        Settings::default(),
+        false
+    )]
+    #[case::cross_namespace(
+        "test_data/rolebinding_cross_namespace.json",
+        Settings::default(),
+        false
+    )]
+    #[case::cross_namespace_allowed(
+        "test_data/rolebinding_cross_namespace.json",
+        Settings {
+            allowed_service_account_namespaces: ["monitoring".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
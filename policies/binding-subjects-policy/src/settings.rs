@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Users that cannot be subjects of the bindings
+    pub denied_users: HashSet<String>,
+    /// Groups that cannot be subjects of the bindings
+    pub denied_groups: HashSet<String>,
+    /// Allow RoleBindings to reference ServiceAccounts defined inside of
+    /// other namespaces
+    pub allow_cross_namespace_service_accounts: bool,
+    /// Namespaces whose ServiceAccounts can be referenced by the
+    /// RoleBindings of all the namespaces
+    pub allowed_service_account_namespaces: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            denied_users: ["system:anonymous".to_owned()].into(),
+            denied_groups: ["system:unauthenticated".to_owned()].into(),
+            allow_cross_namespace_service_accounts: false,
+            allowed_service_account_namespaces: HashSet::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when a RoleBinding defined inside of `namespace` can
+    /// reference a ServiceAccount of `service_account_namespace`
+    pub fn is_service_account_allowed(
+        &self,
+        namespace: &str,
+        service_account_namespace: &str,
+    ) -> bool {
+        self.allow_cross_namespace_service_accounts
+            || namespace == service_account_namespace
+            || self
+                .allowed_service_account_namespaces
+                .contains(service_account_namespace)
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.denied_users.contains("") {
+            return Err("deniedUsers cannot contain empty values".to_owned());
+        }
+        if self.denied_groups.contains("") {
+            return Err("deniedGroups cannot contain empty values".to_owned());
+        }
+        if self.allowed_service_account_namespaces.contains("") {
+            return Err("allowedServiceAccountNamespaces cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::same_namespace(Settings::default(), "team-a", "team-a", true)]
+    #[case::other_namespace(Settings::default(), "team-a", "team-b", false)]
+    #[case::allowed_namespace(
+        Settings {
+            allowed_service_account_namespaces: ["monitoring".to_owned()].into(),
+            ..Default::default()
+        },
+        "team-a",
+        "monitoring",
+        true
+    )]
+    #[case::cross_namespace_allowed(
+        Settings {
+            allow_cross_namespace_service_accounts: true,
+            ..Default::default()
+        },
+        "team-a",
+        "team-b",
+        true
+    )]
+    fn service_account_allowed(
+        #[case] settings: Settings,
+        #[case] namespace: &str,
+        #[case] service_account_namespace: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            settings.is_service_account_allowed(namespace, service_account_namespace),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::no_denied_subjects(r#"{"deniedUsers": [], "deniedGroups": []}"#, true)]
+    #[case::empty_user(r#"{"deniedUsers": [""]}"#, false)]
+    #[case::empty_group(r#"{"deniedGroups": [""]}"#, false)]
+    #[case::empty_namespace(r#"{"allowedServiceAccountNamespaces": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
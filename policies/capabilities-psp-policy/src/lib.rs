@@ -1,6 +1,5 @@
 use guest::prelude::*;
-use k8s_openapi::api::core::v1::PodSpec;
-use kubewarden_policy_sdk::{mutate_pod_spec_from_request, wapc_guest as guest};
+use kubewarden_policy_sdk::wapc_guest as guest;
 
 mod validate;
 use validate::validate_added_caps;
@@ -8,12 +7,14 @@ use validate::validate_added_caps;
 mod mutate;
 use mutate::patch_object;
 
+mod pod_spec;
+
 mod settings;
 use settings::Settings;
 
 use kubewarden_policy_sdk::{
-    accept_request, protocol_version_guest, reject_request, request::ValidationRequest,
-    validate_settings,
+    accept_request, mutate_request, protocol_version_guest, reject_request,
+    request::ValidationRequest, validate_settings,
 };
 
 #[unsafe(no_mangle)]
@@ -28,9 +29,13 @@ fn validate(payload: &[u8]) -> CallResult {
 
     match validate_added_caps(&validation_req) {
         Ok(()) => {
-            if let Some(patched_pod_spec) = patch_object(&validation_req)? {
-                let pod_spec = serde_json::from_value::<PodSpec>(patched_pod_spec)?;
-                mutate_pod_spec_from_request(validation_req, pod_spec)
+            if let Some(pod_spec) = patch_object(&validation_req)? {
+                let request = &validation_req.request;
+                mutate_request(partial_pod_spec::patch(
+                    &request.kind.kind,
+                    &request.object,
+                    &pod_spec,
+                )?)
             } else {
                 accept_request()
             }
@@ -1,64 +1,59 @@
 use anyhow::{Result, anyhow};
-use k8s_openapi::api::core::v1::{Capabilities, PodSpec, SecurityContext};
+use k8s_openapi::api::core::v1::Capabilities;
 use kubewarden_policy_sdk::request::ValidationRequest;
 use std::collections::HashSet;
 
+use crate::pod_spec::{ContainerFields, PodSpecFields, SecurityContextFields};
 use crate::settings::Settings;
 
+/// Returns the Pod spec fields to write back into the object, `None` when
+/// no container has to be changed
 pub(crate) fn patch_object(
     validation_req: &ValidationRequest<Settings>,
-) -> Result<Option<serde_json::Value>> {
-    let pod_spec_option = validation_req
-        .extract_pod_spec_from_object()
-        .map_err(|e| anyhow!("Error deserializing Pod specification: {:?}", e))?;
-
-    let mut pod_spec: PodSpec;
-    if let Some(pod_spec_immut) = pod_spec_option {
-        pod_spec = pod_spec_immut;
+) -> Result<Option<PodSpecFields>> {
+    let request = &validation_req.request;
+    let Some(mut pod_spec) =
+        partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object)
+            .map_err(|e| anyhow!("Error deserializing Pod specification: {:?}", e))?
+    else {
+        return Ok(None);
+    };
+
+    let changed_containers =
+        patch_containers(pod_spec.containers.iter_mut(), &validation_req.settings);
+    let changed_init_containers = patch_containers(
+        pod_spec.init_containers.iter_mut().flatten(),
+        &validation_req.settings,
+    );
+
+    if changed_containers || changed_init_containers {
+        Ok(Some(pod_spec))
     } else {
-        return Ok(Some(validation_req.request.object.clone()));
+        Ok(None)
     }
+}
 
+/// Patches the securityContext of the containers, returns whether one of them
+/// changed. The containers left untouched keep their securityContext.
+fn patch_containers<'a>(
+    containers: impl Iterator<Item = &'a mut ContainerFields>,
+    settings: &Settings,
+) -> bool {
     let mut changed = false;
-
-    for c in pod_spec.containers.iter_mut() {
-        let sc =
-            patch_container_security_context(c.security_context.clone(), &validation_req.settings);
-        if sc.is_some() {
+    for c in containers {
+        if let Some(sc) = patch_container_security_context(c.security_context.clone(), settings) {
             changed = true;
+            c.security_context = Some(sc);
         }
-        c.security_context = sc;
-    }
-
-    if pod_spec.init_containers.is_some() {
-        let mut init_containers = pod_spec.init_containers.clone().unwrap();
-        for c in init_containers.iter_mut() {
-            let sc = patch_container_security_context(
-                c.security_context.clone(),
-                &validation_req.settings,
-            );
-            if sc.is_some() {
-                changed = true;
-            }
-            c.security_context = sc;
-        }
-        pod_spec.init_containers = Some(init_containers);
-    }
-
-    if changed {
-        serde_json::to_value(pod_spec)
-            .map(Some)
-            .map_err(|e| anyhow!("Error serializing modified Pod: {:?}", e.to_string()))
-    } else {
-        Ok(None)
     }
+    changed
 }
 
 fn patch_container_security_context(
-    security_context: Option<SecurityContext>,
+    security_context: Option<SecurityContextFields>,
     settings: &Settings,
-) -> Option<SecurityContext> {
-    let mut sc = security_context.unwrap_or(SecurityContext {
+) -> Option<SecurityContextFields> {
+    let mut sc = security_context.unwrap_or(SecurityContextFields {
         capabilities: Some(Capabilities {
             add: Some(Vec::<String>::new()),
             drop: Some(Vec::<String>::new()),
@@ -122,9 +117,13 @@ mod tests {
 
     fn test_mutate(payload: serde_json::Value, expected_pod_spec: serde_json::Value) -> Result<()> {
         let validation_req = ValidationRequest::<Settings>::new(payload.to_string().as_bytes())?;
-        let mutated = patch_object(&validation_req)?;
+        let request = &validation_req.request;
+        let pod_spec = patch_object(&validation_req)?.expect("the Pod spec should be mutated");
+        let mutated = partial_pod_spec::patch(&request.kind.kind, &request.object, &pod_spec)?;
+        let pointer = partial_pod_spec::pod_spec_pointer(&request.kind.kind)
+            .expect("the kind should embed a Pod spec");
 
-        assert_json_eq!(mutated, expected_pod_spec);
+        assert_json_eq!(mutated.pointer(pointer), Some(&expected_pod_spec));
 
         Ok(())
     }
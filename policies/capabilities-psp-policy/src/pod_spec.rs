@@ -0,0 +1,32 @@
+use k8s_openapi::api::core::v1::Capabilities;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Fields of the Pod spec used by the policy
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PodSpecFields {
+    #[serde(default)]
+    pub(crate) containers: Vec<ContainerFields>,
+    pub(crate) init_containers: Option<Vec<ContainerFields>>,
+}
+
+/// Container with its securityContext. The other fields are kept in `other`,
+/// so that the mutation does not drop the fields unknown to k8s-openapi.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ContainerFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) security_context: Option<SecurityContextFields>,
+    #[serde(flatten)]
+    pub(crate) other: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SecurityContextFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) capabilities: Option<Capabilities>,
+    #[serde(flatten)]
+    pub(crate) other: Map<String, Value>,
+}
@@ -1,14 +1,14 @@
 use anyhow::{Result, anyhow};
-use k8s_openapi::api::core::v1::PodSpec;
 use std::collections::HashSet;
 
+use crate::pod_spec::PodSpecFields;
 use crate::settings::Settings;
 
 use kubewarden_policy_sdk::request::ValidationRequest;
 
 pub(crate) fn validate_added_caps(validation_req: &ValidationRequest<Settings>) -> Result<()> {
-    let pod_spec = validation_req
-        .extract_pod_spec_from_object()
+    let request = &validation_req.request;
+    let pod_spec = partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object)
         .map_err(|e| anyhow!("Error deserializing Pod specification: {:?}", e))?;
 
     let cap_add;
@@ -48,7 +48,7 @@ pub(crate) fn validate_added_caps(validation_req: &ValidationRequest<Settings>)
     Ok(())
 }
 
-fn get_caps(pod_spec: &PodSpec) -> Result<HashSet<String>> {
+fn get_caps(pod_spec: &PodSpecFields) -> Result<HashSet<String>> {
     let mut caps = HashSet::<String>::new();
 
     for c in pod_spec.containers.iter() {
@@ -0,0 +1,54 @@
+#!/usr/bin/env bats
+
+@test "Accept a client certificate request for a developer" {
+	run kwctl run --request-path test_data/csr_developer_client.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a client certificate request for an administrator" {
+	run kwctl run --request-path test_data/csr_admin_client.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*client certificates for the username 'kubernetes-admin' (matching '\*-admin') cannot be requested.*") -ne 0 ]
+	[ $(expr "$output" : ".*client certificates for the group 'system:masters' (matching 'system:masters') cannot be requested.*") -ne 0 ]
+}
+
+@test "Reject a certificate request to a signer not allowed" {
+	run kwctl run --request-path test_data/csr_developer_client.json --settings-json '{"allowedSigners": {"developers": ["example.com/signer"]}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*user 'jane' cannot request certificates to the signer 'kubernetes.io/kube-apiserver-client'.*") -ne 0 ]
+}
+
+@test "Reject a certificate request above the maximum expiration" {
+	run kwctl run --request-path test_data/csr_developer_client.json --settings-json '{"maxExpirationSeconds": 3600}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*expirationSeconds 86400 is greater than the maximum of 3600.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/csr_developer_client.json --settings-json '{"maxExpirationSeconds": 60}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
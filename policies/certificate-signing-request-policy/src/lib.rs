@@ -0,0 +1,210 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::certificates::v1::CertificateSigningRequest;
+use x509_parser::{
+    certification_request::X509CertificationRequest, pem::parse_x509_pem, prelude::FromDer,
+};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::{KUBE_APISERVER_CLIENT_SIGNER, Settings};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    if request.kind.kind != "CertificateSigningRequest" {
+        return kubewarden::accept_request();
+    }
+    let csr = serde_json::from_value::<CertificateSigningRequest>(request.object)?;
+    let groups = request.user_info.groups.unwrap_or_default();
+
+    let mut errors = Vec::new();
+    let signer = csr.spec.signer_name.as_str();
+    if !settings.is_signer_allowed(signer, &groups) {
+        errors.push(format!(
+            "user '{}' cannot request certificates to the signer '{signer}'",
+            request.user_info.username.unwrap_or_default()
+        ));
+    }
+    if signer == KUBE_APISERVER_CLIENT_SIGNER {
+        errors.extend(validate_client_subject(&csr.spec.request.0, settings));
+    }
+    if let Some(max_expiration_seconds) = settings.max_expiration_seconds {
+        match csr.spec.expiration_seconds {
+            Some(expiration_seconds) if expiration_seconds > max_expiration_seconds => {
+                errors.push(format!(
+                    "expirationSeconds {expiration_seconds} is greater than the maximum of {max_expiration_seconds}"
+                ))
+            }
+            Some(_) => {}
+            None => errors.push(format!(
+                "expirationSeconds must be set, with a maximum of {max_expiration_seconds}"
+            )),
+        }
+    }
+
+    if errors.is_empty() {
+        return kubewarden::accept_request();
+    }
+    kubewarden::reject_request(
+        Some(format!(
+            "CertificateSigningRequest '{}' is not allowed: {}",
+            csr.metadata.name.unwrap_or_default(),
+            errors.join("; ")
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+// Returns the reasons why the subject of a client certificate request is not
+// allowed. The common name of the subject is the username, the organizations
+// are the groups of the user.
+fn validate_client_subject(pem: &[u8], settings: &Settings) -> Vec<String> {
+    let der = match parse_x509_pem(pem) {
+        Ok((_, pem)) => pem.contents,
+        Err(e) => return vec![format!("cannot parse the certificate request: {e}")],
+    };
+    let csr = match X509CertificationRequest::from_der(&der) {
+        Ok((_, csr)) => csr,
+        Err(e) => return vec![format!("cannot parse the certificate request: {e}")],
+    };
+    let subject = &csr.certification_request_info.subject;
+
+    let usernames = subject
+        .iter_common_name()
+        .filter_map(|common_name| common_name.as_str().ok())
+        .filter_map(|username| {
+            settings.denied_client_username(username).map(|pattern| {
+                format!("client certificates for the username '{username}' (matching '{pattern}') cannot be requested")
+            })
+        });
+    let groups = subject
+        .iter_organization()
+        .filter_map(|organization| organization.as_str().ok())
+        .filter_map(|group| {
+            settings.denied_client_group(group).map(|pattern| {
+                format!("client certificates for the group '{group}' (matching '{pattern}') cannot be requested")
+            })
+        });
+    usernames.chain(groups).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    const ADMIN_CSR: &str = include_str!("../test_data/admin.csr");
+    const DEVELOPER_CSR: &str = include_str!("../test_data/developer.csr");
+
+    #[rstest]
+    #[case::developer(DEVELOPER_CSR, vec![])]
+    #[case::admin(
+        ADMIN_CSR,
+        vec![
+            "client certificates for the username 'kubernetes-admin' (matching '*-admin') cannot be requested",
+            "client certificates for the group 'system:masters' (matching 'system:masters') cannot be requested",
+        ]
+    )]
+    #[case::invalid("not a certificate request", vec!["cannot parse the certificate request"])]
+    fn client_subject(#[case] pem: &str, #[case] expected: Vec<&str>) {
+        let errors = validate_client_subject(pem.as_bytes(), &Settings::default());
+        assert_eq!(errors.len(), expected.len(), "errors: {errors:?}");
+        for (error, expected) in errors.iter().zip(expected) {
+            assert!(error.starts_with(expected), "unexpected error: {error}");
+        }
+    }
+
+    #[rstest]
+    #[case::developer_client("test_data/csr_developer_client.json", Settings::default(), true)]
+    #[case::admin_client("test_data/csr_admin_client.json", Settings::default(), false)]
+    #[case::admin_client_allowed(
+        "test_data/csr_admin_client.json",
+        Settings {
+            denied_client_usernames: vec![],
+            denied_client_groups: vec![],
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::signer_allowed(
+        "test_data/csr_developer_client.json",
+        Settings {
+            allowed_signers: BTreeMap::from([(
+                "developers".to_owned(),
+                [KUBE_APISERVER_CLIENT_SIGNER.to_owned()].into(),
+            )]),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::signer_not_allowed(
+        "test_data/csr_developer_client.json",
+        Settings {
+            allowed_signers: BTreeMap::from([(
+                "developers".to_owned(),
+                ["example.com/signer".to_owned()].into(),
+            )]),
+            ..Default::default()
+        },
+        false
+    )]
+    #[case::expiration_within_maximum(
+        "test_data/csr_developer_client.json",
+        Settings {
+            max_expiration_seconds: Some(86400),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::expiration_above_maximum(
+        "test_data/csr_developer_client.json",
+        Settings {
+            max_expiration_seconds: Some(3600),
+            ..Default::default()
+        },
+        false
+    )]
+    #[case::expiration_missing(
+        "test_data/csr_admin_client.json",
+        Settings {
+            denied_client_usernames: vec![],
+            denied_client_groups: vec![],
+            max_expiration_seconds: Some(86400),
+            ..Default::default()
+        },
+        false
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
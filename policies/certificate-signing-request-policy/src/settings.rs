@@ -0,0 +1,182 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use wildmatch::WildMatch;
+
+/// Signer issuing client certificates trusted by the API server
+pub(crate) const KUBE_APISERVER_CLIENT_SIGNER: &str = "kubernetes.io/kube-apiserver-client";
+
+/// Minimum lifetime of the certificates accepted by Kubernetes
+const MIN_EXPIRATION_SECONDS: i32 = 600;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Signers each group of users can request certificates to. The `*`
+    /// group matches all the users, the `*` signer matches all the signers.
+    /// When empty, all the signers are allowed.
+    pub allowed_signers: BTreeMap<String, HashSet<String>>,
+    /// Patterns of the usernames that cannot be requested in the client
+    /// certificates of the API server
+    pub denied_client_usernames: Vec<String>,
+    /// Patterns of the groups that cannot be requested in the client
+    /// certificates of the API server
+    pub denied_client_groups: Vec<String>,
+    /// Maximum lifetime, in seconds, of the requested certificates. When
+    /// set, the requests must specify `expirationSeconds`.
+    pub max_expiration_seconds: Option<i32>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            allowed_signers: BTreeMap::new(),
+            denied_client_usernames: vec![
+                "admin".to_owned(),
+                "*-admin".to_owned(),
+                "system:admin*".to_owned(),
+            ],
+            denied_client_groups: vec!["system:masters".to_owned()],
+            max_expiration_seconds: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when one of the groups can request certificates to the signer
+    pub fn is_signer_allowed(&self, signer: &str, groups: &[String]) -> bool {
+        self.allowed_signers.is_empty()
+            || groups
+                .iter()
+                .map(String::as_str)
+                .chain(["*"])
+                .filter_map(|group| self.allowed_signers.get(group))
+                .any(|signers| signers.contains("*") || signers.contains(signer))
+    }
+
+    /// Returns the first pattern matching the username
+    pub fn denied_client_username(&self, username: &str) -> Option<&str> {
+        find_pattern(&self.denied_client_usernames, username)
+    }
+
+    /// Returns the first pattern matching the group
+    pub fn denied_client_group(&self, group: &str) -> Option<&str> {
+        find_pattern(&self.denied_client_groups, group)
+    }
+}
+
+fn find_pattern<'a>(patterns: &'a [String], value: &str) -> Option<&'a str> {
+    patterns
+        .iter()
+        .find(|pattern| WildMatch::new(pattern).matches(value))
+        .map(String::as_str)
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self
+            .allowed_signers
+            .iter()
+            .any(|(group, signers)| group.is_empty() || signers.contains(""))
+        {
+            return Err("allowedSigners cannot contain empty groups or signers".to_owned());
+        }
+        if self.denied_client_usernames.iter().any(String::is_empty) {
+            return Err("deniedClientUsernames cannot contain empty values".to_owned());
+        }
+        if self.denied_client_groups.iter().any(String::is_empty) {
+            return Err("deniedClientGroups cannot contain empty values".to_owned());
+        }
+        if let Some(max_expiration_seconds) = self.max_expiration_seconds
+            && max_expiration_seconds < MIN_EXPIRATION_SECONDS
+        {
+            return Err(format!(
+                "maxExpirationSeconds must be at least {MIN_EXPIRATION_SECONDS}, got {max_expiration_seconds}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::no_allowlist(BTreeMap::new(), "example.com/signer", vec![], true)]
+    #[case::allowed_group(
+        BTreeMap::from([("developers".to_owned(), ["example.com/signer".to_owned()].into())]),
+        "example.com/signer",
+        vec!["developers"],
+        true
+    )]
+    #[case::other_group(
+        BTreeMap::from([("developers".to_owned(), ["example.com/signer".to_owned()].into())]),
+        "example.com/signer",
+        vec!["testers"],
+        false
+    )]
+    #[case::other_signer(
+        BTreeMap::from([("developers".to_owned(), ["example.com/signer".to_owned()].into())]),
+        KUBE_APISERVER_CLIENT_SIGNER,
+        vec!["developers"],
+        false
+    )]
+    #[case::all_groups(
+        BTreeMap::from([("*".to_owned(), ["example.com/signer".to_owned()].into())]),
+        "example.com/signer",
+        vec!["testers"],
+        true
+    )]
+    #[case::all_signers(
+        BTreeMap::from([("cluster-operators".to_owned(), ["*".to_owned()].into())]),
+        KUBE_APISERVER_CLIENT_SIGNER,
+        vec!["cluster-operators"],
+        true
+    )]
+    fn allowed_signers(
+        #[case] allowed_signers: BTreeMap<String, HashSet<String>>,
+        #[case] signer: &str,
+        #[case] groups: Vec<&str>,
+        #[case] expected: bool,
+    ) {
+        let settings = Settings {
+            allowed_signers,
+            ..Default::default()
+        };
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(settings.is_signer_allowed(signer, &groups), expected);
+    }
+
+    #[rstest]
+    #[case::admin("admin", Some("admin"))]
+    #[case::kubernetes_admin("kubernetes-admin", Some("*-admin"))]
+    #[case::system_admin("system:admin", Some("system:admin*"))]
+    #[case::developer("jane", None)]
+    #[case::administrator("administrator", None)]
+    fn denied_client_usernames(#[case] username: &str, #[case] expected: Option<&str>) {
+        assert_eq!(
+            Settings::default().denied_client_username(username),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::allowed_signers(r#"{"allowedSigners": {"developers": ["example.com/signer"]}}"#, true)]
+    #[case::empty_signer(r#"{"allowedSigners": {"developers": [""]}}"#, false)]
+    #[case::empty_group(r#"{"allowedSigners": {"": ["example.com/signer"]}}"#, false)]
+    #[case::empty_username(r#"{"deniedClientUsernames": [""]}"#, false)]
+    #[case::empty_denied_group(r#"{"deniedClientGroups": [""]}"#, false)]
+    #[case::max_expiration(r#"{"maxExpirationSeconds": 86400}"#, true)]
+    #[case::max_expiration_too_short(r#"{"maxExpirationSeconds": 60}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,41 @@
+#!/usr/bin/env bats
+
+@test "Reject a change during a freeze period" {
+	run kwctl run --request-path test_data/deployment_update.json --settings-path test_data/settings_frozen.json annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Deployment 'web': changes are frozen until 2100-01-01T00:00:00+00:00: release freeze.*") -ne 0 ]
+}
+
+@test "Accept an emergency change during a freeze period" {
+	run kwctl run --request-path test_data/deployment_update_sre.json --settings-path test_data/settings_frozen.json annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a change inside of a maintenance window" {
+	run kwctl run --request-path test_data/deployment_update.json --settings-json '{"maintenanceWindows": [{"schedule": "0 0 * * *", "duration": "24h"}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/deployment_update.json --settings-json '{"maintenanceWindows": [{"schedule": "0 22 * * *", "duration": "4h", "timezone": "Mars/Olympus"}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    validate_at(payload, Utc::now())
+}
+
+fn validate_at(payload: &[u8], now: DateTime<Utc>) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    if settings.is_exempt(&request.user_info.groups.unwrap_or_default()) {
+        return kubewarden::accept_request();
+    }
+    match settings.check_change(now) {
+        Ok(()) => kubewarden::accept_request(),
+        Err(error) => kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden::response::ValidationResponse;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use settings::{FreezePeriod, MaintenanceWindow};
+
+    fn settings() -> Settings {
+        Settings {
+            maintenance_windows: vec![MaintenanceWindow {
+                schedule: "0 9 * * 1-5".to_owned(),
+                duration: "8h".to_owned(),
+                timezone: "Europe/Rome".to_owned(),
+            }],
+            freeze_periods: vec![FreezePeriod {
+                start: DateTime::parse_from_rfc3339("2026-12-20T00:00:00+01:00").unwrap(),
+                end: DateTime::parse_from_rfc3339("2027-01-07T00:00:00+01:00").unwrap(),
+                reason: Some("end of year freeze".to_owned()),
+            }],
+            exempt_groups: ["sre-oncall".to_owned()].into(),
+        }
+    }
+
+    fn evaluate(fixture: &str, now: &str) -> ValidationResponse {
+        let request: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(fixture).expect("cannot read fixture"))
+                .expect("cannot parse fixture");
+        let payload = serde_json::to_vec(&json!({"request": request, "settings": settings()}))
+            .expect("cannot serialize payload");
+        let now = DateTime::parse_from_rfc3339(now)
+            .expect("cannot parse time")
+            .with_timezone(&Utc);
+
+        let response = validate_at(&payload, now).expect("validation failed");
+        serde_json::from_slice(&response).expect("cannot parse response")
+    }
+
+    #[rstest]
+    #[case::inside_window("test_data/deployment_update.json", "2026-12-08T10:00:00+01:00", true)]
+    #[case::outside_windows("test_data/deployment_update.json", "2026-12-08T20:00:00+01:00", false)]
+    #[case::frozen("test_data/deployment_update.json", "2026-12-21T10:00:00+01:00", false)]
+    #[case::exempt_group(
+        "test_data/deployment_update_sre.json",
+        "2026-12-21T10:00:00+01:00",
+        true
+    )]
+    fn validate_request(#[case] fixture: &str, #[case] now: &str, #[case] expected: bool) {
+        assert_eq!(evaluate(fixture, now).accepted, expected);
+    }
+
+    #[rstest]
+    #[case::outside_windows(
+        "2026-12-08T20:00:00+01:00",
+        "Deployment 'web': changes are allowed only during the maintenance windows, the next one opens at 2026-12-09T08:00:00+00:00"
+    )]
+    #[case::frozen(
+        "2026-12-21T10:00:00+01:00",
+        "Deployment 'web': changes are frozen until 2027-01-07T00:00:00+01:00: end of year freeze"
+    )]
+    fn rejection_message(#[case] now: &str, #[case] message: &str) {
+        let response = evaluate("test_data/deployment_update.json", now);
+        assert_eq!(response.message.as_deref(), Some(message));
+    }
+}
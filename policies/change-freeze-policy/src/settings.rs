@@ -0,0 +1,350 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use chrono_tz::Tz;
+use croner::Cron;
+use serde::{Deserialize, Serialize};
+
+/// Recurring window during which the changes are allowed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MaintenanceWindow {
+    /// Cron expression, with five fields, of the opening of the window
+    pub schedule: String,
+    /// How long the window stays open, like `4h` or `1h30m`
+    pub duration: String,
+    /// IANA name of the time zone of the schedule
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+}
+
+fn default_timezone() -> String {
+    "UTC".to_owned()
+}
+
+impl MaintenanceWindow {
+    fn parse(&self) -> Result<(Cron, TimeDelta, Tz), String> {
+        let cron = Cron::new(&self.schedule)
+            .parse()
+            .map_err(|e| format!("invalid schedule '{}': {e}", self.schedule))?;
+        let duration = parse_duration(&self.duration)?;
+        let timezone = Tz::from_str(&self.timezone)
+            .map_err(|e| format!("invalid timezone '{}': {e}", self.timezone))?;
+        Ok((cron, duration, timezone))
+    }
+
+    /// Returns true when the window is open at the given time
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        // The windows have been validated with the settings
+        let Ok((cron, duration, timezone)) = self.parse() else {
+            return false;
+        };
+        let now = now.with_timezone(&timezone);
+        cron.find_next_occurrence(&(now - duration), false)
+            .is_ok_and(|opening| opening <= now)
+    }
+
+    /// Returns the first time the window opens after the given time
+    pub fn next_opening(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let (cron, _, timezone) = self.parse().ok()?;
+        cron.find_next_occurrence(&now.with_timezone(&timezone), false)
+            .ok()
+            .map(|opening| opening.with_timezone(&Utc))
+    }
+}
+
+/// Parses the durations made of hours and minutes, like `4h`, `90m` or
+/// `1h30m`
+fn parse_duration(value: &str) -> Result<TimeDelta, String> {
+    let invalid = || format!("invalid duration '{value}', use hours and minutes like '1h30m'");
+
+    let mut duration = TimeDelta::zero();
+    let mut rest = value;
+    while !rest.is_empty() {
+        let unit_start = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let (amount, unit) = rest.split_at(unit_start);
+        let amount: i64 = amount.parse().map_err(|_| invalid())?;
+        let mut unit = unit.chars();
+        let delta = match unit.next() {
+            Some('h') => TimeDelta::try_hours(amount),
+            Some('m') => TimeDelta::try_minutes(amount),
+            _ => None,
+        };
+        duration = delta
+            .and_then(|delta| duration.checked_add(&delta))
+            .ok_or_else(invalid)?;
+        rest = unit.as_str();
+    }
+    if duration <= TimeDelta::zero() {
+        return Err(invalid());
+    }
+    Ok(duration)
+}
+
+/// Period during which the changes are rejected
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FreezePeriod {
+    /// RFC 3339 time of the beginning of the period
+    pub start: DateTime<FixedOffset>,
+    /// RFC 3339 time of the end of the period
+    pub end: DateTime<FixedOffset>,
+    /// Explanation added to the rejection message
+    pub reason: Option<String>,
+}
+
+impl FreezePeriod {
+    /// Returns true when the given time is within the period
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        self.start <= now && now < self.end
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Windows during which the changes are allowed. When empty, the changes
+    /// are allowed at any time outside of the freeze periods.
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+    /// Periods during which the changes are rejected, even inside of the
+    /// maintenance windows
+    pub freeze_periods: Vec<FreezePeriod>,
+    /// Groups of users allowed to make emergency changes at any time
+    pub exempt_groups: HashSet<String>,
+}
+
+impl Settings {
+    /// Returns true when one of the groups can make changes at any time
+    pub fn is_exempt(&self, groups: &[String]) -> bool {
+        groups
+            .iter()
+            .any(|group| self.exempt_groups.contains(group))
+    }
+
+    /// Returns an error explaining why the changes are not allowed at the
+    /// given time
+    pub fn check_change(&self, now: DateTime<Utc>) -> Result<(), String> {
+        if let Some(period) = self
+            .freeze_periods
+            .iter()
+            .find(|period| period.contains(now))
+        {
+            let until = period.end.to_rfc3339();
+            return Err(match &period.reason {
+                Some(reason) => format!("changes are frozen until {until}: {reason}"),
+                None => format!("changes are frozen until {until}"),
+            });
+        }
+        if self.maintenance_windows.is_empty()
+            || self
+                .maintenance_windows
+                .iter()
+                .any(|window| window.is_open(now))
+        {
+            return Ok(());
+        }
+        match self
+            .maintenance_windows
+            .iter()
+            .filter_map(|window| window.next_opening(now))
+            .min()
+        {
+            Some(opening) => Err(format!(
+                "changes are allowed only during the maintenance windows, the next one opens at {}",
+                opening.to_rfc3339()
+            )),
+            None => Err("changes are allowed only during the maintenance windows".to_owned()),
+        }
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.maintenance_windows.is_empty() && self.freeze_periods.is_empty() {
+            return Err(
+                "at least one maintenance window or freeze period must be provided".to_owned(),
+            );
+        }
+        for window in &self.maintenance_windows {
+            window.parse()?;
+        }
+        for period in &self.freeze_periods {
+            if period.end <= period.start {
+                return Err(format!(
+                    "the freeze period starting at {} must end after its start",
+                    period.start.to_rfc3339()
+                ));
+            }
+        }
+        if self.exempt_groups.contains("") {
+            return Err("exemptGroups cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    fn time(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .expect("cannot parse time")
+            .with_timezone(&Utc)
+    }
+
+    fn window(schedule: &str, duration: &str, timezone: &str) -> MaintenanceWindow {
+        MaintenanceWindow {
+            schedule: schedule.to_owned(),
+            duration: duration.to_owned(),
+            timezone: timezone.to_owned(),
+        }
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, false)]
+    #[case::all_settings(
+        r#"{
+            "maintenanceWindows": [
+                {"schedule": "0 22 * * 1-4", "duration": "4h", "timezone": "Europe/Rome"},
+                {"schedule": "30 6 * * SAT", "duration": "1h30m"}
+            ],
+            "freezePeriods": [
+                {"start": "2026-12-20T00:00:00Z", "end": "2027-01-07T00:00:00+01:00", "reason": "end of year freeze"}
+            ],
+            "exemptGroups": ["sre-oncall"]
+        }"#,
+        true
+    )]
+    #[case::invalid_schedule(
+        r#"{"maintenanceWindows": [{"schedule": "every night", "duration": "4h"}]}"#,
+        false
+    )]
+    #[case::invalid_timezone(
+        r#"{"maintenanceWindows": [{"schedule": "0 22 * * *", "duration": "4h", "timezone": "Mars/Olympus"}]}"#,
+        false
+    )]
+    #[case::invalid_duration(
+        r#"{"maintenanceWindows": [{"schedule": "0 22 * * *", "duration": "4 hours"}]}"#,
+        false
+    )]
+    #[case::invalid_time(
+        r#"{"freezePeriods": [{"start": "2026-12-20", "end": "2027-01-07"}]}"#,
+        false
+    )]
+    #[case::period_ending_before_start(
+        r#"{"freezePeriods": [{"start": "2027-01-07T00:00:00Z", "end": "2026-12-20T00:00:00Z"}]}"#,
+        false
+    )]
+    #[case::empty_exempt_group(
+        r#"{"freezePeriods": [{"start": "2026-12-20T00:00:00Z", "end": "2027-01-07T00:00:00Z"}], "exemptGroups": [""]}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let is_valid_settings = serde_json::from_str::<Settings>(settings)
+            .map_err(|e| e.to_string())
+            .and_then(|settings| settings.validate());
+        assert_eq!(is_valid_settings.is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::hours("4h", Some(TimeDelta::hours(4)))]
+    #[case::minutes("90m", Some(TimeDelta::minutes(90)))]
+    #[case::hours_and_minutes("1h30m", Some(TimeDelta::minutes(90)))]
+    #[case::empty("", None)]
+    #[case::zero("0h", None)]
+    #[case::missing_unit("30", None)]
+    #[case::unknown_unit("1d", None)]
+    #[case::overflow("9999999999999999h", None)]
+    fn duration(#[case] value: &str, #[case] expected: Option<TimeDelta>) {
+        assert_eq!(parse_duration(value).ok(), expected);
+    }
+
+    #[rstest]
+    // Monday 22:00 in Rome is 21:00 UTC in winter
+    #[case::opening(
+        window("0 22 * * MON", "4h", "Europe/Rome"),
+        "2026-12-07T21:00:00Z",
+        true
+    )]
+    #[case::past_midnight(
+        window("0 22 * * MON", "4h", "Europe/Rome"),
+        "2026-12-08T00:30:00Z",
+        true
+    )]
+    #[case::closing(
+        window("0 22 * * MON", "4h", "Europe/Rome"),
+        "2026-12-08T01:00:00Z",
+        false
+    )]
+    #[case::before_opening(
+        window("0 22 * * MON", "4h", "Europe/Rome"),
+        "2026-12-07T20:59:00Z",
+        false
+    )]
+    #[case::other_day(
+        window("0 22 * * MON", "4h", "Europe/Rome"),
+        "2026-12-08T21:30:00Z",
+        false
+    )]
+    #[case::utc(window("0 22 * * MON", "4h", "UTC"), "2026-12-07T22:30:00Z", true)]
+    fn window_open(#[case] window: MaintenanceWindow, #[case] now: &str, #[case] expected: bool) {
+        assert_eq!(window.is_open(time(now)), expected);
+    }
+
+    #[rstest]
+    #[case::no_windows(vec![], vec![], "2026-12-08T10:00:00Z", Ok(()))]
+    #[case::inside_window(
+        vec![window("0 9 * * 1-5", "8h", "UTC")],
+        vec![],
+        "2026-12-08T10:00:00Z",
+        Ok(())
+    )]
+    #[case::outside_windows(
+        vec![window("0 9 * * 1-5", "8h", "UTC"), window("0 6 * * SAT", "2h", "UTC")],
+        vec![],
+        "2026-12-11T18:00:00Z",
+        Err("changes are allowed only during the maintenance windows, the next one opens at 2026-12-12T06:00:00+00:00".to_owned())
+    )]
+    #[case::frozen(
+        vec![window("0 9 * * 1-5", "8h", "UTC")],
+        vec![FreezePeriod {
+            start: DateTime::parse_from_rfc3339("2026-12-20T00:00:00Z").unwrap(),
+            end: DateTime::parse_from_rfc3339("2027-01-07T00:00:00+01:00").unwrap(),
+            reason: Some("end of year freeze".to_owned()),
+        }],
+        "2026-12-21T10:00:00Z",
+        Err("changes are frozen until 2027-01-07T00:00:00+01:00: end of year freeze".to_owned())
+    )]
+    #[case::after_freeze(
+        vec![],
+        vec![FreezePeriod {
+            start: DateTime::parse_from_rfc3339("2026-12-20T00:00:00Z").unwrap(),
+            end: DateTime::parse_from_rfc3339("2027-01-07T00:00:00+01:00").unwrap(),
+            reason: None,
+        }],
+        "2027-01-06T23:00:00Z",
+        Ok(())
+    )]
+    fn check_change(
+        #[case] maintenance_windows: Vec<MaintenanceWindow>,
+        #[case] freeze_periods: Vec<FreezePeriod>,
+        #[case] now: &str,
+        #[case] expected: Result<(), String>,
+    ) {
+        let settings = Settings {
+            maintenance_windows,
+            freeze_periods,
+            ..Default::default()
+        };
+        assert_eq!(settings.check_change(time(now)), expected);
+    }
+}
@@ -0,0 +1,65 @@
+#!/usr/bin/env bats
+
+@test "Accept a Deployment whose references exist" {
+	run kwctl run \
+		--request-path test_data/deployment_references.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-references-found.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Deployment referencing a missing Secret" {
+	run kwctl run \
+		--request-path test_data/deployment_references.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-secret-not-found.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the following references cannot be found in the 'team-a' namespace: Secret 'web-tls'.*") -ne 0 ]
+}
+
+@test "Accept a Pod with optional references only" {
+	run kwctl run \
+		--request-path test_data/pod_optional_references.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Pod without references" {
+	run kwctl run \
+		--request-path test_data/pod_without_references.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_without_references.json --settings-json '{"ignoredSecrets": [""]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,334 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+use k8s_openapi::{ListableResource, Resource};
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::ListResourcesByNamespaceRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::list_resources_by_namespace;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::list_resources_by_namespace;
+
+mod references;
+use references::{Reference, ReferenceKind, required_references};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = &validation_request.request;
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        // The resources without Pod spec are not validated
+        Ok(None) => return kubewarden::accept_request(),
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    let mut missing = Vec::new();
+    let mut lookup_errors = Vec::new();
+    for reference in required_references(&pod_spec)
+        .iter()
+        .filter(|reference| !is_ignored(reference, &validation_request.settings))
+    {
+        match reference_exists(reference, &request.namespace) {
+            Ok(true) => {}
+            Ok(false) => missing.push(reference.to_string()),
+            Err(err) => lookup_errors.push(format!("{reference}: {err}")),
+        }
+    }
+    if !lookup_errors.is_empty() {
+        return kubewarden::reject_request(
+            Some(format!(
+                "{} '{}': the following references cannot be read in the '{}' namespace: {}",
+                request.kind.kind,
+                request.name,
+                request.namespace,
+                lookup_errors.join(", ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    if !missing.is_empty() {
+        return kubewarden::reject_request(
+            Some(format!(
+                "{} '{}': the following references cannot be found in the '{}' namespace: {}",
+                request.kind.kind,
+                request.name,
+                request.namespace,
+                missing.join(", ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn is_ignored(reference: &Reference, settings: &Settings) -> bool {
+    match reference.kind {
+        ReferenceKind::ConfigMap => settings.ignored_config_maps.contains(&reference.name),
+        ReferenceKind::Secret => settings.ignored_secrets.contains(&reference.name),
+    }
+}
+
+fn reference_exists(reference: &Reference, namespace: &str) -> Result<bool> {
+    match reference.kind {
+        ReferenceKind::ConfigMap => resource_exists::<ConfigMap>(&reference.name, namespace),
+        ReferenceKind::Secret => resource_exists::<Secret>(&reference.name, namespace),
+    }
+}
+
+// The resource is listed by name: an empty list means it does not exist,
+// while an error is a failure of the host. Only the name of the resources is
+// requested: the data of the Secrets is never read by the policy.
+fn resource_exists<T>(name: &str, namespace: &str) -> Result<bool>
+where
+    T: ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+{
+    let resources = list_resources_by_namespace::<T>(&ListResourcesByNamespaceRequest {
+        api_version: T::API_VERSION.to_owned(),
+        kind: T::KIND.to_owned(),
+        namespace: namespace.to_owned(),
+        label_selector: None,
+        field_selector: Some(format!("metadata.name={name}")),
+        field_masks: Some(BTreeSet::from(["metadata.name".to_owned()])),
+    })?;
+    Ok(!resources.items.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::ListResourcesByNamespaceRequest;
+
+        #[allow(dead_code)]
+        pub fn list_resources_by_namespace<T>(
+            _req: &ListResourcesByNamespaceRequest,
+        ) -> anyhow::Result<k8s_openapi::List<T>>
+        where
+            T: k8s_openapi::ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+        {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    // Returns the name selected by the field selector of the request
+    fn selected_name(req: &ListResourcesByNamespaceRequest) -> &str {
+        req.field_selector
+            .as_deref()
+            .and_then(|selector| selector.strip_prefix("metadata.name="))
+            .expect("the resource is not listed by name")
+    }
+
+    fn metadata(req: &ListResourcesByNamespaceRequest) -> ObjectMeta {
+        ObjectMeta {
+            name: Some(selected_name(req).to_owned()),
+            namespace: Some(req.namespace.clone()),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::all_references_exist(
+        "test_data/deployment_references.json",
+        &["web-config"],
+        &["database", "web-tls"],
+        true
+    )]
+    #[case::missing_references(
+        "test_data/deployment_references.json",
+        &["web-config"],
+        &["database"],
+        false
+    )]
+    #[case::optional_references(
+        "test_data/pod_optional_references.json",
+        &[],
+        &[],
+        true
+    )]
+    #[case::without_references("test_data/pod_without_references.json", &[], &[], true)]
+    #[serial]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] config_maps: &'static [&'static str],
+        #[case] secrets: &'static [&'static str],
+        #[case] expected_validation_result: bool,
+    ) {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<ConfigMap>()
+            .returning(move |req| {
+                if !config_maps.contains(&selected_name(req)) {
+                    return Ok(k8s_openapi::List::default());
+                }
+                Ok(k8s_openapi::List {
+                    items: vec![ConfigMap {
+                        metadata: metadata(req),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })
+            });
+        ctx_list_resources.expect::<Secret>().returning(move |req| {
+            if !secrets.contains(&selected_name(req)) {
+                return Ok(k8s_openapi::List::default());
+            }
+            Ok(k8s_openapi::List {
+                items: vec![Secret {
+                    metadata: metadata(req),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            })
+        });
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    #[serial]
+    fn rejection_message() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<ConfigMap>()
+            .times(1)
+            .returning(|_| Ok(k8s_openapi::List::default()));
+        ctx_list_resources
+            .expect::<Secret>()
+            .times(2)
+            .returning(|_| Ok(k8s_openapi::List::default()));
+
+        let test_case = Testcase {
+            name: "missing references".to_owned(),
+            fixture_file: "test_data/deployment_references.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Deployment 'web': the following references cannot be found in the 'team-a' namespace: ConfigMap 'web-config', Secret 'database', Secret 'web-tls'"
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn lookup_failure() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<ConfigMap>()
+            .times(1)
+            .returning(|req| {
+                Ok(k8s_openapi::List {
+                    items: vec![ConfigMap {
+                        metadata: metadata(req),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })
+            });
+        ctx_list_resources
+            .expect::<Secret>()
+            .times(2)
+            .returning(|_| Err(anyhow::anyhow!("secrets is forbidden")));
+
+        let test_case = Testcase {
+            name: "lookup failure".to_owned(),
+            fixture_file: "test_data/deployment_references.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Deployment 'web': the following references cannot be read in the 'team-a' namespace: Secret 'database': secrets is forbidden, Secret 'web-tls': secrets is forbidden"
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn ignored_references() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources.expect::<ConfigMap>().times(0);
+        ctx_list_resources
+            .expect::<Secret>()
+            .times(1)
+            .returning(|req| {
+                if selected_name(req) != "database" {
+                    return Err(anyhow::anyhow!("it's not searching the expected Secret"));
+                }
+                if req.field_masks != Some(BTreeSet::from(["metadata.name".to_owned()])) {
+                    return Err(anyhow::anyhow!("it's reading more than the Secret name"));
+                }
+                Ok(k8s_openapi::List {
+                    items: vec![Secret {
+                        metadata: metadata(req),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                })
+            });
+
+        let test_case = Testcase {
+            name: "ignored references".to_owned(),
+            fixture_file: "test_data/deployment_references.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings {
+                ignored_config_maps: BTreeSet::from(["web-config".to_owned()]),
+                ignored_secrets: BTreeSet::from(["web-tls".to_owned()]),
+            },
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
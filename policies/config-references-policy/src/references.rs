@@ -0,0 +1,233 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use k8s_openapi::api::core::v1::{EnvFromSource, EnvVar, PodSpec};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum ReferenceKind {
+    ConfigMap,
+    Secret,
+}
+
+impl fmt::Display for ReferenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReferenceKind::ConfigMap => write!(f, "ConfigMap"),
+            ReferenceKind::Secret => write!(f, "Secret"),
+        }
+    }
+}
+
+/// ConfigMap or Secret referenced by a Pod
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Reference {
+    pub kind: ReferenceKind,
+    pub name: String,
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} '{}'", self.kind, self.name)
+    }
+}
+
+#[derive(Default)]
+struct References(BTreeSet<Reference>);
+
+impl References {
+    // The optional references do not prevent the Pod from starting
+    fn add(&mut self, kind: ReferenceKind, name: &str, optional: Option<bool>) {
+        if !optional.unwrap_or(false) && !name.is_empty() {
+            self.0.insert(Reference {
+                kind,
+                name: name.to_owned(),
+            });
+        }
+    }
+
+    fn add_env(&mut self, env: Option<&Vec<EnvVar>>, env_from: Option<&Vec<EnvFromSource>>) {
+        for value_from in env
+            .into_iter()
+            .flatten()
+            .filter_map(|env_var| env_var.value_from.as_ref())
+        {
+            if let Some(selector) = &value_from.config_map_key_ref {
+                self.add(ReferenceKind::ConfigMap, &selector.name, selector.optional);
+            }
+            if let Some(selector) = &value_from.secret_key_ref {
+                self.add(ReferenceKind::Secret, &selector.name, selector.optional);
+            }
+        }
+        for source in env_from.into_iter().flatten() {
+            if let Some(config_map) = &source.config_map_ref {
+                self.add(
+                    ReferenceKind::ConfigMap,
+                    &config_map.name,
+                    config_map.optional,
+                );
+            }
+            if let Some(secret) = &source.secret_ref {
+                self.add(ReferenceKind::Secret, &secret.name, secret.optional);
+            }
+        }
+    }
+}
+
+/// Returns the ConfigMaps and the Secrets the Pod needs to start, referenced
+/// by the environment of its containers and by its volumes. The references
+/// marked as optional are skipped.
+pub(crate) fn required_references(pod_spec: &PodSpec) -> BTreeSet<Reference> {
+    let mut references = References::default();
+
+    for container in pod_spec
+        .containers
+        .iter()
+        .chain(pod_spec.init_containers.iter().flatten())
+    {
+        references.add_env(container.env.as_ref(), container.env_from.as_ref());
+    }
+    for container in pod_spec.ephemeral_containers.iter().flatten() {
+        references.add_env(container.env.as_ref(), container.env_from.as_ref());
+    }
+
+    for volume in pod_spec.volumes.iter().flatten() {
+        if let Some(config_map) = &volume.config_map {
+            references.add(
+                ReferenceKind::ConfigMap,
+                &config_map.name,
+                config_map.optional,
+            );
+        }
+        if let Some(secret) = &volume.secret {
+            references.add(
+                ReferenceKind::Secret,
+                secret.secret_name.as_deref().unwrap_or_default(),
+                secret.optional,
+            );
+        }
+        for source in volume
+            .projected
+            .iter()
+            .flat_map(|projected| projected.sources.iter().flatten())
+        {
+            if let Some(config_map) = &source.config_map {
+                references.add(
+                    ReferenceKind::ConfigMap,
+                    &config_map.name,
+                    config_map.optional,
+                );
+            }
+            if let Some(secret) = &source.secret {
+                references.add(ReferenceKind::Secret, &secret.name, secret.optional);
+            }
+        }
+    }
+    references.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use k8s_openapi::api::core::v1::{
+        ConfigMapEnvSource, ConfigMapKeySelector, ConfigMapVolumeSource, Container, EnvVarSource,
+        ProjectedVolumeSource, SecretKeySelector, SecretProjection, SecretVolumeSource, Volume,
+        VolumeProjection,
+    };
+
+    fn reference(kind: ReferenceKind, name: &str) -> Reference {
+        Reference {
+            kind,
+            name: name.to_owned(),
+        }
+    }
+
+    #[test]
+    fn collect_required_references() {
+        let pod_spec = PodSpec {
+            containers: vec![Container {
+                name: "web".to_owned(),
+                env: Some(vec![
+                    EnvVar {
+                        name: "DATABASE_PASSWORD".to_owned(),
+                        value_from: Some(EnvVarSource {
+                            secret_key_ref: Some(SecretKeySelector {
+                                name: "database".to_owned(),
+                                key: "password".to_owned(),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                    EnvVar {
+                        name: "FEATURE_FLAGS".to_owned(),
+                        value_from: Some(EnvVarSource {
+                            config_map_key_ref: Some(ConfigMapKeySelector {
+                                name: "feature-flags".to_owned(),
+                                key: "flags".to_owned(),
+                                optional: Some(true),
+                            }),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                ]),
+                ..Default::default()
+            }],
+            init_containers: Some(vec![Container {
+                name: "migrations".to_owned(),
+                env_from: Some(vec![EnvFromSource {
+                    config_map_ref: Some(ConfigMapEnvSource {
+                        name: "web-config".to_owned(),
+                        optional: Some(false),
+                    }),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }]),
+            volumes: Some(vec![
+                Volume {
+                    name: "config".to_owned(),
+                    config_map: Some(ConfigMapVolumeSource {
+                        name: "web-config".to_owned(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                Volume {
+                    name: "tls".to_owned(),
+                    secret: Some(SecretVolumeSource {
+                        secret_name: Some("web-tls".to_owned()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                Volume {
+                    name: "credentials".to_owned(),
+                    projected: Some(ProjectedVolumeSource {
+                        sources: Some(vec![VolumeProjection {
+                            secret: Some(SecretProjection {
+                                name: "database".to_owned(),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            required_references(&pod_spec),
+            BTreeSet::from([
+                reference(ReferenceKind::ConfigMap, "web-config"),
+                reference(ReferenceKind::Secret, "database"),
+                reference(ReferenceKind::Secret, "web-tls"),
+            ])
+        );
+    }
+}
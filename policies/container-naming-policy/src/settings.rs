@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Container name reserved to the containers injected by a tool, like the
+/// sidecar of a service mesh
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct ReservedName {
+    pub name: String,
+    /// Annotation set by the tool on the Pods where it injects the container.
+    /// The name is allowed in the Pods having it. When not set or empty, the
+    /// name is never allowed.
+    pub allowed_with_annotation: Option<String>,
+}
+
+impl ReservedName {
+    /// Returns true when the name is allowed in a Pod with the annotations.
+    /// The Pod templates of the workload resources have no annotations: the
+    /// containers are injected in the Pods.
+    pub fn is_allowed(&self, pod_annotations: Option<&BTreeMap<String, String>>) -> bool {
+        self.allowed_with_annotation
+            .as_ref()
+            .filter(|annotation| !annotation.is_empty())
+            .is_some_and(|annotation| {
+                pod_annotations.is_some_and(|annotations| annotations.contains_key(annotation))
+            })
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Regular expression the container names must match. Ignored when empty.
+    pub allowed_pattern: Option<String>,
+    /// Regular expression the container names must not match. Ignored when
+    /// empty.
+    pub denied_pattern: Option<String>,
+    /// Container names reserved to the injected containers
+    pub reserved_names: Vec<ReservedName>,
+}
+
+impl Settings {
+    /// Returns the reasons why the container name does not follow the
+    /// naming conventions
+    pub fn violations(&self, name: &str) -> Vec<String> {
+        let mut violations = Vec::new();
+        if let Some(pattern) = self.allowed_pattern.as_ref().filter(|p| !p.is_empty())
+            && !name_regex(pattern).is_ok_and(|regex| regex.is_match(name))
+        {
+            violations.push(format!("the name must match '{pattern}'"));
+        }
+        if let Some(pattern) = self.denied_pattern.as_ref().filter(|p| !p.is_empty())
+            && name_regex(pattern).is_ok_and(|regex| regex.is_match(name))
+        {
+            violations.push(format!("the name must not match '{pattern}'"));
+        }
+        violations
+    }
+
+    /// Returns the reservation of the name, if any
+    pub fn reservation(&self, name: &str) -> Option<&ReservedName> {
+        self.reserved_names
+            .iter()
+            .find(|reserved| reserved.name == name)
+    }
+}
+
+/// Compiles the pattern, which must match the whole name
+fn name_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{pattern})$"))
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        for pattern in self.allowed_pattern.iter().chain(&self.denied_pattern) {
+            name_regex(pattern).map_err(|e| format!("invalid pattern '{pattern}': {e}"))?;
+        }
+        for reserved in &self.reserved_names {
+            if reserved.name.is_empty() {
+                return Err("the reserved names cannot be empty".to_owned());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "allowedPattern": "[a-z][a-z0-9-]*",
+            "deniedPattern": ".*-tmp",
+            "reservedNames": [
+                {"name": "istio-proxy", "allowedWithAnnotation": "sidecar.istio.io/status"},
+                {"name": "debugger"}
+            ]
+        }"#,
+        true
+    )]
+    #[case::invalid_pattern(r#"{"allowedPattern": "[a-z"}"#, false)]
+    #[case::empty_reserved_name(r#"{"reservedNames": [{"name": ""}]}"#, false)]
+    #[case::empty_annotation(
+        r#"{"reservedNames": [{"name": "istio-proxy", "allowedWithAnnotation": ""}]}"#,
+        true
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::allowed("web", vec![])]
+    #[case::not_allowed("Web", vec!["the name must match '[a-z][a-z0-9-]*'"])]
+    #[case::denied("web-tmp", vec!["the name must not match '.*-tmp'"])]
+    fn violations(#[case] name: &str, #[case] expected: Vec<&str>) {
+        let settings = Settings {
+            allowed_pattern: Some("[a-z][a-z0-9-]*".to_owned()),
+            denied_pattern: Some(".*-tmp".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(settings.violations(name), expected);
+    }
+
+    #[rstest]
+    #[case::with_annotation(Some("sidecar.istio.io/status"), Some("sidecar.istio.io/status"), true)]
+    #[case::other_annotation(Some("sidecar.istio.io/status"), Some("example.com/team"), false)]
+    #[case::without_annotations(Some("sidecar.istio.io/status"), None, false)]
+    #[case::never_allowed(None, Some("sidecar.istio.io/status"), false)]
+    #[case::empty_annotation(Some(""), Some(""), false)]
+    fn reserved_name_allowed(
+        #[case] allowed_with_annotation: Option<&str>,
+        #[case] pod_annotation: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        let reserved = ReservedName {
+            name: "istio-proxy".to_owned(),
+            allowed_with_annotation: allowed_with_annotation.map(str::to_owned),
+        };
+        let annotations = pod_annotation
+            .map(|annotation| BTreeMap::from([(annotation.to_owned(), "{}".to_owned())]));
+        assert_eq!(reserved.is_allowed(annotations.as_ref()), expected);
+    }
+}
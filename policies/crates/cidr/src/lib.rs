@@ -0,0 +1,106 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// An IPv4 or IPv6 network expressed in CIDR notation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cidr {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl FromStr for Cidr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix_len) = s
+            .split_once('/')
+            .ok_or_else(|| format!("'{s}' is not in CIDR notation"))?;
+        let address = address
+            .parse::<IpAddr>()
+            .map_err(|e| format!("'{s}' has an invalid address: {e}"))?;
+        let prefix_len = prefix_len
+            .parse::<u8>()
+            .map_err(|e| format!("'{s}' has an invalid prefix length: {e}"))?;
+        let max_prefix_len = match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        if prefix_len > max_prefix_len {
+            return Err(format!(
+                "'{s}' has a prefix length greater than {max_prefix_len}"
+            ));
+        }
+        Ok(Cidr {
+            address,
+            prefix_len,
+        })
+    }
+}
+
+impl From<IpAddr> for Cidr {
+    fn from(address: IpAddr) -> Self {
+        let prefix_len = match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        Cidr {
+            address,
+            prefix_len,
+        }
+    }
+}
+
+impl Cidr {
+    /// Returns true when all the addresses of `other` belong to this network
+    pub fn contains(&self, other: &Cidr) -> bool {
+        if other.prefix_len < self.prefix_len {
+            return false;
+        }
+        match (self.address, other.address) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(address) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(address) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("10.0.0.0/8", "10.1.2.0/24", true)]
+    #[case("10.0.0.0/8", "10.0.0.0/8", true)]
+    #[case("10.0.0.0/8", "11.0.0.0/24", false)]
+    #[case("10.0.0.0/16", "10.0.0.0/8", false)]
+    #[case("0.0.0.0/0", "192.168.1.1/32", true)]
+    #[case("fd00::/8", "fd12:3456::/32", true)]
+    #[case("fd00::/8", "fe80::/16", false)]
+    #[case("10.0.0.0/8", "fd00::/8", false)]
+    fn cidr_contains(#[case] network: &str, #[case] other: &str, #[case] expected: bool) {
+        let network: Cidr = network.parse().expect("cannot parse network");
+        let other: Cidr = other.parse().expect("cannot parse other network");
+        assert_eq!(network.contains(&other), expected);
+    }
+
+    #[rstest]
+    #[case("10.0.0.0")]
+    #[case("10.0.0.0/33")]
+    #[case("fd00::/129")]
+    #[case("foo/8")]
+    #[case("10.0.0.0/a")]
+    fn invalid_cidr(#[case] cidr: &str) {
+        assert!(cidr.parse::<Cidr>().is_err());
+    }
+}
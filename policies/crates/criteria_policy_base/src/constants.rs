@@ -4,10 +4,11 @@ use const_format::formatcp;
     feature = "env_var",
     feature = "labels",
     feature = "annotations",
-    feature = "cve_name"
+    feature = "cve_name",
+    feature = "json_value"
 )))]
 compile_error!(
-    "At least one of the features: env_var, labels, annotations, or cve_name must be enabled."
+    "At least one of the features: env_var, labels, annotations, cve_name, or json_value must be enabled."
 );
 
 #[cfg(any(
@@ -16,10 +17,14 @@ compile_error!(
     all(feature = "labels", feature = "annotations"),
     all(feature = "env_var", feature = "cve_name"),
     all(feature = "labels", feature = "cve_name"),
-    all(feature = "annotations", feature = "cve_name")
+    all(feature = "annotations", feature = "cve_name"),
+    all(feature = "env_var", feature = "json_value"),
+    all(feature = "labels", feature = "json_value"),
+    all(feature = "annotations", feature = "json_value"),
+    all(feature = "cve_name", feature = "json_value")
 ))]
 compile_error!(
-    "Only one of the features: env_var, labels, annotations, or cve_name can be enabled at a time."
+    "Only one of the features: env_var, labels, annotations, cve_name, or json_value can be enabled at a time."
 );
 #[cfg(feature = "env_var")]
 pub const RESOURCE_STR: &str = "environment variable";
@@ -29,6 +34,8 @@ pub const RESOURCE_STR: &str = "label";
 pub const RESOURCE_STR: &str = "annotation";
 #[cfg(feature = "cve_name")]
 pub const RESOURCE_STR: &str = "CVE name";
+#[cfg(feature = "json_value")]
+pub const RESOURCE_STR: &str = "value";
 
 pub const CONTAINS_ANY_OF_ERROR_MSG: &str = formatcp!(
     "Resource must have at least one of the required {RESOURCE_STR}s specified by the validation rule. None of the expected {RESOURCE_STR}s were found:"
@@ -14,10 +14,8 @@ use serde::{Deserialize, Serialize};
 /// The real policy must call `validate()` from within its own Settings::validate() method.
 /// This enum makes sure the user provided some values for the policy to match against.
 ///
-/// Limitation: the real policy must not require other types of configuration.
-/// We could find workaround for this limitation, like trying to use `serde(flatten)`,
-/// but we don't know if that would actually work. Luckily, the real policy
-/// does not require any other configuration, so we can use this enum directly.
+/// Policies requiring other types of configuration can embed this enum into
+/// their settings struct using `serde(flatten)`.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "criteria")]
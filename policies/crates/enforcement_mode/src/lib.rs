@@ -0,0 +1,55 @@
+use kubewarden_policy_sdk::{response::ValidationResponse, wapc_guest::CallResult};
+use serde::{Deserialize, Serialize};
+
+/// What to do when a request violates the policy
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Mode {
+    /// Reject the request
+    #[default]
+    Enforce,
+    /// Accept the request, but warn the user about the violation. Useful
+    /// while rolling out the policy
+    Warn,
+}
+
+impl Mode {
+    /// Rejects the request with the message, or accepts it returning the
+    /// message as a warning
+    pub fn respond(self, message: String) -> CallResult {
+        match self {
+            Mode::Enforce => kubewarden_policy_sdk::reject_request(Some(message), None, None, None),
+            Mode::Warn => Ok(serde_json::to_vec(&ValidationResponse {
+                accepted: true,
+                message: None,
+                code: None,
+                mutated_object: None,
+                audit_annotations: None,
+                warnings: Some(vec![message]),
+            })?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn respond() {
+        let response = |mode: Mode| -> ValidationResponse {
+            let response = mode
+                .respond("not allowed".to_owned())
+                .expect("cannot respond");
+            serde_json::from_slice(&response).expect("cannot parse the response")
+        };
+
+        let rejection = response(Mode::Enforce);
+        assert!(!rejection.accepted);
+        assert_eq!(rejection.message.as_deref(), Some("not allowed"));
+
+        let warning = response(Mode::Warn);
+        assert!(warning.accepted);
+        assert_eq!(warning.warnings, Some(vec!["not allowed".to_owned()]));
+    }
+}
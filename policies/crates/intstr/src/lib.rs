@@ -0,0 +1,83 @@
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+
+/// Parses percentages like `25%`
+pub fn parse_percentage(value: &str) -> Option<i32> {
+    value
+        .strip_suffix('%')
+        .and_then(|percentage| percentage.parse::<i32>().ok())
+        .filter(|percentage| *percentage >= 0)
+}
+
+/// Returns true when the value is a non-negative number of Pods or a
+/// percentage
+pub fn is_valid(value: &IntOrString) -> bool {
+    match value {
+        IntOrString::Int(pods) => *pods >= 0,
+        IntOrString::String(percentage) => parse_percentage(percentage).is_some(),
+    }
+}
+
+/// Returns the number of Pods of the value, scaling the percentages on the
+/// replicas the way the Deployment controller does: rounding up the surge and
+/// rounding down the unavailable Pods.
+pub fn scaled_value(value: &IntOrString, replicas: i32, round_up: bool) -> Result<i32, String> {
+    match value {
+        IntOrString::Int(pods) => Ok(*pods),
+        IntOrString::String(percentage) => {
+            let percentage = parse_percentage(percentage)
+                .ok_or_else(|| format!("invalid percentage '{percentage}'"))?;
+            let scaled = i64::from(percentage) * i64::from(replicas);
+            let pods = if round_up {
+                (scaled + 99) / 100
+            } else {
+                scaled / 100
+            };
+            Ok(pods as i32)
+        }
+    }
+}
+
+/// Formats the value the way it is written in the manifests
+pub fn format_value(value: &IntOrString) -> String {
+    match value {
+        IntOrString::Int(pods) => pods.to_string(),
+        IntOrString::String(percentage) => percentage.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::pods(IntOrString::Int(2), 10, false, Ok(2))]
+    #[case::round_down(IntOrString::String("25%".to_owned()), 10, false, Ok(2))]
+    #[case::round_up(IntOrString::String("25%".to_owned()), 10, true, Ok(3))]
+    #[case::exact(IntOrString::String("50%".to_owned()), 4, true, Ok(2))]
+    #[case::invalid(
+        IntOrString::String("half".to_owned()),
+        4,
+        true,
+        Err("invalid percentage 'half'".to_owned())
+    )]
+    fn scale(
+        #[case] value: IntOrString,
+        #[case] replicas: i32,
+        #[case] round_up: bool,
+        #[case] expected: Result<i32, String>,
+    ) {
+        assert_eq!(scaled_value(&value, replicas, round_up), expected);
+    }
+
+    #[rstest]
+    #[case::pods(IntOrString::Int(1), true)]
+    #[case::negative_pods(IntOrString::Int(-1), false)]
+    #[case::percentage(IntOrString::String("25%".to_owned()), true)]
+    #[case::negative_percentage(IntOrString::String("-25%".to_owned()), false)]
+    #[case::missing_percent_sign(IntOrString::String("25".to_owned()), false)]
+    fn valid_value(#[case] value: IntOrString, #[case] expected: bool) {
+        assert_eq!(is_valid(&value), expected);
+    }
+}
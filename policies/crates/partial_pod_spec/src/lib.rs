@@ -0,0 +1,161 @@
+use anyhow::{Result, anyhow};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+/// Kinds embedding a Pod spec, with the JSON pointer of the Pod spec
+pub const POD_SPEC_POINTERS: &[(&str, &str)] = &[
+    ("Pod", "/spec"),
+    ("ReplicationController", "/spec/template/spec"),
+    ("DaemonSet", "/spec/template/spec"),
+    ("Deployment", "/spec/template/spec"),
+    ("ReplicaSet", "/spec/template/spec"),
+    ("StatefulSet", "/spec/template/spec"),
+    ("Job", "/spec/template/spec"),
+    ("CronJob", "/spec/jobTemplate/spec/template/spec"),
+];
+
+/// Returns the JSON pointer of the Pod spec of the resources of the given
+/// kind, `None` when the kind does not embed a Pod spec
+pub fn pod_spec_pointer(kind: &str) -> Option<&'static str> {
+    POD_SPEC_POINTERS
+        .iter()
+        .find(|(pod_spec_kind, _)| *pod_spec_kind == kind)
+        .map(|(_, pointer)| *pointer)
+}
+
+/// Deserializes `T` from the Pod spec of the object. `T` declares only the
+/// fields used by the policy: the rest of the Pod spec is neither parsed nor
+/// checked against the fields known to k8s-openapi.
+///
+/// Returns `None` when the kind does not embed a Pod spec or when the object
+/// does not define it.
+pub fn extract<T: DeserializeOwned>(kind: &str, object: &Value) -> Result<Option<T>> {
+    let Some(pod_spec) = pod_spec_pointer(kind)
+        .and_then(|pointer| object.pointer(pointer))
+        .filter(|pod_spec| !pod_spec.is_null())
+    else {
+        return Ok(None);
+    };
+    T::deserialize(pod_spec)
+        .map(Some)
+        .map_err(|e| anyhow!("cannot parse the Pod spec: {e}"))
+}
+
+/// Returns a copy of the object with the fields of `T` written into its Pod
+/// spec. The rest of the object is copied as is, including the fields unknown
+/// to k8s-openapi.
+///
+/// The fields serialized as `null` are removed from the Pod spec: `T` must
+/// not skip the serialization of its `None` fields, otherwise they cannot be
+/// unset. The fields not serialized at all, like the ones only read by the
+/// policy, are left as they are.
+pub fn patch<T: Serialize>(kind: &str, object: &Value, fields: &T) -> Result<Value> {
+    let pointer =
+        pod_spec_pointer(kind).ok_or_else(|| anyhow!("{kind} resources do not have a Pod spec"))?;
+    let Value::Object(fields) = serde_json::to_value(fields)? else {
+        return Err(anyhow!(
+            "the Pod spec fields must be serialized as an object"
+        ));
+    };
+
+    let mut patched = object.clone();
+    let pod_spec = patched
+        .pointer_mut(pointer)
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| anyhow!("the {kind} does not define a Pod spec"))?;
+    for (key, value) in fields {
+        if value.is_null() {
+            pod_spec.remove(&key);
+        } else {
+            pod_spec.insert(key, value);
+        }
+    }
+    Ok(patched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct Fields {
+        enable_service_links: Option<bool>,
+        priority_class_name: Option<String>,
+    }
+
+    fn deployment(pod_spec: Value) -> Value {
+        json!({
+            "metadata": {"name": "web"},
+            "spec": {"replicas": 2, "template": {"spec": pod_spec}}
+        })
+    }
+
+    #[rstest]
+    #[case::pod(
+        "Pod",
+        json!({"spec": {"enableServiceLinks": false, "containers": []}}),
+        Some(Fields { enable_service_links: Some(false), ..Default::default() })
+    )]
+    #[case::deployment(
+        "Deployment",
+        deployment(json!({"priorityClassName": "high", "containers": []})),
+        Some(Fields { priority_class_name: Some("high".to_owned()), ..Default::default() })
+    )]
+    #[case::cronjob(
+        "CronJob",
+        json!({"spec": {"jobTemplate": {"spec": {"template": {"spec": {"enableServiceLinks": true}}}}}}),
+        Some(Fields { enable_service_links: Some(true), ..Default::default() })
+    )]
+    #[case::unknown_fields(
+        "Pod",
+        json!({"spec": {"fieldFromTheFuture": {"enabled": true}}}),
+        Some(Fields::default())
+    )]
+    #[case::no_pod_spec("Pod", json!({"metadata": {"name": "web"}}), None)]
+    #[case::kind_without_pod_spec("Service", json!({"spec": {"type": "ClusterIP"}}), None)]
+    fn extract_fields(#[case] kind: &str, #[case] object: Value, #[case] expected: Option<Fields>) {
+        let fields = extract::<Fields>(kind, &object).expect("cannot extract the fields");
+        assert_eq!(fields, expected);
+    }
+
+    #[test]
+    fn extract_invalid_fields() {
+        let object = json!({"spec": {"enableServiceLinks": "no"}});
+        assert!(extract::<Fields>("Pod", &object).is_err());
+    }
+
+    #[test]
+    fn patch_fields() {
+        let object = deployment(json!({
+            "containers": [{"name": "web", "image": "nginx", "fieldFromTheFuture": 1}],
+            "priorityClassName": "low",
+            "fieldFromTheFuture": {"enabled": true}
+        }));
+        let fields = Fields {
+            enable_service_links: Some(false),
+            priority_class_name: None,
+        };
+
+        let patched = patch("Deployment", &object, &fields).expect("cannot patch the fields");
+        assert_eq!(
+            patched,
+            deployment(json!({
+                "containers": [{"name": "web", "image": "nginx", "fieldFromTheFuture": 1}],
+                "enableServiceLinks": false,
+                "fieldFromTheFuture": {"enabled": true}
+            }))
+        );
+    }
+
+    #[rstest]
+    #[case::kind_without_pod_spec("Service", json!({"spec": {}}))]
+    #[case::no_pod_spec("Deployment", json!({"spec": {"replicas": 1}}))]
+    fn patch_without_pod_spec(#[case] kind: &str, #[case] object: Value) {
+        assert!(patch(kind, &object, &Fields::default()).is_err());
+    }
+}
@@ -1,5 +1,5 @@
-use std::collections::{HashMap, HashSet};
-use versions::Versions;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use versions::{DeprecatedFields, POD_TEMPLATES, Versions};
 
 mod cli;
 mod policy_metadata;
@@ -50,9 +50,56 @@ fn generate_metadata_rules() -> Vec<PolicyMetadataRule> {
         });
     }
 
+    metadata_rules.extend(generate_deprecated_fields_rules());
     metadata_rules
 }
 
+// The deprecated fields are found inside of API versions that are still
+// served: the policy is registered only for the resources having them
+fn generate_deprecated_fields_rules() -> Vec<PolicyMetadataRule> {
+    let fields: DeprecatedFields =
+        serde_yaml::from_slice(include_bytes!("../../versions/deprecated-fields.yaml"))
+            .expect("Cannot decode deprecated-fields.yaml");
+
+    let mut relevant_resources: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+    let mut add_resource = |version: &str, kind: &str| {
+        let (api_group, api_version) = version.split_once('/').unwrap_or(("", version));
+        relevant_resources
+            .entry((api_group.to_string(), api_version.to_string()))
+            .or_default()
+            .insert(resource_name(kind));
+    };
+
+    for rule in &fields.deprecated_fields {
+        add_resource(&rule.version, &rule.kind);
+        if rule.version == "v1" && rule.kind == "Pod" {
+            for (version, kind, _) in POD_TEMPLATES {
+                add_resource(version, kind);
+            }
+        }
+    }
+
+    relevant_resources
+        .into_iter()
+        .map(|((api_group, api_version), resources)| PolicyMetadataRule {
+            api_groups: vec![api_group],
+            api_versions: vec![api_version],
+            resources: resources.into_iter().collect(),
+            operations: vec![Operation::Create],
+        })
+        .collect()
+}
+
+// Returns the name of the resource of the kind, like `ingresses` for `Ingress`
+fn resource_name(kind: &str) -> String {
+    let resource = kind.to_lowercase();
+    if resource.ends_with('s') {
+        format!("{resource}es")
+    } else {
+        format!("{resource}s")
+    }
+}
+
 pub fn main() {
     let cli = cli::Cli::new();
     if cli.metadata_path.is_empty() {
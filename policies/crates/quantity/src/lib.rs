@@ -0,0 +1,58 @@
+/// Parses a quantity, like `1Gi`, `500M`, `1e9` or `+1Gi`, into its value
+pub fn parse_quantity(value: &str) -> Option<f64> {
+    let suffix_start = value
+        .char_indices()
+        .find(|&(index, c)| !(c.is_ascii_digit() || c == '.' || (index == 0 && "+-".contains(c))))
+        .map_or(value.len(), |(index, _)| index);
+    let (number, suffix) = value.split_at(suffix_start);
+    let number = number.parse::<f64>().ok()?;
+    let multiplier = match suffix {
+        "" => 1.0,
+        "Ki" => 1024_f64,
+        "Mi" => 1024_f64.powi(2),
+        "Gi" => 1024_f64.powi(3),
+        "Ti" => 1024_f64.powi(4),
+        "Pi" => 1024_f64.powi(5),
+        "Ei" => 1024_f64.powi(6),
+        "n" => 1e-9,
+        "u" => 1e-6,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        exponent => {
+            let exponent = exponent.strip_prefix(['e', 'E'])?.parse::<i32>().ok()?;
+            10_f64.powi(exponent)
+        }
+    };
+    Some(number * multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::bytes("1024", Some(1024.0))]
+    #[case::binary("1Gi", Some(1073741824.0))]
+    #[case::decimal("500M", Some(5e8))]
+    #[case::fraction("1.5Ki", Some(1536.0))]
+    #[case::exponent("1e3", Some(1000.0))]
+    #[case::negative_exponent("1e-3", Some(1e-3))]
+    #[case::milli("1500m", Some(1.5))]
+    #[case::positive("+1Gi", Some(1073741824.0))]
+    #[case::negative("-1Gi", Some(-1073741824.0))]
+    #[case::unknown_suffix("1GB", None)]
+    #[case::without_number("Gi", None)]
+    #[case::only_sign("+Gi", None)]
+    #[case::sign_after_digits("1+Gi", None)]
+    #[case::space("1 Gi", None)]
+    fn quantity(#[case] value: &str, #[case] expected: Option<f64>) {
+        assert_eq!(parse_quantity(value), expected);
+    }
+}
@@ -0,0 +1,60 @@
+use k8s_openapi::Resource;
+use k8s_openapi::api::rbac::v1::{ClusterRole, PolicyRule, Role};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde_json::Value;
+
+/// Returns the metadata and the rules of the Role or the ClusterRole.
+///
+/// Returns `None` for the other kinds and for the aggregated ClusterRoles:
+/// their rules are managed by the control plane, the aggregated roles are
+/// validated instead.
+pub fn extract_rules(
+    kind: &str,
+    object: Value,
+) -> Result<Option<(ObjectMeta, Vec<PolicyRule>)>, serde_json::Error> {
+    match kind {
+        Role::KIND => {
+            let role = serde_json::from_value::<Role>(object)?;
+            Ok(Some((role.metadata, role.rules.unwrap_or_default())))
+        }
+        ClusterRole::KIND => {
+            let cluster_role = serde_json::from_value::<ClusterRole>(object)?;
+            if cluster_role.aggregation_rule.is_some() {
+                return Ok(None);
+            }
+            Ok(Some((
+                cluster_role.metadata,
+                cluster_role.rules.unwrap_or_default(),
+            )))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+    use serde_json::json;
+
+    #[rstest]
+    #[case::role("Role", json!({"metadata": {"name": "reader"}, "rules": [{"verbs": ["get"]}]}), Some(1))]
+    #[case::cluster_role("ClusterRole", json!({"metadata": {"name": "reader"}}), Some(0))]
+    #[case::aggregated_cluster_role(
+        "ClusterRole",
+        json!({"metadata": {"name": "reader"}, "aggregationRule": {"clusterRoleSelectors": []}}),
+        None
+    )]
+    #[case::other_kind("RoleBinding", json!({"metadata": {"name": "reader"}}), None)]
+    fn rules(#[case] kind: &str, #[case] object: Value, #[case] expected: Option<usize>) {
+        let rules = extract_rules(kind, object).expect("cannot extract the rules");
+        assert_eq!(
+            rules.map(|(metadata, rules)| {
+                assert_eq!(metadata.name.as_deref(), Some("reader"));
+                rules.len()
+            }),
+            expected
+        );
+    }
+}
@@ -0,0 +1,390 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Result, anyhow};
+use kubewarden_policy_sdk::{
+    host_capabilities::oci::OciManifestResponse, logging, settings::Validatable,
+};
+use lazy_static::lazy_static;
+use oci_spec::distribution::Reference;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use slog::{Logger, o, warn};
+
+#[cfg(test)]
+use crate::tests::{mock_oci_sdk::get_manifest, mock_verification_sdk::verify_pub_keys_image};
+#[cfg(not(test))]
+use kubewarden_policy_sdk::host_capabilities::{
+    oci::get_manifest, verification::verify_pub_keys_image,
+};
+
+lazy_static! {
+    static ref LOG_DRAIN: Logger = Logger::root(
+        logging::KubewardenDrain::new(),
+        o!("crate" => "rule-bundle")
+    );
+}
+
+/// Annotation of the manifest of the bundle holding its rules, as a JSON
+/// document. The host capabilities give access to the manifests, not to the
+/// layers, of the OCI artifacts.
+pub const RULES_ANNOTATION: &str = "io.kubewarden.rule-bundle";
+
+/// OCI artifact distributing rules signed with Sigstore, so that they can be
+/// updated without rebuilding the policies using them
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RuleBundle {
+    /// Reference of the artifact. When it is a tag, the rules change as soon
+    /// as a new version of the bundle is pushed.
+    pub image: String,
+    /// Public keys, in PEM format, that must have signed the artifact
+    pub keys: Vec<String>,
+    /// Annotations the signatures must have
+    #[serde(default)]
+    pub annotations: Option<BTreeMap<String, String>>,
+    /// When the host capabilities fail, e.g. because the registry cannot be
+    /// reached, evaluate the requests without the rules of the bundle instead
+    /// of rejecting them. The artifacts reported as not trusted and the
+    /// invalid rules are always errors.
+    #[serde(default)]
+    pub optional: bool,
+}
+
+impl RuleBundle {
+    pub fn validate(&self) -> Result<(), String> {
+        self.image
+            .parse::<Reference>()
+            .map_err(|e| format!("invalid rule bundle image '{}': {e}", self.image))?;
+        if self.keys.is_empty() {
+            return Err(format!(
+                "the rule bundle '{}' must have at least one public key",
+                self.image
+            ));
+        }
+        if self.keys.iter().any(|key| key.trim().is_empty()) {
+            return Err(format!(
+                "the public keys of the rule bundle '{}' cannot be empty",
+                self.image
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fetches and validates the rules of the bundle, after verifying its
+    /// signatures. The manifest is fetched by the digest returned by the
+    /// verification: the rules are the signed ones, even when the tag is
+    /// moved in between.
+    ///
+    /// When the bundle is optional and the host capabilities fail, a warning
+    /// is logged and the default, empty, rules are returned.
+    pub fn fetch<T: DeserializeOwned + Validatable + Default>(&self) -> Result<T> {
+        self.fetch_verified()
+            .map_err(|e| anyhow!("rule bundle '{}': {e}", self.image))
+    }
+
+    fn fetch_verified<T: DeserializeOwned + Validatable + Default>(&self) -> Result<T> {
+        let verification =
+            match verify_pub_keys_image(&self.image, self.keys.clone(), self.annotations.clone()) {
+                Ok(verification) => verification,
+                Err(e) => return self.unreachable(anyhow!("cannot verify the signatures: {e}")),
+            };
+        if !verification.is_trusted {
+            return Err(anyhow!(
+                "the artifact is not signed with the configured keys"
+            ));
+        }
+
+        let reference: Reference = self.image.parse()?;
+        let pinned = Reference::with_digest(
+            reference.registry().to_owned(),
+            reference.repository().to_owned(),
+            verification.digest,
+        );
+        let manifest = match get_manifest(&pinned.whole()) {
+            Ok(manifest) => manifest,
+            Err(e) => return self.unreachable(anyhow!("cannot fetch the manifest: {e}")),
+        };
+        let rules: T = parse_rules(&manifest)?;
+        rules
+            .validate()
+            .map_err(|e| anyhow!("invalid rules: {e}"))?;
+        Ok(rules)
+    }
+
+    // Handles the errors of the host capabilities, raised for example when the
+    // registry cannot be reached
+    fn unreachable<T: Default>(&self, error: anyhow::Error) -> Result<T> {
+        if !self.optional {
+            return Err(error);
+        }
+        warn!(
+            LOG_DRAIN,
+            "the optional rule bundle cannot be fetched, its rules are ignored";
+            "image" => &self.image,
+            "error" => error.to_string()
+        );
+        Ok(T::default())
+    }
+}
+
+fn parse_rules<T: DeserializeOwned>(manifest: &OciManifestResponse) -> Result<T> {
+    let OciManifestResponse::Image(manifest) = manifest else {
+        return Err(anyhow!("the artifact is an image index, not a rule bundle"));
+    };
+    let rules = manifest
+        .annotations()
+        .as_ref()
+        .and_then(|annotations| annotations.get(RULES_ANNOTATION))
+        .ok_or_else(|| anyhow!("the manifest does not have the {RULES_ANNOTATION} annotation"))?;
+    serde_json::from_str(rules).map_err(|e| anyhow!("cannot parse the rules: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::host_capabilities::verification::VerificationResponse;
+    use mockall::automock;
+    use rstest::rstest;
+    use serde_json::json;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod verification_sdk {
+        use anyhow::Result;
+        use kubewarden_policy_sdk::host_capabilities::verification::VerificationResponse;
+        use std::collections::BTreeMap;
+
+        #[allow(dead_code)]
+        pub fn verify_pub_keys_image(
+            _image: &str,
+            _pub_keys: Vec<String>,
+            _annotations: Option<BTreeMap<String, String>>,
+        ) -> Result<VerificationResponse> {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    #[automock]
+    pub mod oci_sdk {
+        use kubewarden_policy_sdk::host_capabilities::oci::OciManifestResponse;
+
+        #[allow(dead_code)]
+        pub fn get_manifest(_image: &str) -> anyhow::Result<OciManifestResponse> {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    const DIGEST: &str = "sha256:0b1bb2f37b2a5f8c27b1b0d25a17ca4f1f6ed3ce5d1fb4b20f5f3c2cf7c9f54a";
+
+    #[derive(Deserialize, Debug, Default, PartialEq)]
+    struct Rules {
+        rules: BTreeMap<String, String>,
+    }
+
+    impl Validatable for Rules {
+        fn validate(&self) -> Result<(), String> {
+            if self.rules.values().any(String::is_empty) {
+                return Err("the rules cannot be empty".to_owned());
+            }
+            Ok(())
+        }
+    }
+
+    fn bundle() -> RuleBundle {
+        RuleBundle {
+            image: "ghcr.io/acme/rules:v1".to_owned(),
+            keys: vec!["-----BEGIN PUBLIC KEY-----".to_owned()],
+            annotations: None,
+            optional: false,
+        }
+    }
+
+    fn manifest(annotations: serde_json::Value) -> OciManifestResponse {
+        let manifest = serde_json::from_value(json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.oci.image.manifest.v1+json",
+            "artifactType": "application/vnd.kubewarden.rule-bundle",
+            "config": {
+                "mediaType": "application/vnd.oci.empty.v1+json",
+                "digest": "sha256:44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a",
+                "size": 2
+            },
+            "layers": [],
+            "annotations": annotations
+        }))
+        .expect("cannot build the manifest");
+        OciManifestResponse::Image(Box::new(manifest))
+    }
+
+    #[test]
+    fn validate_bundle() {
+        assert!(bundle().validate().is_ok());
+        assert!(
+            RuleBundle {
+                image: "ghcr.io/acme/Rules".to_owned(),
+                ..bundle()
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            RuleBundle {
+                keys: vec![],
+                ..bundle()
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            RuleBundle {
+                keys: vec![" ".to_owned()],
+                ..bundle()
+            }
+            .validate()
+            .is_err()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn fetch_signed_rules() {
+        let verification_ctx = mock_verification_sdk::verify_pub_keys_image_context();
+        verification_ctx.expect().times(1).returning(|image, _, _| {
+            assert_eq!(image, "ghcr.io/acme/rules:v1");
+            Ok(VerificationResponse {
+                is_trusted: true,
+                digest: DIGEST.to_owned(),
+            })
+        });
+        let ctx = mock_oci_sdk::get_manifest_context();
+        ctx.expect().times(1).returning(|image| {
+            // the manifest must be the verified one
+            assert_eq!(image, format!("ghcr.io/acme/rules@{DIGEST}"));
+            Ok(manifest(json!({
+                "io.kubewarden.rule-bundle": r#"{"rules": {"Acme API key": "acme_[a-z0-9]{32}"}}"#
+            })))
+        });
+
+        let rules: Rules = bundle().fetch().expect("cannot fetch the rules");
+        assert_eq!(
+            rules.rules,
+            BTreeMap::from([("Acme API key".to_owned(), "acme_[a-z0-9]{32}".to_owned())])
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn fetch_unsigned_rules() {
+        let verification_ctx = mock_verification_sdk::verify_pub_keys_image_context();
+        verification_ctx.expect().times(1).returning(|_, _, _| {
+            Ok(VerificationResponse {
+                is_trusted: false,
+                digest: DIGEST.to_owned(),
+            })
+        });
+        let ctx = mock_oci_sdk::get_manifest_context();
+        ctx.expect().times(0);
+
+        let error = bundle()
+            .fetch::<Rules>()
+            .expect_err("the rules are not signed");
+        assert_eq!(
+            error.to_string(),
+            "rule bundle 'ghcr.io/acme/rules:v1': the artifact is not signed with the configured keys"
+        );
+    }
+
+    #[rstest]
+    #[case::missing_annotation(
+        json!({"org.opencontainers.image.title": "rules"}),
+        "rule bundle 'ghcr.io/acme/rules:v1': the manifest does not have the io.kubewarden.rule-bundle annotation"
+    )]
+    #[case::invalid_rules(
+        json!({"io.kubewarden.rule-bundle": r#"{"rules": ["acme_[a-z0-9]{32}"]}"#}),
+        "rule bundle 'ghcr.io/acme/rules:v1': cannot parse the rules: invalid type: sequence"
+    )]
+    #[case::rules_failing_validation(
+        json!({"io.kubewarden.rule-bundle": r#"{"rules": {"Acme API key": ""}}"#}),
+        "rule bundle 'ghcr.io/acme/rules:v1': invalid rules: the rules cannot be empty"
+    )]
+    #[serial]
+    fn fetch_invalid_rules(#[case] annotations: serde_json::Value, #[case] expected_error: &str) {
+        let verification_ctx = mock_verification_sdk::verify_pub_keys_image_context();
+        verification_ctx.expect().times(1).returning(|_, _, _| {
+            Ok(VerificationResponse {
+                is_trusted: true,
+                digest: DIGEST.to_owned(),
+            })
+        });
+        let ctx = mock_oci_sdk::get_manifest_context();
+        ctx.expect()
+            .times(1)
+            .returning(move |_| Ok(manifest(annotations.clone())));
+
+        let error = bundle()
+            .fetch::<Rules>()
+            .expect_err("the rules are invalid");
+        assert!(
+            error.to_string().starts_with(expected_error),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn fetch_from_unreachable_registry() {
+        let verification_ctx = mock_verification_sdk::verify_pub_keys_image_context();
+        verification_ctx
+            .expect()
+            .times(1)
+            .returning(|_, _, _| Err(anyhow!("connection refused")));
+        let ctx = mock_oci_sdk::get_manifest_context();
+        ctx.expect().times(0);
+
+        let error = bundle()
+            .fetch::<Rules>()
+            .expect_err("the registry cannot be reached");
+        assert_eq!(
+            error.to_string(),
+            "rule bundle 'ghcr.io/acme/rules:v1': cannot verify the signatures: connection refused"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn fetch_optional_bundle_from_unreachable_registry() {
+        let verification_ctx = mock_verification_sdk::verify_pub_keys_image_context();
+        verification_ctx
+            .expect()
+            .times(1)
+            .returning(|_, _, _| Err(anyhow!("connection refused")));
+        let ctx = mock_oci_sdk::get_manifest_context();
+        ctx.expect().times(0);
+
+        let bundle = RuleBundle {
+            optional: true,
+            ..bundle()
+        };
+        let rules: Rules = bundle.fetch().expect("the optional bundle is skipped");
+        assert_eq!(rules, Rules::default());
+    }
+
+    #[test]
+    #[serial]
+    fn fetch_optional_unsigned_rules() {
+        let verification_ctx = mock_verification_sdk::verify_pub_keys_image_context();
+        verification_ctx.expect().times(1).returning(|_, _, _| {
+            Ok(VerificationResponse {
+                is_trusted: false,
+                digest: DIGEST.to_owned(),
+            })
+        });
+        let ctx = mock_oci_sdk::get_manifest_context();
+        ctx.expect().times(0);
+
+        let bundle = RuleBundle {
+            optional: true,
+            ..bundle()
+        };
+        assert!(bundle.fetch::<Rules>().is_err());
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+
+/// Settings listing the users, and the groups of users, allowed to bypass
+/// the checks of a policy
+pub trait UserAllowlist {
+    /// Names of the allowed users
+    fn allowed_users(&self) -> &HashSet<String>;
+    /// Names of the allowed groups of users
+    fn allowed_groups(&self) -> &HashSet<String>;
+
+    /// Returns true when the user, or one of its groups, is allowed
+    fn is_user_allowed(&self, username: &str, groups: &[String]) -> bool {
+        self.allowed_users().contains(username)
+            || groups
+                .iter()
+                .any(|group| self.allowed_groups().contains(group))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    struct Allowlist {
+        users: HashSet<String>,
+        groups: HashSet<String>,
+    }
+
+    impl UserAllowlist for Allowlist {
+        fn allowed_users(&self) -> &HashSet<String> {
+            &self.users
+        }
+
+        fn allowed_groups(&self) -> &HashSet<String> {
+            &self.groups
+        }
+    }
+
+    #[rstest]
+    #[case::allowed_user("admin", vec![], true)]
+    #[case::allowed_group("developer", vec!["system:authenticated", "system:masters"], true)]
+    #[case::not_allowed("developer", vec!["system:authenticated"], false)]
+    #[case::no_groups("developer", vec![], false)]
+    fn user_allowed(#[case] username: &str, #[case] groups: Vec<&str>, #[case] expected: bool) {
+        let allowlist = Allowlist {
+            users: ["admin".to_owned()].into(),
+            groups: ["system:masters".to_owned()].into(),
+        };
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(allowlist.is_user_allowed(username, &groups), expected);
+    }
+}
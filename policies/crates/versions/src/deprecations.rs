@@ -2,6 +2,9 @@ use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use kubewarden_policy_sdk::request::GroupVersionKind;
+
+use crate::api_version;
 use crate::serde_helpers::option_semver_serde;
 
 pub type DeprecationRules = Vec<DeprecationRule>;
@@ -11,49 +14,67 @@ pub type DeprecationRules = Vec<DeprecationRule>;
 pub struct DeprecationRule {
     pub version: String,
     pub kind: String,
+    #[serde(default)]
     #[serde(with = "option_semver_serde")]
     pub deprecated_in: Option<semver::Version>,
     #[serde(default)]
     #[serde(with = "option_semver_serde")]
     pub removed_in: Option<semver::Version>,
+    #[serde(default)]
     pub replacement_api: String,
+    #[serde(default)]
     pub component: String,
 }
 
 impl DeprecationRule {
-    pub fn includes(&self, kubernetes_version: &semver::Version) -> bool {
-        if let Some(removed_in) = &self.removed_in
-            && kubernetes_version >= removed_in
-        {
-            return true;
-        }
-        if let Some(deprecated_in) = &self.deprecated_in
-            && kubernetes_version >= deprecated_in
-        {
-            return true;
-        }
+    /// Returns true when the rule is about the kind and the API version of the object
+    pub fn matches(&self, obj: &GroupVersionKind) -> bool {
+        self.kind == obj.kind && self.version == api_version(obj)
+    }
 
-        false
+    pub fn includes(&self, kubernetes_version: &semver::Version) -> bool {
+        lifecycle_stage(&self.deprecated_in, &self.removed_in, kubernetes_version).is_some()
     }
 
     pub fn is_only_deprecated(&self, kubernetes_version: &semver::Version) -> Result<bool> {
-        if let Some(removed_in) = &self.removed_in
-            && kubernetes_version >= removed_in
-        {
-            return Ok(false);
-        }
-        if let Some(deprecated_in) = &self.deprecated_in
-            && kubernetes_version >= deprecated_in
-        {
-            return Ok(true);
-        }
+        lifecycle_stage(&self.deprecated_in, &self.removed_in, kubernetes_version)
+            .map(|stage| stage == LifecycleStage::Deprecated)
+            .ok_or_else(|| {
+                anyhow!(
+                    "The deprecation rule '{}' does not apply to kubernetes version: {}",
+                    self,
+                    kubernetes_version
+                )
+            })
+    }
+}
 
-        Err(anyhow!(
-            "The deprecation rule '{}' does not apply to kubernetes version: {}",
-            self,
-            kubernetes_version
-        ))
+/// Stage reached by an API, or by a field, in a given Kubernetes version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LifecycleStage {
+    Deprecated,
+    Removed,
+}
+
+/// Returns the stage reached in the Kubernetes version, none when the API, or
+/// the field, is neither deprecated nor removed yet
+pub(crate) fn lifecycle_stage(
+    deprecated_in: &Option<semver::Version>,
+    removed_in: &Option<semver::Version>,
+    kubernetes_version: &semver::Version,
+) -> Option<LifecycleStage> {
+    if let Some(removed_in) = removed_in
+        && kubernetes_version >= removed_in
+    {
+        return Some(LifecycleStage::Removed);
+    }
+    if let Some(deprecated_in) = deprecated_in
+        && kubernetes_version >= deprecated_in
+    {
+        return Some(LifecycleStage::Deprecated);
     }
+
+    None
 }
 
 impl fmt::Display for DeprecationRule {
@@ -68,7 +89,9 @@ impl fmt::Display for DeprecationRule {
         if let Some(removed_in) = &self.removed_in {
             msgs.push(format!("It has been removed starting from {removed_in}."));
         }
-        msgs.push(format!("It has been replaced by {}.", self.replacement_api));
+        if !self.replacement_api.is_empty() {
+            msgs.push(format!("It has been replaced by {}.", self.replacement_api));
+        }
 
         write!(f, "{}", msgs.join(" "))
     }
@@ -105,6 +128,49 @@ component: k8s"#,
         }
     }
 
+    #[test]
+    fn deserialize_deprecation_without_replacement() {
+        let deprecation: DeprecationRule = serde_yaml::from_str(
+            r#"
+version: example.com/v1alpha1
+kind: Widget
+deprecated-in: v1.30.0"#,
+        )
+        .expect("cannot deserialize");
+
+        assert!(deprecation.removed_in.is_none());
+        assert_eq!(
+            deprecation.to_string(),
+            "example.com/v1alpha1 Widget cannot be used. It has been deprecated starting from 1.30.0."
+        );
+    }
+
+    #[test]
+    fn deprecation_matches_object() {
+        let deprecation: DeprecationRule = serde_yaml::from_str(
+            r#"
+version: v1
+kind: ComponentStatus
+deprecated-in: v1.19.0
+component: k8s"#,
+        )
+        .expect("cannot deserialize");
+
+        let obj = GroupVersionKind {
+            group: "".to_string(),
+            version: "v1".to_string(),
+            kind: "ComponentStatus".to_string(),
+        };
+        assert!(deprecation.matches(&obj));
+
+        let obj = GroupVersionKind {
+            group: "example.com".to_string(),
+            version: "v1".to_string(),
+            kind: "ComponentStatus".to_string(),
+        };
+        assert!(!deprecation.matches(&obj));
+    }
+
     #[test]
     fn deprecation_applies_to_kubernetes_release() {
         let deprecation: DeprecationRule = serde_yaml::from_str(
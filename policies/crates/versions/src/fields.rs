@@ -0,0 +1,276 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+use kubewarden_policy_sdk::request::GroupVersionKind;
+
+use crate::deprecations::{LifecycleStage, lifecycle_stage};
+use crate::serde_helpers::option_semver_serde;
+use crate::{DeprecatedFields, api_version};
+
+pub type FieldDeprecationRules = Vec<FieldDeprecationRule>;
+
+/// Kinds embedding a Pod template, with the path of the template. The rules
+/// about the fields of the `v1` Pods apply to these templates too.
+pub const POD_TEMPLATES: &[(&str, &str, &[&str])] = &[
+    ("v1", "ReplicationController", &["spec", "template"]),
+    ("apps/v1", "DaemonSet", &["spec", "template"]),
+    ("apps/v1", "Deployment", &["spec", "template"]),
+    ("apps/v1", "ReplicaSet", &["spec", "template"]),
+    ("apps/v1", "StatefulSet", &["spec", "template"]),
+    (
+        "batch/v1",
+        "CronJob",
+        &["spec", "jobTemplate", "spec", "template"],
+    ),
+    ("batch/v1", "Job", &["spec", "template"]),
+];
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct FieldDeprecationRule {
+    pub version: String,
+    pub kind: String,
+    /// Path of the deprecated field. The `*` segment matches all the keys of
+    /// an object and all the items of an array, a segment ending with `*`
+    /// matches the keys starting with the rest of the segment.
+    pub path: Vec<String>,
+    #[serde(default)]
+    #[serde(with = "option_semver_serde")]
+    pub deprecated_in: Option<semver::Version>,
+    #[serde(default)]
+    #[serde(with = "option_semver_serde")]
+    pub removed_in: Option<semver::Version>,
+    /// Path of the field replacing the deprecated one. The deprecated field is
+    /// not reported when its replacement is set too: the API server keeps
+    /// some legacy fields in sync for the older clients.
+    #[serde(default)]
+    pub replacement: Vec<String>,
+}
+
+impl FieldDeprecationRule {
+    pub fn includes(&self, kubernetes_version: &semver::Version) -> bool {
+        lifecycle_stage(&self.deprecated_in, &self.removed_in, kubernetes_version).is_some()
+    }
+
+    pub fn is_only_deprecated(&self, kubernetes_version: &semver::Version) -> bool {
+        lifecycle_stage(&self.deprecated_in, &self.removed_in, kubernetes_version)
+            == Some(LifecycleStage::Deprecated)
+    }
+
+    /// Returns true when the object, or its Pod template, uses the deprecated
+    /// field
+    pub fn is_used(&self, obj: &GroupVersionKind, object: &Value) -> bool {
+        self.target(obj, object).is_some_and(|target| {
+            is_set(target, &self.path)
+                && (self.replacement.is_empty() || !is_set(target, &self.replacement))
+        })
+    }
+
+    // Returns the part of the object the paths of the rule are relative to:
+    // the object itself, or its Pod template for the rules about the Pods
+    fn target<'a>(&self, obj: &GroupVersionKind, object: &'a Value) -> Option<&'a Value> {
+        let obj_version = api_version(obj);
+        if self.version == obj_version && self.kind == obj.kind {
+            return Some(object);
+        }
+        if self.version != "v1" || self.kind != "Pod" {
+            return None;
+        }
+        POD_TEMPLATES
+            .iter()
+            .find(|(version, kind, _)| *version == obj_version && *kind == obj.kind)
+            .and_then(|(_, _, template)| {
+                template
+                    .iter()
+                    .try_fold(object, |value, segment| value.get(segment))
+            })
+    }
+}
+
+// Returns true when a value that is not null is found at the path
+fn is_set(value: &Value, path: &[String]) -> bool {
+    let Some((segment, rest)) = path.split_first() else {
+        return !value.is_null();
+    };
+    match value {
+        Value::Object(fields) => match segment.strip_suffix('*') {
+            Some(prefix) => fields
+                .iter()
+                .filter(|(key, _)| key.starts_with(prefix))
+                .any(|(_, field)| is_set(field, rest)),
+            None => fields.get(segment).is_some_and(|field| is_set(field, rest)),
+        },
+        Value::Array(items) if segment == "*" => items.iter().any(|item| is_set(item, rest)),
+        _ => false,
+    }
+}
+
+// Formats the path like `metadata.annotations["example.com/key"]`
+fn format_path(path: &[String]) -> String {
+    let mut formatted = String::new();
+    for segment in path {
+        if segment.contains(['.', '/']) {
+            formatted.push_str(&format!("[\"{segment}\"]"));
+        } else {
+            if !formatted.is_empty() {
+                formatted.push('.');
+            }
+            formatted.push_str(segment);
+        }
+    }
+    formatted
+}
+
+impl fmt::Display for FieldDeprecationRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut msgs = vec![format!(
+            "{} {} field {} cannot be used.",
+            self.version,
+            self.kind,
+            format_path(&self.path)
+        )];
+        if let Some(deprecated_in) = &self.deprecated_in {
+            msgs.push(format!(
+                "It has been deprecated starting from {deprecated_in}."
+            ));
+        }
+        if let Some(removed_in) = &self.removed_in {
+            msgs.push(format!("It has been removed starting from {removed_in}."));
+        }
+        if !self.replacement.is_empty() {
+            msgs.push(format!(
+                "It has been replaced by {}.",
+                format_path(&self.replacement)
+            ));
+        }
+
+        write!(f, "{}", msgs.join(" "))
+    }
+}
+
+pub struct FieldDeprecationChecker {
+    rules: FieldDeprecationRules,
+}
+
+impl FieldDeprecationChecker {
+    pub fn from_yaml(data: &[u8]) -> Result<Self, serde_yaml::Error> {
+        let fields: DeprecatedFields = serde_yaml::from_slice(data)?;
+
+        Ok(FieldDeprecationChecker {
+            rules: fields.deprecated_fields,
+        })
+    }
+
+    /// Returns the rules about the deprecated fields used by the object
+    pub fn check(
+        &self,
+        obj: &GroupVersionKind,
+        object: &Value,
+        kubernetes_version: &semver::Version,
+    ) -> Vec<FieldDeprecationRule> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.includes(kubernetes_version) && rule.is_used(obj, object))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use semver::Version;
+    use serde_json::json;
+
+    fn seccomp_rule() -> FieldDeprecationRule {
+        serde_yaml::from_str(
+            r#"
+version: v1
+kind: Pod
+path: [metadata, annotations, "container.seccomp.security.alpha.kubernetes.io/*"]
+deprecated-in: v1.19.0
+replacement: [spec, containers, "*", securityContext, seccompProfile]"#,
+        )
+        .expect("cannot deserialize")
+    }
+
+    fn gvk(group: &str, version: &str, kind: &str) -> GroupVersionKind {
+        GroupVersionKind {
+            group: group.to_string(),
+            version: version.to_string(),
+            kind: kind.to_string(),
+        }
+    }
+
+    #[test]
+    fn field_is_set() {
+        let object = json!({
+            "metadata": {"annotations": {"example.com/owner": "team-a"}},
+            "spec": {"containers": [{"name": "web"}, {"name": "sidecar", "image": "sidecar:1.0"}]},
+        });
+        let path = |path: &[&str]| -> Vec<String> { path.iter().map(|s| s.to_string()).collect() };
+
+        assert!(is_set(
+            &object,
+            &path(&["metadata", "annotations", "example.com/owner"])
+        ));
+        assert!(is_set(
+            &object,
+            &path(&["metadata", "annotations", "example.com/*"])
+        ));
+        assert!(is_set(
+            &object,
+            &path(&["spec", "containers", "*", "image"])
+        ));
+        assert!(!is_set(
+            &object,
+            &path(&["metadata", "annotations", "example.org/*"])
+        ));
+        assert!(!is_set(
+            &object,
+            &path(&["spec", "containers", "*", "command"])
+        ));
+        assert!(!is_set(&object, &path(&["spec", "containers", "name"])));
+    }
+
+    #[test]
+    fn field_used_by_pod_and_pod_templates() {
+        let rule = seccomp_rule();
+        let annotations =
+            json!({"container.seccomp.security.alpha.kubernetes.io/web": "runtime/default"});
+
+        let pod = json!({"metadata": {"annotations": annotations}, "spec": {"containers": [{"name": "web"}]}});
+        assert!(rule.is_used(&gvk("", "v1", "Pod"), &pod));
+
+        let deployment = json!({"spec": {"template": pod}});
+        assert!(rule.is_used(&gvk("apps", "v1", "Deployment"), &deployment));
+        assert!(!rule.is_used(&gvk("apps", "v1beta1", "Deployment"), &deployment));
+
+        let cronjob = json!({"spec": {"jobTemplate": {"spec": {"template": pod}}}});
+        assert!(rule.is_used(&gvk("batch", "v1", "CronJob"), &cronjob));
+        assert!(!rule.is_used(&gvk("batch", "v1", "Job"), &cronjob));
+
+        let pod_with_replacement = json!({
+            "metadata": {"annotations": annotations},
+            "spec": {"containers": [{"name": "web", "securityContext": {"seccompProfile": {"type": "RuntimeDefault"}}}]},
+        });
+        assert!(!rule.is_used(&gvk("", "v1", "Pod"), &pod_with_replacement));
+    }
+
+    #[test]
+    fn field_deprecation_applies_to_kubernetes_release() {
+        let rule = seccomp_rule();
+        assert!(!rule.includes(&Version::new(1, 18, 0)));
+        assert!(rule.includes(&Version::new(1, 19, 0)));
+        assert!(rule.is_only_deprecated(&Version::new(1, 30, 0)));
+    }
+
+    #[test]
+    fn display_field_deprecation() {
+        assert_eq!(
+            seccomp_rule().to_string(),
+            r#"v1 Pod field metadata.annotations["container.seccomp.security.alpha.kubernetes.io/*"] cannot be used. It has been deprecated starting from 1.19.0. It has been replaced by spec.containers.*.securityContext.seccompProfile."#
+        );
+    }
+}
@@ -9,7 +9,12 @@ use std::collections::HashMap;
 pub mod serde_helpers;
 
 mod deprecations;
-use deprecations::{DeprecationRule, DeprecationRules};
+pub use deprecations::{DeprecationRule, DeprecationRules};
+
+mod fields;
+pub use fields::{
+    FieldDeprecationChecker, FieldDeprecationRule, FieldDeprecationRules, POD_TEMPLATES,
+};
 
 lazy_static! {
     pub static ref DEPRECATION_CHECKER: DeprecationChecker = {
@@ -17,6 +22,11 @@ lazy_static! {
             "Cannot deserialize the embedded versions. Check the 'versions.yaml' file for errors",
         )
     };
+    pub static ref FIELD_DEPRECATION_CHECKER: FieldDeprecationChecker = {
+        FieldDeprecationChecker::from_yaml(include_bytes!("../deprecated-fields.yaml")).expect(
+            "Cannot deserialize the embedded deprecated fields. Check the 'deprecated-fields.yaml' file for errors",
+        )
+    };
 }
 
 #[derive(Deserialize, Debug)]
@@ -25,6 +35,12 @@ pub struct Versions {
     pub deprecated_versions: DeprecationRules,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct DeprecatedFields {
+    pub deprecated_fields: FieldDeprecationRules,
+}
+
 pub struct DeprecationChecker {
     deprecated_versions_map: HashMap<String, DeprecationRules>,
 }
@@ -55,10 +71,7 @@ impl DeprecationChecker {
         obj: &GroupVersionKind,
         kubernetes_version: &semver::Version,
     ) -> Option<DeprecationRule> {
-        if let Some(deprecations) = self
-            .deprecated_versions_map
-            .get(format!("{}/{}", obj.group, obj.version).as_str())
-        {
+        if let Some(deprecations) = self.deprecated_versions_map.get(&api_version(obj)) {
             deprecations
                 .iter()
                 .find(move |&deprecation| {
@@ -71,6 +84,15 @@ impl DeprecationChecker {
     }
 }
 
+/// Returns the `apiVersion` of the object, as written in the deprecation rules
+pub(crate) fn api_version(obj: &GroupVersionKind) -> String {
+    if obj.group.is_empty() {
+        obj.version.clone()
+    } else {
+        format!("{}/{}", obj.group, obj.version)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +133,22 @@ deprecated-versions:
         let deprecation = deprecated_versions.check(&obj, &kubernetes_version);
         assert!(deprecation.is_none());
     }
+
+    #[test]
+    fn check_embedded_deprecated_fields() {
+        let obj = GroupVersionKind {
+            group: "networking.k8s.io".to_string(),
+            version: "v1".to_string(),
+            kind: "Ingress".to_string(),
+        };
+        let object = serde_json::json!({
+            "metadata": {"annotations": {"kubernetes.io/ingress.class": "nginx"}},
+        });
+
+        let deprecations = FIELD_DEPRECATION_CHECKER.check(&obj, &object, &Version::new(1, 30, 0));
+        assert_eq!(deprecations.len(), 1);
+
+        let deprecations = FIELD_DEPRECATION_CHECKER.check(&obj, &object, &Version::new(1, 17, 0));
+        assert!(deprecations.is_empty());
+    }
 }
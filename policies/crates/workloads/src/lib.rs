@@ -0,0 +1,51 @@
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+
+/// Returns true when one of the owners is the controller of the resource
+pub fn is_controlled(metadata: &ObjectMeta) -> bool {
+    metadata
+        .owner_references
+        .iter()
+        .flatten()
+        .any(|owner| owner.controller == Some(true))
+}
+
+/// Returns true for the restart policy of the sidecar containers: the init
+/// containers that keep running along the main containers
+pub fn is_sidecar(restart_policy: Option<&str>) -> bool {
+    restart_policy == Some("Always")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::no_owners(vec![], false)]
+    #[case::controller(vec![Some(true)], true)]
+    #[case::owner_not_controller(vec![None, Some(false)], false)]
+    fn controlled(#[case] controllers: Vec<Option<bool>>, #[case] expected: bool) {
+        let metadata = ObjectMeta {
+            owner_references: Some(
+                controllers
+                    .into_iter()
+                    .map(|controller| OwnerReference {
+                        controller,
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        assert_eq!(is_controlled(&metadata), expected);
+    }
+
+    #[rstest]
+    #[case::always(Some("Always"), true)]
+    #[case::unset(None, false)]
+    fn sidecar(#[case] restart_policy: Option<&str>, #[case] expected: bool) {
+        assert_eq!(is_sidecar(restart_policy), expected);
+    }
+}
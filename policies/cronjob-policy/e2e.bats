@@ -0,0 +1,65 @@
+#!/usr/bin/env bats
+
+@test "Accept a valid CronJob" {
+	run kwctl run \
+		--request-path test_data/cronjob_valid.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a CronJob running too often" {
+	run kwctl run \
+		--request-path test_data/cronjob_too_frequent.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*runs every 5 minutes, below the minimum interval of 15 minutes.*') -ne 0 ]
+	[ $(expr "$output" : '.*timeZone Europe/Rome is not allowed.*') -ne 0 ]
+}
+
+@test "Reject a CronJob allowing concurrent runs" {
+	run kwctl run \
+		--request-path test_data/cronjob_defaults.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*concurrencyPolicy Allow is not allowed.*') -ne 0 ]
+	[ $(expr "$output" : '.*startingDeadlineSeconds must be set.*') -ne 0 ]
+}
+
+@test "Accept any CronJob with the default settings" {
+	run kwctl run \
+		--request-path test_data/cronjob_defaults.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/cronjob_valid.json --settings-json '{"allowedConcurrencyPolicies": ["Queue"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
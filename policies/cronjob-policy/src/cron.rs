@@ -0,0 +1,193 @@
+use croner::Cron;
+
+// The days of the week and the leap years repeat every 28 years, as long as
+// no century that is not a leap year is crossed. Going through them finds all
+// the combinations of month, day of the month and day of the week.
+const FIRST_YEAR: i32 = 2001;
+const YEARS: i32 = 28;
+
+// Parses the durations of the `@every` schedules, like `1h30m`
+fn parse_duration(duration: &str) -> Result<u64, String> {
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in duration.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(format!("invalid duration '{duration}'")),
+        };
+        let value = number
+            .parse::<u64>()
+            .map_err(|_| format!("invalid duration '{duration}'"))?;
+        seconds += value * unit;
+        number.clear();
+    }
+    if !number.is_empty() || duration.is_empty() {
+        return Err(format!("invalid duration '{duration}'"));
+    }
+    // The CronJob controller runs the schedules at most once per second
+    Ok(seconds.max(1))
+}
+
+/// Schedule of a CronJob
+#[derive(Debug, Clone)]
+pub(crate) enum Schedule {
+    /// Runs at a fixed interval, in seconds
+    Every(u64),
+    Cron(Box<Cron>),
+}
+
+impl Schedule {
+    /// Parses the schedules in the standard cron format, and the descriptors
+    /// like `@daily` and `@every 1h`
+    pub(crate) fn parse(schedule: &str) -> Result<Self, String> {
+        let schedule = schedule.trim();
+        if schedule.starts_with("TZ=") || schedule.starts_with("CRON_TZ=") {
+            return Err("use the timeZone field to set the time zone".to_owned());
+        }
+        if let Some(duration) = schedule.strip_prefix("@every ") {
+            return parse_duration(duration.trim()).map(Schedule::Every);
+        }
+        // croner knows all the descriptors supported by Kubernetes, but
+        // `@midnight`
+        let schedule = if schedule.eq_ignore_ascii_case("@midnight") {
+            "@daily"
+        } else {
+            schedule
+        };
+        Cron::new(schedule)
+            .parse()
+            .map(|cron| Schedule::Cron(Box::new(cron)))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Returns the shortest interval between two runs, in seconds. Returns
+    /// None when the schedule never runs.
+    pub(crate) fn minimum_interval(&self) -> Option<u64> {
+        let pattern = match self {
+            Schedule::Every(seconds) => return Some(*seconds),
+            Schedule::Cron(cron) => &cron.pattern,
+        };
+
+        // Days with runs, counted from the first day of the cycle
+        let run_days: Vec<u64> = days()
+            .enumerate()
+            .filter(|(_, (year, month, day))| {
+                pattern.month_match(*month).unwrap_or(false)
+                    && pattern.day_match(*year, *month, *day).unwrap_or(false)
+            })
+            .map(|(day, _)| day as u64)
+            .collect();
+        if run_days.is_empty() {
+            return None;
+        }
+
+        // Minutes of the day of the runs, sorted
+        let times: Vec<u64> = (0..24)
+            .filter(|hour| pattern.hour_match(*hour).unwrap_or(false))
+            .flat_map(|hour| {
+                (0..60)
+                    .filter(|minute| pattern.minute_match(*minute).unwrap_or(false))
+                    .map(move |minute| u64::from(hour * 60 + minute))
+            })
+            .collect();
+        let (&first, &last) = (times.first()?, times.last()?);
+        let between_days = run_days
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]) * 24 * 60 + first - last);
+        times
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .chain(between_days)
+            .min()
+            .map(|minutes| minutes * 60)
+    }
+}
+
+// Iterates over the days of the cycle, returning the year, the month and the
+// day of the month of each of them
+fn days() -> impl Iterator<Item = (i32, u32, u32)> {
+    (FIRST_YEAR..FIRST_YEAR + YEARS)
+        .flat_map(|year| (1..=12).map(move |month| (year, month)))
+        .flat_map(|(year, month)| {
+            (1..=days_in_month(year, month)).map(move |day| (year, month, day))
+        })
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Formats an interval in seconds, in minutes when it is a whole number of
+/// minutes
+pub(crate) fn format_interval(seconds: u64) -> String {
+    if seconds.is_multiple_of(60) {
+        format!("{} minutes", seconds / 60)
+    } else {
+        format!("{seconds} seconds")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::every_minute("* * * * *", Some(60))]
+    #[case::every_five_minutes("*/5 * * * *", Some(5 * 60))]
+    #[case::uneven_minutes("0,10,45 * * * *", Some(10 * 60))]
+    #[case::across_hours("50 */2 * * *", Some(2 * 3600))]
+    #[case::twice_a_day("0 8,20 * * *", Some(12 * 3600))]
+    #[case::across_days("0 1,23 * * *", Some(2 * 3600))]
+    #[case::hourly("@hourly", Some(3600))]
+    #[case::daily("@daily", Some(24 * 3600))]
+    #[case::midnight("@midnight", Some(24 * 3600))]
+    #[case::weekdays("0 9 * * MON-FRI", Some(24 * 3600))]
+    #[case::weekends("30 9 * * sat,sun", Some(24 * 3600))]
+    #[case::weekly("@weekly", Some(7 * 24 * 3600))]
+    #[case::monthly("0 0 1 * *", Some(28 * 24 * 3600))]
+    #[case::days_of_month_or_week("0 0 1 * MON", Some(24 * 3600))]
+    #[case::yearly("@yearly", Some(365 * 24 * 3600))]
+    #[case::leap_day("0 0 29 FEB *", Some(4 * 365 * 24 * 3600 + 24 * 3600))]
+    #[case::every_duration("@every 1h30m", Some(90 * 60))]
+    #[case::every_seconds("@every 30s", Some(30))]
+    #[case::never("0 0 30 2 *", None)]
+    fn minimum_interval(#[case] schedule: &str, #[case] expected: Option<u64>) {
+        let schedule = Schedule::parse(schedule).expect("cannot parse the schedule");
+        assert_eq!(schedule.minimum_interval(), expected);
+    }
+
+    #[rstest]
+    #[case::too_few_fields(
+        "* * * *",
+        "Invalid pattern: Pattern must consist of five or six fields (minute, hour, day, month, day of week, and optional second)."
+    )]
+    #[case::seconds(
+        "0 * * * * *",
+        "Invalid pattern: Pattern must consist of five fields, seconds are not allowed by configuration."
+    )]
+    #[case::out_of_range("60 * * * *", "Component error: Number out of bounds.")]
+    #[case::reversed_range("0 5-1 * * *", "Component error: Range out of bounds.")]
+    #[case::invalid_step("*/0 * * * *", "Component error: Step cannot be zero.")]
+    #[case::invalid_value("0 0 * * MONDAY", "Component error: Invalid number.")]
+    #[case::invalid_duration("@every 1d", "invalid duration '1d'")]
+    #[case::time_zone(
+        "TZ=Europe/Rome 0 0 * * *",
+        "use the timeZone field to set the time zone"
+    )]
+    fn invalid_schedule(#[case] schedule: &str, #[case] expected: &str) {
+        assert_eq!(Schedule::parse(schedule).err(), Some(expected.to_owned()));
+    }
+}
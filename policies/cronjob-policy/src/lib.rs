@@ -0,0 +1,198 @@
+use std::collections::BTreeSet;
+
+use guest::prelude::*;
+use k8s_openapi::api::batch::v1::CronJob;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod cron;
+use cron::{Schedule, format_interval};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+
+    let cronjob = match serde_json::from_value::<CronJob>(request.object) {
+        Ok(cronjob) => cronjob,
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    if let Err(error) = validate_cronjob(&cronjob, &validation_request.settings) {
+        return kubewarden::reject_request(
+            Some(format!("CronJob '{}': {error}", request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+// Returns an error when the value is not allowed
+fn validate_value(name: &str, value: &str, allowed: &BTreeSet<String>) -> Result<(), String> {
+    if allowed.is_empty() || allowed.contains(value) {
+        return Ok(());
+    }
+    Err(format!(
+        "{name} {value} is not allowed, use one of: {}",
+        allowed.iter().cloned().collect::<Vec<String>>().join(", ")
+    ))
+}
+
+// Returns an error when the schedule runs more often than allowed
+fn validate_schedule(schedule: &str, minimum_interval_minutes: u64) -> Result<(), String> {
+    let interval = Schedule::parse(schedule)
+        .map_err(|e| format!("cannot parse the schedule '{schedule}': {e}"))?
+        .minimum_interval();
+    match interval {
+        Some(interval) if interval < minimum_interval_minutes * 60 => Err(format!(
+            "the schedule '{schedule}' runs every {}, below the minimum interval of {minimum_interval_minutes} minutes",
+            format_interval(interval)
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn validate_cronjob(cronjob: &CronJob, settings: &Settings) -> Result<(), String> {
+    let spec = cronjob.spec.clone().unwrap_or_default();
+    let mut errors = vec![];
+
+    if let Some(minimum_interval_minutes) = settings.minimum_interval_minutes
+        && let Err(error) = validate_schedule(&spec.schedule, minimum_interval_minutes)
+    {
+        errors.push(error);
+    }
+    // The values that are not set are the ones defaulted by Kubernetes
+    if let Err(error) = validate_value(
+        "concurrencyPolicy",
+        spec.concurrency_policy.as_deref().unwrap_or("Allow"),
+        &settings.allowed_concurrency_policies,
+    ) {
+        errors.push(error);
+    }
+    if settings.require_starting_deadline_seconds && spec.starting_deadline_seconds.is_none() {
+        errors.push("startingDeadlineSeconds must be set".to_owned());
+    }
+    for (name, value, limit) in [
+        (
+            "successfulJobsHistoryLimit",
+            spec.successful_jobs_history_limit.unwrap_or(3),
+            settings.max_successful_jobs_history_limit,
+        ),
+        (
+            "failedJobsHistoryLimit",
+            spec.failed_jobs_history_limit.unwrap_or(1),
+            settings.max_failed_jobs_history_limit,
+        ),
+    ] {
+        if let Some(limit) = limit
+            && value > limit
+        {
+            errors.push(format!("{name} {value} is above the limit of {limit}"));
+        }
+    }
+    // The CronJobs without time zone use the one of the kube-controller-manager
+    if let Some(time_zone) = &spec.time_zone
+        && let Err(error) = validate_value("timeZone", time_zone, &settings.allowed_time_zones)
+    {
+        errors.push(error);
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            minimum_interval_minutes: Some(15),
+            allowed_concurrency_policies: BTreeSet::from([
+                "Forbid".to_owned(),
+                "Replace".to_owned(),
+            ]),
+            require_starting_deadline_seconds: true,
+            max_successful_jobs_history_limit: Some(3),
+            max_failed_jobs_history_limit: Some(1),
+            allowed_time_zones: BTreeSet::from(["Etc/UTC".to_owned()]),
+        }
+    }
+
+    #[rstest]
+    #[case::valid("test_data/cronjob_valid.json", true)]
+    #[case::too_frequent("test_data/cronjob_too_frequent.json", false)]
+    #[case::defaults("test_data/cronjob_defaults.json", false)]
+    #[case::invalid_schedule("test_data/cronjob_invalid_schedule.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn default_settings() {
+        let test_case = Testcase {
+            name: "default settings".to_owned(),
+            fixture_file: "test_data/cronjob_defaults.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::too_frequent(
+        "test_data/cronjob_too_frequent.json",
+        "CronJob 'report': the schedule '*/5 * * * *' runs every 5 minutes, below the minimum interval of 15 minutes; successfulJobsHistoryLimit 10 is above the limit of 3; timeZone Europe/Rome is not allowed, use one of: Etc/UTC"
+    )]
+    #[case::defaults(
+        "test_data/cronjob_defaults.json",
+        "CronJob 'report': concurrencyPolicy Allow is not allowed, use one of: Forbid, Replace; startingDeadlineSeconds must be set"
+    )]
+    #[case::invalid_schedule(
+        "test_data/cronjob_invalid_schedule.json",
+        "CronJob 'report': cannot parse the schedule '0 0 * * MONDAY': Component error: Invalid number."
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+const CONCURRENCY_POLICIES: &[&str] = &["Allow", "Forbid", "Replace"];
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Minimum number of minutes between two runs of the CronJobs
+    pub minimum_interval_minutes: Option<u64>,
+    /// Allowed values of `concurrencyPolicy`. All the values are allowed when
+    /// empty.
+    pub allowed_concurrency_policies: BTreeSet<String>,
+    /// Reject the CronJobs without `startingDeadlineSeconds`
+    pub require_starting_deadline_seconds: bool,
+    /// Maximum number of successful Jobs kept by the CronJobs
+    pub max_successful_jobs_history_limit: Option<i32>,
+    /// Maximum number of failed Jobs kept by the CronJobs
+    pub max_failed_jobs_history_limit: Option<i32>,
+    /// Allowed values of `timeZone`. All the time zones are allowed when
+    /// empty.
+    pub allowed_time_zones: BTreeSet<String>,
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.minimum_interval_minutes == Some(0) {
+            return Err("minimumIntervalMinutes must be greater than zero".to_owned());
+        }
+        if let Some(unknown) = self
+            .allowed_concurrency_policies
+            .iter()
+            .find(|policy| !CONCURRENCY_POLICIES.contains(&policy.as_str()))
+        {
+            return Err(format!(
+                "allowedConcurrencyPolicies: unknown value '{unknown}', use one of: {}",
+                CONCURRENCY_POLICIES.join(", ")
+            ));
+        }
+        for (name, limit) in [
+            (
+                "maxSuccessfulJobsHistoryLimit",
+                self.max_successful_jobs_history_limit,
+            ),
+            (
+                "maxFailedJobsHistoryLimit",
+                self.max_failed_jobs_history_limit,
+            ),
+        ] {
+            if limit.is_some_and(|limit| limit < 0) {
+                return Err(format!("{name} cannot be negative"));
+            }
+        }
+        if self.allowed_time_zones.iter().any(String::is_empty) {
+            return Err("allowedTimeZones cannot contain empty names".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "minimumIntervalMinutes": 15,
+            "allowedConcurrencyPolicies": ["Forbid", "Replace"],
+            "requireStartingDeadlineSeconds": true,
+            "maxSuccessfulJobsHistoryLimit": 3,
+            "maxFailedJobsHistoryLimit": 1,
+            "allowedTimeZones": ["Etc/UTC"]
+        }"#,
+        true
+    )]
+    #[case::zero_interval(r#"{"minimumIntervalMinutes": 0}"#, false)]
+    #[case::unknown_concurrency_policy(r#"{"allowedConcurrencyPolicies": ["Queue"]}"#, false)]
+    #[case::negative_history_limit(r#"{"maxFailedJobsHistoryLimit": -1}"#, false)]
+    #[case::empty_time_zone(r#"{"allowedTimeZones": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,81 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod using an existing SecretProviderClass" {
+	run kwctl run \
+		--request-path test_data/pod_secrets_store.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-secret-provider-class-found.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Pod using a missing SecretProviderClass" {
+	run kwctl run \
+		--request-path test_data/pod_secrets_store.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-secret-provider-class-not-found.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the SecretProviderClass 'team-a/vault-database' cannot be found.*") -ne 0 ]
+}
+
+@test "Reject a Pod with a volumeAttribute not allowed" {
+	run kwctl run \
+		--request-path test_data/pod_secrets_store_pod_identity.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the volumeAttribute 'usePodIdentity' cannot be 'true'.*") -ne 0 ]
+}
+
+@test "Reject a Deployment using a CSI driver not allowed" {
+	run kwctl run \
+		--request-path test_data/deployment_other_driver.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the CSI driver 'inline.storage.kubernetes.io' is not allowed.*") -ne 0 ]
+}
+
+@test "Accept a Pod without CSI inline volumes" {
+	run kwctl run \
+		--request-path test_data/pod_without_csi_volumes.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_secrets_store.json --settings-json '{"allowedDrivers": {"": {}}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
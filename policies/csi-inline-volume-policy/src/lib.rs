@@ -0,0 +1,307 @@
+use std::collections::BTreeSet;
+
+use guest::prelude::*;
+use k8s_openapi::Resource;
+use k8s_openapi::api::core::v1::{CSIVolumeSource, PodSpec};
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::ListResourcesByNamespaceRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::list_resources_by_namespace;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::list_resources_by_namespace;
+
+mod secret_provider_class;
+use secret_provider_class::{
+    SECRET_PROVIDER_CLASS_ATTRIBUTE, SECRETS_STORE_DRIVER, SecretProviderClass,
+};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = &validation_request.request;
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        // The resources without Pod spec are not validated
+        Ok(None) => return kubewarden::accept_request(),
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    let errors = validate_pod_spec(&pod_spec, &request.namespace, &validation_request.settings);
+    if !errors.is_empty() {
+        return kubewarden::reject_request(
+            Some(format!(
+                "{} '{}': {}",
+                request.kind.kind,
+                request.name,
+                errors.join("; ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_pod_spec(pod_spec: &PodSpec, namespace: &str, settings: &Settings) -> Vec<String> {
+    pod_spec
+        .volumes
+        .iter()
+        .flatten()
+        .filter_map(|volume| volume.csi.as_ref().map(|csi| (&volume.name, csi)))
+        .flat_map(|(name, csi)| {
+            validate_csi_volume(csi, namespace, settings)
+                .into_iter()
+                .map(move |error| format!("volume '{name}': {error}"))
+        })
+        .collect()
+}
+
+// Returns the reasons why the CSI inline volume is not allowed
+fn validate_csi_volume(csi: &CSIVolumeSource, namespace: &str, settings: &Settings) -> Vec<String> {
+    let Some(rules) = settings.allowed_drivers.get(&csi.driver) else {
+        return vec![format!("the CSI driver '{}' is not allowed", csi.driver)];
+    };
+    let mut errors = rules.violations(csi.volume_attributes.as_ref());
+
+    if settings.verify_secret_provider_classes && csi.driver == SECRETS_STORE_DRIVER {
+        match csi
+            .volume_attributes
+            .as_ref()
+            .and_then(|attributes| attributes.get(SECRET_PROVIDER_CLASS_ATTRIBUTE))
+        {
+            Some(class) => {
+                // The SecretProviderClass is listed by name: an empty list
+                // means it does not exist, while an error is a failure of the
+                // host
+                let kube_request = ListResourcesByNamespaceRequest {
+                    api_version: SecretProviderClass::API_VERSION.to_owned(),
+                    kind: SecretProviderClass::KIND.to_owned(),
+                    namespace: namespace.to_owned(),
+                    label_selector: None,
+                    field_selector: Some(format!("metadata.name={class}")),
+                    field_masks: Some(BTreeSet::from(["metadata.name".to_owned()])),
+                };
+                match list_resources_by_namespace::<SecretProviderClass>(&kube_request) {
+                    Ok(classes) if classes.items.is_empty() => errors.push(format!(
+                        "the SecretProviderClass '{namespace}/{class}' cannot be found"
+                    )),
+                    Ok(_) => {}
+                    Err(err) => errors.push(format!(
+                        "cannot read the SecretProviderClass '{namespace}/{class}': {err}"
+                    )),
+                }
+            }
+            // Already reported when the attribute is required
+            None if rules
+                .required_attributes
+                .contains(SECRET_PROVIDER_CLASS_ATTRIBUTE) => {}
+            None => errors.push(format!(
+                "the volumeAttribute '{SECRET_PROVIDER_CLASS_ATTRIBUTE}' is required"
+            )),
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+    use settings::DriverRules;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::ListResourcesByNamespaceRequest;
+
+        #[allow(dead_code)]
+        pub fn list_resources_by_namespace<T>(
+            _req: &ListResourcesByNamespaceRequest,
+        ) -> anyhow::Result<k8s_openapi::List<T>>
+        where
+            T: k8s_openapi::ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+        {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    fn settings(verify_secret_provider_classes: bool) -> Settings {
+        Settings {
+            allowed_drivers: BTreeMap::from([(
+                SECRETS_STORE_DRIVER.to_owned(),
+                DriverRules {
+                    required_attributes: BTreeSet::from([
+                        SECRET_PROVIDER_CLASS_ATTRIBUTE.to_owned()
+                    ]),
+                    allowed_values: BTreeMap::from([(
+                        "usePodIdentity".to_owned(),
+                        BTreeSet::from(["false".to_owned()]),
+                    )]),
+                },
+            )]),
+            verify_secret_provider_classes,
+        }
+    }
+
+    #[rstest]
+    #[case::allowed_driver("test_data/pod_secrets_store.json", true)]
+    #[case::not_allowed_driver("test_data/deployment_other_driver.json", false)]
+    #[case::attribute_not_allowed("test_data/pod_secrets_store_pod_identity.json", false)]
+    #[case::without_csi_volumes("test_data/pod_without_csi_volumes.json", true)]
+    #[serial]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources.expect::<SecretProviderClass>().times(0);
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(false),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::not_allowed_driver(
+        "test_data/deployment_other_driver.json",
+        "Deployment 'web': volume 'scratch': the CSI driver 'inline.storage.kubernetes.io' is not allowed"
+    )]
+    #[case::attribute_not_allowed(
+        "test_data/pod_secrets_store_pod_identity.json",
+        "Pod 'web': volume 'secrets': the volumeAttribute 'secretProviderClass' is required; volume 'secrets': the volumeAttribute 'usePodIdentity' cannot be 'true', use one of: false"
+    )]
+    #[serial]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources.expect::<SecretProviderClass>().times(0);
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    #[serial]
+    fn existing_secret_provider_class() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<SecretProviderClass>()
+            .times(1)
+            .returning(|req| {
+                if req.namespace != "team-a"
+                    || req.field_selector.as_deref() != Some("metadata.name=vault-database")
+                {
+                    return Err(anyhow::anyhow!(
+                        "it's not searching the expected SecretProviderClass"
+                    ));
+                }
+                Ok(k8s_openapi::List {
+                    items: vec![SecretProviderClass {
+                        metadata: ObjectMeta {
+                            name: Some("vault-database".to_owned()),
+                            namespace: Some(req.namespace.clone()),
+                            ..Default::default()
+                        },
+                    }],
+                    ..Default::default()
+                })
+            });
+
+        let test_case = Testcase {
+            name: "existing SecretProviderClass".to_owned(),
+            fixture_file: "test_data/pod_secrets_store.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(true),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    #[serial]
+    fn missing_secret_provider_class() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<SecretProviderClass>()
+            .times(1)
+            .returning(|_| Ok(k8s_openapi::List::default()));
+
+        let test_case = Testcase {
+            name: "missing SecretProviderClass".to_owned(),
+            fixture_file: "test_data/pod_secrets_store.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Pod 'web': volume 'secrets': the SecretProviderClass 'team-a/vault-database' cannot be found"
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn secret_provider_class_lookup_failure() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<SecretProviderClass>()
+            .times(1)
+            .returning(|_| Err(anyhow::anyhow!("secretproviderclasses is forbidden")));
+
+        let test_case = Testcase {
+            name: "SecretProviderClass lookup failure".to_owned(),
+            fixture_file: "test_data/pod_secrets_store.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Pod 'web': volume 'secrets': cannot read the SecretProviderClass 'team-a/vault-database': secretproviderclasses is forbidden"
+            )
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+/// Driver of the Secrets Store CSI inline volumes
+pub(crate) const SECRETS_STORE_DRIVER: &str = "secrets-store.csi.k8s.io";
+/// volumeAttribute of the Secrets Store CSI inline volumes naming their
+/// SecretProviderClass
+pub(crate) const SECRET_PROVIDER_CLASS_ATTRIBUTE: &str = "secretProviderClass";
+
+/// Minimal representation of a Secrets Store CSI SecretProviderClass. Only
+/// its existence is checked by the policy.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct SecretProviderClass {
+    #[serde(default)]
+    pub metadata: ObjectMeta,
+}
+
+impl k8s_openapi::DeepMerge for SecretProviderClass {
+    fn merge_from(&mut self, other: Self)
+    where
+        Self: Sized,
+    {
+        *self = other;
+    }
+}
+
+impl k8s_openapi::Resource for SecretProviderClass {
+    const API_VERSION: &'static str = "secrets-store.csi.x-k8s.io/v1";
+    const GROUP: &'static str = "secrets-store.csi.x-k8s.io";
+    const KIND: &'static str = "SecretProviderClass";
+    const VERSION: &'static str = "v1";
+    const URL_PATH_SEGMENT: &'static str = "secretproviderclasses";
+    type Scope = k8s_openapi::NamespaceResourceScope;
+}
+
+impl k8s_openapi::ListableResource for SecretProviderClass {
+    const LIST_KIND: &'static str = "SecretProviderClassList";
+}
@@ -0,0 +1,152 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+/// Rules the volumeAttributes of the inline volumes of a CSI driver must
+/// follow
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct DriverRules {
+    /// volumeAttributes the inline volumes must set
+    pub required_attributes: BTreeSet<String>,
+    /// Values allowed for some volumeAttributes. The attributes not listed
+    /// can have any value.
+    pub allowed_values: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl DriverRules {
+    /// Returns the reasons why the volumeAttributes do not follow the rules
+    pub fn violations(&self, attributes: Option<&BTreeMap<String, String>>) -> Vec<String> {
+        let mut violations: Vec<String> = self
+            .required_attributes
+            .iter()
+            .filter(|attribute| {
+                attributes.is_none_or(|attributes| !attributes.contains_key(*attribute))
+            })
+            .map(|attribute| format!("the volumeAttribute '{attribute}' is required"))
+            .collect();
+        for (attribute, value) in attributes.into_iter().flatten() {
+            if let Some(allowed_values) = self.allowed_values.get(attribute)
+                && !allowed_values.contains(value)
+            {
+                violations.push(format!(
+                    "the volumeAttribute '{attribute}' cannot be '{value}', use one of: {}",
+                    allowed_values
+                        .iter()
+                        .cloned()
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ));
+            }
+        }
+        violations
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// CSI drivers allowed to provide inline volumes, with the rules of their
+    /// volumeAttributes. No driver is allowed when empty.
+    pub allowed_drivers: BTreeMap<String, DriverRules>,
+    /// Check that the SecretProviderClass of the Secrets Store CSI inline
+    /// volumes exists in the namespace of the Pod. This requires the policy
+    /// to be allowed to access the SecretProviderClasses.
+    pub verify_secret_provider_classes: bool,
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_drivers.contains_key("") {
+            return Err("allowedDrivers cannot contain empty driver names".to_owned());
+        }
+        for (driver, rules) in &self.allowed_drivers {
+            if rules.required_attributes.contains("") {
+                return Err(format!(
+                    "driver '{driver}': requiredAttributes cannot contain empty values"
+                ));
+            }
+            if let Some((attribute, _)) = rules
+                .allowed_values
+                .iter()
+                .find(|(attribute, values)| attribute.is_empty() || values.is_empty())
+            {
+                return Err(format!(
+                    "driver '{driver}': allowedValues of the attribute '{attribute}' cannot be empty"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "allowedDrivers": {
+                "secrets-store.csi.k8s.io": {
+                    "requiredAttributes": ["secretProviderClass"],
+                    "allowedValues": {"usePodIdentity": ["false"]}
+                },
+                "inline.storage.kubernetes.io": {}
+            },
+            "verifySecretProviderClasses": true
+        }"#,
+        true
+    )]
+    #[case::empty_driver(r#"{"allowedDrivers": {"": {}}}"#, false)]
+    #[case::empty_required_attribute(
+        r#"{"allowedDrivers": {"secrets-store.csi.k8s.io": {"requiredAttributes": [""]}}}"#,
+        false
+    )]
+    #[case::empty_allowed_values(
+        r#"{"allowedDrivers": {"secrets-store.csi.k8s.io": {"allowedValues": {"usePodIdentity": []}}}}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn unknown_driver_rule() {
+        let settings =
+            r#"{"allowedDrivers": {"secrets-store.csi.k8s.io": {"requiredAttribute": ["x"]}}}"#;
+        assert!(serde_json::from_str::<Settings>(settings).is_err());
+    }
+
+    #[rstest]
+    #[case::valid(&[("secretProviderClass", "vault"), ("usePodIdentity", "false")], vec![])]
+    #[case::missing_attribute(
+        &[("usePodIdentity", "false")],
+        vec!["the volumeAttribute 'secretProviderClass' is required"]
+    )]
+    #[case::value_not_allowed(
+        &[("secretProviderClass", "vault"), ("usePodIdentity", "true")],
+        vec!["the volumeAttribute 'usePodIdentity' cannot be 'true', use one of: false"]
+    )]
+    fn driver_rules(#[case] attributes: &[(&str, &str)], #[case] expected: Vec<&str>) {
+        let rules = DriverRules {
+            required_attributes: BTreeSet::from(["secretProviderClass".to_owned()]),
+            allowed_values: BTreeMap::from([(
+                "usePodIdentity".to_owned(),
+                BTreeSet::from(["false".to_owned()]),
+            )]),
+        };
+        let attributes: BTreeMap<String, String> = attributes
+            .iter()
+            .map(|(attribute, value)| (attribute.to_string(), value.to_string()))
+            .collect();
+        assert_eq!(rules.violations(Some(&attributes)), expected);
+    }
+}
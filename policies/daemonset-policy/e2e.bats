@@ -0,0 +1,64 @@
+#!/usr/bin/env bats
+
+@test "Accept a DaemonSet in an allowed namespace" {
+	run kwctl run \
+		--request-path test_data/daemonset_kube_system.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a DaemonSet created in another namespace" {
+	run kwctl run \
+		--request-path test_data/daemonset_other_namespace.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*user 'developer' cannot create DaemonSets in the 'monitoring' namespace.*") -ne 0 ]
+}
+
+@test "Accept the update of a DaemonSet in another namespace" {
+	run kwctl run \
+		--request-path test_data/daemonset_update_other_namespace.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a DaemonSet using the OnDelete update strategy" {
+	run kwctl run \
+		--request-path test_data/daemonset_on_delete.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*the OnDelete update strategy is not allowed.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/daemonset_kube_system.json --settings-json '{"maxUnavailable": "ten"}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
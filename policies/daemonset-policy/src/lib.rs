@@ -0,0 +1,231 @@
+use guest::prelude::*;
+use k8s_openapi::api::apps::v1::DaemonSet;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+use intstr::{format_value, parse_percentage};
+
+mod settings;
+use settings::Settings;
+
+/// Value used by Kubernetes when `maxUnavailable` is not set
+const DEFAULT_MAX_UNAVAILABLE: IntOrString = IntOrString::Int(1);
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let daemon_set = match serde_json::from_value::<DaemonSet>(request.object) {
+        Ok(daemon_set) => daemon_set,
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    let mut errors = vec![];
+    // The DaemonSets created before the policy can still be updated
+    if request.operation == "CREATE" {
+        let username = request.user_info.username.unwrap_or_default();
+        let groups = request.user_info.groups.unwrap_or_default();
+        if !settings.can_create(&request.namespace, &username, &groups) {
+            errors.push(format!(
+                "user '{username}' cannot create DaemonSets in the '{}' namespace",
+                request.namespace
+            ));
+        }
+    }
+    if let Err(error) = validate_update_strategy(&daemon_set, settings) {
+        errors.push(error);
+    }
+
+    if errors.is_empty() {
+        return kubewarden::accept_request();
+    }
+    kubewarden::reject_request(
+        Some(format!(
+            "DaemonSet '{}': {}",
+            request.name,
+            errors.join("; ")
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+fn validate_update_strategy(daemon_set: &DaemonSet, settings: &Settings) -> Result<(), String> {
+    let update_strategy = daemon_set
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.update_strategy.clone())
+        .unwrap_or_default();
+    match update_strategy.type_.as_deref().unwrap_or("RollingUpdate") {
+        "RollingUpdate" => {}
+        "OnDelete" if settings.allow_on_delete => return Ok(()),
+        strategy_type => {
+            return Err(format!(
+                "the {strategy_type} update strategy is not allowed, use RollingUpdate"
+            ));
+        }
+    }
+
+    let Some(limit) = &settings.max_unavailable else {
+        return Ok(());
+    };
+    let value = update_strategy
+        .rolling_update
+        .and_then(|rolling_update| rolling_update.max_unavailable)
+        .unwrap_or(DEFAULT_MAX_UNAVAILABLE);
+    // The number of nodes running the DaemonSet is not known: the numbers of
+    // Pods and the percentages cannot be compared
+    let above_limit = match (&value, limit) {
+        (IntOrString::Int(pods), IntOrString::Int(limit)) => pods > limit,
+        (IntOrString::String(percentage), IntOrString::String(limit)) => {
+            parse_percentage(percentage) > parse_percentage(limit)
+        }
+        (_, IntOrString::Int(_)) => {
+            return Err(format!(
+                "maxUnavailable {} must be a number of Pods, like its limit of {}",
+                format_value(&value),
+                format_value(limit)
+            ));
+        }
+        (_, IntOrString::String(_)) => {
+            return Err(format!(
+                "maxUnavailable {} must be a percentage, like its limit of {}",
+                format_value(&value),
+                format_value(limit)
+            ));
+        }
+    };
+    if above_limit {
+        return Err(format!(
+            "maxUnavailable {} is above the limit of {}",
+            format_value(&value),
+            format_value(limit)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use k8s_openapi::api::apps::v1::{
+        DaemonSetSpec, DaemonSetUpdateStrategy, RollingUpdateDaemonSet,
+    };
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            max_unavailable: Some(IntOrString::String("10%".to_owned())),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::allowed_namespace("test_data/daemonset_kube_system.json", true)]
+    #[case::other_namespace("test_data/daemonset_other_namespace.json", false)]
+    #[case::update_in_other_namespace("test_data/daemonset_update_other_namespace.json", true)]
+    #[case::on_delete("test_data/daemonset_on_delete.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::other_namespace(
+        "test_data/daemonset_other_namespace.json",
+        "DaemonSet 'node-exporter': user 'developer' cannot create DaemonSets in the 'monitoring' namespace; maxUnavailable 25% is above the limit of 10%"
+    )]
+    #[case::on_delete(
+        "test_data/daemonset_on_delete.json",
+        "DaemonSet 'node-exporter': the OnDelete update strategy is not allowed, use RollingUpdate"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    fn daemon_set(strategy_type: Option<&str>, max_unavailable: Option<IntOrString>) -> DaemonSet {
+        DaemonSet {
+            spec: Some(DaemonSetSpec {
+                update_strategy: Some(DaemonSetUpdateStrategy {
+                    type_: strategy_type.map(str::to_owned),
+                    rolling_update: Some(RollingUpdateDaemonSet {
+                        max_unavailable,
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::default_value(None, None, Some(IntOrString::Int(1)), true)]
+    #[case::pods_within_limit(None, Some(IntOrString::Int(2)), Some(IntOrString::Int(2)), true)]
+    #[case::pods_above_limit(None, Some(IntOrString::Int(3)), Some(IntOrString::Int(2)), false)]
+    #[case::percentage_within_limit(
+        Some("RollingUpdate"),
+        Some(IntOrString::String("5%".to_owned())),
+        Some(IntOrString::String("10%".to_owned())),
+        true
+    )]
+    #[case::pods_with_percentage_limit(
+        None,
+        Some(IntOrString::Int(1)),
+        Some(IntOrString::String("10%".to_owned())),
+        false
+    )]
+    #[case::no_limit(None, Some(IntOrString::String("100%".to_owned())), None, true)]
+    #[case::on_delete(Some("OnDelete"), None, None, false)]
+    fn update_strategy(
+        #[case] strategy_type: Option<&str>,
+        #[case] max_unavailable: Option<IntOrString>,
+        #[case] limit: Option<IntOrString>,
+        #[case] expected: bool,
+    ) {
+        let settings = Settings {
+            max_unavailable: limit,
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_update_strategy(&daemon_set(strategy_type, max_unavailable), &settings)
+                .is_ok(),
+            expected
+        );
+    }
+}
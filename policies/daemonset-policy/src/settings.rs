@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use serde::{Deserialize, Serialize};
+
+use intstr::is_valid;
+use user_allowlist::UserAllowlist;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Namespaces where all the users can create DaemonSets
+    pub allowed_namespaces: HashSet<String>,
+    /// Users allowed to create DaemonSets in all the namespaces
+    pub allowed_users: HashSet<String>,
+    /// Groups of users allowed to create DaemonSets in all the namespaces
+    pub allowed_groups: HashSet<String>,
+    /// Accept the DaemonSets using the OnDelete update strategy
+    pub allow_on_delete: bool,
+    /// Limit of the Pods that can be unavailable during the rollouts, either
+    /// a number of Pods or a percentage of the nodes
+    pub max_unavailable: Option<IntOrString>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            allowed_namespaces: ["kube-system".to_owned()].into(),
+            allowed_users: HashSet::new(),
+            allowed_groups: ["system:masters".to_owned()].into(),
+            allow_on_delete: false,
+            max_unavailable: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the user, member of the groups, can create
+    /// DaemonSets in the namespace
+    pub fn can_create(&self, namespace: &str, username: &str, groups: &[String]) -> bool {
+        self.allowed_namespaces.contains(namespace) || self.is_user_allowed(username, groups)
+    }
+}
+
+impl UserAllowlist for Settings {
+    fn allowed_users(&self) -> &HashSet<String> {
+        &self.allowed_users
+    }
+
+    fn allowed_groups(&self) -> &HashSet<String> {
+        &self.allowed_groups
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_namespaces.contains("") {
+            return Err("allowedNamespaces cannot contain empty values".to_owned());
+        }
+        if self.allowed_users.contains("") {
+            return Err("allowedUsers cannot contain empty values".to_owned());
+        }
+        if self.allowed_groups.contains("") {
+            return Err("allowedGroups cannot contain empty values".to_owned());
+        }
+        if !self.max_unavailable.as_ref().is_none_or(is_valid) {
+            return Err("maxUnavailable must be a non-negative number or a percentage".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::allowed_namespace("kube-system", "developer", vec!["system:authenticated"], true)]
+    #[case::allowed_group("monitoring", "admin", vec!["system:masters"], true)]
+    #[case::not_allowed("monitoring", "developer", vec!["system:authenticated"], false)]
+    fn can_create(
+        #[case] namespace: &str,
+        #[case] username: &str,
+        #[case] groups: Vec<&str>,
+        #[case] expected: bool,
+    ) {
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(
+            Settings::default().can_create(namespace, username, &groups),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::pods(r#"{"maxUnavailable": 2}"#, true)]
+    #[case::percentage(r#"{"maxUnavailable": "10%", "allowOnDelete": true}"#, true)]
+    #[case::negative(r#"{"maxUnavailable": -1}"#, false)]
+    #[case::invalid_percentage(r#"{"maxUnavailable": "ten"}"#, false)]
+    #[case::empty_namespace(r#"{"allowedNamespaces": [""]}"#, false)]
+    #[case::empty_group(r#"{"allowedGroups": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,43 @@
+#!/usr/bin/env bats
+
+@test "Reject a Pod implicitly using the default ServiceAccount" {
+	run kwctl run --request-path test_data/pod_without_service_account.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*workloads of namespace 'team-a' must set serviceAccountName.*") -ne 0 ]
+}
+
+@test "Reject a Deployment using the default ServiceAccount" {
+	run kwctl run --request-path test_data/deployment_default_service_account.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*workloads of namespace 'team-a' cannot use the default ServiceAccount.*") -ne 0 ]
+}
+
+@test "Accept a Pod using a dedicated ServiceAccount" {
+	run kwctl run --request-path test_data/pod_with_service_account.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Pod using the default ServiceAccount in a namespace not selected" {
+	run kwctl run --request-path test_data/pod_without_service_account.json --settings-json '{"namespaces": ["team-b"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
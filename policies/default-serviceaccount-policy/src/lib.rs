@@ -0,0 +1,118 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+// ServiceAccount used by the Pods that do not specify one
+const DEFAULT_SERVICE_ACCOUNT: &str = "default";
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let namespace = &validation_request.request.namespace;
+    if !validation_request.settings.is_namespace_enforced(namespace) {
+        return kubewarden::accept_request();
+    }
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        Ok(None) => return kubewarden::accept_request(),
+        Err(e) => {
+            return kubewarden::reject_request(
+                Some(format!("Cannot parse validation request: {e}")),
+                Some(400),
+                None,
+                None,
+            );
+        }
+    };
+    let service_account = pod_spec
+        .service_account_name
+        .or(pod_spec.service_account)
+        .filter(|service_account| !service_account.is_empty());
+
+    match service_account.as_deref() {
+        Some(DEFAULT_SERVICE_ACCOUNT) => kubewarden::reject_request(
+            Some(format!(
+                "workloads of namespace '{namespace}' cannot use the default ServiceAccount"
+            )),
+            None,
+            None,
+            None,
+        ),
+        None => kubewarden::reject_request(
+            Some(format!(
+                "workloads of namespace '{namespace}' must set serviceAccountName to a ServiceAccount other than the default one"
+            )),
+            None,
+            None,
+            None,
+        ),
+        Some(_) => kubewarden::accept_request(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::implicit_default(
+        "test_data/pod_without_service_account.json",
+        Settings::default(),
+        false
+    )]
+    #[case::explicit_default(
+        "test_data/deployment_default_service_account.json",
+        Settings::default(),
+        false
+    )]
+    #[case::dedicated_service_account(
+        "test_data/pod_with_service_account.json",
+        Settings::default(),
+        true
+    )]
+    #[case::not_selected_namespace(
+        "test_data/pod_without_service_account.json",
+        Settings {
+            namespaces: ["team-b".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::exempt_namespace(
+        "test_data/pod_without_service_account.json",
+        Settings {
+            exempt_namespaces: ["team-a".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
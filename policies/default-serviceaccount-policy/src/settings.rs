@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Namespaces where the workloads cannot use the default ServiceAccount.
+    /// When empty, the policy applies to all the namespaces.
+    pub namespaces: HashSet<String>,
+    /// Namespaces where the workloads can use the default ServiceAccount
+    pub exempt_namespaces: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            namespaces: HashSet::new(),
+            exempt_namespaces: ["kube-system".to_owned()].into(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the workloads of the namespace cannot use the
+    /// default ServiceAccount
+    pub fn is_namespace_enforced(&self, namespace: &str) -> bool {
+        !self.exempt_namespaces.contains(namespace)
+            && (self.namespaces.is_empty() || self.namespaces.contains(namespace))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.namespaces.contains("") {
+            return Err("namespaces cannot contain empty values".to_owned());
+        }
+        if self.exempt_namespaces.contains("") {
+            return Err("exemptNamespaces cannot contain empty values".to_owned());
+        }
+        if let Some(namespace) = self.namespaces.intersection(&self.exempt_namespaces).next() {
+            return Err(format!(
+                "namespace '{namespace}' cannot be both enforced and exempt"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::all_namespaces(Settings::default(), "team-a", true)]
+    #[case::exempt_namespace(Settings::default(), "kube-system", false)]
+    #[case::selected_namespace(
+        Settings {
+            namespaces: ["team-a".to_owned()].into(),
+            ..Default::default()
+        },
+        "team-a",
+        true
+    )]
+    #[case::not_selected_namespace(
+        Settings {
+            namespaces: ["team-a".to_owned()].into(),
+            ..Default::default()
+        },
+        "team-b",
+        false
+    )]
+    fn enforced_namespaces(
+        #[case] settings: Settings,
+        #[case] namespace: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(settings.is_namespace_enforced(namespace), expected);
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::selected_namespaces(r#"{"namespaces": ["team-a", "team-b"]}"#, true)]
+    #[case::empty_namespace(r#"{"namespaces": [""]}"#, false)]
+    #[case::empty_exempt_namespace(r#"{"exemptNamespaces": [""]}"#, false)]
+    #[case::enforced_and_exempt(r#"{"namespaces": ["kube-system"]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
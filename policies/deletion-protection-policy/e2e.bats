@@ -0,0 +1,61 @@
+#!/usr/bin/env bats
+
+@test "Accept the deletion of a resource not protected" {
+	run kwctl run --request-path test_data/configmap_delete.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject the deletion of a resource protected by an annotation" {
+	run kwctl run --request-path test_data/configmap_delete_protected.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*ConfigMap 'web-config' is protected from deletion by 'kubewarden.io/protected: true'.*") -ne 0 ]
+}
+
+@test "Reject the deletion of a resource protected by a label" {
+	run kwctl run --request-path test_data/configmap_delete_protected_by_label.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Accept the deletion of a protected resource by a break-glass group" {
+	run kwctl run --request-path test_data/configmap_delete_protected_break_glass.json --settings-json '{"breakGlassGroups": ["break-glass"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject the removal of the protection" {
+	run kwctl run --request-path test_data/configmap_update_remove_protection.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/configmap_delete.json --settings-json '{"protectionKey": ""}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
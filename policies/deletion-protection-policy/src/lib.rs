@@ -0,0 +1,184 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    if request.operation != "DELETE" && request.operation != "UPDATE" {
+        return kubewarden::accept_request();
+    }
+    // The resource being deleted or updated is the old object of the request
+    let Some(old_metadata) = metadata(&request.old_object)? else {
+        return kubewarden::accept_request();
+    };
+    if !settings.is_protected(
+        old_metadata.annotations.as_ref(),
+        old_metadata.labels.as_ref(),
+    ) {
+        return kubewarden::accept_request();
+    }
+    if request.operation == "UPDATE" {
+        // The updates keeping the protection are allowed, the ones removing
+        // it would allow the deletion with a following request
+        let metadata = metadata(&request.object)?.unwrap_or_default();
+        if settings.is_protected(metadata.annotations.as_ref(), metadata.labels.as_ref()) {
+            return kubewarden::accept_request();
+        }
+    }
+    if settings.is_break_glass(&request.user_info.groups.unwrap_or_default()) {
+        return kubewarden::accept_request();
+    }
+
+    let message = if request.operation == "DELETE" {
+        format!(
+            "{} '{}' is protected from deletion by '{}: {}'",
+            request.kind.kind, request.name, settings.protection_key, settings.protection_value
+        )
+    } else {
+        format!(
+            "{} '{}' is protected from deletion: '{}: {}' cannot be removed or changed",
+            request.kind.kind, request.name, settings.protection_key, settings.protection_value
+        )
+    };
+    kubewarden::reject_request(Some(message), None, None, None)
+}
+
+// Returns the metadata of the object, `None` when the object has no metadata
+fn metadata(object: &serde_json::Value) -> Result<Option<ObjectMeta>, serde_json::Error> {
+    object
+        .get("metadata")
+        .map(|metadata| serde_json::from_value(metadata.clone()))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::not_protected("test_data/configmap_delete.json", Settings::default(), true)]
+    #[case::protected_by_annotation(
+        "test_data/configmap_delete_protected.json",
+        Settings::default(),
+        false
+    )]
+    #[case::protected_by_label(
+        "test_data/configmap_delete_protected_by_label.json",
+        Settings::default(),
+        false
+    )]
+    #[case::break_glass(
+        "test_data/configmap_delete_protected_break_glass.json",
+        Settings {
+            break_glass_groups: ["break-glass".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::no_break_glass_groups(
+        "test_data/configmap_delete_protected_break_glass.json",
+        Settings::default(),
+        false
+    )]
+    #[case::other_protection_key(
+        "test_data/configmap_delete_protected.json",
+        Settings {
+            protection_key: "company.com/protected".to_owned(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::update_keeping_protection(
+        "test_data/configmap_update_protected.json",
+        Settings::default(),
+        true
+    )]
+    #[case::update_removing_protection(
+        "test_data/configmap_update_remove_protection.json",
+        Settings::default(),
+        false
+    )]
+    #[case::update_changing_protection(
+        "test_data/configmap_update_change_protection.json",
+        Settings::default(),
+        false
+    )]
+    #[case::update_removing_protection_break_glass(
+        "test_data/configmap_update_remove_protection_break_glass.json",
+        Settings {
+            break_glass_groups: ["break-glass".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/configmap_delete_protected.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "ConfigMap 'web-config' is protected from deletion by 'kubewarden.io/protected: true'"
+            )
+        );
+    }
+
+    #[test]
+    fn update_rejection_message() {
+        let test_case = Testcase {
+            name: "update rejection message".to_owned(),
+            fixture_file: "test_data/configmap_update_remove_protection.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "ConfigMap 'web-config' is protected from deletion: 'kubewarden.io/protected: true' cannot be removed or changed"
+            )
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Key of the annotation, or of the label, protecting the resources
+    pub protection_key: String,
+    /// Value the annotation, or the label, must have to protect the resources
+    pub protection_value: String,
+    /// Groups of users allowed to delete the protected resources and to
+    /// remove their protection
+    pub break_glass_groups: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            protection_key: "kubewarden.io/protected".to_owned(),
+            protection_value: "true".to_owned(),
+            break_glass_groups: HashSet::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the annotations or the labels of a resource protect
+    /// it from deletion
+    pub fn is_protected(
+        &self,
+        annotations: Option<&BTreeMap<String, String>>,
+        labels: Option<&BTreeMap<String, String>>,
+    ) -> bool {
+        [annotations, labels]
+            .into_iter()
+            .flatten()
+            .any(|values| values.get(&self.protection_key) == Some(&self.protection_value))
+    }
+
+    /// Returns true when one of the groups can delete the protected resources
+    pub fn is_break_glass(&self, groups: &[String]) -> bool {
+        groups
+            .iter()
+            .any(|group| self.break_glass_groups.contains(group))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.protection_key.is_empty() {
+            return Err("protectionKey cannot be empty".to_owned());
+        }
+        if self.break_glass_groups.contains("") {
+            return Err("breakGlassGroups cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::no_metadata(None, None, false)]
+    #[case::annotation(Some(("kubewarden.io/protected", "true")), None, true)]
+    #[case::label(None, Some(("kubewarden.io/protected", "true")), true)]
+    #[case::other_value(Some(("kubewarden.io/protected", "false")), None, false)]
+    #[case::other_key(Some(("example.com/protected", "true")), None, false)]
+    fn protected(
+        #[case] annotation: Option<(&str, &str)>,
+        #[case] label: Option<(&str, &str)>,
+        #[case] expected: bool,
+    ) {
+        let to_map = |value: Option<(&str, &str)>| {
+            value.map(|(key, value)| BTreeMap::from([(key.to_owned(), value.to_owned())]))
+        };
+        let annotations = to_map(annotation);
+        let labels = to_map(label);
+        assert_eq!(
+            Settings::default().is_protected(annotations.as_ref(), labels.as_ref()),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::custom_protection(
+        r#"{"protectionKey": "company.com/protected", "protectionValue": "yes", "breakGlassGroups": ["break-glass"]}"#,
+        true
+    )]
+    #[case::empty_key(r#"{"protectionKey": ""}"#, false)]
+    #[case::empty_group(r#"{"breakGlassGroups": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,51 @@
+#!/usr/bin/env bats
+
+@test "Accept a Deployment within bounds" {
+	run kwctl run \
+		--request-path test_data/deployment_within_bounds.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Deployment whose defaults are above the maximums" {
+	run kwctl run \
+		--request-path test_data/deployment_without_progress_deadline.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*revisionHistoryLimit 10 is above the maximum of 5.*') -ne 0 ]
+}
+
+@test "Reject a Deployment above the maximums" {
+	run kwctl run \
+		--request-path test_data/deployment_above_maximums.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*revisionHistoryLimit 100 is above the maximum of 5.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/deployment_within_bounds.json --settings-json '{"maxRevisionHistoryLimit": -1}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
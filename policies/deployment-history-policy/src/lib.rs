@@ -0,0 +1,162 @@
+use guest::prelude::*;
+use k8s_openapi::api::apps::v1::{Deployment, DeploymentSpec};
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+/// Value used by Kubernetes when `progressDeadlineSeconds` is not set. The
+/// API server sets it before calling the admission policies.
+const DEFAULT_PROGRESS_DEADLINE_SECONDS: i32 = 600;
+
+/// Value used by Kubernetes when `revisionHistoryLimit` is not set
+const DEFAULT_REVISION_HISTORY_LIMIT: i32 = 10;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    let deployment = match serde_json::from_value::<Deployment>(validation_request.request.object) {
+        Ok(deployment) => deployment,
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    let spec = deployment.spec.unwrap_or_default();
+    if let Err(error) = validate_spec(&spec, &validation_request.settings) {
+        return kubewarden::reject_request(
+            Some(format!(
+                "Deployment '{}': {error}",
+                deployment.metadata.name.unwrap_or_default()
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_spec(spec: &DeploymentSpec, settings: &Settings) -> Result<(), String> {
+    let mut errors = vec![];
+    let progress_deadline_seconds = spec
+        .progress_deadline_seconds
+        .unwrap_or(DEFAULT_PROGRESS_DEADLINE_SECONDS);
+    if let Some(maximum) = settings.max_progress_deadline_seconds
+        && progress_deadline_seconds > maximum
+    {
+        errors.push(format!(
+            "progressDeadlineSeconds {progress_deadline_seconds} is above the maximum of {maximum}"
+        ));
+    }
+
+    let revision_history_limit = spec
+        .revision_history_limit
+        .unwrap_or(DEFAULT_REVISION_HISTORY_LIMIT);
+    if let Some(maximum) = settings.max_revision_history_limit
+        && revision_history_limit > maximum
+    {
+        errors.push(format!(
+            "revisionHistoryLimit {revision_history_limit} is above the maximum of {maximum}"
+        ));
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            max_progress_deadline_seconds: Some(900),
+            max_revision_history_limit: Some(5),
+        }
+    }
+
+    #[rstest]
+    #[case::within_bounds("test_data/deployment_within_bounds.json", true)]
+    #[case::without_progress_deadline("test_data/deployment_without_progress_deadline.json", false)]
+    #[case::above_maximums("test_data/deployment_above_maximums.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::without_progress_deadline(
+        "test_data/deployment_without_progress_deadline.json",
+        "Deployment 'web': revisionHistoryLimit 10 is above the maximum of 5"
+    )]
+    #[case::above_maximums(
+        "test_data/deployment_above_maximums.json",
+        "Deployment 'web': progressDeadlineSeconds 3600 is above the maximum of 900; revisionHistoryLimit 100 is above the maximum of 5"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[rstest]
+    #[case::defaults(Some(600), None, Settings::default(), true)]
+    #[case::defaults_without_progress_deadline(None, None, Settings::default(), true)]
+    #[case::default_progress_deadline_within_maximum(None, Some(3), settings(), true)]
+    #[case::default_progress_deadline_above_maximum(
+        None,
+        Some(3),
+        Settings {
+            max_progress_deadline_seconds: Some(300),
+            ..settings()
+        },
+        false
+    )]
+    #[case::no_maximums(Some(3600), Some(100), Settings::default(), true)]
+    #[case::maximums(Some(900), Some(5), settings(), true)]
+    fn validate_spec_settings(
+        #[case] progress_deadline_seconds: Option<i32>,
+        #[case] revision_history_limit: Option<i32>,
+        #[case] settings: Settings,
+        #[case] expected: bool,
+    ) {
+        let spec = DeploymentSpec {
+            progress_deadline_seconds,
+            revision_history_limit,
+            ..Default::default()
+        };
+        assert_eq!(validate_spec(&spec, &settings).is_ok(), expected);
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Maximum value of `progressDeadlineSeconds`
+    pub max_progress_deadline_seconds: Option<i32>,
+    /// Maximum value of `revisionHistoryLimit`
+    pub max_revision_history_limit: Option<i32>,
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self
+            .max_progress_deadline_seconds
+            .is_some_and(|seconds| seconds < 1)
+        {
+            return Err("maxProgressDeadlineSeconds must be greater than zero".to_owned());
+        }
+        if self
+            .max_revision_history_limit
+            .is_some_and(|limit| limit < 0)
+        {
+            return Err("maxRevisionHistoryLimit cannot be negative".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{"maxProgressDeadlineSeconds": 900, "maxRevisionHistoryLimit": 0}"#,
+        true
+    )]
+    #[case::zero_progress_deadline(r#"{"maxProgressDeadlineSeconds": 0}"#, false)]
+    #[case::negative_revision_history_limit(r#"{"maxRevisionHistoryLimit": -1}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -84,4 +84,121 @@
 
   [ "$status" -eq 0 ]
   [[ "$output" == *"allowed\":false"* ]]
-}
\ No newline at end of file
+}
+@test "Reject a custom resource removed by a custom deprecation rule" {
+  run kwctl run \
+    --request-path test_data/custom_resource_creation.json \
+    --settings-json '{"kubernetes_version": "1.31.0", "custom_deprecations": [{"version": "example.com/v1alpha1", "kind": "Widget", "deprecated-in": "v1.29.0", "removed-in": "v1.31.0", "replacement-api": "example.com/v1"}]}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+  [ $(expr "$output" : '.*It has been replaced by example.com/v1.*') -ne 0 ]
+}
+
+@test "Accept a custom resource before its custom deprecation" {
+  run kwctl run \
+    --request-path test_data/custom_resource_creation.json \
+    --settings-json '{"kubernetes_version": "1.28.0", "custom_deprecations": [{"version": "example.com/v1alpha1", "kind": "Widget", "deprecated-in": "v1.29.0"}]}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid custom deprecation rules" {
+  run kwctl run \
+    --request-path test_data/custom_resource_creation.json \
+    --settings-json '{"kubernetes_version": "1.28.0", "custom_deprecations": [{"version": "example.com/v1alpha1", "kind": "Widget"}]}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -ne 0 ]
+}
+
+@test "Reject settings without a kubernetes version nor its detection" {
+  run kwctl run \
+    --request-path test_data/ingress_creation.json \
+    --settings-json '{}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -ne 0 ]
+}
+
+@test "Accept with a warning before the enforcement deadline" {
+  run kwctl run \
+    --request-path test_data/ingress_creation.json \
+    --settings-json '{"kubernetes_version": "1.25.0", "enforcement_deadlines": [{"version": "extensions/v1beta1", "kind": "Ingress", "enforce_after": "9999-12-31"}]}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+  [ $(expr "$output" : '.*It will be rejected after 9999-12-31.*') -ne 0 ]
+}
+
+@test "Reject after the enforcement deadline" {
+  run kwctl run \
+    --request-path test_data/ingress_creation.json \
+    --settings-json '{"kubernetes_version": "1.19.0", "deny_on_deprecation": false, "enforcement_deadlines": [{"version": "extensions/v1beta1", "kind": "Ingress", "enforce_after": "2020-01-01"}]}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Reject a deprecated annotation of a Pod template" {
+  run kwctl run \
+    --request-path test_data/deployment_apparmor_annotation.json \
+    --settings-json '{"kubernetes_version": "1.30.0"}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+  [ $(expr "$output" : '.*It has been replaced by spec.containers.\*.securityContext.appArmorProfile.*') -ne 0 ]
+}
+
+@test "Accept a Pod template using the replacement of a deprecated field" {
+  run kwctl run \
+    --request-path test_data/deployment_apparmor_profile.json \
+    --settings-json '{"kubernetes_version": "1.30.0"}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a field removed by a custom field deprecation rule" {
+  run kwctl run \
+    --request-path test_data/deployment_apparmor_profile.json \
+    --settings-json '{"kubernetes_version": "1.30.0", "custom_field_deprecations": [{"version": "v1", "kind": "Pod", "path": ["metadata", "annotations", "example.com/legacy-mode"], "removed-in": "v1.28.0"}]}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
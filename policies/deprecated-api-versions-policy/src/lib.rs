@@ -1,13 +1,47 @@
+use std::{
+    collections::BTreeSet,
+    sync::{Mutex, PoisonError},
+};
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Utc};
 use guest::prelude::*;
+use k8s_openapi::{Resource, api::core::v1::Node};
 use kubewarden_policy_sdk::wapc_guest as guest;
+use lazy_static::lazy_static;
+use semver::Version;
 
 extern crate kubewarden_policy_sdk as kubewarden;
-use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::list_all_resources;
+use kubewarden::{
+    host_capabilities::kubernetes::ListAllResourcesRequest,
+    protocol_version_guest,
+    request::{GroupVersionKind, ValidationRequest},
+    response::ValidationResponse,
+    validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::list_all_resources;
 
 mod settings;
-use settings::Settings;
+use settings::{BundledRules, Settings};
 
-use versions::DEPRECATION_CHECKER;
+use versions::{
+    DEPRECATION_CHECKER, DeprecationRule, FIELD_DEPRECATION_CHECKER, FieldDeprecationRule,
+};
+
+// Minutes during which the detected version of the cluster is reused, instead
+// of listing the Nodes again
+const DETECTED_KUBERNETES_VERSION_TTL: i64 = 5;
+
+lazy_static! {
+    // Version of the cluster detected by the previous evaluations, with the
+    // time of its detection
+    static ref DETECTED_KUBERNETES_VERSION: Mutex<Option<(Version, DateTime<Utc>)>> =
+        Mutex::new(None);
+}
 
 #[unsafe(no_mangle)]
 pub extern "C" fn wapc_init() {
@@ -19,26 +53,253 @@ pub extern "C" fn wapc_init() {
 fn validate(payload: &[u8]) -> CallResult {
     let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
     let obj = validation_request.request.kind;
-    let kubernetes_version = validation_request.settings.kubernetes_version;
+    let kubernetes_version = match kubernetes_version(&validation_request.settings) {
+        Ok(kubernetes_version) => kubernetes_version,
+        Err(e) => {
+            return kubewarden::reject_request(
+                Some(format!(
+                    "Cannot detect the Kubernetes version of the cluster: {e}"
+                )),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    let kubernetes_version = &kubernetes_version;
+    let bundled_rules = match bundled_rules(&validation_request.settings) {
+        Ok(bundled_rules) => bundled_rules,
+        Err(e) => {
+            return kubewarden::reject_request(
+                Some(format!("Cannot load the rule bundle: {e}")),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    let deprecation = find_deprecation(
+        &obj,
+        kubernetes_version,
+        &validation_request.settings,
+        &bundled_rules,
+    );
+
+    let Some(deprecation_rule) = deprecation else {
+        return validate_fields(
+            &obj,
+            &validation_request.request.object,
+            kubernetes_version,
+            &validation_request.settings,
+            &bundled_rules,
+        );
+    };
 
-    match DEPRECATION_CHECKER.check(&obj, &kubernetes_version) {
-        Some(deprecation_rule) => {
+    match validation_request.settings.enforce_after(&deprecation_rule) {
+        // the migration deadline has not been reached yet
+        Some(enforce_after) if chrono::Utc::now().date_naive() <= enforce_after => {
+            Ok(serde_json::to_vec(&ValidationResponse {
+                accepted: true,
+                message: None,
+                code: None,
+                mutated_object: None,
+                audit_annotations: None,
+                warnings: Some(vec![format!(
+                    "{deprecation_rule} It will be rejected after {enforce_after}."
+                )]),
+            })?)
+        }
+        Some(_) => kubewarden::reject_request(Some(deprecation_rule.to_string()), None, None, None),
+        None => {
             if !validation_request.settings.deny_on_deprecation
-                && deprecation_rule.is_only_deprecated(&kubernetes_version)?
+                && deprecation_rule.is_only_deprecated(kubernetes_version)?
             {
                 return kubewarden::accept_request();
             }
             kubewarden::reject_request(Some(deprecation_rule.to_string()), None, None, None)
         }
-        None => kubewarden::accept_request(),
     }
 }
 
+// Fetches and validates the rules of the rule bundle, if any
+fn bundled_rules(settings: &Settings) -> Result<BundledRules> {
+    let Some(rule_bundle) = &settings.rule_bundle else {
+        return Ok(BundledRules::default());
+    };
+    rule_bundle.fetch()
+}
+
+// Looks for the rule deprecating the API version of the object. The custom
+// rules are checked first, then the bundled ones and the built-in ones.
+fn find_deprecation(
+    obj: &GroupVersionKind,
+    kubernetes_version: &Version,
+    settings: &Settings,
+    bundled_rules: &BundledRules,
+) -> Option<DeprecationRule> {
+    settings
+        .custom_deprecations
+        .iter()
+        .chain(&bundled_rules.deprecations)
+        .find(|deprecation| deprecation.matches(obj) && deprecation.includes(kubernetes_version))
+        .cloned()
+        .or_else(|| DEPRECATION_CHECKER.check(obj, kubernetes_version))
+}
+
+// Looks for the deprecated fields used by an object whose API version is still
+// served. The enforcement deadlines do not apply to the fields.
+fn validate_fields(
+    obj: &GroupVersionKind,
+    object: &serde_json::Value,
+    kubernetes_version: &Version,
+    settings: &Settings,
+    bundled_rules: &BundledRules,
+) -> CallResult {
+    let deprecations: Vec<FieldDeprecationRule> = settings
+        .custom_field_deprecations
+        .iter()
+        .chain(&bundled_rules.field_deprecations)
+        .filter(|deprecation| {
+            deprecation.includes(kubernetes_version) && deprecation.is_used(obj, object)
+        })
+        .cloned()
+        .chain(FIELD_DEPRECATION_CHECKER.check(obj, object, kubernetes_version))
+        .filter(|deprecation| {
+            settings.deny_on_deprecation || !deprecation.is_only_deprecated(kubernetes_version)
+        })
+        .collect();
+    if deprecations.is_empty() {
+        return kubewarden::accept_request();
+    }
+
+    let message = deprecations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ");
+    kubewarden::reject_request(Some(message), None, None, None)
+}
+
+// Returns the version of Kubernetes to use when looking for deprecations.
+// When detection is enabled, the configured version is used only when the
+// version of the cluster cannot be detected.
+fn kubernetes_version(settings: &Settings) -> Result<Version> {
+    if !settings.detect_kubernetes_version {
+        return Ok(settings.kubernetes_version.clone());
+    }
+    cached_kubernetes_version()
+        .or_else(|e| settings.configured_kubernetes_version().cloned().ok_or(e))
+}
+
+// Returns the detected version of the cluster. The Nodes are listed only when
+// the version detected by the previous evaluations is expired, the failures
+// are not cached.
+fn cached_kubernetes_version() -> Result<Version> {
+    let now = Utc::now();
+    let mut detected = DETECTED_KUBERNETES_VERSION
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some((version, detected_at)) = detected.as_ref()
+        && now - *detected_at < Duration::minutes(DETECTED_KUBERNETES_VERSION_TTL)
+    {
+        return Ok(version.clone());
+    }
+
+    let version = detect_kubernetes_version()?;
+    *detected = Some((version.clone(), now));
+    Ok(version)
+}
+
+// Detects the version of Kubernetes from the kubelets of the cluster. The
+// kubelets are never newer than the API server, and they are upgraded after
+// the control plane: the newest kubelet is the closest to the API server.
+fn detect_kubernetes_version() -> Result<Version> {
+    let nodes = list_all_resources::<Node>(&ListAllResourcesRequest {
+        api_version: Node::API_VERSION.to_string(),
+        kind: Node::KIND.to_string(),
+        label_selector: None,
+        field_selector: None,
+        field_masks: Some(BTreeSet::from([
+            "status.nodeInfo.kubeletVersion".to_string()
+        ])),
+    })?;
+
+    nodes
+        .items
+        .iter()
+        .filter_map(|node| node.status.as_ref()?.node_info.as_ref())
+        .filter_map(|node_info| parse_kubelet_version(&node_info.kubelet_version))
+        .max()
+        .ok_or_else(|| anyhow!("no Node reports a valid kubelet version"))
+}
+
+// Parses versions like `v1.30.2+k3s1` or `v1.29.6-eks-1234`, ignoring the
+// pre-release and the build metadata added by the distributions
+fn parse_kubelet_version(kubelet_version: &str) -> Option<Version> {
+    let kubelet_version = kubelet_version.strip_prefix('v').unwrap_or(kubelet_version);
+    Version::parse(kubelet_version)
+        .ok()
+        .map(|version| Version::new(version.major, version.minor, version.patch))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use k8s_openapi::api::core::v1::{NodeStatus, NodeSystemInfo};
     use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::ListAllResourcesRequest;
+
+        #[allow(dead_code)]
+        pub fn list_all_resources<T>(
+            _req: &ListAllResourcesRequest,
+        ) -> anyhow::Result<k8s_openapi::List<T>>
+        where
+            T: k8s_openapi::ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+        {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    fn node(kubelet_version: &str) -> Node {
+        Node {
+            status: Some(NodeStatus {
+                node_info: Some(NodeSystemInfo {
+                    kubelet_version: kubelet_version.to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn forget_detected_kubernetes_version() {
+        DETECTED_KUBERNETES_VERSION.lock().unwrap().take();
+    }
+
+    #[test]
+    fn parse_kubelet_versions() {
+        assert_eq!(
+            parse_kubelet_version("v1.30.2"),
+            Some(Version::new(1, 30, 2))
+        );
+        assert_eq!(
+            parse_kubelet_version("v1.30.2+k3s1"),
+            Some(Version::new(1, 30, 2))
+        );
+        assert_eq!(
+            parse_kubelet_version("v1.29.6-eks-1234"),
+            Some(Version::new(1, 29, 6))
+        );
+        assert_eq!(parse_kubelet_version("unknown"), None);
+    }
 
     #[test]
     fn eval_extensions_v1beta1_ingress() -> Result<(), ()> {
@@ -59,7 +320,12 @@ mod tests {
                 expected_validation_result: false,
                 settings: Settings {
                     kubernetes_version: semver::Version::parse("1.25.0").unwrap(),
+                    detect_kubernetes_version: false,
                     deny_on_deprecation: true,
+                    custom_deprecations: vec![],
+                    custom_field_deprecations: vec![],
+                    enforcement_deadlines: vec![],
+                    rule_bundle: None,
                 },
             },
             Testcase {
@@ -68,7 +334,12 @@ mod tests {
                 expected_validation_result: false,
                 settings: Settings {
                     kubernetes_version: semver::Version::parse("1.19.0").unwrap(),
+                    detect_kubernetes_version: false,
                     deny_on_deprecation: true,
+                    custom_deprecations: vec![],
+                    custom_field_deprecations: vec![],
+                    enforcement_deadlines: vec![],
+                    rule_bundle: None,
                 },
             },
             Testcase {
@@ -79,7 +350,12 @@ mod tests {
                 expected_validation_result: true,
                 settings: Settings {
                     kubernetes_version: semver::Version::parse("1.19.0").unwrap(),
+                    detect_kubernetes_version: false,
                     deny_on_deprecation: false,
+                    custom_deprecations: vec![],
+                    custom_field_deprecations: vec![],
+                    enforcement_deadlines: vec![],
+                    rule_bundle: None,
                 },
             },
             Testcase {
@@ -88,7 +364,12 @@ mod tests {
                 expected_validation_result: true,
                 settings: Settings {
                     kubernetes_version: semver::Version::parse("1.10.0").unwrap(),
+                    detect_kubernetes_version: false,
                     deny_on_deprecation: false,
+                    custom_deprecations: vec![],
+                    custom_field_deprecations: vec![],
+                    enforcement_deadlines: vec![],
+                    rule_bundle: None,
                 },
             },
         ];
@@ -104,4 +385,336 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn eval_custom_deprecations() {
+        let custom_deprecations: versions::DeprecationRules = serde_json::from_str(
+            r#"[
+                {
+                    "version": "example.com/v1alpha1",
+                    "kind": "Widget",
+                    "deprecated-in": "v1.29.0",
+                    "removed-in": "v1.31.0",
+                    "replacement-api": "example.com/v1"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let request_file = "test_data/custom_resource_creation.json";
+        let test_cases = vec![
+            Testcase {
+                name: String::from("Reject because it has been removed by the custom rule"),
+                fixture_file: String::from(request_file),
+                expected_validation_result: false,
+                settings: Settings {
+                    kubernetes_version: semver::Version::parse("1.31.0").unwrap(),
+                    detect_kubernetes_version: false,
+                    deny_on_deprecation: false,
+                    custom_deprecations: custom_deprecations.clone(),
+                    custom_field_deprecations: vec![],
+                    enforcement_deadlines: vec![],
+                    rule_bundle: None,
+                },
+            },
+            Testcase {
+                name: String::from("Accept a deprecated resource because of user settings"),
+                fixture_file: String::from(request_file),
+                expected_validation_result: true,
+                settings: Settings {
+                    kubernetes_version: semver::Version::parse("1.30.0").unwrap(),
+                    detect_kubernetes_version: false,
+                    deny_on_deprecation: false,
+                    custom_deprecations: custom_deprecations.clone(),
+                    custom_field_deprecations: vec![],
+                    enforcement_deadlines: vec![],
+                    rule_bundle: None,
+                },
+            },
+            Testcase {
+                name: String::from("Accept before the deprecation of the custom rule"),
+                fixture_file: String::from(request_file),
+                expected_validation_result: true,
+                settings: Settings {
+                    kubernetes_version: semver::Version::parse("1.28.0").unwrap(),
+                    detect_kubernetes_version: false,
+                    deny_on_deprecation: true,
+                    custom_deprecations: custom_deprecations.clone(),
+                    custom_field_deprecations: vec![],
+                    enforcement_deadlines: vec![],
+                    rule_bundle: None,
+                },
+            },
+            Testcase {
+                name: String::from("Built-in rules are still applied"),
+                fixture_file: String::from("test_data/ingress_creation.json"),
+                expected_validation_result: false,
+                settings: Settings {
+                    kubernetes_version: semver::Version::parse("1.25.0").unwrap(),
+                    detect_kubernetes_version: false,
+                    deny_on_deprecation: true,
+                    custom_deprecations,
+                    custom_field_deprecations: vec![],
+                    enforcement_deadlines: vec![],
+                    rule_bundle: None,
+                },
+            },
+        ];
+
+        for tc in &test_cases {
+            tc.eval(validate).unwrap();
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn eval_detected_kubernetes_version() {
+        forget_detected_kubernetes_version();
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Node>().returning(|req| {
+            assert_eq!(req.kind, "Node");
+            Ok(k8s_openapi::List::<Node> {
+                items: vec![node("v1.24.3"), node("v1.25.1+k3s1"), node("")],
+                ..Default::default()
+            })
+        });
+
+        let settings: Settings =
+            serde_json::from_str(r#"{"detect_kubernetes_version": true}"#).unwrap();
+        assert_eq!(
+            kubernetes_version(&settings).unwrap(),
+            Version::new(1, 25, 1)
+        );
+
+        let tc = Testcase {
+            name: String::from("Reject because it has been dropped from the detected version"),
+            fixture_file: String::from("test_data/ingress_creation.json"),
+            expected_validation_result: false,
+            settings,
+        };
+        tc.eval(validate).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn eval_kubernetes_version_not_detected() {
+        forget_detected_kubernetes_version();
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Node>().returning(|_req| {
+            Ok(k8s_openapi::List::<Node> {
+                items: vec![],
+                ..Default::default()
+            })
+        });
+
+        let settings: Settings = serde_json::from_str(
+            r#"{"kubernetes_version": "1.10.0", "detect_kubernetes_version": true}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            kubernetes_version(&settings).unwrap(),
+            Version::new(1, 10, 0)
+        );
+
+        let settings: Settings =
+            serde_json::from_str(r#"{"detect_kubernetes_version": true}"#).unwrap();
+        assert!(kubernetes_version(&settings).is_err());
+
+        let tc = Testcase {
+            name: String::from("Reject when the version cannot be detected"),
+            fixture_file: String::from("test_data/valid_ingress.json"),
+            expected_validation_result: false,
+            settings,
+        };
+        tc.eval(validate).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn detected_kubernetes_version_is_cached() {
+        forget_detected_kubernetes_version();
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Node>().times(1).returning(|_req| {
+            Ok(k8s_openapi::List::<Node> {
+                items: vec![node("v1.30.2")],
+                ..Default::default()
+            })
+        });
+
+        let settings: Settings =
+            serde_json::from_str(r#"{"detect_kubernetes_version": true}"#).unwrap();
+        for _ in 0..2 {
+            assert_eq!(
+                kubernetes_version(&settings).unwrap(),
+                Version::new(1, 30, 2)
+            );
+        }
+        ctx.checkpoint();
+
+        // the Nodes are listed again once the detected version is expired
+        if let Some((_, detected_at)) = DETECTED_KUBERNETES_VERSION.lock().unwrap().as_mut() {
+            *detected_at -= Duration::minutes(DETECTED_KUBERNETES_VERSION_TTL);
+        }
+        ctx.expect::<Node>().times(1).returning(|_req| {
+            Ok(k8s_openapi::List::<Node> {
+                items: vec![node("v1.31.0")],
+                ..Default::default()
+            })
+        });
+        assert_eq!(
+            kubernetes_version(&settings).unwrap(),
+            Version::new(1, 31, 0)
+        );
+    }
+
+    #[test]
+    fn eval_enforcement_deadlines() {
+        let settings = |enforce_after: &str, deny_on_deprecation: bool| -> Settings {
+            serde_json::from_str(&format!(
+                r#"{{"kubernetes_version": "1.19.0", "deny_on_deprecation": {deny_on_deprecation}, "enforcement_deadlines": [{{"version": "extensions/v1beta1", "kind": "Ingress", "enforce_after": "{enforce_after}"}}]}}"#
+            ))
+            .unwrap()
+        };
+
+        let request_file = "test_data/ingress_creation.json";
+        let tc = Testcase {
+            name: String::from("Accept with a warning before the deadline"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: settings("9999-12-31", true),
+        };
+        let response = tc.eval(validate).unwrap();
+        assert_eq!(
+            response.warnings,
+            Some(vec![
+                "extensions/v1beta1 Ingress cannot be used. It has been deprecated starting from 1.14.0. It has been removed starting from 1.22.0. It has been replaced by networking.k8s.io/v1. It will be rejected after 9999-12-31.".to_string()
+            ])
+        );
+
+        let tc = Testcase {
+            name: String::from("Reject after the deadline, despite the user settings"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: settings("1970-01-01", false),
+        };
+        tc.eval(validate).unwrap();
+    }
+
+    #[test]
+    fn eval_deprecated_fields() {
+        let settings = |kubernetes_version: &str, deny_on_deprecation: bool| -> Settings {
+            serde_json::from_str(&format!(
+                r#"{{"kubernetes_version": "{kubernetes_version}", "deny_on_deprecation": {deny_on_deprecation}}}"#
+            ))
+            .unwrap()
+        };
+
+        let request_file = "test_data/deployment_apparmor_annotation.json";
+        let test_cases = vec![
+            Testcase {
+                name: String::from("Reject the deprecated annotation of the Pod template"),
+                fixture_file: String::from(request_file),
+                expected_validation_result: false,
+                settings: settings("1.30.0", true),
+            },
+            Testcase {
+                name: String::from("Accept the deprecated annotation because of user settings"),
+                fixture_file: String::from(request_file),
+                expected_validation_result: true,
+                settings: settings("1.30.0", false),
+            },
+            Testcase {
+                name: String::from("Accept before the deprecation of the annotation"),
+                fixture_file: String::from(request_file),
+                expected_validation_result: true,
+                settings: settings("1.29.0", true),
+            },
+            Testcase {
+                name: String::from("Accept when the replacement field is set"),
+                fixture_file: String::from("test_data/deployment_apparmor_profile.json"),
+                expected_validation_result: true,
+                settings: settings("1.30.0", true),
+            },
+        ];
+
+        for tc in &test_cases {
+            tc.eval(validate).unwrap();
+        }
+
+        let tc = Testcase {
+            name: String::from("Rejection message"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: settings("1.30.0", true),
+        };
+        let response = tc.eval(validate).unwrap();
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                r#"v1 Pod field metadata.annotations["container.apparmor.security.beta.kubernetes.io/*"] cannot be used. It has been deprecated starting from 1.30.0. It has been replaced by spec.containers.*.securityContext.appArmorProfile."#
+            )
+        );
+    }
+
+    #[test]
+    fn eval_custom_field_deprecations() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"kubernetes_version": "1.30.0", "custom_field_deprecations": [{"version": "v1", "kind": "Pod", "path": ["metadata", "annotations", "example.com/legacy-mode"], "removed-in": "v1.28.0"}]}"#,
+        )
+        .unwrap();
+
+        let tc = Testcase {
+            name: String::from("Reject because of the custom field rule"),
+            fixture_file: String::from("test_data/deployment_apparmor_profile.json"),
+            expected_validation_result: false,
+            settings,
+        };
+        tc.eval(validate).unwrap();
+    }
+
+    #[test]
+    fn find_bundled_deprecation() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"kubernetes_version": "1.31.0", "custom_deprecations": [{"version": "example.com/v1alpha1", "kind": "Gadget", "removed-in": "v1.30.0"}]}"#,
+        )
+        .unwrap();
+        let bundled_rules: BundledRules = serde_json::from_str(
+            r#"{"deprecations": [{"version": "example.com/v1alpha1", "kind": "Widget", "removed-in": "v1.31.0"}]}"#,
+        )
+        .unwrap();
+        let kubernetes_version = Version::new(1, 31, 0);
+        let obj = |kind: &str| GroupVersionKind {
+            group: "example.com".to_string(),
+            version: "v1alpha1".to_string(),
+            kind: kind.to_string(),
+        };
+
+        let deprecation = find_deprecation(
+            &obj("Widget"),
+            &kubernetes_version,
+            &settings,
+            &bundled_rules,
+        )
+        .expect("the bundled rule should match");
+        assert_eq!(deprecation.kind, "Widget");
+
+        let deprecation = find_deprecation(
+            &obj("Gadget"),
+            &kubernetes_version,
+            &settings,
+            &bundled_rules,
+        )
+        .expect("the custom rule should match");
+        assert_eq!(deprecation.kind, "Gadget");
+
+        assert!(
+            find_deprecation(
+                &obj("Widget"),
+                &kubernetes_version,
+                &settings,
+                &BundledRules::default(),
+            )
+            .is_none()
+        );
+    }
 }
@@ -1,20 +1,78 @@
+use chrono::NaiveDate;
 use lazy_static::lazy_static;
+use rule_bundle::RuleBundle;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 
-use versions::serde_helpers::semver_serde;
+use versions::{
+    DeprecationRule, DeprecationRules, FieldDeprecationRules, serde_helpers::semver_serde,
+};
 
 lazy_static! {
     static ref DEFAULT_KUBERNETES_VERSION: Version = Version::parse("0.0.1").unwrap();
 }
 
+/// Date after which the usage of a deprecated API is rejected. Until then,
+/// the usage is accepted with a warning.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub(crate) struct EnforcementDeadline {
+    pub version: String,
+    pub kind: String,
+    pub enforce_after: NaiveDate,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "snake_case")]
 pub(crate) struct Settings {
-    #[serde(with = "semver_serde")]
+    /// Version of Kubernetes used to look for deprecations. When
+    /// `detect_kubernetes_version` is enabled, it is used only when the
+    /// version of the cluster cannot be detected.
+    #[serde(default = "kubernetes_version_default", with = "semver_serde")]
     pub kubernetes_version: Version,
+    /// Detect the version of Kubernetes from the Nodes of the cluster, at
+    /// evaluation time. The detected version is reused for a few minutes.
+    #[serde(default)]
+    pub detect_kubernetes_version: bool,
     #[serde(default = "deny_on_deprecation_default")]
     pub deny_on_deprecation: bool,
+    /// Deprecation rules defined by the operator, using the same format of
+    /// the built-in ones. They are checked before the built-in rules.
+    #[serde(default)]
+    pub custom_deprecations: DeprecationRules,
+    /// Deprecated fields defined by the operator, using the same format of the
+    /// built-in ones. They are reported together with the built-in fields.
+    #[serde(default)]
+    pub custom_field_deprecations: FieldDeprecationRules,
+    /// Deadlines of the migrations away from the deprecated APIs, applying
+    /// to both the built-in and the custom rules
+    #[serde(default)]
+    pub enforcement_deadlines: Vec<EnforcementDeadline>,
+    /// Signed OCI artifact distributing more deprecation rules, fetched at
+    /// evaluation time. Its rules are checked after the custom ones and
+    /// before the built-in ones.
+    #[serde(default)]
+    pub rule_bundle: Option<RuleBundle>,
+}
+
+/// Rules distributed with the rule bundle, using the same format of the
+/// custom ones
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(default, rename_all = "snake_case")]
+pub(crate) struct BundledRules {
+    pub deprecations: DeprecationRules,
+    pub field_deprecations: FieldDeprecationRules,
+}
+
+impl kubewarden::settings::Validatable for BundledRules {
+    fn validate(&self) -> Result<(), String> {
+        validate_deprecations("bundled", &self.deprecations)?;
+        validate_field_deprecations("bundled", &self.field_deprecations)
+    }
+}
+
+fn kubernetes_version_default() -> Version {
+    DEFAULT_KUBERNETES_VERSION.clone()
 }
 
 fn deny_on_deprecation_default() -> bool {
@@ -25,18 +83,133 @@ impl Default for Settings {
     fn default() -> Self {
         Settings {
             kubernetes_version: DEFAULT_KUBERNETES_VERSION.clone(),
+            detect_kubernetes_version: false,
             deny_on_deprecation: true,
+            custom_deprecations: Vec::new(),
+            custom_field_deprecations: Vec::new(),
+            enforcement_deadlines: Vec::new(),
+            rule_bundle: None,
         }
     }
 }
 
+impl Settings {
+    /// Returns the version of Kubernetes provided by the operator, if any
+    pub fn configured_kubernetes_version(&self) -> Option<&Version> {
+        (self.kubernetes_version != *DEFAULT_KUBERNETES_VERSION).then_some(&self.kubernetes_version)
+    }
+
+    /// Returns the date after which the usage of the API deprecated by the
+    /// rule is rejected, if any
+    pub fn enforce_after(&self, deprecation: &DeprecationRule) -> Option<NaiveDate> {
+        self.enforcement_deadlines
+            .iter()
+            .find(|deadline| {
+                deadline.version == deprecation.version && deadline.kind == deprecation.kind
+            })
+            .map(|deadline| deadline.enforce_after)
+    }
+}
+
 impl kubewarden::settings::Validatable for Settings {
     fn validate(&self) -> Result<(), String> {
-        if self.kubernetes_version == *DEFAULT_KUBERNETES_VERSION {
-            Err("Please provide a kubernetes version".to_string())
-        } else {
-            Ok(())
+        if self.configured_kubernetes_version().is_none() && !self.detect_kubernetes_version {
+            return Err(
+                "Please provide a kubernetes version, or enable detect_kubernetes_version"
+                    .to_string(),
+            );
+        }
+
+        validate_deprecations("custom", &self.custom_deprecations)?;
+        validate_field_deprecations("custom", &self.custom_field_deprecations)?;
+
+        let mut deadlines = std::collections::HashSet::new();
+        for deadline in &self.enforcement_deadlines {
+            if deadline.version.is_empty() || deadline.kind.is_empty() {
+                return Err("enforcement deadlines must have a version and a kind".to_string());
+            }
+            if !deadlines.insert((&deadline.version, &deadline.kind)) {
+                return Err(format!(
+                    "multiple enforcement deadlines for {} {}",
+                    deadline.version, deadline.kind
+                ));
+            }
+        }
+
+        if let Some(rule_bundle) = &self.rule_bundle {
+            rule_bundle.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+// Validates the deprecation rules defined by the operator, either in the
+// settings or in the rule bundle
+fn validate_deprecations(origin: &str, deprecations: &DeprecationRules) -> Result<(), String> {
+    for deprecation in deprecations {
+        if deprecation.version.is_empty() || deprecation.kind.is_empty() {
+            return Err(format!(
+                "{origin} deprecations must have a version and a kind"
+            ));
+        }
+        validate_lifecycle(
+            &format!(
+                "{origin} deprecation of {} {}",
+                deprecation.version, deprecation.kind
+            ),
+            &deprecation.deprecated_in,
+            &deprecation.removed_in,
+        )?;
+    }
+    Ok(())
+}
+
+fn validate_field_deprecations(
+    origin: &str,
+    deprecations: &FieldDeprecationRules,
+) -> Result<(), String> {
+    for deprecation in deprecations {
+        if deprecation.version.is_empty()
+            || deprecation.kind.is_empty()
+            || deprecation.path.is_empty()
+        {
+            return Err(format!(
+                "{origin} field deprecations must have a version, a kind and a path"
+            ));
         }
+        let name = format!(
+            "{origin} field deprecation of {} {} {}",
+            deprecation.version,
+            deprecation.kind,
+            deprecation.path.join(".")
+        );
+        if deprecation
+            .path
+            .iter()
+            .chain(&deprecation.replacement)
+            .any(String::is_empty)
+        {
+            return Err(format!("{name} cannot have empty path segments"));
+        }
+        validate_lifecycle(&name, &deprecation.deprecated_in, &deprecation.removed_in)?;
+    }
+    Ok(())
+}
+
+// Validates the Kubernetes releases deprecating and removing an API, or a
+// field, of a rule defined by the operator
+fn validate_lifecycle(
+    name: &str,
+    deprecated_in: &Option<Version>,
+    removed_in: &Option<Version>,
+) -> Result<(), String> {
+    match (deprecated_in, removed_in) {
+        (None, None) => Err(format!("{name} must have deprecated-in or removed-in")),
+        (Some(deprecated_in), Some(removed_in)) if deprecated_in > removed_in => Err(format!(
+            "{name} is removed in {removed_in}, before being deprecated in {deprecated_in}"
+        )),
+        _ => Ok(()),
     }
 }
 
@@ -50,16 +223,159 @@ mod tests {
     fn validate_settings() {
         let settings = Settings {
             kubernetes_version: Version::parse("1.25.0").unwrap(),
+            detect_kubernetes_version: false,
             deny_on_deprecation: true,
+            custom_deprecations: vec![],
+            custom_field_deprecations: vec![],
+            enforcement_deadlines: vec![],
+            rule_bundle: None,
         };
 
         assert!(settings.validate().is_ok());
 
         let settings = Settings {
             kubernetes_version: Version::parse("0.0.1").unwrap(),
+            detect_kubernetes_version: false,
             deny_on_deprecation: true,
+            custom_deprecations: vec![],
+            custom_field_deprecations: vec![],
+            enforcement_deadlines: vec![],
+            rule_bundle: None,
         };
 
         assert!(settings.validate().is_err());
     }
+
+    #[test]
+    fn validate_detect_kubernetes_version() {
+        let settings: Settings =
+            serde_json::from_str(r#"{"detect_kubernetes_version": true}"#).unwrap();
+        assert!(settings.validate().is_ok());
+        assert!(settings.configured_kubernetes_version().is_none());
+
+        let settings: Settings = serde_json::from_str(
+            r#"{"kubernetes_version": "1.30.0", "detect_kubernetes_version": true}"#,
+        )
+        .unwrap();
+        assert!(settings.validate().is_ok());
+        assert_eq!(
+            settings.configured_kubernetes_version(),
+            Some(&Version::new(1, 30, 0))
+        );
+
+        let settings: Settings = serde_json::from_str(r#"{}"#).unwrap();
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn validate_custom_deprecations() {
+        let valid_settings = vec![
+            r#"{"kubernetes_version": "1.30.0", "custom_deprecations": [{"version": "example.com/v1alpha1", "kind": "Widget", "deprecated-in": "v1.29.0", "removed-in": "v1.31.0", "replacement-api": "example.com/v1"}]}"#,
+            r#"{"kubernetes_version": "1.30.0", "custom_deprecations": [{"version": "example.com/v1alpha1", "kind": "Widget", "removed-in": "v1.31.0"}]}"#,
+        ];
+        for settings in valid_settings {
+            let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+            assert!(settings.validate().is_ok(), "{settings:?} should be valid");
+        }
+
+        let invalid_settings = vec![
+            r#"{"kubernetes_version": "1.30.0", "custom_deprecations": [{"version": "", "kind": "Widget", "deprecated-in": "v1.29.0"}]}"#,
+            r#"{"kubernetes_version": "1.30.0", "custom_deprecations": [{"version": "example.com/v1alpha1", "kind": "Widget"}]}"#,
+            r#"{"kubernetes_version": "1.30.0", "custom_deprecations": [{"version": "example.com/v1alpha1", "kind": "Widget", "deprecated-in": "v1.31.0", "removed-in": "v1.29.0"}]}"#,
+        ];
+        for settings in invalid_settings {
+            let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+            assert!(
+                settings.validate().is_err(),
+                "{settings:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn validate_enforcement_deadlines() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"kubernetes_version": "1.30.0", "enforcement_deadlines": [{"version": "extensions/v1beta1", "kind": "Ingress", "enforce_after": "2025-06-30"}]}"#,
+        )
+        .expect("cannot parse settings");
+        assert!(settings.validate().is_ok());
+
+        let invalid_settings = vec![
+            r#"{"kubernetes_version": "1.30.0", "enforcement_deadlines": [{"version": "", "kind": "Ingress", "enforce_after": "2025-06-30"}]}"#,
+            r#"{"kubernetes_version": "1.30.0", "enforcement_deadlines": [{"version": "extensions/v1beta1", "kind": "Ingress", "enforce_after": "2025-06-30"}, {"version": "extensions/v1beta1", "kind": "Ingress", "enforce_after": "2025-12-31"}]}"#,
+        ];
+        for settings in invalid_settings {
+            let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+            assert!(
+                settings.validate().is_err(),
+                "{settings:?} should be invalid"
+            );
+        }
+
+        assert!(
+            serde_json::from_str::<Settings>(
+                r#"{"kubernetes_version": "1.30.0", "enforcement_deadlines": [{"version": "extensions/v1beta1", "kind": "Ingress", "enforce_after": "June 30th"}]}"#,
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn validate_custom_field_deprecations() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"kubernetes_version": "1.30.0", "custom_field_deprecations": [{"version": "example.com/v1", "kind": "Widget", "path": ["spec", "legacyMode"], "deprecated-in": "v1.29.0", "replacement": ["spec", "mode"]}]}"#,
+        )
+        .expect("cannot parse settings");
+        assert!(settings.validate().is_ok());
+
+        let invalid_settings = vec![
+            r#"{"kubernetes_version": "1.30.0", "custom_field_deprecations": [{"version": "example.com/v1", "kind": "Widget", "path": [], "deprecated-in": "v1.29.0"}]}"#,
+            r#"{"kubernetes_version": "1.30.0", "custom_field_deprecations": [{"version": "example.com/v1", "kind": "Widget", "path": ["spec", ""], "deprecated-in": "v1.29.0"}]}"#,
+            r#"{"kubernetes_version": "1.30.0", "custom_field_deprecations": [{"version": "example.com/v1", "kind": "Widget", "path": ["spec", "legacyMode"]}]}"#,
+            r#"{"kubernetes_version": "1.30.0", "custom_field_deprecations": [{"version": "example.com/v1", "kind": "Widget", "path": ["spec", "legacyMode"], "deprecated-in": "v1.31.0", "removed-in": "v1.29.0"}]}"#,
+        ];
+        for settings in invalid_settings {
+            let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+            assert!(
+                settings.validate().is_err(),
+                "{settings:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn validate_rule_bundle() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"kubernetes_version": "1.30.0", "rule_bundle": {"image": "ghcr.io/acme/deprecations:latest", "keys": ["-----BEGIN PUBLIC KEY-----"]}}"#,
+        )
+        .expect("cannot parse settings");
+        assert!(settings.validate().is_ok());
+
+        let settings: Settings = serde_json::from_str(
+            r#"{"kubernetes_version": "1.30.0", "rule_bundle": {"image": "ghcr.io/acme/deprecations:latest", "keys": []}}"#,
+        )
+        .expect("cannot parse settings");
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn validate_bundled_rules() {
+        let rules: BundledRules = serde_json::from_str(
+            r#"{"deprecations": [{"version": "example.com/v1alpha1", "kind": "Widget", "removed-in": "v1.31.0"}], "field_deprecations": [{"version": "example.com/v1", "kind": "Widget", "path": ["spec", "legacyMode"], "deprecated-in": "v1.29.0"}]}"#,
+        )
+        .expect("cannot parse the rules");
+        assert!(rules.validate().is_ok());
+
+        let rules: BundledRules = serde_json::from_str(
+            r#"{"deprecations": [{"version": "example.com/v1alpha1", "kind": "Widget"}]}"#,
+        )
+        .expect("cannot parse the rules");
+        assert_eq!(
+            rules.validate(),
+            Err(
+                "bundled deprecation of example.com/v1alpha1 Widget must have deprecated-in or removed-in"
+                    .to_string()
+            )
+        );
+    }
 }
@@ -3,3 +3,49 @@
 @test "Dyummy test" {
   echo "This is a dummy test just to make the CI happy"
 }
+
+@test "Echo the whole request" {
+	run kwctl run --request-path test_data/create_reject.json annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*"code":418.*') -ne 0 ]
+	[ $(expr "$output" : '.*requestResource.*') -ne 0 ]
+}
+
+@test "Redact the data of the Secrets" {
+	run kwctl run --request-path test_data/secret_create_reject.json annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*<redacted>.*') -ne 0 ]
+	[ $(expr "$output" : '.*czNjcjN0.*') -eq 0 ]
+	[ $(expr "$output" : '.*s3cr3t.*') -eq 0 ]
+}
+
+@test "Echo a summary of the request" {
+	run kwctl run --request-path test_data/create_reject.json --settings-json '{"format": "summary", "fields": ["object.spec.replicas"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*object.spec.replicas.*') -ne 0 ]
+	[ $(expr "$output" : '.*requestResource.*') -eq 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/create_reject.json --settings-json '{"fields": ["object.spec.replicas"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
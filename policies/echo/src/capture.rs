@@ -0,0 +1,177 @@
+use serde_json::{Map, Value, json};
+
+use crate::settings::{Format, Settings};
+
+const REDACTED: &str = "<redacted>";
+const LAST_APPLIED_CONFIGURATION: &str = "kubectl.kubernetes.io/last-applied-configuration";
+
+/// Returns the AdmissionRequest to echo back, redacted and summarized as
+/// requested by the settings
+pub(crate) fn capture(mut request: Value, settings: &Settings) -> Value {
+    if settings.redact_secret_data {
+        redact_secret_data(&mut request);
+    }
+    for path in &settings.redacted_fields {
+        redact(&mut request, &segments(path));
+    }
+    match settings.format {
+        Format::Full => request,
+        Format::Summary => summary(&request, &settings.fields),
+    }
+}
+
+fn segments(path: &str) -> Vec<&str> {
+    path.split('.').collect()
+}
+
+// The keys of the Secret data are kept, they are useful to debug the
+// policies and they are not sensitive
+fn redact_secret_data(request: &mut Value) {
+    if request["kind"]["group"] != "" || request["kind"]["kind"] != "Secret" {
+        return;
+    }
+    for object in ["object", "oldObject"] {
+        let Some(object) = request.get_mut(object) else {
+            continue;
+        };
+        redact(object, &["data", "*"]);
+        redact(object, &["stringData", "*"]);
+        redact(
+            object,
+            &["metadata", "annotations", LAST_APPLIED_CONFIGURATION],
+        );
+    }
+}
+
+/// Replaces the values found at the path
+fn redact(value: &mut Value, segments: &[&str]) {
+    let Some((segment, rest)) = segments.split_first() else {
+        *value = Value::String(REDACTED.to_owned());
+        return;
+    };
+    match value {
+        Value::Object(map) if *segment == "*" => {
+            map.values_mut().for_each(|value| redact(value, rest))
+        }
+        Value::Object(map) => {
+            if let Some(value) = map.get_mut(*segment) {
+                redact(value, rest);
+            }
+        }
+        Value::Array(items) if *segment == "*" => {
+            items.iter_mut().for_each(|item| redact(item, rest))
+        }
+        Value::Array(items) => {
+            if let Some(item) = segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get_mut(index))
+            {
+                redact(item, rest);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the value found at the path. The values matched by a `*` segment
+/// are returned as a list.
+fn select(value: &Value, segments: &[&str]) -> Option<Value> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Some(value.clone());
+    };
+    match value {
+        Value::Object(map) if *segment == "*" => Some(Value::Array(
+            map.values()
+                .filter_map(|value| select(value, rest))
+                .collect(),
+        )),
+        Value::Object(map) => map.get(*segment).and_then(|value| select(value, rest)),
+        Value::Array(items) if *segment == "*" => Some(Value::Array(
+            items.iter().filter_map(|item| select(item, rest)).collect(),
+        )),
+        Value::Array(items) => segment
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| items.get(index))
+            .and_then(|item| select(item, rest)),
+        _ => None,
+    }
+}
+
+// The fields missing from the request are left out of the summary
+fn summary(request: &Value, fields: &[String]) -> Value {
+    let fields: Map<String, Value> = fields
+        .iter()
+        .filter_map(|path| select(request, &segments(path)).map(|value| (path.to_owned(), value)))
+        .collect();
+    json!({
+        "uid": request["uid"],
+        "operation": request["operation"],
+        "kind": request["kind"],
+        "resource": request["resource"],
+        "subResource": request["subResource"],
+        "namespace": request["namespace"],
+        "name": request["name"],
+        "user": {
+            "username": request["userInfo"]["username"],
+            "groups": request["userInfo"]["groups"],
+        },
+        "dryRun": request["dryRun"],
+        "fields": fields,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    fn document() -> Value {
+        json!({
+            "spec": {
+                "replicas": 2,
+                "containers": [
+                    {"name": "web", "env": [{"name": "TOKEN", "value": "s3cr3t"}]},
+                    {"name": "proxy"},
+                ],
+            },
+        })
+    }
+
+    #[rstest]
+    #[case::scalar(
+        "spec.replicas",
+        json!({"spec": {"replicas": REDACTED, "containers": document()["spec"]["containers"]}})
+    )]
+    #[case::wildcard(
+        "spec.containers.*.env",
+        json!({"spec": {"replicas": 2, "containers": [
+            {"name": "web", "env": REDACTED},
+            {"name": "proxy"},
+        ]}})
+    )]
+    #[case::index(
+        "spec.containers.1.name",
+        json!({"spec": {"replicas": 2, "containers": [
+            document()["spec"]["containers"][0],
+            {"name": REDACTED},
+        ]}})
+    )]
+    #[case::missing("spec.volumes", document())]
+    fn redact_path(#[case] path: &str, #[case] expected: Value) {
+        let mut value = document();
+        redact(&mut value, &segments(path));
+        assert_eq!(value, expected);
+    }
+
+    #[rstest]
+    #[case::scalar("spec.replicas", Some(json!(2)))]
+    #[case::wildcard("spec.containers.*.name", Some(json!(["web", "proxy"])))]
+    #[case::index("spec.containers.0.env.0.name", Some(json!("TOKEN")))]
+    #[case::missing("spec.volumes", None)]
+    fn select_path(#[case] path: &str, #[case] expected: Option<Value>) {
+        assert_eq!(select(&document(), &segments(path)), expected);
+    }
+}
@@ -5,6 +5,7 @@ use std::convert::TryFrom;
 extern crate kubewarden_policy_sdk as kubewarden;
 use kubewarden::{protocol_version_guest, validate_settings};
 
+mod capture;
 mod settings;
 use settings::Settings;
 
@@ -61,7 +62,6 @@ pub extern "C" fn wapc_init() {
 }
 
 fn validate(payload: &[u8]) -> CallResult {
-    let request_expr = jmespath::compile("request")?;
     let operation_expr = jmespath::compile("request.operation")?;
 
     let json = std::str::from_utf8(payload)?;
@@ -79,7 +79,9 @@ fn validate(payload: &[u8]) -> CallResult {
     if result.is_object() {
         let annotations = result.as_object().unwrap();
         if annotations.contains_key(operation.annotation_key()) {
-            let request = request_expr.search(data)?;
+            let mut payload: serde_json::Value = serde_json::from_str(json)?;
+            let settings: Settings = serde_json::from_value(payload["settings"].take())?;
+            let request = capture::capture(payload["request"].take(), &settings);
 
             return kubewarden::reject_request(Some(request.to_string()), Some(418), None, None);
         }
@@ -92,6 +94,8 @@ mod tests {
     use super::*;
     use assert_json_diff::assert_json_include;
     use kubewarden_policy_sdk::test::Testcase;
+    use serde_json::json;
+    use settings::Format;
     use std::fs;
 
     #[test]
@@ -101,19 +105,19 @@ mod tests {
                 name: String::from("create"),
                 fixture_file: String::from("test_data/create_accept.json"),
                 expected_validation_result: true,
-                settings: Settings {},
+                settings: Settings::default(),
             },
             Testcase {
                 name: String::from("update"),
                 fixture_file: String::from("test_data/update_accept.json"),
                 expected_validation_result: true,
-                settings: Settings {},
+                settings: Settings::default(),
             },
             Testcase {
                 name: String::from("delete"),
                 fixture_file: String::from("test_data/delete_accept.json"),
                 expected_validation_result: true,
-                settings: Settings {},
+                settings: Settings::default(),
             },
         ];
 
@@ -136,19 +140,19 @@ mod tests {
                 name: String::from("create"),
                 fixture_file: String::from("test_data/create_reject.json"),
                 expected_validation_result: false,
-                settings: Settings {},
+                settings: Settings::default(),
             },
             Testcase {
                 name: String::from("update"),
                 fixture_file: String::from("test_data/update_reject.json"),
                 expected_validation_result: false,
-                settings: Settings {},
+                settings: Settings::default(),
             },
             Testcase {
                 name: String::from("delete"),
                 fixture_file: String::from("test_data/delete_reject.json"),
                 expected_validation_result: false,
-                settings: Settings {},
+                settings: Settings::default(),
             },
         ];
 
@@ -173,4 +177,84 @@ mod tests {
             assert_json_include!(actual: request, expected: expected_json);
         }
     }
+
+    fn echoed_request(fixture: &str, settings: Settings) -> serde_json::Value {
+        let tc = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings,
+        };
+        let res = tc
+            .eval(validate)
+            .expect("Validation should not raise errors");
+        serde_json::from_str(&res.message.expect("missing response message"))
+            .expect("response message should contain a valid JSON document")
+    }
+
+    #[test]
+    fn redact_secret_data() {
+        let request = echoed_request("test_data/secret_create_reject.json", Settings::default());
+
+        assert_eq!(request["object"]["data"], json!({"password": "<redacted>"}));
+        assert_eq!(
+            request["object"]["metadata"]["annotations"]["kubectl.kubernetes.io/last-applied-configuration"],
+            json!("<redacted>")
+        );
+        assert_eq!(
+            request["object"]["metadata"]["labels"],
+            json!({"app": "database"})
+        );
+    }
+
+    #[test]
+    fn keep_secret_data() {
+        let request = echoed_request(
+            "test_data/secret_create_reject.json",
+            Settings {
+                redact_secret_data: false,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(request["object"]["data"], json!({"password": "czNjcjN0"}));
+    }
+
+    #[test]
+    fn summary() {
+        let request = echoed_request(
+            "test_data/create_reject.json",
+            Settings {
+                format: Format::Summary,
+                fields: vec![
+                    "object.spec.replicas".to_owned(),
+                    "object.spec.template.spec.containers.*.image".to_owned(),
+                    "object.spec.strategy.rollingUpdate.maxSurge".to_owned(),
+                    "oldObject".to_owned(),
+                ],
+                redacted_fields: vec!["userInfo.groups".to_owned()],
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            request,
+            json!({
+                "uid": "307362f8-ffb1-481a-9ce7-7e6192fbc699",
+                "operation": "CREATE",
+                "kind": {"group": "apps", "kind": "Deployment", "version": "v1"},
+                "resource": {"group": "apps", "resource": "deployments", "version": "v1"},
+                "subResource": null,
+                "namespace": "default",
+                "name": "nginx",
+                "user": {"username": "minikube-user", "groups": "<redacted>"},
+                "dryRun": false,
+                "fields": {
+                    "object.spec.replicas": 0,
+                    "object.spec.template.spec.containers.*.image": ["nginx:latest"],
+                    "object.spec.strategy.rollingUpdate.maxSurge": "25%",
+                },
+            })
+        );
+    }
 }
@@ -1,11 +1,88 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Default, Debug)]
-#[serde(default)]
-pub(crate) struct Settings {}
+/// How the AdmissionRequest is echoed back
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Format {
+    /// The whole AdmissionRequest
+    #[default]
+    Full,
+    /// The operation, the user, the kind and the allowed fields of the
+    /// AdmissionRequest
+    Summary,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    pub format: Format,
+    /// Dot separated paths of the AdmissionRequest fields included in the
+    /// summary, like `object.spec.replicas`
+    pub fields: Vec<String>,
+    /// Redact the data of the Secrets, including the copy kept by the
+    /// last-applied-configuration annotation
+    pub redact_secret_data: bool,
+    /// Dot separated paths of the AdmissionRequest fields whose value is
+    /// redacted. The `*` segment matches all the items of a list or of a map.
+    pub redacted_fields: Vec<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            format: Format::default(),
+            fields: Vec::new(),
+            redact_secret_data: true,
+            redacted_fields: Vec::new(),
+        }
+    }
+}
+
+fn validate_path(path: &str) -> Result<(), String> {
+    if path.split('.').any(str::is_empty) {
+        return Err(format!("invalid field path '{path}'"));
+    }
+    Ok(())
+}
 
 impl kubewarden::settings::Validatable for Settings {
     fn validate(&self) -> Result<(), String> {
-        Ok(())
+        if !self.fields.is_empty() && self.format != Format::Summary {
+            return Err("fields can be set only with the summary format".to_owned());
+        }
+        self.fields
+            .iter()
+            .chain(self.redacted_fields.iter())
+            .try_for_each(|path| validate_path(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "format": "summary",
+            "fields": ["object.metadata.labels", "object.spec.replicas"],
+            "redactSecretData": false,
+            "redactedFields": ["object.spec.template.spec.containers.*.env"]
+        }"#,
+        true
+    )]
+    #[case::fields_with_full_format(r#"{"fields": ["object.spec"]}"#, false)]
+    #[case::unknown_format(r#"{"format": "yaml"}"#, false)]
+    #[case::empty_field(r#"{"format": "summary", "fields": [""]}"#, false)]
+    #[case::empty_segment(r#"{"redactedFields": ["object..data"]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let is_valid_settings = serde_json::from_str::<Settings>(settings)
+            .map_err(|e| e.to_string())
+            .and_then(|settings| settings.validate());
+        assert_eq!(is_valid_settings.is_ok(), is_valid);
     }
 }
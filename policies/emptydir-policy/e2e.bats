@@ -0,0 +1,64 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod with emptyDir volumes within the limits" {
+	run kwctl run \
+		--request-path test_data/pod_within_limits.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Deployment with an emptyDir volume without sizeLimit" {
+	run kwctl run \
+		--request-path test_data/deployment_without_size_limit.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*sizeLimit must be set.*') -ne 0 ]
+}
+
+@test "Accept a memory-backed emptyDir volume in an allowed namespace" {
+	run kwctl run \
+		--request-path test_data/pod_memory_allowed_namespace.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a memory-backed emptyDir volume above the maximum" {
+	run kwctl run \
+		--request-path test_data/pod_memory_above_maximum.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*the sizeLimit 2Gi is above the maximum of 512Mi.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_within_limits.json --settings-json '{"maxSizeLimit": "1Gi", "maxMemorySizeLimit": "2Gi"}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
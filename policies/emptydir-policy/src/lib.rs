@@ -0,0 +1,189 @@
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::{EmptyDirVolumeSource, PodSpec};
+use kubewarden_policy_sdk::wapc_guest as guest;
+use quantity::parse_quantity;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = &validation_request.request;
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        // The resources without Pod spec are not validated
+        Ok(None) => return kubewarden::accept_request(),
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    if let Err(error) =
+        validate_pod_spec(&pod_spec, &request.namespace, &validation_request.settings)
+    {
+        return kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_pod_spec(
+    pod_spec: &PodSpec,
+    namespace: &str,
+    settings: &Settings,
+) -> Result<(), String> {
+    let errors: Vec<String> = pod_spec
+        .volumes
+        .iter()
+        .flatten()
+        .filter_map(|volume| {
+            volume
+                .empty_dir
+                .as_ref()
+                .map(|empty_dir| (&volume.name, empty_dir))
+        })
+        .flat_map(|(name, empty_dir)| {
+            validate_empty_dir(empty_dir, namespace, settings)
+                .into_iter()
+                .map(move |error| format!("emptyDir volume '{name}': {error}"))
+        })
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+// Returns the reasons why the emptyDir volume is not allowed
+fn validate_empty_dir(
+    empty_dir: &EmptyDirVolumeSource,
+    namespace: &str,
+    settings: &Settings,
+) -> Vec<String> {
+    let mut errors = vec![];
+    // The memory-backed emptyDir volumes count against the memory limit of
+    // the containers writing to them
+    let memory_medium = empty_dir.medium.as_deref() == Some("Memory");
+    if memory_medium && !settings.is_memory_medium_allowed(namespace) {
+        errors.push(format!(
+            "the Memory medium is not allowed in the '{namespace}' namespace"
+        ));
+    }
+
+    let max_size_limit = settings.max_size_limit_of(memory_medium);
+    let Some(size_limit) = empty_dir
+        .size_limit
+        .as_ref()
+        .map(|quantity| quantity.0.as_str())
+    else {
+        if settings.require_size_limit || max_size_limit.is_some() {
+            errors.push("sizeLimit must be set".to_owned());
+        }
+        return errors;
+    };
+    // The maximum has been checked when validating the settings
+    if let Some(max_size_limit) = max_size_limit
+        && let Some(max) = parse_quantity(max_size_limit)
+    {
+        match parse_quantity(size_limit) {
+            Some(limit) if limit > max => errors.push(format!(
+                "the sizeLimit {size_limit} is above the maximum of {max_size_limit}"
+            )),
+            Some(_) => {}
+            None => errors.push(format!("cannot parse the sizeLimit '{size_limit}'")),
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        serde_json::from_str(
+            r#"{
+                "maxSizeLimit": "10Gi",
+                "maxMemorySizeLimit": "512Mi",
+                "memoryMediumNamespaces": ["cache"]
+            }"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    #[rstest]
+    #[case::within_limits("test_data/pod_within_limits.json", true)]
+    #[case::without_size_limit("test_data/deployment_without_size_limit.json", false)]
+    #[case::memory_in_allowed_namespace("test_data/pod_memory_allowed_namespace.json", true)]
+    #[case::memory_above_maximum("test_data/pod_memory_above_maximum.json", false)]
+    #[case::without_empty_dir("test_data/pod_without_empty_dir.json", true)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::without_size_limit(
+        "test_data/deployment_without_size_limit.json",
+        "Deployment 'web': emptyDir volume 'scratch': sizeLimit must be set"
+    )]
+    #[case::memory_above_maximum(
+        "test_data/pod_memory_above_maximum.json",
+        "Pod 'web': emptyDir volume 'shm': the Memory medium is not allowed in the 'team-a' namespace; emptyDir volume 'shm': the sizeLimit 2Gi is above the maximum of 512Mi; emptyDir volume 'scratch': the sizeLimit 20Gi is above the maximum of 10Gi"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn size_limit_not_required() {
+        let test_case = Testcase {
+            name: "sizeLimit not required".to_owned(),
+            fixture_file: "test_data/deployment_without_size_limit.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings {
+                require_size_limit: false,
+                ..Default::default()
+            },
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+
+use quantity::parse_quantity;
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Reject the emptyDir volumes without `sizeLimit`
+    pub require_size_limit: bool,
+    /// Maximum `sizeLimit` of the emptyDir volumes, as a quantity like `1Gi`
+    pub max_size_limit: Option<String>,
+    /// Maximum `sizeLimit` of the emptyDir volumes backed by memory. It
+    /// replaces `maxSizeLimit` for these volumes.
+    pub max_memory_size_limit: Option<String>,
+    /// Namespaces where the emptyDir volumes can be backed by memory. When
+    /// not set, they are allowed in all the namespaces.
+    pub memory_medium_namespaces: Option<HashSet<String>>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            require_size_limit: true,
+            max_size_limit: None,
+            max_memory_size_limit: None,
+            memory_medium_namespaces: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the maximum `sizeLimit` of the emptyDir volumes of the medium
+    pub fn max_size_limit_of(&self, memory_medium: bool) -> Option<&str> {
+        if memory_medium {
+            self.max_memory_size_limit
+                .as_deref()
+                .or(self.max_size_limit.as_deref())
+        } else {
+            self.max_size_limit.as_deref()
+        }
+    }
+
+    /// Returns true when the emptyDir volumes of the namespace can be backed
+    /// by memory
+    pub fn is_memory_medium_allowed(&self, namespace: &str) -> bool {
+        self.memory_medium_namespaces
+            .as_ref()
+            .is_none_or(|namespaces| namespaces.contains(namespace))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        let mut limits = vec![];
+        for (field, value) in [
+            ("maxSizeLimit", &self.max_size_limit),
+            ("maxMemorySizeLimit", &self.max_memory_size_limit),
+        ] {
+            if let Some(value) = value {
+                let limit = parse_quantity(value)
+                    .filter(|limit| *limit >= 0.0)
+                    .ok_or_else(|| format!("{field}: invalid quantity '{value}'"))?;
+                limits.push(limit);
+            }
+        }
+        if let [max, max_memory] = limits[..]
+            && max_memory > max
+        {
+            return Err("maxMemorySizeLimit cannot be above maxSizeLimit".to_owned());
+        }
+        if self
+            .memory_medium_namespaces
+            .as_ref()
+            .is_some_and(|namespaces| namespaces.contains(""))
+        {
+            return Err("memoryMediumNamespaces cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "requireSizeLimit": false,
+            "maxSizeLimit": "10Gi",
+            "maxMemorySizeLimit": "512Mi",
+            "memoryMediumNamespaces": ["cache"]
+        }"#,
+        true
+    )]
+    #[case::invalid_quantity(r#"{"maxSizeLimit": "10 GB"}"#, false)]
+    #[case::negative_quantity(r#"{"maxSizeLimit": "-10Gi"}"#, false)]
+    #[case::memory_limit_above_limit(
+        r#"{"maxSizeLimit": "1Gi", "maxMemorySizeLimit": "2Gi"}"#,
+        false
+    )]
+    #[case::memory_limit_only(r#"{"maxMemorySizeLimit": "2Gi"}"#, true)]
+    #[case::empty_namespace(r#"{"memoryMediumNamespaces": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::disk(
+        r#"{"maxSizeLimit": "10Gi", "maxMemorySizeLimit": "512Mi"}"#,
+        false,
+        Some("10Gi")
+    )]
+    #[case::memory(
+        r#"{"maxSizeLimit": "10Gi", "maxMemorySizeLimit": "512Mi"}"#,
+        true,
+        Some("512Mi")
+    )]
+    #[case::memory_without_memory_limit(r#"{"maxSizeLimit": "10Gi"}"#, true, Some("10Gi"))]
+    #[case::disk_without_limit(r#"{"maxMemorySizeLimit": "512Mi"}"#, false, None)]
+    fn max_size_limit(
+        #[case] settings: &str,
+        #[case] memory_medium: bool,
+        #[case] expected: Option<&str>,
+    ) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.max_size_limit_of(memory_medium), expected);
+    }
+
+    #[rstest]
+    #[case::all_namespaces(r#"{}"#, true)]
+    #[case::allowed_namespace(r#"{"memoryMediumNamespaces": ["team-a"]}"#, true)]
+    #[case::other_namespace(r#"{"memoryMediumNamespaces": ["cache"]}"#, false)]
+    #[case::no_namespace(r#"{"memoryMediumNamespaces": []}"#, false)]
+    fn memory_medium_allowed(#[case] settings: &str, #[case] expected: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.is_memory_medium_allowed("team-a"), expected);
+    }
+}
@@ -0,0 +1,78 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod disabling the service links" {
+	run kwctl run \
+		--request-path test_data/pod_service_links_disabled.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patch".*') -eq 0 ]
+}
+
+@test "Reject a Pod not setting enableServiceLinks" {
+	run kwctl run \
+		--request-path test_data/pod_service_links_unset.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Pod 'web' must set enableServiceLinks to false.*") -ne 0 ]
+}
+
+@test "Mutate a Pod not setting enableServiceLinks" {
+	run kwctl run \
+		--request-path test_data/pod_service_links_unset.json \
+		--settings-json '{"namespaces": ["team-a"]}' \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+}
+
+@test "Reject a Deployment enabling the service links" {
+	run kwctl run \
+		--request-path test_data/deployment_service_links_enabled.json \
+		--settings-json '{"namespaces": ["team-a"]}' \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Accept a Pod in another namespace" {
+	run kwctl run \
+		--request-path test_data/pod_other_namespace.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_service_links_disabled.json --settings-json '{"namespaces": [""]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
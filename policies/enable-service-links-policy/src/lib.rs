@@ -0,0 +1,176 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use serde::{Deserialize, Serialize};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+/// The only field of the Pod spec used by the policy
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    enable_service_links: Option<bool>,
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let settings = &validation_request.settings;
+    let request = &validation_request.request;
+
+    if !settings.applies_to(&request.namespace) {
+        return kubewarden::accept_request();
+    }
+    let mut pod_spec =
+        match partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object) {
+            Ok(Some(pod_spec)) => pod_spec,
+            // The resources without Pod spec are not validated
+            Ok(None) => return kubewarden::accept_request(),
+            Err(_) => {
+                return kubewarden::reject_request(
+                    Some("Cannot parse validation request".to_owned()),
+                    None,
+                    None,
+                    None,
+                );
+            }
+        };
+
+    // Kubernetes enables the service links when `enableServiceLinks` is not
+    // set. The API server defaults it to `true` before calling the admission
+    // webhooks, hence the mutation rewrites `true` as well.
+    match pod_spec.enable_service_links {
+        Some(false) => kubewarden::accept_request(),
+        _ if settings.mutate => {
+            pod_spec.enable_service_links = Some(false);
+            kubewarden::mutate_request(partial_pod_spec::patch(
+                &request.kind.kind,
+                &request.object,
+                &pod_spec,
+            )?)
+        }
+        _ => kubewarden::reject_request(
+            Some(format!(
+                "{} '{}' must set enableServiceLinks to false",
+                request.kind.kind, request.name
+            )),
+            None,
+            None,
+            None,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn settings(mutate: bool) -> Settings {
+        Settings {
+            namespaces: ["team-a".to_owned()].into(),
+            mutate,
+        }
+    }
+
+    #[rstest]
+    #[case::service_links_disabled("test_data/pod_service_links_disabled.json", true, true)]
+    #[case::service_links_unset("test_data/pod_service_links_unset.json", false, false)]
+    #[case::service_links_enabled("test_data/deployment_service_links_enabled.json", false, false)]
+    #[case::other_namespace("test_data/pod_other_namespace.json", false, true)]
+    #[case::without_pod_spec("test_data/configmap.json", false, true)]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] mutate: bool,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(mutate),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert!(response.mutated_object.is_none());
+    }
+
+    #[rstest]
+    #[case::service_links_unset(
+        "test_data/pod_service_links_unset.json",
+        "Pod 'web' must set enableServiceLinks to false"
+    )]
+    #[case::service_links_enabled(
+        "test_data/deployment_service_links_enabled.json",
+        "Deployment 'web' must set enableServiceLinks to false"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(false),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn mutate_pod() {
+        let test_case = Testcase {
+            name: "mutate pod".to_owned(),
+            fixture_file: "test_data/pod_service_links_unset.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response.mutated_object.expect("the pod should be mutated");
+        assert_eq!(mutated_object["spec"]["enableServiceLinks"], json!(false));
+    }
+
+    #[test]
+    fn mutate_defaulted_deployment() {
+        let test_case = Testcase {
+            name: "mutate deployment".to_owned(),
+            fixture_file: "test_data/deployment_service_links_enabled.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response
+            .mutated_object
+            .expect("the deployment should be mutated");
+        assert_eq!(
+            mutated_object["spec"]["template"]["spec"]["enableServiceLinks"],
+            json!(false)
+        );
+    }
+
+    #[test]
+    fn all_namespaces() {
+        let test_case = Testcase {
+            name: "all namespaces".to_owned(),
+            fixture_file: "test_data/pod_other_namespace.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert!(response.mutated_object.is_some());
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Namespaces where the Pods must disable the service links. The policy
+    /// applies to all the namespaces when empty.
+    pub namespaces: BTreeSet<String>,
+    /// Set `enableServiceLinks` to false when it is not already false, instead of
+    /// rejecting the request
+    pub mutate: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            namespaces: BTreeSet::new(),
+            mutate: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the Pods of the namespace must disable the service
+    /// links
+    pub fn applies_to(&self, namespace: &str) -> bool {
+        self.namespaces.is_empty() || self.namespaces.contains(namespace)
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.namespaces.contains("") {
+            return Err("namespaces cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::namespaces(r#"{"namespaces": ["team-a", "team-b"], "mutate": false}"#, true)]
+    #[case::empty_namespace(r#"{"namespaces": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::all_namespaces(vec![], "default", true)]
+    #[case::listed_namespace(vec!["team-a"], "team-a", true)]
+    #[case::other_namespace(vec!["team-a"], "default", false)]
+    fn applies_to(#[case] namespaces: Vec<&str>, #[case] namespace: &str, #[case] expected: bool) {
+        let settings = Settings {
+            namespaces: namespaces.into_iter().map(str::to_owned).collect(),
+            ..Default::default()
+        };
+        assert_eq!(settings.applies_to(namespace), expected);
+    }
+}
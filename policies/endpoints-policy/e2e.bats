@@ -0,0 +1,51 @@
+#!/usr/bin/env bats
+
+@test "Reject Endpoints pointing to the metadata service" {
+	run kwctl run --request-path test_data/endpoints_metadata.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*restricted addresses: 169.254.169.254.*') -ne 0 ]
+}
+
+@test "Accept Endpoints created by the endpoint controller" {
+	run kwctl run --request-path test_data/endpoints_controller.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept an EndpointSlice pointing to an external address" {
+	run kwctl run --request-path test_data/endpointslice_external.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject an EndpointSlice pointing to the service CIDR" {
+	run kwctl run --request-path test_data/endpointslice_service_cidr.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/endpointslice_external.json --settings-json '{"deniedCidrs": ["10.96.0.0"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
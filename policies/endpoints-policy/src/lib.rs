@@ -0,0 +1,182 @@
+use std::net::IpAddr;
+
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::{core::v1::Endpoints, discovery::v1::EndpointSlice};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+use cidr::Cidr;
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let settings = &validation_request.settings;
+    let user_info = &validation_request.request.user_info;
+
+    let username = user_info.username.clone().unwrap_or_default();
+    let groups = user_info.groups.clone().unwrap_or_default();
+    if settings.allowed_users.contains(&username)
+        || groups
+            .iter()
+            .any(|group| settings.allowed_groups.contains(group))
+    {
+        return kubewarden::accept_request();
+    }
+
+    let addresses = match validation_request.request.kind.kind.as_str() {
+        "Endpoints" => endpoints_addresses(&serde_json::from_value::<Endpoints>(
+            validation_request.request.object,
+        )?),
+        "EndpointSlice" => endpoint_slice_addresses(&serde_json::from_value::<EndpointSlice>(
+            validation_request.request.object,
+        )?),
+        _ => return kubewarden::accept_request(),
+    };
+
+    let denied = denied_addresses(&addresses, &settings.denied_networks());
+    if !denied.is_empty() {
+        return kubewarden::reject_request(
+            Some(format!(
+                "user '{username}' cannot create endpoints pointing to the restricted addresses: {}",
+                denied.join(", ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn endpoints_addresses(endpoints: &Endpoints) -> Vec<String> {
+    endpoints
+        .subsets
+        .iter()
+        .flatten()
+        .flat_map(|subset| {
+            subset
+                .addresses
+                .iter()
+                .chain(subset.not_ready_addresses.iter())
+                .flatten()
+        })
+        .map(|address| address.ip.clone())
+        .collect()
+}
+
+fn endpoint_slice_addresses(endpoint_slice: &EndpointSlice) -> Vec<String> {
+    // FQDN endpoints cannot be checked against the CIDR ranges
+    if endpoint_slice.address_type == "FQDN" {
+        return vec![];
+    }
+    endpoint_slice
+        .endpoints
+        .iter()
+        .flat_map(|endpoint| endpoint.addresses.iter().cloned())
+        .collect()
+}
+
+// Returns the addresses contained in any of the denied networks. Values that
+// are not IP addresses are rejected by the API server, hence they are ignored
+// here.
+fn denied_addresses(addresses: &[String], denied_networks: &[Cidr]) -> Vec<String> {
+    let mut denied: Vec<String> = addresses
+        .iter()
+        .filter(|address| {
+            address.parse::<IpAddr>().is_ok_and(|ip| {
+                let address = Cidr::from(normalize(ip));
+                denied_networks
+                    .iter()
+                    .any(|network| network.contains(&address))
+            })
+        })
+        .cloned()
+        .collect();
+    denied.sort();
+    denied.dedup();
+    denied
+}
+
+// IPv4-mapped IPv6 addresses, like `::ffff:169.254.169.254`, reach the IPv4
+// address they embed: they are checked against the IPv4 networks.
+fn normalize(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ipv6) => ipv6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        IpAddr::V4(_) => ip,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            denied_cidrs: vec!["10.96.0.0/12".to_owned(), "10.244.0.0/16".to_owned()],
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::external_address(vec!["192.168.1.10"], vec![])]
+    #[case::service_cidr(vec!["192.168.1.10", "10.96.0.10"], vec!["10.96.0.10"])]
+    #[case::pod_cidr(vec!["10.244.3.4"], vec!["10.244.3.4"])]
+    #[case::metadata_service(vec!["169.254.169.254"], vec!["169.254.169.254"])]
+    #[case::ipv4_mapped_metadata_service(
+        vec!["::ffff:169.254.169.254"],
+        vec!["::ffff:169.254.169.254"]
+    )]
+    #[case::ipv4_mapped_service_cidr(vec!["::ffff:10.96.0.10"], vec!["::ffff:10.96.0.10"])]
+    #[case::link_local_ipv6(vec!["fe80::1"], vec!["fe80::1"])]
+    #[case::duplicated_address(vec!["10.96.0.10", "10.96.0.10"], vec!["10.96.0.10"])]
+    #[case::not_an_address(vec!["example.com"], vec![])]
+    fn restricted_addresses(#[case] addresses: Vec<&str>, #[case] expected: Vec<&str>) {
+        let addresses: Vec<String> = addresses.into_iter().map(String::from).collect();
+        assert_eq!(
+            denied_addresses(&addresses, &settings().denied_networks()),
+            expected
+        );
+    }
+
+    #[test]
+    fn fqdn_endpoint_slice() {
+        let endpoint_slice: EndpointSlice = serde_json::from_value(serde_json::json!({
+            "metadata": {"name": "example"},
+            "addressType": "FQDN",
+            "endpoints": [{"addresses": ["10.96.0.10"]}],
+        }))
+        .expect("cannot parse EndpointSlice");
+        assert!(endpoint_slice_addresses(&endpoint_slice).is_empty());
+    }
+
+    #[rstest]
+    #[case::endpoints_with_metadata_address("test_data/endpoints_metadata.json", false)]
+    #[case::endpoints_from_controller("test_data/endpoints_controller.json", true)]
+    #[case::endpoint_slice_with_external_address("test_data/endpointslice_external.json", true)]
+    #[case::endpoint_slice_with_service_address("test_data/endpointslice_service_cidr.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
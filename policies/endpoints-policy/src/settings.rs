@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use cidr::Cidr;
+
+const LINK_LOCAL_CIDRS: [&str; 2] = ["169.254.0.0/16", "fe80::/10"];
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// CIDR ranges endpoints cannot point to. These are usually the service
+    /// and the pod CIDRs of the cluster.
+    pub denied_cidrs: Vec<String>,
+    /// Reject endpoints pointing to link-local addresses, like the ones used
+    /// by the cloud providers metadata services.
+    pub deny_link_local: bool,
+    /// Users allowed to create and update any endpoint.
+    pub allowed_users: HashSet<String>,
+    /// Groups allowed to create and update any endpoint.
+    pub allowed_groups: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            denied_cidrs: Vec::new(),
+            deny_link_local: true,
+            allowed_users: HashSet::from([
+                "system:kube-controller-manager".to_owned(),
+                "system:serviceaccount:kube-system:endpoint-controller".to_owned(),
+                "system:serviceaccount:kube-system:endpointslice-controller".to_owned(),
+                "system:serviceaccount:kube-system:endpointslicemirroring-controller".to_owned(),
+            ]),
+            allowed_groups: HashSet::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the CIDR ranges endpoints cannot point to. The values are
+    /// checked during settings validation, hence invalid entries are skipped
+    /// here.
+    pub fn denied_networks(&self) -> Vec<Cidr> {
+        let link_local = if self.deny_link_local {
+            LINK_LOCAL_CIDRS.as_slice()
+        } else {
+            &[]
+        };
+        self.denied_cidrs
+            .iter()
+            .map(String::as_str)
+            .chain(link_local.iter().copied())
+            .filter_map(|cidr| cidr.parse::<Cidr>().ok())
+            .collect()
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        let invalid: Vec<String> = self
+            .denied_cidrs
+            .iter()
+            .filter(|cidr| cidr.parse::<Cidr>().is_err())
+            .cloned()
+            .collect();
+        if !invalid.is_empty() {
+            return Err(format!(
+                "deniedCidrs contains invalid CIDRs: {}",
+                invalid.join(", ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(vec![], true)]
+    #[case::valid_cidrs(vec!["10.96.0.0/12", "fd00::/108"], true)]
+    #[case::invalid_cidr(vec!["10.96.0.0/12", "10.244.0.0"], false)]
+    fn validate_settings(#[case] denied_cidrs: Vec<&str>, #[case] is_valid: bool) {
+        let settings = Settings {
+            denied_cidrs: denied_cidrs.into_iter().map(String::from).collect(),
+            ..Default::default()
+        };
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::with_link_local(true, 3)]
+    #[case::without_link_local(false, 1)]
+    fn denied_networks(#[case] deny_link_local: bool, #[case] expected: usize) {
+        let settings = Settings {
+            denied_cidrs: vec!["10.96.0.0/12".to_owned()],
+            deny_link_local,
+            ..Default::default()
+        };
+        assert_eq!(settings.denied_networks().len(), expected);
+    }
+}
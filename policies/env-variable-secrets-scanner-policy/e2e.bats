@@ -35,3 +35,26 @@
 	[ $(expr "$output" : '.*container: busybox, key: email, reason: Email address.*') -ne 0 ]
 	[ $(expr "$output" : '.*container: nginx, key: rsa, reason: RSA private key.*') -ne 0 ]
  }
+
+@test "Reject pod with secrets found by custom rules" {
+	run kwctl run  --request-path test_data/pod_creation_with_secrets.json --settings-json '{"customRules": {"Example domain": "@example[.]com"}}' annotated-policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*container: nginx, key: email, reason: Example domain.*') -ne 0 ]
+ }
+
+@test "Accept pod with secrets in allowed variables" {
+	run kwctl run  --request-path test_data/pod_creation_with_secrets.json --settings-json '{"allowedVariables": ["email", "rsa"]}' annotated-policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+ }
+
+@test "Report pod with secrets" {
+	run kwctl run  --request-path test_data/pod_creation_with_secrets.json --settings-json '{"mode": "report"}' annotated-policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*environment variable .email. of container .nginx., rule: Email address.*') -ne 0 ]
+ }
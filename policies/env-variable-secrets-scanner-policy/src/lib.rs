@@ -4,12 +4,19 @@ extern crate kubewarden_policy_sdk as kubewarden;
 
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STD_ENGINE};
 use k8s_openapi::api::core::v1::{EnvVar, PodSpec};
-use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+use kubewarden::{
+    protocol_version_guest, request::ValidationRequest, response::ValidationResponse,
+    validate_settings,
+};
+use regex::bytes::Regex;
 use rusty_hog_scanner::{SecretScanner, SecretScannerBuilder};
-use std::{collections::HashSet, fmt, string::String};
+use std::{collections::BTreeSet, fmt, string::String};
 
 mod settings;
-use settings::Settings;
+use settings::{BundledRules, Mode, Settings};
+
+/// Reason reported for the values whose entropy is above the configured threshold
+const HIGH_ENTROPY_REASON: &str = "High entropy string";
 
 #[unsafe(no_mangle)]
 pub extern "C" fn wapc_init() {
@@ -19,7 +26,7 @@ pub extern "C" fn wapc_init() {
 }
 
 /// Represents a secret that has been found in an env var
-#[derive(Eq, Hash, PartialEq, Debug)]
+#[derive(Eq, Hash, PartialEq, Ord, PartialOrd, Debug)]
 struct EnvVarFinding {
     /// name of the container where the secret was found
     container: String,
@@ -39,13 +46,33 @@ impl fmt::Display for EnvVarFinding {
     }
 }
 
+/// Rules used to look for secrets inside of the env vars
+struct Scanner<'a> {
+    /// built-in rules of rusty hog
+    secret_scanner: SecretScanner,
+    /// rules provided by the user, with their names
+    custom_rules: Vec<(String, Regex)>,
+    settings: &'a Settings,
+}
+
 fn validate(payload: &[u8]) -> CallResult {
     let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
 
     match validation_request.extract_pod_spec_from_object() {
         Ok(pod_spec) => {
             if let Some(pod_spec) = pod_spec {
-                return validate_pod_spec(pod_spec);
+                let settings = match with_bundled_rules(validation_request.settings) {
+                    Ok(settings) => settings,
+                    Err(e) => {
+                        return kubewarden::reject_request(
+                            Some(format!("Cannot load the rule bundle: {e}")),
+                            None,
+                            None,
+                            None,
+                        );
+                    }
+                };
+                return validate_pod_spec(pod_spec, &settings);
             }
             // If there is no pod spec, just accept it. There is no data to be validated.
             kubewarden::accept_request()
@@ -59,16 +86,30 @@ fn validate(payload: &[u8]) -> CallResult {
     }
 }
 
+/// Adds the rules of the rule bundle, if any, to the custom rules of the
+/// settings. The bundle is fetched only when there is a Pod spec to scan.
+fn with_bundled_rules(settings: Settings) -> Result<Settings, String> {
+    let Some(rule_bundle) = &settings.rule_bundle else {
+        return Ok(settings);
+    };
+    let bundled_rules: BundledRules = rule_bundle.fetch().map_err(|e| e.to_string())?;
+    Ok(settings.with_bundled_rules(bundled_rules))
+}
+
 /// Scan all containers in containers, init_containers and ephemeral_containers.
 /// Returns reject_request if any secret var was found in an env_var in any container or
-/// accept_request otherwise.
-fn validate_pod_spec(pod_spec: PodSpec) -> CallResult {
-    let mut findings: HashSet<EnvVarFinding> = HashSet::new();
-    let secret_scanner = SecretScannerBuilder::new().build();
+/// accept_request otherwise. In report mode, the request is accepted with a
+/// warning for each secret found.
+fn validate_pod_spec(pod_spec: PodSpec, settings: &Settings) -> CallResult {
+    let mut findings: BTreeSet<EnvVarFinding> = BTreeSet::new();
+    let scanner = Scanner {
+        secret_scanner: SecretScannerBuilder::new().build(),
+        custom_rules: settings.custom_rules()?,
+        settings,
+    };
 
     for container in pod_spec.containers {
-        if let Some(secrets_in_container) =
-            scan_env_vars(container.env, &secret_scanner, &container.name)
+        if let Some(secrets_in_container) = scan_env_vars(container.env, &scanner, &container.name)
         {
             findings.extend(secrets_in_container);
         }
@@ -77,7 +118,7 @@ fn validate_pod_spec(pod_spec: PodSpec) -> CallResult {
     if let Some(init_containers) = pod_spec.init_containers {
         for container in init_containers {
             if let Some(secrets_in_container) =
-                scan_env_vars(container.env, &secret_scanner, &container.name)
+                scan_env_vars(container.env, &scanner, &container.name)
             {
                 findings.extend(secrets_in_container);
             }
@@ -87,15 +128,19 @@ fn validate_pod_spec(pod_spec: PodSpec) -> CallResult {
     if let Some(ephemeral_containers) = pod_spec.ephemeral_containers {
         for container in ephemeral_containers {
             if let Some(secrets_in_container) =
-                scan_env_vars(container.env, &secret_scanner, &container.name)
+                scan_env_vars(container.env, &scanner, &container.name)
             {
                 findings.extend(secrets_in_container);
             }
         }
     }
 
-    if !findings.is_empty() {
-        return kubewarden::reject_request(
+    if findings.is_empty() {
+        return kubewarden::accept_request();
+    }
+
+    match settings.mode {
+        Mode::Enforce => kubewarden::reject_request(
             Some(format!(
                 "The following secrets were found in environment variables -> {}",
                 create_error_message(findings)
@@ -103,25 +148,34 @@ fn validate_pod_spec(pod_spec: PodSpec) -> CallResult {
             None,
             None,
             None,
-        );
+        ),
+        Mode::Report => Ok(serde_json::to_vec(&ValidationResponse {
+            accepted: true,
+            message: None,
+            code: None,
+            mutated_object: None,
+            audit_annotations: None,
+            warnings: Some(create_warnings(findings)),
+        })?),
     }
-
-    kubewarden::accept_request()
 }
 
 fn scan_env_vars(
     env_vars: Option<Vec<EnvVar>>,
-    secret_scanner: &SecretScanner,
+    scanner: &Scanner,
     container_name: &str,
-) -> Option<HashSet<EnvVarFinding>> {
-    let mut findings: HashSet<EnvVarFinding> = HashSet::new();
+) -> Option<BTreeSet<EnvVarFinding>> {
+    let mut findings: BTreeSet<EnvVarFinding> = BTreeSet::new();
 
     if let Some(env_vars) = env_vars {
         for env_var in env_vars {
+            if scanner.settings.allowed_variables.contains(&env_var.name) {
+                continue;
+            }
             if let Some(value) = env_var.value {
                 findings.extend(scan_env_var(
                     value.as_bytes().to_vec(),
-                    secret_scanner,
+                    scanner,
                     env_var.name.as_str(),
                     container_name,
                 ));
@@ -138,17 +192,17 @@ fn scan_env_vars(
 
 fn scan_env_var(
     input: Vec<u8>,
-    secret_scanner: &SecretScanner,
+    scanner: &Scanner,
     key: &str,
     container: &str,
-) -> HashSet<EnvVarFinding> {
-    let mut findings = scan_text(&input, secret_scanner, key, container);
+) -> BTreeSet<EnvVarFinding> {
+    let mut findings = scan_text(&input, scanner, key, container);
 
     // try decoding content from base64 if no secret was found
     if findings.is_empty() {
         let input = BASE64_STD_ENGINE.decode(input);
         if let Ok(input) = input {
-            findings = scan_text(&input, secret_scanner, key, container);
+            findings = scan_text(&input, scanner, key, container);
         }
     }
 
@@ -157,30 +211,66 @@ fn scan_env_var(
 
 fn scan_text(
     input: &[u8],
-    secret_scanner: &SecretScanner,
+    scanner: &Scanner,
     key: &str,
     container: &str,
-) -> HashSet<EnvVarFinding> {
-    let mut findings: HashSet<EnvVarFinding> = HashSet::new();
+) -> BTreeSet<EnvVarFinding> {
+    let mut reasons: BTreeSet<String> = BTreeSet::new();
     let lines = input.split(|&x| (x as char) == '\n');
 
     for new_line in lines.into_iter() {
-        let results = secret_scanner.matches(new_line);
+        let results = scanner.secret_scanner.matches(new_line);
         for (reason, matches) in results {
             for _ in matches {
-                findings.insert(EnvVarFinding {
-                    reason: reason.to_string(),
-                    key: key.to_string(),
-                    container: container.to_string(),
-                });
+                reasons.insert(reason.to_string());
+            }
+        }
+
+        for (name, regex) in &scanner.custom_rules {
+            if regex.is_match(new_line) {
+                reasons.insert(name.clone());
             }
         }
+
+        if let Some(threshold) = scanner.settings.entropy_threshold
+            && new_line
+                .split(|x| x.is_ascii_whitespace())
+                .filter(|word| word.len() >= scanner.settings.entropy_min_length)
+                .any(|word| shannon_entropy(word) >= threshold)
+        {
+            reasons.insert(HIGH_ENTROPY_REASON.to_string());
+        }
     }
 
-    findings
+    reasons
+        .into_iter()
+        .map(|reason| EnvVarFinding {
+            reason,
+            key: key.to_string(),
+            container: container.to_string(),
+        })
+        .collect()
+}
+
+/// Shannon entropy of the input, in bits per byte
+fn shannon_entropy(input: &[u8]) -> f64 {
+    let mut occurrences = [0usize; 256];
+    for &byte in input {
+        occurrences[byte as usize] += 1;
+    }
+
+    let length = input.len() as f64;
+    occurrences
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let probability = count as f64 / length;
+            -probability * probability.log2()
+        })
+        .sum()
 }
 
-fn create_error_message(secrets: HashSet<EnvVarFinding>) -> String {
+fn create_error_message(secrets: BTreeSet<EnvVarFinding>) -> String {
     let mut message = String::new();
     for secret in secrets {
         message.push_str(secret.to_string().as_str())
@@ -189,11 +279,32 @@ fn create_error_message(secrets: HashSet<EnvVarFinding>) -> String {
     message
 }
 
+fn create_warnings(secrets: BTreeSet<EnvVarFinding>) -> Vec<String> {
+    secrets
+        .into_iter()
+        .map(|secret| {
+            format!(
+                "secret found in environment variable '{}' of container '{}', rule: {}",
+                secret.key, secret.container, secret.reason
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn scanner(settings: &Settings) -> Scanner<'_> {
+        Scanner {
+            secret_scanner: SecretScannerBuilder::new().build(),
+            custom_rules: settings.custom_rules().unwrap(),
+            settings,
+        }
+    }
 
     #[test]
     fn reject_pod_with_secrets() -> Result<(), ()> {
@@ -202,7 +313,7 @@ mod tests {
             name: String::from("pod with secrets"),
             fixture_file: String::from(request_file),
             expected_validation_result: false,
-            settings: Settings {},
+            settings: Settings::default(),
         };
 
         let res = tc.eval(validate).unwrap();
@@ -241,7 +352,7 @@ mod tests {
             name: String::from("pod with secrets"),
             fixture_file: String::from(request_file),
             expected_validation_result: false,
-            settings: Settings {},
+            settings: Settings::default(),
         };
 
         let res = tc.eval(validate).unwrap();
@@ -274,7 +385,7 @@ mod tests {
             name: String::from("pod with secrets in init and ephemeral containers"),
             fixture_file: String::from(request_file),
             expected_validation_result: false,
-            settings: Settings {},
+            settings: Settings::default(),
         };
 
         let res = tc.eval(validate).unwrap();
@@ -313,7 +424,7 @@ mod tests {
             name: String::from("pod without secrets"),
             fixture_file: String::from(request_file),
             expected_validation_result: true,
-            settings: Settings {},
+            settings: Settings::default(),
         };
 
         let res = tc.eval(validate).unwrap();
@@ -325,4 +436,100 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn allowed_variables_are_not_scanned() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_with_secrets.json";
+        let tc = Testcase {
+            name: String::from("pod with secrets in allowed variables"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: false,
+            settings: Settings {
+                allowed_variables: ["email".to_owned()].into(),
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        let message = res.message.unwrap_or_default();
+        assert!(message.contains("container: nginx, key: rsa, reason: RSA private key"));
+        assert!(!message.contains("key: email"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn report_pod_with_secrets() -> Result<(), ()> {
+        let request_file = "test_data/pod_creation_with_secrets.json";
+        let tc = Testcase {
+            name: String::from("pod with secrets in report mode"),
+            fixture_file: String::from(request_file),
+            expected_validation_result: true,
+            settings: Settings {
+                mode: Mode::Report,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        let warnings = res.warnings.unwrap_or_default();
+        assert!(warnings.contains(
+            &"secret found in environment variable 'email' of container 'nginx', rule: Email address"
+                .to_owned()
+        ));
+        assert!(warnings.contains(
+            &"secret found in environment variable 'rsa' of container 'nginx', rule: RSA private key"
+                .to_owned()
+        ));
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case::custom_rule("acme_0123456789abcdef0123456789abcdef", None, vec!["Acme API key"])]
+    #[case::custom_rule_base64(
+        "YWNtZV8wMTIzNDU2Nzg5YWJjZGVmMDEyMzQ1Njc4OWFiY2RlZg==",
+        None,
+        vec!["Acme API key"]
+    )]
+    #[case::high_entropy(
+        "Bearer 8fK2pQz7Lm4Xv9Rt1Yw6Nb3Hc5Jd0Gs",
+        Some(4.0),
+        vec![HIGH_ENTROPY_REASON]
+    )]
+    #[case::entropy_not_checked("Bearer 8fK2pQz7Lm4Xv9Rt1Yw6Nb3Hc5Jd0Gs", None, vec![])]
+    #[case::low_entropy("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", Some(4.0), vec![])]
+    #[case::short_value("8fK2pQz7Lm4X", Some(3.0), vec![])]
+    #[case::no_secret("info", Some(1.0), vec![])]
+    fn scan_custom_rules_and_entropy(
+        #[case] value: &str,
+        #[case] entropy_threshold: Option<f64>,
+        #[case] expected: Vec<&str>,
+    ) {
+        let settings = Settings {
+            custom_rules: [("Acme API key".to_owned(), "acme_[a-f0-9]{32}".to_owned())].into(),
+            entropy_threshold,
+            ..Default::default()
+        };
+        let findings = scan_env_var(
+            value.as_bytes().to_vec(),
+            &scanner(&settings),
+            "TOKEN",
+            "app",
+        );
+        let reasons: Vec<&str> = findings
+            .iter()
+            .map(|finding| finding.reason.as_str())
+            .collect();
+        assert_eq!(reasons, expected);
+    }
+
+    #[rstest]
+    #[case::empty(b"", 0.0)]
+    #[case::single_symbol(b"aaaa", 0.0)]
+    #[case::two_symbols(b"abab", 1.0)]
+    #[case::four_symbols(b"abcd", 2.0)]
+    fn entropy(#[case] input: &[u8], #[case] expected: f64) {
+        assert!((shannon_entropy(input) - expected).abs() < f64::EPSILON);
+    }
 }
@@ -1,13 +1,122 @@
+use std::collections::{BTreeMap, HashSet};
+
+use regex::bytes::Regex;
+use rule_bundle::RuleBundle;
 use serde::{Deserialize, Serialize};
 
+/// What to do when secrets are found inside of environment variables
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Mode {
+    /// Reject the request
+    #[default]
+    Enforce,
+    /// Accept the request, and return a warning for each variable holding a
+    /// secret, reporting the rule that found it
+    Report,
+}
+
 // Describe the settings your policy expects when
 // loaded by the policy server.
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Additional rules used to find secrets. The keys are the names of the
+    /// rules, reported when they match, the values are regular expressions.
+    pub custom_rules: BTreeMap<String, String>,
+    /// Minimum Shannon entropy, in bits per character, of the values
+    /// considered secrets. Entropy is not checked when unset.
+    pub entropy_threshold: Option<f64>,
+    /// Minimum length of the values checked against the entropy threshold
+    pub entropy_min_length: usize,
+    /// Names of the environment variables that are not scanned
+    pub allowed_variables: HashSet<String>,
+    pub mode: Mode,
+    /// Signed OCI artifact distributing more rules, fetched when a request
+    /// is evaluated
+    pub rule_bundle: Option<RuleBundle>,
+}
+
+/// Rules distributed with the rule bundle, named like the custom ones
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(default)]
-pub(crate) struct Settings {}
+pub(crate) struct BundledRules {
+    pub rules: BTreeMap<String, String>,
+}
+
+impl kubewarden::settings::Validatable for BundledRules {
+    fn validate(&self) -> Result<(), String> {
+        for (name, pattern) in &self.rules {
+            if name.is_empty() {
+                return Err("the bundled rules must have a name".to_owned());
+            }
+            Regex::new(pattern)
+                .map_err(|e| format!("invalid regular expression of bundled rule '{name}': {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            custom_rules: BTreeMap::new(),
+            entropy_threshold: None,
+            entropy_min_length: 20,
+            allowed_variables: HashSet::new(),
+            mode: Mode::default(),
+            rule_bundle: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Compiles the custom rules
+    pub fn custom_rules(&self) -> Result<Vec<(String, Regex)>, String> {
+        self.custom_rules
+            .iter()
+            .map(|(name, pattern)| {
+                Regex::new(pattern)
+                    .map(|regex| (name.clone(), regex))
+                    .map_err(|e| format!("invalid regular expression of custom rule '{name}': {e}"))
+            })
+            .collect()
+    }
+
+    /// Adds the bundled rules to the custom ones. A custom rule takes
+    /// precedence over the bundled rule with the same name.
+    pub fn with_bundled_rules(mut self, bundled_rules: BundledRules) -> Self {
+        for (name, pattern) in bundled_rules.rules {
+            self.custom_rules.entry(name).or_insert(pattern);
+        }
+        self
+    }
+}
 
 impl kubewarden::settings::Validatable for Settings {
     fn validate(&self) -> Result<(), String> {
+        if let Some(name) = self.custom_rules.keys().find(|name| name.is_empty()) {
+            return Err(format!(
+                "customRules cannot contain rules without a name: '{name}'"
+            ));
+        }
+        self.custom_rules()?;
+        if let Some(threshold) = self.entropy_threshold
+            && !(threshold > 0.0 && threshold <= 8.0)
+        {
+            return Err(format!(
+                "entropyThreshold must be greater than 0 and less than or equal to 8, got {threshold}"
+            ));
+        }
+        if self.entropy_min_length == 0 {
+            return Err("entropyMinLength must be greater than 0".to_owned());
+        }
+        if self.allowed_variables.contains("") {
+            return Err("allowedVariables cannot contain empty values".to_owned());
+        }
+        if let Some(rule_bundle) = &self.rule_bundle {
+            rule_bundle.validate()?;
+        }
         Ok(())
     }
 }
@@ -17,12 +126,70 @@ mod tests {
     use super::*;
 
     use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
 
     #[test]
     fn validate_settings() -> Result<(), ()> {
-        let settings = Settings {};
+        let settings = Settings::default();
 
         assert!(settings.validate().is_ok());
         Ok(())
     }
+
+    #[rstest]
+    #[case::custom_rules(r#"{"customRules": {"Acme API key": "acme_[a-z0-9]{32}"}}"#, true)]
+    #[case::invalid_custom_rule(r#"{"customRules": {"Acme API key": "acme_[a-z"}}"#, false)]
+    #[case::unnamed_custom_rule(r#"{"customRules": {"": "acme_[a-z0-9]{32}"}}"#, false)]
+    #[case::entropy_threshold(r#"{"entropyThreshold": 4.5, "entropyMinLength": 16}"#, true)]
+    #[case::negative_entropy_threshold(r#"{"entropyThreshold": -1}"#, false)]
+    #[case::entropy_threshold_too_high(r#"{"entropyThreshold": 9}"#, false)]
+    #[case::zero_entropy_min_length(r#"{"entropyMinLength": 0}"#, false)]
+    #[case::allowed_variables(r#"{"allowedVariables": ["ADMIN_EMAIL"]}"#, true)]
+    #[case::empty_allowed_variable(r#"{"allowedVariables": [""]}"#, false)]
+    #[case::report_mode(r#"{"mode": "report"}"#, true)]
+    #[case::rule_bundle(
+        r#"{"ruleBundle": {"image": "ghcr.io/acme/rules:v1", "keys": ["-----BEGIN PUBLIC KEY-----"]}}"#,
+        true
+    )]
+    #[case::rule_bundle_without_keys(
+        r#"{"ruleBundle": {"image": "ghcr.io/acme/rules:v1", "keys": []}}"#,
+        false
+    )]
+    fn validate_custom_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::valid(r#"{"rules": {"Acme API key": "acme_[a-z0-9]{32}"}}"#, true)]
+    #[case::invalid_regex(r#"{"rules": {"Acme API key": "acme_[a-z"}}"#, false)]
+    #[case::unnamed(r#"{"rules": {"": "acme_[a-z0-9]{32}"}}"#, false)]
+    fn validate_bundled_rules(#[case] rules: &str, #[case] is_valid: bool) {
+        let rules: BundledRules = serde_json::from_str(rules).expect("cannot parse the rules");
+        assert_eq!(rules.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn custom_rules_take_precedence() {
+        let settings = Settings {
+            custom_rules: [("Acme API key".to_owned(), "acme_[a-f0-9]{32}".to_owned())].into(),
+            ..Default::default()
+        };
+        let bundled_rules = BundledRules {
+            rules: [
+                ("Acme API key".to_owned(), "acme_.+".to_owned()),
+                ("Initech token".to_owned(), "itk_[A-Z0-9]{24}".to_owned()),
+            ]
+            .into(),
+        };
+
+        let settings = settings.with_bundled_rules(bundled_rules);
+        assert_eq!(
+            settings.custom_rules,
+            BTreeMap::from([
+                ("Acme API key".to_owned(), "acme_[a-f0-9]{32}".to_owned()),
+                ("Initech token".to_owned(), "itk_[A-Z0-9]{24}".to_owned()),
+            ])
+        );
+    }
 }
@@ -55,3 +55,33 @@
 	echo "$output"
 	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
 }
+
+@test "Test value rules rejecting values not matching the pattern" {
+	run kwctl run --request-path test_data/deployment_containsAnyOf.json --settings-json '{"criteria": "containsAnyOf", "values": ["a"], "valueRules": [{"name": "a", "valuePattern": "foo"}]}' annotated-policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*value of environment variable 'a' does not match 'foo'.*") -ne 0 ]
+}
+
+@test "Test value rules accepting values matching the pattern" {
+	run kwctl run --request-path test_data/deployment_containsAnyOf.json --settings-json '{"criteria": "containsAnyOf", "values": ["a"], "valueRules": [{"name": "a", "valuePattern": "bar|baz"}]}' annotated-policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Test containerPattern skipping containers not matching the pattern" {
+	run kwctl run --request-path test_data/deployment_no_envvars.json --settings-json '{"criteria": "containsAnyOf", "values": ["a"], "containerPattern": "sidecar-.*"}' annotated-policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Test forbidEnvFrom" {
+	run kwctl run --request-path test_data/deployment_env_from.json --settings-json '{"criteria": "containsAnyOf", "values": ["a"], "forbidEnvFrom": true}' annotated-policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*importing environment variables with envFrom is not allowed: ConfigMap 'nginx-config', Secret 'nginx-credentials'.*") -ne 0 ]
+}
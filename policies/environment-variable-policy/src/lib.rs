@@ -9,8 +9,11 @@ use criteria_policy_base::{
     validate::validate_values,
 };
 use guest::prelude::*;
-use k8s_openapi::api::core::v1::{self as apicore, Container, EphemeralContainer};
-use settings::Settings;
+use k8s_openapi::api::core::v1::{
+    self as apicore, Container, EnvFromSource, EnvVar, EphemeralContainer,
+};
+use regex::Regex;
+use settings::{Settings, ValueRule, full_match_regex};
 
 mod settings;
 
@@ -22,7 +25,10 @@ pub extern "C" fn wapc_init() {
 }
 
 fn validate_envvar(settings: &Settings, env_vars: &[String]) -> Result<()> {
-    validate_values(&settings.0, env_vars)
+    match &settings.criteria {
+        Some(criteria) => validate_values(criteria, env_vars),
+        None => Ok(()),
+    }
 }
 
 // Returns a map with container names as keys and their environment variable names as values
@@ -55,10 +61,127 @@ fn get_ephemeral_containers_env_vars(
     results
 }
 
+// Returns the name, the environment variables and the envFrom sources of all
+// the containers, init containers and ephemeral containers of the pod
+fn get_containers_environment(pod: &apicore::PodSpec) -> Vec<(&str, &[EnvVar], &[EnvFromSource])> {
+    pod.containers
+        .iter()
+        .chain(pod.init_containers.iter().flatten())
+        .map(|container| {
+            (
+                container.name.as_str(),
+                container.env.as_deref().unwrap_or_default(),
+                container.env_from.as_deref().unwrap_or_default(),
+            )
+        })
+        .chain(pod.ephemeral_containers.iter().flatten().map(|container| {
+            (
+                container.name.as_str(),
+                container.env.as_deref().unwrap_or_default(),
+                container.env_from.as_deref().unwrap_or_default(),
+            )
+        }))
+        .collect()
+}
+
+// Value rule with its regular expressions compiled
+struct CompiledValueRule<'a> {
+    rule: &'a ValueRule,
+    value_regex: Regex,
+    container_regex: Option<Regex>,
+}
+
+fn compile_value_rules(settings: &Settings) -> Result<Vec<CompiledValueRule<'_>>> {
+    settings
+        .value_rules
+        .iter()
+        .map(|rule| {
+            Ok(CompiledValueRule {
+                rule,
+                value_regex: full_match_regex(&rule.value_pattern)?,
+                container_regex: rule
+                    .container_pattern
+                    .as_deref()
+                    .map(full_match_regex)
+                    .transpose()?,
+            })
+        })
+        .collect()
+}
+
+// Returns the errors about the values of the environment variables of a container
+fn validate_values_of_envvars(
+    container_name: &str,
+    env_vars: &[EnvVar],
+    value_rules: &[CompiledValueRule],
+) -> Vec<String> {
+    value_rules
+        .iter()
+        .filter(|value_rule| {
+            value_rule
+                .container_regex
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(container_name))
+        })
+        .flat_map(|value_rule| {
+            env_vars
+                .iter()
+                .filter(|env_var| env_var.name == value_rule.rule.name)
+                .filter_map(|env_var| match &env_var.value {
+                    Some(value) if value_rule.value_regex.is_match(value) => None,
+                    Some(_) => Some(format!(
+                        "{container_name}: value of environment variable '{}' does not match '{}'",
+                        env_var.name, value_rule.rule.value_pattern
+                    )),
+                    None => Some(format!(
+                        "{container_name}: environment variable '{}' must have a literal value matching '{}'",
+                        env_var.name, value_rule.rule.value_pattern
+                    )),
+                })
+        })
+        .collect()
+}
+
+// Returns an error when a container imports ConfigMaps or Secrets with envFrom
+fn validate_env_from(container_name: &str, env_from: &[EnvFromSource]) -> Option<String> {
+    if env_from.is_empty() {
+        return None;
+    }
+    let sources = env_from
+        .iter()
+        .map(|source| {
+            if let Some(config_map) = &source.config_map_ref {
+                format!("ConfigMap '{}'", config_map.name)
+            } else if let Some(secret) = &source.secret_ref {
+                format!("Secret '{}'", secret.name)
+            } else {
+                "unknown source".to_owned()
+            }
+        })
+        .collect::<Vec<_>>();
+    Some(format!(
+        "{container_name}: importing environment variables with envFrom is not allowed: {}",
+        sources.join(", ")
+    ))
+}
+
 fn validate_environment_variables(
     pod: &apicore::PodSpec,
     settings: &settings::Settings,
 ) -> Result<(), Vec<String>> {
+    let container_regex = settings
+        .container_pattern
+        .as_deref()
+        .map(full_match_regex)
+        .transpose()
+        .map_err(|err| vec![err.to_string()])?;
+    let is_container_validated = |container_name: &str| {
+        container_regex
+            .as_ref()
+            .is_none_or(|regex| regex.is_match(container_name))
+    };
+    let value_rules = compile_value_rules(settings).map_err(|err| vec![err.to_string()])?;
+
     let mut envvars = get_containers_env_vars(&pod.containers);
     envvars.extend(get_containers_env_vars(
         pod.init_containers.as_ref().unwrap_or(&vec![]),
@@ -66,8 +189,9 @@ fn validate_environment_variables(
     envvars.extend(get_ephemeral_containers_env_vars(
         pod.ephemeral_containers.as_ref().unwrap_or(&vec![]),
     ));
-    let errors = envvars
+    let mut errors = envvars
         .iter()
+        .filter(|(container_name, _)| is_container_validated(container_name))
         .filter_map(|(container_name, envvar)| {
             validate_envvar(settings, envvar)
                 .map_err(|err| anyhow!("{}: {}", container_name, err))
@@ -76,6 +200,20 @@ fn validate_environment_variables(
         .map(|err| err.to_string())
         .collect::<Vec<_>>();
 
+    for (container_name, env_vars, env_from) in get_containers_environment(pod) {
+        if !is_container_validated(container_name) {
+            continue;
+        }
+        errors.extend(validate_values_of_envvars(
+            container_name,
+            env_vars,
+            &value_rules,
+        ));
+        if settings.forbid_env_from {
+            errors.extend(validate_env_from(container_name, env_from));
+        }
+    }
+
     if !errors.is_empty() {
         return Err(errors);
     }
@@ -215,9 +353,12 @@ mod tests {
 
     #[test]
     fn test_multiple_container_error_message() {
-        let settings = Settings(BaseSettings::ContainsAnyOf {
-            values: HashSet::from(["a".to_owned(), "b".to_owned()]),
-        });
+        let settings = Settings {
+            criteria: Some(BaseSettings::ContainsAnyOf {
+                values: HashSet::from(["a".to_owned(), "b".to_owned()]),
+            }),
+            ..Default::default()
+        };
         let container_envvar = Some(Vec::from([apicore::EnvVar {
             name: "c".to_owned(),
             ..Default::default()
@@ -275,4 +416,165 @@ mod tests {
             );
         }
     }
+
+    fn env_var(name: &str, value: &str) -> apicore::EnvVar {
+        apicore::EnvVar {
+            name: name.to_owned(),
+            value: Some(value.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::matching_value(vec![env_var("LOG_LEVEL", "info")], None, Ok(()))]
+    #[case::partially_matching_value(
+        vec![env_var("LOG_LEVEL", "information")],
+        None,
+        Err(vec!["app-web: value of environment variable 'LOG_LEVEL' does not match 'debug|info'".to_owned()])
+    )]
+    #[case::value_from(
+        vec![apicore::EnvVar {
+            name: "LOG_LEVEL".to_owned(),
+            value_from: Some(apicore::EnvVarSource {
+                config_map_key_ref: Some(apicore::ConfigMapKeySelector {
+                    name: "app-config".to_owned(),
+                    key: "log-level".to_owned(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }],
+        None,
+        Err(vec!["app-web: environment variable 'LOG_LEVEL' must have a literal value matching 'debug|info'".to_owned()])
+    )]
+    #[case::rule_of_other_containers(
+        vec![env_var("LOG_LEVEL", "trace")],
+        Some("worker-.*".to_owned()),
+        Ok(())
+    )]
+    #[case::rule_of_container(
+        vec![env_var("LOG_LEVEL", "trace")],
+        Some("app-.*".to_owned()),
+        Err(vec!["app-web: value of environment variable 'LOG_LEVEL' does not match 'debug|info'".to_owned()])
+    )]
+    fn test_value_rules(
+        #[case] env: Vec<apicore::EnvVar>,
+        #[case] container_pattern: Option<String>,
+        #[case] expected: Result<(), Vec<String>>,
+    ) {
+        let settings = Settings {
+            criteria: Some(BaseSettings::DoesNotContainAnyOf {
+                values: HashSet::from(["DEBUG".to_owned()]),
+            }),
+            value_rules: vec![ValueRule {
+                name: "LOG_LEVEL".to_owned(),
+                value_pattern: "debug|info".to_owned(),
+                container_pattern,
+            }],
+            ..Default::default()
+        };
+        let pod_spec = apicore::PodSpec {
+            containers: vec![apicore::Container {
+                name: "app-web".to_owned(),
+                env: Some(env),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_environment_variables(&pod_spec, &settings),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::all_containers(None, vec!["app-web", "sidecar"])]
+    #[case::selected_containers(Some("app-.*".to_owned()), vec!["app-web"])]
+    fn test_container_pattern(
+        #[case] container_pattern: Option<String>,
+        #[case] expected_containers: Vec<&str>,
+    ) {
+        let settings = Settings {
+            criteria: Some(BaseSettings::DoesNotContainAnyOf {
+                values: HashSet::from(["DEBUG".to_owned()]),
+            }),
+            container_pattern,
+            ..Default::default()
+        };
+        let pod_spec = apicore::PodSpec {
+            containers: vec![
+                apicore::Container {
+                    name: "app-web".to_owned(),
+                    env: Some(vec![env_var("DEBUG", "true")]),
+                    ..Default::default()
+                },
+                apicore::Container {
+                    name: "sidecar".to_owned(),
+                    env: Some(vec![env_var("DEBUG", "true")]),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let errors = validate_environment_variables(&pod_spec, &settings)
+            .expect_err("Expected validation to fail");
+        let mut containers: Vec<&str> = errors
+            .iter()
+            .filter_map(|err| err.split(':').next())
+            .collect();
+        containers.sort();
+        assert_eq!(containers, expected_containers);
+    }
+
+    #[rstest]
+    #[case::allowed(false, Ok(()))]
+    #[case::forbidden(
+        true,
+        Err(vec!["test-init: importing environment variables with envFrom is not allowed: ConfigMap 'app-config', Secret 'app-credentials'".to_owned()])
+    )]
+    fn test_forbid_env_from(
+        #[case] forbid_env_from: bool,
+        #[case] expected: Result<(), Vec<String>>,
+    ) {
+        let settings = Settings {
+            criteria: Some(BaseSettings::DoesNotContainAnyOf {
+                values: HashSet::from(["DEBUG".to_owned()]),
+            }),
+            forbid_env_from,
+            ..Default::default()
+        };
+        let pod_spec = apicore::PodSpec {
+            containers: vec![apicore::Container {
+                name: "test-container".to_owned(),
+                ..Default::default()
+            }],
+            init_containers: Some(vec![apicore::Container {
+                name: "test-init".to_owned(),
+                env_from: Some(vec![
+                    apicore::EnvFromSource {
+                        config_map_ref: Some(apicore::ConfigMapEnvSource {
+                            name: "app-config".to_owned(),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                    apicore::EnvFromSource {
+                        secret_ref: Some(apicore::SecretEnvSource {
+                            name: "app-credentials".to_owned(),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                ]),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_environment_variables(&pod_spec, &settings),
+            expected
+        );
+    }
 }
@@ -1,18 +1,71 @@
-use std::collections::HashSet;
-
 use criteria_policy_base::{kubewarden_policy_sdk as kubewarden, settings::BaseSettings};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Constraint on the value of an environment variable
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ValueRule {
+    /// Name of the environment variable
+    pub name: String,
+    /// Regular expression the whole value of the environment variable must match
+    pub value_pattern: String,
+    /// Regular expression the whole name of the containers must match for
+    /// the rule to be applied. When unset, the rule applies to all the containers.
+    #[serde(default)]
+    pub container_pattern: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", try_from = "RawSettings")]
+pub(crate) struct Settings {
+    /// Criteria on the names of the environment variables. Optional when
+    /// `valueRules` or `forbidEnvFrom` are set.
+    #[serde(flatten)]
+    pub criteria: Option<BaseSettings>,
+    /// Regular expression the whole name of the containers must match to be
+    /// validated. When unset, all the containers are validated.
+    #[serde(default)]
+    pub container_pattern: Option<String>,
+    #[serde(default)]
+    pub value_rules: Vec<ValueRule>,
+    /// Reject the containers importing ConfigMaps or Secrets with `envFrom`
+    #[serde(default)]
+    pub forbid_env_from: bool,
+}
 
-#[derive(Serialize, Deserialize, Debug)]
-pub(crate) struct Settings(pub(crate) BaseSettings);
+/// Settings as written by the user. A flattened `Option<BaseSettings>` would
+/// turn an invalid criteria into a missing one, so the criteria is parsed
+/// only when its fields are set, reporting its errors.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSettings {
+    #[serde(default)]
+    container_pattern: Option<String>,
+    #[serde(default)]
+    value_rules: Vec<ValueRule>,
+    #[serde(default)]
+    forbid_env_from: bool,
+    #[serde(flatten)]
+    criteria: Map<String, Value>,
+}
+
+impl TryFrom<RawSettings> for Settings {
+    type Error = serde_json::Error;
 
-// It's not possible to use the Default in the derive macro because we cannot
-// set a #[default] attribute to enum item that is no unit enums.
-impl Default for Settings {
-    fn default() -> Self {
-        Settings(BaseSettings::ContainsAnyOf {
-            values: HashSet::new(),
+    fn try_from(raw: RawSettings) -> Result<Self, Self::Error> {
+        let criteria =
+            if raw.criteria.contains_key("criteria") || raw.criteria.contains_key("values") {
+                Some(serde_json::from_value(Value::Object(raw.criteria))?)
+            } else {
+                None
+            };
+        Ok(Settings {
+            criteria,
+            container_pattern: raw.container_pattern,
+            value_rules: raw.value_rules,
+            forbid_env_from: raw.forbid_env_from,
         })
     }
 }
@@ -21,16 +74,36 @@ impl Default for Settings {
 // only C_IDENTIFIERS names.
 const ENVIRONMENT_VARIABLE_NAME_REGEX: &str = r"^[a-zA-Z_][a-zA-Z_\d]*$";
 
+/// Builds a regular expression matching only when the whole input matches the pattern
+pub(crate) fn full_match_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{pattern})$"))
+}
+
 impl kubewarden::settings::Validatable for Settings {
     fn validate(&self) -> Result<(), String> {
-        self.0.validate()?;
+        match &self.criteria {
+            Some(criteria) => criteria.validate()?,
+            None if self.value_rules.is_empty() && !self.forbid_env_from => {
+                return Err(
+                    "criteria is required when neither valueRules nor forbidEnvFrom are set"
+                        .to_owned(),
+                );
+            }
+            None => {}
+        }
 
-        let envvars = self.0.values();
+        let envvars = self
+            .criteria
+            .as_ref()
+            .map(BaseSettings::values)
+            .cloned()
+            .unwrap_or_default();
 
         // Validate that the environment variable names are valid.
         let environment_variable_name_regex = Regex::new(ENVIRONMENT_VARIABLE_NAME_REGEX).unwrap();
         let invalid_envvar: Vec<String> = envvars
             .iter()
+            .chain(self.value_rules.iter().map(|rule| &rule.name))
             .filter_map(|envvar| {
                 if environment_variable_name_regex.is_match(envvar) {
                     return None;
@@ -44,6 +117,27 @@ impl kubewarden::settings::Validatable for Settings {
                 invalid_envvar.join(", "),
             ));
         }
+
+        if let Some(pattern) = &self.container_pattern {
+            full_match_regex(pattern)
+                .map_err(|e| format!("Invalid containerPattern '{pattern}': {e}"))?;
+        }
+        for rule in &self.value_rules {
+            full_match_regex(&rule.value_pattern).map_err(|e| {
+                format!(
+                    "Invalid valuePattern '{}' of environment variable '{}': {e}",
+                    rule.value_pattern, rule.name
+                )
+            })?;
+            if let Some(pattern) = &rule.container_pattern {
+                full_match_regex(pattern).map_err(|e| {
+                    format!(
+                        "Invalid containerPattern '{pattern}' of environment variable '{}': {e}",
+                        rule.name
+                    )
+                })?;
+            }
+        }
         Ok(())
     }
 }
@@ -52,6 +146,8 @@ impl kubewarden::settings::Validatable for Settings {
 mod tests {
     use super::*;
 
+    use std::collections::HashSet;
+
     use kubewarden::settings::Validatable;
     use rstest::rstest;
 
@@ -66,12 +162,72 @@ mod tests {
     #[case::uppercase_beginning_with_number(vec!["3VAR"], false)]
     #[case::lowercase_beginning_with_number(vec!["4var"], false)]
     fn test_validation(#[case] variables: Vec<&str>, #[case] is_ok: bool) {
-        let settings = Settings(BaseSettings::ContainsAllOf {
-            values: variables
-                .iter()
-                .map(|v| v.to_string())
-                .collect::<HashSet<String>>(),
-        });
+        let settings = Settings {
+            criteria: Some(BaseSettings::ContainsAllOf {
+                values: variables
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<HashSet<String>>(),
+            }),
+            ..Default::default()
+        };
         assert_eq!(settings.validate().is_ok(), is_ok);
     }
+
+    #[rstest]
+    #[case::criteria_only(r#"{"criteria": "containsAnyOf", "values": ["a"]}"#, true)]
+    #[case::container_pattern(
+        r#"{"criteria": "containsAnyOf", "values": ["a"], "containerPattern": "app-.*"}"#,
+        true
+    )]
+    #[case::invalid_container_pattern(
+        r#"{"criteria": "containsAnyOf", "values": ["a"], "containerPattern": "app-("}"#,
+        false
+    )]
+    #[case::value_rules(
+        r#"{"criteria": "containsAnyOf", "values": ["a"], "valueRules": [{"name": "LOG_LEVEL", "valuePattern": "debug|info", "containerPattern": "app-.*"}]}"#,
+        true
+    )]
+    #[case::invalid_value_pattern(
+        r#"{"criteria": "containsAnyOf", "values": ["a"], "valueRules": [{"name": "LOG_LEVEL", "valuePattern": "debug|(info"}]}"#,
+        false
+    )]
+    #[case::invalid_value_rule_container_pattern(
+        r#"{"criteria": "containsAnyOf", "values": ["a"], "valueRules": [{"name": "LOG_LEVEL", "valuePattern": "info", "containerPattern": "app-("}]}"#,
+        false
+    )]
+    #[case::invalid_value_rule_name(
+        r#"{"criteria": "containsAnyOf", "values": ["a"], "valueRules": [{"name": "1LOG_LEVEL", "valuePattern": "info"}]}"#,
+        false
+    )]
+    #[case::forbid_env_from(
+        r#"{"criteria": "containsAnyOf", "values": ["a"], "forbidEnvFrom": true}"#,
+        true
+    )]
+    #[case::value_rules_only(
+        r#"{"valueRules": [{"name": "LOG_LEVEL", "valuePattern": "debug|info"}]}"#,
+        true
+    )]
+    #[case::forbid_env_from_only(r#"{"forbidEnvFrom": true}"#, true)]
+    #[case::nothing_to_validate(r#"{"forbidEnvFrom": false}"#, false)]
+    #[case::invalid_criteria_with_value_rules(
+        r#"{"criteria": "containsAnyof", "values": ["a"], "forbidEnvFrom": true}"#,
+        false
+    )]
+    #[case::values_without_criteria(r#"{"values": ["a"], "forbidEnvFrom": true}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_ok: bool) {
+        let settings = serde_json::from_str::<Settings>(settings);
+        assert_eq!(
+            settings.is_ok_and(|settings| settings.validate().is_ok()),
+            is_ok
+        );
+    }
+
+    #[rstest]
+    #[case::whole_match("app-.*", "app-web", true)]
+    #[case::partial_match("app-.*", "sidecar-app-web", false)]
+    #[case::alternation("web|worker", "worker", true)]
+    fn full_match(#[case] pattern: &str, #[case] input: &str, #[case] expected: bool) {
+        assert_eq!(full_match_regex(pattern).unwrap().is_match(input), expected);
+    }
 }
@@ -0,0 +1,77 @@
+#!/usr/bin/env bats
+
+@test "Accept a PersistentVolumeClaim setting its StorageClass" {
+	run kwctl run \
+		--request-path test_data/pvc_with_storage_class.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patch".*') -eq 0 ]
+}
+
+@test "Reject a PersistentVolumeClaim without StorageClass" {
+	run kwctl run \
+		--request-path test_data/pvc_without_storage_class.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*storageClassName must be set.*') -ne 0 ]
+}
+
+@test "Mutate a PersistentVolumeClaim without StorageClass" {
+	run kwctl run \
+		--request-path test_data/pvc_without_storage_class.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+}
+
+@test "Reject a StatefulSet with a volumeClaimTemplate without StorageClass" {
+	run kwctl run \
+		--request-path test_data/statefulset_without_storage_class.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*volumeClaimTemplate 'wal': storageClassName must be set.*") -ne 0 ]
+}
+
+@test "Mutate a StatefulSet with a volumeClaimTemplate without StorageClass" {
+	run kwctl run \
+		--request-path test_data/statefulset_without_storage_class.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pvc_with_storage_class.json --settings-json '{"defaultStorageClass": ""}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
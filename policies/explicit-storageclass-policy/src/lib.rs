@@ -0,0 +1,271 @@
+use guest::prelude::*;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use serde::Deserialize;
+use serde_json::Value;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+// Deprecated annotation still honored by Kubernetes instead of
+// `storageClassName`
+const STORAGE_CLASS_ANNOTATION: &str = "volume.beta.kubernetes.io/storage-class";
+
+/// Fields of the PersistentVolumeClaims used by the policy. The claims are
+/// only read through these fields: the mutation changes the
+/// `storageClassName` of the object, without dropping the fields unknown to
+/// k8s-openapi.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct ClaimFields {
+    #[serde(default)]
+    metadata: ObjectMeta,
+    #[serde(default)]
+    spec: ClaimSpecFields,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct ClaimSpecFields {
+    storage_class_name: Option<String>,
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let storage_class = validation_request
+        .settings
+        .storage_class_of(&request.namespace);
+
+    let mut object = request.object;
+    let claims: Option<Vec<&mut Value>> =
+        match (request.kind.group.as_str(), request.kind.kind.as_str()) {
+            ("", "PersistentVolumeClaim") => Some(vec![&mut object]),
+            ("apps", "StatefulSet") => match object.pointer_mut("/spec/volumeClaimTemplates") {
+                None | Some(Value::Null) => Some(Vec::new()),
+                Some(Value::Array(templates)) => Some(templates.iter_mut().collect()),
+                Some(_) => None,
+            },
+            _ => return kubewarden::accept_request(),
+        };
+    let result = claims
+        .ok_or_else(|| "volumeClaimTemplates must be a list".to_owned())
+        .and_then(|claims| {
+            set_storage_class(claims, storage_class).map_err(|error| error.to_string())
+        });
+
+    match result {
+        Ok(Ok(false)) => kubewarden::accept_request(),
+        Ok(Ok(true)) => kubewarden::mutate_request(object),
+        Ok(Err(names)) => {
+            let error = if request.kind.kind == "PersistentVolumeClaim" {
+                "storageClassName must be set".to_owned()
+            } else {
+                names
+                    .iter()
+                    .map(|name| {
+                        format!("volumeClaimTemplate '{name}': storageClassName must be set")
+                    })
+                    .collect::<Vec<String>>()
+                    .join("; ")
+            };
+            kubewarden::reject_request(
+                Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+                None,
+                None,
+                None,
+            )
+        }
+        Err(_) => kubewarden::reject_request(
+            Some("Cannot parse validation request".to_owned()),
+            None,
+            None,
+            None,
+        ),
+    }
+}
+
+// Returns true when the claim chooses its StorageClass. An empty
+// `storageClassName` explicitly requests a PersistentVolume without class.
+fn has_storage_class(claim: &ClaimFields) -> bool {
+    claim.spec.storage_class_name.is_some()
+        || claim
+            .metadata
+            .annotations
+            .as_ref()
+            .is_some_and(|annotations| annotations.contains_key(STORAGE_CLASS_ANNOTATION))
+}
+
+// Sets the StorageClass on the claims that do not choose one, changing only
+// their `spec.storageClassName`. Returns true when a claim has been changed,
+// or the names of the claims without StorageClass when there is no class to
+// set.
+fn set_storage_class(
+    claims: Vec<&mut Value>,
+    storage_class: Option<&str>,
+) -> Result<Result<bool, Vec<String>>, serde_json::Error> {
+    let mut missing: Vec<(String, &mut Value)> = Vec::new();
+    for claim in claims {
+        let fields = ClaimFields::deserialize(&*claim)?;
+        if !has_storage_class(&fields) {
+            missing.push((fields.metadata.name.unwrap_or_default(), claim));
+        }
+    }
+    let Some(storage_class) = storage_class else {
+        if missing.is_empty() {
+            return Ok(Ok(false));
+        }
+        return Ok(Err(missing.into_iter().map(|(name, _)| name).collect()));
+    };
+    for (_, claim) in &mut missing {
+        claim["spec"]["storageClassName"] = Value::from(storage_class);
+    }
+    Ok(Ok(!missing.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn settings() -> Settings {
+        serde_json::from_str(
+            r#"{"defaultStorageClass": "standard", "namespaceStorageClasses": {"databases": "fast-ssd"}}"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    #[rstest]
+    #[case::pvc_with_class("test_data/pvc_with_storage_class.json")]
+    #[case::pvc_without_class_name("test_data/pvc_empty_storage_class.json")]
+    #[case::pvc_with_annotation("test_data/pvc_storage_class_annotation.json")]
+    #[case::statefulset_with_classes("test_data/statefulset_with_storage_classes.json")]
+    #[case::without_claims("test_data/configmap.json")]
+    fn accept_request(#[case] fixture: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert!(response.mutated_object.is_none());
+    }
+
+    #[rstest]
+    #[case::pvc_without_class(
+        "test_data/pvc_without_storage_class.json",
+        "PersistentVolumeClaim 'postgres-data': storageClassName must be set"
+    )]
+    #[case::statefulset_without_class(
+        "test_data/statefulset_without_storage_class.json",
+        "StatefulSet 'postgres': volumeClaimTemplate 'wal': storageClassName must be set"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn mutate_pvc() {
+        let test_case = Testcase {
+            name: "mutate pvc".to_owned(),
+            fixture_file: "test_data/pvc_without_storage_class.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response.mutated_object.expect("the pvc should be mutated");
+        assert_eq!(
+            mutated_object["spec"]["storageClassName"],
+            json!("standard")
+        );
+    }
+
+    #[test]
+    fn mutate_statefulset_with_namespace_class() {
+        let test_case = Testcase {
+            name: "mutate statefulset".to_owned(),
+            fixture_file: "test_data/statefulset_without_storage_class.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response
+            .mutated_object
+            .expect("the statefulset should be mutated");
+        let templates = &mutated_object["spec"]["volumeClaimTemplates"];
+        assert_eq!(templates[0]["spec"]["storageClassName"], json!("premium"));
+        assert_eq!(templates[1]["spec"]["storageClassName"], json!("fast-ssd"));
+    }
+
+    #[test]
+    fn mutate_only_storage_class_name() {
+        let mut statefulset = json!({
+            "spec": {
+                "fieldFromTheFuture": true,
+                "volumeClaimTemplates": [{
+                    "metadata": {"name": "data"},
+                    "spec": {"accessModes": ["ReadWriteOnce"], "fieldFromTheFuture": 1}
+                }]
+            }
+        });
+        let claims = statefulset["spec"]["volumeClaimTemplates"]
+            .as_array_mut()
+            .expect("volumeClaimTemplates is a list")
+            .iter_mut()
+            .collect();
+
+        let changed = set_storage_class(claims, Some("standard")).expect("cannot parse claims");
+        assert_eq!(changed, Ok(true));
+        assert_eq!(
+            statefulset,
+            json!({
+                "spec": {
+                    "fieldFromTheFuture": true,
+                    "volumeClaimTemplates": [{
+                        "metadata": {"name": "data"},
+                        "spec": {
+                            "accessModes": ["ReadWriteOnce"],
+                            "fieldFromTheFuture": 1,
+                            "storageClassName": "standard"
+                        }
+                    }]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn mutate_claim_without_spec() {
+        let mut pvc = json!({"metadata": {"name": "data"}});
+
+        let changed =
+            set_storage_class(vec![&mut pvc], Some("standard")).expect("cannot parse claims");
+        assert_eq!(changed, Ok(true));
+        assert_eq!(pvc["spec"]["storageClassName"], json!("standard"));
+    }
+}
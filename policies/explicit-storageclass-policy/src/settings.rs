@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// StorageClass set on the claims that do not define one, instead of
+    /// rejecting them
+    pub default_storage_class: Option<String>,
+    /// StorageClasses replacing the default one in some namespaces
+    pub namespace_storage_classes: BTreeMap<String, String>,
+}
+
+impl Settings {
+    /// Returns the StorageClass to set on the claims of the namespace, if any
+    pub fn storage_class_of(&self, namespace: &str) -> Option<&str> {
+        self.namespace_storage_classes
+            .get(namespace)
+            .or(self.default_storage_class.as_ref())
+            .map(String::as_str)
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.default_storage_class.as_deref() == Some("") {
+            return Err("defaultStorageClass cannot be empty".to_owned());
+        }
+        if let Some(namespace) = self
+            .namespace_storage_classes
+            .iter()
+            .find(|(namespace, class)| namespace.is_empty() || class.is_empty())
+            .map(|(namespace, _)| namespace)
+        {
+            return Err(format!(
+                "namespaceStorageClasses: invalid entry for the namespace '{namespace}', both the namespace and the StorageClass must be set"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{"defaultStorageClass": "standard", "namespaceStorageClasses": {"databases": "fast-ssd"}}"#,
+        true
+    )]
+    #[case::empty_default(r#"{"defaultStorageClass": ""}"#, false)]
+    #[case::empty_namespace_class(r#"{"namespaceStorageClasses": {"databases": ""}}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::namespace_class("databases", Some("fast-ssd"))]
+    #[case::default_class("team-a", Some("standard"))]
+    fn storage_class(#[case] namespace: &str, #[case] expected: Option<&str>) {
+        let settings: Settings = serde_json::from_str(
+            r#"{"defaultStorageClass": "standard", "namespaceStorageClasses": {"databases": "fast-ssd"}}"#,
+        )
+        .expect("cannot parse settings");
+        assert_eq!(settings.storage_class_of(namespace), expected);
+    }
+
+    #[test]
+    fn no_storage_class_by_default() {
+        assert_eq!(Settings::default().storage_class_of("team-a"), None);
+    }
+}
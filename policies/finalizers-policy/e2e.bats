@@ -0,0 +1,52 @@
+#!/usr/bin/env bats
+
+@test "Reject a resource created with a custom finalizer" {
+	run kwctl run --request-path test_data/configmap_create_custom_finalizer.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*ConfigMap 'web-config' cannot add the finalizers not allowed: 'example.com/hold'.*") -ne 0 ]
+}
+
+@test "Accept a resource created with an allowed custom finalizer" {
+	run kwctl run --request-path test_data/configmap_create_custom_finalizer.json --settings-json '{"allowedFinalizers": ["example.com/*"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept an update adding an allowed finalizer" {
+	run kwctl run --request-path test_data/configmap_update_allowed_finalizer.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject an update adding a custom finalizer" {
+	run kwctl run --request-path test_data/configmap_update_custom_finalizer.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*cannot add the finalizers not allowed: 'acme.io/wedge'.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/configmap_create_custom_finalizer.json --settings-json '{"allowedFinalizers": [""]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
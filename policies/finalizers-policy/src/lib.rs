@@ -0,0 +1,130 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use serde_json::Value;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    // The finalizers already set when the resource is updated are not
+    // validated again, the ones being removed are always allowed
+    let old_finalizers = finalizers(&request.old_object)?;
+    let denied: Vec<String> = finalizers(&request.object)?
+        .into_iter()
+        .filter(|finalizer| !old_finalizers.contains(finalizer))
+        .filter(|finalizer| !settings.is_finalizer_allowed(finalizer))
+        .map(|finalizer| format!("'{finalizer}'"))
+        .collect();
+    if denied.is_empty() {
+        return kubewarden::accept_request();
+    }
+    kubewarden::reject_request(
+        Some(format!(
+            "{} '{}' cannot add the finalizers not allowed: {}",
+            request.kind.kind,
+            request.name,
+            denied.join(", ")
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+// Returns the finalizers of the resource, none when the resource is not set
+fn finalizers(object: &Value) -> Result<Vec<String>, serde_json::Error> {
+    match object.pointer("/metadata/finalizers") {
+        Some(finalizers) if !finalizers.is_null() => serde_json::from_value(finalizers.clone()),
+        _ => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use serde_json::json;
+
+    #[rstest]
+    #[case::not_set(Value::Null, vec![])]
+    #[case::no_finalizers(json!({"metadata": {"name": "web-config"}}), vec![])]
+    #[case::null_finalizers(json!({"metadata": {"finalizers": null}}), vec![])]
+    #[case::finalizers(
+        json!({"metadata": {"finalizers": ["kubernetes", "example.com/hold"]}}),
+        vec!["kubernetes", "example.com/hold"]
+    )]
+    fn object_finalizers(#[case] object: Value, #[case] expected: Vec<&str>) {
+        assert_eq!(finalizers(&object).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::create_custom_finalizer(
+        "test_data/configmap_create_custom_finalizer.json",
+        Settings::default(),
+        false
+    )]
+    #[case::create_allowed_custom_finalizer(
+        "test_data/configmap_create_custom_finalizer.json",
+        Settings {
+            allowed_finalizers: vec!["example.com/*".to_owned()],
+        },
+        true
+    )]
+    #[case::update_allowed_finalizer(
+        "test_data/configmap_update_allowed_finalizer.json",
+        Settings::default(),
+        true
+    )]
+    #[case::update_custom_finalizer(
+        "test_data/configmap_update_custom_finalizer.json",
+        Settings::default(),
+        false
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/configmap_update_custom_finalizer.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some("ConfigMap 'web-config' cannot add the finalizers not allowed: 'acme.io/wedge'")
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use wildmatch::WildMatch;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Patterns of the finalizers that can be added to the resources. `*`
+    /// matches any sequence of characters, `?` matches a single character.
+    pub allowed_finalizers: Vec<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            allowed_finalizers: vec![
+                "kubernetes".to_owned(),
+                "orphan".to_owned(),
+                "foregroundDeletion".to_owned(),
+                "kubernetes.io/*".to_owned(),
+                "*.kubernetes.io/*".to_owned(),
+                "*.k8s.io/*".to_owned(),
+            ],
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the finalizer can be added to the resources
+    pub fn is_finalizer_allowed(&self, finalizer: &str) -> bool {
+        self.allowed_finalizers
+            .iter()
+            .any(|pattern| WildMatch::new(pattern).matches(finalizer))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_finalizers.iter().any(String::is_empty) {
+            return Err("allowedFinalizers cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::namespace_finalizer("kubernetes", true)]
+    #[case::foreground_deletion("foregroundDeletion", true)]
+    #[case::pvc_protection("kubernetes.io/pvc-protection", true)]
+    #[case::kubernetes_subdomain("batch.kubernetes.io/job-tracking", true)]
+    #[case::k8s_subdomain("service.k8s.io/load-balancer-cleanup", true)]
+    #[case::custom_finalizer("example.com/hold", false)]
+    #[case::lookalike("kubernetes.io.example.com/hold", false)]
+    fn default_allowed_finalizers(#[case] finalizer: &str, #[case] expected: bool) {
+        assert_eq!(
+            Settings::default().is_finalizer_allowed(finalizer),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::allowed_finalizers(
+        r#"{"allowedFinalizers": ["kubernetes", "resources-finalizer.argocd.argoproj.io"]}"#,
+        true
+    )]
+    #[case::no_finalizers_allowed(r#"{"allowedFinalizers": []}"#, true)]
+    #[case::empty_finalizer(r#"{"allowedFinalizers": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,44 @@
+#!/usr/bin/env bats
+
+@test "Accept a Gateway with a TLS listener using a delegated hostname" {
+	run kwctl run --request-path test_data/gateway_https.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Gateway with a plain HTTP listener" {
+	run kwctl run --request-path test_data/gateway_http_all_namespaces.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*listener 'http' uses the 'HTTP' protocol.*") -ne 0 ]
+	[ $(expr "$output" : ".*listener 'http' cannot allow routes from 'All' namespaces.*") -ne 0 ]
+}
+
+@test "Reject a Gateway using a hostname and a GatewayClass not allowed in its namespace" {
+	run kwctl run --request-path test_data/gateway_http_all_namespaces.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*GatewayClass 'public' cannot be used inside of the namespace 'team-a'.*") -ne 0 ]
+	[ $(expr "$output" : ".*hostname 'shop.b.example.com' is not part of the domains delegated.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/gateway_https.json --settings-json '{"allowedRoutesNamespacesFrom": ["Everywhere"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,348 @@
+use std::collections::HashSet;
+
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod gateway;
+use gateway::{GATEWAY_KIND, Gateway, Listener, RouteNamespaces};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    if validation_request.request.kind.kind != GATEWAY_KIND {
+        return kubewarden::accept_request();
+    }
+    let gateway = serde_json::from_value::<Gateway>(validation_request.request.object)?;
+    let namespace = gateway
+        .metadata
+        .namespace
+        .clone()
+        .unwrap_or(validation_request.request.namespace);
+
+    let errors = validate_gateway(&gateway, &namespace, &validation_request.settings);
+    if !errors.is_empty() {
+        return kubewarden::reject_request(Some(errors.join(", ")), None, None, None);
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_gateway(gateway: &Gateway, namespace: &str, settings: &Settings) -> Vec<String> {
+    let mut errors = vec![];
+
+    if let Some(allowed_classes) = settings.gateway_classes_for(namespace)
+        && !allowed_classes.contains(&gateway.spec.gateway_class_name)
+    {
+        errors.push(format!(
+            "GatewayClass '{}' cannot be used inside of the namespace '{namespace}'",
+            gateway.spec.gateway_class_name
+        ));
+    }
+
+    let delegated_domains = if settings.delegated_domains.is_empty() {
+        None
+    } else {
+        Some(
+            settings
+                .delegated_domains
+                .get(namespace)
+                .cloned()
+                .unwrap_or_default(),
+        )
+    };
+
+    for listener in &gateway.spec.listeners {
+        if settings.require_tls
+            && let Err(e) = validate_listener_tls(listener)
+        {
+            errors.push(e);
+        }
+        if let Some(domains) = &delegated_domains
+            && let Err(e) = validate_listener_hostname(listener, domains, namespace)
+        {
+            errors.push(e);
+        }
+        if let Err(e) = validate_listener_allowed_routes(listener, settings) {
+            errors.push(e);
+        }
+    }
+
+    errors
+}
+
+fn validate_listener_tls(listener: &Listener) -> Result<(), String> {
+    match listener.protocol.as_str() {
+        "HTTPS" => {
+            let tls = listener.tls.clone().unwrap_or_default();
+            if tls.mode.as_deref().unwrap_or("Terminate") == "Terminate"
+                && tls.certificate_refs.is_empty()
+            {
+                return Err(format!(
+                    "listener '{}' must reference a TLS certificate",
+                    listener.name
+                ));
+            }
+            Ok(())
+        }
+        "TLS" => Ok(()),
+        protocol => Err(format!(
+            "listener '{}' uses the '{protocol}' protocol, only HTTPS and TLS are allowed",
+            listener.name
+        )),
+    }
+}
+
+fn validate_listener_allowed_routes(
+    listener: &Listener,
+    settings: &Settings,
+) -> Result<(), String> {
+    let namespaces = listener
+        .allowed_routes
+        .as_ref()
+        .and_then(|allowed_routes| allowed_routes.namespaces.as_ref());
+    let from = namespaces
+        .and_then(|namespaces| namespaces.from.as_deref())
+        .unwrap_or("Same");
+    if !settings.allowed_routes_namespaces_from.is_empty()
+        && !settings.allowed_routes_namespaces_from.contains(from)
+    {
+        return Err(format!(
+            "listener '{}' cannot allow routes from '{from}' namespaces",
+            listener.name
+        ));
+    }
+    // An empty selector matches all the namespaces, like `All`
+    if from == "Selector" && !namespaces.is_some_and(has_selector) {
+        return Err(format!(
+            "listener '{}' must define a non-empty selector to allow routes from 'Selector' namespaces",
+            listener.name
+        ));
+    }
+    Ok(())
+}
+
+fn has_selector(namespaces: &RouteNamespaces) -> bool {
+    namespaces.selector.as_ref().is_some_and(|selector| {
+        selector
+            .match_labels
+            .as_ref()
+            .is_some_and(|labels| !labels.is_empty())
+            || selector
+                .match_expressions
+                .as_ref()
+                .is_some_and(|expressions| !expressions.is_empty())
+    })
+}
+
+fn validate_listener_hostname(
+    listener: &Listener,
+    domains: &HashSet<String>,
+    namespace: &str,
+) -> Result<(), String> {
+    match &listener.hostname {
+        None => Err(format!(
+            "listener '{}' must define a hostname belonging to the domains delegated to the namespace '{namespace}'",
+            listener.name
+        )),
+        Some(hostname) if !hostname_in_domains(hostname, domains) => Err(format!(
+            "listener '{}' hostname '{hostname}' is not part of the domains delegated to the namespace '{namespace}'",
+            listener.name
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+// A hostname belongs to a domain when it's equal to it or when it's one of
+// its subdomains. Wildcard hostnames are evaluated without the leading `*.`.
+fn hostname_in_domains(hostname: &str, domains: &HashSet<String>) -> bool {
+    let hostname = hostname.strip_prefix("*.").unwrap_or(hostname);
+    domains
+        .iter()
+        .any(|domain| hostname == domain || hostname.ends_with(&format!(".{domain}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use gateway::{AllowedRoutes, GatewaySpec, GatewayTlsConfig};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn https_listener(hostname: Option<&str>) -> Listener {
+        Listener {
+            name: "https".to_owned(),
+            hostname: hostname.map(String::from),
+            protocol: "HTTPS".to_owned(),
+            tls: Some(GatewayTlsConfig {
+                mode: None,
+                certificate_refs: vec![serde_json::json!({"name": "cert"})],
+            }),
+            allowed_routes: None,
+        }
+    }
+
+    fn gateway(class: &str, listeners: Vec<Listener>) -> Gateway {
+        Gateway {
+            spec: GatewaySpec {
+                gateway_class_name: class.to_owned(),
+                listeners,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case("a.example.com", true)]
+    #[case("www.a.example.com", true)]
+    #[case("*.a.example.com", true)]
+    #[case("b.example.com", false)]
+    #[case("evila.example.com", false)]
+    #[case("*.example.com", false)]
+    fn hostname_matching(#[case] hostname: &str, #[case] expected: bool) {
+        let domains = HashSet::from(["a.example.com".to_owned()]);
+        assert_eq!(hostname_in_domains(hostname, &domains), expected);
+    }
+
+    #[rstest]
+    #[case::https_with_certificate(https_listener(None), true)]
+    #[case::https_without_certificate(Listener {
+        tls: None,
+        ..https_listener(None)
+    }, false)]
+    #[case::https_passthrough(Listener {
+        tls: Some(GatewayTlsConfig {
+            mode: Some("Passthrough".to_owned()),
+            certificate_refs: vec![],
+        }),
+        ..https_listener(None)
+    }, true)]
+    #[case::tls(Listener {
+        protocol: "TLS".to_owned(),
+        ..https_listener(None)
+    }, true)]
+    #[case::http(Listener {
+        protocol: "HTTP".to_owned(),
+        tls: None,
+        ..https_listener(None)
+    }, false)]
+    fn listener_tls(#[case] listener: Listener, #[case] is_valid: bool) {
+        assert_eq!(validate_listener_tls(&listener).is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::delegated_hostname("team-a", Some("shop.a.example.com"), true)]
+    #[case::missing_hostname("team-a", None, false)]
+    #[case::other_domain("team-a", Some("shop.b.example.com"), false)]
+    #[case::namespace_without_delegation("team-c", Some("shop.a.example.com"), false)]
+    fn delegated_domains(
+        #[case] namespace: &str,
+        #[case] hostname: Option<&str>,
+        #[case] is_valid: bool,
+    ) {
+        let settings = Settings {
+            delegated_domains: BTreeMap::from([
+                (
+                    "team-a".to_owned(),
+                    HashSet::from(["a.example.com".to_owned()]),
+                ),
+                (
+                    "team-b".to_owned(),
+                    HashSet::from(["b.example.com".to_owned()]),
+                ),
+            ]),
+            ..Default::default()
+        };
+        let errors = validate_gateway(
+            &gateway("shared", vec![https_listener(hostname)]),
+            namespace,
+            &settings,
+        );
+        assert_eq!(errors.is_empty(), is_valid, "errors: {errors:?}");
+    }
+
+    #[rstest]
+    #[case::default_same(None, None, true)]
+    #[case::same(Some("Same"), None, true)]
+    #[case::selector(
+        Some("Selector"),
+        Some(LabelSelector {
+            match_labels: Some(BTreeMap::from([("team".to_owned(), "a".to_owned())])),
+            ..Default::default()
+        }),
+        true
+    )]
+    #[case::missing_selector(Some("Selector"), None, false)]
+    #[case::empty_selector(Some("Selector"), Some(LabelSelector::default()), false)]
+    #[case::all(Some("All"), None, false)]
+    fn allowed_routes(
+        #[case] from: Option<&str>,
+        #[case] selector: Option<LabelSelector>,
+        #[case] is_valid: bool,
+    ) {
+        let listener = Listener {
+            allowed_routes: Some(AllowedRoutes {
+                namespaces: Some(RouteNamespaces {
+                    from: from.map(String::from),
+                    selector,
+                }),
+            }),
+            ..https_listener(None)
+        };
+        let errors = validate_gateway(
+            &gateway("shared", vec![listener]),
+            "default",
+            &Settings::default(),
+        );
+        assert_eq!(errors.is_empty(), is_valid, "errors: {errors:?}");
+    }
+
+    #[rstest]
+    #[case::allowed_class("team-a", "internal", true)]
+    #[case::denied_class("team-a", "public", false)]
+    #[case::fallback_class("team-b", "shared", true)]
+    fn gateway_classes(#[case] namespace: &str, #[case] class: &str, #[case] is_valid: bool) {
+        let settings = Settings {
+            allowed_gateway_classes: BTreeMap::from([
+                ("team-a".to_owned(), HashSet::from(["internal".to_owned()])),
+                ("*".to_owned(), HashSet::from(["shared".to_owned()])),
+            ]),
+            ..Default::default()
+        };
+        let errors = validate_gateway(
+            &gateway(class, vec![https_listener(None)]),
+            namespace,
+            &settings,
+        );
+        assert_eq!(errors.is_empty(), is_valid, "errors: {errors:?}");
+    }
+
+    #[rstest]
+    #[case::valid_gateway("test_data/gateway_https.json", true)]
+    #[case::http_gateway("test_data/gateway_http_all_namespaces.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+pub(crate) const ANY_NAMESPACE: &str = "*";
+const ROUTE_NAMESPACES_FROM: [&str; 3] = ["All", "Same", "Selector"];
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Listeners must use the HTTPS or TLS protocol
+    pub require_tls: bool,
+    /// Domains delegated to each namespace. Listener hostnames must belong
+    /// to one of the domains delegated to the namespace of the Gateway.
+    pub delegated_domains: BTreeMap<String, HashSet<String>>,
+    /// Values allowed inside of `allowedRoutes.namespaces.from`
+    pub allowed_routes_namespaces_from: HashSet<String>,
+    /// GatewayClasses each namespace is allowed to use. The `*` key applies
+    /// to the namespaces that are not explicitly listed.
+    pub allowed_gateway_classes: BTreeMap<String, HashSet<String>>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            require_tls: true,
+            delegated_domains: BTreeMap::new(),
+            allowed_routes_namespaces_from: HashSet::from([
+                "Same".to_owned(),
+                "Selector".to_owned(),
+            ]),
+            allowed_gateway_classes: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the GatewayClasses the given namespace is allowed to use, `None`
+    /// when no restriction is configured
+    pub fn gateway_classes_for(&self, namespace: &str) -> Option<HashSet<String>> {
+        if self.allowed_gateway_classes.is_empty() {
+            return None;
+        }
+        Some(
+            self.allowed_gateway_classes
+                .get(namespace)
+                .or_else(|| self.allowed_gateway_classes.get(ANY_NAMESPACE))
+                .cloned()
+                .unwrap_or_default(),
+        )
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(value) = self
+            .allowed_routes_namespaces_from
+            .iter()
+            .find(|from| !ROUTE_NAMESPACES_FROM.contains(&from.as_str()))
+        {
+            return Err(format!(
+                "allowedRoutesNamespacesFrom contains an invalid value '{value}', allowed values are: {}",
+                ROUTE_NAMESPACES_FROM.join(", ")
+            ));
+        }
+        if let Some((namespace, _)) = self
+            .delegated_domains
+            .iter()
+            .find(|(_, domains)| domains.is_empty() || domains.iter().any(|d| d.is_empty()))
+        {
+            return Err(format!(
+                "delegatedDomains of namespace '{namespace}' cannot be empty"
+            ));
+        }
+        if let Some((namespace, _)) = self
+            .allowed_gateway_classes
+            .iter()
+            .find(|(_, classes)| classes.iter().any(|c| c.is_empty()))
+        {
+            return Err(format!(
+                "allowedGatewayClasses of namespace '{namespace}' cannot contain empty names"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(Settings::default(), true)]
+    #[case::invalid_from(Settings {
+        allowed_routes_namespaces_from: HashSet::from(["Others".to_owned()]),
+        ..Default::default()
+    }, false)]
+    #[case::empty_domains(Settings {
+        delegated_domains: BTreeMap::from([("team-a".to_owned(), HashSet::new())]),
+        ..Default::default()
+    }, false)]
+    #[case::valid_domains(Settings {
+        delegated_domains: BTreeMap::from([("team-a".to_owned(), HashSet::from(["a.example.com".to_owned()]))]),
+        ..Default::default()
+    }, true)]
+    #[case::empty_class_name(Settings {
+        allowed_gateway_classes: BTreeMap::from([("*".to_owned(), HashSet::from([String::new()]))]),
+        ..Default::default()
+    }, false)]
+    fn validate_settings(#[case] settings: Settings, #[case] is_valid: bool) {
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::no_restriction(BTreeMap::new(), "team-a", None)]
+    #[case::explicit_namespace(
+        BTreeMap::from([("team-a".to_owned(), HashSet::from(["internal".to_owned()]))]),
+        "team-a",
+        Some(HashSet::from(["internal".to_owned()]))
+    )]
+    #[case::fallback(
+        BTreeMap::from([("*".to_owned(), HashSet::from(["shared".to_owned()]))]),
+        "team-b",
+        Some(HashSet::from(["shared".to_owned()]))
+    )]
+    #[case::not_listed(
+        BTreeMap::from([("team-a".to_owned(), HashSet::from(["internal".to_owned()]))]),
+        "team-b",
+        Some(HashSet::new())
+    )]
+    fn gateway_classes_for(
+        #[case] allowed_gateway_classes: BTreeMap<String, HashSet<String>>,
+        #[case] namespace: &str,
+        #[case] expected: Option<HashSet<String>>,
+    ) {
+        let settings = Settings {
+            allowed_gateway_classes,
+            ..Default::default()
+        };
+        assert_eq!(settings.gateway_classes_for(namespace), expected);
+    }
+}
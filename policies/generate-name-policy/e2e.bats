@@ -0,0 +1,41 @@
+#!/usr/bin/env bats
+
+@test "Reject a standalone Pod using generateName" {
+	run kwctl run --request-path test_data/pod_generate_name.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Pod resources in namespace 'prod' cannot use generateName ('debug-').*") -ne 0 ]
+}
+
+@test "Accept a Pod created by a ReplicaSet" {
+	run kwctl run --request-path test_data/pod_owned_generate_name.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a ConfigMap using generateName outside of the selected namespaces" {
+	run kwctl run --request-path test_data/configmap_generate_name.json --settings-json '{"namespaces": ["staging"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_generate_name.json --settings-json '{"kinds": []}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,130 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use workloads::is_controlled;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    if !settings.is_enforced(&request.kind.group, &request.kind.kind, &request.namespace) {
+        return kubewarden::accept_request();
+    }
+    let metadata: ObjectMeta = match request.object.get("metadata") {
+        Some(metadata) => serde_json::from_value(metadata.clone())?,
+        None => return kubewarden::accept_request(),
+    };
+    // generateName is ignored by the API server when the name is set
+    if metadata.name.as_ref().is_some_and(|name| !name.is_empty()) {
+        return kubewarden::accept_request();
+    }
+    let Some(generate_name) = metadata
+        .generate_name
+        .as_deref()
+        .filter(|name| !name.is_empty())
+    else {
+        return kubewarden::accept_request();
+    };
+    if settings.allow_controlled_resources && is_controlled(&metadata) {
+        return kubewarden::accept_request();
+    }
+
+    let location = if request.namespace.is_empty() {
+        String::new()
+    } else {
+        format!(" in namespace '{}'", request.namespace)
+    };
+    kubewarden::reject_request(
+        Some(format!(
+            "{} resources{location} cannot use generateName ('{generate_name}'): set metadata.name instead",
+            request.kind.kind
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::standalone_pod("test_data/pod_generate_name.json", Settings::default(), false)]
+    #[case::configmap("test_data/configmap_generate_name.json", Settings::default(), false)]
+    #[case::controlled_pod("test_data/pod_owned_generate_name.json", Settings::default(), true)]
+    #[case::controlled_pod_not_allowed(
+        "test_data/pod_owned_generate_name.json",
+        Settings {
+            allow_controlled_resources: false,
+            ..Default::default()
+        },
+        false
+    )]
+    #[case::selected_namespace(
+        "test_data/pod_generate_name.json",
+        Settings {
+            namespaces: ["prod".to_owned()].into(),
+            ..Default::default()
+        },
+        false
+    )]
+    #[case::not_selected_namespace(
+        "test_data/pod_generate_name.json",
+        Settings {
+            namespaces: ["dev".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/configmap_generate_name.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "ConfigMap resources in namespace 'prod' cannot use generateName ('web-config-'): set metadata.name instead"
+            )
+        );
+    }
+}
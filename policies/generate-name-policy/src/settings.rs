@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+/// Kind of a Kubernetes resource
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Kind {
+    /// API group of the kind, empty for the core group
+    #[serde(default)]
+    pub group: String,
+    /// Name of the kind. `*` matches all the kinds of the group.
+    pub kind: String,
+}
+
+impl Kind {
+    /// Returns true when the given kind is matched
+    pub fn matches(&self, group: &str, kind: &str) -> bool {
+        self.group == group && (self.kind == "*" || self.kind == kind)
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Kinds of the resources that cannot use `generateName`
+    pub kinds: Vec<Kind>,
+    /// Namespaces where the resources cannot use `generateName`. When empty,
+    /// the policy applies to all the namespaces and to the cluster-wide
+    /// resources.
+    pub namespaces: HashSet<String>,
+    /// Allow `generateName` for the resources managed by a controller, such
+    /// as the Pods of a ReplicaSet
+    pub allow_controlled_resources: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            kinds: vec![
+                Kind {
+                    group: "".to_owned(),
+                    kind: "Pod".to_owned(),
+                },
+                Kind {
+                    group: "".to_owned(),
+                    kind: "ConfigMap".to_owned(),
+                },
+            ],
+            namespaces: HashSet::new(),
+            allow_controlled_resources: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the resources of the kind, created in the
+    /// namespace, cannot use `generateName`. Cluster-wide resources have an
+    /// empty namespace.
+    pub fn is_enforced(&self, group: &str, kind: &str, namespace: &str) -> bool {
+        self.kinds.iter().any(|k| k.matches(group, kind))
+            && (self.namespaces.is_empty() || self.namespaces.contains(namespace))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.kinds.is_empty() {
+            return Err("at least one kind must be provided".to_owned());
+        }
+        if self.kinds.iter().any(|kind| kind.kind.is_empty()) {
+            return Err("kinds cannot contain empty kinds".to_owned());
+        }
+        if self.namespaces.contains("") {
+            return Err("namespaces cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::pod(Settings::default(), "", "Pod", "team-a", true)]
+    #[case::other_kind(Settings::default(), "", "Secret", "team-a", false)]
+    #[case::other_group(Settings::default(), "example.com", "Pod", "team-a", false)]
+    #[case::selected_namespace(
+        Settings {
+            namespaces: ["prod".to_owned()].into(),
+            ..Default::default()
+        },
+        "",
+        "ConfigMap",
+        "prod",
+        true
+    )]
+    #[case::not_selected_namespace(
+        Settings {
+            namespaces: ["prod".to_owned()].into(),
+            ..Default::default()
+        },
+        "",
+        "ConfigMap",
+        "dev",
+        false
+    )]
+    #[case::all_kinds_of_group(
+        Settings {
+            kinds: vec![Kind {
+                group: "batch".to_owned(),
+                kind: "*".to_owned(),
+            }],
+            ..Default::default()
+        },
+        "batch",
+        "Job",
+        "team-a",
+        true
+    )]
+    fn enforced(
+        #[case] settings: Settings,
+        #[case] group: &str,
+        #[case] kind: &str,
+        #[case] namespace: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(settings.is_enforced(group, kind, namespace), expected);
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::kinds(
+        r#"{"kinds": [{"group": "batch", "kind": "Job"}], "namespaces": ["prod"]}"#,
+        true
+    )]
+    #[case::no_kinds(r#"{"kinds": []}"#, false)]
+    #[case::empty_kind(r#"{"kinds": [{"group": "batch", "kind": ""}]}"#, false)]
+    #[case::empty_namespace(r#"{"namespaces": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
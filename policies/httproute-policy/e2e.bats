@@ -0,0 +1,42 @@
+#!/usr/bin/env bats
+
+@test "Accept an HTTPRoute using a delegated hostname" {
+	run kwctl run --request-path test_data/httproute_valid.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject an HTTPRoute using a hostname not delegated and redirecting to an external host" {
+	run kwctl run --request-path test_data/httproute_external_redirect.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*hostnames not part of the domains delegated to the namespace 'team-a': shop.b.example.com.*") -ne 0 ]
+	[ $(expr "$output" : ".*RequestRedirect filters cannot redirect to external hosts: evil.example.org.*") -ne 0 ]
+}
+
+@test "Accept redirects when no restriction is configured" {
+	run kwctl run --request-path test_data/httproute_external_redirect.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/httproute_valid.json --settings-json '{"delegatedDomains": {"team-a": []}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,381 @@
+use std::collections::{BTreeMap, HashSet};
+
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::Resource;
+use kubewarden_policy_sdk::host_capabilities::kubernetes::{
+    ListResourcesByNamespaceRequest, list_resources_by_namespace,
+};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod gateway_api;
+use gateway_api::{
+    GATEWAY_API_GROUP, HTTP_ROUTE_KIND, HttpBackendRef, HttpRoute, HttpRouteFilter, ReferenceGrant,
+};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    if validation_request.request.kind.kind != HTTP_ROUTE_KIND {
+        return kubewarden::accept_request();
+    }
+    let route = serde_json::from_value::<HttpRoute>(validation_request.request.object)?;
+    let namespace = route
+        .metadata
+        .namespace
+        .clone()
+        .unwrap_or(validation_request.request.namespace);
+    let settings = &validation_request.settings;
+
+    let mut errors = validate_hostnames(&route, &namespace, settings);
+    if settings.require_reference_grant {
+        errors.extend(validate_backend_refs(
+            &route,
+            &namespace,
+            list_reference_grants,
+        ));
+    }
+    if !errors.is_empty() {
+        return kubewarden::reject_request(Some(errors.join(", ")), None, None, None);
+    }
+    kubewarden::accept_request()
+}
+
+fn list_reference_grants(namespace: &str) -> Result<Vec<ReferenceGrant>, String> {
+    let request = ListResourcesByNamespaceRequest {
+        api_version: ReferenceGrant::API_VERSION.to_owned(),
+        kind: ReferenceGrant::KIND.to_owned(),
+        namespace: namespace.to_owned(),
+        label_selector: None,
+        field_selector: None,
+        field_masks: None,
+    };
+    list_resources_by_namespace::<ReferenceGrant>(&request)
+        .map(|list| list.items)
+        .map_err(|e| format!("cannot list ReferenceGrants of namespace '{namespace}': {e}"))
+}
+
+// Validates the hostnames of the route and the hostnames used by the
+// RequestRedirect filters
+fn validate_hostnames(route: &HttpRoute, namespace: &str, settings: &Settings) -> Vec<String> {
+    let mut errors = vec![];
+    let delegated_domains = if settings.delegated_domains.is_empty() {
+        None
+    } else {
+        Some(
+            settings
+                .delegated_domains
+                .get(namespace)
+                .cloned()
+                .unwrap_or_default(),
+        )
+    };
+
+    if let Some(domains) = &delegated_domains {
+        if route.spec.hostnames.is_empty() {
+            errors.push(format!(
+                "HTTPRoute must define hostnames belonging to the domains delegated to the namespace '{namespace}'"
+            ));
+        }
+        let invalid: Vec<&str> = route
+            .spec
+            .hostnames
+            .iter()
+            .filter(|hostname| !hostname_in_domains(hostname, domains))
+            .map(String::as_str)
+            .collect();
+        if !invalid.is_empty() {
+            errors.push(format!(
+                "hostnames not part of the domains delegated to the namespace '{namespace}': {}",
+                invalid.join(", ")
+            ));
+        }
+    }
+
+    let redirect_domains: HashSet<String> = settings
+        .allowed_redirect_domains
+        .iter()
+        .chain(delegated_domains.iter().flatten())
+        .cloned()
+        .collect();
+    let filters = route.spec.rules.iter().flat_map(|rule| {
+        rule.filters
+            .iter()
+            .chain(rule.backend_refs.iter().flat_map(|b| b.filters.iter()))
+    });
+    let mut invalid_redirects: Vec<String> = filters
+        .filter_map(redirect_hostname)
+        .filter(|hostname| {
+            (delegated_domains.is_some() || !settings.allowed_redirect_domains.is_empty())
+                && !hostname_in_domains(hostname, &redirect_domains)
+        })
+        .collect();
+    invalid_redirects.sort();
+    invalid_redirects.dedup();
+    if !invalid_redirects.is_empty() {
+        errors.push(format!(
+            "RequestRedirect filters cannot redirect to external hosts: {}",
+            invalid_redirects.join(", ")
+        ));
+    }
+
+    errors
+}
+
+fn redirect_hostname(filter: &HttpRouteFilter) -> Option<String> {
+    if filter.filter_type != "RequestRedirect" {
+        return None;
+    }
+    filter
+        .request_redirect
+        .as_ref()
+        .and_then(|redirect| redirect.hostname.clone())
+}
+
+// Validates that all the backends living inside of other namespaces are
+// allowed by a ReferenceGrant. The ReferenceGrants of each namespace are
+// fetched only once.
+fn validate_backend_refs<F>(route: &HttpRoute, namespace: &str, list_grants: F) -> Vec<String>
+where
+    F: Fn(&str) -> Result<Vec<ReferenceGrant>, String>,
+{
+    let mut errors = vec![];
+    let mut grants_by_namespace: BTreeMap<String, Vec<ReferenceGrant>> = BTreeMap::new();
+
+    let backend_refs = route
+        .spec
+        .rules
+        .iter()
+        .flat_map(|rule| rule.backend_refs.iter())
+        .filter(|backend_ref| {
+            backend_ref
+                .namespace
+                .as_deref()
+                .is_some_and(|ns| ns != namespace)
+        });
+
+    for backend_ref in backend_refs {
+        let backend_namespace = backend_ref.namespace.clone().unwrap_or_default();
+        if !grants_by_namespace.contains_key(&backend_namespace) {
+            match list_grants(&backend_namespace) {
+                Ok(grants) => {
+                    grants_by_namespace.insert(backend_namespace.clone(), grants);
+                }
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            }
+        }
+        let allowed = grants_by_namespace
+            .get(&backend_namespace)
+            .is_some_and(|grants| {
+                grants
+                    .iter()
+                    .any(|grant| reference_grant_allows(grant, namespace, backend_ref))
+            });
+        if !allowed {
+            errors.push(format!(
+                "backend '{}/{}' is not allowed by any ReferenceGrant",
+                backend_namespace, backend_ref.name
+            ));
+        }
+    }
+
+    errors
+}
+
+fn reference_grant_allows(
+    grant: &ReferenceGrant,
+    route_namespace: &str,
+    backend_ref: &HttpBackendRef,
+) -> bool {
+    let from_allowed = grant.spec.from.iter().any(|from| {
+        from.group == GATEWAY_API_GROUP
+            && from.kind == HTTP_ROUTE_KIND
+            && from.namespace == route_namespace
+    });
+    let to_allowed = grant.spec.to.iter().any(|to| {
+        to.group == backend_ref.group()
+            && to.kind == backend_ref.kind()
+            && to
+                .name
+                .as_ref()
+                .is_none_or(|name| *name == backend_ref.name)
+    });
+    from_allowed && to_allowed
+}
+
+// A hostname belongs to a domain when it's equal to it or when it's one of
+// its subdomains. Wildcard hostnames are evaluated without the leading `*.`.
+fn hostname_in_domains(hostname: &str, domains: &HashSet<String>) -> bool {
+    let hostname = hostname.strip_prefix("*.").unwrap_or(hostname);
+    domains
+        .iter()
+        .any(|domain| hostname == domain || hostname.ends_with(&format!(".{domain}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use gateway_api::{
+        HttpRequestRedirectFilter, HttpRouteRule, HttpRouteSpec, ReferenceGrantFrom,
+        ReferenceGrantSpec, ReferenceGrantTo,
+    };
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn backend_ref(namespace: Option<&str>, name: &str) -> HttpBackendRef {
+        HttpBackendRef {
+            name: name.to_owned(),
+            namespace: namespace.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    fn redirect_filter(hostname: &str) -> HttpRouteFilter {
+        HttpRouteFilter {
+            filter_type: "RequestRedirect".to_owned(),
+            request_redirect: Some(HttpRequestRedirectFilter {
+                hostname: Some(hostname.to_owned()),
+            }),
+        }
+    }
+
+    fn route(hostnames: Vec<&str>, rules: Vec<HttpRouteRule>) -> HttpRoute {
+        HttpRoute {
+            spec: HttpRouteSpec {
+                hostnames: hostnames.into_iter().map(String::from).collect(),
+                rules,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn reference_grant(from_namespace: &str, to_name: Option<&str>) -> ReferenceGrant {
+        ReferenceGrant {
+            spec: ReferenceGrantSpec {
+                from: vec![ReferenceGrantFrom {
+                    group: GATEWAY_API_GROUP.to_owned(),
+                    kind: HTTP_ROUTE_KIND.to_owned(),
+                    namespace: from_namespace.to_owned(),
+                }],
+                to: vec![ReferenceGrantTo {
+                    group: "".to_owned(),
+                    kind: "Service".to_owned(),
+                    name: to_name.map(String::from),
+                }],
+            },
+            ..Default::default()
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            delegated_domains: BTreeMap::from([(
+                "team-a".to_owned(),
+                HashSet::from(["a.example.com".to_owned()]),
+            )]),
+            allowed_redirect_domains: HashSet::from(["login.example.com".to_owned()]),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::delegated_hostname(vec!["shop.a.example.com"], true)]
+    #[case::wildcard_hostname(vec!["*.a.example.com"], true)]
+    #[case::no_hostnames(vec![], false)]
+    #[case::other_domain(vec!["shop.a.example.com", "shop.b.example.com"], false)]
+    fn route_hostnames(#[case] hostnames: Vec<&str>, #[case] is_valid: bool) {
+        let errors = validate_hostnames(&route(hostnames, vec![]), "team-a", &settings());
+        assert_eq!(errors.is_empty(), is_valid, "errors: {errors:?}");
+    }
+
+    #[rstest]
+    #[case::delegated_domain("www.a.example.com", true)]
+    #[case::allowed_domain("login.example.com", true)]
+    #[case::external_host("evil.example.org", false)]
+    fn redirect_filters(#[case] hostname: &str, #[case] is_valid: bool) {
+        let rules = vec![HttpRouteRule {
+            filters: vec![redirect_filter(hostname)],
+            backend_refs: vec![],
+        }];
+        let errors = validate_hostnames(
+            &route(vec!["shop.a.example.com"], rules),
+            "team-a",
+            &settings(),
+        );
+        assert_eq!(errors.is_empty(), is_valid, "errors: {errors:?}");
+    }
+
+    #[test]
+    fn redirect_filters_without_restrictions() {
+        let rules = vec![HttpRouteRule {
+            filters: vec![redirect_filter("evil.example.org")],
+            backend_refs: vec![],
+        }];
+        let errors = validate_hostnames(&route(vec![], rules), "team-a", &Settings::default());
+        assert!(errors.is_empty(), "errors: {errors:?}");
+    }
+
+    #[rstest]
+    #[case::same_namespace(backend_ref(None, "web"), vec![], true)]
+    #[case::explicit_same_namespace(backend_ref(Some("team-a"), "web"), vec![], true)]
+    #[case::granted_backend(backend_ref(Some("shared"), "web"), vec![reference_grant("team-a", None)], true)]
+    #[case::granted_backend_by_name(backend_ref(Some("shared"), "web"), vec![reference_grant("team-a", Some("web"))], true)]
+    #[case::other_backend_name(backend_ref(Some("shared"), "web"), vec![reference_grant("team-a", Some("api"))], false)]
+    #[case::grant_for_other_namespace(backend_ref(Some("shared"), "web"), vec![reference_grant("team-b", None)], false)]
+    #[case::no_grants(backend_ref(Some("shared"), "web"), vec![], false)]
+    fn backend_refs(
+        #[case] backend_ref: HttpBackendRef,
+        #[case] grants: Vec<ReferenceGrant>,
+        #[case] is_valid: bool,
+    ) {
+        let rules = vec![HttpRouteRule {
+            filters: vec![],
+            backend_refs: vec![backend_ref],
+        }];
+        let errors = validate_backend_refs(&route(vec![], rules), "team-a", |_| Ok(grants.clone()));
+        assert_eq!(errors.is_empty(), is_valid, "errors: {errors:?}");
+    }
+
+    #[test]
+    fn backend_refs_lookup_failure() {
+        let rules = vec![HttpRouteRule {
+            filters: vec![],
+            backend_refs: vec![backend_ref(Some("shared"), "web")],
+        }];
+        let errors = validate_backend_refs(&route(vec![], rules), "team-a", |_| {
+            Err("lookup failed".to_owned())
+        });
+        assert_eq!(errors, vec!["lookup failed".to_owned()]);
+    }
+
+    #[rstest]
+    #[case::valid_route("test_data/httproute_valid.json", true)]
+    #[case::external_redirect("test_data/httproute_external_redirect.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Domains delegated to each namespace. Route hostnames must belong to
+    /// one of the domains delegated to the namespace of the HTTPRoute.
+    pub delegated_domains: BTreeMap<String, HashSet<String>>,
+    /// Backends living in other namespaces must be allowed by a
+    /// ReferenceGrant
+    pub require_reference_grant: bool,
+    /// Domains, besides the delegated ones, RequestRedirect filters can
+    /// redirect to
+    pub allowed_redirect_domains: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            delegated_domains: BTreeMap::new(),
+            require_reference_grant: true,
+            allowed_redirect_domains: HashSet::new(),
+        }
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if let Some((namespace, _)) = self
+            .delegated_domains
+            .iter()
+            .find(|(_, domains)| domains.is_empty() || domains.iter().any(|d| d.is_empty()))
+        {
+            return Err(format!(
+                "delegatedDomains of namespace '{namespace}' cannot be empty"
+            ));
+        }
+        if self.allowed_redirect_domains.iter().any(|d| d.is_empty()) {
+            return Err("allowedRedirectDomains cannot contain empty domains".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(Settings::default(), true)]
+    #[case::empty_domains(Settings {
+        delegated_domains: BTreeMap::from([("team-a".to_owned(), HashSet::new())]),
+        ..Default::default()
+    }, false)]
+    #[case::empty_redirect_domain(Settings {
+        allowed_redirect_domains: HashSet::from([String::new()]),
+        ..Default::default()
+    }, false)]
+    #[case::valid(Settings {
+        delegated_domains: BTreeMap::from([("team-a".to_owned(), HashSet::from(["a.example.com".to_owned()]))]),
+        allowed_redirect_domains: HashSet::from(["login.example.com".to_owned()]),
+        ..Default::default()
+    }, true)]
+    fn validate_settings(#[case] settings: Settings, #[case] is_valid: bool) {
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
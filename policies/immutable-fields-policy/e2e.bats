@@ -0,0 +1,43 @@
+#!/usr/bin/env bats
+
+SETTINGS='{"rules": [{"kind": "PersistentVolumeClaim", "paths": ["$.spec.storageClassName"]}], "exemptGroups": ["storage-admins"]}'
+
+@test "Reject an update changing an immutable field" {
+	run kwctl run --request-path test_data/pvc_update_storage_class.json --settings-json "$SETTINGS" annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*PersistentVolumeClaim 'data': the following fields cannot be changed: \$.spec.storageClassName.*") -ne 0 ]
+}
+
+@test "Accept an update changing other fields" {
+	run kwctl run --request-path test_data/pvc_update_size.json --settings-json "$SETTINGS" annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept an update changing an immutable field by an exempt group" {
+	run kwctl run --request-path test_data/pvc_update_storage_class_by_admin.json --settings-json "$SETTINGS" annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pvc_update_size.json --settings-json '{"rules": [{"kind": "PersistentVolumeClaim", "paths": []}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
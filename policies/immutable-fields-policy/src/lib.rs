@@ -0,0 +1,130 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    if request.operation != "UPDATE" {
+        return kubewarden::accept_request();
+    }
+    let username = request.user_info.username.unwrap_or_default();
+    let groups = request.user_info.groups.unwrap_or_default();
+    if settings.is_exempt(&username, &groups) {
+        return kubewarden::accept_request();
+    }
+
+    let changed_paths: Vec<&str> = settings
+        .rules
+        .iter()
+        .filter(|rule| rule.matches(&request.kind.group, &request.kind.kind))
+        .flat_map(|rule| rule.changed_paths(&request.old_object, &request.object))
+        .collect();
+    if changed_paths.is_empty() {
+        return kubewarden::accept_request();
+    }
+    kubewarden::reject_request(
+        Some(format!(
+            "{} '{}': the following fields cannot be changed: {}",
+            request.kind.kind,
+            request.name,
+            changed_paths.join(", ")
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    use settings::Rule;
+
+    fn settings() -> Settings {
+        Settings {
+            rules: vec![Rule {
+                group: "".to_owned(),
+                kind: "PersistentVolumeClaim".to_owned(),
+                paths: vec!["$.spec.storageClassName".to_owned()],
+            }],
+            exempt_groups: ["storage-admins".to_owned()].into(),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::storage_class_changed("test_data/pvc_update_storage_class.json", settings(), false)]
+    #[case::size_changed("test_data/pvc_update_size.json", settings(), true)]
+    #[case::exempt_group("test_data/pvc_update_storage_class_by_admin.json", settings(), true)]
+    #[case::exempt_user(
+        "test_data/pvc_update_storage_class.json",
+        Settings {
+            exempt_users: ["developer".to_owned()].into(),
+            ..settings()
+        },
+        true
+    )]
+    #[case::other_kind(
+        "test_data/pvc_update_storage_class.json",
+        Settings {
+            rules: vec![Rule {
+                group: "".to_owned(),
+                kind: "Service".to_owned(),
+                paths: vec!["$.spec.storageClassName".to_owned()],
+            }],
+            ..Default::default()
+        },
+        true
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/pvc_update_storage_class.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "PersistentVolumeClaim 'data': the following fields cannot be changed: $.spec.storageClassName"
+            )
+        );
+    }
+}
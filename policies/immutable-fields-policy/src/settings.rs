@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+
+use jsonpath_lib as jsonpath;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Fields of the resources of a kind that cannot be changed
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Rule {
+    /// API group of the kind, empty for the core group
+    pub group: String,
+    /// Name of the kind
+    pub kind: String,
+    /// JSONPath expressions selecting the immutable fields
+    pub paths: Vec<String>,
+}
+
+impl Rule {
+    /// Returns true when the rule applies to the given kind
+    pub fn matches(&self, group: &str, kind: &str) -> bool {
+        self.group == group && self.kind == kind
+    }
+
+    /// Returns the paths whose value differs between the two objects
+    pub fn changed_paths<'a>(&'a self, old_object: &Value, object: &Value) -> Vec<&'a str> {
+        self.paths
+            .iter()
+            .filter(|path| {
+                // The paths have been validated with the settings
+                jsonpath::select(old_object, path).ok() != jsonpath::select(object, path).ok()
+            })
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    pub rules: Vec<Rule>,
+    /// Users allowed to change the immutable fields
+    pub exempt_users: HashSet<String>,
+    /// Groups of users allowed to change the immutable fields
+    pub exempt_groups: HashSet<String>,
+}
+
+impl Settings {
+    /// Returns true when the user, member of the groups, can change the
+    /// immutable fields
+    pub fn is_exempt(&self, username: &str, groups: &[String]) -> bool {
+        self.exempt_users.contains(username)
+            || groups
+                .iter()
+                .any(|group| self.exempt_groups.contains(group))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.rules.is_empty() {
+            return Err("at least one rule must be provided".to_owned());
+        }
+        for rule in &self.rules {
+            if rule.kind.is_empty() {
+                return Err("the kind of the rules cannot be empty".to_owned());
+            }
+            if rule.paths.is_empty() {
+                return Err(format!("rule of kind '{}': no paths provided", rule.kind));
+            }
+            for path in &rule.paths {
+                jsonpath::select(&Value::Null, path).map_err(|e| {
+                    format!("rule of kind '{}': invalid path '{path}': {e}", rule.kind)
+                })?;
+            }
+        }
+        if self.exempt_users.contains("") {
+            return Err("exemptUsers cannot contain empty values".to_owned());
+        }
+        if self.exempt_groups.contains("") {
+            return Err("exemptGroups cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+    use serde_json::json;
+
+    #[rstest]
+    #[case::unchanged(
+        json!({"spec": {"storageClassName": "standard"}}),
+        json!({"spec": {"storageClassName": "standard"}}),
+        vec![]
+    )]
+    #[case::changed(
+        json!({"spec": {"storageClassName": "standard"}}),
+        json!({"spec": {"storageClassName": "fast"}}),
+        vec!["$.spec.storageClassName"]
+    )]
+    #[case::added(
+        json!({"spec": {}}),
+        json!({"spec": {"storageClassName": "fast"}}),
+        vec!["$.spec.storageClassName"]
+    )]
+    #[case::removed(
+        json!({"spec": {"storageClassName": "standard"}}),
+        json!({"spec": {}}),
+        vec!["$.spec.storageClassName"]
+    )]
+    #[case::missing(json!({"spec": {}}), json!({"spec": {}}), vec![])]
+    #[case::other_field_changed(
+        json!({"spec": {"storageClassName": "standard", "volumeName": "a"}}),
+        json!({"spec": {"storageClassName": "standard", "volumeName": "b"}}),
+        vec![]
+    )]
+    #[case::wildcard(
+        json!({"spec": {"ports": [{"port": 80}, {"port": 443}]}}),
+        json!({"spec": {"ports": [{"port": 80}, {"port": 8443}]}}),
+        vec!["$.spec.ports[*].port"]
+    )]
+    fn changed_paths(
+        #[case] old_object: Value,
+        #[case] object: Value,
+        #[case] expected: Vec<&str>,
+    ) {
+        let rule = Rule {
+            kind: "PersistentVolumeClaim".to_owned(),
+            paths: vec![
+                "$.spec.storageClassName".to_owned(),
+                "$.spec.ports[*].port".to_owned(),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(rule.changed_paths(&old_object, &object), expected);
+    }
+
+    #[rstest]
+    #[case::no_rules(r#"{}"#, false)]
+    #[case::rules(
+        r#"{"rules": [{"kind": "PersistentVolumeClaim", "paths": ["$.spec.storageClassName"]}], "exemptGroups": ["storage-admins"]}"#,
+        true
+    )]
+    #[case::without_kind(r#"{"rules": [{"paths": ["$.spec.storageClassName"]}]}"#, false)]
+    #[case::without_paths(r#"{"rules": [{"kind": "PersistentVolumeClaim"}]}"#, false)]
+    #[case::invalid_path(
+        r#"{"rules": [{"kind": "PersistentVolumeClaim", "paths": ["$.spec[storageClassName"]}]}"#,
+        false
+    )]
+    #[case::empty_user(
+        r#"{"rules": [{"kind": "Service", "paths": ["$.spec.type"]}], "exemptUsers": [""]}"#,
+        false
+    )]
+    #[case::empty_group(
+        r#"{"rules": [{"kind": "Service", "paths": ["$.spec.type"]}], "exemptGroups": [""]}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
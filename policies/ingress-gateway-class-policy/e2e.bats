@@ -0,0 +1,42 @@
+#!/usr/bin/env bats
+
+@test "Accept an IngressClass using an allowed controller" {
+	run kwctl run --request-path test_data/ingressclass_nginx.json --settings-json '{"allowedControllers": ["k8s.io/ingress-nginx"], "allowedGroups": ["network-admins"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject an IngressClass created by a group that is not allowed" {
+	run kwctl run --request-path test_data/ingressclass_nginx.json --settings-json '{"allowedControllers": ["k8s.io/ingress-nginx"], "allowedGroups": ["platform-admins"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*the user is not allowed to manage IngressClass objects.*') -ne 0 ]
+}
+
+@test "Reject a GatewayClass using a rogue controller" {
+	run kwctl run --request-path test_data/gatewayclass_rogue.json --settings-json '{"allowedControllers": ["gateway.envoyproxy.io/gatewayclass-controller"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*GatewayClass cannot use the controller 'example.com/rogue-controller'.*") -ne 0 ]
+}
+
+@test "Reject settings without allowed controllers" {
+	run kwctl run --request-path test_data/ingressclass_nginx.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,127 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::networking::v1::IngressClass;
+use serde::Deserialize;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+/// Minimal representation of a Gateway API GatewayClass, only its
+/// controller is checked by the policy
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct GatewayClass {
+    #[serde(default)]
+    spec: GatewayClassSpec,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct GatewayClassSpec {
+    #[serde(default)]
+    controller_name: String,
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let kind = request.kind.kind.as_str();
+    let controller = match kind {
+        "IngressClass" => serde_json::from_value::<IngressClass>(request.object)?
+            .spec
+            .and_then(|spec| spec.controller)
+            .unwrap_or_default(),
+        "GatewayClass" => {
+            serde_json::from_value::<GatewayClass>(request.object)?
+                .spec
+                .controller_name
+        }
+        _ => return kubewarden::accept_request(),
+    };
+
+    if let Err(e) = validate_class(
+        kind,
+        &controller,
+        &request.user_info.groups.unwrap_or_default(),
+        settings,
+    ) {
+        return kubewarden::reject_request(Some(e), None, None, None);
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_class(
+    kind: &str,
+    controller: &str,
+    groups: &[String],
+    settings: &Settings,
+) -> Result<(), String> {
+    if !settings.is_group_allowed(groups) {
+        return Err(format!("the user is not allowed to manage {kind} objects"));
+    }
+    if !settings.allowed_controllers.contains(controller) {
+        return Err(format!(
+            "{kind} cannot use the controller '{controller}', it's not part of the allowed controllers"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            allowed_controllers: [
+                "k8s.io/ingress-nginx".to_owned(),
+                "gateway.envoyproxy.io/gatewayclass-controller".to_owned(),
+            ]
+            .into(),
+            allowed_groups: ["network-admins".to_owned()].into(),
+        }
+    }
+
+    #[rstest]
+    #[case::allowed_controller("k8s.io/ingress-nginx", vec!["network-admins"], true)]
+    #[case::rogue_controller("example.com/rogue-controller", vec!["network-admins"], false)]
+    #[case::missing_controller("", vec!["network-admins"], false)]
+    #[case::not_allowed_group("k8s.io/ingress-nginx", vec!["system:authenticated"], false)]
+    fn classes(#[case] controller: &str, #[case] groups: Vec<&str>, #[case] is_valid: bool) {
+        let groups: Vec<String> = groups.into_iter().map(String::from).collect();
+        assert_eq!(
+            validate_class("IngressClass", controller, &groups, &settings()).is_ok(),
+            is_valid
+        );
+    }
+
+    #[rstest]
+    #[case::allowed_ingress_class("test_data/ingressclass_nginx.json", true)]
+    #[case::rogue_gateway_class("test_data/gatewayclass_rogue.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Controllers that can be referenced by the IngressClass and the
+    /// GatewayClass objects
+    pub allowed_controllers: HashSet<String>,
+    /// Groups allowed to create and update the IngressClass and the
+    /// GatewayClass objects. When empty, all the users are allowed.
+    pub allowed_groups: HashSet<String>,
+}
+
+impl Settings {
+    /// Returns true when one of the groups of the user is allowed to manage
+    /// the classes
+    pub fn is_group_allowed(&self, groups: &[String]) -> bool {
+        self.allowed_groups.is_empty()
+            || groups
+                .iter()
+                .any(|group| self.allowed_groups.contains(group))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_controllers.is_empty() {
+            return Err("allowedControllers cannot be empty".to_owned());
+        }
+        if self.allowed_controllers.contains("") {
+            return Err("allowedControllers cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::no_restriction(vec![], vec!["system:authenticated"], true)]
+    #[case::allowed_group(vec!["network-admins"], vec!["system:authenticated", "network-admins"], true)]
+    #[case::not_allowed_group(vec!["network-admins"], vec!["system:authenticated"], false)]
+    #[case::no_groups(vec!["network-admins"], vec![], false)]
+    fn group_allowed(
+        #[case] allowed_groups: Vec<&str>,
+        #[case] groups: Vec<&str>,
+        #[case] expected: bool,
+    ) {
+        let settings = Settings {
+            allowed_groups: allowed_groups.into_iter().map(String::from).collect(),
+            ..Default::default()
+        };
+        let groups: Vec<String> = groups.into_iter().map(String::from).collect();
+        assert_eq!(settings.is_group_allowed(&groups), expected);
+    }
+
+    #[rstest]
+    #[case::valid(vec!["k8s.io/ingress-nginx"], true)]
+    #[case::no_controllers(vec![], false)]
+    #[case::empty_controller(vec!["k8s.io/ingress-nginx", ""], false)]
+    fn validate_settings(#[case] allowed_controllers: Vec<&str>, #[case] is_valid: bool) {
+        let settings = Settings {
+            allowed_controllers: allowed_controllers.into_iter().map(String::from).collect(),
+            ..Default::default()
+        };
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
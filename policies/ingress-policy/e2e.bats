@@ -47,6 +47,39 @@
   [ "$status" -eq 1 ]
 }
 
+@test "reject because a risky annotation is used" {
+  run kwctl run annotated-policy.wasm -r test_data/ingress-with-configuration-snippet.json --settings-json '{}'
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  # request rejected
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*allowed.*false') -ne 0 ]
+  [ $(expr "$output" : '.*these annotations are not allowed: nginx.ingress.kubernetes.io/configuration-snippet.*') -ne 0 ]
+}
+
+@test "accept a risky annotation inside of an exempt namespace" {
+  run kwctl run annotated-policy.wasm -r test_data/ingress-with-configuration-snippet.json --settings-json '{"deniedAnnotationsExemptNamespaces": ["default"]}'
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  # request accepted
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*allowed.*true') -ne 0 ]
+}
+
+@test "reject because invalid denied annotation pattern" {
+  run kwctl run annotated-policy.wasm -r test_data/ingress-wildcard.json --settings-json '{"deniedAnnotations": ["(snippet"]}'
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  # settings validation fails
+  [ "$status" -eq 1 ]
+}
+
 @test "accept" {
   run kwctl run annotated-policy.wasm -r test_data/single-backend-with-tls-termination.json --settings-json '{"requireTLS": true, "denyPorts": [3000]}'
   # this prints the output when one the checks below fails
@@ -56,3 +89,47 @@
   [ "$status" -eq 0 ]
   [ $(expr "$output" : '.*allowed.*true') -ne 0 ]
 }
+
+@test "accept an Ingress referencing an existing TLS secret" {
+  run kwctl run annotated-policy.wasm -r test_data/ingress-with-tls-secret.json \
+    --settings-json '{"validateTLSSecrets": true}' \
+    --allow-context-aware \
+    --replay-host-capabilities-interactions test_data/session-tls-secret-found.yml
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  # request accepted
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*allowed.*true') -ne 0 ]
+}
+
+@test "reject an Ingress referencing a secret that is not of type kubernetes.io/tls" {
+  run kwctl run annotated-policy.wasm -r test_data/ingress-with-tls-secret.json \
+    --settings-json '{"validateTLSSecrets": true}' \
+    --allow-context-aware \
+    --replay-host-capabilities-interactions test_data/session-tls-secret-opaque.yml
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  # request rejected
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*allowed.*false') -ne 0 ]
+  [ $(expr "$output" : ".*TLS secret 'testsecret-tls' is of type 'Opaque'.*") -ne 0 ]
+}
+
+@test "reject an Ingress referencing a missing TLS secret" {
+  run kwctl run annotated-policy.wasm -r test_data/ingress-with-tls-secret.json \
+    --settings-json '{"validateTLSSecrets": true}' \
+    --allow-context-aware \
+    --replay-host-capabilities-interactions test_data/session-tls-secret-not-found.yml
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  # request rejected
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*allowed.*false') -ne 0 ]
+  [ $(expr "$output" : ".*TLS secret 'testsecret-tls' cannot be found.*") -ne 0 ]
+}
@@ -15,7 +15,12 @@ require (
 replace github.com/go-openapi/strfmt => github.com/kubewarden/strfmt v0.1.3
 
 require (
+	github.com/davecgh/go-spew v1.1.1 // indirect
 	github.com/go-openapi/strfmt v0.21.3 // indirect
+	github.com/pmezard/go-difflib v1.0.0 // indirect
+	github.com/stretchr/objx v0.5.2 // indirect
+	github.com/stretchr/testify v1.11.1 // indirect
 	github.com/tidwall/match v1.0.3 // indirect
 	github.com/tidwall/pretty v1.0.2 // indirect
+	gopkg.in/yaml.v3 v3.0.1 // indirect
 )
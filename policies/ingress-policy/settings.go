@@ -2,16 +2,30 @@ package main
 
 import (
 	"encoding/json"
+	"fmt"
+	"regexp"
 
 	mapset "github.com/deckarep/golang-set/v2"
 	kubewarden "github.com/kubewarden/policy-sdk-go"
 	kubewarden_protocol "github.com/kubewarden/policy-sdk-go/protocol"
 )
 
+// Annotations of the ingress controllers that allow to inject arbitrary
+// configuration, or to use regular expressions inside of the paths. These
+// led to escalations like CVE-2021-25742.
+var builtinDeniedAnnotations = []string{
+	`^nginx\.ingress\.kubernetes\.io/[a-z-]*snippet$`,
+	`^nginx\.ingress\.kubernetes\.io/use-regex$`,
+	`^nginx\.org/[a-z-]*snippets$`,
+}
+
 type Settings struct {
-	RequireTls bool               `json:"requireTLS"`
-	AllowPorts mapset.Set[uint64] `json:"allowPorts"`
-	DenyPorts  mapset.Set[uint64] `json:"denyPorts"`
+	RequireTls                        bool               `json:"requireTLS"`
+	AllowPorts                        mapset.Set[uint64] `json:"allowPorts"`
+	DenyPorts                         mapset.Set[uint64] `json:"denyPorts"`
+	DeniedAnnotations                 []string           `json:"deniedAnnotations"`
+	DeniedAnnotationsExemptNamespaces mapset.Set[string] `json:"deniedAnnotationsExemptNamespaces"`
+	ValidateTlsSecrets                bool               `json:"validateTLSSecrets"`
 }
 
 func NewSettingsFromValidationReq(validationReq *kubewarden_protocol.ValidationRequest) (Settings, error) {
@@ -31,13 +45,30 @@ func (s *Settings) Valid() bool {
 	return common.Cardinality() == 0
 }
 
+// Returns the compiled regular expressions matching the denied annotation
+// keys: the built-in ones followed by the ones provided by the user
+func (s *Settings) DeniedAnnotationPatterns() ([]*regexp.Regexp, error) {
+	patterns := make([]*regexp.Regexp, 0, len(builtinDeniedAnnotations)+len(s.DeniedAnnotations))
+	for _, pattern := range append(builtinDeniedAnnotations, s.DeniedAnnotations...) {
+		re, err := regexp.Compile(pattern)
+		if err != nil {
+			return nil, fmt.Errorf("invalid denied annotation pattern '%s': %w", pattern, err)
+		}
+		patterns = append(patterns, re)
+	}
+	return patterns, nil
+}
+
 func (s *Settings) UnmarshalJSON(data []byte) error {
 	// This is needed becaus golang-set v2.3.0 has a bug that prevents
 	// the correct unmarshalling of ThreadUnsafeSet types.
 	rawSettings := struct {
-		RequireTls bool     `json:"requireTLS"`
-		AllowPorts []uint64 `json:"allowPorts"`
-		DenyPorts  []uint64 `json:"denyPorts"`
+		RequireTls                        bool     `json:"requireTLS"`
+		AllowPorts                        []uint64 `json:"allowPorts"`
+		DenyPorts                         []uint64 `json:"denyPorts"`
+		DeniedAnnotations                 []string `json:"deniedAnnotations"`
+		DeniedAnnotationsExemptNamespaces []string `json:"deniedAnnotationsExemptNamespaces"`
+		ValidateTlsSecrets                bool     `json:"validateTLSSecrets"`
 	}{}
 
 	err := json.Unmarshal(data, &rawSettings)
@@ -48,6 +79,9 @@ func (s *Settings) UnmarshalJSON(data []byte) error {
 	s.RequireTls = rawSettings.RequireTls
 	s.AllowPorts = mapset.NewThreadUnsafeSet[uint64](rawSettings.AllowPorts...)
 	s.DenyPorts = mapset.NewThreadUnsafeSet[uint64](rawSettings.DenyPorts...)
+	s.DeniedAnnotations = rawSettings.DeniedAnnotations
+	s.DeniedAnnotationsExemptNamespaces = mapset.NewThreadUnsafeSet[string](rawSettings.DeniedAnnotationsExemptNamespaces...)
+	s.ValidateTlsSecrets = rawSettings.ValidateTlsSecrets
 
 	return nil
 }
@@ -59,9 +93,13 @@ func validateSettings(payload []byte) ([]byte, error) {
 		return []byte{}, err
 	}
 
-	if settings.Valid() {
-		return kubewarden.AcceptSettings()
+	if !settings.Valid() {
+		return kubewarden.RejectSettings(kubewarden.Message("No port can be allowed and denied at the same time"))
+	}
+
+	if _, err := settings.DeniedAnnotationPatterns(); err != nil {
+		return kubewarden.RejectSettings(kubewarden.Message(err.Error()))
 	}
 
-	return kubewarden.RejectSettings(kubewarden.Message("No port can be allowed and denied at the same time"))
+	return kubewarden.AcceptSettings()
 }
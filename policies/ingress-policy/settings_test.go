@@ -165,3 +165,60 @@ func TestSettingsAreNotValid(t *testing.T) {
 		t.Errorf("Settings are reported as Valid")
 	}
 }
+
+func TestParsingDeniedAnnotationsSettings(t *testing.T) {
+	request := `
+	{
+		"deniedAnnotations": [ "^example\\.com/.*" ],
+		"deniedAnnotationsExemptNamespaces": [ "ingress-nginx" ]
+	}
+	`
+	settings := Settings{}
+	err := json.Unmarshal([]byte(request), &settings)
+	if err != nil {
+		t.Errorf("Unexpected error %+v", err)
+	}
+
+	if len(settings.DeniedAnnotations) != 1 || settings.DeniedAnnotations[0] != `^example\.com/.*` {
+		t.Errorf("Wrong value for DeniedAnnotations: %v", settings.DeniedAnnotations)
+	}
+
+	if !settings.DeniedAnnotationsExemptNamespaces.Contains("ingress-nginx") {
+		t.Errorf("Missing value from DeniedAnnotationsExemptNamespaces")
+	}
+
+	patterns, err := settings.DeniedAnnotationPatterns()
+	if err != nil {
+		t.Errorf("Unexpected error %+v", err)
+	}
+	if len(patterns) != len(builtinDeniedAnnotations)+1 {
+		t.Errorf("Got %d patterns instead of %d", len(patterns), len(builtinDeniedAnnotations)+1)
+	}
+}
+
+func TestDeniedAnnotationPatternsAreNotValid(t *testing.T) {
+	settings := Settings{
+		DeniedAnnotations: []string{"example.com/(snippet"},
+	}
+
+	if _, err := settings.DeniedAnnotationPatterns(); err == nil {
+		t.Errorf("Invalid pattern not reported")
+	}
+}
+
+func TestParsingValidateTlsSecretsSettings(t *testing.T) {
+	settings := Settings{}
+	if err := json.Unmarshal([]byte(`{}`), &settings); err != nil {
+		t.Errorf("Unexpected error %+v", err)
+	}
+	if settings.ValidateTlsSecrets {
+		t.Errorf("ValidateTlsSecrets should be disabled by default")
+	}
+
+	if err := json.Unmarshal([]byte(`{"validateTLSSecrets": true}`), &settings); err != nil {
+		t.Errorf("Unexpected error %+v", err)
+	}
+	if !settings.ValidateTlsSecrets {
+		t.Errorf("Wrong value for ValidateTlsSecrets")
+	}
+}
@@ -3,13 +3,22 @@ package main
 import (
 	"encoding/json"
 	"fmt"
+	"sort"
+	"strings"
 
 	mapset "github.com/deckarep/golang-set/v2"
 	"github.com/kubewarden/gjson"
+	corev1 "github.com/kubewarden/k8s-objects/api/core/v1"
 	kubewarden "github.com/kubewarden/policy-sdk-go"
+	"github.com/kubewarden/policy-sdk-go/pkg/capabilities"
+	kubernetes "github.com/kubewarden/policy-sdk-go/pkg/capabilities/kubernetes"
 	kubewarden_protocol "github.com/kubewarden/policy-sdk-go/protocol"
 )
 
+const tlsSecretType = "kubernetes.io/tls"
+
+var host = capabilities.NewHost()
+
 func validate(payload []byte) ([]byte, error) {
 	validationRequest := kubewarden_protocol.ValidationRequest{}
 	err := json.Unmarshal(payload, &validationRequest)
@@ -45,6 +54,18 @@ func validate(payload []byte) ([]byte, error) {
 			kubewarden.NoCode)
 	}
 
+	if err := checkDeniedAnnotations(payload, validationRequest.Request.Namespace, &settings); err != nil {
+		return kubewarden.RejectRequest(
+			kubewarden.Message(err.Error()),
+			kubewarden.NoCode)
+	}
+
+	if err := checkTlsSecrets(payload, validationRequest.Request.Namespace, &settings); err != nil {
+		return kubewarden.RejectRequest(
+			kubewarden.Message(err.Error()),
+			kubewarden.NoCode)
+	}
+
 	return kubewarden.AcceptRequest()
 }
 
@@ -115,3 +136,93 @@ func checkDeniedPorts(ports mapset.Set[uint64], settings *Settings) error {
 
 	return fmt.Errorf("these ports are explicitly denied: %v", denied)
 }
+
+func checkDeniedAnnotations(payload []byte, namespace string, settings *Settings) error {
+	if settings.DeniedAnnotationsExemptNamespaces != nil &&
+		settings.DeniedAnnotationsExemptNamespaces.Contains(namespace) {
+		return nil
+	}
+
+	patterns, err := settings.DeniedAnnotationPatterns()
+	if err != nil {
+		return err
+	}
+
+	denied := []string{}
+	data := gjson.GetBytes(payload, "request.object.metadata.annotations")
+	data.ForEach(func(key, _ gjson.Result) bool {
+		for _, pattern := range patterns {
+			if pattern.MatchString(key.String()) {
+				denied = append(denied, key.String())
+				break
+			}
+		}
+		return true
+	})
+	if len(denied) == 0 {
+		return nil
+	}
+
+	sort.Strings(denied)
+	return fmt.Errorf("these annotations are not allowed: %s", strings.Join(denied, ", "))
+}
+
+func checkTlsSecrets(payload []byte, namespace string, settings *Settings) error {
+	if !settings.ValidateTlsSecrets {
+		return nil
+	}
+
+	errs := []string{}
+	data := gjson.GetBytes(payload, "request.object.spec.tls.#.secretName")
+	data.ForEach(func(_, entry gjson.Result) bool {
+		if err := checkTlsSecret(entry.String(), namespace); err != nil {
+			errs = append(errs, err.Error())
+		}
+		return true
+	})
+	if len(errs) == 0 {
+		return nil
+	}
+
+	return fmt.Errorf("%s", strings.Join(errs, ", "))
+}
+
+// Some ingress controllers accept TLS secrets expressed as
+// `<namespace>/<name>`. Only the secrets defined inside of the namespace of
+// the Ingress are allowed.
+func checkTlsSecret(secretName, namespace string) error {
+	name := secretName
+	if secretNamespace, secret, found := strings.Cut(secretName, "/"); found {
+		if secretNamespace != namespace {
+			return fmt.Errorf("TLS secret '%s' is not defined inside of the namespace of the Ingress", secretName)
+		}
+		name = secret
+	}
+
+	// The secret is listed by name: an empty list means the secret does not
+	// exist, while an error is a failure of the host
+	fieldSelector := "metadata.name=" + name
+	secretsRaw, err := kubernetes.ListResourcesByNamespace(&host, kubernetes.ListResourcesByNamespaceRequest{
+		APIVersion:    "v1",
+		Kind:          "Secret",
+		Namespace:     namespace,
+		FieldSelector: &fieldSelector,
+	})
+	if err != nil {
+		return fmt.Errorf("cannot read TLS secret '%s': %w", secretName, err)
+	}
+
+	secrets := corev1.SecretList{}
+	if err := json.Unmarshal(secretsRaw, &secrets); err != nil {
+		return fmt.Errorf("cannot parse TLS secret '%s': %w", secretName, err)
+	}
+	if len(secrets.Items) == 0 {
+		return fmt.Errorf("TLS secret '%s' cannot be found", secretName)
+	}
+	secret := secrets.Items[0]
+	if secret.Type != tlsSecretType {
+		return fmt.Errorf("TLS secret '%s' is of type '%s' instead of '%s'", secretName, secret.Type, tlsSecretType)
+	}
+
+	return nil
+}
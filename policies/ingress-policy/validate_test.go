@@ -2,10 +2,15 @@ package main
 
 import (
 	"encoding/json"
+	"fmt"
 	"testing"
 
 	mapset "github.com/deckarep/golang-set/v2"
 
+	corev1 "github.com/kubewarden/k8s-objects/api/core/v1"
+	metav1 "github.com/kubewarden/k8s-objects/apimachinery/pkg/apis/meta/v1"
+	"github.com/kubewarden/policy-sdk-go/pkg/capabilities/kubernetes"
+	"github.com/kubewarden/policy-sdk-go/pkg/capabilities/mocks"
 	kubewarden_protocol "github.com/kubewarden/policy-sdk-go/protocol"
 	kubewarden_testing "github.com/kubewarden/policy-sdk-go/testing"
 )
@@ -316,3 +321,281 @@ func TestValidationAccept(t *testing.T) {
 		t.Error("Unexpected rejection")
 	}
 }
+
+func TestCheckDeniedAnnotationsBuiltinPatterns(t *testing.T) {
+	settings := Settings{}
+
+	payload, err := kubewarden_testing.BuildValidationRequestFromFixture(
+		"test_data/ingress-with-configuration-snippet.json",
+		&settings)
+	if err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+
+	err = checkDeniedAnnotations(payload, "default", &settings)
+	if err == nil {
+		t.Fatalf("No error returned")
+	}
+
+	expectedMessage := "these annotations are not allowed: nginx.ingress.kubernetes.io/configuration-snippet"
+	if err.Error() != expectedMessage {
+		t.Errorf("Got '%s' instead of '%s'", err.Error(), expectedMessage)
+	}
+}
+
+func TestCheckDeniedAnnotationsUserPatterns(t *testing.T) {
+	settings := Settings{
+		DeniedAnnotations: []string{`^nginx\.ingress\.kubernetes\.io/ssl-.*`},
+	}
+
+	payload, err := kubewarden_testing.BuildValidationRequestFromFixture(
+		"test_data/ingress-with-configuration-snippet.json",
+		&settings)
+	if err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+
+	err = checkDeniedAnnotations(payload, "default", &settings)
+	if err == nil {
+		t.Fatalf("No error returned")
+	}
+
+	expectedMessage := "these annotations are not allowed: nginx.ingress.kubernetes.io/configuration-snippet, nginx.ingress.kubernetes.io/ssl-redirect"
+	if err.Error() != expectedMessage {
+		t.Errorf("Got '%s' instead of '%s'", err.Error(), expectedMessage)
+	}
+}
+
+func TestCheckDeniedAnnotationsExemptNamespace(t *testing.T) {
+	settings := Settings{
+		DeniedAnnotationsExemptNamespaces: mapset.NewThreadUnsafeSet[string]("default"),
+	}
+
+	payload, err := kubewarden_testing.BuildValidationRequestFromFixture(
+		"test_data/ingress-with-configuration-snippet.json",
+		&settings)
+	if err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+
+	if err := checkDeniedAnnotations(payload, "default", &settings); err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+}
+
+func TestCheckDeniedAnnotationsNoAnnotations(t *testing.T) {
+	settings := Settings{}
+
+	payload, err := kubewarden_testing.BuildValidationRequestFromFixture(
+		"test_data/ingress-wildcard.json",
+		&settings)
+	if err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+
+	if err := checkDeniedAnnotations(payload, "default", &settings); err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+}
+
+func TestValidationDeniedAnnotationsRejection(t *testing.T) {
+	settings := Settings{
+		RequireTls: true,
+		AllowPorts: mapset.NewThreadUnsafeSet[uint64](),
+		DenyPorts:  mapset.NewThreadUnsafeSet[uint64](),
+	}
+
+	payload, err := kubewarden_testing.BuildValidationRequestFromFixture(
+		"test_data/ingress-with-configuration-snippet.json",
+		&settings)
+	if err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+
+	responsePayload, err := validate(payload)
+	if err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+
+	var response kubewarden_protocol.ValidationResponse
+	if err := json.Unmarshal(responsePayload, &response); err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+
+	if response.Accepted != false {
+		t.Error("Unexpected approval")
+	}
+
+	expectedMessage := "these annotations are not allowed: nginx.ingress.kubernetes.io/configuration-snippet"
+	if *response.Message != expectedMessage {
+		t.Errorf("Got '%s' instead of '%s'", *response.Message, expectedMessage)
+	}
+}
+
+func secretLookupRequest(t *testing.T, name, namespace string) []byte {
+	fieldSelector := "metadata.name=" + name
+	wapcRequest, err := json.Marshal(&kubernetes.ListResourcesByNamespaceRequest{
+		APIVersion:    "v1",
+		Kind:          "Secret",
+		Namespace:     namespace,
+		FieldSelector: &fieldSelector,
+	})
+	if err != nil {
+		t.Fatalf("Cannot create wapcRequest payload: %+v", err)
+	}
+	return wapcRequest
+}
+
+// mockSecretLookup mocks the lookup of the secret. The host returns an empty
+// list when secret is nil.
+func mockSecretLookup(t *testing.T, name, namespace string, secret *corev1.Secret) {
+	secrets := corev1.SecretList{}
+	if secret != nil {
+		secrets.Items = append(secrets.Items, secret)
+	}
+
+	wapcResponse, err := json.Marshal(secrets)
+	if err != nil {
+		t.Fatalf("Cannot create wapcResponse payload: %+v", err)
+	}
+	wapcClient := mocks.NewMockWapcClient(t)
+	wapcClient.On("HostCall", "kubewarden", "kubernetes", "list_resources_by_namespace",
+		secretLookupRequest(t, name, namespace)).Return(wapcResponse, nil)
+	host.Client = wapcClient
+}
+
+func mockSecretLookupFailure(t *testing.T, name, namespace string, lookupErr error) {
+	wapcClient := mocks.NewMockWapcClient(t)
+	wapcClient.On("HostCall", "kubewarden", "kubernetes", "list_resources_by_namespace",
+		secretLookupRequest(t, name, namespace)).Return([]byte{}, lookupErr)
+	host.Client = wapcClient
+}
+
+func TestCheckTlsSecretOfTlsType(t *testing.T) {
+	mockSecretLookup(t, "testsecret-tls", "default", &corev1.Secret{
+		Metadata: &metav1.ObjectMeta{
+			Name:      "testsecret-tls",
+			Namespace: "default",
+		},
+		Type: "kubernetes.io/tls",
+	})
+
+	if err := checkTlsSecret("testsecret-tls", "default"); err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+}
+
+func TestCheckTlsSecretWithNamespaceOfTheIngress(t *testing.T) {
+	mockSecretLookup(t, "testsecret-tls", "default", &corev1.Secret{
+		Metadata: &metav1.ObjectMeta{
+			Name:      "testsecret-tls",
+			Namespace: "default",
+		},
+		Type: "kubernetes.io/tls",
+	})
+
+	if err := checkTlsSecret("default/testsecret-tls", "default"); err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+}
+
+func TestCheckTlsSecretOfOpaqueType(t *testing.T) {
+	mockSecretLookup(t, "testsecret-tls", "default", &corev1.Secret{
+		Metadata: &metav1.ObjectMeta{
+			Name:      "testsecret-tls",
+			Namespace: "default",
+		},
+		Type: "Opaque",
+	})
+
+	err := checkTlsSecret("testsecret-tls", "default")
+	if err == nil {
+		t.Fatalf("No error returned")
+	}
+
+	expectedMessage := "TLS secret 'testsecret-tls' is of type 'Opaque' instead of 'kubernetes.io/tls'"
+	if err.Error() != expectedMessage {
+		t.Errorf("Got '%s' instead of '%s'", err.Error(), expectedMessage)
+	}
+}
+
+func TestCheckTlsSecretMissing(t *testing.T) {
+	mockSecretLookup(t, "testsecret-tls", "default", nil)
+
+	err := checkTlsSecret("testsecret-tls", "default")
+	if err == nil {
+		t.Fatalf("No error returned")
+	}
+
+	expectedMessage := "TLS secret 'testsecret-tls' cannot be found"
+	if err.Error() != expectedMessage {
+		t.Errorf("Got '%s' instead of '%s'", err.Error(), expectedMessage)
+	}
+}
+
+func TestCheckTlsSecretLookupFailure(t *testing.T) {
+	mockSecretLookupFailure(t, "testsecret-tls", "default",
+		fmt.Errorf("secrets is forbidden: User cannot list resource"))
+
+	err := checkTlsSecret("testsecret-tls", "default")
+	if err == nil {
+		t.Fatalf("No error returned")
+	}
+
+	expectedMessage := "cannot read TLS secret 'testsecret-tls': secrets is forbidden: User cannot list resource"
+	if err.Error() != expectedMessage {
+		t.Errorf("Got '%s' instead of '%s'", err.Error(), expectedMessage)
+	}
+}
+
+func TestCheckTlsSecretOfAnotherNamespace(t *testing.T) {
+	// no lookup is expected
+	host.Client = mocks.NewMockWapcClient(t)
+
+	err := checkTlsSecret("kube-system/testsecret-tls", "default")
+	if err == nil {
+		t.Fatalf("No error returned")
+	}
+
+	expectedMessage := "TLS secret 'kube-system/testsecret-tls' is not defined inside of the namespace of the Ingress"
+	if err.Error() != expectedMessage {
+		t.Errorf("Got '%s' instead of '%s'", err.Error(), expectedMessage)
+	}
+}
+
+func TestValidationTlsSecretRejection(t *testing.T) {
+	settings := Settings{
+		RequireTls:         true,
+		AllowPorts:         mapset.NewThreadUnsafeSet[uint64](),
+		DenyPorts:          mapset.NewThreadUnsafeSet[uint64](),
+		ValidateTlsSecrets: true,
+	}
+
+	payload, err := kubewarden_testing.BuildValidationRequestFromFixture(
+		"test_data/ingress-with-tls-secret.json",
+		&settings)
+	if err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+
+	mockSecretLookup(t, "testsecret-tls", "default", nil)
+
+	responsePayload, err := validate(payload)
+	if err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+
+	var response kubewarden_protocol.ValidationResponse
+	if err := json.Unmarshal(responsePayload, &response); err != nil {
+		t.Errorf("Unexpected error: %+v", err)
+	}
+
+	if response.Accepted != false {
+		t.Error("Unexpected approval")
+	}
+
+	expectedMessage := "TLS secret 'testsecret-tls' cannot be found"
+	if *response.Message != expectedMessage {
+		t.Errorf("Got '%s' instead of '%s'", *response.Message, expectedMessage)
+	}
+}
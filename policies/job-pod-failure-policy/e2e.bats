@@ -0,0 +1,64 @@
+#!/usr/bin/env bats
+
+@test "Accept a small Job without podFailurePolicy" {
+	run kwctl run \
+		--request-path test_data/job_small.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a large Job with a valid podFailurePolicy" {
+	run kwctl run \
+		--request-path test_data/job_with_pod_failure_policy.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a large Job without podFailurePolicy" {
+	run kwctl run \
+		--request-path test_data/job_without_pod_failure_policy.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*podFailurePolicy must be set when parallelism is above 4.*') -ne 0 ]
+}
+
+@test "Reject a podFailurePolicy not ignoring the disruptions" {
+	run kwctl run \
+		--request-path test_data/job_invalid_pod_failure_policy.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*must ignore the Pods with the DisruptionTarget condition.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/job_small.json --settings-json '{"allowedActions": ["Retry"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,200 @@
+use guest::prelude::*;
+use k8s_openapi::api::batch::v1::{Job, JobSpec, PodFailurePolicy};
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+
+    let job = match serde_json::from_value::<Job>(request.object) {
+        Ok(job) => job,
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    if let Err(error) =
+        validate_job_spec(&job.spec.unwrap_or_default(), &validation_request.settings)
+    {
+        return kubewarden::reject_request(
+            Some(format!("Job '{}': {error}", request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+// Returns the reason why the Job must define a `podFailurePolicy`, if any.
+// All the Jobs must define it when no threshold is set.
+fn requirement(spec: &JobSpec, settings: &Settings) -> Option<String> {
+    // Kubernetes runs one Pod at a time, until one of them succeeds, when
+    // these fields are not set
+    let parallelism = spec.parallelism.unwrap_or(1);
+    let completions = spec.completions.unwrap_or(1);
+
+    match (
+        settings.parallelism_threshold,
+        settings.completions_threshold,
+    ) {
+        (None, None) => Some("podFailurePolicy must be set".to_owned()),
+        (Some(threshold), _) if parallelism > threshold => Some(format!(
+            "podFailurePolicy must be set when parallelism is above {threshold}"
+        )),
+        (_, Some(threshold)) if completions > threshold => Some(format!(
+            "podFailurePolicy must be set when completions are above {threshold}"
+        )),
+        _ => None,
+    }
+}
+
+// Returns true when the policy ignores the failures of the Pods disrupted by
+// the cluster: they do not count towards the backoff limit
+fn ignores_disruptions(pod_failure_policy: &PodFailurePolicy) -> bool {
+    pod_failure_policy.rules.iter().any(|rule| {
+        rule.action == "Ignore"
+            && rule
+                .on_pod_conditions
+                .iter()
+                .flatten()
+                .any(|pattern| pattern.type_ == "DisruptionTarget")
+    })
+}
+
+fn validate_job_spec(spec: &JobSpec, settings: &Settings) -> Result<(), String> {
+    let Some(pod_failure_policy) = spec.pod_failure_policy.as_ref() else {
+        return match requirement(spec, settings) {
+            Some(error) => Err(error),
+            None => Ok(()),
+        };
+    };
+
+    let mut errors = vec![];
+    if settings.require_disruption_rule && !ignores_disruptions(pod_failure_policy) {
+        errors.push(
+            "podFailurePolicy must ignore the Pods with the DisruptionTarget condition".to_owned(),
+        );
+    }
+    if !settings.allowed_actions.is_empty() {
+        for (index, rule) in pod_failure_policy.rules.iter().enumerate() {
+            if !settings.allowed_actions.contains(&rule.action) {
+                errors.push(format!(
+                    "podFailurePolicy rule {index}: action {} is not allowed, use one of: {}",
+                    rule.action,
+                    settings
+                        .allowed_actions
+                        .iter()
+                        .cloned()
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeSet;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            parallelism_threshold: Some(4),
+            completions_threshold: Some(10),
+            require_disruption_rule: true,
+            allowed_actions: BTreeSet::from(["FailJob".to_owned(), "Ignore".to_owned()]),
+        }
+    }
+
+    #[rstest]
+    #[case::small_job("test_data/job_small.json", true)]
+    #[case::large_job_with_policy("test_data/job_with_pod_failure_policy.json", true)]
+    #[case::large_job_without_policy("test_data/job_without_pod_failure_policy.json", false)]
+    #[case::invalid_policy("test_data/job_invalid_pod_failure_policy.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::large_job_without_policy(
+        "test_data/job_without_pod_failure_policy.json",
+        "Job 'render-frames': podFailurePolicy must be set when parallelism is above 4"
+    )]
+    #[case::invalid_policy(
+        "test_data/job_invalid_pod_failure_policy.json",
+        "Job 'render-frames': podFailurePolicy must ignore the Pods with the DisruptionTarget condition; podFailurePolicy rule 1: action Count is not allowed, use one of: FailJob, Ignore"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[rstest]
+    #[case::no_thresholds(None, None, None, None, true)]
+    #[case::small_job(Some(4), Some(10), Some(2), Some(8), false)]
+    #[case::parallelism_above(Some(4), Some(10), Some(5), Some(8), true)]
+    #[case::completions_above(Some(4), Some(10), Some(2), Some(11), true)]
+    #[case::defaults(Some(0), None, None, None, true)]
+    #[case::only_completions(None, Some(10), Some(50), None, false)]
+    fn required(
+        #[case] parallelism_threshold: Option<i32>,
+        #[case] completions_threshold: Option<i32>,
+        #[case] parallelism: Option<i32>,
+        #[case] completions: Option<i32>,
+        #[case] expected: bool,
+    ) {
+        let settings = Settings {
+            parallelism_threshold,
+            completions_threshold,
+            ..Default::default()
+        };
+        let spec = JobSpec {
+            parallelism,
+            completions,
+            ..Default::default()
+        };
+        assert_eq!(requirement(&spec, &settings).is_some(), expected);
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+pub(crate) const ACTIONS: &[&str] = &["FailJob", "FailIndex", "Ignore", "Count"];
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Jobs whose parallelism is above this value must define a
+    /// `podFailurePolicy`
+    pub parallelism_threshold: Option<i32>,
+    /// Jobs whose completions are above this value must define a
+    /// `podFailurePolicy`
+    pub completions_threshold: Option<i32>,
+    /// Require a rule ignoring the failures of the Pods disrupted by the
+    /// cluster, like the evicted or preempted ones
+    pub require_disruption_rule: bool,
+    /// Actions the rules can use. All the actions are allowed when empty.
+    pub allowed_actions: BTreeSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            parallelism_threshold: None,
+            completions_threshold: None,
+            require_disruption_rule: true,
+            allowed_actions: BTreeSet::new(),
+        }
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self
+            .parallelism_threshold
+            .is_some_and(|threshold| threshold < 0)
+        {
+            return Err("parallelismThreshold cannot be negative".to_owned());
+        }
+        if self
+            .completions_threshold
+            .is_some_and(|threshold| threshold < 0)
+        {
+            return Err("completionsThreshold cannot be negative".to_owned());
+        }
+        if let Some(unknown) = self
+            .allowed_actions
+            .iter()
+            .find(|action| !ACTIONS.contains(&action.as_str()))
+        {
+            return Err(format!(
+                "allowedActions: unknown value '{unknown}', use one of: {}",
+                ACTIONS.join(", ")
+            ));
+        }
+        if self.require_disruption_rule
+            && !self.allowed_actions.is_empty()
+            && !self.allowed_actions.contains("Ignore")
+        {
+            return Err(
+                "allowedActions must contain Ignore when requireDisruptionRule is enabled"
+                    .to_owned(),
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "parallelismThreshold": 4,
+            "completionsThreshold": 10,
+            "requireDisruptionRule": true,
+            "allowedActions": ["FailJob", "Ignore"]
+        }"#,
+        true
+    )]
+    #[case::negative_parallelism(r#"{"parallelismThreshold": -1}"#, false)]
+    #[case::negative_completions(r#"{"completionsThreshold": -1}"#, false)]
+    #[case::unknown_action(r#"{"allowedActions": ["Retry"]}"#, false)]
+    #[case::disruption_rule_without_ignore(r#"{"allowedActions": ["FailJob"]}"#, false)]
+    #[case::without_disruption_rule(
+        r#"{"allowedActions": ["FailJob"], "requireDisruptionRule": false}"#,
+        true
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,94 @@
+#!/usr/bin/env bats
+
+@test "Accept a Job within the limits" {
+	run kwctl run \
+		--request-path test_data/job_valid.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patch".*') -eq 0 ]
+}
+
+@test "Reject a Job above the limits" {
+	run kwctl run \
+		--request-path test_data/job_above_limits.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*backoffLimit 6 is above the limit of 3.*') -ne 0 ]
+	[ $(expr "$output" : '.*parallelism 8 is above the limit of 4.*') -ne 0 ]
+}
+
+@test "Reject a Job without deadline in a CI namespace" {
+	run kwctl run \
+		--request-path test_data/job_ci_without_deadline.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*activeDeadlineSeconds must be set in the 'ci' namespace.*") -ne 0 ]
+}
+
+@test "Accept a Job with deadline in a CI namespace" {
+	run kwctl run \
+		--request-path test_data/job_ci_with_deadline.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Mutate a Job without ttlSecondsAfterFinished" {
+	run kwctl run \
+		--request-path test_data/job_without_ttl.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+}
+
+@test "Reject a Job without ttlSecondsAfterFinished and no default" {
+	run kwctl run \
+		--request-path test_data/job_without_ttl.json \
+		--settings-json '{"maxBackoffLimit": 3}' \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*ttlSecondsAfterFinished must be set.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/job_valid.json --settings-json '{"allowedCompletionModes": ["Ordered"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
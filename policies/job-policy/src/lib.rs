@@ -0,0 +1,217 @@
+use guest::prelude::*;
+use k8s_openapi::api::batch::v1::Job;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use serde_json::{Value, json};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = validation_request.settings;
+    let mut object = request.object;
+
+    let mutated = settings
+        .default_ttl_seconds_after_finished
+        .is_some_and(|ttl| set_default_ttl(&mut object, ttl));
+
+    let job = match serde_json::from_value::<Job>(object.clone()) {
+        Ok(job) => job,
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    if let Err(error) = validate_job(&job, &request.namespace, &settings) {
+        return kubewarden::reject_request(
+            Some(format!("Job '{}': {error}", request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+
+    if mutated {
+        return kubewarden::mutate_request(object);
+    }
+    kubewarden::accept_request()
+}
+
+// Sets `ttlSecondsAfterFinished` into the Job when it omits it. Returns true
+// when the Job has been changed.
+fn set_default_ttl(object: &mut Value, ttl: i32) -> bool {
+    let Some(spec) = object.get_mut("spec").and_then(Value::as_object_mut) else {
+        return false;
+    };
+    if spec
+        .get("ttlSecondsAfterFinished")
+        .is_some_and(|value| !value.is_null())
+    {
+        return false;
+    }
+    spec.insert("ttlSecondsAfterFinished".to_owned(), json!(ttl));
+    true
+}
+
+fn validate_job(job: &Job, namespace: &str, settings: &Settings) -> Result<(), String> {
+    let spec = job.spec.clone().unwrap_or_default();
+    let mut errors = vec![];
+
+    // Kubernetes retries the Jobs six times when the backoff limit is not set
+    let backoff_limit = spec.backoff_limit.unwrap_or(6);
+    if let Some(max_backoff_limit) = settings.max_backoff_limit
+        && backoff_limit > max_backoff_limit
+    {
+        errors.push(format!(
+            "backoffLimit {backoff_limit} is above the limit of {max_backoff_limit}"
+        ));
+    }
+    if spec.ttl_seconds_after_finished.is_none() {
+        errors.push("ttlSecondsAfterFinished must be set".to_owned());
+    }
+    if settings.ci_namespaces.contains(namespace) && spec.active_deadline_seconds.is_none() {
+        errors.push(format!(
+            "activeDeadlineSeconds must be set in the '{namespace}' namespace"
+        ));
+    }
+    let completion_mode = spec.completion_mode.as_deref().unwrap_or("NonIndexed");
+    if !settings.allowed_completion_modes.is_empty()
+        && !settings.allowed_completion_modes.contains(completion_mode)
+    {
+        errors.push(format!(
+            "completionMode {completion_mode} is not allowed, use one of: {}",
+            settings
+                .allowed_completion_modes
+                .iter()
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+    let parallelism = spec.parallelism.unwrap_or(1);
+    if let Some(max_parallelism) = settings.max_parallelism
+        && parallelism > max_parallelism
+    {
+        errors.push(format!(
+            "parallelism {parallelism} is above the limit of {max_parallelism}"
+        ));
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeSet;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            max_backoff_limit: Some(3),
+            default_ttl_seconds_after_finished: None,
+            ci_namespaces: BTreeSet::from(["ci".to_owned()]),
+            allowed_completion_modes: BTreeSet::from(["NonIndexed".to_owned()]),
+            max_parallelism: Some(4),
+        }
+    }
+
+    #[rstest]
+    #[case::ttl_missing(json!({"spec": {"backoffLimit": 1}}), true, json!(600))]
+    #[case::ttl_null(json!({"spec": {"ttlSecondsAfterFinished": null}}), true, json!(600))]
+    #[case::ttl_set(json!({"spec": {"ttlSecondsAfterFinished": 60}}), false, json!(60))]
+    #[case::ttl_zero(json!({"spec": {"ttlSecondsAfterFinished": 0}}), false, json!(0))]
+    fn default_ttl(
+        #[case] mut object: Value,
+        #[case] expected_mutated: bool,
+        #[case] expected_ttl: Value,
+    ) {
+        assert_eq!(set_default_ttl(&mut object, 600), expected_mutated);
+        assert_eq!(object["spec"]["ttlSecondsAfterFinished"], expected_ttl);
+    }
+
+    #[rstest]
+    #[case::valid_job("test_data/job_valid.json", true)]
+    #[case::ci_job_with_deadline("test_data/job_ci_with_deadline.json", true)]
+    #[case::ci_job_without_deadline("test_data/job_ci_without_deadline.json", false)]
+    #[case::without_ttl("test_data/job_without_ttl.json", false)]
+    #[case::above_limits("test_data/job_above_limits.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert!(response.mutated_object.is_none());
+    }
+
+    #[rstest]
+    #[case::ci_job_without_deadline(
+        "test_data/job_ci_without_deadline.json",
+        "Job 'integration-tests': activeDeadlineSeconds must be set in the 'ci' namespace"
+    )]
+    #[case::without_ttl(
+        "test_data/job_without_ttl.json",
+        "Job 'db-migration': ttlSecondsAfterFinished must be set"
+    )]
+    #[case::above_limits(
+        "test_data/job_above_limits.json",
+        "Job 'db-migration': backoffLimit 6 is above the limit of 3; completionMode Indexed is not allowed, use one of: NonIndexed; parallelism 8 is above the limit of 4"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn mutate_job_without_ttl() {
+        let test_case = Testcase {
+            name: "mutate job without ttl".to_owned(),
+            fixture_file: "test_data/job_without_ttl.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings {
+                default_ttl_seconds_after_finished: Some(3600),
+                ..settings()
+            },
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response.mutated_object.expect("the job should be mutated");
+        assert_eq!(
+            mutated_object["spec"]["ttlSecondsAfterFinished"],
+            json!(3600)
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+const COMPLETION_MODES: &[&str] = &["NonIndexed", "Indexed"];
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Maximum number of retries of the Jobs
+    pub max_backoff_limit: Option<i32>,
+    /// Value of `ttlSecondsAfterFinished` set into the Jobs that omit it.
+    /// The Jobs without it are rejected when not set.
+    pub default_ttl_seconds_after_finished: Option<i32>,
+    /// Namespaces whose Jobs must set `activeDeadlineSeconds`
+    pub ci_namespaces: BTreeSet<String>,
+    /// Allowed values of `completionMode`. All the values are allowed when
+    /// empty.
+    pub allowed_completion_modes: BTreeSet<String>,
+    /// Maximum number of Pods of a Job running at the same time
+    pub max_parallelism: Option<i32>,
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.max_backoff_limit.is_some_and(|limit| limit < 0) {
+            return Err("maxBackoffLimit cannot be negative".to_owned());
+        }
+        if self
+            .default_ttl_seconds_after_finished
+            .is_some_and(|ttl| ttl < 0)
+        {
+            return Err("defaultTtlSecondsAfterFinished cannot be negative".to_owned());
+        }
+        if self.ci_namespaces.iter().any(String::is_empty) {
+            return Err("ciNamespaces cannot contain empty names".to_owned());
+        }
+        if let Some(unknown) = self
+            .allowed_completion_modes
+            .iter()
+            .find(|mode| !COMPLETION_MODES.contains(&mode.as_str()))
+        {
+            return Err(format!(
+                "allowedCompletionModes: unknown value '{unknown}', use one of: {}",
+                COMPLETION_MODES.join(", ")
+            ));
+        }
+        if self.max_parallelism.is_some_and(|limit| limit < 1) {
+            return Err("maxParallelism must be greater than zero".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "maxBackoffLimit": 3,
+            "defaultTtlSecondsAfterFinished": 3600,
+            "ciNamespaces": ["ci"],
+            "allowedCompletionModes": ["NonIndexed"],
+            "maxParallelism": 10
+        }"#,
+        true
+    )]
+    #[case::negative_backoff_limit(r#"{"maxBackoffLimit": -1}"#, false)]
+    #[case::negative_ttl(r#"{"defaultTtlSecondsAfterFinished": -1}"#, false)]
+    #[case::empty_ci_namespace(r#"{"ciNamespaces": [""]}"#, false)]
+    #[case::unknown_completion_mode(r#"{"allowedCompletionModes": ["Ordered"]}"#, false)]
+    #[case::zero_parallelism(r#"{"maxParallelism": 0}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,47 @@
+#!/usr/bin/env bats
+
+@test "Remove the annotation from a Secret" {
+	run kwctl run --request-path test_data/secret_create_last_applied.json annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+
+	patch=$(echo "${output}" | grep '{.*\"patch\".*}' | jq -r ".patch" | base64 --decode)
+	echo "patch=$patch"
+	echo "${patch}" | jq -e '.[] | select(.op == "remove" and .path == "/metadata/annotations/kubectl.kubernetes.io~1last-applied-configuration")'
+}
+
+@test "Accept a Secret without the annotation" {
+	run kwctl run --request-path test_data/secret_create.json annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -eq 0 ]
+}
+
+@test "Remove the annotation from the selected kinds" {
+	run kwctl run --request-path test_data/configmap_update_last_applied.json --settings-json '{"kinds": [{"kind": "Secret"}, {"kind": "ConfigMap"}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/secret_create.json --settings-json '{"kinds": []}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,130 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use serde_json::Value;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+/// Annotation set by `kubectl apply`, holding the whole resource as it was
+/// last applied
+const LAST_APPLIED_CONFIGURATION: &str = "kubectl.kubernetes.io/last-applied-configuration";
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let mut request = validation_request.request;
+
+    if !validation_request
+        .settings
+        .is_selected(&request.kind.group, &request.kind.kind)
+    {
+        return kubewarden::accept_request();
+    }
+    if !remove_last_applied_configuration(&mut request.object) {
+        return kubewarden::accept_request();
+    }
+    kubewarden::mutate_request(request.object)
+}
+
+// Removes the last applied configuration annotation from the resource.
+// Returns true when the annotation was set.
+fn remove_last_applied_configuration(object: &mut Value) -> bool {
+    object
+        .pointer_mut("/metadata/annotations")
+        .and_then(Value::as_object_mut)
+        .and_then(|annotations| annotations.remove(LAST_APPLIED_CONFIGURATION))
+        .is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use settings::Kind;
+
+    #[rstest]
+    #[case::no_metadata(json!({}), false)]
+    #[case::no_annotations(json!({"metadata": {"name": "web"}}), false)]
+    #[case::other_annotations(json!({"metadata": {"annotations": {"example.com/owner": "web"}}}), false)]
+    #[case::last_applied_configuration(
+        json!({"metadata": {"annotations": {LAST_APPLIED_CONFIGURATION: "{}"}}}),
+        true
+    )]
+    fn remove_annotation(#[case] mut object: Value, #[case] expected: bool) {
+        assert_eq!(remove_last_applied_configuration(&mut object), expected);
+        assert!(
+            object
+                .pointer("/metadata/annotations/kubectl.kubernetes.io~1last-applied-configuration")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn strip_secret_annotation() {
+        let test_case = Testcase {
+            name: "strip secret annotation".to_owned(),
+            fixture_file: "test_data/secret_create_last_applied.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response
+            .mutated_object
+            .expect("the secret should be mutated");
+        assert_eq!(
+            mutated_object["metadata"]["annotations"],
+            json!({"example.com/owner": "ci-team"})
+        );
+    }
+
+    #[rstest]
+    #[case::secret_without_annotation("test_data/secret_create.json", Settings::default())]
+    #[case::kind_not_selected("test_data/configmap_update_last_applied.json", Settings::default())]
+    fn not_mutated(#[case] fixture: &str, #[case] settings: Settings) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: true,
+            settings,
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert!(response.mutated_object.is_none());
+    }
+
+    #[test]
+    fn strip_selected_kind_annotation() {
+        let test_case = Testcase {
+            name: "strip selected kind annotation".to_owned(),
+            fixture_file: "test_data/configmap_update_last_applied.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings {
+                kinds: vec![Kind {
+                    group: "".to_owned(),
+                    kind: "ConfigMap".to_owned(),
+                }],
+            },
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response
+            .mutated_object
+            .expect("the configmap should be mutated");
+        assert_eq!(mutated_object["metadata"]["annotations"], json!({}));
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+/// Kind of a Kubernetes resource
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Kind {
+    /// API group of the kind, empty for the core group
+    #[serde(default)]
+    pub group: String,
+    /// Name of the kind
+    pub kind: String,
+}
+
+impl Kind {
+    /// Returns true when the given kind is matched
+    pub fn matches(&self, group: &str, kind: &str) -> bool {
+        self.group == group && self.kind == kind
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Kinds of the resources the annotation is removed from
+    pub kinds: Vec<Kind>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            kinds: vec![Kind {
+                group: "".to_owned(),
+                kind: "Secret".to_owned(),
+            }],
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the annotation must be removed from the resources of
+    /// the kind
+    pub fn is_selected(&self, group: &str, kind: &str) -> bool {
+        self.kinds.iter().any(|k| k.matches(group, kind))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.kinds.is_empty() {
+            return Err("at least one kind must be provided".to_owned());
+        }
+        if self.kinds.iter().any(|kind| kind.kind.is_empty()) {
+            return Err("kinds cannot contain empty kinds".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::secret("", "Secret", true)]
+    #[case::other_kind("", "ConfigMap", false)]
+    #[case::other_group("example.com", "Secret", false)]
+    fn selected(#[case] group: &str, #[case] kind: &str, #[case] expected: bool) {
+        assert_eq!(Settings::default().is_selected(group, kind), expected);
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::kinds(
+        r#"{"kinds": [{"kind": "Secret"}, {"group": "", "kind": "ConfigMap"}]}"#,
+        true
+    )]
+    #[case::no_kinds(r#"{"kinds": []}"#, false)]
+    #[case::empty_kind(r#"{"kinds": [{"group": "apps", "kind": ""}]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
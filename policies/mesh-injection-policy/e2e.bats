@@ -0,0 +1,41 @@
+#!/usr/bin/env bats
+
+@test "Reject a Pod disabling the injection inside of a mandatory namespace" {
+	run kwctl run --request-path test_data/pod_injection_disabled.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Pods cannot disable the sidecar injection inside of the namespace 'payments'.*") -ne 0 ]
+}
+
+@test "Accept a mandatory namespace enabling the injection" {
+	run kwctl run --request-path test_data/namespace_injection_enabled.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a mandatory namespace not enabling the injection" {
+	run kwctl run --request-path test_data/namespace_injection_missing.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/namespace_injection_enabled.json --settings-json '{"mandatoryNamespaces": ["payments"], "forbiddenNamespaces": ["payments"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::Deserialize;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+const ISTIO_INJECTION_LABEL: &str = "istio-injection";
+const ISTIO_REVISION_LABEL: &str = "istio.io/rev";
+const ISTIO_SIDECAR_INJECT: &str = "sidecar.istio.io/inject";
+const LINKERD_INJECT: &str = "linkerd.io/inject";
+
+#[derive(Deserialize, Default)]
+struct Object {
+    #[serde(default)]
+    metadata: ObjectMeta,
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let settings = &validation_request.settings;
+    let metadata = serde_json::from_value::<Object>(validation_request.request.object)?.metadata;
+
+    let result = match validation_request.request.kind.kind.as_str() {
+        "Namespace" => validate_namespace(&metadata, settings),
+        "Pod" => validate_pod(
+            &metadata,
+            metadata
+                .namespace
+                .as_deref()
+                .unwrap_or(&validation_request.request.namespace),
+            settings,
+        ),
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        return kubewarden::reject_request(Some(e), None, None, None);
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_namespace(metadata: &ObjectMeta, settings: &Settings) -> Result<(), String> {
+    let name = metadata.name.clone().unwrap_or_default();
+    let injection_enabled = namespace_injection_enabled(metadata);
+
+    if settings.mandatory_namespaces.contains(&name) && !injection_enabled {
+        return Err(format!(
+            "namespace '{name}' must enable the sidecar injection: set the '{ISTIO_INJECTION_LABEL}: enabled' or the '{ISTIO_REVISION_LABEL}' label, or the '{LINKERD_INJECT}: enabled' annotation"
+        ));
+    }
+    if settings.forbidden_namespaces.contains(&name) && injection_enabled {
+        return Err(format!(
+            "namespace '{name}' cannot enable the sidecar injection"
+        ));
+    }
+    Ok(())
+}
+
+fn validate_pod(metadata: &ObjectMeta, namespace: &str, settings: &Settings) -> Result<(), String> {
+    match pod_injection(metadata) {
+        Some(false) if settings.mandatory_namespaces.contains(namespace) => Err(format!(
+            "Pods cannot disable the sidecar injection inside of the namespace '{namespace}'"
+        )),
+        Some(true) if settings.forbidden_namespaces.contains(namespace) => Err(format!(
+            "Pods cannot enable the sidecar injection inside of the namespace '{namespace}'"
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn namespace_injection_enabled(metadata: &ObjectMeta) -> bool {
+    let labels = metadata.labels.clone().unwrap_or_default();
+    let annotations = metadata.annotations.clone().unwrap_or_default();
+
+    labels.get(ISTIO_INJECTION_LABEL).map(String::as_str) == Some("enabled")
+        || labels.contains_key(ISTIO_REVISION_LABEL)
+        || annotations.get(LINKERD_INJECT).map(String::as_str) == Some("enabled")
+}
+
+// Returns the sidecar injection explicitly requested by the Pod, if any.
+// Disabling the injection wins over enabling it.
+fn pod_injection(metadata: &ObjectMeta) -> Option<bool> {
+    let labels = metadata.labels.clone().unwrap_or_default();
+    let annotations = metadata.annotations.clone().unwrap_or_default();
+
+    let requests: Vec<bool> = [
+        istio_injection(&labels),
+        istio_injection(&annotations),
+        annotations
+            .get(LINKERD_INJECT)
+            .and_then(|value| match value.as_str() {
+                "enabled" | "ingress" => Some(true),
+                "disabled" => Some(false),
+                _ => None,
+            }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    if requests.contains(&false) {
+        Some(false)
+    } else if requests.contains(&true) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+fn istio_injection(values: &BTreeMap<String, String>) -> Option<bool> {
+    values
+        .get(ISTIO_SIDECAR_INJECT)
+        .and_then(|value| value.parse::<bool>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn metadata(name: &str, labels: &[(&str, &str)], annotations: &[(&str, &str)]) -> ObjectMeta {
+        let to_map = |values: &[(&str, &str)]| {
+            values
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<BTreeMap<String, String>>()
+        };
+        ObjectMeta {
+            name: Some(name.to_owned()),
+            labels: Some(to_map(labels)),
+            annotations: Some(to_map(annotations)),
+            ..Default::default()
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            mandatory_namespaces: ["payments".to_owned()].into(),
+            forbidden_namespaces: ["kube-system".to_owned()].into(),
+        }
+    }
+
+    #[rstest]
+    #[case::istio_label(metadata("payments", &[("istio-injection", "enabled")], &[]), true)]
+    #[case::istio_revision(metadata("payments", &[("istio.io/rev", "1-24")], &[]), true)]
+    #[case::linkerd(metadata("payments", &[], &[("linkerd.io/inject", "enabled")]), true)]
+    #[case::missing_injection(metadata("payments", &[], &[]), false)]
+    #[case::disabled_injection(metadata("payments", &[("istio-injection", "disabled")], &[]), false)]
+    #[case::forbidden_injection(metadata("kube-system", &[("istio-injection", "enabled")], &[]), false)]
+    #[case::forbidden_without_injection(metadata("kube-system", &[], &[]), true)]
+    #[case::other_namespace(metadata("default", &[], &[]), true)]
+    fn namespaces(#[case] metadata: ObjectMeta, #[case] is_valid: bool) {
+        assert_eq!(validate_namespace(&metadata, &settings()).is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::no_request("payments", metadata("web", &[], &[]), true)]
+    #[case::istio_label_disabled("payments", metadata("web", &[("sidecar.istio.io/inject", "false")], &[]), false)]
+    #[case::istio_annotation_disabled("payments", metadata("web", &[], &[("sidecar.istio.io/inject", "false")]), false)]
+    #[case::linkerd_disabled("payments", metadata("web", &[], &[("linkerd.io/inject", "disabled")]), false)]
+    #[case::conflicting_requests("payments", metadata("web", &[("sidecar.istio.io/inject", "true")], &[("linkerd.io/inject", "disabled")]), false)]
+    #[case::enabled_in_forbidden("kube-system", metadata("web", &[("sidecar.istio.io/inject", "true")], &[]), false)]
+    #[case::disabled_in_forbidden("kube-system", metadata("web", &[("sidecar.istio.io/inject", "false")], &[]), true)]
+    #[case::disabled_elsewhere("default", metadata("web", &[("sidecar.istio.io/inject", "false")], &[]), true)]
+    fn pods(#[case] namespace: &str, #[case] metadata: ObjectMeta, #[case] is_valid: bool) {
+        assert_eq!(
+            validate_pod(&metadata, namespace, &settings()).is_ok(),
+            is_valid
+        );
+    }
+
+    #[rstest]
+    #[case::pod_disabling_injection("test_data/pod_injection_disabled.json", false)]
+    #[case::namespace_with_injection("test_data/namespace_injection_enabled.json", true)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Namespaces where the sidecar injection is mandatory
+    pub mandatory_namespaces: HashSet<String>,
+    /// Namespaces where the sidecar injection is forbidden
+    pub forbidden_namespaces: HashSet<String>,
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        let mut common: Vec<&String> = self
+            .mandatory_namespaces
+            .intersection(&self.forbidden_namespaces)
+            .collect();
+        if !common.is_empty() {
+            common.sort();
+            return Err(format!(
+                "namespaces cannot be both mandatory and forbidden: {}",
+                common
+                    .into_iter()
+                    .map(String::as_str)
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(vec![], vec![], true)]
+    #[case::disjoint(vec!["payments"], vec!["kube-system"], true)]
+    #[case::overlapping(vec!["payments", "kube-system"], vec!["kube-system"], false)]
+    fn validate_settings(
+        #[case] mandatory: Vec<&str>,
+        #[case] forbidden: Vec<&str>,
+        #[case] is_valid: bool,
+    ) {
+        let settings = Settings {
+            mandatory_namespaces: mandatory.into_iter().map(String::from).collect(),
+            forbidden_namespaces: forbidden.into_iter().map(String::from).collect(),
+        };
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
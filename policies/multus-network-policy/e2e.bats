@@ -0,0 +1,56 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod attached to allowed and existing networks" {
+	run kwctl run \
+		--request-path test_data/pod_with_networks.json \
+		--settings-path test_data/settings.yaml \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-networks-found.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Pod attached to a missing network" {
+	run kwctl run \
+		--request-path test_data/pod_with_networks.json \
+		--settings-json '{"allowedNetworks": {"team-a": ["macvlan"]}}' \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-network-not-found.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*NetworkAttachmentDefinition 'team-a/macvlan' cannot be found.*") -ne 0 ]
+	[ $(expr "$output" : ".*network 'infra/sriov' is not allowed.*") -ne 0 ]
+}
+
+@test "Reject a Pod attached to a network not allowed" {
+	run kwctl run \
+		--request-path test_data/pod_with_not_allowed_network.json \
+		--settings-path test_data/settings.yaml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*network 'infra/shared' is not allowed.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_with_networks.json --settings-json '{"allowedNetworks": {"team-a": ["infra/"]}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
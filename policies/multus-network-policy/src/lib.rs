@@ -0,0 +1,261 @@
+use std::collections::BTreeSet;
+
+use guest::prelude::*;
+use k8s_openapi::Resource;
+use k8s_openapi::api::core::v1::Pod;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::ListResourcesByNamespaceRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::list_resources_by_namespace;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::list_resources_by_namespace;
+
+mod network;
+use network::{NETWORKS_ANNOTATION, NetworkAttachmentDefinition, NetworkSelection, parse_networks};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    if validation_request.request.kind.kind != "Pod" {
+        return kubewarden::accept_request();
+    }
+    let pod = serde_json::from_value::<Pod>(validation_request.request.object)?;
+    let Some(annotation) = pod
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(NETWORKS_ANNOTATION))
+    else {
+        return kubewarden::accept_request();
+    };
+    let pod_namespace = pod
+        .metadata
+        .namespace
+        .clone()
+        .unwrap_or(validation_request.request.namespace);
+
+    let networks = match parse_networks(annotation) {
+        Ok(networks) => networks,
+        Err(e) => return kubewarden::reject_request(Some(e), None, None, None),
+    };
+
+    let errors = validate_networks(&networks, &pod_namespace, &validation_request.settings);
+    if !errors.is_empty() {
+        return kubewarden::reject_request(Some(errors.join(", ")), None, None, None);
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_networks(
+    networks: &[NetworkSelection],
+    pod_namespace: &str,
+    settings: &Settings,
+) -> Vec<String> {
+    let mut errors = vec![];
+    for network in networks {
+        let network_namespace = network.namespace_or(pod_namespace);
+        if !settings.is_network_allowed(pod_namespace, network) {
+            errors.push(format!(
+                "network '{network_namespace}/{}' is not allowed",
+                network.name
+            ));
+            continue;
+        }
+
+        // The network is listed by name: an empty list means it does not
+        // exist, while an error is a failure of the host
+        let kube_request = ListResourcesByNamespaceRequest {
+            api_version: NetworkAttachmentDefinition::API_VERSION.to_owned(),
+            kind: NetworkAttachmentDefinition::KIND.to_owned(),
+            namespace: network_namespace.to_owned(),
+            label_selector: None,
+            field_selector: Some(format!("metadata.name={}", network.name)),
+            field_masks: Some(BTreeSet::from(["metadata.name".to_owned()])),
+        };
+        match list_resources_by_namespace::<NetworkAttachmentDefinition>(&kube_request) {
+            Ok(definitions) if definitions.items.is_empty() => errors.push(format!(
+                "NetworkAttachmentDefinition '{network_namespace}/{}' cannot be found",
+                network.name
+            )),
+            Ok(_) => {}
+            Err(err) => errors.push(format!(
+                "cannot read the NetworkAttachmentDefinition '{network_namespace}/{}': {err}",
+                network.name
+            )),
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::{BTreeMap, HashSet};
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden::{
+        request::{GroupVersionKind, KubernetesAdmissionRequest},
+        response::ValidationResponse,
+    };
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::ListResourcesByNamespaceRequest;
+
+        #[allow(dead_code)]
+        pub fn list_resources_by_namespace<T>(
+            _req: &ListResourcesByNamespaceRequest,
+        ) -> anyhow::Result<k8s_openapi::List<T>>
+        where
+            T: k8s_openapi::ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+        {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            allowed_networks: BTreeMap::from([(
+                "team-a".to_owned(),
+                HashSet::from(["macvlan".to_owned(), "infra/sriov".to_owned()]),
+            )]),
+        }
+    }
+
+    fn validate_pod(annotation: Option<&str>) -> ValidationResponse {
+        let pod = Pod {
+            metadata: ObjectMeta {
+                name: Some("web".to_owned()),
+                namespace: Some("team-a".to_owned()),
+                annotations: annotation.map(|value| {
+                    BTreeMap::from([(NETWORKS_ANNOTATION.to_owned(), value.to_owned())])
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let request = KubernetesAdmissionRequest {
+            kind: GroupVersionKind {
+                group: "".to_owned(),
+                version: "v1".to_owned(),
+                kind: "Pod".to_owned(),
+            },
+            namespace: "team-a".to_owned(),
+            object: serde_json::to_value(pod).expect("cannot serialize Pod"),
+            ..Default::default()
+        };
+        let validation_request = ValidationRequest::<Settings> {
+            settings: settings(),
+            request,
+        };
+        let payload = serde_json::to_string(&validation_request)
+            .expect("cannot serialize validation request");
+
+        let response = validate(payload.as_bytes()).expect("validation failed");
+        serde_json::from_slice(&response).expect("cannot deserialize validation response")
+    }
+
+    #[rstest]
+    #[case::no_annotation(None, 0, true)]
+    #[case::existing_networks(Some("macvlan, infra/sriov@net1"), 2, true)]
+    #[case::not_allowed_network(Some("infra/shared"), 0, false)]
+    #[case::invalid_annotation(Some("infra/"), 0, false)]
+    #[serial]
+    fn allowed_and_existing_networks(
+        #[case] annotation: Option<&str>,
+        #[case] lookups: usize,
+        #[case] accepted: bool,
+    ) {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<NetworkAttachmentDefinition>()
+            .times(lookups)
+            .returning(|req| {
+                let name = req
+                    .field_selector
+                    .as_deref()
+                    .and_then(|selector| selector.strip_prefix("metadata.name="))
+                    .expect("the network is not listed by name");
+                Ok(k8s_openapi::List {
+                    items: vec![NetworkAttachmentDefinition {
+                        metadata: ObjectMeta {
+                            name: Some(name.to_owned()),
+                            namespace: Some(req.namespace.clone()),
+                            ..Default::default()
+                        },
+                    }],
+                    ..Default::default()
+                })
+            });
+
+        assert_eq!(validate_pod(annotation).accepted, accepted);
+    }
+
+    #[test]
+    #[serial]
+    fn missing_network() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<NetworkAttachmentDefinition>()
+            .times(1)
+            .returning(|req| {
+                if req.namespace != "team-a"
+                    || req.field_selector.as_deref() != Some("metadata.name=macvlan")
+                {
+                    return Err(anyhow::anyhow!("it's not searching the expected network"));
+                }
+                Ok(k8s_openapi::List::default())
+            });
+
+        let response = validate_pod(Some("macvlan"));
+        assert!(!response.accepted);
+        assert_eq!(
+            response.message,
+            Some("NetworkAttachmentDefinition 'team-a/macvlan' cannot be found".to_owned())
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn network_lookup_failure() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<NetworkAttachmentDefinition>()
+            .times(1)
+            .returning(|_req| {
+                Err(anyhow::anyhow!(
+                    "network-attachment-definitions.k8s.cni.cncf.io is forbidden"
+                ))
+            });
+
+        let response = validate_pod(Some("macvlan"));
+        assert!(!response.accepted);
+        assert_eq!(
+            response.message,
+            Some(
+                "cannot read the NetworkAttachmentDefinition 'team-a/macvlan': network-attachment-definitions.k8s.cni.cncf.io is forbidden"
+                    .to_owned()
+            )
+        );
+    }
+}
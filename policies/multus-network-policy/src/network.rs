@@ -0,0 +1,134 @@
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+pub(crate) const NETWORKS_ANNOTATION: &str = "k8s.v1.cni.cncf.io/networks";
+
+/// A network the Pod is attached to, as listed by the
+/// `k8s.v1.cni.cncf.io/networks` annotation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NetworkSelection {
+    pub name: String,
+    pub namespace: Option<String>,
+}
+
+impl NetworkSelection {
+    /// Returns the namespace of the NetworkAttachmentDefinition. Networks
+    /// without a namespace are looked up inside of the namespace of the Pod.
+    pub fn namespace_or<'a>(&'a self, pod_namespace: &'a str) -> &'a str {
+        self.namespace.as_deref().unwrap_or(pod_namespace)
+    }
+}
+
+/// Parses the value of the `k8s.v1.cni.cncf.io/networks` annotation. The
+/// annotation holds either a JSON list of network selection objects or a
+/// comma separated list of `<namespace>/<name>@<interface>` entries, where
+/// both the namespace and the interface are optional.
+pub(crate) fn parse_networks(value: &str) -> Result<Vec<NetworkSelection>, String> {
+    let value = value.trim();
+    if value.starts_with('[') {
+        let networks: Vec<NetworkSelection> = serde_json::from_str(value)
+            .map_err(|e| format!("cannot parse the '{NETWORKS_ANNOTATION}' annotation: {e}"))?;
+        if networks.iter().any(|network| network.name.is_empty()) {
+            return Err(format!(
+                "the '{NETWORKS_ANNOTATION}' annotation contains networks without a name"
+            ));
+        }
+        return Ok(networks);
+    }
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let entry = entry
+                .split_once('@')
+                .map_or(entry, |(network, _interface)| network);
+            let (namespace, name) = match entry.split_once('/') {
+                Some((namespace, name)) => (Some(namespace.to_owned()), name),
+                None => (None, entry),
+            };
+            if name.is_empty() || namespace.as_deref() == Some("") {
+                return Err(format!(
+                    "the '{NETWORKS_ANNOTATION}' annotation contains the invalid network '{entry}'"
+                ));
+            }
+            Ok(NetworkSelection {
+                name: name.to_owned(),
+                namespace,
+            })
+        })
+        .collect()
+}
+
+/// Minimal representation of a Multus NetworkAttachmentDefinition. Only its
+/// existence is checked by the policy.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct NetworkAttachmentDefinition {
+    #[serde(default)]
+    pub metadata: ObjectMeta,
+}
+
+impl k8s_openapi::DeepMerge for NetworkAttachmentDefinition {
+    fn merge_from(&mut self, other: Self)
+    where
+        Self: Sized,
+    {
+        *self = other;
+    }
+}
+
+impl k8s_openapi::Resource for NetworkAttachmentDefinition {
+    const API_VERSION: &'static str = "k8s.cni.cncf.io/v1";
+    const GROUP: &'static str = "k8s.cni.cncf.io";
+    const KIND: &'static str = "NetworkAttachmentDefinition";
+    const VERSION: &'static str = "v1";
+    const URL_PATH_SEGMENT: &'static str = "network-attachment-definitions";
+    type Scope = k8s_openapi::NamespaceResourceScope;
+}
+
+impl k8s_openapi::ListableResource for NetworkAttachmentDefinition {
+    const LIST_KIND: &'static str = "NetworkAttachmentDefinitionList";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    fn network(namespace: Option<&str>, name: &str) -> NetworkSelection {
+        NetworkSelection {
+            name: name.to_owned(),
+            namespace: namespace.map(String::from),
+        }
+    }
+
+    #[rstest]
+    #[case::single("macvlan", vec![network(None, "macvlan")])]
+    #[case::list(
+        "macvlan, infra/sriov@net1,bridge@eth2",
+        vec![network(None, "macvlan"), network(Some("infra"), "sriov"), network(None, "bridge")]
+    )]
+    #[case::json(
+        r#"[{"name": "macvlan"}, {"name": "sriov", "namespace": "infra", "interface": "net1"}]"#,
+        vec![network(None, "macvlan"), network(Some("infra"), "sriov")]
+    )]
+    #[case::empty("", vec![])]
+    fn valid_annotations(#[case] value: &str, #[case] expected: Vec<NetworkSelection>) {
+        assert_eq!(
+            parse_networks(value).expect("cannot parse networks"),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::missing_name("infra/")]
+    #[case::missing_namespace("/sriov")]
+    #[case::broken_json(r#"[{"name": "macvlan"}"#)]
+    #[case::json_without_name(r#"[{"namespace": "infra"}]"#)]
+    fn invalid_annotations(#[case] value: &str) {
+        assert!(parse_networks(value).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::network::NetworkSelection;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Networks the Pods of each namespace can be attached to. Networks are
+    /// expressed as `<namespace>/<name>`, or just as `<name>` when they are
+    /// defined inside of the namespace of the Pod. The `*` key applies to all
+    /// the namespaces that are not explicitly listed.
+    pub allowed_networks: BTreeMap<String, HashSet<String>>,
+}
+
+impl Settings {
+    /// Returns true when the network can be used by the Pods of the given
+    /// namespace. When no allowlist is defined, all the networks are allowed.
+    pub fn is_network_allowed(&self, pod_namespace: &str, network: &NetworkSelection) -> bool {
+        if self.allowed_networks.is_empty() {
+            return true;
+        }
+        let Some(allowed) = self
+            .allowed_networks
+            .get(pod_namespace)
+            .or_else(|| self.allowed_networks.get("*"))
+        else {
+            return false;
+        };
+
+        let network_namespace = network.namespace_or(pod_namespace);
+        allowed.iter().any(|entry| match entry.split_once('/') {
+            Some((namespace, name)) => namespace == network_namespace && name == network.name,
+            None => network_namespace == pod_namespace && *entry == network.name,
+        })
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        for (namespace, networks) in &self.allowed_networks {
+            if let Some(network) = networks.iter().find(|network| {
+                network.is_empty()
+                    || network
+                        .split_once('/')
+                        .is_some_and(|(namespace, name)| namespace.is_empty() || name.is_empty())
+            }) {
+                return Err(format!(
+                    "allowedNetworks of namespace '{namespace}' contains the invalid network '{network}'"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            allowed_networks: BTreeMap::from([
+                (
+                    "team-a".to_owned(),
+                    HashSet::from(["macvlan".to_owned(), "infra/sriov".to_owned()]),
+                ),
+                ("*".to_owned(), HashSet::from(["infra/shared".to_owned()])),
+            ]),
+        }
+    }
+
+    fn network(namespace: Option<&str>, name: &str) -> NetworkSelection {
+        NetworkSelection {
+            name: name.to_owned(),
+            namespace: namespace.map(String::from),
+        }
+    }
+
+    #[rstest]
+    #[case::local_network("team-a", network(None, "macvlan"), true)]
+    #[case::local_network_explicit_namespace("team-a", network(Some("team-a"), "macvlan"), true)]
+    #[case::foreign_network("team-a", network(Some("infra"), "sriov"), true)]
+    #[case::not_allowed_network("team-a", network(Some("infra"), "shared"), false)]
+    #[case::same_name_other_namespace("team-a", network(Some("team-b"), "macvlan"), false)]
+    #[case::fallback("team-b", network(Some("infra"), "shared"), true)]
+    #[case::fallback_not_allowed("team-b", network(None, "macvlan"), false)]
+    fn allowed_networks(
+        #[case] pod_namespace: &str,
+        #[case] network: NetworkSelection,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            settings().is_network_allowed(pod_namespace, &network),
+            expected
+        );
+    }
+
+    #[test]
+    fn no_allowlist() {
+        assert!(Settings::default().is_network_allowed("team-a", &network(None, "macvlan")));
+    }
+
+    #[rstest]
+    #[case::valid(settings(), true)]
+    #[case::empty_network(Settings {
+        allowed_networks: BTreeMap::from([("team-a".to_owned(), HashSet::from([String::new()]))]),
+    }, false)]
+    #[case::missing_name(Settings {
+        allowed_networks: BTreeMap::from([("team-a".to_owned(), HashSet::from(["infra/".to_owned()]))]),
+    }, false)]
+    fn validate_settings(#[case] settings: Settings, #[case] is_valid: bool) {
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,39 @@
+#!/usr/bin/env bats
+
+SETTINGS='{"labels": {"pod-security.kubernetes.io/enforce": "baseline", "example.com/tier": "best-effort"}, "annotations": {"example.com/owner": "unassigned"}, "groupDefaults": [{"group": "team-payments", "labels": {"example.com/tier": "critical"}, "annotations": {"example.com/owner": "team-payments"}}]}'
+
+@test "Set the defaults of the groups of the user" {
+	run kwctl run --request-path test_data/namespace_creation.json --settings-json "$SETTINGS" annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+
+	patch=$(echo "${output}" | grep '{.*\"patch\".*}' | jq -r ".patch" | base64 --decode)
+	echo "patch=$patch"
+	echo "${patch}" | jq -e '.[] | select(.path == "/metadata/labels") | .value["example.com/tier"] == "critical"'
+	echo "${patch}" | jq -e '.[] | select(.path == "/metadata/annotations") | .value["example.com/owner"] == "team-payments"'
+}
+
+@test "Keep the values set by the user" {
+	run kwctl run --request-path test_data/namespace_creation_with_labels.json --settings-json '{"labels": {"pod-security.kubernetes.io/enforce": "baseline"}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -eq 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/namespace_creation.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
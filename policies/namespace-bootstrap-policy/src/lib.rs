@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::core::v1::Namespace;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+
+    if request.kind.kind != "Namespace" || request.operation != "CREATE" {
+        return kubewarden::accept_request();
+    }
+    let mut namespace = serde_json::from_value::<Namespace>(request.object)?;
+    let groups = request.user_info.groups.unwrap_or_default();
+    let defaults = validation_request.settings.defaults_for(&groups);
+
+    let labels_set = set_missing(&mut namespace.metadata.labels, &defaults.labels);
+    let annotations_set = set_missing(&mut namespace.metadata.annotations, &defaults.annotations);
+    if !labels_set && !annotations_set {
+        return kubewarden::accept_request();
+    }
+    kubewarden::mutate_request(serde_json::to_value(namespace)?)
+}
+
+// Sets the values not defined yet, leaving the ones chosen by the user
+// untouched. Returns true when some value has been set.
+fn set_missing(
+    values: &mut Option<BTreeMap<String, String>>,
+    defaults: &BTreeMap<&str, &str>,
+) -> bool {
+    let missing: Vec<_> = defaults
+        .iter()
+        .filter(|(key, _)| {
+            values
+                .as_ref()
+                .is_none_or(|values| !values.contains_key(**key))
+        })
+        .collect();
+    if missing.is_empty() {
+        return false;
+    }
+    let values = values.get_or_insert_default();
+    for (key, value) in missing {
+        values.insert(key.to_string(), value.to_string());
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::response::ValidationResponse;
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use settings::GroupDefaults;
+
+    fn settings() -> Settings {
+        Settings {
+            labels: BTreeMap::from([
+                (
+                    "pod-security.kubernetes.io/enforce".to_owned(),
+                    "baseline".to_owned(),
+                ),
+                ("example.com/tier".to_owned(), "best-effort".to_owned()),
+            ]),
+            annotations: BTreeMap::from([(
+                "example.com/owner".to_owned(),
+                "unassigned".to_owned(),
+            )]),
+            group_defaults: vec![GroupDefaults {
+                group: "team-payments".to_owned(),
+                labels: BTreeMap::from([("example.com/tier".to_owned(), "critical".to_owned())]),
+                annotations: BTreeMap::from([(
+                    "example.com/owner".to_owned(),
+                    "team-payments".to_owned(),
+                )]),
+            }],
+        }
+    }
+
+    fn mutated_metadata(response: &ValidationResponse) -> serde_json::Value {
+        response
+            .mutated_object
+            .as_ref()
+            .expect("the namespace should be mutated")["metadata"]
+            .clone()
+    }
+
+    #[rstest]
+    #[case::group_defaults(
+        "test_data/namespace_creation.json",
+        json!({
+            "name": "payments-prod",
+            "labels": {
+                "example.com/tier": "critical",
+                "pod-security.kubernetes.io/enforce": "baseline",
+            },
+            "annotations": {
+                "example.com/owner": "team-payments",
+            },
+        })
+    )]
+    #[case::values_set_by_the_user(
+        "test_data/namespace_creation_with_labels.json",
+        json!({
+            "name": "sandbox",
+            "labels": {
+                "example.com/tier": "best-effort",
+                "pod-security.kubernetes.io/enforce": "privileged",
+            },
+            "annotations": {
+                "example.com/owner": "john",
+            },
+        })
+    )]
+    fn mutate_request(#[case] fixture: &str, #[case] expected_metadata: serde_json::Value) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: true,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(mutated_metadata(&response), expected_metadata);
+    }
+
+    #[test]
+    fn values_already_set() {
+        let test_case = Testcase {
+            name: "values already set".to_owned(),
+            fixture_file: "test_data/namespace_creation_with_labels.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings {
+                labels: BTreeMap::from([(
+                    "pod-security.kubernetes.io/enforce".to_owned(),
+                    "baseline".to_owned(),
+                )]),
+                ..Default::default()
+            },
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert!(response.mutated_object.is_none());
+    }
+
+    #[rstest]
+    #[case::no_values(None, true, "default")]
+    #[case::missing_value(Some(BTreeMap::from([("other".to_owned(), "value".to_owned())])), true, "default")]
+    #[case::value_set(Some(BTreeMap::from([("key".to_owned(), "user".to_owned())])), false, "user")]
+    fn set_missing_values(
+        #[case] mut values: Option<BTreeMap<String, String>>,
+        #[case] expected_set: bool,
+        #[case] expected_value: &str,
+    ) {
+        let defaults = BTreeMap::from([("key", "default")]);
+        assert_eq!(set_missing(&mut values, &defaults), expected_set);
+        assert_eq!(
+            values.unwrap_or_default().get("key").map(String::as_str),
+            Some(expected_value)
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Labels and annotations set on the namespaces created by the members of a
+/// group
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct GroupDefaults {
+    pub group: String,
+    pub labels: BTreeMap<String, String>,
+    pub annotations: BTreeMap<String, String>,
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Labels set on all the new namespaces
+    pub labels: BTreeMap<String, String>,
+    /// Annotations set on all the new namespaces
+    pub annotations: BTreeMap<String, String>,
+    /// Labels and annotations set on the new namespaces depending on the
+    /// groups of the requester. They take precedence over the ones set on all
+    /// the namespaces, the first entries taking precedence over the next ones.
+    pub group_defaults: Vec<GroupDefaults>,
+}
+
+/// Labels and annotations to set on a new namespace
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Defaults<'a> {
+    pub labels: BTreeMap<&'a str, &'a str>,
+    pub annotations: BTreeMap<&'a str, &'a str>,
+}
+
+impl Settings {
+    /// Returns the labels and the annotations to set on the namespaces
+    /// created by a member of the groups
+    pub fn defaults_for(&self, groups: &[String]) -> Defaults<'_> {
+        let mut defaults = Defaults::default();
+        let group_defaults = self
+            .group_defaults
+            .iter()
+            .filter(|group_defaults| groups.contains(&group_defaults.group))
+            .map(|group_defaults| (&group_defaults.labels, &group_defaults.annotations));
+        for (labels, annotations) in group_defaults.chain([(&self.labels, &self.annotations)]) {
+            for (key, value) in labels {
+                defaults.labels.entry(key).or_insert(value);
+            }
+            for (key, value) in annotations {
+                defaults.annotations.entry(key).or_insert(value);
+            }
+        }
+        defaults
+    }
+}
+
+// Returns an error when one of the keys is empty
+fn validate_keys(values: &BTreeMap<String, String>, field: &str) -> Result<(), String> {
+    if values.contains_key("") {
+        return Err(format!("{field} cannot contain empty keys"));
+    }
+    Ok(())
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        validate_keys(&self.labels, "labels")?;
+        validate_keys(&self.annotations, "annotations")?;
+        for group_defaults in &self.group_defaults {
+            if group_defaults.group.is_empty() {
+                return Err("groupDefaults cannot contain entries without a group".to_owned());
+            }
+            validate_keys(&group_defaults.labels, "groupDefaults labels")?;
+            validate_keys(&group_defaults.annotations, "groupDefaults annotations")?;
+        }
+        if self.labels.is_empty()
+            && self.annotations.is_empty()
+            && self.group_defaults.iter().all(|group_defaults| {
+                group_defaults.labels.is_empty() && group_defaults.annotations.is_empty()
+            })
+        {
+            return Err("at least one label or annotation must be provided".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            labels: BTreeMap::from([
+                (
+                    "pod-security.kubernetes.io/enforce".to_owned(),
+                    "baseline".to_owned(),
+                ),
+                ("example.com/tier".to_owned(), "best-effort".to_owned()),
+            ]),
+            annotations: BTreeMap::from([(
+                "example.com/owner".to_owned(),
+                "unassigned".to_owned(),
+            )]),
+            group_defaults: vec![
+                GroupDefaults {
+                    group: "team-payments".to_owned(),
+                    labels: BTreeMap::from([(
+                        "example.com/tier".to_owned(),
+                        "critical".to_owned(),
+                    )]),
+                    annotations: BTreeMap::from([(
+                        "example.com/owner".to_owned(),
+                        "team-payments".to_owned(),
+                    )]),
+                },
+                GroupDefaults {
+                    group: "developers".to_owned(),
+                    labels: BTreeMap::from([
+                        ("example.com/tier".to_owned(), "standard".to_owned()),
+                        ("example.com/environment".to_owned(), "dev".to_owned()),
+                    ]),
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+
+    #[rstest]
+    #[case::no_groups(
+        vec![],
+        vec![
+            ("example.com/tier", "best-effort"),
+            ("pod-security.kubernetes.io/enforce", "baseline"),
+        ],
+        vec![("example.com/owner", "unassigned")]
+    )]
+    #[case::group(
+        vec!["developers"],
+        vec![
+            ("example.com/environment", "dev"),
+            ("example.com/tier", "standard"),
+            ("pod-security.kubernetes.io/enforce", "baseline"),
+        ],
+        vec![("example.com/owner", "unassigned")]
+    )]
+    #[case::first_entry_first(
+        vec!["developers", "team-payments"],
+        vec![
+            ("example.com/environment", "dev"),
+            ("example.com/tier", "critical"),
+            ("pod-security.kubernetes.io/enforce", "baseline"),
+        ],
+        vec![("example.com/owner", "team-payments")]
+    )]
+    fn defaults_for(
+        #[case] groups: Vec<&str>,
+        #[case] labels: Vec<(&str, &str)>,
+        #[case] annotations: Vec<(&str, &str)>,
+    ) {
+        let settings = settings();
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(
+            settings.defaults_for(&groups),
+            Defaults {
+                labels: labels.into_iter().collect(),
+                annotations: annotations.into_iter().collect(),
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::no_values(r#"{}"#, false)]
+    #[case::labels(r#"{"labels": {"example.com/tier": "best-effort"}}"#, true)]
+    #[case::annotations(r#"{"annotations": {"example.com/owner": "unassigned"}}"#, true)]
+    #[case::group_defaults(
+        r#"{"groupDefaults": [{"group": "team-payments", "labels": {"example.com/tier": "critical"}}]}"#,
+        true
+    )]
+    #[case::group_defaults_without_values(
+        r#"{"groupDefaults": [{"group": "team-payments"}]}"#,
+        false
+    )]
+    #[case::group_defaults_without_group(
+        r#"{"groupDefaults": [{"labels": {"example.com/tier": "critical"}}]}"#,
+        false
+    )]
+    #[case::empty_label(r#"{"labels": {"": "best-effort"}}"#, false)]
+    #[case::empty_annotation(r#"{"annotations": {"": "unassigned"}}"#, false)]
+    #[case::empty_group_label(
+        r#"{"groupDefaults": [{"group": "team-payments", "labels": {"": "critical"}}]}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn unknown_group_defaults_field() {
+        let settings = r#"{"groupDefaults": [{"group": "team-payments", "label": {"example.com/tier": "critical"}}]}"#;
+        assert!(serde_json::from_str::<Settings>(settings).is_err());
+    }
+}
@@ -84,3 +84,14 @@
   [ "$status" -eq 0 ]
   [ $(expr "$output" : '.*allowed.*true') -ne 0 ]
 }
+
+@test "Reject invalid conflict policy" {
+  run kwctl run --allow-context-aware -r test_data/pod.json \
+	--replay-host-capabilities-interactions test_data/session_replay.yml \
+	--settings-json '{"propagatedLabels": ["cccenter"], "conflictPolicy": "merge"}' annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -ne 0 ]
+}
@@ -9,25 +9,55 @@ import (
 	kubewarden_protocol "github.com/kubewarden/policy-sdk-go/protocol"
 )
 
+const (
+	// The values defined in the namespace replace the ones of the resource
+	CONFLICT_POLICY_OVERWRITE = "overwrite"
+	// The values already defined in the resource are kept
+	CONFLICT_POLICY_SKIP = "skip"
+)
+
 type Settings struct {
-	PropagatedLabels []string `json:"propagatedLabels"`
+	PropagatedLabels      []string `json:"propagatedLabels"`
+	PropagatedAnnotations []string `json:"propagatedAnnotations"`
+	// Copy the propagated annotations also to the pod templates of the
+	// workloads. The propagated labels are always copied to them.
+	PropagateAnnotationsToPodTemplates bool `json:"propagateAnnotationsToPodTemplates"`
+	// What to do when the resource already defines a propagated label or
+	// annotation with a different value. Defaults to `overwrite`.
+	ConflictPolicy string `json:"conflictPolicy"`
 }
 
-// The Settings class is defined inside of the `types.go` file
-
-// No special checks have to be done
 func (s *Settings) Valid() (bool, error) {
-	if len(s.PropagatedLabels) == 0 {
-		return false, errors.New("some label must be provided")
+	if len(s.PropagatedLabels) == 0 && len(s.PropagatedAnnotations) == 0 {
+		return false, errors.New("some label or annotation must be provided")
 	}
 	for _, label := range s.PropagatedLabels {
 		if len(label) == 0 {
 			return false, errors.New("empty labels are not allowed")
 		}
 	}
+	for _, annotation := range s.PropagatedAnnotations {
+		if len(annotation) == 0 {
+			return false, errors.New("empty annotations are not allowed")
+		}
+	}
+	if s.PropagateAnnotationsToPodTemplates && len(s.PropagatedAnnotations) == 0 {
+		return false, errors.New("propagateAnnotationsToPodTemplates requires some annotation to be provided")
+	}
+	switch s.ConflictPolicy {
+	case "", CONFLICT_POLICY_OVERWRITE, CONFLICT_POLICY_SKIP:
+	default:
+		return false, fmt.Errorf("invalid conflictPolicy \"%s\", allowed values are: %s, %s", s.ConflictPolicy, CONFLICT_POLICY_OVERWRITE, CONFLICT_POLICY_SKIP)
+	}
 	return true, nil
 }
 
+// Overwrite returns true when the values defined in the namespace replace
+// the ones already defined in the resource
+func (s *Settings) Overwrite() bool {
+	return s.ConflictPolicy != CONFLICT_POLICY_SKIP
+}
+
 func NewSettingsFromValidationReq(validationReq *kubewarden_protocol.ValidationRequest) (Settings, error) {
 	settings := Settings{}
 	err := json.Unmarshal(validationReq.Settings, &settings)
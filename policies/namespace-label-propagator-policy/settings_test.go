@@ -55,3 +55,71 @@ func TestParsingSettingsWithNoLabels(t *testing.T) {
 		t.Errorf("At least one label must be provided")
 	}
 }
+
+func TestParsingSettingsWithAnnotationsOnly(t *testing.T) {
+	rawSettings := []byte(`{"propagatedAnnotations": ["example.com/owner"], "propagateAnnotationsToPodTemplates": true}`)
+	settings := &Settings{}
+	if err := json.Unmarshal(rawSettings, settings); err != nil {
+		t.Errorf("Unexpected error %+v", err)
+	}
+
+	valid, err := settings.Valid()
+	if !valid {
+		t.Errorf("Settings with annotations only should be valid: %+v", err)
+	}
+	if !settings.Overwrite() {
+		t.Errorf("The default conflict policy should overwrite the values")
+	}
+}
+
+func TestParsingSettingsWithEmptyStringAnnotation(t *testing.T) {
+	rawSettings := []byte(`{"propagatedLabels": ["label"], "propagatedAnnotations": [""]}`)
+	settings := &Settings{}
+	if err := json.Unmarshal(rawSettings, settings); err != nil {
+		t.Errorf("Unexpected error %+v", err)
+	}
+
+	valid, _ := settings.Valid()
+	if valid {
+		t.Errorf("Empty annotation string should not be valid")
+	}
+}
+
+func TestParsingSettingsWithPodTemplatesAndNoAnnotations(t *testing.T) {
+	rawSettings := []byte(`{"propagatedLabels": ["label"], "propagateAnnotationsToPodTemplates": true}`)
+	settings := &Settings{}
+	if err := json.Unmarshal(rawSettings, settings); err != nil {
+		t.Errorf("Unexpected error %+v", err)
+	}
+
+	valid, _ := settings.Valid()
+	if valid {
+		t.Errorf("Propagating annotations to pod templates without annotations should not be valid")
+	}
+}
+
+func TestParsingSettingsConflictPolicy(t *testing.T) {
+	cases := []struct {
+		conflictPolicy string
+		valid          bool
+		overwrite      bool
+	}{
+		{"overwrite", true, true},
+		{"skip", true, false},
+		{"merge", false, true},
+	}
+
+	for _, tc := range cases {
+		settings := Settings{
+			PropagatedLabels: []string{"label"},
+			ConflictPolicy:   tc.conflictPolicy,
+		}
+		valid, _ := settings.Valid()
+		if valid != tc.valid {
+			t.Errorf("conflictPolicy %s: expected valid to be %t", tc.conflictPolicy, tc.valid)
+		}
+		if settings.Overwrite() != tc.overwrite {
+			t.Errorf("conflictPolicy %s: expected overwrite to be %t", tc.conflictPolicy, tc.overwrite)
+		}
+	}
+}
@@ -50,43 +50,90 @@ func getNamespace(validationRequest kubewarden_protocol.ValidationRequest) (*cor
 	return namespace, nil
 }
 
-func validateResourceLabels(namespaceLabels map[string]string, request kubewarden_protocol.ValidationRequest, settings Settings) ([]byte, error) {
-	labelsToPropagate := make(map[string]string)
+// propagation describes the labels and the annotations to copy from the
+// namespace to the resource
+type propagation struct {
+	labels                    map[string]string
+	annotations               map[string]string
+	annotationsToPodTemplates bool
+	overwrite                 bool
+}
+
+func newPropagation(namespaceMeta *metav1.ObjectMeta, settings Settings) propagation {
+	p := propagation{
+		labels:                    make(map[string]string),
+		annotations:               make(map[string]string),
+		annotationsToPodTemplates: settings.PropagateAnnotationsToPodTemplates,
+		overwrite:                 settings.Overwrite(),
+	}
+	if namespaceMeta == nil {
+		return p
+	}
 	for _, label := range settings.PropagatedLabels {
-		if value, namespace_has_label := namespaceLabels[label]; namespace_has_label {
-			labelsToPropagate[label] = value
+		if value, namespace_has_label := namespaceMeta.Labels[label]; namespace_has_label {
+			p.labels[label] = value
+		}
+	}
+	for _, annotation := range settings.PropagatedAnnotations {
+		if value, namespace_has_annotation := namespaceMeta.Annotations[annotation]; namespace_has_annotation {
+			p.annotations[annotation] = value
 		}
 	}
-	return updateResourceLabels(request, labelsToPropagate)
+	return p
 }
 
-// propagateLabels ensures the labels defined in the meta object contains the
-// same labels defined in the `labelsToPropagate` map. Returns `true` when
-// the meta object has been changed
-func propagateLabels(meta *metav1.ObjectMeta, labelsToPropagate map[string]string) bool {
-	if meta.Labels == nil {
-		meta.Labels = make(map[string]string)
+// propagate ensures the `values` map contains the entries defined in the
+// `valuesToPropagate` map. The entries already defined with a different
+// value are replaced only when `overwrite` is true. Returns `true` when the
+// `values` map has been changed
+func propagate(values *map[string]string, valuesToPropagate map[string]string, overwrite bool) bool {
+	if len(valuesToPropagate) == 0 {
+		return false
+	}
+	if *values == nil {
+		*values = make(map[string]string)
 	}
 
 	hasMutation := false
-	for label, newValue := range labelsToPropagate {
-		if oldValue, has_label := meta.Labels[label]; !has_label || oldValue != newValue {
-			meta.Labels[label] = newValue
-			hasMutation = true
+	for key, newValue := range valuesToPropagate {
+		if oldValue, has_key := (*values)[key]; has_key && (oldValue == newValue || !overwrite) {
+			continue
 		}
+		(*values)[key] = newValue
+		hasMutation = true
 	}
 	return hasMutation
 }
 
-func updateResourceLabels(object kubewarden_protocol.ValidationRequest, labelsToPropagate map[string]string) ([]byte, error) {
+// toObject propagates the labels and the annotations to the metadata of the
+// resource. Returns `true` when the meta object has been changed
+func (p propagation) toObject(meta *metav1.ObjectMeta) bool {
+	labelsChanged := propagate(&meta.Labels, p.labels, p.overwrite)
+	annotationsChanged := propagate(&meta.Annotations, p.annotations, p.overwrite)
+	return labelsChanged || annotationsChanged
+}
+
+// toPodTemplate propagates the labels, and optionally the annotations, to the
+// metadata of a pod template. Returns `true` when the meta object has been
+// changed
+func (p propagation) toPodTemplate(meta *metav1.ObjectMeta) bool {
+	labelsChanged := propagate(&meta.Labels, p.labels, p.overwrite)
+	annotationsChanged := false
+	if p.annotationsToPodTemplates {
+		annotationsChanged = propagate(&meta.Annotations, p.annotations, p.overwrite)
+	}
+	return labelsChanged || annotationsChanged
+}
+
+func updateResource(object kubewarden_protocol.ValidationRequest, p propagation) ([]byte, error) {
 	switch strings.ToLower(object.Request.Kind.Kind) {
 	case DEPLOYMENT_KIND:
 		deployment := appsv1.Deployment{}
 		if err := json.Unmarshal(object.Request.Object, &deployment); err != nil {
 			return nil, err
 		}
-		objChanged := propagateLabels(deployment.Metadata, labelsToPropagate)
-		podSpecChanged := propagateLabels(deployment.Spec.Template.Metadata, labelsToPropagate)
+		objChanged := p.toObject(deployment.Metadata)
+		podSpecChanged := p.toPodTemplate(deployment.Spec.Template.Metadata)
 		if objChanged || podSpecChanged {
 			return kubewarden.MutateRequest(deployment)
 		}
@@ -95,8 +142,8 @@ func updateResourceLabels(object kubewarden_protocol.ValidationRequest, labelsTo
 		if err := json.Unmarshal(object.Request.Object, &replicaset); err != nil {
 			return nil, err
 		}
-		objChanged := propagateLabels(replicaset.Metadata, labelsToPropagate)
-		podSpecChanged := propagateLabels(replicaset.Spec.Template.Metadata, labelsToPropagate)
+		objChanged := p.toObject(replicaset.Metadata)
+		podSpecChanged := p.toPodTemplate(replicaset.Spec.Template.Metadata)
 		if objChanged || podSpecChanged {
 			return kubewarden.MutateRequest(replicaset)
 		}
@@ -105,8 +152,8 @@ func updateResourceLabels(object kubewarden_protocol.ValidationRequest, labelsTo
 		if err := json.Unmarshal(object.Request.Object, &statefulset); err != nil {
 			return nil, err
 		}
-		objChanged := propagateLabels(statefulset.Metadata, labelsToPropagate)
-		podSpecChanged := propagateLabels(statefulset.Spec.Template.Metadata, labelsToPropagate)
+		objChanged := p.toObject(statefulset.Metadata)
+		podSpecChanged := p.toPodTemplate(statefulset.Spec.Template.Metadata)
 		if objChanged || podSpecChanged {
 			return kubewarden.MutateRequest(statefulset)
 		}
@@ -115,8 +162,8 @@ func updateResourceLabels(object kubewarden_protocol.ValidationRequest, labelsTo
 		if err := json.Unmarshal(object.Request.Object, &daemonset); err != nil {
 			return nil, err
 		}
-		objChanged := propagateLabels(daemonset.Metadata, labelsToPropagate)
-		podSpecChanged := propagateLabels(daemonset.Spec.Template.Metadata, labelsToPropagate)
+		objChanged := p.toObject(daemonset.Metadata)
+		podSpecChanged := p.toPodTemplate(daemonset.Spec.Template.Metadata)
 		if objChanged || podSpecChanged {
 			return kubewarden.MutateRequest(daemonset)
 		}
@@ -125,8 +172,8 @@ func updateResourceLabels(object kubewarden_protocol.ValidationRequest, labelsTo
 		if err := json.Unmarshal(object.Request.Object, &replicationController); err != nil {
 			return nil, err
 		}
-		objChanged := propagateLabels(replicationController.Metadata, labelsToPropagate)
-		podSpecChanged := propagateLabels(replicationController.Spec.Template.Metadata, labelsToPropagate)
+		objChanged := p.toObject(replicationController.Metadata)
+		podSpecChanged := p.toPodTemplate(replicationController.Spec.Template.Metadata)
 		if objChanged || podSpecChanged {
 			return kubewarden.MutateRequest(replicationController)
 		}
@@ -135,8 +182,8 @@ func updateResourceLabels(object kubewarden_protocol.ValidationRequest, labelsTo
 		if err := json.Unmarshal(object.Request.Object, &cronjob); err != nil {
 			return nil, err
 		}
-		objChanged := propagateLabels(cronjob.Metadata, labelsToPropagate)
-		podSpecChanged := propagateLabels(cronjob.Spec.JobTemplate.Spec.Template.Metadata, labelsToPropagate)
+		objChanged := p.toObject(cronjob.Metadata)
+		podSpecChanged := p.toPodTemplate(cronjob.Spec.JobTemplate.Spec.Template.Metadata)
 		if objChanged || podSpecChanged {
 			return kubewarden.MutateRequest(cronjob)
 		}
@@ -145,8 +192,8 @@ func updateResourceLabels(object kubewarden_protocol.ValidationRequest, labelsTo
 		if err := json.Unmarshal(object.Request.Object, &job); err != nil {
 			return nil, err
 		}
-		objChanged := propagateLabels(job.Metadata, labelsToPropagate)
-		podSpecChanged := propagateLabels(job.Spec.Template.Metadata, labelsToPropagate)
+		objChanged := p.toObject(job.Metadata)
+		podSpecChanged := p.toPodTemplate(job.Spec.Template.Metadata)
 		if objChanged || podSpecChanged {
 			return kubewarden.MutateRequest(job)
 		}
@@ -155,7 +202,7 @@ func updateResourceLabels(object kubewarden_protocol.ValidationRequest, labelsTo
 		if err := json.Unmarshal(object.Request.Object, &pod); err != nil {
 			return nil, err
 		}
-		objChanged := propagateLabels(pod.Metadata, labelsToPropagate)
+		objChanged := p.toObject(pod.Metadata)
 		if objChanged {
 			return kubewarden.MutateRequest(pod)
 		}
@@ -188,5 +235,5 @@ func validate(payload []byte) ([]byte, error) {
 		return kubewarden.RejectRequest(kubewarden.Message(err.Error()), kubewarden.Code(400))
 	}
 
-	return validateResourceLabels(namespace.Metadata.Labels, validationRequest, settings)
+	return updateResource(validationRequest, newPropagation(namespace.Metadata, settings))
 }
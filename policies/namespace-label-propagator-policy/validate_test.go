@@ -536,3 +536,152 @@ func TestLabelsShouldOverwrittenLabelsOnlyDefinedInSettings(t *testing.T) {
 		})
 	}
 }
+
+func TestAnnotationsPropagation(t *testing.T) {
+	namespaceMeta := &metav1.ObjectMeta{
+		Labels: map[string]string{"testing": "foo"},
+		Annotations: map[string]string{
+			"example.com/owner":  "team-a",
+			"example.com/budget": "1000",
+		},
+	}
+
+	cases := []struct {
+		name                           string
+		settings                       Settings
+		expectedAnnotations            map[string]string
+		expectedPodTemplateAnnotations map[string]string
+	}{
+		{
+			"annotations propagated to the resource only",
+			Settings{
+				PropagatedAnnotations: []string{"example.com/owner"},
+			},
+			map[string]string{"example.com/owner": "team-a", "other": "bar"},
+			map[string]string{"example.com/owner": "pod-owner"},
+		},
+		{
+			"annotations propagated to the pod template too",
+			Settings{
+				PropagatedAnnotations:              []string{"example.com/owner", "example.com/budget"},
+				PropagateAnnotationsToPodTemplates: true,
+			},
+			map[string]string{"example.com/owner": "team-a", "example.com/budget": "1000", "other": "bar"},
+			map[string]string{"example.com/owner": "team-a", "example.com/budget": "1000"},
+		},
+		{
+			"values defined in the resource are kept",
+			Settings{
+				PropagatedAnnotations:              []string{"example.com/owner", "example.com/budget"},
+				PropagateAnnotationsToPodTemplates: true,
+				ConflictPolicy:                     CONFLICT_POLICY_SKIP,
+			},
+			map[string]string{"example.com/owner": "team-a", "example.com/budget": "1000", "other": "bar"},
+			map[string]string{"example.com/owner": "pod-owner", "example.com/budget": "1000"},
+		},
+	}
+
+	for _, tc := range cases {
+		t.Run(tc.name, func(t *testing.T) {
+			deployment := appsv1.Deployment{
+				Metadata: &metav1.ObjectMeta{
+					Name:        "test",
+					Namespace:   "default",
+					Annotations: map[string]string{"other": "bar"},
+				},
+				Spec: &appsv1.DeploymentSpec{
+					Template: &corev1.PodTemplateSpec{
+						Metadata: &metav1.ObjectMeta{
+							Annotations: map[string]string{"example.com/owner": "pod-owner"},
+						},
+					},
+				},
+			}
+			payload, err := kubewarden_testing.BuildValidationRequest(deployment, &tc.settings)
+			if err != nil {
+				t.Fatalf("Unexpected error: %+v", err)
+			}
+			payload, err = updateValidationRequestKindAndNamespace(payload, DEPLOYMENT_KIND)
+			if err != nil {
+				t.Fatalf("Unexpected error: %+v", err)
+			}
+			validationRequest := kubewarden_protocol.ValidationRequest{}
+			if err := json.Unmarshal(payload, &validationRequest); err != nil {
+				t.Fatalf("Unexpected error: %+v", err)
+			}
+
+			responsePayload, err := updateResource(validationRequest, newPropagation(namespaceMeta, tc.settings))
+			if err != nil {
+				t.Fatalf("Unexpected error: %+v", err)
+			}
+			response, err := basicResposeValidation(responsePayload, SHOULD_ACCEPT, SHOULD_MUTATE)
+			if err != nil {
+				t.Fatalf("Unexpected error: %+v", err)
+			}
+
+			mutatedResourceJSON, err := json.Marshal(response.MutatedObject.(map[string]interface{}))
+			if err != nil {
+				t.Fatalf("Unexpected error: %+v", err)
+			}
+			mutatedDeployment := appsv1.Deployment{}
+			if err := json.Unmarshal(mutatedResourceJSON, &mutatedDeployment); err != nil {
+				t.Fatalf("Unexpected error: %+v", err)
+			}
+
+			if err := validateLabels(mutatedDeployment.Metadata.Annotations, tc.expectedAnnotations); err != nil {
+				t.Error(err.Error())
+			}
+			if err := validateLabels(mutatedDeployment.Spec.Template.Metadata.Annotations, tc.expectedPodTemplateAnnotations); err != nil {
+				t.Error(err.Error())
+			}
+		})
+	}
+}
+
+func TestLabelsConflictPolicySkip(t *testing.T) {
+	namespaceMeta := &metav1.ObjectMeta{
+		Labels: map[string]string{"testing": "foo", "testing2": "zpto"},
+	}
+	settings := Settings{
+		PropagatedLabels: []string{"testing", "testing2"},
+		ConflictPolicy:   CONFLICT_POLICY_SKIP,
+	}
+	pod := corev1.Pod{
+		Metadata: &metav1.ObjectMeta{
+			Name:      "test",
+			Namespace: "default",
+			Labels:    map[string]string{"testing": "bar"},
+		},
+	}
+
+	payload, err := buildValidationRequest(settings.PropagatedLabels, pod, POD_KIND)
+	if err != nil {
+		t.Fatalf("Unexpected error: %+v", err)
+	}
+	validationRequest := kubewarden_protocol.ValidationRequest{}
+	if err := json.Unmarshal(payload, &validationRequest); err != nil {
+		t.Fatalf("Unexpected error: %+v", err)
+	}
+
+	responsePayload, err := updateResource(validationRequest, newPropagation(namespaceMeta, settings))
+	if err != nil {
+		t.Fatalf("Unexpected error: %+v", err)
+	}
+	response, err := basicResposeValidation(responsePayload, SHOULD_ACCEPT, SHOULD_MUTATE)
+	if err != nil {
+		t.Fatalf("Unexpected error: %+v", err)
+	}
+
+	mutatedResourceJSON, err := json.Marshal(response.MutatedObject.(map[string]interface{}))
+	if err != nil {
+		t.Fatalf("Unexpected error: %+v", err)
+	}
+	mutatedPod := corev1.Pod{}
+	if err := json.Unmarshal(mutatedResourceJSON, &mutatedPod); err != nil {
+		t.Fatalf("Unexpected error: %+v", err)
+	}
+
+	if err := validateLabels(mutatedPod.Metadata.Labels, map[string]string{"testing": "bar", "testing2": "zpto"}); err != nil {
+		t.Error(err.Error())
+	}
+}
@@ -0,0 +1,53 @@
+#!/usr/bin/env bats
+
+@test "Accept a namespace matching the patterns of the user" {
+	run kwctl run --request-path test_data/namespace_dev_payments.json --settings-json '{"allowedPatterns": {"developers": ["dev-[a-z0-9-]+"]}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a namespace with a forbidden prefix" {
+	run kwctl run --request-path test_data/namespace_kube_payments.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Namespace 'kube-payments' is not allowed: the prefix 'kube-' is reserved.*") -ne 0 ]
+}
+
+@test "Reject a namespace not matching the patterns of the user" {
+	run kwctl run --request-path test_data/namespace_kube_payments.json --settings-json '{"allowedPatterns": {"developers": ["dev-[a-z0-9-]+"]}, "forbiddenPrefixes": []}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the name must match one of the patterns allowed for the groups of the user.*") -ne 0 ]
+}
+
+@test "Reject a namespace longer than the maximum length" {
+	run kwctl run --request-path test_data/namespace_dev_payments.json --settings-json '{"maxLength": 8}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the name is longer than the maximum of 8 characters.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/namespace_dev_payments.json --settings-json '{"allowedPatterns": {"developers": ["dev-[a-z"]}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
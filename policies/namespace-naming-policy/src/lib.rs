@@ -0,0 +1,178 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::core::v1::Namespace;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::{Settings, name_regex};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    if request.kind.kind != "Namespace" {
+        return kubewarden::accept_request();
+    }
+    let namespace = serde_json::from_value::<Namespace>(request.object)?;
+    let name = namespace.metadata.name.unwrap_or_default();
+    let groups = request.user_info.groups.unwrap_or_default();
+
+    let errors = validate_name(&name, &groups, settings);
+    if errors.is_empty() {
+        return kubewarden::accept_request();
+    }
+    kubewarden::reject_request(
+        Some(format!(
+            "Namespace '{name}' is not allowed: {}",
+            errors.join("; ")
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+// Returns the reasons why the name is not allowed for a user member of the
+// groups
+fn validate_name(name: &str, groups: &[String], settings: &Settings) -> Vec<String> {
+    let mut errors = Vec::new();
+    if let Some(max_length) = settings.max_length
+        && name.len() > max_length
+    {
+        errors.push(format!(
+            "the name is longer than the maximum of {max_length} characters"
+        ));
+    }
+    if let Some(prefix) = settings.forbidden_prefix(name) {
+        errors.push(format!("the prefix '{prefix}' is reserved"));
+    }
+    if let Some(patterns) = settings.patterns_for(groups)
+        && !patterns
+            .iter()
+            .any(|pattern| name_regex(pattern).is_ok_and(|regex| regex.is_match(name)))
+    {
+        errors.push(format!(
+            "the name must match one of the patterns allowed for the groups of the user: {}",
+            patterns
+                .iter()
+                .map(|pattern| format!("'{pattern}'"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn developers_settings() -> Settings {
+        Settings {
+            allowed_patterns: BTreeMap::from([(
+                "developers".to_owned(),
+                vec!["dev-[a-z0-9-]+".to_owned(), "team-(a|b)".to_owned()],
+            )]),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::defaults("payments", vec!["developers"], Settings::default(), vec![])]
+    #[case::forbidden_prefix(
+        "kube-payments",
+        vec!["developers"],
+        Settings::default(),
+        vec!["the prefix 'kube-' is reserved"]
+    )]
+    #[case::too_long(
+        "payments-production",
+        vec!["developers"],
+        Settings {
+            max_length: Some(10),
+            ..Default::default()
+        },
+        vec!["the name is longer than the maximum of 10 characters"]
+    )]
+    #[case::pattern_matched("dev-payments", vec!["developers"], developers_settings(), vec![])]
+    #[case::second_pattern_matched("team-a", vec!["developers"], developers_settings(), vec![])]
+    #[case::pattern_matched_partially(
+        "team-abc",
+        vec!["developers"],
+        developers_settings(),
+        vec!["the name must match one of the patterns allowed for the groups of the user: 'dev-[a-z0-9-]+', 'team-(a|b)'"]
+    )]
+    #[case::group_not_constrained("payments", vec!["operators"], developers_settings(), vec![])]
+    #[case::all_errors(
+        "openshift-payments",
+        vec!["developers"],
+        Settings {
+            max_length: Some(10),
+            ..developers_settings()
+        },
+        vec![
+            "the name is longer than the maximum of 10 characters",
+            "the prefix 'openshift-' is reserved",
+            "the name must match one of the patterns allowed for the groups of the user: 'dev-[a-z0-9-]+', 'team-(a|b)'",
+        ]
+    )]
+    fn name(
+        #[case] name: &str,
+        #[case] groups: Vec<&str>,
+        #[case] settings: Settings,
+        #[case] expected: Vec<&str>,
+    ) {
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(validate_name(name, &groups, &settings), expected);
+    }
+
+    #[rstest]
+    #[case::allowed("test_data/namespace_dev_payments.json", developers_settings(), true)]
+    #[case::forbidden_prefix("test_data/namespace_kube_payments.json", Settings::default(), false)]
+    #[case::forbidden_prefixes_disabled(
+        "test_data/namespace_kube_payments.json",
+        Settings {
+            forbidden_prefixes: vec![],
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::pattern_not_matched(
+        "test_data/namespace_kube_payments.json",
+        Settings {
+            forbidden_prefixes: vec![],
+            ..developers_settings()
+        },
+        false
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
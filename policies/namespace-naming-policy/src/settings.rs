@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Maximum length of the namespace names accepted by Kubernetes
+const MAX_NAME_LENGTH: usize = 63;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Regular expressions the names of the namespaces created by each group
+    /// of users must match. The `*` group matches all the users. The
+    /// namespaces created by users without patterns are not constrained.
+    pub allowed_patterns: BTreeMap<String, Vec<String>>,
+    /// Prefixes that cannot be used by the names of the namespaces
+    pub forbidden_prefixes: Vec<String>,
+    /// Maximum length of the names of the namespaces
+    pub max_length: Option<usize>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            allowed_patterns: BTreeMap::new(),
+            forbidden_prefixes: vec!["kube-".to_owned(), "openshift-".to_owned()],
+            max_length: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the patterns allowed for the groups, or None when the names
+    /// of the namespaces created by these groups are not constrained
+    pub fn patterns_for(&self, groups: &[String]) -> Option<Vec<&str>> {
+        let patterns: Vec<&str> = groups
+            .iter()
+            .map(String::as_str)
+            .chain(["*"])
+            .filter_map(|group| self.allowed_patterns.get(group))
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        (!patterns.is_empty()).then_some(patterns)
+    }
+
+    /// Returns the first forbidden prefix of the name
+    pub fn forbidden_prefix(&self, name: &str) -> Option<&str> {
+        self.forbidden_prefixes
+            .iter()
+            .find(|prefix| name.starts_with(prefix.as_str()))
+            .map(String::as_str)
+    }
+}
+
+/// Compiles the pattern, which must match the whole name
+pub(crate) fn name_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{pattern})$"))
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        for (group, patterns) in &self.allowed_patterns {
+            if group.is_empty() || patterns.is_empty() {
+                return Err(
+                    "allowedPatterns cannot contain empty groups or groups without patterns"
+                        .to_owned(),
+                );
+            }
+            for pattern in patterns {
+                name_regex(pattern).map_err(|e| {
+                    format!("invalid pattern '{pattern}' of the group '{group}': {e}")
+                })?;
+            }
+        }
+        if self.forbidden_prefixes.iter().any(String::is_empty) {
+            return Err("forbiddenPrefixes cannot contain empty values".to_owned());
+        }
+        if let Some(max_length) = self.max_length
+            && !(1..=MAX_NAME_LENGTH).contains(&max_length)
+        {
+            return Err(format!(
+                "maxLength must be between 1 and {MAX_NAME_LENGTH}, got {max_length}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::no_patterns(BTreeMap::new(), vec!["developers"], None)]
+    #[case::group_patterns(
+        BTreeMap::from([("developers".to_owned(), vec!["dev-.*".to_owned()])]),
+        vec!["developers"],
+        Some(vec!["dev-.*"])
+    )]
+    #[case::other_group(
+        BTreeMap::from([("developers".to_owned(), vec!["dev-.*".to_owned()])]),
+        vec!["testers"],
+        None
+    )]
+    #[case::all_users(
+        BTreeMap::from([
+            ("*".to_owned(), vec!["sandbox-.*".to_owned()]),
+            ("developers".to_owned(), vec!["dev-.*".to_owned()]),
+        ]),
+        vec!["developers"],
+        Some(vec!["dev-.*", "sandbox-.*"])
+    )]
+    fn patterns_for(
+        #[case] allowed_patterns: BTreeMap<String, Vec<String>>,
+        #[case] groups: Vec<&str>,
+        #[case] expected: Option<Vec<&str>>,
+    ) {
+        let settings = Settings {
+            allowed_patterns,
+            ..Default::default()
+        };
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(settings.patterns_for(&groups), expected);
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::allowed_patterns(r#"{"allowedPatterns": {"developers": ["dev-[a-z0-9-]+"]}}"#, true)]
+    #[case::invalid_pattern(r#"{"allowedPatterns": {"developers": ["dev-[a-z"]}}"#, false)]
+    #[case::empty_group(r#"{"allowedPatterns": {"": ["dev-.*"]}}"#, false)]
+    #[case::group_without_patterns(r#"{"allowedPatterns": {"developers": []}}"#, false)]
+    #[case::no_forbidden_prefixes(r#"{"forbiddenPrefixes": []}"#, true)]
+    #[case::empty_prefix(r#"{"forbiddenPrefixes": [""]}"#, false)]
+    #[case::max_length(r#"{"maxLength": 30}"#, true)]
+    #[case::zero_max_length(r#"{"maxLength": 0}"#, false)]
+    #[case::max_length_too_long(r#"{"maxLength": 100}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,164 @@
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::{Container, PodSpec};
+use kubewarden_policy_sdk::wapc_guest as guest;
+use workloads::is_sidecar;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+// Resources the sidecars must request
+const REQUIRED_REQUESTS: [&str; 2] = ["cpu", "memory"];
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = &validation_request.request;
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        // The resources without Pod spec are not validated
+        Ok(None) => return kubewarden::accept_request(),
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    if let Err(error) = validate_pod_spec(&pod_spec, &validation_request.settings) {
+        return kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_pod_spec(pod_spec: &PodSpec, settings: &Settings) -> Result<(), String> {
+    let errors: Vec<String> = pod_spec
+        .init_containers
+        .iter()
+        .flatten()
+        .filter(|container| is_sidecar(container.restart_policy.as_deref()))
+        .flat_map(|container| {
+            validate_sidecar(container, settings)
+                .into_iter()
+                .map(|error| format!("sidecar '{}': {error}", container.name))
+        })
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+fn validate_sidecar(container: &Container, settings: &Settings) -> Vec<String> {
+    let mut errors = vec![];
+    let image = container.image.as_deref().unwrap_or_default();
+    if !settings.is_image_allowed(image) {
+        errors.push(format!("the image '{image}' is not allowed"));
+    }
+    if settings.require_resource_requests {
+        let requests = container
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.requests.as_ref());
+        let missing: Vec<&str> = REQUIRED_REQUESTS
+            .into_iter()
+            .filter(|resource| requests.is_none_or(|requests| !requests.contains_key(*resource)))
+            .collect();
+        if !missing.is_empty() {
+            errors.push(format!(
+                "the requests of {} must be set",
+                missing.join(" and ")
+            ));
+        }
+    }
+    let privileged = container
+        .security_context
+        .as_ref()
+        .and_then(|security_context| security_context.privileged)
+        .unwrap_or_default();
+    if privileged && !settings.allow_privileged {
+        errors.push("privileged sidecars are not allowed".to_owned());
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            allowed_images: vec!["docker.io/istio/proxyv2:*".to_owned()],
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::valid_sidecar("test_data/pod_valid_sidecar.json", true)]
+    #[case::regular_init_container("test_data/pod_regular_init_container.json", true)]
+    #[case::invalid_sidecar("test_data/deployment_invalid_sidecar.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/deployment_invalid_sidecar.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Deployment 'web': sidecar 'debugger': the image 'registry.example.com/debugger:1.0' is not allowed; sidecar 'debugger': the requests of memory must be set; sidecar 'debugger': privileged sidecars are not allowed"
+            )
+        );
+    }
+
+    #[test]
+    fn relaxed_settings() {
+        let test_case = Testcase {
+            name: "relaxed settings".to_owned(),
+            fixture_file: "test_data/deployment_invalid_sidecar.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings {
+                allowed_images: vec![],
+                require_resource_requests: false,
+                allow_privileged: true,
+            },
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use wildmatch::WildMatch;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Images the sidecars can run, as patterns where `*` matches any
+    /// sequence of characters and `?` a single character. All the images
+    /// are allowed when empty.
+    pub allowed_images: Vec<String>,
+    /// Require the sidecars to request CPU and memory
+    pub require_resource_requests: bool,
+    /// Accept the privileged sidecars
+    pub allow_privileged: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            allowed_images: Vec::new(),
+            require_resource_requests: true,
+            allow_privileged: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when a sidecar can run the image
+    pub fn is_image_allowed(&self, image: &str) -> bool {
+        self.allowed_images.is_empty()
+            || self
+                .allowed_images
+                .iter()
+                .any(|pattern| WildMatch::new(pattern).matches(image))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_images.iter().any(String::is_empty) {
+            return Err("allowedImages cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{"allowedImages": ["docker.io/istio/proxyv2:*"], "requireResourceRequests": false, "allowPrivileged": true}"#,
+        true
+    )]
+    #[case::empty_image(r#"{"allowedImages": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::no_patterns(vec![], "registry.example.com/anything:1.0", true)]
+    #[case::matching_tag(vec!["docker.io/istio/proxyv2:*"], "docker.io/istio/proxyv2:1.27.0", true)]
+    #[case::other_repository(vec!["docker.io/istio/proxyv2:*"], "docker.io/istio/pilot:1.27.0", false)]
+    #[case::second_pattern(
+        vec!["docker.io/istio/proxyv2:*", "registry.example.com/sidecars/*"],
+        "registry.example.com/sidecars/log-shipper:2.1",
+        true
+    )]
+    fn image_allowed(
+        #[case] allowed_images: Vec<&str>,
+        #[case] image: &str,
+        #[case] expected: bool,
+    ) {
+        let settings = Settings {
+            allowed_images: allowed_images.into_iter().map(str::to_owned).collect(),
+            ..Default::default()
+        };
+        assert_eq!(settings.is_image_allowed(image), expected);
+    }
+}
@@ -0,0 +1,64 @@
+#!/usr/bin/env bats
+
+@test "Reject NetworkPolicy allowing all ingress traffic in a protected namespace" {
+	run kwctl run --request-path test_data/networkpolicy_allow_all_ingress.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*cannot allow ingress traffic from everywhere.*") -ne 0 ]
+}
+
+@test "Accept NetworkPolicy with restricted ingress and egress rules" {
+	run kwctl run --request-path test_data/networkpolicy_restricted_ingress.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject NetworkPolicy with the Egress type and no egress rules" {
+	run kwctl run --request-path test_data/networkpolicy_egress_without_rules.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*must define egress rules.*') -ne 0 ]
+}
+
+@test "Reject NetworkPolicy with an ipBlock outside of the allowed ranges" {
+	run kwctl run --request-path test_data/networkpolicy_public_ip_block.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*ipBlock CIDRs not contained in the allowed ranges: 0.0.0.0/0.*') -ne 0 ]
+}
+
+@test "Accept NetworkPolicy with a public ipBlock when no CIDR restriction is configured" {
+	run kwctl run --request-path test_data/networkpolicy_public_ip_block.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid CIDR in settings" {
+	run kwctl run --request-path test_data/networkpolicy_public_ip_block.json --settings-json '{"allowedIpBlockCidrs": ["10.0.0.0/33"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+	[ $(expr "$output" : '.*allowedIpBlockCidrs contains invalid CIDRs.*') -ne 0 ]
+}
@@ -0,0 +1,294 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::Resource;
+use k8s_openapi::api::networking::v1::{NetworkPolicy, NetworkPolicyPeer};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+use cidr::Cidr;
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    if validation_request.request.kind.kind != NetworkPolicy::KIND {
+        return kubewarden::accept_request();
+    }
+    let network_policy =
+        serde_json::from_value::<NetworkPolicy>(validation_request.request.object)?;
+    let namespace = network_policy
+        .metadata
+        .namespace
+        .clone()
+        .unwrap_or(validation_request.request.namespace);
+
+    let errors = validate_network_policy(&network_policy, &namespace, &validation_request.settings);
+    if !errors.is_empty() {
+        return kubewarden::reject_request(Some(errors.join(", ")), None, None, None);
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_network_policy(
+    network_policy: &NetworkPolicy,
+    namespace: &str,
+    settings: &Settings,
+) -> Vec<String> {
+    let mut errors = vec![];
+    let spec = network_policy.spec.clone().unwrap_or_default();
+
+    if settings.protected_namespaces.contains(namespace)
+        && is_empty_selector(spec.pod_selector.clone())
+        && spec
+            .ingress
+            .as_ref()
+            .is_some_and(|rules| rules.iter().any(|rule| allows_all_peers(&rule.from)))
+    {
+        errors.push(format!(
+            "NetworkPolicies selecting all the Pods of the protected namespace '{namespace}' cannot allow ingress traffic from everywhere"
+        ));
+    }
+
+    if let Some(allowed_cidrs) = settings.allowed_cidrs() {
+        let ingress_peers = spec
+            .ingress
+            .iter()
+            .flatten()
+            .flat_map(|rule| rule.from.iter().flatten());
+        let egress_peers = spec
+            .egress
+            .iter()
+            .flatten()
+            .flat_map(|rule| rule.to.iter().flatten());
+        let mut invalid_cidrs: Vec<String> = ingress_peers
+            .chain(egress_peers)
+            .filter_map(|peer| peer.ip_block.as_ref())
+            .filter(|ip_block| !is_cidr_allowed(&ip_block.cidr, &allowed_cidrs))
+            .map(|ip_block| ip_block.cidr.clone())
+            .collect();
+        invalid_cidrs.sort();
+        invalid_cidrs.dedup();
+        if !invalid_cidrs.is_empty() {
+            errors.push(format!(
+                "ipBlock CIDRs not contained in the allowed ranges: {}",
+                invalid_cidrs.join(", ")
+            ));
+        }
+    }
+
+    if settings.require_egress_rules
+        && spec
+            .policy_types
+            .as_ref()
+            .is_some_and(|types| types.iter().any(|t| t == "Egress"))
+        && spec.egress.as_ref().is_none_or(|rules| rules.is_empty())
+    {
+        errors.push(
+            "NetworkPolicies with the Egress policy type must define egress rules".to_owned(),
+        );
+    }
+
+    errors
+}
+
+// An ingress rule without peers, or with a peer matching all the Pods of all
+// the namespaces, allows traffic from everywhere.
+fn allows_all_peers(peers: &Option<Vec<NetworkPolicyPeer>>) -> bool {
+    match peers {
+        None => true,
+        Some(peers) if peers.is_empty() => true,
+        Some(peers) => peers.iter().any(|peer| {
+            peer.ip_block.is_none()
+                && peer
+                    .namespace_selector
+                    .as_ref()
+                    .is_some_and(|selector| is_empty_selector(selector.clone()))
+                && is_empty_selector(peer.pod_selector.clone())
+        }),
+    }
+}
+
+fn is_empty_selector(selector: impl Into<Option<LabelSelector>>) -> bool {
+    let selector = selector.into().unwrap_or_default();
+    selector.match_labels.unwrap_or_default().is_empty()
+        && selector.match_expressions.unwrap_or_default().is_empty()
+}
+
+fn is_cidr_allowed(cidr: &str, allowed_cidrs: &[Cidr]) -> bool {
+    match cidr.parse::<Cidr>() {
+        Ok(cidr) => allowed_cidrs.iter().any(|allowed| allowed.contains(&cidr)),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::{BTreeMap, HashSet};
+
+    use k8s_openapi::api::networking::v1::{
+        IPBlock, NetworkPolicyEgressRule, NetworkPolicyIngressRule, NetworkPolicySpec,
+    };
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn ip_block_peer(cidr: &str) -> NetworkPolicyPeer {
+        NetworkPolicyPeer {
+            ip_block: Some(IPBlock {
+                cidr: cidr.to_owned(),
+                except: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn labels_selector() -> LabelSelector {
+        LabelSelector {
+            match_labels: Some(BTreeMap::from([("app".to_owned(), "web".to_owned())])),
+            ..Default::default()
+        }
+    }
+
+    fn network_policy(spec: NetworkPolicySpec) -> NetworkPolicy {
+        NetworkPolicy {
+            spec: Some(spec),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::no_peers(None, true)]
+    #[case::empty_peers(Some(vec![]), true)]
+    #[case::all_namespaces(Some(vec![NetworkPolicyPeer {
+        namespace_selector: Some(LabelSelector::default()),
+        ..Default::default()
+    }]), true)]
+    #[case::selected_namespaces(Some(vec![NetworkPolicyPeer {
+        namespace_selector: Some(labels_selector()),
+        ..Default::default()
+    }]), false)]
+    #[case::same_namespace_pods(Some(vec![NetworkPolicyPeer {
+        pod_selector: Some(LabelSelector::default()),
+        ..Default::default()
+    }]), false)]
+    #[case::ip_block(Some(vec![ip_block_peer("10.0.0.0/8")]), false)]
+    fn allow_all_peers(#[case] peers: Option<Vec<NetworkPolicyPeer>>, #[case] expected: bool) {
+        assert_eq!(allows_all_peers(&peers), expected);
+    }
+
+    #[rstest]
+    #[case::allow_all_in_protected_namespace("kube-system", None, true)]
+    #[case::allow_all_in_other_namespace("default", None, false)]
+    #[case::pod_selector_in_protected_namespace("kube-system", Some(labels_selector()), false)]
+    fn protected_namespaces(
+        #[case] namespace: &str,
+        #[case] pod_selector: Option<LabelSelector>,
+        #[case] expect_error: bool,
+    ) {
+        let settings = Settings {
+            protected_namespaces: HashSet::from(["kube-system".to_owned()]),
+            ..Default::default()
+        };
+        let network_policy = network_policy(NetworkPolicySpec {
+            pod_selector: pod_selector.unwrap_or_default().into(),
+            ingress: Some(vec![NetworkPolicyIngressRule::default()]),
+            ..Default::default()
+        });
+
+        let errors = validate_network_policy(&network_policy, namespace, &settings);
+        assert_eq!(!errors.is_empty(), expect_error, "errors: {errors:?}");
+    }
+
+    #[rstest]
+    #[case::allowed_ingress(vec!["10.1.0.0/16"], vec![], true)]
+    #[case::allowed_egress(vec![], vec!["192.168.10.0/24"], true)]
+    #[case::denied_ingress(vec!["0.0.0.0/0"], vec![], false)]
+    #[case::denied_egress(vec![], vec!["8.8.8.8/32"], false)]
+    #[case::invalid_cidr(vec!["not-a-cidr"], vec![], false)]
+    fn ip_block_cidrs(
+        #[case] ingress_cidrs: Vec<&str>,
+        #[case] egress_cidrs: Vec<&str>,
+        #[case] is_valid: bool,
+    ) {
+        let settings = Settings {
+            allowed_ip_block_cidrs: vec!["10.0.0.0/8".to_owned(), "192.168.0.0/16".to_owned()],
+            ..Default::default()
+        };
+        let network_policy = network_policy(NetworkPolicySpec {
+            pod_selector: labels_selector().into(),
+            ingress: Some(vec![NetworkPolicyIngressRule {
+                from: Some(ingress_cidrs.into_iter().map(ip_block_peer).collect()),
+                ..Default::default()
+            }]),
+            egress: Some(vec![NetworkPolicyEgressRule {
+                to: Some(egress_cidrs.into_iter().map(ip_block_peer).collect()),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        });
+
+        let errors = validate_network_policy(&network_policy, "default", &settings);
+        assert_eq!(errors.is_empty(), is_valid, "errors: {errors:?}");
+    }
+
+    #[rstest]
+    #[case::egress_type_without_rules(Some(vec!["Egress"]), None, true, false)]
+    #[case::egress_type_with_empty_rules(Some(vec!["Ingress", "Egress"]), Some(vec![]), true, false)]
+    #[case::egress_type_with_rules(Some(vec!["Egress"]), Some(vec![NetworkPolicyEgressRule::default()]), true, true)]
+    #[case::ingress_type_only(Some(vec!["Ingress"]), None, true, true)]
+    #[case::check_disabled(Some(vec!["Egress"]), None, false, true)]
+    fn egress_rules(
+        #[case] policy_types: Option<Vec<&str>>,
+        #[case] egress: Option<Vec<NetworkPolicyEgressRule>>,
+        #[case] require_egress_rules: bool,
+        #[case] is_valid: bool,
+    ) {
+        let settings = Settings {
+            require_egress_rules,
+            ..Default::default()
+        };
+        let network_policy = network_policy(NetworkPolicySpec {
+            pod_selector: labels_selector().into(),
+            policy_types: policy_types.map(|types| types.into_iter().map(String::from).collect()),
+            egress,
+            ..Default::default()
+        });
+
+        let errors = validate_network_policy(&network_policy, "default", &settings);
+        assert_eq!(errors.is_empty(), is_valid, "errors: {errors:?}");
+    }
+
+    #[rstest]
+    #[case::allow_all_in_protected_namespace(
+        "test_data/networkpolicy_allow_all_ingress.json",
+        false
+    )]
+    #[case::restricted_ingress("test_data/networkpolicy_restricted_ingress.json", true)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: Settings {
+                protected_namespaces: HashSet::from(["kube-system".to_owned()]),
+                allowed_ip_block_cidrs: vec!["10.0.0.0/8".to_owned()],
+                ..Default::default()
+            },
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
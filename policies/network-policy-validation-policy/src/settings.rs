@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use cidr::Cidr;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Namespaces where NetworkPolicies selecting all the Pods cannot allow
+    /// ingress traffic from everywhere.
+    pub protected_namespaces: HashSet<String>,
+    /// CIDR ranges that `ipBlock` peers must be contained in. When empty,
+    /// any CIDR is accepted.
+    pub allowed_ip_block_cidrs: Vec<String>,
+    /// Reject NetworkPolicies having `Egress` among their `policyTypes`
+    /// without any egress rule.
+    pub require_egress_rules: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            protected_namespaces: HashSet::new(),
+            allowed_ip_block_cidrs: Vec::new(),
+            require_egress_rules: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the parsed `allowedIpBlockCidrs`, or `None` when no restriction
+    /// is configured. The values are checked during settings validation,
+    /// hence invalid entries are skipped here.
+    pub fn allowed_cidrs(&self) -> Option<Vec<Cidr>> {
+        if self.allowed_ip_block_cidrs.is_empty() {
+            return None;
+        }
+        Some(
+            self.allowed_ip_block_cidrs
+                .iter()
+                .filter_map(|cidr| cidr.parse::<Cidr>().ok())
+                .collect(),
+        )
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        let invalid: Vec<String> = self
+            .allowed_ip_block_cidrs
+            .iter()
+            .filter(|cidr| cidr.parse::<Cidr>().is_err())
+            .cloned()
+            .collect();
+        if !invalid.is_empty() {
+            return Err(format!(
+                "allowedIpBlockCidrs contains invalid CIDRs: {}",
+                invalid.join(", ")
+            ));
+        }
+        if self.protected_namespaces.iter().any(|ns| ns.is_empty()) {
+            return Err("protectedNamespaces cannot contain empty names".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(vec![], vec![], true)]
+    #[case::valid_cidrs(vec!["10.0.0.0/8", "fd00::/8"], vec!["kube-system"], true)]
+    #[case::invalid_cidr(vec!["10.0.0.0/33"], vec![], false)]
+    #[case::not_a_cidr(vec!["foo"], vec![], false)]
+    #[case::empty_namespace(vec![], vec![""], false)]
+    fn validate_settings(
+        #[case] allowed_ip_block_cidrs: Vec<&str>,
+        #[case] protected_namespaces: Vec<&str>,
+        #[case] is_valid: bool,
+    ) {
+        let settings = Settings {
+            allowed_ip_block_cidrs: allowed_ip_block_cidrs
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            protected_namespaces: protected_namespaces.into_iter().map(String::from).collect(),
+            ..Default::default()
+        };
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,61 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod without nodeName" {
+	run kwctl run --request-path test_data/pod_without_node_name.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Pod with nodeName" {
+	run kwctl run --request-path test_data/pod_node_name.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*user 'developer' cannot set nodeName.*") -ne 0 ]
+}
+
+@test "Reject a Deployment with nodeName in its template" {
+	run kwctl run --request-path test_data/deployment_node_name.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Accept a mirror Pod created by the kubelet" {
+	run kwctl run --request-path test_data/pod_mirror.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Pod with nodeName created by an allowed user" {
+	run kwctl run --request-path test_data/pod_node_name.json --settings-json '{"allowedUsers": ["developer"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_node_name.json --settings-json '{"allowedGroups": [""]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,126 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use user_allowlist::UserAllowlist;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = &validation_request.request;
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        // The resources without Pod spec are not validated
+        Ok(None) => return kubewarden::accept_request(),
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    if pod_spec.node_name.as_deref().is_none_or(str::is_empty) {
+        return kubewarden::accept_request();
+    }
+
+    let username = request.user_info.username.clone().unwrap_or_default();
+    let groups = request.user_info.groups.clone().unwrap_or_default();
+    if validation_request
+        .settings
+        .is_user_allowed(&username, &groups)
+    {
+        return kubewarden::accept_request();
+    }
+
+    kubewarden::reject_request(
+        Some(format!(
+            "{} '{}': user '{username}' cannot set nodeName, use a nodeSelector or a node affinity instead",
+            request.kind.kind, request.name
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::without_node_name("test_data/pod_without_node_name.json", Settings::default(), true)]
+    #[case::node_name("test_data/pod_node_name.json", Settings::default(), false)]
+    #[case::node_name_in_template(
+        "test_data/deployment_node_name.json",
+        Settings::default(),
+        false
+    )]
+    #[case::mirror_pod("test_data/pod_mirror.json", Settings::default(), true)]
+    #[case::allowed_user(
+        "test_data/pod_node_name.json",
+        Settings {
+            allowed_users: ["developer".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::mirror_pod_without_allowed_groups(
+        "test_data/pod_mirror.json",
+        Settings {
+            allowed_groups: [].into(),
+            ..Default::default()
+        },
+        false
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::node_name(
+        "test_data/pod_node_name.json",
+        "Pod 'web': user 'developer' cannot set nodeName, use a nodeSelector or a node affinity instead"
+    )]
+    #[case::node_name_in_template(
+        "test_data/deployment_node_name.json",
+        "Deployment 'web': user 'developer' cannot set nodeName, use a nodeSelector or a node affinity instead"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+}
@@ -0,0 +1,54 @@
+#!/usr/bin/env bats
+
+@test "Reject a LoadBalancer Service exceeding the quota" {
+	run kwctl run \
+		--request-path test_data/service_load_balancer_create.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-quota-reached.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Service 'web': the 'team-a' namespace cannot have more than 2 Service resources of type LoadBalancer.*") -ne 0 ]
+}
+
+@test "Accept a LoadBalancer Service below the quota" {
+	run kwctl run \
+		--request-path test_data/service_load_balancer_create.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-below-quota.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Service not counted by the quotas" {
+	run kwctl run \
+		--request-path test_data/service_cluster_ip_create.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/service_cluster_ip_create.json --settings-json '{"quotas": [{"kind": "Ingress", "max": 1, "serviceTypes": ["LoadBalancer"]}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
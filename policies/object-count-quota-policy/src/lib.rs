@@ -0,0 +1,289 @@
+use std::collections::BTreeSet;
+
+use guest::prelude::*;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{
+    ConfigMap, PersistentVolumeClaim, Secret, Service, ServiceAccount,
+};
+use k8s_openapi::api::networking::v1::{Ingress, NetworkPolicy};
+use k8s_openapi::api::rbac::v1::{Role, RoleBinding};
+use k8s_openapi::{ListableResource, Resource};
+use kubewarden_policy_sdk::wapc_guest as guest;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::ListResourcesByNamespaceRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::list_resources_by_namespace;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::list_resources_by_namespace;
+
+mod settings;
+use settings::{Quota, QuotaKind, Settings};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = &validation_request.request;
+
+    // Only the resources being created or updated can exceed the quotas
+    if request.operation != "CREATE" && request.operation != "UPDATE" {
+        return kubewarden::accept_request();
+    }
+
+    let errors: Vec<String> = validation_request
+        .settings
+        .quotas
+        .iter()
+        .filter(|quota| quota.kind.matches(&request.kind.group, &request.kind.kind))
+        .filter(|quota| quota.counts(&request.object))
+        // The updates of the resources already counted do not change the count
+        .filter(|quota| request.operation != "UPDATE" || !quota.counts(&request.old_object))
+        .filter_map(
+            |quota| match count_resources(quota, &request.namespace, &request.name) {
+                Ok(count) if count >= quota.max => Some(format!(
+                    "the '{}' namespace cannot have more than {quota}",
+                    request.namespace
+                )),
+                Ok(_) => None,
+                Err(e) => Some(format!(
+                    "cannot count the {} resources of the '{}' namespace: {e}",
+                    quota.kind, request.namespace
+                )),
+            },
+        )
+        .collect();
+    if !errors.is_empty() {
+        return kubewarden::reject_request(
+            Some(format!(
+                "{} '{}': {}",
+                request.kind.kind,
+                request.name,
+                errors.join("; ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+// Only the fields used to count the resources are requested: the data of the
+// Secrets and of the ConfigMaps is never read by the policy
+fn list_resources<T>(quota: &Quota, namespace: &str) -> anyhow::Result<Vec<Value>>
+where
+    T: ListableResource + DeserializeOwned + Serialize + Clone + 'static,
+{
+    let resources = list_resources_by_namespace::<T>(&ListResourcesByNamespaceRequest {
+        api_version: T::API_VERSION.to_owned(),
+        kind: T::KIND.to_owned(),
+        namespace: namespace.to_owned(),
+        label_selector: quota.label_selector(),
+        field_selector: None,
+        field_masks: Some(BTreeSet::from([
+            "metadata.name".to_owned(),
+            "metadata.labels".to_owned(),
+            "spec.type".to_owned(),
+        ])),
+    })?;
+    resources
+        .items
+        .iter()
+        .map(|resource| serde_json::to_value(resource).map_err(anyhow::Error::from))
+        .collect()
+}
+
+/// Returns the number of resources of the namespace counted by the quota,
+/// the resource being validated excluded
+fn count_resources(quota: &Quota, namespace: &str, name: &str) -> anyhow::Result<usize> {
+    let resources = match quota.kind {
+        QuotaKind::ConfigMap => list_resources::<ConfigMap>(quota, namespace),
+        QuotaKind::CronJob => list_resources::<CronJob>(quota, namespace),
+        QuotaKind::DaemonSet => list_resources::<DaemonSet>(quota, namespace),
+        QuotaKind::Deployment => list_resources::<Deployment>(quota, namespace),
+        QuotaKind::Ingress => list_resources::<Ingress>(quota, namespace),
+        QuotaKind::Job => list_resources::<Job>(quota, namespace),
+        QuotaKind::NetworkPolicy => list_resources::<NetworkPolicy>(quota, namespace),
+        QuotaKind::PersistentVolumeClaim => {
+            list_resources::<PersistentVolumeClaim>(quota, namespace)
+        }
+        QuotaKind::Role => list_resources::<Role>(quota, namespace),
+        QuotaKind::RoleBinding => list_resources::<RoleBinding>(quota, namespace),
+        QuotaKind::Secret => list_resources::<Secret>(quota, namespace),
+        QuotaKind::Service => list_resources::<Service>(quota, namespace),
+        QuotaKind::ServiceAccount => list_resources::<ServiceAccount>(quota, namespace),
+        QuotaKind::StatefulSet => list_resources::<StatefulSet>(quota, namespace),
+    }?;
+    Ok(resources
+        .iter()
+        .filter(|resource| resource["metadata"]["name"] != name)
+        .filter(|resource| quota.counts(resource))
+        .count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use k8s_openapi::api::core::v1::ServiceSpec;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::ListResourcesByNamespaceRequest;
+
+        #[allow(dead_code)]
+        pub fn list_resources_by_namespace<T>(
+            _req: &ListResourcesByNamespaceRequest,
+        ) -> anyhow::Result<k8s_openapi::List<T>>
+        where
+            T: k8s_openapi::ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+        {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    fn service(name: &str, type_: &str) -> Service {
+        Service {
+            metadata: ObjectMeta {
+                name: Some(name.to_owned()),
+                namespace: Some("team-a".to_owned()),
+                ..Default::default()
+            },
+            spec: Some(ServiceSpec {
+                type_: Some(type_.to_owned()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn settings() -> Settings {
+        serde_json::from_str(
+            r#"{"quotas": [
+                {"kind": "Service", "max": 2, "serviceTypes": ["LoadBalancer"]},
+                {"kind": "CronJob", "max": 50}
+            ]}"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    #[rstest]
+    #[case::below_quota(
+        "test_data/service_load_balancer_create.json",
+        &[("api", "LoadBalancer"), ("db", "ClusterIP")],
+        true
+    )]
+    #[case::quota_reached(
+        "test_data/service_load_balancer_create.json",
+        &[("api", "LoadBalancer"), ("admin", "LoadBalancer"), ("db", "ClusterIP")],
+        false
+    )]
+    #[case::already_counted(
+        "test_data/service_load_balancer_update.json",
+        &[("api", "LoadBalancer"), ("admin", "LoadBalancer"), ("web", "LoadBalancer")],
+        true
+    )]
+    #[case::changed_to_load_balancer(
+        "test_data/service_type_change_update.json",
+        &[("api", "LoadBalancer"), ("admin", "LoadBalancer"), ("web", "ClusterIP")],
+        false
+    )]
+    #[serial]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] services: &'static [(&'static str, &'static str)],
+        #[case] expected_validation_result: bool,
+    ) {
+        let ctx = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx.expect::<Service>().returning(move |req| {
+            if req.namespace != "team-a" {
+                return Err(anyhow::anyhow!(
+                    "it's not listing the Services of the namespace"
+                ));
+            }
+            Ok(k8s_openapi::List {
+                items: services
+                    .iter()
+                    .map(|(name, type_)| service(name, type_))
+                    .collect(),
+                ..Default::default()
+            })
+        });
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::not_counted_kind("test_data/configmap_create.json")]
+    #[case::not_counted_type("test_data/service_cluster_ip_create.json")]
+    #[serial]
+    fn not_counted(#[case] fixture: &str) {
+        let ctx = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx.expect::<Service>().times(0);
+        ctx.expect::<ConfigMap>().times(0);
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: true,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    #[serial]
+    fn rejection_message() {
+        let ctx = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx.expect::<Service>().times(1).returning(|_| {
+            Ok(k8s_openapi::List {
+                items: vec![
+                    service("api", "LoadBalancer"),
+                    service("admin", "LoadBalancer"),
+                ],
+                ..Default::default()
+            })
+        });
+
+        let test_case = Testcase {
+            name: "quota reached".to_owned(),
+            fixture_file: "test_data/service_load_balancer_create.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Service 'web': the 'team-a' namespace cannot have more than 2 Service resources of type LoadBalancer"
+            )
+        );
+    }
+}
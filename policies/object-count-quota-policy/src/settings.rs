@@ -0,0 +1,261 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use k8s_openapi::Resource;
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{
+    ConfigMap, PersistentVolumeClaim, Secret, Service, ServiceAccount,
+};
+use k8s_openapi::api::networking::v1::{Ingress, NetworkPolicy};
+use k8s_openapi::api::rbac::v1::{Role, RoleBinding};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const SERVICE_TYPES: [&str; 4] = ["ClusterIP", "NodePort", "LoadBalancer", "ExternalName"];
+
+/// Kinds whose resources can be counted
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum QuotaKind {
+    ConfigMap,
+    CronJob,
+    DaemonSet,
+    Deployment,
+    Ingress,
+    Job,
+    NetworkPolicy,
+    PersistentVolumeClaim,
+    Role,
+    RoleBinding,
+    Secret,
+    Service,
+    ServiceAccount,
+    StatefulSet,
+}
+
+impl QuotaKind {
+    /// Returns the API group and the name of the kind
+    pub fn group_kind(&self) -> (&'static str, &'static str) {
+        match self {
+            QuotaKind::ConfigMap => (ConfigMap::GROUP, ConfigMap::KIND),
+            QuotaKind::CronJob => (CronJob::GROUP, CronJob::KIND),
+            QuotaKind::DaemonSet => (DaemonSet::GROUP, DaemonSet::KIND),
+            QuotaKind::Deployment => (Deployment::GROUP, Deployment::KIND),
+            QuotaKind::Ingress => (Ingress::GROUP, Ingress::KIND),
+            QuotaKind::Job => (Job::GROUP, Job::KIND),
+            QuotaKind::NetworkPolicy => (NetworkPolicy::GROUP, NetworkPolicy::KIND),
+            QuotaKind::PersistentVolumeClaim => {
+                (PersistentVolumeClaim::GROUP, PersistentVolumeClaim::KIND)
+            }
+            QuotaKind::Role => (Role::GROUP, Role::KIND),
+            QuotaKind::RoleBinding => (RoleBinding::GROUP, RoleBinding::KIND),
+            QuotaKind::Secret => (Secret::GROUP, Secret::KIND),
+            QuotaKind::Service => (Service::GROUP, Service::KIND),
+            QuotaKind::ServiceAccount => (ServiceAccount::GROUP, ServiceAccount::KIND),
+            QuotaKind::StatefulSet => (StatefulSet::GROUP, StatefulSet::KIND),
+        }
+    }
+
+    /// Returns true when the kind has the given API group and name
+    pub fn matches(&self, group: &str, kind: &str) -> bool {
+        self.group_kind() == (group, kind)
+    }
+}
+
+impl fmt::Display for QuotaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.group_kind().1)
+    }
+}
+
+/// Maximum number of resources of a kind per namespace
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Quota {
+    pub kind: QuotaKind,
+    pub max: usize,
+    /// Labels the resources must have to be counted
+    #[serde(default)]
+    pub match_labels: BTreeMap<String, String>,
+    /// Types of the Services counted, all of them when empty
+    #[serde(default)]
+    pub service_types: BTreeSet<String>,
+}
+
+impl Quota {
+    /// Returns the label selector matching the resources counted by the
+    /// quota
+    pub fn label_selector(&self) -> Option<String> {
+        if self.match_labels.is_empty() {
+            return None;
+        }
+        Some(
+            self.match_labels
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+    }
+
+    /// Returns true when the resource is counted by the quota
+    pub fn counts(&self, object: &Value) -> bool {
+        let labels = &object["metadata"]["labels"];
+        let labels_match = self
+            .match_labels
+            .iter()
+            .all(|(key, value)| labels[key] == *value);
+        // The Services without type are ClusterIP Services
+        let service_type = object["spec"]["type"].as_str().unwrap_or("ClusterIP");
+        labels_match && (self.service_types.is_empty() || self.service_types.contains(service_type))
+    }
+}
+
+impl fmt::Display for Quota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} resources", self.max, self.kind)?;
+        if !self.service_types.is_empty() {
+            let service_types: Vec<&str> = self.service_types.iter().map(String::as_str).collect();
+            write!(f, " of type {}", service_types.join(", "))?;
+        }
+        if let Some(label_selector) = self.label_selector() {
+            write!(f, " with labels {label_selector}")?;
+        }
+        Ok(())
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    pub quotas: Vec<Quota>,
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.quotas.is_empty() {
+            return Err("at least one quota must be provided".to_owned());
+        }
+        for quota in &self.quotas {
+            if quota.match_labels.contains_key("") {
+                return Err(format!(
+                    "quota of kind {}: the keys of matchLabels cannot be empty",
+                    quota.kind
+                ));
+            }
+            if quota.service_types.is_empty() {
+                continue;
+            }
+            if quota.kind != QuotaKind::Service {
+                return Err(format!(
+                    "quota of kind {}: serviceTypes can be set only for the Services",
+                    quota.kind
+                ));
+            }
+            if let Some(service_type) = quota
+                .service_types
+                .iter()
+                .find(|service_type| !SERVICE_TYPES.contains(&service_type.as_str()))
+            {
+                return Err(format!(
+                    "quota of kind {}: unknown Service type '{service_type}'",
+                    quota.kind
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+    use serde_json::json;
+
+    #[rstest]
+    #[case::no_quotas(r#"{}"#, false)]
+    #[case::all_settings(
+        r#"{"quotas": [
+            {"kind": "Service", "max": 2, "serviceTypes": ["LoadBalancer"]},
+            {"kind": "CronJob", "max": 50, "matchLabels": {"team": "data"}}
+        ]}"#,
+        true
+    )]
+    #[case::unknown_kind(r#"{"quotas": [{"kind": "Pod", "max": 10}]}"#, false)]
+    #[case::negative_max(r#"{"quotas": [{"kind": "CronJob", "max": -1}]}"#, false)]
+    #[case::empty_label_key(
+        r#"{"quotas": [{"kind": "CronJob", "max": 1, "matchLabels": {"": "data"}}]}"#,
+        false
+    )]
+    #[case::service_types_of_other_kind(
+        r#"{"quotas": [{"kind": "Ingress", "max": 1, "serviceTypes": ["LoadBalancer"]}]}"#,
+        false
+    )]
+    #[case::unknown_service_type(
+        r#"{"quotas": [{"kind": "Service", "max": 1, "serviceTypes": ["Headless"]}]}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let is_valid_settings = serde_json::from_str::<Settings>(settings)
+            .map_err(|e| e.to_string())
+            .and_then(|settings| settings.validate());
+        assert_eq!(is_valid_settings.is_ok(), is_valid);
+    }
+
+    fn quota(service_types: &[&str], match_labels: &[(&str, &str)]) -> Quota {
+        Quota {
+            kind: QuotaKind::Service,
+            max: 2,
+            match_labels: match_labels
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            service_types: service_types.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[rstest]
+    #[case::no_filters(quota(&[], &[]), json!({"metadata": {"name": "web"}}), true)]
+    #[case::service_type(
+        quota(&["LoadBalancer"], &[]),
+        json!({"metadata": {"name": "web"}, "spec": {"type": "LoadBalancer"}}),
+        true
+    )]
+    #[case::other_service_type(
+        quota(&["LoadBalancer"], &[]),
+        json!({"metadata": {"name": "web"}, "spec": {"type": "NodePort"}}),
+        false
+    )]
+    #[case::default_service_type(
+        quota(&["ClusterIP"], &[]),
+        json!({"metadata": {"name": "web"}, "spec": {}}),
+        true
+    )]
+    #[case::labels(
+        quota(&[], &[("team", "data")]),
+        json!({"metadata": {"name": "web", "labels": {"team": "data", "app": "web"}}}),
+        true
+    )]
+    #[case::other_labels(
+        quota(&[], &[("team", "data")]),
+        json!({"metadata": {"name": "web", "labels": {"team": "web"}}}),
+        false
+    )]
+    fn counts(#[case] quota: Quota, #[case] object: Value, #[case] expected: bool) {
+        assert_eq!(quota.counts(&object), expected);
+    }
+
+    #[test]
+    fn describe_quota() {
+        let quota = quota(&["LoadBalancer", "NodePort"], &[("team", "data")]);
+        assert_eq!(
+            quota.to_string(),
+            "2 Service resources of type LoadBalancer, NodePort with labels team=data"
+        );
+    }
+}
@@ -0,0 +1,55 @@
+#!/usr/bin/env bats
+
+@test "Accept a Namespace with a known owner" {
+	run kwctl run \
+		--request-path test_data/namespace_with_owner.json \
+		--settings-path test_data/settings.yaml \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-owner-known.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Namespace with an unknown owner" {
+	run kwctl run \
+		--request-path test_data/namespace_with_owner.json \
+		--settings-path test_data/settings.yaml \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-owner-unknown.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Namespace 'payments-prod': unknown owner 'team-payments'.*") -ne 0 ]
+}
+
+@test "Reject a Deployment without owner" {
+	run kwctl run \
+		--request-path test_data/deployment_without_owner.json \
+		--settings-path test_data/settings.yaml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Deployment 'checkout': the 'owner' label or annotation must be set.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/deployment_without_owner.json --settings-json '{"directory": {"namespace": "kubewarden"}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
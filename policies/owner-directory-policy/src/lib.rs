@@ -0,0 +1,237 @@
+use guest::prelude::*;
+use k8s_openapi::Resource;
+use k8s_openapi::api::core::v1::ConfigMap;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::GetResourceRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::get_resource;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::get_resource;
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+
+    let metadata: ObjectMeta = match request.object.get("metadata") {
+        Some(metadata) => serde_json::from_value(metadata.clone())?,
+        None => return kubewarden::accept_request(),
+    };
+    if let Err(error) = validate_owner(&metadata, &validation_request.settings) {
+        return kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+// Returns the owner of the resource. The label takes precedence over the
+// annotation.
+fn owner<'a>(metadata: &'a ObjectMeta, owner_key: &str) -> Option<&'a str> {
+    [&metadata.labels, &metadata.annotations]
+        .into_iter()
+        .flatten()
+        .find_map(|values| values.get(owner_key))
+        .map(String::as_str)
+}
+
+fn validate_owner(metadata: &ObjectMeta, settings: &Settings) -> Result<(), String> {
+    let Some(owner) = owner(metadata, &settings.owner_key).filter(|owner| !owner.is_empty()) else {
+        return Err(format!(
+            "the '{}' label or annotation must be set",
+            settings.owner_key
+        ));
+    };
+
+    let kube_request = GetResourceRequest {
+        name: settings.directory.name.clone(),
+        api_version: ConfigMap::API_VERSION.to_owned(),
+        kind: ConfigMap::KIND.to_owned(),
+        field_masks: None,
+        namespace: Some(settings.directory.namespace.clone()),
+        disable_cache: false,
+    };
+    let directory = get_resource::<ConfigMap>(&kube_request).map_err(|e| {
+        format!(
+            "cannot read the owner directory '{}/{}': {e}",
+            settings.directory.namespace, settings.directory.name
+        )
+    })?;
+    if !directory
+        .data
+        .is_some_and(|owners| owners.contains_key(owner))
+    {
+        return Err(format!("unknown owner '{owner}'"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+
+    use settings::Directory;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::GetResourceRequest;
+
+        #[allow(dead_code)]
+        pub fn get_resource<T: 'static>(_req: &GetResourceRequest) -> anyhow::Result<T> {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            directory: Directory {
+                namespace: "kubewarden".to_owned(),
+                name: "owners".to_owned(),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn directory() -> ConfigMap {
+        ConfigMap {
+            data: Some(BTreeMap::from([
+                (
+                    "team-payments".to_owned(),
+                    "payments@example.com".to_owned(),
+                ),
+                ("team-search".to_owned(), "search@example.com".to_owned()),
+            ])),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::label(Some("team-a"), None, Some("team-a"))]
+    #[case::annotation(None, Some("team-b"), Some("team-b"))]
+    #[case::label_over_annotation(Some("team-a"), Some("team-b"), Some("team-a"))]
+    #[case::not_set(None, None, None)]
+    fn resource_owner(
+        #[case] label: Option<&str>,
+        #[case] annotation: Option<&str>,
+        #[case] expected: Option<&str>,
+    ) {
+        let to_map = |value: Option<&str>| {
+            value.map(|value| BTreeMap::from([("owner".to_owned(), value.to_owned())]))
+        };
+        let metadata = ObjectMeta {
+            labels: to_map(label),
+            annotations: to_map(annotation),
+            ..Default::default()
+        };
+        assert_eq!(owner(&metadata, "owner"), expected);
+    }
+
+    #[rstest]
+    #[case::known_owner("test_data/namespace_with_owner.json", 1, true)]
+    #[case::unknown_owner("test_data/deployment_with_unknown_owner.json", 1, false)]
+    #[case::no_owner("test_data/deployment_without_owner.json", 0, false)]
+    #[serial]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] lookups: usize,
+        #[case] expected_validation_result: bool,
+    ) {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<ConfigMap>()
+            .times(lookups)
+            .returning(|req| {
+                if req.namespace.as_deref() != Some("kubewarden") || req.name != "owners" {
+                    return Err(anyhow::anyhow!("it's not searching the expected ConfigMap"));
+                }
+                Ok(directory())
+            });
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::unknown_owner(
+        "test_data/deployment_with_unknown_owner.json",
+        "Deployment 'checkout': unknown owner 'team-shipping'"
+    )]
+    #[case::no_owner(
+        "test_data/deployment_without_owner.json",
+        "Deployment 'checkout': the 'owner' label or annotation must be set"
+    )]
+    #[serial]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<ConfigMap>()
+            .returning(|_| Ok(directory()));
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    #[serial]
+    fn missing_directory() {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<ConfigMap>()
+            .times(1)
+            .returning(|_| Err(anyhow::anyhow!("not found")));
+
+        let test_case = Testcase {
+            name: "missing directory".to_owned(),
+            fixture_file: "test_data/namespace_with_owner.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Namespace 'payments-prod': cannot read the owner directory 'kubewarden/owners': not found"
+            )
+        );
+    }
+}
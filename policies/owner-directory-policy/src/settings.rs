@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+/// ConfigMap listing the known owners
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Directory {
+    /// Namespace of the ConfigMap
+    pub namespace: String,
+    /// Name of the ConfigMap
+    pub name: String,
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Key of the label, or of the annotation, holding the owner of the
+    /// resources
+    pub owner_key: String,
+    /// ConfigMap whose data keys are the known owners
+    pub directory: Directory,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            owner_key: "owner".to_owned(),
+            directory: Directory::default(),
+        }
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.owner_key.is_empty() {
+            return Err("ownerKey cannot be empty".to_owned());
+        }
+        if self.directory.namespace.is_empty() || self.directory.name.is_empty() {
+            return Err("the namespace and the name of the directory must be provided".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::directory(
+        r#"{"directory": {"namespace": "kubewarden", "name": "owners"}}"#,
+        true
+    )]
+    #[case::owner_key(
+        r#"{"ownerKey": "example.com/owner", "directory": {"namespace": "kubewarden", "name": "owners"}}"#,
+        true
+    )]
+    #[case::no_directory(r#"{}"#, false)]
+    #[case::no_directory_name(r#"{"directory": {"namespace": "kubewarden"}}"#, false)]
+    #[case::empty_owner_key(
+        r#"{"ownerKey": "", "directory": {"namespace": "kubewarden", "name": "owners"}}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn unknown_directory_field() {
+        let settings = r#"{"directory": {"namespace": "kubewarden", "configMap": "owners"}}"#;
+        assert!(serde_json::from_str::<Settings>(settings).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+#!/usr/bin/env bats
+
+@test "Accept a local PersistentVolume pinned to an allowed node" {
+	run kwctl run \
+		--request-path test_data/pv_local_allowed_node.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a hostPath PersistentVolume not pinned to a node" {
+	run kwctl run \
+		--request-path test_data/pv_host_path_not_pinned.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*must be pinned to the allowed nodes.*') -ne 0 ]
+}
+
+@test "Accept an NFS PersistentVolume of an allowed server" {
+	run kwctl run \
+		--request-path test_data/pv_nfs_allowed_server.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a production NFS PersistentVolume with the Delete reclaimPolicy" {
+	run kwctl run \
+		--request-path test_data/pv_nfs_production_delete.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*must be Retain, not Delete.*') -ne 0 ]
+}
+
+@test "Reject an iSCSI PersistentVolume with a portal outside of the allowed CIDRs" {
+	run kwctl run \
+		--request-path test_data/pv_iscsi_other_portal.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*is not an address of the allowed CIDRs.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pv_nfs_allowed_server.json --settings-json '{"allowedServerCidrs": ["10.20.0.0"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,274 @@
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::{PersistentVolume, PersistentVolumeSpec};
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+use cidr::Cidr;
+
+mod settings;
+use settings::Settings;
+
+// Node label and field identifying the node a PersistentVolume is pinned to
+const HOSTNAME_LABEL: &str = "kubernetes.io/hostname";
+const NODE_NAME_FIELD: &str = "metadata.name";
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+
+    let pv = match serde_json::from_value::<PersistentVolume>(request.object) {
+        Ok(pv) => pv,
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    let Some(spec) = pv.spec else {
+        return kubewarden::accept_request();
+    };
+
+    if let Err(error) = validate_pv_spec(&spec, &validation_request.settings) {
+        return kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_pv_spec(spec: &PersistentVolumeSpec, settings: &Settings) -> Result<(), String> {
+    let mut errors = vec![];
+
+    let node_volume = if spec.host_path.is_some() {
+        Some("hostPath")
+    } else if spec.local.is_some() {
+        Some("local")
+    } else {
+        None
+    };
+    if let Some(volume) = node_volume {
+        errors.extend(validate_nodes(volume, spec, settings));
+    }
+
+    let networks = settings.allowed_networks();
+    if !networks.is_empty() {
+        let servers = spec
+            .nfs
+            .iter()
+            .map(|nfs| ("NFS server", nfs.server.as_str()))
+            .chain(spec.iscsi.iter().flat_map(|iscsi| {
+                std::iter::once(iscsi.target_portal.as_str())
+                    .chain(iscsi.portals.iter().flatten().map(String::as_str))
+                    .map(|portal| ("iSCSI portal", portal))
+            }));
+        for (server_kind, server) in servers {
+            if !is_allowed_server(server, &networks) {
+                errors.push(format!(
+                    "the {server_kind} '{server}' is not an address of the allowed CIDRs"
+                ));
+            }
+        }
+    }
+
+    // Kubernetes defaults the reclaimPolicy of the PersistentVolumes created
+    // manually to Retain
+    let storage_class = spec.storage_class_name.as_deref().unwrap_or_default();
+    let reclaim_policy = spec.persistent_volume_reclaim_policy.as_deref();
+    if settings.production_storage_classes.contains(storage_class)
+        && reclaim_policy.is_some_and(|policy| policy != "Retain")
+    {
+        errors.push(format!(
+            "the reclaimPolicy of a PersistentVolume of the production StorageClass '{storage_class}' must be Retain, not {}",
+            reclaim_policy.unwrap_or_default()
+        ));
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+// Returns the reasons why the hostPath or local PersistentVolume cannot be
+// used on the nodes it is pinned to
+fn validate_nodes(volume: &str, spec: &PersistentVolumeSpec, settings: &Settings) -> Vec<String> {
+    if settings.allowed_host_path_nodes.is_empty() {
+        return vec![format!("{volume} PersistentVolumes are not allowed")];
+    }
+    let Some(nodes) = pinned_nodes(spec) else {
+        return vec![format!(
+            "the {volume} PersistentVolume must be pinned to the allowed nodes with nodeAffinity on {HOSTNAME_LABEL}"
+        )];
+    };
+    nodes
+        .into_iter()
+        .filter(|node| !settings.allowed_host_path_nodes.contains(*node))
+        .map(|node| format!("the {volume} PersistentVolume cannot be used on the node '{node}'"))
+        .collect()
+}
+
+// Returns the names of the nodes the PersistentVolume is pinned to by its
+// nodeAffinity, or None when a node selector term does not pin it. The
+// terms are ORed: all of them must select the nodes by name.
+fn pinned_nodes(spec: &PersistentVolumeSpec) -> Option<BTreeSet<&str>> {
+    let terms = &spec
+        .node_affinity
+        .as_ref()?
+        .required
+        .as_ref()?
+        .node_selector_terms;
+    if terms.is_empty() {
+        return None;
+    }
+    let mut nodes = BTreeSet::new();
+    for term in terms {
+        let requirement = term
+            .match_expressions
+            .iter()
+            .flatten()
+            .find(|requirement| requirement.key == HOSTNAME_LABEL && requirement.operator == "In")
+            .or_else(|| {
+                term.match_fields.iter().flatten().find(|requirement| {
+                    requirement.key == NODE_NAME_FIELD && requirement.operator == "In"
+                })
+            })?;
+        nodes.extend(requirement.values.iter().flatten().map(String::as_str));
+    }
+    Some(nodes)
+}
+
+// Returns true when the address of the server, which can be followed by a
+// port, belongs to one of the networks. The host names are not resolved,
+// hence never allowed.
+fn is_allowed_server(server: &str, networks: &[Cidr]) -> bool {
+    let address = if let Some(bracketed) = server.strip_prefix('[') {
+        // IPv6 address with a port, like [fd00::1]:3260
+        bracketed
+            .split_once(']')
+            .map_or(bracketed, |(address, _)| address)
+    } else if server.matches(':').count() == 1 {
+        // IPv4 address or host name with a port
+        server
+            .split_once(':')
+            .map_or(server, |(address, _)| address)
+    } else {
+        server
+    };
+    address.parse::<IpAddr>().is_ok_and(|address| {
+        let address = Cidr::from(address);
+        networks.iter().any(|network| network.contains(&address))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        serde_json::from_str(
+            r#"{
+                "allowedHostPathNodes": ["storage-1", "storage-2"],
+                "allowedServerCidrs": ["10.20.0.0/16", "fd00:20::/64"],
+                "productionStorageClasses": ["nfs-production"]
+            }"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    #[rstest]
+    #[case::local_allowed_node("test_data/pv_local_allowed_node.json", true)]
+    #[case::host_path_other_node("test_data/pv_host_path_other_node.json", false)]
+    #[case::host_path_not_pinned("test_data/pv_host_path_not_pinned.json", false)]
+    #[case::nfs_allowed_server("test_data/pv_nfs_allowed_server.json", true)]
+    #[case::nfs_production_delete("test_data/pv_nfs_production_delete.json", false)]
+    #[case::iscsi_other_portal("test_data/pv_iscsi_other_portal.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::host_path_other_node(
+        "test_data/pv_host_path_other_node.json",
+        "PersistentVolume 'scratch': the hostPath PersistentVolume cannot be used on the node 'worker-1'"
+    )]
+    #[case::host_path_not_pinned(
+        "test_data/pv_host_path_not_pinned.json",
+        "PersistentVolume 'scratch': the hostPath PersistentVolume must be pinned to the allowed nodes with nodeAffinity on kubernetes.io/hostname"
+    )]
+    #[case::nfs_production_delete(
+        "test_data/pv_nfs_production_delete.json",
+        "PersistentVolume 'shared-data': the NFS server 'nfs.example.com' is not an address of the allowed CIDRs; the reclaimPolicy of a PersistentVolume of the production StorageClass 'nfs-production' must be Retain, not Delete"
+    )]
+    #[case::iscsi_other_portal(
+        "test_data/pv_iscsi_other_portal.json",
+        "PersistentVolume 'database': the iSCSI portal '192.168.1.10:3260' is not an address of the allowed CIDRs"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn host_path_rejected_by_default() {
+        let test_case = Testcase {
+            name: "hostPath rejected by default".to_owned(),
+            fixture_file: "test_data/pv_local_allowed_node.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some("PersistentVolume 'fast-disk': local PersistentVolumes are not allowed")
+        );
+    }
+
+    #[rstest]
+    #[case::ipv4("10.20.1.5", true)]
+    #[case::ipv4_with_port("10.20.1.5:3260", true)]
+    #[case::ipv6("fd00:20::5", true)]
+    #[case::ipv6_with_port("[fd00:20::5]:3260", true)]
+    #[case::other_network("10.30.1.5", false)]
+    #[case::host_name("nfs.example.com", false)]
+    #[case::host_name_with_port("nfs.example.com:2049", false)]
+    fn allowed_server(#[case] server: &str, #[case] expected: bool) {
+        let networks = settings().allowed_networks();
+        assert_eq!(is_allowed_server(server, &networks), expected);
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use cidr::Cidr;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Nodes where the hostPath and local PersistentVolumes can be pinned.
+    /// These PersistentVolumes are rejected when empty.
+    pub allowed_host_path_nodes: HashSet<String>,
+    /// CIDR ranges the NFS servers and the iSCSI portals must belong to. The
+    /// servers are not restricted when empty.
+    pub allowed_server_cidrs: Vec<String>,
+    /// StorageClasses whose PersistentVolumes must use the Retain
+    /// reclaimPolicy
+    pub production_storage_classes: HashSet<String>,
+}
+
+impl Settings {
+    /// Returns the CIDR ranges the servers must belong to. The values are
+    /// checked during settings validation, hence invalid entries are skipped
+    /// here.
+    pub fn allowed_networks(&self) -> Vec<Cidr> {
+        self.allowed_server_cidrs
+            .iter()
+            .filter_map(|cidr| cidr.parse::<Cidr>().ok())
+            .collect()
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_host_path_nodes.contains("") {
+            return Err("allowedHostPathNodes cannot contain empty values".to_owned());
+        }
+        let invalid: Vec<String> = self
+            .allowed_server_cidrs
+            .iter()
+            .filter(|cidr| cidr.parse::<Cidr>().is_err())
+            .cloned()
+            .collect();
+        if !invalid.is_empty() {
+            return Err(format!(
+                "allowedServerCidrs contains invalid CIDRs: {}",
+                invalid.join(", ")
+            ));
+        }
+        if self.production_storage_classes.contains("") {
+            return Err("productionStorageClasses cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "allowedHostPathNodes": ["storage-1"],
+            "allowedServerCidrs": ["10.20.0.0/16", "fd00:20::/64"],
+            "productionStorageClasses": ["nfs-production"]
+        }"#,
+        true
+    )]
+    #[case::empty_node(r#"{"allowedHostPathNodes": [""]}"#, false)]
+    #[case::invalid_cidr(r#"{"allowedServerCidrs": ["10.20.0.0"]}"#, false)]
+    #[case::empty_storage_class(r#"{"productionStorageClasses": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
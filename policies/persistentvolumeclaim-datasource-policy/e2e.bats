@@ -0,0 +1,93 @@
+#!/usr/bin/env bats
+
+@test "Accept a PersistentVolumeClaim restored from an existing VolumeSnapshot of a granted namespace" {
+	run kwctl run \
+		--request-path test_data/pvc_granted_namespace_snapshot.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-volume-snapshot-found.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a PersistentVolumeClaim cloned from a missing PersistentVolumeClaim" {
+	run kwctl run \
+		--request-path test_data/pvc_clone.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-persistentvolumeclaim-not-found.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the PersistentVolumeClaim 'team-a/postgres-origin' used as data source cannot be found.*") -ne 0 ]
+}
+
+@test "Reject a PersistentVolumeClaim restored from a VolumeSnapshot of another namespace" {
+	run kwctl run \
+		--request-path test_data/pvc_other_namespace_snapshot.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the VolumeSnapshot 'production/postgres-data' of another namespace cannot be used as data source.*") -ne 0 ]
+}
+
+@test "Reject a PersistentVolumeClaim using a data source kind not allowed" {
+	run kwctl run \
+		--request-path test_data/pvc_populator_source.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the data source kind 'VolumeImportSource.cdi.kubevirt.io' is not allowed.*") -ne 0 ]
+}
+
+@test "Reject a StatefulSet with a volumeClaimTemplate using a source of another namespace" {
+	run kwctl run \
+		--request-path test_data/statefulset_other_namespace_source.json \
+		--settings-json '{"crossNamespaceSources": {"golden-images": ["team-a"]}}' \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*volumeClaimTemplate 'wal': the PersistentVolumeClaim 'production/postgres-wal' of another namespace cannot be used as data source.*") -ne 0 ]
+}
+
+@test "Accept a PersistentVolumeClaim without data source" {
+	run kwctl run \
+		--request-path test_data/pvc_without_data_source.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pvc_clone.json --settings-json '{"crossNamespaceSources": {"golden-images": []}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,438 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use guest::prelude::*;
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use k8s_openapi::{ListableResource, Resource};
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::ListResourcesByNamespaceRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::list_resources_by_namespace;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::list_resources_by_namespace;
+
+mod settings;
+use settings::Settings;
+
+mod volume_snapshot;
+use volume_snapshot::VolumeSnapshot;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let result = match (request.kind.group.as_str(), request.kind.kind.as_str()) {
+        ("", "PersistentVolumeClaim") => {
+            serde_json::from_value::<PersistentVolumeClaim>(request.object)
+                .map(|pvc| validate_claim(&pvc, &request.namespace, settings))
+        }
+        ("apps", "StatefulSet") => {
+            serde_json::from_value::<StatefulSet>(request.object).map(|statefulset| {
+                let errors: Vec<String> = statefulset
+                    .spec
+                    .iter()
+                    .flat_map(|spec| spec.volume_claim_templates.iter().flatten())
+                    .filter_map(|template| {
+                        validate_claim(template, &request.namespace, settings)
+                            .err()
+                            .map(|error| {
+                                format!(
+                                    "volumeClaimTemplate '{}': {error}",
+                                    template.metadata.name.as_deref().unwrap_or_default()
+                                )
+                            })
+                    })
+                    .collect();
+                if errors.is_empty() {
+                    Ok(())
+                } else {
+                    Err(errors.join("; "))
+                }
+            })
+        }
+        _ => return kubewarden::accept_request(),
+    };
+    match result {
+        Ok(Ok(())) => kubewarden::accept_request(),
+        Ok(Err(error)) => kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        ),
+        Err(_) => kubewarden::reject_request(
+            Some("Cannot parse validation request".to_owned()),
+            None,
+            None,
+            None,
+        ),
+    }
+}
+
+// Data source of a claim. It is taken from `dataSourceRef` when set, the
+// only field able to reference the resources of other namespaces.
+struct DataSource<'a> {
+    api_group: &'a str,
+    kind: &'a str,
+    name: &'a str,
+    namespace: Option<&'a str>,
+}
+
+impl DataSource<'_> {
+    // Returns the kind written as `Kind` for the core resources and
+    // `Kind.group` for the others, like in the settings
+    fn qualified_kind(&self) -> String {
+        if self.api_group.is_empty() {
+            self.kind.to_owned()
+        } else {
+            format!("{}.{}", self.kind, self.api_group)
+        }
+    }
+}
+
+fn data_source_of(pvc: &PersistentVolumeClaim) -> Option<DataSource<'_>> {
+    let spec = pvc.spec.as_ref()?;
+    if let Some(data_source_ref) = &spec.data_source_ref {
+        return Some(DataSource {
+            api_group: data_source_ref.api_group.as_deref().unwrap_or_default(),
+            kind: &data_source_ref.kind,
+            name: &data_source_ref.name,
+            namespace: data_source_ref.namespace.as_deref(),
+        });
+    }
+    spec.data_source.as_ref().map(|data_source| DataSource {
+        api_group: data_source.api_group.as_deref().unwrap_or_default(),
+        kind: &data_source.kind,
+        name: &data_source.name,
+        namespace: None,
+    })
+}
+
+fn validate_claim(
+    pvc: &PersistentVolumeClaim,
+    namespace: &str,
+    settings: &Settings,
+) -> Result<(), String> {
+    let Some(data_source) = data_source_of(pvc) else {
+        return Ok(());
+    };
+
+    let kind = data_source.qualified_kind();
+    if !settings.allowed_kinds.contains(&kind) {
+        if settings.allowed_kinds.is_empty() {
+            return Err("data sources are not allowed".to_owned());
+        }
+        return Err(format!(
+            "the data source kind '{kind}' is not allowed, use one of: {}",
+            settings
+                .allowed_kinds
+                .iter()
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+
+    let source_namespace = data_source.namespace.unwrap_or(namespace);
+    if !settings.is_source_namespace_allowed(namespace, source_namespace) {
+        return Err(format!(
+            "the {} '{source_namespace}/{}' of another namespace cannot be used as data source",
+            data_source.kind, data_source.name
+        ));
+    }
+
+    if settings.verify_sources_exist {
+        match data_source_exists(&data_source, source_namespace) {
+            Ok(true) => {}
+            Ok(false) => {
+                return Err(format!(
+                    "the {} '{source_namespace}/{}' used as data source cannot be found",
+                    data_source.kind, data_source.name
+                ));
+            }
+            Err(err) => {
+                return Err(format!(
+                    "cannot read the {} '{source_namespace}/{}' used as data source: {err}",
+                    data_source.kind, data_source.name
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Only the PersistentVolumeClaims and the VolumeSnapshots are looked up, the
+// resources of the volume populators are not checked
+fn data_source_exists(data_source: &DataSource<'_>, namespace: &str) -> Result<bool> {
+    match (data_source.api_group, data_source.kind) {
+        (PersistentVolumeClaim::GROUP, PersistentVolumeClaim::KIND) => {
+            resource_exists::<PersistentVolumeClaim>(data_source.name, namespace)
+        }
+        (VolumeSnapshot::GROUP, VolumeSnapshot::KIND) => {
+            resource_exists::<VolumeSnapshot>(data_source.name, namespace)
+        }
+        _ => Ok(true),
+    }
+}
+
+// The resource is listed by name: an empty list means it does not exist,
+// while an error is a failure of the host
+fn resource_exists<T>(name: &str, namespace: &str) -> Result<bool>
+where
+    T: ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+{
+    let resources = list_resources_by_namespace::<T>(&ListResourcesByNamespaceRequest {
+        api_version: T::API_VERSION.to_owned(),
+        kind: T::KIND.to_owned(),
+        namespace: namespace.to_owned(),
+        label_selector: None,
+        field_selector: Some(format!("metadata.name={name}")),
+        field_masks: Some(BTreeSet::from(["metadata.name".to_owned()])),
+    })?;
+    Ok(!resources.items.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::ListResourcesByNamespaceRequest;
+
+        #[allow(dead_code)]
+        pub fn list_resources_by_namespace<T>(
+            _req: &ListResourcesByNamespaceRequest,
+        ) -> anyhow::Result<k8s_openapi::List<T>>
+        where
+            T: k8s_openapi::ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+        {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    fn settings(verify_sources_exist: bool) -> Settings {
+        Settings {
+            verify_sources_exist,
+            ..serde_json::from_str(r#"{"crossNamespaceSources": {"golden-images": ["team-a"]}}"#)
+                .expect("cannot parse settings")
+        }
+    }
+
+    #[rstest]
+    #[case::same_namespace_snapshot("test_data/pvc_same_namespace_snapshot.json", true)]
+    #[case::clone("test_data/pvc_clone.json", true)]
+    #[case::granted_namespace_snapshot("test_data/pvc_granted_namespace_snapshot.json", true)]
+    #[case::other_namespace_snapshot("test_data/pvc_other_namespace_snapshot.json", false)]
+    #[case::populator_source("test_data/pvc_populator_source.json", false)]
+    #[case::without_data_source("test_data/pvc_without_data_source.json", true)]
+    #[case::statefulset_other_namespace_source(
+        "test_data/statefulset_other_namespace_source.json",
+        false
+    )]
+    #[serial]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<PersistentVolumeClaim>()
+            .times(0);
+        ctx_list_resources.expect::<VolumeSnapshot>().times(0);
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(false),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::other_namespace_snapshot(
+        "test_data/pvc_other_namespace_snapshot.json",
+        "PersistentVolumeClaim 'postgres-data': the VolumeSnapshot 'production/postgres-data' of another namespace cannot be used as data source"
+    )]
+    #[case::populator_source(
+        "test_data/pvc_populator_source.json",
+        "PersistentVolumeClaim 'postgres-data': the data source kind 'VolumeImportSource.cdi.kubevirt.io' is not allowed, use one of: PersistentVolumeClaim, VolumeSnapshot.snapshot.storage.k8s.io"
+    )]
+    #[case::statefulset_other_namespace_source(
+        "test_data/statefulset_other_namespace_source.json",
+        "StatefulSet 'postgres': volumeClaimTemplate 'wal': the PersistentVolumeClaim 'production/postgres-wal' of another namespace cannot be used as data source"
+    )]
+    #[serial]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<PersistentVolumeClaim>()
+            .times(0);
+        ctx_list_resources.expect::<VolumeSnapshot>().times(0);
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(false),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    #[serial]
+    fn data_sources_not_allowed() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<PersistentVolumeClaim>()
+            .times(0);
+
+        let test_case = Testcase {
+            name: "data sources not allowed".to_owned(),
+            fixture_file: "test_data/pvc_clone.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings {
+                allowed_kinds: Default::default(),
+                ..Default::default()
+            },
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some("PersistentVolumeClaim 'postgres-data': data sources are not allowed")
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn existing_volume_snapshot() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<VolumeSnapshot>()
+            .times(1)
+            .returning(|req| {
+                if req.namespace != "golden-images"
+                    || req.field_selector.as_deref() != Some("metadata.name=postgres-base")
+                {
+                    return Err(anyhow::anyhow!(
+                        "it's not searching the expected VolumeSnapshot"
+                    ));
+                }
+                Ok(k8s_openapi::List {
+                    items: vec![VolumeSnapshot {
+                        metadata: ObjectMeta {
+                            name: Some("postgres-base".to_owned()),
+                            namespace: Some(req.namespace.clone()),
+                            ..Default::default()
+                        },
+                    }],
+                    ..Default::default()
+                })
+            });
+
+        let test_case = Testcase {
+            name: "existing VolumeSnapshot".to_owned(),
+            fixture_file: "test_data/pvc_granted_namespace_snapshot.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(true),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    #[serial]
+    fn missing_persistent_volume_claim() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<PersistentVolumeClaim>()
+            .times(1)
+            .returning(|_| Ok(k8s_openapi::List::default()));
+
+        let test_case = Testcase {
+            name: "missing PersistentVolumeClaim".to_owned(),
+            fixture_file: "test_data/pvc_clone.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "PersistentVolumeClaim 'postgres-data': the PersistentVolumeClaim 'team-a/postgres-origin' used as data source cannot be found"
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn persistent_volume_claim_lookup_failure() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<PersistentVolumeClaim>()
+            .times(1)
+            .returning(|_| Err(anyhow::anyhow!("persistentvolumeclaims is forbidden")));
+
+        let test_case = Testcase {
+            name: "PersistentVolumeClaim lookup failure".to_owned(),
+            fixture_file: "test_data/pvc_clone.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "PersistentVolumeClaim 'postgres-data': cannot read the PersistentVolumeClaim 'team-a/postgres-origin' used as data source: persistentvolumeclaims is forbidden"
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn populator_source_not_looked_up() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<PersistentVolumeClaim>()
+            .times(0);
+        ctx_list_resources.expect::<VolumeSnapshot>().times(0);
+
+        let mut settings = settings(true);
+        settings
+            .allowed_kinds
+            .insert("VolumeImportSource.cdi.kubevirt.io".to_owned());
+        let test_case = Testcase {
+            name: "populator source".to_owned(),
+            fixture_file: "test_data/pvc_populator_source.json".to_owned(),
+            expected_validation_result: true,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
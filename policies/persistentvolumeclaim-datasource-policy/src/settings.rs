@@ -0,0 +1,110 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Kinds of the data sources the claims can use, written as `Kind` for
+    /// the core resources and `Kind.group` for the others. No data source is
+    /// allowed when empty.
+    pub allowed_kinds: BTreeSet<String>,
+    /// Namespaces whose resources can be used as data source by the claims
+    /// of other namespaces, with the namespaces of these claims
+    pub cross_namespace_sources: BTreeMap<String, BTreeSet<String>>,
+    /// Check that the PersistentVolumeClaims and the VolumeSnapshots used as
+    /// data source exist. This requires the policy to be allowed to access
+    /// them.
+    pub verify_sources_exist: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            allowed_kinds: BTreeSet::from([
+                "PersistentVolumeClaim".to_owned(),
+                "VolumeSnapshot.snapshot.storage.k8s.io".to_owned(),
+            ]),
+            cross_namespace_sources: BTreeMap::new(),
+            verify_sources_exist: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the claims of the namespace can use the resources
+    /// of the source namespace as data source
+    pub fn is_source_namespace_allowed(&self, namespace: &str, source_namespace: &str) -> bool {
+        namespace == source_namespace
+            || self
+                .cross_namespace_sources
+                .get(source_namespace)
+                .is_some_and(|namespaces| namespaces.contains(namespace))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_kinds.contains("") {
+            return Err("allowedKinds cannot contain empty values".to_owned());
+        }
+        for (source_namespace, namespaces) in &self.cross_namespace_sources {
+            if source_namespace.is_empty() {
+                return Err("crossNamespaceSources cannot contain empty namespaces".to_owned());
+            }
+            if namespaces.is_empty() || namespaces.contains("") {
+                return Err(format!(
+                    "crossNamespaceSources: the namespaces allowed to use '{source_namespace}' cannot be empty"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "allowedKinds": ["VolumeSnapshot.snapshot.storage.k8s.io"],
+            "crossNamespaceSources": {"golden-images": ["team-a", "team-b"]},
+            "verifySourcesExist": true
+        }"#,
+        true
+    )]
+    #[case::empty_kind(r#"{"allowedKinds": [""]}"#, false)]
+    #[case::empty_source_namespace(r#"{"crossNamespaceSources": {"": ["team-a"]}}"#, false)]
+    #[case::no_namespace(r#"{"crossNamespaceSources": {"golden-images": []}}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::same_namespace("team-a", "team-a", true)]
+    #[case::granted_namespace("team-a", "golden-images", true)]
+    #[case::other_namespace("team-c", "golden-images", false)]
+    #[case::not_granted_namespace("team-a", "production", false)]
+    fn source_namespace_allowed(
+        #[case] namespace: &str,
+        #[case] source_namespace: &str,
+        #[case] expected: bool,
+    ) {
+        let settings: Settings =
+            serde_json::from_str(r#"{"crossNamespaceSources": {"golden-images": ["team-a"]}}"#)
+                .expect("cannot parse settings");
+        assert_eq!(
+            settings.is_source_namespace_allowed(namespace, source_namespace),
+            expected
+        );
+    }
+}
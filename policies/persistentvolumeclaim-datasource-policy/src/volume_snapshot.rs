@@ -0,0 +1,32 @@
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+/// Minimal representation of a VolumeSnapshot of the CSI external
+/// snapshotter. Only its existence is checked by the policy.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct VolumeSnapshot {
+    #[serde(default)]
+    pub metadata: ObjectMeta,
+}
+
+impl k8s_openapi::DeepMerge for VolumeSnapshot {
+    fn merge_from(&mut self, other: Self)
+    where
+        Self: Sized,
+    {
+        *self = other;
+    }
+}
+
+impl k8s_openapi::Resource for VolumeSnapshot {
+    const API_VERSION: &'static str = "snapshot.storage.k8s.io/v1";
+    const GROUP: &'static str = "snapshot.storage.k8s.io";
+    const KIND: &'static str = "VolumeSnapshot";
+    const VERSION: &'static str = "v1";
+    const URL_PATH_SEGMENT: &'static str = "volumesnapshots";
+    type Scope = k8s_openapi::NamespaceResourceScope;
+}
+
+impl k8s_openapi::ListableResource for VolumeSnapshot {
+    const LIST_KIND: &'static str = "VolumeSnapshotList";
+}
@@ -0,0 +1,90 @@
+#!/usr/bin/env bats
+
+@test "Accept a PersistentVolumeClaim within the bounds" {
+	run kwctl run \
+		--request-path test_data/pvc_within_bounds.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a PersistentVolumeClaim above the maximum of its storage class" {
+	run kwctl run \
+		--request-path test_data/pvc_above_maximum.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*the requested storage 2Ti is above the maximum of 1Ti.*') -ne 0 ]
+}
+
+@test "Reject a PersistentVolumeClaim below the minimum" {
+	run kwctl run \
+		--request-path test_data/pvc_below_minimum.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*the requested storage 500Mi is below the minimum of 1Gi.*') -ne 0 ]
+}
+
+@test "Accept a PersistentVolumeClaim within the bounds of its namespace" {
+	run kwctl run \
+		--request-path test_data/pvc_namespace_override.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject an expansion above the maximum" {
+	run kwctl run \
+		--request-path test_data/pvc_expansion_above_maximum.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Accept an update not expanding the storage" {
+	run kwctl run \
+		--request-path test_data/pvc_update_without_expansion.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pvc_within_bounds.json --settings-json '{"minStorage": "1Ti", "maxStorage": "1Gi"}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,159 @@
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use quantity::parse_quantity;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::{Bounds, Settings};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+
+    let (pvc, old_pvc) = match (
+        serde_json::from_value::<PersistentVolumeClaim>(request.object),
+        serde_json::from_value::<Option<PersistentVolumeClaim>>(request.old_object),
+    ) {
+        (Ok(pvc), Ok(old_pvc)) => (pvc, old_pvc),
+        _ => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    let Some(storage) = requested_storage(&pvc) else {
+        return kubewarden::accept_request();
+    };
+    // Only the expansions are validated on update: the PersistentVolumeClaims
+    // created before a change of the settings can still be updated
+    if request.operation == "UPDATE"
+        && old_pvc.as_ref().and_then(requested_storage).as_ref() == Some(&storage)
+    {
+        return kubewarden::accept_request();
+    }
+
+    let storage_class = pvc
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.storage_class_name.as_deref())
+        .unwrap_or_default();
+    let bounds = validation_request
+        .settings
+        .bounds_of(&request.namespace, storage_class);
+    if let Err(error) = validate_storage(&storage, &bounds) {
+        return kubewarden::reject_request(
+            Some(format!("PersistentVolumeClaim '{}': {error}", request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+// Returns the storage requested by the PersistentVolumeClaim
+fn requested_storage(pvc: &PersistentVolumeClaim) -> Option<String> {
+    pvc.spec
+        .as_ref()
+        .and_then(|spec| spec.resources.as_ref())
+        .and_then(|resources| resources.requests.as_ref())
+        .and_then(|requests| requests.get("storage"))
+        .map(|quantity| quantity.0.clone())
+}
+
+fn validate_storage(storage: &str, bounds: &Bounds) -> Result<(), String> {
+    let Some(requested) = parse_quantity(storage) else {
+        return Err(format!("cannot parse the requested storage '{storage}'"));
+    };
+    // The bounds have been checked when validating the settings
+    if let Some(min_storage) = bounds.min_storage.as_deref()
+        && parse_quantity(min_storage).is_some_and(|min| requested < min)
+    {
+        return Err(format!(
+            "the requested storage {storage} is below the minimum of {min_storage}"
+        ));
+    }
+    if let Some(max_storage) = bounds.max_storage.as_deref()
+        && parse_quantity(max_storage).is_some_and(|max| requested > max)
+    {
+        return Err(format!(
+            "the requested storage {storage} is above the maximum of {max_storage}"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        serde_json::from_str(
+            r#"{
+                "minStorage": "1Gi",
+                "storageClasses": {"fast-ssd": {"maxStorage": "1Ti"}},
+                "namespaces": {"data": {"storageClasses": {"fast-ssd": {"maxStorage": "4Ti"}}}}
+            }"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    #[rstest]
+    #[case::within_bounds("test_data/pvc_within_bounds.json", true)]
+    #[case::above_maximum("test_data/pvc_above_maximum.json", false)]
+    #[case::below_minimum("test_data/pvc_below_minimum.json", false)]
+    #[case::namespace_override("test_data/pvc_namespace_override.json", true)]
+    #[case::expansion_above_maximum("test_data/pvc_expansion_above_maximum.json", false)]
+    #[case::update_without_expansion("test_data/pvc_update_without_expansion.json", true)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::above_maximum(
+        "test_data/pvc_above_maximum.json",
+        "PersistentVolumeClaim 'postgres-data': the requested storage 2Ti is above the maximum of 1Ti"
+    )]
+    #[case::below_minimum(
+        "test_data/pvc_below_minimum.json",
+        "PersistentVolumeClaim 'scratch': the requested storage 500Mi is below the minimum of 1Gi"
+    )]
+    #[case::expansion_above_maximum(
+        "test_data/pvc_expansion_above_maximum.json",
+        "PersistentVolumeClaim 'postgres-data': the requested storage 2Ti is above the maximum of 1Ti"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use quantity::parse_quantity;
+use serde::{Deserialize, Serialize};
+
+/// Bounds of the storage requested by a PersistentVolumeClaim, as quantities
+/// like `1Gi` or `500M`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct Bounds {
+    pub min_storage: Option<String>,
+    pub max_storage: Option<String>,
+}
+
+impl Bounds {
+    fn validate(&self) -> Result<(), String> {
+        let mut quantities = vec![];
+        for (field, value) in [
+            ("minStorage", &self.min_storage),
+            ("maxStorage", &self.max_storage),
+        ] {
+            if let Some(value) = value {
+                let quantity = parse_quantity(value)
+                    .filter(|quantity| *quantity >= 0.0)
+                    .ok_or_else(|| format!("{field}: invalid quantity '{value}'"))?;
+                quantities.push(quantity);
+            }
+        }
+        if let [min, max] = quantities[..]
+            && min > max
+        {
+            return Err("minStorage cannot be above maxStorage".to_owned());
+        }
+        Ok(())
+    }
+}
+
+/// Bounds applying to all the storage classes, and the ones replacing them
+/// for some storage classes
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct StorageBounds {
+    pub min_storage: Option<String>,
+    pub max_storage: Option<String>,
+    pub storage_classes: BTreeMap<String, Bounds>,
+}
+
+impl StorageBounds {
+    /// Returns the bounds of the storage class. Each bound of the storage
+    /// class replaces the one of all the storage classes.
+    pub fn bounds_of(&self, storage_class: &str) -> Bounds {
+        let class_bounds = self.storage_classes.get(storage_class);
+        Bounds {
+            min_storage: class_bounds
+                .and_then(|bounds| bounds.min_storage.clone())
+                .or_else(|| self.min_storage.clone()),
+            max_storage: class_bounds
+                .and_then(|bounds| bounds.max_storage.clone())
+                .or_else(|| self.max_storage.clone()),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        Bounds {
+            min_storage: self.min_storage.clone(),
+            max_storage: self.max_storage.clone(),
+        }
+        .validate()?;
+        for (storage_class, bounds) in &self.storage_classes {
+            if storage_class.is_empty() {
+                return Err("storageClasses cannot contain empty names".to_owned());
+            }
+            bounds
+                .validate()
+                .map_err(|e| format!("storage class '{storage_class}': {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    #[serde(flatten)]
+    pub bounds: StorageBounds,
+    /// Bounds replacing the global ones in some namespaces
+    pub namespaces: BTreeMap<String, StorageBounds>,
+}
+
+impl Settings {
+    /// Returns the bounds of the storage class in the namespace. Each bound
+    /// of the namespace replaces the global one.
+    pub fn bounds_of(&self, namespace: &str, storage_class: &str) -> Bounds {
+        let bounds = self.bounds.bounds_of(storage_class);
+        let Some(namespace_bounds) = self.namespaces.get(namespace) else {
+            return bounds;
+        };
+        let namespace_bounds = namespace_bounds.bounds_of(storage_class);
+        Bounds {
+            min_storage: namespace_bounds.min_storage.or(bounds.min_storage),
+            max_storage: namespace_bounds.max_storage.or(bounds.max_storage),
+        }
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        self.bounds.validate()?;
+        for (namespace, bounds) in &self.namespaces {
+            if namespace.is_empty() {
+                return Err("namespaces cannot contain empty names".to_owned());
+            }
+            bounds
+                .validate()
+                .map_err(|e| format!("namespace '{namespace}': {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        serde_json::from_str(
+            r#"{
+                "minStorage": "1Gi",
+                "storageClasses": {"fast-ssd": {"maxStorage": "1Ti"}},
+                "namespaces": {
+                    "data": {"storageClasses": {"fast-ssd": {"maxStorage": "4Ti"}}},
+                    "sandbox": {"maxStorage": "10Gi"}
+                }
+            }"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "minStorage": "1Gi",
+            "maxStorage": "10Ti",
+            "storageClasses": {"fast-ssd": {"minStorage": "10Gi", "maxStorage": "1Ti"}},
+            "namespaces": {"data": {"storageClasses": {"fast-ssd": {"maxStorage": "4Ti"}}}}
+        }"#,
+        true
+    )]
+    #[case::invalid_quantity(r#"{"minStorage": "1GB"}"#, false)]
+    #[case::signed_quantity(r#"{"minStorage": "+1Gi"}"#, true)]
+    #[case::negative_quantity(r#"{"minStorage": "-1Gi"}"#, false)]
+    #[case::min_above_max(r#"{"minStorage": "1Ti", "maxStorage": "1Gi"}"#, false)]
+    #[case::invalid_class_quantity(
+        r#"{"storageClasses": {"fast-ssd": {"maxStorage": "lots"}}}"#,
+        false
+    )]
+    #[case::empty_class(r#"{"storageClasses": {"": {"maxStorage": "1Ti"}}}"#, false)]
+    #[case::invalid_namespace_bounds(
+        r#"{"namespaces": {"data": {"minStorage": "2Ti", "maxStorage": "1Ti"}}}"#,
+        false
+    )]
+    #[case::empty_namespace(r#"{"namespaces": {"": {"maxStorage": "1Ti"}}}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn unknown_bound() {
+        let settings = r#"{"storageClasses": {"fast-ssd": {"max": "1Ti"}}}"#;
+        assert!(serde_json::from_str::<Settings>(settings).is_err());
+    }
+
+    #[rstest]
+    #[case::storage_class("default", "fast-ssd", Some("1Gi"), Some("1Ti"))]
+    #[case::other_storage_class("default", "standard", Some("1Gi"), None)]
+    #[case::namespace_storage_class("data", "fast-ssd", Some("1Gi"), Some("4Ti"))]
+    #[case::namespace_bounds("sandbox", "fast-ssd", Some("1Gi"), Some("10Gi"))]
+    #[case::without_storage_class("default", "", Some("1Gi"), None)]
+    fn bounds_of(
+        #[case] namespace: &str,
+        #[case] storage_class: &str,
+        #[case] min_storage: Option<&str>,
+        #[case] max_storage: Option<&str>,
+    ) {
+        assert_eq!(
+            settings().bounds_of(namespace, storage_class),
+            Bounds {
+                min_storage: min_storage.map(str::to_owned),
+                max_storage: max_storage.map(str::to_owned),
+            }
+        );
+    }
+}
@@ -0,0 +1,84 @@
+#!/usr/bin/env bats
+
+@test "Accept the expansion of a PersistentVolumeClaim" {
+	run kwctl run \
+		--request-path test_data/pvc_expansion.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject the shrink of a PersistentVolumeClaim" {
+	run kwctl run \
+		--request-path test_data/pvc_shrink.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*the requested storage cannot shrink from 100Gi to 50Gi.*') -ne 0 ]
+}
+
+@test "Reject the change of the storage class" {
+	run kwctl run \
+		--request-path test_data/pvc_storage_class_change.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the storage class cannot change from 'standard' to 'fast-ssd'.*") -ne 0 ]
+}
+
+@test "Reject the rebinding to another volume" {
+	run kwctl run \
+		--request-path test_data/pvc_rebinding.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Accept the binding of a PersistentVolumeClaim" {
+	run kwctl run \
+		--request-path test_data/pvc_binding.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept the shrink made by an administrator" {
+	run kwctl run \
+		--request-path test_data/pvc_admin_shrink.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pvc_expansion.json --settings-json '{"allowedGroups": [""]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,194 @@
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::{PersistentVolumeClaim, PersistentVolumeClaimSpec};
+use kubewarden_policy_sdk::wapc_guest as guest;
+use quantity::parse_quantity;
+use user_allowlist::UserAllowlist;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+
+    if request.operation != "UPDATE" {
+        return kubewarden::accept_request();
+    }
+    let username = request.user_info.username.unwrap_or_default();
+    let groups = request.user_info.groups.unwrap_or_default();
+    if validation_request
+        .settings
+        .is_user_allowed(&username, &groups)
+    {
+        return kubewarden::accept_request();
+    }
+
+    let (pvc, old_pvc) = match (
+        serde_json::from_value::<PersistentVolumeClaim>(request.object),
+        serde_json::from_value::<PersistentVolumeClaim>(request.old_object),
+    ) {
+        (Ok(pvc), Ok(old_pvc)) => (pvc, old_pvc),
+        _ => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    if let Err(error) = validate_update(&old_pvc, &pvc) {
+        return kubewarden::reject_request(
+            Some(format!(
+                "PersistentVolumeClaim '{}': user '{username}' cannot update it, {error}",
+                request.name
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+// Returns the storage requested by the PersistentVolumeClaim
+fn requested_storage(pvc: &PersistentVolumeClaim) -> Option<&str> {
+    pvc.spec
+        .as_ref()
+        .and_then(|spec| spec.resources.as_ref())
+        .and_then(|resources| resources.requests.as_ref())
+        .and_then(|requests| requests.get("storage"))
+        .map(|quantity| quantity.0.as_str())
+}
+
+// Returns the non-empty value of a field of the spec
+fn spec_field<'a>(
+    pvc: &'a PersistentVolumeClaim,
+    field: impl Fn(&'a PersistentVolumeClaimSpec) -> Option<&'a String>,
+) -> Option<&'a str> {
+    pvc.spec
+        .as_ref()
+        .and_then(field)
+        .map(String::as_str)
+        .filter(|value| !value.is_empty())
+}
+
+fn validate_update(
+    old_pvc: &PersistentVolumeClaim,
+    pvc: &PersistentVolumeClaim,
+) -> Result<(), String> {
+    let mut errors = vec![];
+
+    // Kubernetes can expand the volumes, but never shrink them: the
+    // PersistentVolumeClaim would be stuck in a resize that cannot complete
+    if let (Some(old_storage), Some(storage)) = (requested_storage(old_pvc), requested_storage(pvc))
+        && let (Some(old_quantity), Some(quantity)) =
+            (parse_quantity(old_storage), parse_quantity(storage))
+        && quantity < old_quantity
+    {
+        errors.push(format!(
+            "the requested storage cannot shrink from {old_storage} to {storage}"
+        ));
+    }
+
+    // The storage class can be set on the PersistentVolumeClaims without it,
+    // which is what Kubernetes does when a default storage class is added
+    if let Some(old_storage_class) = spec_field(old_pvc, |spec| spec.storage_class_name.as_ref()) {
+        let storage_class = spec_field(pvc, |spec| spec.storage_class_name.as_ref());
+        if storage_class != Some(old_storage_class) {
+            errors.push(format!(
+                "the storage class cannot change from '{old_storage_class}' to '{}'",
+                storage_class.unwrap_or_default()
+            ));
+        }
+    }
+
+    // The volume is set when the PersistentVolumeClaim is bound, pointing it
+    // to another volume leaves the data of the bound one behind
+    if let Some(old_volume) = spec_field(old_pvc, |spec| spec.volume_name.as_ref()) {
+        let volume = spec_field(pvc, |spec| spec.volume_name.as_ref());
+        if volume != Some(old_volume) {
+            errors.push(format!(
+                "the volume cannot change from '{old_volume}' to '{}'",
+                volume.unwrap_or_default()
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::expansion("test_data/pvc_expansion.json", true)]
+    #[case::shrink("test_data/pvc_shrink.json", false)]
+    #[case::storage_class_change("test_data/pvc_storage_class_change.json", false)]
+    #[case::rebinding("test_data/pvc_rebinding.json", false)]
+    #[case::binding("test_data/pvc_binding.json", true)]
+    #[case::admin_shrink("test_data/pvc_admin_shrink.json", true)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::shrink(
+        "test_data/pvc_shrink.json",
+        "PersistentVolumeClaim 'postgres-data': user 'jane' cannot update it, the requested storage cannot shrink from 100Gi to 50Gi"
+    )]
+    #[case::storage_class_change(
+        "test_data/pvc_storage_class_change.json",
+        "PersistentVolumeClaim 'postgres-data': user 'jane' cannot update it, the storage class cannot change from 'standard' to 'fast-ssd'"
+    )]
+    #[case::rebinding(
+        "test_data/pvc_rebinding.json",
+        "PersistentVolumeClaim 'postgres-data': user 'jane' cannot update it, the volume cannot change from 'pvc-3f2a' to 'pv-restored'"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn equivalent_quantities() {
+        let pvc = |storage: &str| -> PersistentVolumeClaim {
+            serde_json::from_value(serde_json::json!({
+                "spec": {"resources": {"requests": {"storage": storage}}}
+            }))
+            .expect("cannot parse pvc")
+        };
+        assert!(validate_update(&pvc("1Ti"), &pvc("1024Gi")).is_ok());
+    }
+}
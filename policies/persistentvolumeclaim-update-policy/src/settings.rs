@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use user_allowlist::UserAllowlist;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Users, usually the cluster and storage administrators, allowed to
+    /// make any update to the PersistentVolumeClaims
+    pub allowed_users: HashSet<String>,
+    /// Groups of users allowed to make any update to the
+    /// PersistentVolumeClaims
+    pub allowed_groups: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            allowed_users: HashSet::new(),
+            allowed_groups: [
+                "system:masters".to_owned(),
+                "system:serviceaccounts:kube-system".to_owned(),
+            ]
+            .into(),
+        }
+    }
+}
+
+impl UserAllowlist for Settings {
+    fn allowed_users(&self) -> &HashSet<String> {
+        &self.allowed_users
+    }
+
+    fn allowed_groups(&self) -> &HashSet<String> {
+        &self.allowed_groups
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_users.contains("") {
+            return Err("allowedUsers cannot contain empty values".to_owned());
+        }
+        if self.allowed_groups.contains("") {
+            return Err("allowedGroups cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::developer("jane", vec!["system:authenticated"], false)]
+    #[case::admin("kubernetes-admin", vec!["system:masters", "system:authenticated"], true)]
+    #[case::kube_system_service_account(
+        "system:serviceaccount:kube-system:persistent-volume-binder",
+        vec!["system:serviceaccounts", "system:serviceaccounts:kube-system", "system:authenticated"],
+        true
+    )]
+    fn allowed_users(#[case] username: &str, #[case] groups: Vec<&str>, #[case] expected: bool) {
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(
+            Settings::default().is_user_allowed(username, &groups),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::allowed_users(r#"{"allowedUsers": ["storage-admin"], "allowedGroups": []}"#, true)]
+    #[case::empty_user(r#"{"allowedUsers": [""]}"#, false)]
+    #[case::empty_group(r#"{"allowedGroups": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
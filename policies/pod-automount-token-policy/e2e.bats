@@ -0,0 +1,77 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod disabling the automount of the token" {
+	run kwctl run \
+		--request-path test_data/pod_automount_disabled.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patch".*') -eq 0 ]
+}
+
+@test "Reject a Pod not setting automountServiceAccountToken" {
+	run kwctl run \
+		--request-path test_data/pod_automount_unset.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the ServiceAccount 'default' cannot automount its token.*") -ne 0 ]
+}
+
+@test "Mutate a Pod not setting automountServiceAccountToken" {
+	run kwctl run \
+		--request-path test_data/pod_automount_unset.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+}
+
+@test "Reject a Deployment enabling the automount of the token" {
+	run kwctl run \
+		--request-path test_data/deployment_automount_enabled.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Accept a Pod using an allowed ServiceAccount" {
+	run kwctl run \
+		--request-path test_data/pod_allowed_service_account.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patch".*') -eq 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_automount_disabled.json --settings-json '{"allowedNamespaces": [""]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,307 @@
+use std::collections::HashSet;
+
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+/// Prefix of the name of the projected volume added by the ServiceAccount
+/// admission plugin to mount the token
+const TOKEN_VOLUME_PREFIX: &str = "kube-api-access-";
+
+/// Fields of the Pod spec used by the policy. The ServiceAccount is only read:
+/// its fields are not serialized, so the mutation leaves them untouched.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    automount_service_account_token: Option<bool>,
+    #[serde(default, skip_serializing)]
+    service_account_name: Option<String>,
+    #[serde(default, skip_serializing)]
+    service_account: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    volumes: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    containers: Option<Vec<ContainerFields>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    init_containers: Option<Vec<ContainerFields>>,
+}
+
+/// Container with its volumeMounts. The other fields are kept in `other`, so
+/// that the mutation does not drop the fields unknown to k8s-openapi.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct ContainerFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    volume_mounts: Option<Vec<Value>>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let settings = &validation_request.settings;
+    let request = &validation_request.request;
+
+    let mut pod_spec =
+        match partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object) {
+            Ok(Some(pod_spec)) => pod_spec,
+            // The resources without Pod spec are not validated
+            Ok(None) => return kubewarden::accept_request(),
+            Err(_) => {
+                return kubewarden::reject_request(
+                    Some("Cannot parse validation request".to_owned()),
+                    None,
+                    None,
+                    None,
+                );
+            }
+        };
+    let service_account = service_account_name(&pod_spec).to_owned();
+    if settings.is_allowed(&request.namespace, &service_account) {
+        return kubewarden::accept_request();
+    }
+
+    // When the Pod does not set `automountServiceAccountToken`, Kubernetes
+    // uses the value of the ServiceAccount, which mounts the token by default
+    match pod_spec.automount_service_account_token {
+        Some(false) => kubewarden::accept_request(),
+        None if settings.mutate => {
+            pod_spec.automount_service_account_token = Some(false);
+            remove_token_volume(&mut pod_spec);
+            kubewarden::mutate_request(partial_pod_spec::patch(
+                &request.kind.kind,
+                &request.object,
+                &pod_spec,
+            )?)
+        }
+        _ => kubewarden::reject_request(
+            Some(format!(
+                "{} '{}': the ServiceAccount '{service_account}' cannot automount its token, set automountServiceAccountToken to false",
+                request.kind.kind, request.name
+            )),
+            None,
+            None,
+            None,
+        ),
+    }
+}
+
+// Removes the projected volume of the ServiceAccount token, and its mounts.
+// The ServiceAccount admission plugin adds them to the Pods before the
+// admission webhooks run: disabling the automount does not remove them.
+fn remove_token_volume(pod_spec: &mut PodSpecFields) {
+    let Some(volumes) = pod_spec.volumes.as_mut() else {
+        return;
+    };
+    let token_volumes: HashSet<String> = volumes
+        .iter()
+        .filter(|volume| is_token_volume(volume))
+        .filter_map(|volume| volume["name"].as_str().map(str::to_owned))
+        .collect();
+    if token_volumes.is_empty() {
+        return;
+    }
+    volumes.retain(|volume| !is_token_volume(volume));
+
+    for container in pod_spec
+        .containers
+        .iter_mut()
+        .chain(pod_spec.init_containers.iter_mut())
+        .flatten()
+    {
+        if let Some(volume_mounts) = container.volume_mounts.as_mut() {
+            volume_mounts.retain(|volume_mount| {
+                !volume_mount["name"]
+                    .as_str()
+                    .is_some_and(|name| token_volumes.contains(name))
+            });
+        }
+    }
+}
+
+// Returns true when the volume is the projected volume of the ServiceAccount
+// token added by the ServiceAccount admission plugin
+fn is_token_volume(volume: &Value) -> bool {
+    volume["name"]
+        .as_str()
+        .is_some_and(|name| name.starts_with(TOKEN_VOLUME_PREFIX))
+        && volume["projected"]["sources"]
+            .as_array()
+            .is_some_and(|sources| {
+                sources
+                    .iter()
+                    .any(|source| source.get("serviceAccountToken").is_some())
+            })
+}
+
+// Returns the name of the ServiceAccount used by the Pod. The deprecated
+// `serviceAccount` field is still honored by Kubernetes.
+fn service_account_name(pod_spec: &PodSpecFields) -> &str {
+    pod_spec
+        .service_account_name
+        .as_deref()
+        .or(pod_spec.service_account.as_deref())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("default")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use serde_json::json;
+    use settings::ServiceAccount;
+
+    fn settings(mutate: bool) -> Settings {
+        Settings {
+            allowed_service_accounts: [ServiceAccount {
+                namespace: "argocd".to_owned(),
+                name: "argocd-application-controller".to_owned(),
+            }]
+            .into(),
+            mutate,
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::automount_disabled("test_data/pod_automount_disabled.json", false, true)]
+    #[case::automount_unset("test_data/pod_automount_unset.json", false, false)]
+    #[case::automount_enabled("test_data/deployment_automount_enabled.json", true, false)]
+    #[case::allowed_service_account("test_data/pod_allowed_service_account.json", true, true)]
+    #[case::without_pod_spec("test_data/configmap.json", true, true)]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] mutate: bool,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(mutate),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert!(response.mutated_object.is_none());
+    }
+
+    #[rstest]
+    #[case::automount_unset(
+        "test_data/pod_automount_unset.json",
+        "Pod 'web': the ServiceAccount 'default' cannot automount its token, set automountServiceAccountToken to false"
+    )]
+    #[case::automount_enabled(
+        "test_data/deployment_automount_enabled.json",
+        "Deployment 'web': the ServiceAccount 'web' cannot automount its token, set automountServiceAccountToken to false"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(false),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn mutate_pod() {
+        let test_case = Testcase {
+            name: "mutate pod".to_owned(),
+            fixture_file: "test_data/pod_automount_unset.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response.mutated_object.expect("the pod should be mutated");
+        assert_eq!(
+            mutated_object["spec"]["automountServiceAccountToken"],
+            json!(false)
+        );
+    }
+
+    #[test]
+    fn mutate_pod_removing_token_volume() {
+        let test_case = Testcase {
+            name: "mutate pod with token volume".to_owned(),
+            fixture_file: "test_data/pod_automount_unset_token_volume.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response.mutated_object.expect("the pod should be mutated");
+        assert_eq!(
+            mutated_object["spec"]["automountServiceAccountToken"],
+            json!(false)
+        );
+        assert_eq!(
+            mutated_object["spec"]["volumes"],
+            json!([{"name": "cache", "emptyDir": {}}])
+        );
+        assert_eq!(
+            mutated_object["spec"]["containers"],
+            json!([{
+                "name": "web",
+                "image": "registry.example.com/web:1.0",
+                "volumeMounts": [{"name": "cache", "mountPath": "/var/cache/web"}]
+            }])
+        );
+    }
+
+    #[rstest]
+    #[case::token_volume(
+        json!({"name": "kube-api-access-7x2kq", "projected": {"sources": [{"serviceAccountToken": {"path": "token"}}]}}),
+        true
+    )]
+    #[case::other_projected_volume(
+        json!({"name": "kube-api-access-7x2kq", "projected": {"sources": [{"configMap": {"name": "kube-root-ca.crt"}}]}}),
+        false
+    )]
+    #[case::other_name(
+        json!({"name": "token", "projected": {"sources": [{"serviceAccountToken": {"path": "token"}}]}}),
+        false
+    )]
+    #[case::empty_dir(json!({"name": "kube-api-access-7x2kq", "emptyDir": {}}), false)]
+    fn token_volume(#[case] volume: Value, #[case] expected: bool) {
+        assert_eq!(is_token_volume(&volume), expected);
+    }
+
+    #[rstest]
+    #[case::service_account_name(Some("web"), Some("legacy"), "web")]
+    #[case::deprecated_field(None, Some("legacy"), "legacy")]
+    #[case::default_service_account(None, None, "default")]
+    #[case::empty_name(Some(""), None, "default")]
+    fn service_account_of_pod(
+        #[case] name: Option<&str>,
+        #[case] deprecated_name: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let pod_spec = PodSpecFields {
+            service_account_name: name.map(str::to_owned),
+            service_account: deprecated_name.map(str::to_owned),
+            ..Default::default()
+        };
+        assert_eq!(service_account_name(&pod_spec), expected);
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+/// ServiceAccount whose Pods can automount its token
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ServiceAccount {
+    pub namespace: String,
+    pub name: String,
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// ServiceAccounts of the workloads consuming the Kubernetes API, whose
+    /// Pods can automount the token
+    pub allowed_service_accounts: HashSet<ServiceAccount>,
+    /// Namespaces whose Pods can automount the token of any ServiceAccount
+    pub allowed_namespaces: HashSet<String>,
+    /// Set `automountServiceAccountToken` to false when it is not defined,
+    /// instead of rejecting the request
+    pub mutate: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            allowed_service_accounts: HashSet::new(),
+            allowed_namespaces: ["kube-system".to_owned()].into(),
+            mutate: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the Pods using the ServiceAccount can automount its
+    /// token
+    pub fn is_allowed(&self, namespace: &str, name: &str) -> bool {
+        self.allowed_namespaces.contains(namespace)
+            || self.allowed_service_accounts.iter().any(|service_account| {
+                service_account.namespace == namespace && service_account.name == name
+            })
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_service_accounts.iter().any(|service_account| {
+            service_account.namespace.is_empty() || service_account.name.is_empty()
+        }) {
+            return Err(
+                "allowedServiceAccounts entries must have both a namespace and a name".to_owned(),
+            );
+        }
+        if self.allowed_namespaces.contains("") {
+            return Err("allowedNamespaces cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::allowed_service_account("argocd", "argocd-application-controller", true)]
+    #[case::other_namespace("team-a", "argocd-application-controller", false)]
+    #[case::allowed_namespace("kube-system", "coredns", true)]
+    #[case::not_allowed("team-a", "default", false)]
+    fn allowed(#[case] namespace: &str, #[case] name: &str, #[case] expected: bool) {
+        let settings = Settings {
+            allowed_service_accounts: [ServiceAccount {
+                namespace: "argocd".to_owned(),
+                name: "argocd-application-controller".to_owned(),
+            }]
+            .into(),
+            ..Default::default()
+        };
+        assert_eq!(settings.is_allowed(namespace, name), expected);
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::allowed_service_account(
+        r#"{"allowedServiceAccounts": [{"namespace": "argocd", "name": "argocd-application-controller"}]}"#,
+        true
+    )]
+    #[case::service_account_without_namespace(
+        r#"{"allowedServiceAccounts": [{"namespace": "", "name": "argocd-application-controller"}]}"#,
+        false
+    )]
+    #[case::empty_namespace(r#"{"allowedNamespaces": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,83 @@
+#!/usr/bin/env bats
+
+@test "Accept exec by a team member into a regular Pod" {
+	run kwctl run \
+		--request-path test_data/exec_team_member.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-regular-pod.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject exec by a team member into a sensitive Pod" {
+	run kwctl run \
+		--request-path test_data/exec_team_member.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-sensitive-pod.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*user 'john' is not allowed to exec into the sensitive Pod 'ledger-0'.*") -ne 0 ]
+}
+
+@test "Reject attach by a team member to a sensitive Pod" {
+	run kwctl run \
+		--request-path test_data/attach_team_member.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-sensitive-pod.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Accept exec by an SRE into a sensitive Pod" {
+	run kwctl run \
+		--request-path test_data/exec_sre.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject exec by a user not allowed in the namespace" {
+	run kwctl run \
+		--request-path test_data/exec_other_user.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*user 'mallory' is not allowed to exec into the Pods of the 'payments' namespace.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/exec_sre.json --settings-json '{"namespaces": {"payments": {"allowedGroups": [""]}}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
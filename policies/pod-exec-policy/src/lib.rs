@@ -0,0 +1,233 @@
+use guest::prelude::*;
+use k8s_openapi::Resource;
+use k8s_openapi::api::core::v1::Pod;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use user_allowlist::UserAllowlist;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::GetResourceRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::get_resource;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::get_resource;
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    // The CONNECT requests on the exec and attach subresources carry the
+    // options of the command, not the Pod
+    let action = match request.kind.kind.as_str() {
+        "PodExecOptions" => "exec into",
+        "PodAttachOptions" => "attach to",
+        _ => return kubewarden::accept_request(),
+    };
+    let username = request.user_info.username.unwrap_or_default();
+    let groups = request.user_info.groups.unwrap_or_default();
+    if settings.is_user_allowed(&username, &groups) {
+        return kubewarden::accept_request();
+    }
+
+    let error = if !settings.is_user_allowed_in(&request.namespace, &username, &groups) {
+        Some(format!(
+            "user '{username}' is not allowed to {action} the Pods of the '{}' namespace",
+            request.namespace
+        ))
+    } else if settings.sensitive_pod_labels.is_empty() {
+        None
+    } else {
+        match is_sensitive_pod(&request.name, &request.namespace, settings) {
+            Ok(false) => None,
+            Ok(true) => Some(format!(
+                "user '{username}' is not allowed to {action} the sensitive Pod '{}'",
+                request.name
+            )),
+            Err(error) => Some(error),
+        }
+    };
+    match error {
+        Some(error) => kubewarden::reject_request(Some(error), None, None, None),
+        None => kubewarden::accept_request(),
+    }
+}
+
+fn is_sensitive_pod(name: &str, namespace: &str, settings: &Settings) -> Result<bool, String> {
+    let kube_request = GetResourceRequest {
+        name: name.to_owned(),
+        api_version: Pod::API_VERSION.to_owned(),
+        kind: Pod::KIND.to_owned(),
+        field_masks: None,
+        namespace: Some(namespace.to_owned()),
+        disable_cache: true,
+    };
+    let pod = get_resource::<Pod>(&kube_request)
+        .map_err(|e| format!("cannot read the Pod '{namespace}/{name}': {e}"))?;
+    Ok(settings.is_sensitive(pod.metadata.labels.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::GetResourceRequest;
+
+        #[allow(dead_code)]
+        pub fn get_resource<T: 'static>(_req: &GetResourceRequest) -> anyhow::Result<T> {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    fn settings() -> Settings {
+        serde_json::from_str(
+            r#"{
+                "allowedGroups": ["system:masters", "sre"],
+                "namespaces": {"payments": {"allowedGroups": ["team-payments"]}},
+                "sensitivePodLabels": {"kubewarden.io/sensitive": "true"}
+            }"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    fn pod(sensitive: bool) -> Pod {
+        Pod {
+            metadata: ObjectMeta {
+                name: Some("ledger-0".to_owned()),
+                namespace: Some("payments".to_owned()),
+                labels: Some(BTreeMap::from([(
+                    "kubewarden.io/sensitive".to_owned(),
+                    sensitive.to_string(),
+                )])),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::team_member("test_data/exec_team_member.json", false, 1, true)]
+    #[case::team_member_sensitive_pod("test_data/exec_team_member.json", true, 1, false)]
+    #[case::attach_team_member_sensitive_pod("test_data/attach_team_member.json", true, 1, false)]
+    #[case::sre_sensitive_pod("test_data/exec_sre.json", true, 0, true)]
+    #[case::other_user("test_data/exec_other_user.json", false, 0, false)]
+    #[serial]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] sensitive: bool,
+        #[case] lookups: usize,
+        #[case] expected_validation_result: bool,
+    ) {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<Pod>()
+            .times(lookups)
+            .returning(move |req| {
+                if req.name != "ledger-0" || req.namespace.as_deref() != Some("payments") {
+                    return Err(anyhow::anyhow!("it's not searching the expected Pod"));
+                }
+                Ok(pod(sensitive))
+            });
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::other_user(
+        "test_data/exec_other_user.json",
+        "user 'mallory' is not allowed to exec into the Pods of the 'payments' namespace"
+    )]
+    #[case::sensitive_pod(
+        "test_data/attach_team_member.json",
+        "user 'john' is not allowed to attach to the sensitive Pod 'ledger-0'"
+    )]
+    #[serial]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<Pod>()
+            .returning(|_| Ok(pod(true)));
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    #[serial]
+    fn missing_pod() {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<Pod>()
+            .times(1)
+            .returning(|_| Err(anyhow::anyhow!("not found")));
+
+        let test_case = Testcase {
+            name: "missing pod".to_owned(),
+            fixture_file: "test_data/exec_team_member.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some("cannot read the Pod 'payments/ledger-0': not found")
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn without_sensitive_pod_labels() {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource.expect::<Pod>().times(0);
+
+        let test_case = Testcase {
+            name: "without sensitive pod labels".to_owned(),
+            fixture_file: "test_data/exec_team_member.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings {
+                sensitive_pod_labels: BTreeMap::new(),
+                ..settings()
+            },
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use user_allowlist::UserAllowlist;
+
+/// Users and groups allowed to exec into the Pods of a namespace
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct NamespaceSettings {
+    pub allowed_users: HashSet<String>,
+    pub allowed_groups: HashSet<String>,
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Users allowed to exec into all the Pods, including the sensitive ones
+    pub allowed_users: HashSet<String>,
+    /// Groups of users allowed to exec into all the Pods, including the
+    /// sensitive ones
+    pub allowed_groups: HashSet<String>,
+    /// Users and groups allowed to exec into the Pods of each namespace
+    pub namespaces: BTreeMap<String, NamespaceSettings>,
+    /// Labels of the sensitive Pods. A Pod having one of them can be
+    /// accessed only by the users allowed in all the namespaces.
+    pub sensitive_pod_labels: BTreeMap<String, String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            allowed_users: HashSet::new(),
+            allowed_groups: ["system:masters".to_owned()].into(),
+            namespaces: BTreeMap::new(),
+            sensitive_pod_labels: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the user, member of the groups, can exec into the
+    /// Pods of the namespace that are not sensitive
+    pub fn is_user_allowed_in(&self, namespace: &str, username: &str, groups: &[String]) -> bool {
+        self.namespaces
+            .get(namespace)
+            .is_some_and(|namespace| namespace.is_user_allowed(username, groups))
+    }
+
+    /// Returns true when the labels identify a sensitive Pod
+    pub fn is_sensitive(&self, labels: Option<&BTreeMap<String, String>>) -> bool {
+        labels.is_some_and(|labels| {
+            self.sensitive_pod_labels
+                .iter()
+                .any(|(key, value)| labels.get(key) == Some(value))
+        })
+    }
+}
+
+impl UserAllowlist for NamespaceSettings {
+    fn allowed_users(&self) -> &HashSet<String> {
+        &self.allowed_users
+    }
+
+    fn allowed_groups(&self) -> &HashSet<String> {
+        &self.allowed_groups
+    }
+}
+
+impl UserAllowlist for Settings {
+    fn allowed_users(&self) -> &HashSet<String> {
+        &self.allowed_users
+    }
+
+    fn allowed_groups(&self) -> &HashSet<String> {
+        &self.allowed_groups
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_users.contains("") {
+            return Err("allowedUsers cannot contain empty values".to_owned());
+        }
+        if self.allowed_groups.contains("") {
+            return Err("allowedGroups cannot contain empty values".to_owned());
+        }
+        for (namespace, settings) in &self.namespaces {
+            if namespace.is_empty() {
+                return Err("namespaces cannot contain empty names".to_owned());
+            }
+            if settings.allowed_users.contains("") || settings.allowed_groups.contains("") {
+                return Err(format!(
+                    "namespace '{namespace}': allowedUsers and allowedGroups cannot contain empty values"
+                ));
+            }
+        }
+        if self.sensitive_pod_labels.contains_key("") {
+            return Err("sensitivePodLabels cannot contain empty keys".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        serde_json::from_str(
+            r#"{
+                "allowedGroups": ["system:masters", "sre"],
+                "namespaces": {"payments": {"allowedGroups": ["team-payments"]}},
+                "sensitivePodLabels": {"kubewarden.io/sensitive": "true"}
+            }"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    #[rstest]
+    #[case::sre("payments", "jane", vec!["sre"], true)]
+    #[case::team_member("payments", "john", vec!["team-payments"], true)]
+    #[case::team_member_in_other_namespace("billing", "john", vec!["team-payments"], false)]
+    #[case::other_user("payments", "mallory", vec!["system:authenticated"], false)]
+    fn allowed_users(
+        #[case] namespace: &str,
+        #[case] username: &str,
+        #[case] groups: Vec<&str>,
+        #[case] expected: bool,
+    ) {
+        let settings = settings();
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(
+            settings.is_user_allowed(username, &groups)
+                || settings.is_user_allowed_in(namespace, username, &groups),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::sensitive(&[("kubewarden.io/sensitive", "true"), ("app", "vault")], true)]
+    #[case::other_value(&[("kubewarden.io/sensitive", "false")], false)]
+    #[case::no_label(&[("app", "web")], false)]
+    fn sensitive(#[case] labels: &[(&str, &str)], #[case] expected: bool) {
+        let labels: BTreeMap<String, String> = labels
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        assert_eq!(settings().is_sensitive(Some(&labels)), expected);
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::namespaces(
+        r#"{"namespaces": {"payments": {"allowedUsers": ["john"], "allowedGroups": ["team-payments"]}}}"#,
+        true
+    )]
+    #[case::empty_user(r#"{"allowedUsers": [""]}"#, false)]
+    #[case::empty_group(r#"{"allowedGroups": [""]}"#, false)]
+    #[case::empty_namespace(r#"{"namespaces": {"": {}}}"#, false)]
+    #[case::empty_namespace_group(
+        r#"{"namespaces": {"payments": {"allowedGroups": [""]}}}"#,
+        false
+    )]
+    #[case::empty_label(r#"{"sensitivePodLabels": {"": "true"}}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -28,3 +28,90 @@
   [ "$status" -eq 1 ]
   [ $(expr "$output" : 'null') -ne 0 ]
 }
+
+@test "Reject Pod with a different ndots value in enforce mode" {
+  run kwctl run \
+    --request-path test_data/pod_with_5_ndots.json \
+    --settings-json '{"ndots": 2, "mode": "enforce"}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+  [ $(expr "$output" : ".*the ndots DNS option must be set to 2, found 5.*") -ne 0 ]
+}
+
+@test "Clamp the ndots value of a Pod outside of the accepted range" {
+  # Need to run the command inside of `bash -c` because of a bats
+  # limitation: https://bats-core.readthedocs.io/en/stable/gotchas.html?highlight=pipe#my-piped-command-does-not-work-under-run
+  run bash -c 'kwctl run \
+    --request-path test_data/pod_with_5_ndots.json \
+    --settings-json "{\"minNdots\": 1, \"maxNdots\": 3}" \
+    annotated-policy.wasm 2>/dev/null | jq -er ".patch | @base64d"'
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*{"op":"replace","path":"/spec/dnsConfig/options/0/value","value":"3"}.*') -ne 0 ]
+}
+
+@test "Add the configured DNS options to a Pod" {
+  # Need to run the command inside of `bash -c` because of a bats
+  # limitation: https://bats-core.readthedocs.io/en/stable/gotchas.html?highlight=pipe#my-piped-command-does-not-work-under-run
+  run bash -c 'kwctl run \
+    --request-path test_data/pod_with_5_ndots.json \
+    --settings-json "{\"ndots\": 5, \"dnsOptions\": {\"timeout\": 2}}" \
+    annotated-policy.wasm 2>/dev/null | jq -er ".patch | @base64d"'
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"name":"timeout","value":"2".*') -ne 0 ]
+}
+
+@test "Reject a Pod using a DNS policy that is not allowed" {
+  run kwctl run \
+    --request-path test_data/pod_with_default_dns_policy.json \
+    --settings-json '{"ndots": 2, "allowedDnsPolicies": ["ClusterFirst"]}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*allowed.*false') -ne 0 ]
+  [ $(expr "$output" : '.*the Default DNS policy is not allowed.*') -ne 0 ]
+}
+
+@test "Set the fallback DNS policy" {
+  # Need to run the command inside of `bash -c` because of a bats
+  # limitation: https://bats-core.readthedocs.io/en/stable/gotchas.html?highlight=pipe#my-piped-command-does-not-work-under-run
+  run bash -c 'kwctl run \
+    --request-path test_data/pod_with_default_dns_policy.json \
+    --settings-json "{\"ndots\": 2, \"allowedDnsPolicies\": [\"ClusterFirst\"], \"fallbackDnsPolicy\": \"ClusterFirst\"}" \
+    annotated-policy.wasm 2>/dev/null | jq -er ".patch | @base64d"'
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"path":"/spec/dnsPolicy","value":"ClusterFirst".*') -ne 0 ]
+}
+
+@test "Do not mutate a Pod using the host network" {
+  run kwctl run \
+    --request-path test_data/pod_with_host_network.json \
+    --settings-json '{"ndots": 2, "skipHostNetworkPods": true}' \
+    annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*allowed.*true') -ne 0 ]
+  [ $(expr "$output" : '.*"patch".*') -eq 0 ]
+}
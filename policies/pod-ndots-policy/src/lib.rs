@@ -1,14 +1,40 @@
 use guest::prelude::*;
 use kubewarden_policy_sdk::wapc_guest as guest;
 
+use std::collections::BTreeMap;
+
+use anyhow::{Result, anyhow};
 use k8s_openapi::Resource;
-use k8s_openapi::api::core::v1::{self as apicore, PodSpec};
+use k8s_openapi::api::core::v1 as apicore;
+use serde::{Deserialize, Serialize};
 
 extern crate kubewarden_policy_sdk as kubewarden;
-use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+use kubewarden::{
+    host_capabilities::kubernetes::GetResourceRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::get_resource;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::get_resource;
 
 mod settings;
-use settings::Settings;
+use settings::{DEFAULT_DNS_POLICY, Mode, Settings, same_search_domain};
+
+/// Annotation of the Namespaces overriding the ndots value of their Pods
+const NDOTS_ANNOTATION: &str = "policies.kubewarden.io/ndots";
+
+/// Fields of the Pod spec used by the policy. The rest of the Pod spec is not
+/// parsed and it is left untouched by the mutation.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    dns_config: Option<apicore::PodDNSConfig>,
+    dns_policy: Option<String>,
+    host_network: Option<bool>,
+    service_account_name: Option<String>,
+}
 
 #[unsafe(no_mangle)]
 pub extern "C" fn wapc_init() {
@@ -19,26 +45,180 @@ pub extern "C" fn wapc_init() {
 
 fn validate(payload: &[u8]) -> CallResult {
     let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = &validation_request.request;
 
-    if validation_request.request.kind.kind != apicore::Pod::KIND {
+    if request.kind.kind != apicore::Pod::KIND {
+        return kubewarden::accept_request();
+    }
+    let Some(podspec) =
+        partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object)?
+    else {
+        return kubewarden::accept_request();
+    };
+    let labels: BTreeMap<String, String> = request
+        .object
+        .pointer("/metadata/labels")
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()?
+        .unwrap_or_default();
+    if validation_request.settings.exemptions.is_exempt(
+        &request.namespace,
+        &labels,
+        podspec.service_account_name.as_deref(),
+    ) {
         return kubewarden::accept_request();
     }
-    let pod = serde_json::from_value::<apicore::Pod>(validation_request.request.object)?;
 
-    let podspec = pod.spec.clone().unwrap_or_default();
-    let podspec_patched = enforce_ndots(&validation_request.settings, &podspec);
+    let settings = &match namespace_ndots(&validation_request.settings, &request.namespace)? {
+        Some(ndots) => validation_request.settings.with_ndots(ndots),
+        None => validation_request.settings.clone(),
+    };
+    let required_options = required_dns_options(settings, &podspec);
+    let mut podspec_patched =
+        enforce_dns_options(&strip_dns_config(settings, &podspec), &required_options);
+    let mut errors = Vec::new();
+    if let Some(error) = dns_policy_error(settings, &podspec) {
+        match (settings.mode, &settings.fallback_dns_policy) {
+            (Mode::Mutate, Some(fallback)) => podspec_patched.dns_policy = Some(fallback.clone()),
+            _ => errors.push(error),
+        }
+    }
+    if settings.mode == Mode::Enforce {
+        errors.extend(dns_options_errors(settings, &podspec, &required_options));
+        errors.extend(dns_config_errors(settings, &podspec));
+    }
+
+    if !errors.is_empty() {
+        return kubewarden::reject_request(
+            Some(format!("Pod '{}': {}", request.name, errors.join("; "))),
+            None,
+            None,
+            None,
+        );
+    }
     if podspec_patched != podspec {
-        let patched_pod = apicore::Pod {
-            spec: Some(podspec_patched),
-            ..pod
-        };
-        return kubewarden::mutate_request(serde_json::to_value(&patched_pod)?);
+        return kubewarden::mutate_request(partial_pod_spec::patch(
+            &request.kind.kind,
+            &request.object,
+            &podspec_patched,
+        )?);
     }
 
     kubewarden::accept_request()
 }
 
-fn enforce_ndots(settings: &Settings, podspec: &apicore::PodSpec) -> PodSpec {
+/// Returns the ndots value of the annotation of the namespace, when the
+/// namespaces can override the ndots value of their Pods
+fn namespace_ndots(settings: &Settings, namespace: &str) -> Result<Option<usize>> {
+    if !settings.namespace_override || namespace.is_empty() {
+        return Ok(None);
+    }
+    let req = GetResourceRequest {
+        api_version: "v1".to_string(),
+        kind: "Namespace".to_string(),
+        name: namespace.to_string(),
+        namespace: None,
+        disable_cache: false,
+        field_masks: None,
+    };
+    let namespace: apicore::Namespace = get_resource(&req)?;
+    namespace
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(NDOTS_ANNOTATION))
+        .map(|ndots| {
+            ndots.parse::<usize>().map_err(|_| {
+                anyhow!(
+                    "invalid {NDOTS_ANNOTATION} annotation of the '{}' namespace: '{ndots}' is not a number",
+                    req.name
+                )
+            })
+        })
+        .transpose()
+}
+
+/// Describes the DNS policy of the Pod when it is not allowed. The Pods
+/// without a DNS policy use the ClusterFirst one.
+fn dns_policy_error(settings: &Settings, podspec: &PodSpecFields) -> Option<String> {
+    let dns_policy = podspec.dns_policy.as_deref().unwrap_or(DEFAULT_DNS_POLICY);
+    if settings.is_dns_policy_allowed(dns_policy) {
+        return None;
+    }
+    Some(format!(
+        "the {dns_policy} DNS policy is not allowed, the allowed ones are: {}",
+        settings
+            .allowed_dns_policies
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+/// Returns true when the DNS configuration of the Pod is left untouched
+/// because it uses the network of the node
+fn is_skipped(settings: &Settings, podspec: &PodSpecFields) -> bool {
+    settings.skip_host_network_pods && podspec.host_network == Some(true)
+}
+
+/// Returns the DNS option of the Pod with the given name, if any
+fn dns_option_of<'a>(
+    podspec: &'a PodSpecFields,
+    name: &str,
+) -> Option<&'a apicore::PodDNSConfigOption> {
+    podspec
+        .dns_config
+        .as_ref()?
+        .options
+        .as_ref()?
+        .iter()
+        .find(|option| option.name.as_deref() == Some(name))
+}
+
+/// Returns the value of the ndots DNS option of the Pod, if any
+fn ndots_of(podspec: &PodSpecFields) -> Option<&str> {
+    dns_option_of(podspec, "ndots").map(|option| option.value.as_deref().unwrap_or_default())
+}
+
+/// Returns the DNS options the Pod must have, with their values. The ndots
+/// option is required only when the value of the Pod is outside of the range
+/// accepted, and no option is required for the Pods using the network of the
+/// node when they are skipped.
+fn required_dns_options(
+    settings: &Settings,
+    podspec: &PodSpecFields,
+) -> Vec<apicore::PodDNSConfigOption> {
+    let mut required_options = Vec::new();
+    if is_skipped(settings, podspec) {
+        return required_options;
+    }
+    let current = ndots_of(podspec).and_then(|ndots| ndots.parse::<usize>().ok());
+    if !current.is_some_and(|ndots| settings.range().contains(&ndots)) {
+        required_options.push(apicore::PodDNSConfigOption {
+            name: Some("ndots".to_string()),
+            value: Some(settings.target_ndots(current).to_string()),
+        });
+    }
+    required_options.extend(settings.dns_options.iter().map(|(name, value)| {
+        apicore::PodDNSConfigOption {
+            name: Some(name.clone()),
+            value: value.as_ref().map(ToString::to_string),
+        }
+    }));
+    required_options
+}
+
+/// Returns a copy of the Pod spec fields with the required DNS options
+fn enforce_dns_options(
+    podspec: &PodSpecFields,
+    required_options: &[apicore::PodDNSConfigOption],
+) -> PodSpecFields {
+    if required_options.is_empty() {
+        return podspec.clone();
+    }
+
     // preserve the order of the options to prevent needless updates
     let mut dns_options: Vec<apicore::PodDNSConfigOption> = podspec
         .dns_config
@@ -47,29 +227,25 @@ fn enforce_ndots(settings: &Settings, podspec: &apicore::PodSpec) -> PodSpec {
         .unwrap_or_default()
         .iter()
         .map(|option| {
-            if option.name == Some("ndots".to_string()) {
-                apicore::PodDNSConfigOption {
-                    name: Some("ndots".to_string()),
-                    value: Some(settings.ndots.to_string()),
-                }
-            } else {
-                option.clone()
-            }
+            required_options
+                .iter()
+                .find(|required_option| required_option.name == option.name)
+                .unwrap_or(option)
+                .clone()
         })
         .collect();
 
-    // ensure the option is added if it's not present
-    if dns_options
-        .iter()
-        .all(|option| option.name != Some("ndots".to_string()))
-    {
-        dns_options.push(apicore::PodDNSConfigOption {
-            name: Some("ndots".to_string()),
-            value: Some(settings.ndots.to_string()),
-        });
+    // ensure the options are added if they are not present
+    for required_option in required_options {
+        if dns_options
+            .iter()
+            .all(|option| option.name != required_option.name)
+        {
+            dns_options.push(required_option.clone());
+        }
     }
 
-    PodSpec {
+    PodSpecFields {
         dns_config: Some(apicore::PodDNSConfig {
             nameservers: podspec
                 .dns_config
@@ -85,12 +261,134 @@ fn enforce_ndots(settings: &Settings, podspec: &apicore::PodSpec) -> PodSpec {
     }
 }
 
+/// Returns the search domains without their duplicates, keeping the first
+/// occurrence of each of them
+fn dedup_search_domains(searches: &[String]) -> Vec<String> {
+    let mut deduped: Vec<String> = Vec::new();
+    for search in searches {
+        if !deduped
+            .iter()
+            .any(|deduped_search| same_search_domain(deduped_search, search))
+        {
+            deduped.push(search.clone());
+        }
+    }
+    deduped
+}
+
+/// Returns a copy of the Pod spec fields without the search domains and the
+/// nameservers that are not allowed. When the search domains are limited,
+/// their duplicates are removed and the list is truncated.
+fn strip_dns_config(settings: &Settings, podspec: &PodSpecFields) -> PodSpecFields {
+    let mut podspec = podspec.clone();
+    if is_skipped(settings, &podspec) {
+        return podspec;
+    }
+    if let Some(dns_config) = podspec.dns_config.as_mut() {
+        if let Some(searches) = dns_config.searches.as_mut() {
+            searches.retain(|search| settings.is_search_domain_allowed(search));
+            if let Some(max_search_domains) = settings.max_search_domains {
+                *searches = dedup_search_domains(searches);
+                searches.truncate(max_search_domains);
+            }
+        }
+        if let Some(nameservers) = dns_config.nameservers.as_mut() {
+            nameservers.retain(|nameserver| settings.is_nameserver_allowed(nameserver));
+        }
+    }
+    podspec
+}
+
+/// Describes the search domains and the nameservers of the Pod that are not
+/// allowed, for the rejection message
+fn dns_config_errors(settings: &Settings, podspec: &PodSpecFields) -> Vec<String> {
+    let Some(dns_config) = podspec
+        .dns_config
+        .as_ref()
+        .filter(|_| !is_skipped(settings, podspec))
+    else {
+        return Vec::new();
+    };
+    let searches = dns_config
+        .searches
+        .iter()
+        .flatten()
+        .filter(|search| !settings.is_search_domain_allowed(search))
+        .map(|search| format!("the {search} search domain is not allowed"));
+    let search_domains_count = settings.max_search_domains.and_then(|max_search_domains| {
+        let searches = dns_config.searches.as_deref().unwrap_or_default();
+        let deduped = dedup_search_domains(searches);
+        if deduped.len() < searches.len() {
+            Some("the search domains cannot contain duplicates".to_owned())
+        } else if deduped.len() > max_search_domains {
+            Some(format!(
+                "the DNS configuration has {} search domains, at most {max_search_domains} are allowed",
+                deduped.len()
+            ))
+        } else {
+            None
+        }
+    });
+    let nameservers = dns_config
+        .nameservers
+        .iter()
+        .flatten()
+        .filter(|nameserver| !settings.is_nameserver_allowed(nameserver))
+        .map(|nameserver| format!("the {nameserver} nameserver is not allowed"));
+    searches
+        .chain(search_domains_count)
+        .chain(nameservers)
+        .collect()
+}
+
+/// Describes the required DNS options the Pod does not have, for the
+/// rejection message
+fn dns_options_errors(
+    settings: &Settings,
+    podspec: &PodSpecFields,
+    required_options: &[apicore::PodDNSConfigOption],
+) -> Vec<String> {
+    required_options
+        .iter()
+        .filter_map(|required_option| {
+            let name = required_option.name.as_deref().unwrap_or_default();
+            let current = dns_option_of(podspec, name);
+            if current == Some(required_option) {
+                return None;
+            }
+            let expected = match (name, &required_option.value) {
+                ("ndots", _) => settings.describe_range(),
+                (_, Some(value)) => format!("set to {value}"),
+                (_, None) => "set without a value".to_owned(),
+            };
+            let found = match current.map(|option| option.value.as_deref()) {
+                Some(Some(value)) => format!("found {value}"),
+                Some(None) => "found it without a value".to_owned(),
+                None => "it is not set".to_owned(),
+            };
+            Some(format!("the {name} DNS option must be {expected}, {found}"))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
     use rstest::*;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::GetResourceRequest;
+
+        #[allow(dead_code)]
+        pub fn get_resource<T: 'static>(_req: &GetResourceRequest) -> anyhow::Result<T> {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
 
     fn build_pod_dns_config(ndots: Option<usize>) -> apicore::PodDNSConfig {
         let mut options = vec![apicore::PodDNSConfigOption {
@@ -112,6 +410,10 @@ mod tests {
         }
     }
 
+    fn enforce(settings: &Settings, podspec: &PodSpecFields) -> PodSpecFields {
+        enforce_dns_options(podspec, &required_dns_options(settings, podspec))
+    }
+
     #[rstest]
     #[case::no_dns_config(None, apicore::PodDNSConfig {
         options: Some(vec![apicore::PodDNSConfigOption{
@@ -132,22 +434,20 @@ mod tests {
         #[case] dns_config: Option<apicore::PodDNSConfig>,
         #[case] expected_dns_config: apicore::PodDNSConfig,
     ) {
-        let settings = Settings { ndots: 5 };
-        let podspec = PodSpec {
+        let settings = Settings {
+            ndots: 5,
+            ..Default::default()
+        };
+        let podspec = PodSpecFields {
             dns_config,
-            containers: vec![apicore::Container {
-                name: "nginx".to_string(),
-                image: Some("nginx".to_string()),
-                ..Default::default()
-            }],
             ..Default::default()
         };
-        let expected_podspec = PodSpec {
+        let expected_podspec = PodSpecFields {
             dns_config: Some(expected_dns_config),
-            ..podspec.clone()
+            ..Default::default()
         };
 
-        let podspec_patched = enforce_ndots(&settings, &podspec);
+        let podspec_patched = enforce(&settings, &podspec);
         assert_eq!(
             podspec_patched, expected_podspec,
             "got: {:?} instead of {:?}",
@@ -155,12 +455,79 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case::inside_range(Some(3), Some(3))]
+    #[case::below_range(Some(1), Some(2))]
+    #[case::above_range(Some(8), Some(5))]
+    #[case::without_ndots(None, Some(2))]
+    fn enforce_ndots_range(#[case] ndots: Option<usize>, #[case] expected_ndots: Option<usize>) {
+        let settings = Settings {
+            min_ndots: Some(2),
+            max_ndots: Some(5),
+            ..Default::default()
+        };
+        let podspec = PodSpecFields {
+            dns_config: Some(build_pod_dns_config(ndots)),
+            ..Default::default()
+        };
+        let expected_podspec = PodSpecFields {
+            dns_config: Some(build_pod_dns_config(expected_ndots)),
+            ..Default::default()
+        };
+
+        assert_eq!(enforce(&settings, &podspec), expected_podspec);
+    }
+
+    #[test]
+    fn enforce_other_dns_options() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"ndots": 5, "dnsOptions": {"timeout": 2, "attempts": "3", "use-vc": null}}"#,
+        )
+        .expect("cannot parse settings");
+        let podspec = PodSpecFields {
+            dns_config: Some(build_pod_dns_config(Some(5))),
+            ..Default::default()
+        };
+        let option = |name: &str, value: Option<&str>| apicore::PodDNSConfigOption {
+            name: Some(name.to_string()),
+            value: value.map(str::to_string),
+        };
+
+        let podspec_patched = enforce(&settings, &podspec);
+        assert_eq!(
+            podspec_patched
+                .dns_config
+                .and_then(|dns_config| dns_config.options),
+            Some(vec![
+                option("timeout", Some("2")),
+                option("ndots", Some("5")),
+                option("attempts", Some("3")),
+                option("use-vc", None),
+            ])
+        );
+        assert_eq!(
+            dns_options_errors(
+                &settings,
+                &podspec,
+                &required_dns_options(&settings, &podspec)
+            ),
+            vec![
+                "the attempts DNS option must be set to 3, it is not set",
+                "the timeout DNS option must be set to 2, found 5",
+                "the use-vc DNS option must be set without a value, it is not set",
+            ]
+        );
+    }
+
     #[rstest]
     // Note: this test cares only about covering the switch statement of the resournce kind
     #[case::change_pod("test_data/pod_without_ndots.json", true)]
     #[case::do_not_change_pod("test_data/pod_with_5_ndots.json", false)]
     fn test_validate(#[case] fixture: &str, #[case] expect_mutated_object: bool) {
-        let settings = Settings { ndots: 5 };
+        let settings = Settings {
+            ndots: 5,
+            ..Default::default()
+        };
 
         let test_case = Testcase {
             name: "test".to_string(),
@@ -184,4 +551,410 @@ mod tests {
             assert!(validation_response.mutated_object.is_none());
         }
     }
+
+    #[test]
+    fn mutation_keeps_unknown_fields() {
+        let test_case = Testcase {
+            name: "unknown fields".to_string(),
+            fixture_file: "test_data/pod_with_unknown_fields.json".to_string(),
+            expected_validation_result: true,
+            settings: Settings {
+                ndots: 5,
+                ..Default::default()
+            },
+        };
+
+        let validation_response = test_case.eval(validate).expect("validation failed");
+        let pod = validation_response
+            .mutated_object
+            .expect("the pod should be mutated");
+        assert_eq!(
+            pod["spec"]["dnsConfig"]["options"][0],
+            serde_json::json!({"name": "ndots", "value": "5"})
+        );
+        assert_eq!(
+            pod["spec"]["fieldFromTheFuture"],
+            serde_json::json!({"enabled": true})
+        );
+        assert_eq!(
+            pod["spec"]["containers"][0]["fieldFromTheFuture"],
+            serde_json::json!("nginx")
+        );
+    }
+
+    #[rstest]
+    #[case::without_ndots(
+        "test_data/pod_without_ndots.json",
+        None,
+        Some("Pod 'nginx': the ndots DNS option must be set to 2, it is not set")
+    )]
+    #[case::other_ndots(
+        "test_data/pod_with_5_ndots.json",
+        None,
+        Some("Pod 'nginx': the ndots DNS option must be set to 2, found 5")
+    )]
+    #[case::expected_ndots("test_data/pod_with_2_ndots.json", None, None)]
+    #[case::inside_range("test_data/pod_with_2_ndots.json", Some(3), None)]
+    #[case::out_of_range(
+        "test_data/pod_with_5_ndots.json",
+        Some(3),
+        Some("Pod 'nginx': the ndots DNS option must be at most 3, found 5")
+    )]
+    fn enforce_mode(
+        #[case] fixture: &str,
+        #[case] max_ndots: Option<usize>,
+        #[case] expected_message: Option<&str>,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_string(),
+            fixture_file: fixture.to_string(),
+            expected_validation_result: expected_message.is_none(),
+            settings: Settings {
+                ndots: 2,
+                max_ndots,
+                mode: Mode::Enforce,
+                ..Default::default()
+            },
+        };
+
+        let validation_response = test_case.eval(validate).expect("validation failed");
+        assert!(validation_response.mutated_object.is_none());
+        assert_eq!(validation_response.message.as_deref(), expected_message);
+    }
+
+    #[rstest]
+    #[case::allowed_dns_policy(
+        "test_data/pod_with_default_dns_policy.json",
+        &["ClusterFirst", "Default"],
+        None,
+        Mode::Mutate,
+        None
+    )]
+    #[case::default_dns_policy(
+        "test_data/pod_with_2_ndots.json",
+        &["ClusterFirst"],
+        None,
+        Mode::Enforce,
+        None
+    )]
+    #[case::fallback_dns_policy(
+        "test_data/pod_with_default_dns_policy.json",
+        &["ClusterFirst"],
+        Some("ClusterFirst"),
+        Mode::Mutate,
+        None
+    )]
+    #[case::no_fallback_dns_policy(
+        "test_data/pod_with_default_dns_policy.json",
+        &["ClusterFirst", "ClusterFirstWithHostNet"],
+        None,
+        Mode::Mutate,
+        Some("Pod 'nginx': the Default DNS policy is not allowed, the allowed ones are: ClusterFirst, ClusterFirstWithHostNet")
+    )]
+    #[case::enforce_mode(
+        "test_data/pod_with_default_dns_policy.json",
+        &["ClusterFirst"],
+        Some("ClusterFirst"),
+        Mode::Enforce,
+        Some("Pod 'nginx': the Default DNS policy is not allowed, the allowed ones are: ClusterFirst")
+    )]
+    fn allowed_dns_policies(
+        #[case] fixture: &str,
+        #[case] allowed_dns_policies: &[&str],
+        #[case] fallback_dns_policy: Option<&str>,
+        #[case] mode: Mode,
+        #[case] expected_message: Option<&str>,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_string(),
+            fixture_file: fixture.to_string(),
+            expected_validation_result: expected_message.is_none(),
+            settings: Settings {
+                ndots: 2,
+                allowed_dns_policies: allowed_dns_policies.iter().map(|p| p.to_string()).collect(),
+                fallback_dns_policy: fallback_dns_policy.map(str::to_string),
+                mode,
+                ..Default::default()
+            },
+        };
+
+        let validation_response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(validation_response.message.as_deref(), expected_message);
+        let dns_policy = validation_response
+            .mutated_object
+            .map(|pod| pod["spec"]["dnsPolicy"].clone());
+        assert_eq!(
+            dns_policy,
+            fallback_dns_policy
+                .filter(|_| mode == Mode::Mutate)
+                .map(serde_json::Value::from)
+        );
+    }
+
+    #[rstest]
+    #[case::skipped(true, None)]
+    #[case::not_skipped(
+        false,
+        Some("Pod 'node-exporter': the ndots DNS option must be set to 2, it is not set")
+    )]
+    fn skip_host_network_pods(
+        #[case] skip_host_network_pods: bool,
+        #[case] expected_message: Option<&str>,
+    ) {
+        let test_case = Testcase {
+            name: "host network".to_string(),
+            fixture_file: "test_data/pod_with_host_network.json".to_string(),
+            expected_validation_result: expected_message.is_none(),
+            settings: Settings {
+                ndots: 2,
+                skip_host_network_pods,
+                mode: Mode::Enforce,
+                ..Default::default()
+            },
+        };
+
+        let validation_response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(validation_response.message.as_deref(), expected_message);
+    }
+
+    #[rstest]
+    #[case::allowed(
+        build_pod_dns_config(Some(5)),
+        r#"{"allowedSearchDomains": ["example.com."], "allowedNameservers": ["1.1.1.1"]}"#,
+        build_pod_dns_config(Some(5))
+    )]
+    #[case::not_allowed(
+        build_pod_dns_config(Some(5)),
+        r#"{"allowedSearchDomains": ["svc.cluster.local"], "allowedNameservers": ["10.0.0.10"]}"#,
+        apicore::PodDNSConfig {
+            nameservers: Some(vec![]),
+            searches: Some(vec![]),
+            ..build_pod_dns_config(Some(5))
+        }
+    )]
+    #[case::all_allowed(build_pod_dns_config(Some(5)), r#"{}"#, build_pod_dns_config(Some(5)))]
+    fn strip_search_domains_and_nameservers(
+        #[case] dns_config: apicore::PodDNSConfig,
+        #[case] settings: &str,
+        #[case] expected_dns_config: apicore::PodDNSConfig,
+    ) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        let podspec = PodSpecFields {
+            dns_config: Some(dns_config),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            strip_dns_config(&settings, &podspec).dns_config,
+            Some(expected_dns_config)
+        );
+    }
+
+    #[test]
+    fn search_domains_and_nameservers_errors() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"allowedSearchDomains": ["svc.cluster.local"], "allowedNameservers": ["10.0.0.10"]}"#,
+        )
+        .expect("cannot parse settings");
+        let podspec = PodSpecFields {
+            dns_config: Some(apicore::PodDNSConfig {
+                nameservers: Some(vec!["10.0.0.10".to_string(), "8.8.8.8".to_string()]),
+                searches: Some(vec![
+                    "SVC.cluster.local".to_string(),
+                    "corp.example.com".to_string(),
+                ]),
+                options: None,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            dns_config_errors(&settings, &podspec),
+            vec![
+                "the corp.example.com search domain is not allowed",
+                "the 8.8.8.8 nameserver is not allowed",
+            ]
+        );
+    }
+
+    fn namespace(annotations: &[(&str, &str)]) -> apicore::Namespace {
+        apicore::Namespace {
+            metadata: k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta {
+                name: Some("default".to_string()),
+                annotations: Some(
+                    annotations
+                        .iter()
+                        .map(|(key, value)| (key.to_string(), value.to_string()))
+                        .collect(),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::annotated_namespace(&[(NDOTS_ANNOTATION, "2")], "2")]
+    #[case::namespace_without_annotation(&[], "5")]
+    #[serial]
+    fn namespace_override(#[case] annotations: &[(&str, &str)], #[case] expected_ndots: &str) {
+        let namespace = namespace(annotations);
+        let ctx = mock_kubernetes_sdk::get_resource_context();
+        ctx.expect::<apicore::Namespace>()
+            .times(1)
+            .returning(move |req| {
+                assert_eq!(req.kind, "Namespace");
+                assert_eq!(req.name, "default");
+                Ok(namespace.clone())
+            });
+
+        let test_case = Testcase {
+            name: "namespace override".to_string(),
+            fixture_file: "test_data/pod_without_ndots.json".to_string(),
+            expected_validation_result: true,
+            settings: Settings {
+                ndots: 5,
+                min_ndots: Some(4),
+                namespace_override: true,
+                ..Default::default()
+            },
+        };
+
+        let validation_response = test_case.eval(validate).expect("validation failed");
+        let pod = validation_response
+            .mutated_object
+            .expect("the pod should be mutated");
+        assert_eq!(
+            pod["spec"]["dnsConfig"]["options"][0],
+            serde_json::json!({"name": "ndots", "value": expected_ndots})
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn namespace_override_disabled() {
+        let ctx = mock_kubernetes_sdk::get_resource_context();
+        ctx.expect::<apicore::Namespace>().times(0);
+
+        let test_case = Testcase {
+            name: "namespace override disabled".to_string(),
+            fixture_file: "test_data/pod_with_5_ndots.json".to_string(),
+            expected_validation_result: true,
+            settings: Settings {
+                ndots: 5,
+                ..Default::default()
+            },
+        };
+
+        let validation_response = test_case.eval(validate).expect("validation failed");
+        assert!(validation_response.mutated_object.is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn invalid_namespace_override() {
+        let ctx = mock_kubernetes_sdk::get_resource_context();
+        ctx.expect::<apicore::Namespace>()
+            .times(1)
+            .returning(|_| Ok(namespace(&[(NDOTS_ANNOTATION, "two")])));
+
+        let settings = Settings {
+            namespace_override: true,
+            ..Default::default()
+        };
+        let payload = std::fs::read_to_string("test_data/pod_without_ndots.json")
+            .expect("cannot read the fixture");
+        let mut request: serde_json::Value =
+            serde_json::from_str(&payload).expect("cannot parse the fixture");
+        request = serde_json::json!({"request": request, "settings": settings});
+
+        let error =
+            validate(request.to_string().as_bytes()).expect_err("the annotation is not a number");
+        assert_eq!(
+            error.to_string(),
+            "invalid policies.kubewarden.io/ndots annotation of the 'default' namespace: 'two' is not a number"
+        );
+    }
+
+    #[rstest]
+    #[case::label_selector(r#"{"labelSelectors": [{"k8s-app": "node-local-dns"}]}"#, true)]
+    #[case::partial_label_selector(
+        r#"{"labelSelectors": [{"k8s-app": "node-local-dns", "tier": "dns"}]}"#,
+        false
+    )]
+    #[case::namespace(r#"{"namespaces": ["kube-system"]}"#, true)]
+    #[case::service_account_name(r#"{"serviceAccountNames": ["node-local-dns"]}"#, true)]
+    #[case::other_service_account_name(r#"{"serviceAccountNames": ["coredns"]}"#, false)]
+    #[case::no_exemptions(r#"{}"#, false)]
+    fn exemptions(#[case] exemptions: &str, #[case] is_exempt: bool) {
+        let test_case = Testcase {
+            name: exemptions.to_string(),
+            fixture_file: "test_data/pod_node_local_dns.json".to_string(),
+            expected_validation_result: is_exempt,
+            settings: Settings {
+                ndots: 2,
+                exemptions: serde_json::from_str(exemptions).expect("cannot parse exemptions"),
+                mode: Mode::Enforce,
+                ..Default::default()
+            },
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::deduplicated(
+        &["a.example.com", "b.example.com", "A.example.com.", "c.example.com"],
+        None,
+        &["a.example.com", "b.example.com", "c.example.com"],
+        "the search domains cannot contain duplicates"
+    )]
+    #[case::truncated(
+        &["a.example.com", "b.example.com", "c.example.com"],
+        None,
+        &["a.example.com", "b.example.com"],
+        "the DNS configuration has 3 search domains, at most 2 are allowed"
+    )]
+    #[case::allowed_then_truncated(
+        &["corp.example.com", "a.example.com", "b.example.com", "c.example.com"],
+        Some(&["a.example.com", "b.example.com", "c.example.com"][..]),
+        &["a.example.com", "b.example.com"],
+        "the corp.example.com search domain is not allowed"
+    )]
+    fn limit_search_domains(
+        #[case] searches: &[&str],
+        #[case] allowed_search_domains: Option<&[&str]>,
+        #[case] expected_searches: &[&str],
+        #[case] expected_error: &str,
+    ) {
+        let settings = Settings {
+            max_search_domains: Some(2),
+            allowed_search_domains: allowed_search_domains
+                .unwrap_or_default()
+                .iter()
+                .map(|search| search.to_string())
+                .collect(),
+            ..Default::default()
+        };
+        let podspec = PodSpecFields {
+            dns_config: Some(apicore::PodDNSConfig {
+                searches: Some(searches.iter().map(|search| search.to_string()).collect()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            strip_dns_config(&settings, &podspec)
+                .dns_config
+                .and_then(|dns_config| dns_config.searches),
+            Some(
+                expected_searches
+                    .iter()
+                    .map(|search| search.to_string())
+                    .collect()
+            )
+        );
+        assert_eq!(dns_config_errors(&settings, &podspec)[0], expected_error);
+    }
 }
@@ -1,19 +1,344 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    net::IpAddr,
+    ops::RangeInclusive,
+};
+
 use serde::{Deserialize, Serialize};
 
+/// What to do with the Pods not using the configured ndots value
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Mode {
+    /// Set the ndots option of the Pod
+    #[default]
+    Mutate,
+    /// Reject the Pod, for the clusters where mutating policies are not
+    /// allowed
+    Enforce,
+}
+
+/// DNS policies of the Pods
+const DNS_POLICIES: &[&str] = &["ClusterFirst", "ClusterFirstWithHostNet", "Default", "None"];
+
+/// Search domains accepted by Kubernetes in the DNS configuration of a Pod
+const MAX_SEARCH_DOMAINS: usize = 32;
+
+/// DNS policy of the Pods without the `dnsPolicy` field
+pub(crate) const DEFAULT_DNS_POLICY: &str = "ClusterFirst";
+
+/// Value of a DNS option. The numbers are accepted too, as YAML users write
+/// `timeout: 2` rather than `timeout: "2"`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub(crate) enum DnsOptionValue {
+    Number(u64),
+    Text(String),
+}
+
+impl fmt::Display for DnsOptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DnsOptionValue::Number(number) => write!(f, "{number}"),
+            DnsOptionValue::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+/// Pods whose DNS configuration is neither changed nor validated, like the
+/// ones of the DNS and CNI infrastructure
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Exemptions {
+    /// A Pod is exempt when it has all the labels of one of the selectors
+    pub label_selectors: Vec<BTreeMap<String, String>>,
+    pub namespaces: BTreeSet<String>,
+    /// Names of the ServiceAccounts of the Pods, in all the namespaces. The
+    /// Pods without a ServiceAccount use the `default` one.
+    pub service_account_names: BTreeSet<String>,
+}
+
+impl Exemptions {
+    pub fn is_exempt(
+        &self,
+        namespace: &str,
+        labels: &BTreeMap<String, String>,
+        service_account_name: Option<&str>,
+    ) -> bool {
+        self.namespaces.contains(namespace)
+            || self
+                .service_account_names
+                .contains(service_account_name.unwrap_or("default"))
+            || self.label_selectors.iter().any(|selector| {
+                selector
+                    .iter()
+                    .all(|(key, value)| labels.get(key) == Some(value))
+            })
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.label_selectors.iter().any(BTreeMap::is_empty) {
+            return Err("exemptions: the label selectors cannot be empty".to_owned());
+        }
+        if self.namespaces.contains("") {
+            return Err("exemptions: the namespaces cannot be empty".to_owned());
+        }
+        if self.service_account_names.contains("") {
+            return Err("exemptions: the ServiceAccount names cannot be empty".to_owned());
+        }
+        Ok(())
+    }
+}
+
 // Describe the settings your policy expects when
 // loaded by the policy server.
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 pub(crate) struct Settings {
     #[serde(default = "default_ndots")]
     pub ndots: usize,
+    /// Lowest ndots value accepted. When a bound of the range is set, the
+    /// `ndots` value is used only for the Pods without the option.
+    #[serde(default, rename = "minNdots")]
+    pub min_ndots: Option<usize>,
+    /// Highest ndots value accepted
+    #[serde(default, rename = "maxNdots")]
+    pub max_ndots: Option<usize>,
+    /// DNS options, besides ndots, the Pods must have, with their values.
+    /// The options without a value, like `use-vc`, are set to null.
+    #[serde(default, rename = "dnsOptions")]
+    pub dns_options: BTreeMap<String, Option<DnsOptionValue>>,
+    /// DNS policies the Pods can use. All of them when empty.
+    #[serde(default, rename = "allowedDnsPolicies")]
+    pub allowed_dns_policies: BTreeSet<String>,
+    /// DNS policy set on the Pods using a policy that is not allowed. Without
+    /// it, these Pods are rejected.
+    #[serde(default, rename = "fallbackDnsPolicy")]
+    pub fallback_dns_policy: Option<String>,
+    /// Search domains the Pods can use. All of them when empty.
+    #[serde(default, rename = "allowedSearchDomains")]
+    pub allowed_search_domains: BTreeSet<String>,
+    /// Number of search domains the Pods can have, once the duplicates are
+    /// removed. The search domains are not limited nor deduplicated when not
+    /// set.
+    #[serde(default, rename = "maxSearchDomains")]
+    pub max_search_domains: Option<usize>,
+    /// Nameservers the Pods can use. All of them when empty.
+    #[serde(default, rename = "allowedNameservers")]
+    pub allowed_nameservers: BTreeSet<IpAddr>,
+    /// Do not enforce the DNS configuration of the Pods using the network of
+    /// the node, like the ones of the node agents
+    #[serde(default, rename = "skipHostNetworkPods")]
+    pub skip_host_network_pods: bool,
+    /// Use the ndots value of the `policies.kubewarden.io/ndots` annotation
+    /// of the namespace of the Pods, when it is set
+    #[serde(default, rename = "namespaceOverride")]
+    pub namespace_override: bool,
+    #[serde(default)]
+    pub exemptions: Exemptions,
+    #[serde(default)]
+    pub mode: Mode,
 }
 
 fn default_ndots() -> usize {
     1
 }
 
+impl Settings {
+    /// Returns the settings requiring the given ndots value, without range
+    pub fn with_ndots(&self, ndots: usize) -> Settings {
+        Settings {
+            ndots,
+            min_ndots: None,
+            max_ndots: None,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the ndots values accepted. Without `minNdots` and `maxNdots`,
+    /// only the `ndots` value is accepted.
+    pub fn range(&self) -> RangeInclusive<usize> {
+        if self.min_ndots.is_none() && self.max_ndots.is_none() {
+            return self.ndots..=self.ndots;
+        }
+        self.min_ndots.unwrap_or(0)..=self.max_ndots.unwrap_or(usize::MAX)
+    }
+
+    /// Returns the ndots value to set on a Pod: its current value, or the
+    /// `ndots` one when the Pod does not have a valid ndots option, clamped
+    /// to the range
+    pub fn target_ndots(&self, current: Option<usize>) -> usize {
+        let range = self.range();
+        current
+            .unwrap_or(self.ndots)
+            .clamp(*range.start(), *range.end())
+    }
+
+    /// Describes the values accepted, for the rejection messages
+    pub fn describe_range(&self) -> String {
+        match (self.min_ndots, self.max_ndots) {
+            (None, None) => format!("set to {}", self.ndots),
+            (Some(min), None) => format!("at least {min}"),
+            (None, Some(max)) => format!("at most {max}"),
+            (Some(min), Some(max)) if min == max => format!("set to {min}"),
+            (Some(min), Some(max)) => format!("between {min} and {max}"),
+        }
+    }
+
+    /// Returns true when the Pods can use the search domain
+    pub fn is_search_domain_allowed(&self, search_domain: &str) -> bool {
+        self.allowed_search_domains.is_empty()
+            || self
+                .allowed_search_domains
+                .iter()
+                .any(|allowed| same_search_domain(allowed, search_domain))
+    }
+
+    /// Returns true when the Pods can use the nameserver. The nameservers
+    /// that are not IP addresses are never allowed by a list.
+    pub fn is_nameserver_allowed(&self, nameserver: &str) -> bool {
+        self.allowed_nameservers.is_empty()
+            || nameserver
+                .parse::<IpAddr>()
+                .is_ok_and(|nameserver| self.allowed_nameservers.contains(&nameserver))
+    }
+
+    /// Returns true when the Pods can use the DNS policy
+    pub fn is_dns_policy_allowed(&self, dns_policy: &str) -> bool {
+        self.allowed_dns_policies.is_empty() || self.allowed_dns_policies.contains(dns_policy)
+    }
+}
+
+/// Returns true when the search domains are the same, ignoring the case and
+/// the trailing dot
+pub(crate) fn same_search_domain(a: &str, b: &str) -> bool {
+    a.trim_end_matches('.')
+        .eq_ignore_ascii_case(b.trim_end_matches('.'))
+}
+
 impl kubewarden::settings::Validatable for Settings {
     fn validate(&self) -> Result<(), String> {
+        if let (Some(min), Some(max)) = (self.min_ndots, self.max_ndots)
+            && min > max
+        {
+            return Err(format!(
+                "minNdots ({min}) cannot be greater than maxNdots ({max})"
+            ));
+        }
+        self.exemptions.validate()?;
+        if self.dns_options.contains_key("ndots") {
+            return Err(
+                "dnsOptions cannot contain ndots, use the ndots, minNdots and maxNdots settings"
+                    .to_owned(),
+            );
+        }
+        if self.dns_options.contains_key("") {
+            return Err("dnsOptions cannot contain options without a name".to_owned());
+        }
+        if self
+            .allowed_search_domains
+            .iter()
+            .any(|search_domain| search_domain.trim_end_matches('.').is_empty())
+        {
+            return Err("allowedSearchDomains cannot contain empty domains".to_owned());
+        }
+        if let Some(max_search_domains) = self.max_search_domains
+            && !(1..=MAX_SEARCH_DOMAINS).contains(&max_search_domains)
+        {
+            return Err(format!(
+                "maxSearchDomains must be between 1 and {MAX_SEARCH_DOMAINS}, found {max_search_domains}"
+            ));
+        }
+        if let Some(dns_policy) = self
+            .allowed_dns_policies
+            .iter()
+            .chain(&self.fallback_dns_policy)
+            .find(|dns_policy| !DNS_POLICIES.contains(&dns_policy.as_str()))
+        {
+            return Err(format!(
+                "unknown DNS policy '{dns_policy}', the DNS policies are: {}",
+                DNS_POLICIES.join(", ")
+            ));
+        }
+        if let Some(fallback) = &self.fallback_dns_policy {
+            // the None policy requires the nameservers of the Pod
+            if fallback == "None" {
+                return Err("fallbackDnsPolicy cannot be None".to_owned());
+            }
+            if !self.is_dns_policy_allowed(fallback) {
+                return Err(format!(
+                    "fallbackDnsPolicy ({fallback}) must be one of the allowedDnsPolicies"
+                ));
+            }
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::range(r#"{"minNdots": 2, "maxNdots": 5}"#, true)]
+    #[case::single_value_range(r#"{"minNdots": 3, "maxNdots": 3}"#, true)]
+    #[case::lower_bound(r#"{"minNdots": 2}"#, true)]
+    #[case::inverted_range(r#"{"minNdots": 5, "maxNdots": 2}"#, false)]
+    #[case::dns_options(
+        r#"{"dnsOptions": {"timeout": 2, "attempts": "3", "single-request-reopen": null}}"#,
+        true
+    )]
+    #[case::ndots_dns_option(r#"{"dnsOptions": {"ndots": 2}}"#, false)]
+    #[case::unnamed_dns_option(r#"{"dnsOptions": {"": "2"}}"#, false)]
+    #[case::dns_policies(
+        r#"{"allowedDnsPolicies": ["ClusterFirst", "ClusterFirstWithHostNet"], "fallbackDnsPolicy": "ClusterFirst"}"#,
+        true
+    )]
+    #[case::unknown_dns_policy(r#"{"allowedDnsPolicies": ["clusterFirst"]}"#, false)]
+    #[case::fallback_not_allowed(
+        r#"{"allowedDnsPolicies": ["ClusterFirst"], "fallbackDnsPolicy": "Default"}"#,
+        false
+    )]
+    #[case::none_fallback(r#"{"fallbackDnsPolicy": "None"}"#, false)]
+    #[case::search_domains_and_nameservers(
+        r#"{"allowedSearchDomains": ["svc.cluster.local"], "allowedNameservers": ["10.0.0.10", "fd00::a"]}"#,
+        true
+    )]
+    #[case::empty_search_domain(r#"{"allowedSearchDomains": ["."]}"#, false)]
+    #[case::exemptions(
+        r#"{"exemptions": {"labelSelectors": [{"k8s-app": "kube-dns"}], "namespaces": ["kube-system"], "serviceAccountNames": ["coredns"]}}"#,
+        true
+    )]
+    #[case::empty_label_selector(r#"{"exemptions": {"labelSelectors": [{}]}}"#, false)]
+    #[case::max_search_domains(r#"{"maxSearchDomains": 6}"#, true)]
+    #[case::no_search_domains(r#"{"maxSearchDomains": 0}"#, false)]
+    #[case::too_many_search_domains(r#"{"maxSearchDomains": 33}"#, false)]
+    #[case::invalid_nameserver(r#"{"allowedNameservers": ["dns.example.com"]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings = serde_json::from_str::<Settings>(settings);
+        assert_eq!(
+            settings.is_ok_and(|settings| settings.validate().is_ok()),
+            is_valid
+        );
+    }
+
+    #[rstest]
+    #[case::no_range(r#"{"ndots": 3}"#, Some(7), 3)]
+    #[case::inside_range(r#"{"minNdots": 2, "maxNdots": 5}"#, Some(4), 4)]
+    #[case::below_range(r#"{"minNdots": 2, "maxNdots": 5}"#, Some(1), 2)]
+    #[case::above_range(r#"{"minNdots": 2, "maxNdots": 5}"#, Some(9), 5)]
+    #[case::unset_inside_range(r#"{"ndots": 3, "minNdots": 2}"#, None, 3)]
+    #[case::unset_outside_range(r#"{"minNdots": 2}"#, None, 2)]
+    fn target_ndots(
+        #[case] settings: &str,
+        #[case] current: Option<usize>,
+        #[case] expected: usize,
+    ) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.target_ndots(current), expected);
+    }
+}
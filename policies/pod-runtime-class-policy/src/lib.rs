@@ -1,15 +1,26 @@
 use guest::prelude::*;
-use k8s_openapi::api::core::v1::PodSpec;
 use kubewarden_policy_sdk::wapc_guest as guest;
+use serde::{Deserialize, Serialize};
 
 extern crate kubewarden_policy_sdk as kubewarden;
-use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+use kubewarden::{
+    protocol_version_guest,
+    request::{KubernetesAdmissionRequest, ValidationRequest},
+    validate_settings,
+};
 
-use kubewarden::{accept_request, mutate_pod_spec_from_request, reject_request};
+use kubewarden::{accept_request, mutate_request, reject_request};
 
 mod settings;
 use settings::Settings;
 
+/// The only field of the Pod spec read and changed by the policy
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    runtime_class_name: Option<String>,
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn wapc_init() {
     register_function("validate", validate);
@@ -17,22 +28,31 @@ pub extern "C" fn wapc_init() {
     register_function("protocol_version", protocol_version_guest);
 }
 
+fn mutate_runtime(request: &KubernetesAdmissionRequest, runtime: &str) -> CallResult {
+    let pod_spec = PodSpecFields {
+        runtime_class_name: Some(runtime.to_owned()),
+    };
+    mutate_request(partial_pod_spec::patch(
+        &request.kind.kind,
+        &request.object,
+        &pod_spec,
+    )?)
+}
+
 fn mutate_or_reject(
-    validation_request: ValidationRequest<Settings>,
-    pod_spec: &PodSpec,
+    validation_request: &ValidationRequest<Settings>,
     error_message: String,
 ) -> CallResult {
     if let Some(ref fallback_runtime) = validation_request.settings.fallback_runtime {
-        let mut new_pod_spec = pod_spec.clone();
-        new_pod_spec.runtime_class_name = Some(fallback_runtime.to_string());
-        return mutate_pod_spec_from_request(validation_request, new_pod_spec);
+        return mutate_runtime(&validation_request.request, fallback_runtime);
     }
     reject_request(Some(error_message), None, None, None)
 }
 
 fn validate(payload: &[u8]) -> CallResult {
     let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
-    let pod = validation_request.extract_pod_spec_from_object()?;
+    let request = &validation_request.request;
+    let pod = partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object)?;
     if let Some(pod_spec) = pod {
         if let Some(ref runtime_class_name) = pod_spec.runtime_class_name
             && validation_request
@@ -41,8 +61,7 @@ fn validate(payload: &[u8]) -> CallResult {
                 .contains(runtime_class_name)
         {
             return mutate_or_reject(
-                validation_request,
-                &pod_spec,
+                &validation_request,
                 format!("runtime '{runtime_class_name}' is reserved"),
             );
         }
@@ -53,17 +72,14 @@ fn validate(payload: &[u8]) -> CallResult {
             && default_runtime_reserved
         {
             return mutate_or_reject(
-                validation_request,
-                &pod_spec,
+                &validation_request,
                 "Usage of the default runtime is reserved".to_string(),
             );
         }
         // The default runtime is not reserved, but a fallback is specified. Therefore
         // the Pod is mutated to use the fallback runtime.
         if let Some(ref fallback_runtime) = validation_request.settings.fallback_runtime {
-            let mut new_pod_spec = pod_spec;
-            new_pod_spec.runtime_class_name = Some(fallback_runtime.clone());
-            return mutate_pod_spec_from_request(validation_request, new_pod_spec);
+            return mutate_runtime(request, fallback_runtime);
         }
     }
     accept_request()
@@ -74,7 +90,6 @@ mod tests {
     use super::*;
     use k8s_openapi::Resource;
     use k8s_openapi::api::core::v1::{Pod, PodSpec};
-    use kubewarden::request::{KubernetesAdmissionRequest, ValidationRequest};
     use kubewarden_policy_sdk::request::GroupVersionKind;
     use kubewarden_policy_sdk::response::ValidationResponse;
     use rstest::*;
@@ -0,0 +1,170 @@
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::PodSpec;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = &validation_request.request;
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        // The resources without Pod spec are not validated
+        Ok(None) => return kubewarden::accept_request(),
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    if let Err(error) = validate_pod_spec(&pod_spec, &validation_request.settings) {
+        return kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_pod_spec(pod_spec: &PodSpec, settings: &Settings) -> Result<(), String> {
+    let mut errors = vec![];
+    if let Some(maximum) = settings.max_pod_spec_bytes {
+        // The size is measured on the compact JSON serialization, the one
+        // stored by the API server is in the same order of magnitude
+        let size = serde_json::to_vec(pod_spec)
+            .map_err(|e| format!("cannot serialize the Pod spec: {e}"))?
+            .len();
+        if size > maximum {
+            errors.push(format!(
+                "the Pod spec is {size} bytes, above the maximum of {maximum}"
+            ));
+        }
+    }
+
+    for container in pod_spec
+        .init_containers
+        .iter()
+        .flatten()
+        .chain(&pod_spec.containers)
+    {
+        let env_vars = container.env.as_ref().map_or(0, Vec::len);
+        if let Some(maximum) = settings.max_env_vars
+            && env_vars > maximum
+        {
+            errors.push(format!(
+                "container '{}': {env_vars} environment variables, above the maximum of {maximum}",
+                container.name
+            ));
+        }
+        let volume_mounts = container.volume_mounts.as_ref().map_or(0, Vec::len);
+        if let Some(maximum) = settings.max_volume_mounts
+            && volume_mounts > maximum
+        {
+            errors.push(format!(
+                "container '{}': {volume_mounts} volume mounts, above the maximum of {maximum}",
+                container.name
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use k8s_openapi::api::core::v1::{Container, EnvVar};
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            max_pod_spec_bytes: Some(4096),
+            max_env_vars: Some(5),
+            max_volume_mounts: Some(2),
+        }
+    }
+
+    #[rstest]
+    #[case::within_limits("test_data/pod_within_limits.json", true)]
+    #[case::above_limits("test_data/deployment_above_limits.json", false)]
+    #[case::large_spec("test_data/pod_large_spec.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/deployment_above_limits.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Deployment 'web': container 'web': 8 environment variables, above the maximum of 5; container 'web': 3 volume mounts, above the maximum of 2"
+            )
+        );
+    }
+
+    #[rstest]
+    #[case::below_maximum(5, true)]
+    #[case::at_maximum(100, true)]
+    #[case::above_maximum(101, false)]
+    fn env_vars(#[case] count: usize, #[case] expected: bool) {
+        let pod_spec = PodSpec {
+            containers: vec![Container {
+                name: "web".to_owned(),
+                env: Some(
+                    (0..count)
+                        .map(|i| EnvVar {
+                            name: format!("VAR_{i}"),
+                            value: Some("value".to_owned()),
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let settings = Settings {
+            max_env_vars: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(validate_pod_spec(&pod_spec, &settings).is_ok(), expected);
+    }
+}
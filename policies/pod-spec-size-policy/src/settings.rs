@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Maximum size, in bytes, of the Pod spec serialized as JSON
+    pub max_pod_spec_bytes: Option<usize>,
+    /// Maximum number of environment variables of each container
+    pub max_env_vars: Option<usize>,
+    /// Maximum number of volume mounts of each container
+    pub max_volume_mounts: Option<usize>,
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.max_pod_spec_bytes.is_none()
+            && self.max_env_vars.is_none()
+            && self.max_volume_mounts.is_none()
+        {
+            return Err(
+                "at least one of maxPodSpecBytes, maxEnvVars and maxVolumeMounts must be set"
+                    .to_owned(),
+            );
+        }
+        if self.max_pod_spec_bytes == Some(0) {
+            return Err("maxPodSpecBytes must be greater than zero".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::all_settings(
+        r#"{"maxPodSpecBytes": 262144, "maxEnvVars": 100, "maxVolumeMounts": 32}"#,
+        true
+    )]
+    #[case::env_vars_only(r#"{"maxEnvVars": 0}"#, true)]
+    #[case::no_limits(r#"{}"#, false)]
+    #[case::zero_bytes(r#"{"maxPodSpecBytes": 0}"#, false)]
+    #[case::negative_limit(r#"{"maxEnvVars": -1}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings = serde_json::from_str::<Settings>(settings);
+        assert_eq!(
+            settings.is_ok_and(|settings| settings.validate().is_ok()),
+            is_valid
+        );
+    }
+}
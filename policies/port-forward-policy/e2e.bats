@@ -0,0 +1,50 @@
+#!/usr/bin/env bats
+
+@test "Reject a port-forward to a protected namespace" {
+	run kwctl run \
+		--request-path test_data/portforward_protected_namespace.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*user 'developer' is not allowed to port-forward to the Pod 'postgres-0' of the protected namespace 'databases'.*") -ne 0 ]
+}
+
+@test "Accept a port-forward by a break-glass group" {
+	run kwctl run \
+		--request-path test_data/portforward_break_glass.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a port-forward to another namespace" {
+	run kwctl run \
+		--request-path test_data/portforward_other_namespace.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/portforward_other_namespace.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
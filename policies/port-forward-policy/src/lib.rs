@@ -0,0 +1,94 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use user_allowlist::UserAllowlist;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    // The CONNECT requests on the portforward subresource carry the options of
+    // the port-forward, not the Pod
+    if request.kind.kind != "PodPortForwardOptions"
+        || !settings.protected_namespaces.contains(&request.namespace)
+    {
+        return kubewarden::accept_request();
+    }
+    let username = request.user_info.username.unwrap_or_default();
+    let groups = request.user_info.groups.unwrap_or_default();
+    if settings.is_user_allowed(&username, &groups) {
+        return kubewarden::accept_request();
+    }
+
+    kubewarden::reject_request(
+        Some(format!(
+            "user '{username}' is not allowed to port-forward to the Pod '{}' of the protected namespace '{}'",
+            request.name, request.namespace
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            protected_namespaces: ["databases".to_owned()].into(),
+            allowed_groups: ["system:masters".to_owned(), "dba-oncall".to_owned()].into(),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::protected_namespace("test_data/portforward_protected_namespace.json", false)]
+    #[case::break_glass_group("test_data/portforward_break_glass.json", true)]
+    #[case::other_namespace("test_data/portforward_other_namespace.json", true)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/portforward_protected_namespace.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "user 'developer' is not allowed to port-forward to the Pod 'postgres-0' of the protected namespace 'databases'"
+            )
+        );
+    }
+}
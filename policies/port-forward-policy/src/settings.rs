@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use user_allowlist::UserAllowlist;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Namespaces whose Pods cannot be port-forwarded to
+    pub protected_namespaces: HashSet<String>,
+    /// Break-glass users allowed to port-forward to the Pods of the protected
+    /// namespaces
+    pub allowed_users: HashSet<String>,
+    /// Break-glass groups of users allowed to port-forward to the Pods of the
+    /// protected namespaces
+    pub allowed_groups: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            protected_namespaces: HashSet::new(),
+            allowed_users: HashSet::new(),
+            allowed_groups: ["system:masters".to_owned()].into(),
+        }
+    }
+}
+
+impl UserAllowlist for Settings {
+    fn allowed_users(&self) -> &HashSet<String> {
+        &self.allowed_users
+    }
+
+    fn allowed_groups(&self) -> &HashSet<String> {
+        &self.allowed_groups
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.protected_namespaces.is_empty() {
+            return Err("protectedNamespaces cannot be empty".to_owned());
+        }
+        if self.protected_namespaces.contains("") {
+            return Err("protectedNamespaces cannot contain empty values".to_owned());
+        }
+        if self.allowed_users.contains("") {
+            return Err("allowedUsers cannot contain empty values".to_owned());
+        }
+        if self.allowed_groups.contains("") {
+            return Err("allowedGroups cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::developer("developer", vec!["system:authenticated"], false)]
+    #[case::cluster_admin("admin", vec!["system:masters", "system:authenticated"], true)]
+    #[case::break_glass_user("dba-oncall", vec!["system:authenticated"], true)]
+    fn allowed_users(#[case] username: &str, #[case] groups: Vec<&str>, #[case] expected: bool) {
+        let settings = Settings {
+            allowed_users: ["dba-oncall".to_owned()].into(),
+            ..Default::default()
+        };
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(settings.is_user_allowed(username, &groups), expected);
+    }
+
+    #[rstest]
+    #[case::protected_namespaces(r#"{"protectedNamespaces": ["databases"]}"#, true)]
+    #[case::break_glass_groups(
+        r#"{"protectedNamespaces": ["databases"], "allowedGroups": ["dba-oncall"]}"#,
+        true
+    )]
+    #[case::defaults(r#"{}"#, false)]
+    #[case::empty_protected_namespace(r#"{"protectedNamespaces": [""]}"#, false)]
+    #[case::empty_user(
+        r#"{"protectedNamespaces": ["databases"], "allowedUsers": [""]}"#,
+        false
+    )]
+    #[case::empty_group(
+        r#"{"protectedNamespaces": ["databases"], "allowedGroups": [""]}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
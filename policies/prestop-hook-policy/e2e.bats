@@ -0,0 +1,80 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod with a sleep preStop hook" {
+	run kwctl run \
+		--request-path test_data/pod_sleep_hook.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patch".*') -eq 0 ]
+}
+
+@test "Reject a Pod without preStop hook" {
+	run kwctl run \
+		--request-path test_data/pod_without_hook.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*the containers declaring ports must have a preStop hook.*') -ne 0 ]
+}
+
+@test "Reject a Pod sleeping too little" {
+	run kwctl run \
+		--request-path test_data/pod_short_sleep_hook.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*the preStop hook sleeps 2 seconds, below the minimum of 5.*') -ne 0 ]
+}
+
+@test "Reject a Deployment running a command not allowed" {
+	run kwctl run \
+		--request-path test_data/deployment_exec_hook.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the preStop command 'nginx -s quit' does not match any of the allowed commands.*") -ne 0 ]
+}
+
+@test "Mutate a Pod without preStop hook" {
+	run kwctl run \
+		--request-path test_data/pod_without_hook.json \
+		--settings-json '{"defaultSleepSeconds": 10}' \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_sleep_hook.json --settings-json '{"allowedCommands": ["sleep ("]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,241 @@
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::{Lifecycle, LifecycleHandler, SleepAction};
+use kubewarden_policy_sdk::wapc_guest as guest;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use workloads::is_sidecar;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+/// Containers of the Pod spec, the only fields read and changed by the policy
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    #[serde(default)]
+    containers: Vec<ContainerFields>,
+    init_containers: Option<Vec<ContainerFields>>,
+}
+
+/// Fields of a container used by the policy. The other ones are kept as they
+/// are in `other`, so that the mutation does not drop the fields unknown to
+/// k8s-openapi.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct ContainerFields {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ports: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    restart_policy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lifecycle: Option<Lifecycle>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    let request = &validation_request.request;
+    let mut pod_spec =
+        match partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object) {
+            Ok(Some(pod_spec)) => pod_spec,
+            // The resources without Pod spec are not validated
+            Ok(None) => return kubewarden::accept_request(),
+            Err(_) => {
+                return kubewarden::reject_request(
+                    Some("Cannot parse validation request".to_owned()),
+                    None,
+                    None,
+                    None,
+                );
+            }
+        };
+
+    let settings = &validation_request.settings;
+    let mut errors = vec![];
+    let mut mutated = false;
+    let containers = pod_spec.containers.iter_mut().chain(
+        pod_spec
+            .init_containers
+            .iter_mut()
+            .flatten()
+            .filter(|container| is_sidecar(container.restart_policy.as_deref())),
+    );
+    for container in containers.filter(|container| is_serving(container)) {
+        match validate_container(container, settings) {
+            Ok(container_mutated) => mutated |= container_mutated,
+            Err(error) => errors.push(format!("container '{}': {error}", container.name)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return kubewarden::reject_request(
+            Some(format!(
+                "{} '{}': {}",
+                request.kind.kind,
+                request.name,
+                errors.join("; ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    if mutated {
+        return kubewarden::mutate_request(partial_pod_spec::patch(
+            &request.kind.kind,
+            &request.object,
+            &pod_spec,
+        )?);
+    }
+    kubewarden::accept_request()
+}
+
+// Returns true for the containers receiving traffic, the ones declaring ports
+fn is_serving(container: &ContainerFields) -> bool {
+    container
+        .ports
+        .as_ref()
+        .is_some_and(|ports| !ports.is_empty())
+}
+
+// Validates the preStop hook of the container, adding the default one when
+// it is missing. Returns true when the container has been changed.
+fn validate_container(
+    container: &mut ContainerFields,
+    settings: &Settings,
+) -> Result<bool, String> {
+    let Some(pre_stop) = container
+        .lifecycle
+        .as_ref()
+        .and_then(|lifecycle| lifecycle.pre_stop.as_ref())
+    else {
+        let Some(seconds) = settings.default_sleep_seconds else {
+            return Err("the containers declaring ports must have a preStop hook".to_owned());
+        };
+        container.lifecycle = Some(Lifecycle {
+            pre_stop: Some(LifecycleHandler {
+                sleep: Some(SleepAction { seconds }),
+                ..Default::default()
+            }),
+            ..container.lifecycle.clone().unwrap_or_default()
+        });
+        return Ok(true);
+    };
+
+    if let Some(sleep) = &pre_stop.sleep
+        && let Some(minimum) = settings.minimum_sleep_seconds
+        && sleep.seconds < minimum
+    {
+        return Err(format!(
+            "the preStop hook sleeps {} seconds, below the minimum of {minimum}",
+            sleep.seconds
+        ));
+    }
+    if let Some(exec) = &pre_stop.exec {
+        let command = exec.command.clone().unwrap_or_default().join(" ");
+        if !settings.is_command_allowed(&command) {
+            return Err(format!(
+                "the preStop command '{command}' does not match any of the allowed commands"
+            ));
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn settings() -> Settings {
+        Settings {
+            minimum_sleep_seconds: Some(5),
+            allowed_commands: vec!["sleep \\d+".to_owned()],
+            default_sleep_seconds: None,
+        }
+    }
+
+    #[rstest]
+    #[case::sleep_hook("test_data/pod_sleep_hook.json", true)]
+    #[case::exec_hook("test_data/pod_exec_hook.json", true)]
+    #[case::without_ports("test_data/pod_without_ports.json", true)]
+    #[case::without_hook("test_data/pod_without_hook.json", false)]
+    #[case::short_sleep("test_data/pod_short_sleep_hook.json", false)]
+    #[case::exec_hook_not_allowed("test_data/deployment_exec_hook.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert!(response.mutated_object.is_none());
+    }
+
+    #[rstest]
+    #[case::without_hook(
+        "test_data/pod_without_hook.json",
+        "Pod 'web': container 'web': the containers declaring ports must have a preStop hook; container 'metrics': the containers declaring ports must have a preStop hook"
+    )]
+    #[case::short_sleep(
+        "test_data/pod_short_sleep_hook.json",
+        "Pod 'web': container 'web': the preStop hook sleeps 2 seconds, below the minimum of 5"
+    )]
+    #[case::exec_hook_not_allowed(
+        "test_data/deployment_exec_hook.json",
+        "Deployment 'web': container 'web': the preStop command 'nginx -s quit' does not match any of the allowed commands"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn mutate_pod_without_hook() {
+        let test_case = Testcase {
+            name: "mutate pod without hook".to_owned(),
+            fixture_file: "test_data/pod_without_hook.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings {
+                default_sleep_seconds: Some(10),
+                ..settings()
+            },
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response.mutated_object.expect("the pod should be mutated");
+        assert_eq!(
+            mutated_object["spec"]["containers"][0]["lifecycle"],
+            json!({"preStop": {"sleep": {"seconds": 10}}})
+        );
+        assert_eq!(
+            mutated_object["spec"]["initContainers"][0]["lifecycle"],
+            json!({"preStop": {"sleep": {"seconds": 10}}})
+        );
+    }
+}
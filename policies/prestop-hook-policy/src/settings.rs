@@ -0,0 +1,105 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Minimum number of seconds of the preStop hooks using the sleep action
+    pub minimum_sleep_seconds: Option<i64>,
+    /// Regular expressions the commands of the exec preStop hooks must match.
+    /// All the commands are allowed when empty.
+    pub allowed_commands: Vec<String>,
+    /// Seconds of the sleep preStop hook added to the containers without
+    /// preStop hook. The containers without it are rejected when not set.
+    pub default_sleep_seconds: Option<i64>,
+}
+
+impl Settings {
+    /// Returns true when the command matches one of the allowed patterns
+    pub fn is_command_allowed(&self, command: &str) -> bool {
+        self.allowed_commands.is_empty()
+            || self
+                .allowed_commands
+                .iter()
+                .any(|pattern| command_regex(pattern).is_ok_and(|regex| regex.is_match(command)))
+    }
+}
+
+/// Compiles the pattern, which must match the whole command
+fn command_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{pattern})$"))
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self
+            .minimum_sleep_seconds
+            .is_some_and(|seconds| seconds < 0)
+        {
+            return Err("minimumSleepSeconds cannot be negative".to_owned());
+        }
+        if let Some(seconds) = self.default_sleep_seconds {
+            if seconds < 1 {
+                return Err("defaultSleepSeconds must be greater than zero".to_owned());
+            }
+            if let Some(minimum) = self.minimum_sleep_seconds
+                && seconds < minimum
+            {
+                return Err(format!(
+                    "defaultSleepSeconds {seconds} cannot be below minimumSleepSeconds {minimum}"
+                ));
+            }
+        }
+        for pattern in &self.allowed_commands {
+            command_regex(pattern)
+                .map_err(|e| format!("allowedCommands: invalid pattern '{pattern}': {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{"minimumSleepSeconds": 5, "allowedCommands": ["sleep \\d+", "/bin/drain.*"], "defaultSleepSeconds": 10}"#,
+        true
+    )]
+    #[case::negative_minimum(r#"{"minimumSleepSeconds": -1}"#, false)]
+    #[case::zero_default(r#"{"defaultSleepSeconds": 0}"#, false)]
+    #[case::default_below_minimum(r#"{"minimumSleepSeconds": 5, "defaultSleepSeconds": 3}"#, false)]
+    #[case::invalid_pattern(r#"{"allowedCommands": ["sleep ("]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::no_patterns(vec![], "rm -rf /tmp/cache", true)]
+    #[case::matching(vec!["sleep \\d+".to_owned()], "sleep 5", true)]
+    #[case::partial_match(vec!["sleep \\d+".to_owned()], "sleep 5; kill 1", false)]
+    #[case::second_pattern(
+        vec!["sleep \\d+".to_owned(), "nginx -s quit".to_owned()],
+        "nginx -s quit",
+        true
+    )]
+    fn command_allowed(
+        #[case] allowed_commands: Vec<String>,
+        #[case] command: &str,
+        #[case] expected: bool,
+    ) {
+        let settings = Settings {
+            allowed_commands,
+            ..Default::default()
+        };
+        assert_eq!(settings.is_command_allowed(command), expected);
+    }
+}
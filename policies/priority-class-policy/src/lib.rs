@@ -1,14 +1,22 @@
 use guest::prelude::*;
-use k8s_openapi::api::core::v1 as apicore;
 use kubewarden_policy_sdk::wapc_guest as guest;
 use lazy_static::lazy_static;
 extern crate kubewarden_policy_sdk as kubewarden;
 use kubewarden::{logging, protocol_version_guest, request::ValidationRequest, validate_settings};
+use serde::{Deserialize, Serialize};
 use slog::{Logger, error, o, warn};
 
 use settings::Settings;
 mod settings;
 
+/// Fields of the Pod spec checked by the policy. The other ones are neither
+/// parsed nor changed.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    priority_class_name: Option<String>,
+}
+
 #[derive(PartialEq, Debug)]
 enum PodSpecMutationState {
     Mutated,
@@ -31,13 +39,14 @@ pub extern "C" fn wapc_init() {
 
 fn validate(payload: &[u8]) -> CallResult {
     let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
-    match validation_request.extract_pod_spec_from_object() {
+    let request = &validation_request.request;
+    match partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object) {
         Ok(Some(mut pod_spec)) => {
             match validate_pod_priority_class(&mut pod_spec, &validation_request.settings) {
                 Ok(PodSpecMutationState::NotMutated) => kubewarden::accept_request(),
-                Ok(PodSpecMutationState::Mutated) => {
-                    kubewarden::mutate_pod_spec_from_request(validation_request, pod_spec)
-                }
+                Ok(PodSpecMutationState::Mutated) => kubewarden::mutate_request(
+                    partial_pod_spec::patch(&request.kind.kind, &request.object, &pod_spec)?,
+                ),
                 Err(err) => kubewarden::reject_request(Some(err.to_owned()), None, None, None),
             }
         }
@@ -60,7 +69,7 @@ fn validate(payload: &[u8]) -> CallResult {
 }
 
 fn validate_pod_priority_class(
-    pod: &mut apicore::PodSpec,
+    pod: &mut PodSpecFields,
     settings: &Settings,
 ) -> Result<PodSpecMutationState, String> {
     if pod.priority_class_name.is_none() && settings.default_priority_class.is_none() {
@@ -102,8 +111,6 @@ mod tests {
     use super::*;
     use rstest::rstest;
 
-    use k8s_openapi::api::core::v1::PodSpec;
-
     #[rstest]
     #[case::pod_priority_allowed(Some("low-priority"), &["high-priority", "low-priority"], &[],None, true)]
     #[case::pod_priority_not_allowed(Some("no-priority"), &["high-priority", "low-priority"],&[], None, false)]
@@ -122,9 +129,8 @@ mod tests {
         #[case] default_priority_class: Option<&str>,
         #[case] should_succeed: bool,
     ) {
-        let mut pod = PodSpec {
+        let mut pod = PodSpecFields {
             priority_class_name: pod_priority_class.map(|s| s.to_owned()).clone(),
-            ..Default::default()
         };
         let settings = Settings {
             allowed_priority_classes: allowed_classes.iter().map(|s| s.to_string()).collect(),
@@ -147,9 +153,8 @@ mod tests {
         #[case] denied_classes: &[&str],
         #[case] default_priority_class: Option<&str>,
     ) {
-        let mut pod = PodSpec {
+        let mut pod = PodSpecFields {
             priority_class_name: None,
-            ..Default::default()
         };
         let expected_default_priority_class = default_priority_class.map(|s| s.to_owned()).clone();
         let settings = Settings {
@@ -0,0 +1,52 @@
+#!/usr/bin/env bats
+
+@test "Reject a ClusterRoleBinding granting cluster-admin" {
+	run kwctl run --request-path test_data/clusterrolebinding_cluster_admin.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*ClusterRoleBinding 'argocd-cluster-admin' cannot bind the protected ClusterRole 'cluster-admin'.*") -ne 0 ]
+}
+
+@test "Warn about a ClusterRoleBinding granting cluster-admin in warn mode" {
+	run kwctl run --request-path test_data/clusterrolebinding_cluster_admin.json --settings-json '{"mode": "warn"}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"warnings":\[.*cannot bind the protected ClusterRole.*') -ne 0 ]
+}
+
+@test "Accept a ClusterRoleBinding granting cluster-admin to an allowed subject by an allowed user" {
+	run kwctl run --request-path test_data/clusterrolebinding_cluster_admin.json --settings-json '{"allowedGroups": ["platform-admins"], "allowedSubjects": [{"kind": "ServiceAccount", "name": "argocd-application-controller", "namespace": "argocd"}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a RoleBinding granting a role that is not protected" {
+	run kwctl run --request-path test_data/rolebinding_view.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject settings with an invalid subject kind" {
+	run kwctl run --request-path test_data/rolebinding_view.json --settings-json '{"allowedSubjects": [{"kind": "Pod", "name": "foo"}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,233 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::rbac::v1::{ClusterRoleBinding, RoleBinding, RoleRef, Subject};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let (kind, name, role_ref, subjects) = match request.kind.kind.as_str() {
+        "RoleBinding" => {
+            let binding = serde_json::from_value::<RoleBinding>(request.object)?;
+            (
+                "RoleBinding",
+                binding.metadata.name,
+                binding.role_ref,
+                binding.subjects,
+            )
+        }
+        "ClusterRoleBinding" => {
+            let binding = serde_json::from_value::<ClusterRoleBinding>(request.object)?;
+            (
+                "ClusterRoleBinding",
+                binding.metadata.name,
+                binding.role_ref,
+                binding.subjects,
+            )
+        }
+        _ => return kubewarden::accept_request(),
+    };
+
+    let errors = find_violations(
+        settings,
+        &role_ref,
+        &subjects.unwrap_or_default(),
+        request.user_info.username.as_deref(),
+        &request.user_info.groups.unwrap_or_default(),
+    );
+    if errors.is_empty() {
+        return kubewarden::accept_request();
+    }
+
+    let message = format!(
+        "{kind} '{}' cannot bind the protected {} '{}': {}",
+        name.unwrap_or_default(),
+        role_ref.kind,
+        role_ref.name,
+        errors.join("; ")
+    );
+    settings.mode.respond(message)
+}
+
+// Returns the reasons why the binding of the role is not allowed
+fn find_violations(
+    settings: &Settings,
+    role_ref: &RoleRef,
+    subjects: &[Subject],
+    username: Option<&str>,
+    groups: &[String],
+) -> Vec<String> {
+    if !settings.protected_roles.contains(&role_ref.name) {
+        return vec![];
+    }
+
+    let mut errors = vec![];
+    if !settings.is_user_allowed(username, groups) {
+        errors.push(format!(
+            "user '{}' is not allowed to bind it",
+            username.unwrap_or_default()
+        ));
+    }
+
+    let denied_subjects: Vec<String> = subjects
+        .iter()
+        .filter(|subject| !settings.is_subject_allowed(subject))
+        .map(|subject| match &subject.namespace {
+            Some(namespace) if subject.kind == "ServiceAccount" => {
+                format!("{} '{namespace}/{}'", subject.kind, subject.name)
+            }
+            _ => format!("{} '{}'", subject.kind, subject.name),
+        })
+        .collect();
+    if !denied_subjects.is_empty() {
+        errors.push(format!(
+            "subjects not allowed: {}",
+            denied_subjects.join(", ")
+        ));
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use enforcement_mode::Mode;
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use settings::AllowedSubject;
+
+    fn role_ref(name: &str) -> RoleRef {
+        RoleRef {
+            api_group: "rbac.authorization.k8s.io".to_owned(),
+            kind: "ClusterRole".to_owned(),
+            name: name.to_owned(),
+        }
+    }
+
+    fn subject(kind: &str, name: &str, namespace: Option<&str>) -> Subject {
+        Subject {
+            kind: kind.to_owned(),
+            name: name.to_owned(),
+            namespace: namespace.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            allowed_subjects: vec![AllowedSubject {
+                kind: "Group".to_owned(),
+                name: "platform-admins".to_owned(),
+                namespace: None,
+            }],
+            allowed_users: ["alice".to_owned()].into(),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::not_protected(
+        role_ref("view"),
+        vec![subject("Group", "developers", None)],
+        Some("bob"),
+        vec![]
+    )]
+    #[case::allowed(
+        role_ref("cluster-admin"),
+        vec![subject("Group", "platform-admins", None)],
+        Some("alice"),
+        vec![]
+    )]
+    #[case::user_not_allowed(
+        role_ref("cluster-admin"),
+        vec![subject("Group", "platform-admins", None)],
+        Some("bob"),
+        vec!["user 'bob' is not allowed to bind it"]
+    )]
+    #[case::subjects_not_allowed(
+        role_ref("cluster-admin"),
+        vec![
+            subject("Group", "platform-admins", None),
+            subject("User", "bob", None),
+            subject("ServiceAccount", "default", Some("team-a")),
+        ],
+        Some("alice"),
+        vec!["subjects not allowed: User 'bob', ServiceAccount 'team-a/default'"]
+    )]
+    #[case::nothing_allowed(
+        role_ref("cluster-admin"),
+        vec![subject("User", "bob", None)],
+        Some("bob"),
+        vec!["user 'bob' is not allowed to bind it", "subjects not allowed: User 'bob'"]
+    )]
+    fn violations(
+        #[case] role_ref: RoleRef,
+        #[case] subjects: Vec<Subject>,
+        #[case] username: Option<&str>,
+        #[case] expected: Vec<&str>,
+    ) {
+        assert_eq!(
+            find_violations(&settings(), &role_ref, &subjects, username, &[]),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::cluster_admin(
+        "test_data/clusterrolebinding_cluster_admin.json",
+        Settings::default(),
+        false
+    )]
+    #[case::cluster_admin_warn(
+        "test_data/clusterrolebinding_cluster_admin.json",
+        Settings {
+            mode: Mode::Warn,
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::cluster_admin_allowed(
+        "test_data/clusterrolebinding_cluster_admin.json",
+        Settings {
+            allowed_groups: ["platform-admins".to_owned()].into(),
+            allowed_subjects: vec![AllowedSubject {
+                kind: "ServiceAccount".to_owned(),
+                name: "argocd-application-controller".to_owned(),
+                namespace: Some("argocd".to_owned()),
+            }],
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::role_binding_view("test_data/rolebinding_view.json", Settings::default(), true)]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
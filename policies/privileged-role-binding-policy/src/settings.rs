@@ -0,0 +1,186 @@
+use std::collections::HashSet;
+
+use enforcement_mode::Mode;
+use k8s_openapi::api::rbac::v1::Subject;
+use serde::{Deserialize, Serialize};
+
+/// Subject that can be bound to the protected roles
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AllowedSubject {
+    /// Kind of the subject: `User`, `Group` or `ServiceAccount`
+    pub kind: String,
+    pub name: String,
+    /// Namespace of the ServiceAccount, ignored by the other kinds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+impl AllowedSubject {
+    fn matches(&self, subject: &Subject) -> bool {
+        if self.kind != subject.kind || self.name != subject.name {
+            return false;
+        }
+        self.kind != "ServiceAccount" || self.namespace == subject.namespace
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Roles and ClusterRoles that can be bound only by the allowed users
+    /// to the allowed subjects
+    pub protected_roles: HashSet<String>,
+    /// Subjects that can be bound to the protected roles
+    pub allowed_subjects: Vec<AllowedSubject>,
+    /// Users allowed to bind the protected roles
+    pub allowed_users: HashSet<String>,
+    /// Groups allowed to bind the protected roles
+    pub allowed_groups: HashSet<String>,
+    /// What to do when a binding grants a protected role without being allowed
+    pub mode: Mode,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            protected_roles: ["cluster-admin".to_owned()].into(),
+            allowed_subjects: Vec::new(),
+            allowed_users: HashSet::new(),
+            allowed_groups: HashSet::new(),
+            mode: Mode::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the subject can be bound to the protected roles
+    pub fn is_subject_allowed(&self, subject: &Subject) -> bool {
+        self.allowed_subjects
+            .iter()
+            .any(|allowed_subject| allowed_subject.matches(subject))
+    }
+
+    /// Returns true when the user, or one of its groups, can bind the
+    /// protected roles
+    pub fn is_user_allowed(&self, username: Option<&str>, groups: &[String]) -> bool {
+        username.is_some_and(|username| self.allowed_users.contains(username))
+            || groups
+                .iter()
+                .any(|group| self.allowed_groups.contains(group))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.protected_roles.is_empty() {
+            return Err("protectedRoles cannot be empty".to_owned());
+        }
+        if self.protected_roles.contains("") {
+            return Err("protectedRoles cannot contain empty values".to_owned());
+        }
+        for subject in &self.allowed_subjects {
+            if !["User", "Group", "ServiceAccount"].contains(&subject.kind.as_str()) {
+                return Err(format!(
+                    "allowedSubjects: invalid kind '{}', must be one of User, Group, ServiceAccount",
+                    subject.kind
+                ));
+            }
+            if subject.name.is_empty() {
+                return Err("allowedSubjects cannot contain subjects without a name".to_owned());
+            }
+            if subject.kind == "ServiceAccount"
+                && subject.namespace.as_deref().unwrap_or_default().is_empty()
+            {
+                return Err(format!(
+                    "allowedSubjects: ServiceAccount '{}' must have a namespace",
+                    subject.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    fn subject(kind: &str, name: &str, namespace: Option<&str>) -> Subject {
+        Subject {
+            kind: kind.to_owned(),
+            name: name.to_owned(),
+            namespace: namespace.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    fn settings_with_subjects() -> Settings {
+        serde_json::from_str(
+            r#"{
+                "allowedSubjects": [
+                    {"kind": "Group", "name": "platform-admins"},
+                    {"kind": "ServiceAccount", "name": "argocd", "namespace": "argocd"}
+                ]
+            }"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    #[rstest]
+    #[case::allowed_group(subject("Group", "platform-admins", None), true)]
+    #[case::other_kind(subject("User", "platform-admins", None), false)]
+    #[case::allowed_service_account(subject("ServiceAccount", "argocd", Some("argocd")), true)]
+    #[case::other_namespace(subject("ServiceAccount", "argocd", Some("default")), false)]
+    fn allowed_subjects(#[case] subject: Subject, #[case] expected: bool) {
+        assert_eq!(
+            settings_with_subjects().is_subject_allowed(&subject),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::allowed_user(Some("alice"), vec![], true)]
+    #[case::allowed_group(Some("bob"), vec!["system:authenticated", "platform-admins"], true)]
+    #[case::not_allowed(Some("bob"), vec!["system:authenticated"], false)]
+    #[case::anonymous(None, vec![], false)]
+    fn allowed_users(
+        #[case] username: Option<&str>,
+        #[case] groups: Vec<&str>,
+        #[case] expected: bool,
+    ) {
+        let settings = Settings {
+            allowed_users: ["alice".to_owned()].into(),
+            allowed_groups: ["platform-admins".to_owned()].into(),
+            ..Default::default()
+        };
+        let groups: Vec<String> = groups.into_iter().map(String::from).collect();
+        assert_eq!(settings.is_user_allowed(username, &groups), expected);
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::warn(r#"{"mode": "warn"}"#, true)]
+    #[case::subjects(r#"{"allowedSubjects": [{"kind": "User", "name": "alice"}]}"#, true)]
+    #[case::no_protected_roles(r#"{"protectedRoles": []}"#, false)]
+    #[case::empty_protected_role(r#"{"protectedRoles": [""]}"#, false)]
+    #[case::invalid_kind(r#"{"allowedSubjects": [{"kind": "Pod", "name": "alice"}]}"#, false)]
+    #[case::service_account_without_namespace(
+        r#"{"allowedSubjects": [{"kind": "ServiceAccount", "name": "argocd"}]}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn unknown_mode() {
+        assert!(serde_json::from_str::<Settings>(r#"{"mode": "audit"}"#).is_err());
+    }
+}
@@ -28,3 +28,26 @@
 	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
 	[ $(expr "$output" : '.*periodSeconds validation failed: 30 is above the limit of 10.*') -ne 0 ]
 }
+
+@test "Reject exec probe when forbidden" {
+	run kwctl run  --request-path test_data/pod_creation_exec_probe.json --settings-json '{"liveness": {"enforce": true}, "readiness": {"enforce": true}, "forbiddenHandlers": ["exec"]}' policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*liveness probe uses the forbidden exec handler.*') -ne 0 ]
+}
+
+@test "Accept Job pod without probes when exempted" {
+	run kwctl run  --request-path test_data/pod_creation_job.json --settings-json '{"liveness": {"enforce": true}, "readiness": {"enforce": true}, "exemptJobPods": true}' policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject liveness probe checking the readiness endpoint" {
+	run kwctl run  --request-path test_data/pod_creation_same_endpoint.json --settings-json '{"liveness": {"enforce": true}, "readiness": {"enforce": true}, "requireDistinctEndpoints": true}' policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*liveness and readiness probes cannot check the same endpoint.*') -ne 0 ]
+}
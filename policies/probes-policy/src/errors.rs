@@ -9,8 +9,10 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum SettingsValidationError {
-    #[error("at least one of liveness or readiness probe enforcement must be enabled")]
+    #[error("at least one of liveness, readiness or startup probe enforcement must be enabled")]
     NoneEnforcement,
+    #[error("all the probe handlers cannot be forbidden")]
+    AllHandlersForbidden,
     #[error("at least one of minimum or limit must be set")]
     MissingMinimumAndLimit,
     #[error("minimum cannot be greater than limit")]
@@ -33,6 +35,8 @@ pub enum SettingsError {
     InvalidLivenessSettings(#[source] ProbeSettingError),
     #[error("invalid readiness probe settings: {0}")]
     InvalidReadinessSettings(#[source] ProbeSettingError),
+    #[error("invalid startup probe settings: {0}")]
+    InvalidStartupSettings(#[source] ProbeSettingError),
 }
 
 #[derive(Error, Debug, PartialEq, Eq)]
@@ -53,6 +57,12 @@ pub enum ProbeError {
     MissingLivenessProbe(String),
     #[error("container {0} without readiness probe is not accepted")]
     MissingReadinessProbe(String),
+    #[error("container {0} without startup probe is not accepted")]
+    MissingStartupProbe(String),
+    #[error("{0} probe uses the forbidden {1} handler")]
+    ForbiddenHandler(String, String),
+    #[error("liveness and readiness probes cannot check the same endpoint")]
+    SameEndpoint,
 }
 
 #[derive(Error, Debug, PartialEq, Eq)]
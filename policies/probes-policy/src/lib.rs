@@ -5,13 +5,19 @@ use guest::prelude::*;
 use kubewarden_policy_sdk::wapc_guest as guest;
 
 use k8s_openapi::api::core::v1 as apicore;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use workloads::is_sidecar;
 
 extern crate kubewarden_policy_sdk as kubewarden;
-use kubewarden::{logging, protocol_version_guest, request::ValidationRequest, validate_settings};
+use kubewarden::{
+    logging, protocol_version_guest,
+    request::{KubernetesAdmissionRequest, ValidationRequest},
+    validate_settings,
+};
 
 mod errors;
 mod settings;
-use settings::Settings;
+use settings::{ProbeHandler, Settings};
 
 use slog::{Logger, info, o, warn};
 
@@ -105,6 +111,38 @@ fn validate_probe(
     Ok(())
 }
 
+// Validate the action performed by the probe.
+fn validate_probe_handler(
+    probe_name: &str,
+    probe: &apicore::Probe,
+    forbidden_handlers: &[ProbeHandler],
+) -> Result<(), ProbeError> {
+    if let Some(handler) = forbidden_handlers
+        .iter()
+        .find(|handler| handler.is_used_by(probe))
+    {
+        return Err(ProbeError::ForbiddenHandler(
+            probe_name.to_owned(),
+            handler.to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// Returns true when both the probes perform the same action. A liveness probe
+// checking the readiness endpoint fails when the container is overloaded:
+// the container is then restarted instead of being removed from the load
+// balancers, increasing the load of the other replicas.
+fn is_same_endpoint(liveness: &apicore::Probe, readiness: &apicore::Probe) -> bool {
+    ProbeHandler::ALL
+        .iter()
+        .any(|handler| handler.is_used_by(liveness) && handler.is_used_by(readiness))
+        && liveness.exec == readiness.exec
+        && liveness.http_get == readiness.http_get
+        && liveness.tcp_socket == readiness.tcp_socket
+        && liveness.grpc == readiness.grpc
+}
+
 fn validate_container(
     container: &apicore::Container,
     settings: &Settings,
@@ -134,6 +172,37 @@ fn validate_container(
         );
         return Err(ProbeError::MissingReadinessProbe(container.name.clone()));
     }
+
+    if let Some(startup_probe) = &container.startup_probe {
+        if settings.startup.enforce {
+            validate_probe(startup_probe, &settings.startup)?;
+        }
+    } else if settings.startup.enforce {
+        info!(
+            LOG_DRAIN,
+            "rejecting pod due to missing startup probe";
+            "container_name" => &container.name
+        );
+        return Err(ProbeError::MissingStartupProbe(container.name.clone()));
+    }
+
+    for (probe_name, probe) in [
+        ("liveness", &container.liveness_probe),
+        ("readiness", &container.readiness_probe),
+        ("startup", &container.startup_probe),
+    ] {
+        if let Some(probe) = probe {
+            validate_probe_handler(probe_name, probe, &settings.forbidden_handlers)?;
+        }
+    }
+
+    if settings.require_distinct_endpoints
+        && let (Some(liveness_probe), Some(readiness_probe)) =
+            (&container.liveness_probe, &container.readiness_probe)
+        && is_same_endpoint(liveness_probe, readiness_probe)
+    {
+        return Err(ProbeError::SameEndpoint);
+    }
     Ok(())
 }
 
@@ -157,9 +226,34 @@ fn validate_ephemeral_container(
         );
         return Err(ProbeError::MissingReadinessProbe(container.name.clone()));
     }
+    if container.startup_probe.is_none() && settings.startup.enforce {
+        info!(
+            LOG_DRAIN,
+            "rejecting pod";
+            "container_name" => &container.name
+        );
+        return Err(ProbeError::MissingStartupProbe(container.name.clone()));
+    }
     Ok(())
 }
 
+// Returns true for the Jobs, the CronJobs and the Pods created by the Jobs.
+// Their containers are expected to exit: the probes are seldom useful.
+fn is_job_workload(request: &KubernetesAdmissionRequest) -> bool {
+    match (request.kind.group.as_str(), request.kind.kind.as_str()) {
+        ("batch", "Job") | ("batch", "CronJob") => true,
+        ("", "Pod") => request
+            .object
+            .get("metadata")
+            .and_then(|metadata| serde_json::from_value::<ObjectMeta>(metadata.clone()).ok())
+            .and_then(|metadata| metadata.owner_references)
+            .unwrap_or_default()
+            .iter()
+            .any(|owner| owner.kind == "Job" && owner.api_version.starts_with("batch/")),
+        _ => false,
+    }
+}
+
 fn validate_pod(pod: &apicore::PodSpec, settings: &Settings) -> Result<()> {
     let mut err_message = String::new();
     for container in &pod.containers {
@@ -171,6 +265,11 @@ fn validate_pod(pod: &apicore::PodSpec, settings: &Settings) -> Result<()> {
     }
     if let Some(init_containers) = &pod.init_containers {
         for container in init_containers {
+            // The sidecar containers run along the main containers: their
+            // probes matter
+            if settings.exempt_init_containers && !is_sidecar(container.restart_policy.as_deref()) {
+                continue;
+            }
             let container_valid = validate_container(container, settings)
                 .map_err(|e| ContainerError::InitContainer(container.name.clone(), e));
             if let Err(e) = container_valid {
@@ -197,6 +296,9 @@ fn validate(payload: &[u8]) -> CallResult {
     let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
 
     info!(LOG_DRAIN, "starting validation");
+    if validation_request.settings.exempt_job_pods && is_job_workload(&validation_request.request) {
+        return kubewarden::accept_request();
+    }
     match validation_request.extract_pod_spec_from_object() {
         Ok(pod_spec) => {
             if let Some(pod_spec) = pod_spec {
@@ -229,6 +331,7 @@ mod tests {
     use super::*;
     use rstest::rstest;
 
+    use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
     use kubewarden_policy_sdk::test::Testcase;
 
     #[rstest]
@@ -668,4 +771,175 @@ mod tests {
 
         Ok(())
     }
+
+    #[rstest]
+    #[case::startup_not_enforced("test_data/pod_creation.json", Settings::default(), true)]
+    #[case::startup_enforced(
+        "test_data/pod_creation.json",
+        Settings {
+            startup: settings::ProbeConfiguration::default(),
+            ..Default::default()
+        },
+        false
+    )]
+    #[case::exec_allowed("test_data/pod_creation_exec_probe.json", Settings::default(), true)]
+    #[case::exec_forbidden(
+        "test_data/pod_creation_exec_probe.json",
+        Settings {
+            forbidden_handlers: vec![ProbeHandler::Exec],
+            ..Default::default()
+        },
+        false
+    )]
+    #[case::http_get_allowed(
+        "test_data/pod_creation.json",
+        Settings {
+            forbidden_handlers: vec![ProbeHandler::Exec],
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::init_container_exempted(
+        "test_data/pod_creation_invalid_liveness_init_container.json",
+        Settings {
+            exempt_init_containers: true,
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::sidecar_not_exempted(
+        "test_data/pod_creation_sidecar_without_probes.json",
+        Settings {
+            exempt_init_containers: true,
+            ..Default::default()
+        },
+        false
+    )]
+    #[case::job_pod("test_data/pod_creation_job.json", Settings::default(), false)]
+    #[case::job_pod_exempted(
+        "test_data/pod_creation_job.json",
+        Settings {
+            exempt_job_pods: true,
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::other_pod_not_exempted(
+        "test_data/pod_creation_invalid_liveness.json",
+        Settings {
+            exempt_job_pods: true,
+            ..Default::default()
+        },
+        false
+    )]
+    fn validate_with_settings(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let tc = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        tc.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn forbidden_handler_message() {
+        let tc = Testcase {
+            name: String::from("Forbidden exec handler"),
+            fixture_file: String::from("test_data/pod_creation_exec_probe.json"),
+            expected_validation_result: false,
+            settings: Settings {
+                forbidden_handlers: vec![ProbeHandler::Exec],
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        assert_eq!(
+            res.message.as_deref(),
+            Some("container nginx is invalid: liveness probe uses the forbidden exec handler")
+        );
+    }
+
+    fn http_get_probe(path: &str, port: i32) -> apicore::Probe {
+        apicore::Probe {
+            http_get: Some(apicore::HTTPGetAction {
+                path: Some(path.to_owned()),
+                port: IntOrString::Int(port),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::same_http_get(http_get_probe("/ready", 8080), http_get_probe("/ready", 8080), true)]
+    #[case::same_http_get_other_thresholds(
+        apicore::Probe {
+            failure_threshold: Some(5),
+            ..http_get_probe("/ready", 8080)
+        },
+        http_get_probe("/ready", 8080),
+        true
+    )]
+    #[case::other_path(
+        http_get_probe("/healthz", 8080),
+        http_get_probe("/ready", 8080),
+        false
+    )]
+    #[case::other_port(http_get_probe("/ready", 8081), http_get_probe("/ready", 8080), false)]
+    #[case::other_handler(
+        apicore::Probe {
+            tcp_socket: Some(apicore::TCPSocketAction {
+                port: IntOrString::Int(8080),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        http_get_probe("/ready", 8080),
+        false
+    )]
+    #[case::no_handler(apicore::Probe::default(), apicore::Probe::default(), false)]
+    fn same_endpoint(
+        #[case] liveness: apicore::Probe,
+        #[case] readiness: apicore::Probe,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(is_same_endpoint(&liveness, &readiness), expected);
+    }
+
+    #[rstest]
+    #[case::distinct_endpoints("test_data/pod_creation.json", true, true)]
+    #[case::same_endpoint("test_data/pod_creation_same_endpoint.json", true, false)]
+    #[case::same_endpoint_allowed("test_data/pod_creation_same_endpoint.json", false, true)]
+    fn validate_distinct_endpoints(
+        #[case] fixture: &str,
+        #[case] require_distinct_endpoints: bool,
+        #[case] expected_validation_result: bool,
+    ) {
+        let tc = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: Settings {
+                require_distinct_endpoints,
+                ..Default::default()
+            },
+        };
+
+        let res = tc.eval(validate).unwrap();
+        if !expected_validation_result {
+            assert_eq!(
+                res.message.as_deref(),
+                Some(
+                    "container nginx is invalid: liveness and readiness probes cannot check the same endpoint"
+                )
+            );
+        }
+    }
 }
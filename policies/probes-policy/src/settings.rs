@@ -1,13 +1,87 @@
+use std::fmt;
+
+use k8s_openapi::api::core::v1 as apicore;
 use serde::{Deserialize, Serialize};
 
 use crate::errors::{ProbeSettingError, SettingsError, SettingsValidationError};
 
 // Describe the settings your policy expects when
 // loaded by the policy server.
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct Settings {
     pub liveness: ProbeConfiguration,
     pub readiness: ProbeConfiguration,
+    #[serde(default = "ProbeConfiguration::disabled")]
+    pub startup: ProbeConfiguration,
+    /// Handlers the probes cannot use
+    #[serde(default)]
+    pub forbidden_handlers: Vec<ProbeHandler>,
+    /// Reject the containers whose liveness and readiness probes check the
+    /// same endpoint
+    #[serde(default)]
+    pub require_distinct_endpoints: bool,
+    /// Do not check the init containers, but the sidecar ones
+    #[serde(default)]
+    pub exempt_init_containers: bool,
+    /// Do not check the Pods of the Jobs, and the Jobs and the CronJobs
+    #[serde(default)]
+    pub exempt_job_pods: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            liveness: ProbeConfiguration::default(),
+            readiness: ProbeConfiguration::default(),
+            startup: ProbeConfiguration::disabled(),
+            forbidden_handlers: vec![],
+            require_distinct_endpoints: false,
+            exempt_init_containers: false,
+            exempt_job_pods: false,
+        }
+    }
+}
+
+/// Action performed by a probe to check the container
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ProbeHandler {
+    Exec,
+    HttpGet,
+    TcpSocket,
+    Grpc,
+}
+
+impl ProbeHandler {
+    pub const ALL: [ProbeHandler; 4] = [
+        ProbeHandler::Exec,
+        ProbeHandler::HttpGet,
+        ProbeHandler::TcpSocket,
+        ProbeHandler::Grpc,
+    ];
+
+    /// Returns true when the probe uses the handler
+    pub fn is_used_by(&self, probe: &apicore::Probe) -> bool {
+        match self {
+            ProbeHandler::Exec => probe.exec.is_some(),
+            ProbeHandler::HttpGet => probe.http_get.is_some(),
+            ProbeHandler::TcpSocket => probe.tcp_socket.is_some(),
+            ProbeHandler::Grpc => probe.grpc.is_some(),
+        }
+    }
+}
+
+impl fmt::Display for ProbeHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ProbeHandler::Exec => "exec",
+            ProbeHandler::HttpGet => "httpGet",
+            ProbeHandler::TcpSocket => "tcpSocket",
+            ProbeHandler::Grpc => "grpc",
+        };
+        write!(f, "{name}")
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -48,17 +122,37 @@ impl Default for ProbeConfiguration {
     }
 }
 
+impl ProbeConfiguration {
+    // The startup probes are required only by the slow starting containers:
+    // they are not enforced by default
+    fn disabled() -> Self {
+        ProbeConfiguration {
+            enforce: false,
+            ..Default::default()
+        }
+    }
+}
+
 impl kubewarden::settings::Validatable for Settings {
     fn validate(&self) -> Result<(), String> {
-        if !self.liveness.enforce && !self.readiness.enforce {
+        if !self.liveness.enforce && !self.readiness.enforce && !self.startup.enforce {
             return Err(SettingsValidationError::NoneEnforcement.to_string());
         }
+        if ProbeHandler::ALL
+            .iter()
+            .all(|handler| self.forbidden_handlers.contains(handler))
+        {
+            return Err(SettingsValidationError::AllHandlersForbidden.to_string());
+        }
         self.liveness
             .validate()
             .map_err(|e| SettingsError::InvalidLivenessSettings(e).to_string())?;
         self.readiness
             .validate()
-            .map_err(|e| SettingsError::InvalidReadinessSettings(e).to_string())
+            .map_err(|e| SettingsError::InvalidReadinessSettings(e).to_string())?;
+        self.startup
+            .validate()
+            .map_err(|e| SettingsError::InvalidStartupSettings(e).to_string())
     }
 }
 
@@ -153,7 +247,39 @@ mod tests {
                 enforce: false,
                 ..Default::default()
             },
+            ..Default::default()
         }, Some(SettingsValidationError::NoneEnforcement))]
+    #[case::only_startup_enforced(Settings {
+            liveness: ProbeConfiguration {
+                enforce: false,
+                ..Default::default()
+            },
+            readiness: ProbeConfiguration {
+                enforce: false,
+                ..Default::default()
+            },
+            startup: ProbeConfiguration::default(),
+            ..Default::default()
+        }, None)]
+    #[case::exec_forbidden(Settings {
+            forbidden_handlers: vec![ProbeHandler::Exec],
+            ..Default::default()
+        }, None)]
+    #[case::all_handlers_forbidden(Settings {
+            forbidden_handlers: ProbeHandler::ALL.to_vec(),
+            ..Default::default()
+        }, Some(SettingsValidationError::AllHandlersForbidden))]
+    #[case::invalid_startup_settings(Settings {
+            startup: ProbeConfiguration {
+                enforce: true,
+                failure_threshold: Some(ProbeTimeConfiguration {
+                    minimum: Some(30),
+                    limit: Some(10),
+                }),
+                ..Default::default()
+            },
+                ..Default::default()
+        }, Some(SettingsValidationError::MinimumGreaterThanLimit))]
     #[case::validate_settings_with_invalid_period_seconds_range(Settings {
             readiness: ProbeConfiguration {
                 enforce: true,
@@ -215,4 +341,21 @@ mod tests {
             assert!(result.is_ok());
         }
     }
+
+    #[test]
+    fn deserialize_settings() {
+        let settings: Settings = serde_json::from_str(
+            r#"{
+                "liveness": {"enforce": true},
+                "readiness": {"enforce": true},
+                "forbiddenHandlers": ["exec"],
+                "exemptJobPods": true
+            }"#,
+        )
+        .expect("cannot parse settings");
+        assert!(!settings.startup.enforce);
+        assert_eq!(settings.forbidden_handlers, vec![ProbeHandler::Exec]);
+        assert!(!settings.exempt_init_containers);
+        assert!(settings.exempt_job_pods);
+    }
 }
@@ -0,0 +1,52 @@
+#!/usr/bin/env bats
+
+@test "Reject a Deployment requesting a token valid for multiple days" {
+	run kwctl run --request-path test_data/deployment_long_lived_token.json --settings-json '{"maxExpirationSeconds": 86400}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*volume 'api-token' requests a token valid for multiple days (172800 seconds).*") -ne 0 ]
+}
+
+@test "Accept a Pod requesting a short lived token" {
+	run kwctl run --request-path test_data/pod_short_lived_token.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Pod requesting a token valid as long as the maximum" {
+	run kwctl run --request-path test_data/pod_short_lived_token.json --settings-json '{"maxExpirationSeconds": 600}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Pod requesting a token for an audience not allowed" {
+	run kwctl run --request-path test_data/pod_short_lived_token.json --settings-json '{"allowedAudiences": ["storage"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*volume 'vault-token' requests a token for the audience 'vault', which is not allowed.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_short_lived_token.json --settings-json '{"maxExpirationSeconds": 172800}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,238 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::core::v1::{PodSpec, ServiceAccountTokenProjection};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::{MAX_EXPIRATION_SECONDS, Settings};
+
+// Lifetime given by Kubernetes to the projected tokens without expirationSeconds
+const DEFAULT_EXPIRATION_SECONDS: i64 = 3600;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        Ok(None) => return kubewarden::accept_request(),
+        Err(e) => {
+            return kubewarden::reject_request(
+                Some(format!("Cannot parse validation request: {e}")),
+                Some(400),
+                None,
+                None,
+            );
+        }
+    };
+
+    let errors = validate_pod_spec(&pod_spec, &validation_request.settings);
+    if !errors.is_empty() {
+        return kubewarden::reject_request(
+            Some(format!(
+                "Projected ServiceAccount tokens are not compliant: {}",
+                errors.join("; ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_pod_spec(pod_spec: &PodSpec, settings: &Settings) -> Vec<String> {
+    pod_spec
+        .volumes
+        .iter()
+        .flatten()
+        .flat_map(|volume| {
+            volume
+                .projected
+                .iter()
+                .flat_map(|projected| projected.sources.iter().flatten())
+                .filter_map(|source| source.service_account_token.as_ref())
+                .flat_map(|token| validate_token(token, settings))
+                .map(|error| format!("volume '{}' {error}", volume.name))
+        })
+        .collect()
+}
+
+// Returns the reasons why the projected token is not allowed
+fn validate_token(token: &ServiceAccountTokenProjection, settings: &Settings) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    let expiration_seconds = token
+        .expiration_seconds
+        .unwrap_or(DEFAULT_EXPIRATION_SECONDS);
+    if expiration_seconds > MAX_EXPIRATION_SECONDS {
+        errors.push(format!(
+            "requests a token valid for multiple days ({expiration_seconds} seconds)"
+        ));
+    } else if expiration_seconds > settings.max_expiration_seconds {
+        errors.push(format!(
+            "requests a token valid for {expiration_seconds} seconds, more than the maximum of {} seconds",
+            settings.max_expiration_seconds
+        ));
+    }
+
+    // Tokens without audience are issued for the API server
+    if let Some(audience) = token.audience.as_deref().filter(|a| !a.is_empty())
+        && !settings.allowed_audiences.is_empty()
+        && !settings.allowed_audiences.contains(audience)
+    {
+        errors.push(format!(
+            "requests a token for the audience '{audience}', which is not allowed"
+        ));
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use k8s_openapi::api::core::v1::{ProjectedVolumeSource, Volume, VolumeProjection};
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn token(
+        expiration_seconds: Option<i64>,
+        audience: Option<&str>,
+    ) -> ServiceAccountTokenProjection {
+        ServiceAccountTokenProjection {
+            expiration_seconds,
+            audience: audience.map(str::to_owned),
+            path: "token".to_owned(),
+        }
+    }
+
+    #[rstest]
+    #[case::kubernetes_defaults(token(None, None), vec![])]
+    #[case::short_lived(token(Some(600), Some("vault")), vec![])]
+    #[case::above_maximum(
+        token(Some(7200), None),
+        vec!["requests a token valid for 7200 seconds, more than the maximum of 3600 seconds"]
+    )]
+    #[case::multiple_days(
+        token(Some(172800), None),
+        vec!["requests a token valid for multiple days (172800 seconds)"]
+    )]
+    #[case::audience_not_allowed(
+        token(None, Some("storage")),
+        vec!["requests a token for the audience 'storage', which is not allowed"]
+    )]
+    #[case::multiple_errors(
+        token(Some(7200), Some("storage")),
+        vec![
+            "requests a token valid for 7200 seconds, more than the maximum of 3600 seconds",
+            "requests a token for the audience 'storage', which is not allowed",
+        ]
+    )]
+    fn validate_tokens(#[case] token: ServiceAccountTokenProjection, #[case] expected: Vec<&str>) {
+        let settings = Settings {
+            allowed_audiences: ["vault".to_owned()].into(),
+            ..Default::default()
+        };
+        assert_eq!(validate_token(&token, &settings), expected);
+    }
+
+    #[test]
+    fn any_audience_allowed_by_default() {
+        assert!(validate_token(&token(None, Some("storage")), &Settings::default()).is_empty());
+    }
+
+    #[test]
+    fn multiple_days_above_configured_maximum() {
+        let settings = Settings {
+            max_expiration_seconds: MAX_EXPIRATION_SECONDS,
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_token(&token(Some(MAX_EXPIRATION_SECONDS + 1), None), &settings),
+            vec!["requests a token valid for multiple days (86401 seconds)"]
+        );
+    }
+
+    #[test]
+    fn volume_names_in_errors() {
+        let pod_spec = PodSpec {
+            volumes: Some(vec![
+                Volume {
+                    name: "config".to_owned(),
+                    ..Default::default()
+                },
+                Volume {
+                    name: "vault-token".to_owned(),
+                    projected: Some(ProjectedVolumeSource {
+                        sources: Some(vec![
+                            VolumeProjection {
+                                service_account_token: Some(token(Some(600), Some("vault"))),
+                                ..Default::default()
+                            },
+                            VolumeProjection {
+                                service_account_token: Some(token(Some(172800), None)),
+                                ..Default::default()
+                            },
+                        ]),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_pod_spec(&pod_spec, &Settings::default()),
+            vec!["volume 'vault-token' requests a token valid for multiple days (172800 seconds)"]
+        );
+    }
+
+    #[rstest]
+    #[case::long_lived_token(
+        "test_data/deployment_long_lived_token.json",
+        Settings::default(),
+        false
+    )]
+    #[case::long_lived_token_above_maximum(
+        "test_data/deployment_long_lived_token.json",
+        Settings {
+            max_expiration_seconds: 86400,
+            ..Default::default()
+        },
+        false
+    )]
+    #[case::short_lived_token("test_data/pod_short_lived_token.json", Settings::default(), true)]
+    #[case::audience_not_allowed(
+        "test_data/pod_short_lived_token.json",
+        Settings {
+            allowed_audiences: ["storage".to_owned()].into(),
+            ..Default::default()
+        },
+        false
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
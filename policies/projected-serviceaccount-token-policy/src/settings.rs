@@ -0,0 +1,67 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+/// Minimum lifetime of a projected ServiceAccount token accepted by Kubernetes
+pub(crate) const MIN_EXPIRATION_SECONDS: i64 = 600;
+/// Maximum lifetime allowed by the policy: tokens lasting more than one day
+/// are always rejected
+pub(crate) const MAX_EXPIRATION_SECONDS: i64 = 86400;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Maximum lifetime, in seconds, of the projected ServiceAccount tokens
+    pub max_expiration_seconds: i64,
+    /// Audiences the projected ServiceAccount tokens can be requested for.
+    /// When empty, all the audiences are allowed.
+    pub allowed_audiences: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            max_expiration_seconds: 3600,
+            allowed_audiences: HashSet::new(),
+        }
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if !(MIN_EXPIRATION_SECONDS..=MAX_EXPIRATION_SECONDS).contains(&self.max_expiration_seconds)
+        {
+            return Err(format!(
+                "maxExpirationSeconds must be between {MIN_EXPIRATION_SECONDS} and {MAX_EXPIRATION_SECONDS}, got {}",
+                self.max_expiration_seconds
+            ));
+        }
+        if self.allowed_audiences.contains("") {
+            return Err("allowedAudiences cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::max_expiration(r#"{"maxExpirationSeconds": 7200}"#, true)]
+    #[case::one_day(r#"{"maxExpirationSeconds": 86400}"#, true)]
+    #[case::multiple_days(r#"{"maxExpirationSeconds": 172800}"#, false)]
+    #[case::below_kubernetes_minimum(r#"{"maxExpirationSeconds": 300}"#, false)]
+    #[case::allowed_audiences(r#"{"allowedAudiences": ["vault"]}"#, true)]
+    #[case::empty_audience(r#"{"allowedAudiences": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,51 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod in a namespace not protected" {
+	run kwctl run --request-path test_data/pod_team_namespace.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Pod in the default namespace" {
+	run kwctl run --request-path test_data/pod_default_namespace.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*user 'developer' cannot create Pod resources in the protected namespace 'default'.*") -ne 0 ]
+}
+
+@test "Accept a Pod created by a controller of kube-system" {
+	run kwctl run --request-path test_data/pod_kube_system_controller.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Deployment in kube-system created by an allowed user" {
+	run kwctl run --request-path test_data/deployment_kube_system.json --settings-json '{"allowedUsers": ["developer"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_team_namespace.json --settings-json '{"protectedNamespaces": [""]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
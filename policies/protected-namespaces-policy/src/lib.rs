@@ -0,0 +1,123 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use user_allowlist::UserAllowlist;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    if !settings.protected_namespaces.contains(&request.namespace) {
+        return kubewarden::accept_request();
+    }
+    let username = request.user_info.username.unwrap_or_default();
+    let groups = request.user_info.groups.unwrap_or_default();
+    if settings.is_user_allowed(&username, &groups) {
+        return kubewarden::accept_request();
+    }
+
+    kubewarden::reject_request(
+        Some(format!(
+            "user '{username}' cannot create {} resources in the protected namespace '{}'",
+            request.kind.kind, request.namespace
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::team_namespace("test_data/pod_team_namespace.json", Settings::default(), true)]
+    #[case::default_namespace("test_data/pod_default_namespace.json", Settings::default(), false)]
+    #[case::kube_system("test_data/deployment_kube_system.json", Settings::default(), false)]
+    #[case::kube_system_controller(
+        "test_data/pod_kube_system_controller.json",
+        Settings::default(),
+        true
+    )]
+    #[case::allowed_user(
+        "test_data/deployment_kube_system.json",
+        Settings {
+            allowed_users: ["developer".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::allowed_group(
+        "test_data/pod_default_namespace.json",
+        Settings {
+            allowed_groups: ["system:authenticated".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::protected_team_namespace(
+        "test_data/pod_team_namespace.json",
+        Settings {
+            protected_namespaces: ["team-a".to_owned()].into(),
+            ..Default::default()
+        },
+        false
+    )]
+    #[case::default_namespace_not_protected(
+        "test_data/pod_default_namespace.json",
+        Settings {
+            protected_namespaces: ["kube-system".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/deployment_kube_system.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "user 'developer' cannot create Deployment resources in the protected namespace 'kube-system'"
+            )
+        );
+    }
+}
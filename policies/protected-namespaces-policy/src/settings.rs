@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use user_allowlist::UserAllowlist;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Namespaces where the workloads cannot be created
+    pub protected_namespaces: HashSet<String>,
+    /// Users allowed to create workloads in the protected namespaces
+    pub allowed_users: HashSet<String>,
+    /// Groups of users allowed to create workloads in the protected
+    /// namespaces
+    pub allowed_groups: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            protected_namespaces: [
+                "default".to_owned(),
+                "kube-system".to_owned(),
+                "kube-public".to_owned(),
+            ]
+            .into(),
+            allowed_users: HashSet::new(),
+            allowed_groups: [
+                "system:masters".to_owned(),
+                "system:nodes".to_owned(),
+                "system:serviceaccounts:kube-system".to_owned(),
+            ]
+            .into(),
+        }
+    }
+}
+
+impl UserAllowlist for Settings {
+    fn allowed_users(&self) -> &HashSet<String> {
+        &self.allowed_users
+    }
+
+    fn allowed_groups(&self) -> &HashSet<String> {
+        &self.allowed_groups
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.protected_namespaces.contains("") {
+            return Err("protectedNamespaces cannot contain empty values".to_owned());
+        }
+        if self.allowed_users.contains("") {
+            return Err("allowedUsers cannot contain empty values".to_owned());
+        }
+        if self.allowed_groups.contains("") {
+            return Err("allowedGroups cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::developer("developer", vec!["system:authenticated"], false)]
+    #[case::cluster_admin("admin", vec!["system:masters", "system:authenticated"], true)]
+    #[case::kube_system_service_account(
+        "system:serviceaccount:kube-system:daemon-set-controller",
+        vec!["system:serviceaccounts", "system:serviceaccounts:kube-system", "system:authenticated"],
+        true
+    )]
+    #[case::other_service_account(
+        "system:serviceaccount:team-a:deployer",
+        vec!["system:serviceaccounts", "system:serviceaccounts:team-a", "system:authenticated"],
+        false
+    )]
+    fn allowed_users(#[case] username: &str, #[case] groups: Vec<&str>, #[case] expected: bool) {
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(
+            Settings::default().is_user_allowed(username, &groups),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::allowed_users(r#"{"allowedUsers": ["platform-bot"], "allowedGroups": []}"#, true)]
+    #[case::empty_protected_namespace(r#"{"protectedNamespaces": [""]}"#, false)]
+    #[case::empty_user(r#"{"allowedUsers": [""]}"#, false)]
+    #[case::empty_group(r#"{"allowedGroups": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -30,3 +30,25 @@
 	echo "$output"
 	[ $(expr "$output" : '.*Provided settings are not valid.*') -ne 0 ]
 }
+
+@test "Apply the modes of the rule matching the namespace name" {
+	run kwctl run  --request-path test_data/kube_namespace_with_no_labels.json --settings-path test_data/settings_with_rules.json annotated-policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+ }
+
+@test "Invalid rules should cause an error" {
+	run kwctl run  --request-path test_data/namespace_with_no_labels.json --settings-json '{"rules": [{"namespace-pattern": "kube-(", "modes": {"enforce": "privileged"}}]}' annotated-policy.wasm
+	[ "$status" -ne 0 ]
+	echo "$output"
+ }
+
+@test "Mutate namespace with audit and warn modes only" {
+	run kwctl run  --request-path test_data/namespace_with_labels.json --settings-path test_data/settings_audit_warn_modes.json annotated-policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+ }
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use guest::prelude::*;
 use kubewarden_policy_sdk::wapc_guest as guest;
 
@@ -7,7 +9,7 @@ extern crate kubewarden_policy_sdk as kubewarden;
 use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
 
 mod settings;
-use settings::Settings;
+use settings::{Modes, Settings};
 
 #[unsafe(no_mangle)]
 pub extern "C" fn wapc_init() {
@@ -21,46 +23,15 @@ fn validate(payload: &[u8]) -> CallResult {
 
     match serde_json::from_value::<apicore::Namespace>(validation_request.request.object) {
         Ok(mut namespace) => {
-            let mut labels = namespace.metadata.labels.clone().unwrap_or_default();
-            if let Some(mode) = validation_request.settings.modes.enforce {
-                labels.insert(
-                    "pod-security.kubernetes.io/enforce".to_string(),
-                    mode.to_string(),
-                );
-            }
-            if let Some(version) = validation_request.settings.modes.enforce_version {
-                labels.insert(
-                    "pod-security.kubernetes.io/enforce-version".to_string(),
-                    version,
-                );
-            }
-
-            if let Some(mode) = validation_request.settings.modes.warn {
-                labels.insert(
-                    "pod-security.kubernetes.io/warn".to_string(),
-                    mode.to_string(),
-                );
-            }
-            if let Some(version) = validation_request.settings.modes.warn_version {
-                labels.insert(
-                    "pod-security.kubernetes.io/warn-version".to_string(),
-                    version,
-                );
-            }
+            let original_labels = namespace.metadata.labels.clone().unwrap_or_default();
+            let name = namespace.metadata.name.clone().unwrap_or_default();
+            let modes = validation_request
+                .settings
+                .modes_for(&name, &original_labels);
 
-            if let Some(mode) = validation_request.settings.modes.audit {
-                labels.insert(
-                    "pod-security.kubernetes.io/audit".to_string(),
-                    mode.to_string(),
-                );
-            }
-            if let Some(version) = validation_request.settings.modes.audit_version {
-                labels.insert(
-                    "pod-security.kubernetes.io/audit-version".to_string(),
-                    version,
-                );
-            }
-            if labels != namespace.metadata.labels.unwrap_or_default() {
+            let mut labels = original_labels.clone();
+            apply_modes(&mut labels, modes);
+            if labels != original_labels {
                 namespace.metadata.labels = Some(labels);
                 kubewarden::mutate_request(
                     serde_json::to_value(namespace).expect("cannot serialize mutated object"),
@@ -73,6 +44,48 @@ fn validate(payload: &[u8]) -> CallResult {
     }
 }
 
+// Sets the PSA labels of the modes defined in the settings
+fn apply_modes(labels: &mut BTreeMap<String, String>, modes: &Modes) {
+    if let Some(mode) = &modes.enforce {
+        labels.insert(
+            "pod-security.kubernetes.io/enforce".to_string(),
+            mode.to_string(),
+        );
+    }
+    if let Some(version) = &modes.enforce_version {
+        labels.insert(
+            "pod-security.kubernetes.io/enforce-version".to_string(),
+            version.clone(),
+        );
+    }
+
+    if let Some(mode) = &modes.warn {
+        labels.insert(
+            "pod-security.kubernetes.io/warn".to_string(),
+            mode.to_string(),
+        );
+    }
+    if let Some(version) = &modes.warn_version {
+        labels.insert(
+            "pod-security.kubernetes.io/warn-version".to_string(),
+            version.clone(),
+        );
+    }
+
+    if let Some(mode) = &modes.audit {
+        labels.insert(
+            "pod-security.kubernetes.io/audit".to_string(),
+            mode.to_string(),
+        );
+    }
+    if let Some(version) = &modes.audit_version {
+        labels.insert(
+            "pod-security.kubernetes.io/audit-version".to_string(),
+            version.clone(),
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,7 +96,6 @@ mod tests {
     use kubewarden_policy_sdk::response::ValidationResponse;
     use rstest::rstest;
     use serde_json::Value;
-    use std::collections::BTreeMap;
 
     #[rstest]
     #[case::partial_label_update(
@@ -108,6 +120,27 @@ mod tests {
         Some("v1.27".to_string()),
         serde_json::json!({ })
     )]
+    #[case::audit_and_warn_only(
+        None,
+        None,
+        Some(settings::Level::Restricted),
+        Some("v1.27".to_string()),
+        Some(settings::Level::Restricted),
+        Some("latest".to_string()),
+        serde_json::json!({
+            "pod-security.kubernetes.io/enforce": "privileged",
+            "pod-security.kubernetes.io/enforce-version": "latest"
+        })
+    )]
+    #[case::warn_version_pinning(
+        None, None, None, None,
+        Some(settings::Level::Baseline),
+        Some("v1.25".to_string()),
+        serde_json::json!({
+            "pod-security.kubernetes.io/warn": "baseline",
+            "pod-security.kubernetes.io/warn-version": "latest"
+        })
+    )]
     #[case::update_all_labels(
         Some(settings::Level::Baseline),
         Some("v1.25".to_string()),
@@ -159,6 +192,7 @@ mod tests {
                 warn,
                 warn_version,
             },
+            rules: vec![],
         };
         let validation_request = ValidationRequest::<Settings> { settings, request };
         let payload = serde_json::to_string(&validation_request).expect("Cannot serialize payload");
@@ -265,6 +299,7 @@ mod tests {
                 warn: Some(settings::Level::Restricted),
                 warn_version: Some("v1.27".to_string()),
             },
+            rules: vec![],
         };
         let validation_request = ValidationRequest::<Settings> { settings, request };
         let payload = serde_json::to_string(&validation_request).expect("Cannot serialize payload");
@@ -275,4 +310,45 @@ mod tests {
         assert!(response.accepted);
         assert!(response.mutated_object.is_none());
     }
+
+    #[rstest]
+    #[case::matching_rule("kube-system", "privileged")]
+    #[case::no_matching_rule("team-a", "restricted")]
+    fn rules_tests(#[case] name: &str, #[case] expected_enforce: &str) {
+        let namespace = apicore::Namespace {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let request = KubernetesAdmissionRequest {
+            kind: GroupVersionKind {
+                kind: apicore::Namespace::KIND.to_string(),
+                ..Default::default()
+            },
+            object: serde_json::to_value(namespace).expect("Cannot serialize namespace object"),
+            ..Default::default()
+        };
+        let settings: Settings =
+            serde_json::from_str(include_str!("../test_data/settings_with_rules.json"))
+                .expect("cannot parse settings");
+        let validation_request = ValidationRequest::<Settings> { settings, request };
+        let payload = serde_json::to_string(&validation_request).expect("Cannot serialize payload");
+
+        let response: ValidationResponse =
+            serde_json::from_slice(&validate(payload.as_bytes()).expect("Validation failed"))
+                .expect("Cannot parse response JSON");
+        assert!(response.accepted);
+        let mutated_object: apicore::Namespace =
+            serde_json::from_value(response.mutated_object.expect("namespace not mutated"))
+                .expect("cannot deserialize mutated object");
+        let labels = mutated_object.metadata.labels.unwrap_or_default();
+        assert_eq!(
+            labels
+                .get("pod-security.kubernetes.io/enforce")
+                .map(String::as_str),
+            Some(expected_enforce)
+        );
+    }
 }
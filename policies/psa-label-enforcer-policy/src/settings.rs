@@ -1,5 +1,7 @@
+use std::collections::BTreeMap;
+
 use lazy_static::lazy_static;
-use regex::RegexSet;
+use regex::{Regex, RegexSet};
 
 use serde::{Deserialize, Serialize};
 
@@ -44,53 +46,49 @@ pub(crate) struct Modes {
     pub warn_version: Option<String>,
 }
 
-// Describe the settings your policy expects when
-// loaded by the policy server.
-#[derive(Serialize, Deserialize, Default, Debug)]
-#[serde(default)]
-pub(crate) struct Settings {
-    pub modes: Modes,
-}
+impl Modes {
+    fn is_empty(&self) -> bool {
+        self.enforce.is_none() && self.audit.is_none() && self.warn.is_none()
+    }
 
-impl kubewarden::settings::Validatable for Settings {
     fn validate(&self) -> Result<(), String> {
-        if self.modes.enforce.is_none() && self.modes.audit.is_none() && self.modes.warn.is_none() {
+        if self.is_empty() {
             return Err(
                 "At least one of the 'enforce', 'audit' or 'warn' configuration must be defined"
                     .to_string(),
             );
         }
 
-        if let Some(version) = &self.modes.enforce_version {
+        if let Some(version) = &self.enforce_version {
             let matches: Vec<_> = VERSION_REGEXES.matches(version).into_iter().collect();
             if matches.is_empty() {
                 return Err(format!(
                     "Version {version} is invalid. It must follow the v<major>.<minor> pattern or be 'latest' value"
                 ));
             }
-            if self.modes.enforce.is_none() {
+            if self.enforce.is_none() {
                 return Err("cannot define enforce version with no enforce mode.".to_string());
             }
         }
-        if let Some(version) = &self.modes.audit_version {
+        if let Some(version) = &self.audit_version {
             let matches: Vec<_> = VERSION_REGEXES.matches(version).into_iter().collect();
             if matches.is_empty() {
                 return Err(format!(
                     "Version {version} is invalid. It must follow the v<major>.<minor> pattern or be 'latest' value"
                 ));
             }
-            if self.modes.audit.is_none() {
+            if self.audit.is_none() {
                 return Err("cannot define audit version with no audit mode.".to_string());
             }
         }
-        if let Some(version) = &self.modes.warn_version {
+        if let Some(version) = &self.warn_version {
             let matches: Vec<_> = VERSION_REGEXES.matches(version).into_iter().collect();
             if matches.is_empty() {
                 return Err(format!(
                     "Version {version} is invalid. It must follow the v<major>.<minor> pattern or be 'latest' value"
                 ));
             }
-            if self.modes.warn.is_none() {
+            if self.warn.is_none() {
                 return Err("cannot define warn version with no warn mode.".to_string());
             }
         }
@@ -98,6 +96,79 @@ impl kubewarden::settings::Validatable for Settings {
     }
 }
 
+/// Modes applied to the namespaces whose name matches the pattern and that
+/// have all the labels
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct Rule {
+    /// Regular expression matching the whole name of the namespace
+    pub namespace_pattern: Option<String>,
+    pub match_labels: BTreeMap<String, String>,
+    pub modes: Modes,
+}
+
+impl Rule {
+    /// Returns true when the rule applies to the namespace
+    pub fn matches(&self, name: &str, labels: &BTreeMap<String, String>) -> bool {
+        let name_matches = self
+            .namespace_pattern
+            .as_ref()
+            .is_none_or(|pattern| namespace_regex(pattern).is_ok_and(|regex| regex.is_match(name)));
+        let labels_match = self
+            .match_labels
+            .iter()
+            .all(|(key, value)| labels.get(key) == Some(value));
+        name_matches && labels_match
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.namespace_pattern.is_none() && self.match_labels.is_empty() {
+            return Err("rules must define a namespace-pattern or match-labels".to_string());
+        }
+        if let Some(pattern) = &self.namespace_pattern {
+            namespace_regex(pattern)
+                .map_err(|e| format!("invalid namespace-pattern '{pattern}': {e}"))?;
+        }
+        self.modes.validate()
+    }
+}
+
+fn namespace_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{pattern})$"))
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub(crate) struct Settings {
+    /// Modes applied to the namespaces not matched by any rule
+    pub modes: Modes,
+    /// Rules evaluated in order, the first one matching the namespace wins
+    pub rules: Vec<Rule>,
+}
+
+impl Settings {
+    /// Returns the modes to apply to the namespace
+    pub fn modes_for(&self, name: &str, labels: &BTreeMap<String, String>) -> &Modes {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(name, labels))
+            .map_or(&self.modes, |rule| &rule.modes)
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        // when there are rules, the namespaces not matched by any of them
+        // can be left untouched
+        if self.rules.is_empty() || !self.modes.is_empty() {
+            self.modes.validate()?;
+        }
+        self.rules.iter().try_for_each(Rule::validate)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,6 +187,7 @@ mod tests {
                 warn: None,
                 warn_version: None,
             },
+            rules: vec![],
         };
         assert!(settings.validate().is_err());
         settings = Settings {
@@ -127,6 +199,7 @@ mod tests {
                 warn: None,
                 warn_version: None,
             },
+            rules: vec![],
         };
         assert!(settings.validate().is_err());
 
@@ -139,6 +212,7 @@ mod tests {
                 warn: None,
                 warn_version: None,
             },
+            rules: vec![],
         };
         assert!(settings.validate().is_err());
 
@@ -151,6 +225,7 @@ mod tests {
                 warn: None,
                 warn_version: Some("1.25.0".to_string()),
             },
+            rules: vec![],
         };
         assert!(settings.validate().is_err());
     }
@@ -166,6 +241,7 @@ mod tests {
                 warn: None,
                 warn_version: None,
             },
+            rules: vec![],
         };
         assert!(settings.validate().is_err());
     }
@@ -191,6 +267,7 @@ mod tests {
                 warn: Some(Level::Baseline),
                 warn_version: Some(version.clone()),
             },
+            rules: vec![],
         };
         assert_eq!(settings.validate().is_ok(), is_valid);
 
@@ -203,6 +280,7 @@ mod tests {
                 warn: None,
                 warn_version: None,
             },
+            rules: vec![],
         };
         assert_eq!(settings.validate().is_ok(), is_valid);
 
@@ -215,6 +293,7 @@ mod tests {
                 warn: None,
                 warn_version: None,
             },
+            rules: vec![],
         };
         assert_eq!(settings.validate().is_ok(), is_valid);
 
@@ -227,7 +306,54 @@ mod tests {
                 warn: Some(Level::Baseline),
                 warn_version: Some(version.clone()),
             },
+            rules: vec![],
         };
         assert_eq!(settings.validate().is_ok(), is_valid);
     }
+
+    #[rstest]
+    #[case::rules_without_default_modes(
+        r#"{"rules": [{"namespace-pattern": "kube-.*", "modes": {"enforce": "privileged"}}]}"#,
+        true
+    )]
+    #[case::rules_with_default_modes(
+        r#"{"modes": {"enforce": "restricted"}, "rules": [{"match-labels": {"example.com/team": "platform"}, "modes": {"enforce": "baseline"}}]}"#,
+        true
+    )]
+    #[case::rule_without_selection(r#"{"rules": [{"modes": {"enforce": "privileged"}}]}"#, false)]
+    #[case::rule_with_invalid_pattern(
+        r#"{"rules": [{"namespace-pattern": "kube-(", "modes": {"enforce": "privileged"}}]}"#,
+        false
+    )]
+    #[case::rule_without_modes(r#"{"rules": [{"namespace-pattern": "kube-.*"}]}"#, false)]
+    #[case::rule_with_invalid_version(
+        r#"{"rules": [{"namespace-pattern": "kube-.*", "modes": {"enforce": "privileged", "enforce-version": "1.25"}}]}"#,
+        false
+    )]
+    fn rules_validation_test(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::name_pattern("kube-system", &[], Some(Level::Privileged))]
+    #[case::second_name_pattern("infra-logging", &[], Some(Level::Privileged))]
+    #[case::pattern_matches_whole_name("my-kube-apps", &[], Some(Level::Restricted))]
+    #[case::labels("payments", &[("example.com/team", "platform")], Some(Level::Baseline))]
+    #[case::first_rule_wins("kube-platform", &[("example.com/team", "platform")], Some(Level::Privileged))]
+    #[case::other_labels("payments", &[("example.com/team", "billing")], Some(Level::Restricted))]
+    fn modes_for_test(
+        #[case] name: &str,
+        #[case] labels: &[(&str, &str)],
+        #[case] expected_enforce: Option<Level>,
+    ) {
+        let settings: Settings =
+            serde_json::from_str(include_str!("../test_data/settings_with_rules.json"))
+                .expect("cannot parse settings");
+        let labels = labels
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        assert_eq!(settings.modes_for(name, &labels).enforce, expected_enforce);
+    }
 }
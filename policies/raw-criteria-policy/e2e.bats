@@ -0,0 +1,38 @@
+#!/usr/bin/env bats
+
+@test "Accept a Terraform plan with allowed changes" {
+	run kwctl run --raw \
+		--request-path test_data/terraform_plan.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Terraform plan with forbidden changes" {
+	run kwctl run --raw \
+		--request-path test_data/terraform_plan_destroy.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*The following invalid values were found: aws_iam_user.*') -ne 0 ]
+	[ $(expr "$output" : '.*The following values were found that should not be present: delete.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --raw --request-path test_data/terraform_plan.json --settings-json '{"rules": [{"selector": "$.[", "criteria": "containsAnyOf", "values": ["a"]}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,133 @@
+use criteria_policy_base::{
+    kubewarden_policy_sdk::{
+        accept_request, protocol_version_guest, reject_request, validate_settings,
+        wapc_guest as guest,
+    },
+    validate::validate_values,
+};
+use guest::prelude::*;
+
+mod request;
+use request::RawValidationRequest;
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let Ok(validation_request) = serde_json::from_slice::<RawValidationRequest>(payload) else {
+        return reject_request(
+            Some("cannot unmarshal request".to_owned()),
+            None,
+            None,
+            None,
+        );
+    };
+
+    let errors: Vec<String> = validation_request
+        .settings
+        .rules
+        .iter()
+        .filter_map(|rule| {
+            let values = rule.selected_values(&validation_request.request);
+            validate_values(&rule.criteria, &values)
+                .err()
+                .map(|e| format!("rule '{}': {e}", rule.selector))
+        })
+        .collect();
+    if !errors.is_empty() {
+        return reject_request(Some(errors.join("; ")), None, None, None);
+    }
+    accept_request()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    use criteria_policy_base::{
+        kubewarden_policy_sdk::{response::ValidationResponse, test::Testcase},
+        settings::BaseSettings,
+    };
+    use rstest::rstest;
+
+    use settings::Rule;
+
+    fn terraform_settings() -> Settings {
+        Settings {
+            rules: vec![
+                Rule {
+                    selector: "$.resource_changes[*].type".to_owned(),
+                    criteria: BaseSettings::DoesNotContainAnyOf {
+                        values: HashSet::from(["aws_iam_user".to_owned()]),
+                    },
+                },
+                Rule {
+                    selector: "$.resource_changes[*].change.actions".to_owned(),
+                    criteria: BaseSettings::DoesNotContainOtherThan {
+                        values: HashSet::from([
+                            "no-op".to_owned(),
+                            "create".to_owned(),
+                            "update".to_owned(),
+                        ]),
+                    },
+                },
+            ],
+        }
+    }
+
+    #[rstest]
+    #[case::allowed_changes("test_data/terraform_plan.json", true)]
+    #[case::forbidden_changes("test_data/terraform_plan_destroy.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: terraform_settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "forbidden changes".to_owned(),
+            fixture_file: "test_data/terraform_plan_destroy.json".to_owned(),
+            expected_validation_result: false,
+            settings: terraform_settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "rule '$.resource_changes[*].type': Resource must not have any of the values specified in the validation rule. The following invalid values were found: aws_iam_user; rule '$.resource_changes[*].change.actions': Resource must have only values from the validation rule. The following values were found that should not be present: delete"
+            )
+        );
+    }
+
+    #[test]
+    fn reject_invalid_payload() {
+        let payload = r#"{"invalid": "payload"}"#;
+
+        let raw_result = validate(payload.as_bytes()).expect("validation failed");
+        let response: ValidationResponse =
+            serde_json::from_slice(&raw_result).expect("cannot parse response");
+
+        assert!(!response.accepted);
+        assert_eq!(
+            response.message.as_deref(),
+            Some("cannot unmarshal request")
+        );
+    }
+}
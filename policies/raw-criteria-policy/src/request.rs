@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::settings::Settings;
+
+#[derive(Deserialize)]
+pub(crate) struct RawValidationRequest {
+    /// Arbitrary JSON document sent to the policy
+    pub(crate) request: Value,
+    pub(crate) settings: Settings,
+}
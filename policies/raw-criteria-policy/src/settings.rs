@@ -0,0 +1,124 @@
+use criteria_policy_base::{kubewarden_policy_sdk as kubewarden, settings::BaseSettings};
+use jsonpath_lib as jsonpath;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Criteria applied to the values selected from the request
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Rule {
+    /// JSONPath expression selecting the values to validate
+    pub selector: String,
+    #[serde(flatten)]
+    pub criteria: BaseSettings,
+}
+
+impl Rule {
+    /// Returns the values selected from the document. The strings are taken
+    /// as they are, the numbers and the booleans are converted to strings and
+    /// the arrays are flattened. The nulls and the objects are skipped.
+    pub fn selected_values(&self, document: &Value) -> Vec<String> {
+        let mut values = Vec::new();
+        // The selector has been validated with the settings
+        for value in jsonpath::select(document, &self.selector).unwrap_or_default() {
+            collect_values(value, &mut values);
+        }
+        values
+    }
+}
+
+fn collect_values(value: &Value, values: &mut Vec<String>) {
+    match value {
+        Value::String(value) => values.push(value.clone()),
+        Value::Bool(_) | Value::Number(_) => values.push(value.to_string()),
+        Value::Array(items) => {
+            for item in items {
+                collect_values(item, values);
+            }
+        }
+        Value::Null | Value::Object(_) => {}
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    pub rules: Vec<Rule>,
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.rules.is_empty() {
+            return Err("at least one rule must be provided".to_owned());
+        }
+        for rule in &self.rules {
+            if rule.selector.is_empty() {
+                return Err("the selector of the rules cannot be empty".to_owned());
+            }
+            jsonpath::select(&Value::Null, &rule.selector)
+                .map_err(|e| format!("rule '{}': invalid selector: {e}", rule.selector))?;
+            rule.criteria
+                .validate()
+                .map_err(|e| format!("rule '{}': {e}", rule.selector))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden::settings::Validatable;
+    use rstest::rstest;
+    use serde_json::json;
+
+    #[rstest]
+    #[case::valid(
+        r#"{"rules": [{"selector": "$.resource_changes[*].type", "criteria": "doesNotContainAnyOf", "values": ["aws_iam_user"]}]}"#,
+        true
+    )]
+    #[case::no_rules(r#"{}"#, false)]
+    #[case::empty_selector(
+        r#"{"rules": [{"selector": "", "criteria": "containsAnyOf", "values": ["a"]}]}"#,
+        false
+    )]
+    #[case::invalid_selector(
+        r#"{"rules": [{"selector": "$.[", "criteria": "containsAnyOf", "values": ["a"]}]}"#,
+        false
+    )]
+    #[case::empty_values(
+        r#"{"rules": [{"selector": "$.user", "criteria": "containsAnyOf", "values": []}]}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::strings("$.actions", vec!["create", "delete"])]
+    #[case::number("$.limits.replicas", vec!["10"])]
+    #[case::boolean("$.limits.enabled", vec!["true"])]
+    #[case::null("$.limits.owner", vec![])]
+    #[case::nested_arrays("$.changes[*].actions", vec!["update", "delete", "create"])]
+    #[case::objects("$.*", vec!["create", "delete"])]
+    #[case::missing("$.owner", vec![])]
+    fn selected_values(#[case] selector: &str, #[case] expected: Vec<&str>) {
+        let document = json!({
+            "actions": ["create", "delete"],
+            "limits": {"replicas": 10, "enabled": true, "owner": null},
+            "changes": [
+                {"actions": ["update"]},
+                {"actions": ["delete", "create"]},
+            ],
+        });
+        let rule = Rule {
+            selector: selector.to_owned(),
+            criteria: BaseSettings::default(),
+        };
+        assert_eq!(rule.selected_values(&document), expected);
+    }
+}
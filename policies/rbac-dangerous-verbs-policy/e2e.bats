@@ -0,0 +1,51 @@
+#!/usr/bin/env bats
+
+@test "Reject a ClusterRole granting impersonate without the exception label" {
+	run kwctl run --request-path test_data/clusterrole_impersonate.json --settings-json '{"allowedGroups": ["security-team"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*ClusterRole 'support-impersonator' cannot grant dangerous verbs without the 'kubewarden.io/rbac-exception' exception label: rule #1 grants impersonate.*") -ne 0 ]
+}
+
+@test "Reject a ClusterRole with the exception label created by a group not allowed" {
+	run kwctl run --request-path test_data/clusterrole_impersonate_exception.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Accept a ClusterRole with the exception label created by an allowed group" {
+	run kwctl run --request-path test_data/clusterrole_impersonate_exception.json --settings-json '{"allowedGroups": ["security-team"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Role without dangerous verbs" {
+	run kwctl run --request-path test_data/role_safe.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject settings with an empty exception label" {
+	run kwctl run --request-path test_data/role_safe.json --settings-json '{"exceptionLabel": ""}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
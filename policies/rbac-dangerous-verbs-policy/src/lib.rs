@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::rbac::v1::PolicyRule;
+use role_rules::extract_rules;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let kind = request.kind.kind.as_str();
+    let Some((metadata, rules)) = extract_rules(kind, request.object)? else {
+        return kubewarden::accept_request();
+    };
+
+    let errors = find_dangerous_verbs(&rules, &settings.dangerous_verbs);
+    if errors.is_empty() {
+        return kubewarden::accept_request();
+    }
+
+    let name = metadata.name.unwrap_or_default();
+    let has_exception = metadata
+        .labels
+        .unwrap_or_default()
+        .get(&settings.exception_label)
+        .is_some_and(|reason| !reason.is_empty());
+    if !has_exception {
+        return kubewarden::reject_request(
+            Some(format!(
+                "{kind} '{name}' cannot grant dangerous verbs without the '{}' exception label: {}",
+                settings.exception_label,
+                errors.join("; ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    if !settings.is_group_allowed(&request.user_info.groups.unwrap_or_default()) {
+        return kubewarden::reject_request(
+            Some(format!(
+                "{kind} '{name}' has the '{}' exception label, but the user is not allowed to grant dangerous verbs: {}",
+                settings.exception_label,
+                errors.join("; ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+/// Resources the `approve` verb is dangerous on: approving the
+/// CertificateSigningRequests issues certificates for any identity
+const APPROVE_RESOURCES: [&str; 3] = ["certificatesigningrequests", "signers", "*"];
+
+// Returns a description of the dangerous verbs granted by each rule. The
+// wildcard verb grants all the dangerous verbs.
+fn find_dangerous_verbs(rules: &[PolicyRule], dangerous_verbs: &HashSet<String>) -> Vec<String> {
+    rules
+        .iter()
+        .enumerate()
+        .filter_map(|(index, rule)| {
+            let is_dangerous = |verb: &str| {
+                dangerous_verbs.contains(verb)
+                    && (verb != "approve"
+                        || rule
+                            .resources
+                            .iter()
+                            .flatten()
+                            .any(|resource| APPROVE_RESOURCES.contains(&resource.as_str())))
+            };
+            let wildcard = rule.verbs.iter().any(|verb| verb == "*");
+            let verbs: Vec<&str> = if wildcard {
+                let mut verbs: Vec<&str> = dangerous_verbs
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|verb| is_dangerous(verb))
+                    .collect();
+                verbs.sort_unstable();
+                verbs
+            } else {
+                rule.verbs
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|verb| is_dangerous(verb))
+                    .collect()
+            };
+            if verbs.is_empty() {
+                None
+            } else if wildcard {
+                Some(format!("rule #{index} grants * ({})", verbs.join(", ")))
+            } else {
+                Some(format!("rule #{index} grants {}", verbs.join(", ")))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn rule(api_groups: &[&str], resources: &[&str], verbs: &[&str]) -> PolicyRule {
+        let to_vec = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        PolicyRule {
+            api_groups: Some(to_vec(api_groups)),
+            resources: Some(to_vec(resources)),
+            verbs: to_vec(verbs),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::safe_verbs(vec![rule(&[""], &["pods"], &["get", "list"])], vec![])]
+    #[case::escalate_and_bind(
+        vec![rule(&["rbac.authorization.k8s.io"], &["clusterroles"], &["get", "escalate", "bind"])],
+        vec!["rule #0 grants escalate, bind"]
+    )]
+    #[case::impersonate(
+        vec![rule(&[""], &["pods"], &["get"]), rule(&[""], &["users", "groups"], &["impersonate"])],
+        vec!["rule #1 grants impersonate"]
+    )]
+    #[case::approve(
+        vec![rule(&["certificates.k8s.io"], &["signers"], &["approve"])],
+        vec!["rule #0 grants approve"]
+    )]
+    #[case::approve_other_resource(
+        vec![rule(&["example.com"], &["deployments"], &["get", "approve"])],
+        vec![]
+    )]
+    #[case::wildcard_verb(
+        vec![rule(&["*"], &["*"], &["*"])],
+        vec!["rule #0 grants * (approve, bind, escalate, impersonate)"]
+    )]
+    #[case::wildcard_verb_on_other_resources(
+        vec![rule(&[""], &["pods"], &["get"]), rule(&[""], &["pods"], &["*"])],
+        vec!["rule #1 grants * (bind, escalate, impersonate)"]
+    )]
+    fn dangerous_verbs(#[case] rules: Vec<PolicyRule>, #[case] expected: Vec<&str>) {
+        assert_eq!(
+            find_dangerous_verbs(&rules, &Settings::default().dangerous_verbs),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::without_exception("test_data/clusterrole_impersonate.json", Settings::default(), false)]
+    #[case::exception_not_allowed_group(
+        "test_data/clusterrole_impersonate_exception.json",
+        Settings::default(),
+        false
+    )]
+    #[case::exception_allowed_group(
+        "test_data/clusterrole_impersonate_exception.json",
+        Settings {
+            allowed_groups: ["security-team".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::allowed_group_without_exception(
+        "test_data/clusterrole_impersonate.json",
+        Settings {
+            allowed_groups: ["security-team".to_owned()].into(),
+            ..Default::default()
+        },
+        false
+    )]
+    #[case::safe_role("test_data/role_safe.json", Settings::default(), true)]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Verbs that cannot be granted by Roles and ClusterRoles
+    pub dangerous_verbs: HashSet<String>,
+    /// Label marking the roles granted an exception. Its value should
+    /// document the reason of the exception
+    pub exception_label: String,
+    /// Groups allowed to create and update the roles having the exception
+    /// label
+    pub allowed_groups: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            dangerous_verbs: ["escalate", "bind", "impersonate", "approve"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            exception_label: "kubewarden.io/rbac-exception".to_owned(),
+            allowed_groups: HashSet::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when one of the groups of the user can create roles
+    /// granting the dangerous verbs
+    pub fn is_group_allowed(&self, groups: &[String]) -> bool {
+        groups
+            .iter()
+            .any(|group| self.allowed_groups.contains(group))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.dangerous_verbs.is_empty() {
+            return Err("dangerousVerbs cannot be empty".to_owned());
+        }
+        if self
+            .dangerous_verbs
+            .iter()
+            .any(|verb| verb.is_empty() || verb == "*")
+        {
+            return Err("dangerousVerbs cannot contain empty values or '*'".to_owned());
+        }
+        if self.exception_label.is_empty() {
+            return Err("exceptionLabel cannot be empty".to_owned());
+        }
+        if self.allowed_groups.contains("") {
+            return Err("allowedGroups cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::custom_verbs(r#"{"dangerousVerbs": ["escalate", "delete"]}"#, true)]
+    #[case::no_verbs(r#"{"dangerousVerbs": []}"#, false)]
+    #[case::wildcard_verb(r#"{"dangerousVerbs": ["*"]}"#, false)]
+    #[case::empty_label(r#"{"exceptionLabel": ""}"#, false)]
+    #[case::empty_group(r#"{"allowedGroups": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::allowed(vec!["system:authenticated", "security-team"], true)]
+    #[case::not_allowed(vec!["system:authenticated"], false)]
+    fn allowed_groups(#[case] groups: Vec<&str>, #[case] expected: bool) {
+        let settings = Settings {
+            allowed_groups: ["security-team".to_owned()].into(),
+            ..Default::default()
+        };
+        let groups: Vec<String> = groups.into_iter().map(String::from).collect();
+        assert_eq!(settings.is_group_allowed(&groups), expected);
+    }
+}
@@ -0,0 +1,33 @@
+#!/usr/bin/env bats
+
+@test "Reject a ClusterRole reading all the Secrets" {
+	run kwctl run --request-path test_data/clusterrole_secret_reader.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*ClusterRole 'secret-reader' cannot read Secrets: rule #1 grants get, list, watch.*") -ne 0 ]
+}
+
+@test "Warn about a ClusterRole reading all the Secrets in warn mode" {
+	run kwctl run --request-path test_data/clusterrole_secret_reader.json --settings-json '{"mode": "warn"}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"warnings":\[.*cannot read Secrets.*') -ne 0 ]
+}
+
+@test "Accept a ClusterRole reading all the Secrets created by an allowed operator" {
+	run kwctl run --request-path test_data/clusterrole_secret_reader.json --settings-json '{"allowedServiceAccounts": [{"namespace": "external-secrets", "name": "external-secrets"}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
@@ -0,0 +1,175 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::Resource;
+use k8s_openapi::api::rbac::v1::{ClusterRole, PolicyRule};
+use role_rules::extract_rules;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+const READ_VERBS: [&str; 4] = ["get", "list", "watch", "*"];
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    if request.kind.kind != ClusterRole::KIND {
+        return kubewarden::accept_request();
+    }
+    let Some((metadata, rules)) = extract_rules(&request.kind.kind, request.object)? else {
+        return kubewarden::accept_request();
+    };
+
+    let name = metadata.name.unwrap_or_default();
+    if settings.is_cluster_role_exempt(&name)
+        || settings.is_user_allowed(request.user_info.username.as_deref())
+        || settings.is_group_exempt(&request.user_info.groups.unwrap_or_default())
+    {
+        return kubewarden::accept_request();
+    }
+
+    let errors = find_secrets_read_access(&rules);
+    if errors.is_empty() {
+        return kubewarden::accept_request();
+    }
+
+    let message = format!(
+        "ClusterRole '{name}' cannot read Secrets: {}",
+        errors.join("; ")
+    );
+    settings.mode.respond(message)
+}
+
+// Returns a description of the rules granting read access to all the
+// Secrets. The rules restricted to some `resourceNames` are not considered.
+fn find_secrets_read_access(rules: &[PolicyRule]) -> Vec<String> {
+    let contains_any = |values: Option<&Vec<String>>, expected: &[&str]| {
+        values.is_some_and(|values| {
+            values
+                .iter()
+                .any(|value| expected.contains(&value.as_str()))
+        })
+    };
+
+    rules
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| {
+            contains_any(rule.api_groups.as_ref(), &["", "*"])
+                && contains_any(rule.resources.as_ref(), &["secrets", "*"])
+                && rule.resource_names.as_ref().is_none_or(Vec::is_empty)
+        })
+        .filter_map(|(index, rule)| {
+            let verbs: Vec<&str> = rule
+                .verbs
+                .iter()
+                .map(String::as_str)
+                .filter(|verb| READ_VERBS.contains(verb))
+                .collect();
+            if verbs.is_empty() {
+                None
+            } else {
+                Some(format!("rule #{index} grants {}", verbs.join(", ")))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use enforcement_mode::Mode;
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use settings::ServiceAccount;
+
+    fn rule(api_groups: &[&str], resources: &[&str], verbs: &[&str]) -> PolicyRule {
+        let to_vec = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        PolicyRule {
+            api_groups: Some(to_vec(api_groups)),
+            resources: Some(to_vec(resources)),
+            verbs: to_vec(verbs),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::configmaps(vec![rule(&[""], &["configmaps"], &["get", "list"])], vec![])]
+    #[case::secrets(
+        vec![rule(&[""], &["configmaps", "secrets"], &["get", "list", "create"])],
+        vec!["rule #0 grants get, list"]
+    )]
+    #[case::write_only(vec![rule(&[""], &["secrets"], &["create", "delete"])], vec![])]
+    #[case::wildcards(
+        vec![rule(&[""], &["pods"], &["get"]), rule(&["*"], &["*"], &["*"])],
+        vec!["rule #1 grants *"]
+    )]
+    #[case::other_api_group(vec![rule(&["example.com"], &["secrets"], &["get"])], vec![])]
+    #[case::resource_names(
+        vec![PolicyRule {
+            resource_names: Some(vec!["tls-certificate".to_owned()]),
+            ..rule(&[""], &["secrets"], &["get"])
+        }],
+        vec![]
+    )]
+    fn secrets_read_access(#[case] rules: Vec<PolicyRule>, #[case] expected: Vec<&str>) {
+        assert_eq!(find_secrets_read_access(&rules), expected);
+    }
+
+    #[rstest]
+    #[case::secret_reader("test_data/clusterrole_secret_reader.json", Settings::default(), false)]
+    #[case::secret_reader_warn(
+        "test_data/clusterrole_secret_reader.json",
+        Settings {
+            mode: Mode::Warn,
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::allowed_service_account(
+        "test_data/clusterrole_secret_reader.json",
+        Settings {
+            allowed_service_accounts: vec![ServiceAccount {
+                namespace: "external-secrets".to_owned(),
+                name: "external-secrets".to_owned(),
+            }],
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::exempt_cluster_role(
+        "test_data/clusterrole_secret_reader.json",
+        Settings {
+            exempt_cluster_roles: ["secret-*".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashSet;
+
+use enforcement_mode::Mode;
+use serde::{Deserialize, Serialize};
+
+/// ServiceAccount allowed to create ClusterRoles reading the Secrets
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ServiceAccount {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl ServiceAccount {
+    /// Returns the username used by the ServiceAccount
+    pub fn username(&self) -> String {
+        format!("system:serviceaccount:{}:{}", self.namespace, self.name)
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// ServiceAccounts of the operators allowed to create and update
+    /// ClusterRoles reading the Secrets
+    pub allowed_service_accounts: Vec<ServiceAccount>,
+    /// ClusterRoles that can read the Secrets. Entries ending with `*`
+    /// match all the ClusterRoles starting with the given prefix.
+    pub exempt_cluster_roles: HashSet<String>,
+    /// Groups allowed to create and update ClusterRoles reading the Secrets
+    pub exempt_groups: HashSet<String>,
+    /// What to do when a ClusterRole grants read access to the Secrets
+    pub mode: Mode,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            allowed_service_accounts: Vec::new(),
+            exempt_cluster_roles: HashSet::new(),
+            exempt_groups: ["system:masters".to_owned()].into(),
+            mode: Mode::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the user is one of the allowed ServiceAccounts
+    pub fn is_user_allowed(&self, username: Option<&str>) -> bool {
+        username.is_some_and(|username| {
+            self.allowed_service_accounts
+                .iter()
+                .any(|service_account| service_account.username() == username)
+        })
+    }
+
+    /// Returns true when one of the groups of the user can create
+    /// ClusterRoles reading the Secrets
+    pub fn is_group_exempt(&self, groups: &[String]) -> bool {
+        groups
+            .iter()
+            .any(|group| self.exempt_groups.contains(group))
+    }
+
+    /// Returns true when the ClusterRole can read the Secrets
+    pub fn is_cluster_role_exempt(&self, name: &str) -> bool {
+        self.exempt_cluster_roles
+            .iter()
+            .any(|exempt_role| match exempt_role.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => exempt_role == name,
+            })
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_service_accounts.iter().any(|service_account| {
+            service_account.namespace.is_empty() || service_account.name.is_empty()
+        }) {
+            return Err(
+                "allowedServiceAccounts entries must have both a namespace and a name".to_owned(),
+            );
+        }
+        if self
+            .exempt_cluster_roles
+            .iter()
+            .any(|role| role.is_empty() || role == "*")
+        {
+            return Err("exemptClusterRoles cannot contain empty values or '*'".to_owned());
+        }
+        if self.exempt_groups.contains("") {
+            return Err("exemptGroups cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::allowed(Some("system:serviceaccount:cert-manager:cert-manager"), true)]
+    #[case::other_namespace(Some("system:serviceaccount:default:cert-manager"), false)]
+    #[case::user(Some("cert-manager"), false)]
+    #[case::anonymous(None, false)]
+    fn allowed_users(#[case] username: Option<&str>, #[case] expected: bool) {
+        let settings = Settings {
+            allowed_service_accounts: vec![ServiceAccount {
+                namespace: "cert-manager".to_owned(),
+                name: "cert-manager".to_owned(),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(settings.is_user_allowed(username), expected);
+    }
+
+    #[rstest]
+    #[case::prefix_match("secret-reader", true)]
+    #[case::system_role("system:controller:namespace-controller", false)]
+    #[case::not_exempt("tls-reader", false)]
+    fn exempt_cluster_roles(#[case] name: &str, #[case] expected: bool) {
+        let settings = Settings {
+            exempt_cluster_roles: ["secret-*".to_owned()].into(),
+            ..Default::default()
+        };
+        assert_eq!(settings.is_cluster_role_exempt(name), expected);
+    }
+
+    #[test]
+    fn no_exempt_cluster_roles_by_default() {
+        assert!(
+            !Settings::default().is_cluster_role_exempt("system:controller:namespace-controller")
+        );
+    }
+
+    #[rstest]
+    #[case::exempt_group(vec!["system:authenticated", "system:masters"], true)]
+    #[case::not_exempt_group(vec!["system:authenticated"], false)]
+    fn exempt_groups(#[case] groups: Vec<&str>, #[case] expected: bool) {
+        let groups: Vec<String> = groups.into_iter().map(String::from).collect();
+        assert_eq!(Settings::default().is_group_exempt(&groups), expected);
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::service_account(
+        r#"{"allowedServiceAccounts": [{"namespace": "cert-manager", "name": "cert-manager"}]}"#,
+        true
+    )]
+    #[case::service_account_without_namespace(
+        r#"{"allowedServiceAccounts": [{"namespace": "", "name": "cert-manager"}]}"#,
+        false
+    )]
+    #[case::exempt_everything(r#"{"exemptClusterRoles": ["*"]}"#, false)]
+    #[case::empty_group(r#"{"exemptGroups": [""]}"#, false)]
+    #[case::warn(r#"{"mode": "warn"}"#, true)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,41 @@
+#!/usr/bin/env bats
+
+@test "Reject a Role using wildcards" {
+	run kwctl run --request-path test_data/role_wildcard.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Role 'team-a-admin' cannot use wildcards: rule #1 uses '\*' in verbs, resources, apiGroups.*") -ne 0 ]
+}
+
+@test "Accept a Role using wildcards created by an exempt group" {
+	run kwctl run --request-path test_data/role_wildcard.json --settings-json '{"exemptGroups": ["break-glass"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a ClusterRole without wildcards" {
+	run kwctl run --request-path test_data/clusterrole_scoped.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject settings exempting all the roles" {
+	run kwctl run --request-path test_data/clusterrole_scoped.json --settings-json '{"exemptRoles": ["*"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,154 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::rbac::v1::PolicyRule;
+use role_rules::extract_rules;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let kind = request.kind.kind.as_str();
+    let Some((metadata, rules)) = extract_rules(kind, request.object)? else {
+        return kubewarden::accept_request();
+    };
+
+    let name = metadata.name.unwrap_or_default();
+    if settings.is_role_exempt(&name)
+        || settings.is_group_exempt(&request.user_info.groups.unwrap_or_default())
+    {
+        return kubewarden::accept_request();
+    }
+
+    let errors = find_wildcards(&rules);
+    if !errors.is_empty() {
+        return kubewarden::reject_request(
+            Some(format!(
+                "{kind} '{name}' cannot use wildcards: {}",
+                errors.join("; ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+// Returns a description of the wildcards used by each rule
+fn find_wildcards(rules: &[PolicyRule]) -> Vec<String> {
+    rules
+        .iter()
+        .enumerate()
+        .filter_map(|(index, rule)| {
+            let fields: Vec<&str> = [
+                ("verbs", Some(&rule.verbs)),
+                ("resources", rule.resources.as_ref()),
+                ("apiGroups", rule.api_groups.as_ref()),
+            ]
+            .into_iter()
+            .filter(|(_, values)| {
+                values.is_some_and(|values| values.iter().any(|value| value.contains('*')))
+            })
+            .map(|(field, _)| field)
+            .collect();
+            if fields.is_empty() {
+                None
+            } else {
+                Some(format!("rule #{index} uses '*' in {}", fields.join(", ")))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn rule(api_groups: &[&str], resources: &[&str], verbs: &[&str]) -> PolicyRule {
+        let to_vec = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        PolicyRule {
+            api_groups: Some(to_vec(api_groups)),
+            resources: Some(to_vec(resources)),
+            verbs: to_vec(verbs),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::no_wildcards(vec![rule(&[""], &["pods"], &["get", "list"])], vec![])]
+    #[case::wildcard_verbs(
+        vec![rule(&[""], &["pods"], &["*"])],
+        vec!["rule #0 uses '*' in verbs"]
+    )]
+    #[case::wildcard_subresources(
+        vec![rule(&[""], &["pods"], &["get"]), rule(&["apps"], &["deployments/*"], &["get"])],
+        vec!["rule #1 uses '*' in resources"]
+    )]
+    #[case::wildcard_everything(
+        vec![rule(&["*"], &["*"], &["*"])],
+        vec!["rule #0 uses '*' in verbs, resources, apiGroups"]
+    )]
+    #[case::non_resource_urls(
+        vec![PolicyRule {
+            non_resource_urls: Some(vec!["/healthz".to_owned()]),
+            verbs: vec!["get".to_owned()],
+            ..Default::default()
+        }],
+        vec![]
+    )]
+    fn wildcards(#[case] rules: Vec<PolicyRule>, #[case] expected: Vec<&str>) {
+        assert_eq!(find_wildcards(&rules), expected);
+    }
+
+    #[rstest]
+    #[case::wildcard_role("test_data/role_wildcard.json", Settings::default(), false)]
+    #[case::exempt_role(
+        "test_data/role_wildcard.json",
+        Settings {
+            exempt_roles: ["team-a-*".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::exempt_group(
+        "test_data/role_wildcard.json",
+        Settings {
+            exempt_groups: ["break-glass".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::scoped_cluster_role("test_data/clusterrole_scoped.json", Settings::default(), true)]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Roles and ClusterRoles that can use wildcards. Entries ending with
+    /// `*` match all the roles starting with the given prefix.
+    pub exempt_roles: HashSet<String>,
+    /// Groups allowed to create and update roles using wildcards
+    pub exempt_groups: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            exempt_roles: ["cluster-admin".to_owned()].into(),
+            exempt_groups: ["system:masters".to_owned()].into(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the role can use wildcards
+    pub fn is_role_exempt(&self, name: &str) -> bool {
+        self.exempt_roles
+            .iter()
+            .any(|exempt_role| match exempt_role.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => exempt_role == name,
+            })
+    }
+
+    /// Returns true when one of the groups of the user can create roles
+    /// using wildcards
+    pub fn is_group_exempt(&self, groups: &[String]) -> bool {
+        groups
+            .iter()
+            .any(|group| self.exempt_groups.contains(group))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self
+            .exempt_roles
+            .iter()
+            .any(|role| role.is_empty() || role == "*")
+        {
+            return Err("exemptRoles cannot contain empty values or '*'".to_owned());
+        }
+        if self.exempt_groups.contains("") {
+            return Err("exemptGroups cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::exact_match("cluster-admin", true)]
+    #[case::system_role("system:controller:namespace-controller", false)]
+    #[case::not_exempt("team-a-admin", false)]
+    #[case::partial_name("cluster-admin-copy", false)]
+    fn exempt_roles(#[case] name: &str, #[case] expected: bool) {
+        assert_eq!(Settings::default().is_role_exempt(name), expected);
+    }
+
+    #[rstest]
+    #[case::prefix_match("team-a-admin", true)]
+    #[case::other_prefix("team-b-admin", false)]
+    fn exempt_role_prefixes(#[case] name: &str, #[case] expected: bool) {
+        let settings = Settings {
+            exempt_roles: ["team-a-*".to_owned()].into(),
+            ..Default::default()
+        };
+        assert_eq!(settings.is_role_exempt(name), expected);
+    }
+
+    #[rstest]
+    #[case::exempt_group(vec!["system:authenticated", "system:masters"], true)]
+    #[case::not_exempt_group(vec!["system:authenticated"], false)]
+    fn exempt_groups(#[case] groups: Vec<&str>, #[case] expected: bool) {
+        let groups: Vec<String> = groups.into_iter().map(String::from).collect();
+        assert_eq!(Settings::default().is_group_exempt(&groups), expected);
+    }
+
+    #[rstest]
+    #[case::defaults(Settings::default(), true)]
+    #[case::exempt_everything(Settings {
+        exempt_roles: ["*".to_owned()].into(),
+        ..Default::default()
+    }, false)]
+    #[case::empty_role(Settings {
+        exempt_roles: [String::new()].into(),
+        ..Default::default()
+    }, false)]
+    #[case::empty_group(Settings {
+        exempt_groups: [String::new()].into(),
+        ..Default::default()
+    }, false)]
+    fn validate_settings(#[case] settings: Settings, #[case] is_valid: bool) {
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
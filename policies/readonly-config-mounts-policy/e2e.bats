@@ -0,0 +1,78 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod mounting its configuration read only" {
+	run kwctl run \
+		--request-path test_data/pod_read_only_mounts.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patch".*') -eq 0 ]
+}
+
+@test "Reject a Pod not setting readOnly" {
+	run kwctl run \
+		--request-path test_data/pod_read_only_unset.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the Secret volume 'tls' must be mounted with readOnly set to true.*") -ne 0 ]
+}
+
+@test "Mutate a Pod not setting readOnly" {
+	run kwctl run \
+		--request-path test_data/pod_read_only_unset.json \
+		--settings-json '{"mutate": true}' \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+}
+
+@test "Reject a Deployment mounting its configuration writable" {
+	run kwctl run \
+		--request-path test_data/deployment_writable_mounts.json \
+		--settings-json '{"mutate": true}' \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Reject a Pod mounting an entire Secret" {
+	run kwctl run \
+		--request-path test_data/pod_secret_without_items.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the Secret 'web-tls' must be mounted selecting its keys with items.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_read_only_mounts.json --settings-json '{"mutate": "yes"}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::Volume;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+/// Fields of the Pod spec used by the policy. The volumes are only read: they
+/// are not serialized, so the mutation leaves them untouched.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    #[serde(default, skip_serializing)]
+    volumes: Option<Vec<Volume>>,
+    #[serde(default)]
+    containers: Vec<ContainerFields>,
+    init_containers: Option<Vec<ContainerFields>>,
+    ephemeral_containers: Option<Vec<ContainerFields>>,
+}
+
+/// Container with its volumeMounts. The other fields are kept in `other`, so
+/// that the mutation does not drop the fields unknown to k8s-openapi.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct ContainerFields {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    volume_mounts: Option<Vec<VolumeMountFields>>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct VolumeMountFields {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    read_only: Option<bool>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    let request = &validation_request.request;
+    let mut pod_spec =
+        match partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object) {
+            Ok(Some(pod_spec)) => pod_spec,
+            // The resources without Pod spec are not validated
+            Ok(None) => return kubewarden::accept_request(),
+            Err(_) => {
+                return kubewarden::reject_request(
+                    Some("Cannot parse validation request".to_owned()),
+                    None,
+                    None,
+                    None,
+                );
+            }
+        };
+
+    match validate_pod_spec(&mut pod_spec, &validation_request.settings) {
+        Ok(false) => kubewarden::accept_request(),
+        Ok(true) => kubewarden::mutate_request(partial_pod_spec::patch(
+            &request.kind.kind,
+            &request.object,
+            &pod_spec,
+        )?),
+        Err(error) => kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        ),
+    }
+}
+
+// Returns the kind of the volume when it exposes configuration or
+// credentials to the containers
+fn config_volume_kind(volume: &Volume) -> Option<&'static str> {
+    if volume.secret.is_some() {
+        Some("Secret")
+    } else if volume.config_map.is_some() {
+        Some("ConfigMap")
+    } else if volume.downward_api.is_some() {
+        Some("downwardAPI")
+    } else if volume.projected.is_some() {
+        Some("projected")
+    } else {
+        None
+    }
+}
+
+// Returns the names of the Secrets the volume mounts entirely, without
+// selecting their keys with `items`
+fn secrets_without_items(volume: &Volume) -> Vec<&str> {
+    let secret = volume
+        .secret
+        .iter()
+        .filter(|secret| secret.items.as_ref().is_none_or(Vec::is_empty))
+        .map(|secret| secret.secret_name.as_deref().unwrap_or_default());
+    let projected_secrets = volume
+        .projected
+        .iter()
+        .flat_map(|projected| projected.sources.iter().flatten())
+        .filter_map(|source| source.secret.as_ref())
+        .filter(|secret| secret.items.as_ref().is_none_or(Vec::is_empty))
+        .map(|secret| secret.name.as_str());
+    secret.chain(projected_secrets).collect()
+}
+
+// Checks the volumeMounts of a container. The ones not defining `readOnly`
+// are set to read only when the mutation is enabled. Returns true when a
+// volumeMount has been changed.
+fn validate_volume_mounts(
+    container_name: &str,
+    volume_mounts: Option<&mut Vec<VolumeMountFields>>,
+    config_volumes: &HashMap<String, &str>,
+    mutate: bool,
+    errors: &mut Vec<String>,
+) -> bool {
+    let mut mutated = false;
+    for volume_mount in volume_mounts.into_iter().flatten() {
+        let Some(kind) = config_volumes.get(&volume_mount.name) else {
+            continue;
+        };
+        match volume_mount.read_only {
+            Some(true) => {}
+            None if mutate => {
+                volume_mount.read_only = Some(true);
+                mutated = true;
+            }
+            _ => errors.push(format!(
+                "container '{container_name}': the {kind} volume '{}' must be mounted with readOnly set to true",
+                volume_mount.name
+            )),
+        }
+    }
+    mutated
+}
+
+// Returns true when the Pod spec has been mutated
+fn validate_pod_spec(pod_spec: &mut PodSpecFields, settings: &Settings) -> Result<bool, String> {
+    let mut errors = vec![];
+    let volumes = pod_spec.volumes.iter().flatten();
+    if settings.require_secret_items {
+        for volume in volumes.clone() {
+            errors.extend(secrets_without_items(volume).into_iter().map(|secret| {
+                format!(
+                    "volume '{}': the Secret '{secret}' must be mounted selecting its keys with items",
+                    volume.name
+                )
+            }));
+        }
+    }
+    let config_volumes: HashMap<String, &str> = volumes
+        .filter_map(|volume| config_volume_kind(volume).map(|kind| (volume.name.clone(), kind)))
+        .collect();
+
+    let mut mutated = false;
+    for container in pod_spec
+        .init_containers
+        .iter_mut()
+        .flatten()
+        .chain(pod_spec.containers.iter_mut())
+    {
+        mutated |= validate_volume_mounts(
+            &container.name,
+            container.volume_mounts.as_mut(),
+            &config_volumes,
+            settings.mutate,
+            &mut errors,
+        );
+    }
+    for container in pod_spec.ephemeral_containers.iter_mut().flatten() {
+        mutated |= validate_volume_mounts(
+            &container.name,
+            container.volume_mounts.as_mut(),
+            &config_volumes,
+            settings.mutate,
+            &mut errors,
+        );
+    }
+
+    if errors.is_empty() {
+        return Ok(mutated);
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn settings(mutate: bool) -> Settings {
+        Settings {
+            mutate,
+            require_secret_items: true,
+        }
+    }
+
+    #[rstest]
+    #[case::read_only_mounts("test_data/pod_read_only_mounts.json", false, true)]
+    #[case::writable_mounts("test_data/deployment_writable_mounts.json", true, false)]
+    #[case::read_only_unset("test_data/pod_read_only_unset.json", false, false)]
+    #[case::secret_without_items("test_data/pod_secret_without_items.json", true, false)]
+    #[case::without_pod_spec("test_data/configmap.json", true, true)]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] mutate: bool,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(mutate),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert!(response.mutated_object.is_none());
+    }
+
+    #[rstest]
+    #[case::writable_mounts(
+        "test_data/deployment_writable_mounts.json",
+        "Deployment 'web': container 'web': the ConfigMap volume 'config' must be mounted with readOnly set to true; container 'web': the projected volume 'credentials' must be mounted with readOnly set to true"
+    )]
+    #[case::read_only_unset(
+        "test_data/pod_read_only_unset.json",
+        "Pod 'web': container 'setup': the Secret volume 'tls' must be mounted with readOnly set to true; container 'web': the downwardAPI volume 'podinfo' must be mounted with readOnly set to true"
+    )]
+    #[case::secret_without_items(
+        "test_data/pod_secret_without_items.json",
+        "Pod 'web': volume 'tls': the Secret 'web-tls' must be mounted selecting its keys with items; volume 'credentials': the Secret 'web-credentials' must be mounted selecting its keys with items"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(false),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn mutate_pod() {
+        let test_case = Testcase {
+            name: "mutate pod".to_owned(),
+            fixture_file: "test_data/pod_read_only_unset.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response.mutated_object.expect("the pod should be mutated");
+        assert_eq!(
+            mutated_object["spec"]["initContainers"][0]["volumeMounts"][0]["readOnly"],
+            json!(true)
+        );
+        assert_eq!(
+            mutated_object["spec"]["containers"][0]["volumeMounts"][0]["readOnly"],
+            json!(true)
+        );
+        // The emptyDir volume is left writable
+        assert!(mutated_object["spec"]["containers"][0]["volumeMounts"][1]["readOnly"].is_null());
+    }
+
+    #[test]
+    fn secret_items_not_required() {
+        let test_case = Testcase {
+            name: "secret items not required".to_owned(),
+            fixture_file: "test_data/pod_secret_without_items.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,63 @@
+#!/usr/bin/env bats
+
+@test "Reject Recreate strategy in a high-availability namespace" {
+	run kwctl run \
+		--request-path test_data/deployment_recreate.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-ha-namespace.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Deployment 'ledger': the Recreate strategy is not allowed in the high-availability namespace 'payments'.*") -ne 0 ]
+}
+
+@test "Accept Recreate strategy in another namespace" {
+	run kwctl run \
+		--request-path test_data/deployment_recreate.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-other-namespace.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept exempted Recreate strategy" {
+	run kwctl run \
+		--request-path test_data/deployment_recreate_exempted.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept RollingUpdate strategy" {
+	run kwctl run \
+		--request-path test_data/deployment_rolling_update.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/deployment_recreate.json --settings-json '{"namespaceLabels": {}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
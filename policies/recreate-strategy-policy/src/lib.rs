@@ -0,0 +1,216 @@
+use guest::prelude::*;
+use k8s_openapi::Resource;
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::Namespace;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::GetResourceRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::get_resource;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::get_resource;
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+
+    let deployment = match serde_json::from_value::<Deployment>(request.object) {
+        Ok(deployment) => deployment,
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    if let Err(error) = validate_deployment(
+        &deployment,
+        &request.namespace,
+        &validation_request.settings,
+    ) {
+        return kubewarden::reject_request(
+            Some(format!("Deployment '{}': {error}", request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_deployment(
+    deployment: &Deployment,
+    namespace: &str,
+    settings: &Settings,
+) -> Result<(), String> {
+    let uses_recreate = deployment
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.strategy.as_ref())
+        .and_then(|strategy| strategy.type_.as_deref())
+        == Some("Recreate");
+    if !uses_recreate {
+        return Ok(());
+    }
+    let exempted = deployment
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(&settings.exemption_annotation))
+        .is_some_and(|value| value == "true");
+    if exempted {
+        return Ok(());
+    }
+
+    let kube_request = GetResourceRequest {
+        name: namespace.to_owned(),
+        api_version: Namespace::API_VERSION.to_owned(),
+        kind: Namespace::KIND.to_owned(),
+        field_masks: None,
+        namespace: None,
+        disable_cache: false,
+    };
+    let namespace_resource = get_resource::<Namespace>(&kube_request)
+        .map_err(|e| format!("cannot read the namespace '{namespace}': {e}"))?;
+    if !settings.is_high_availability(namespace_resource.metadata.labels.as_ref()) {
+        return Ok(());
+    }
+    Err(format!(
+        "the Recreate strategy is not allowed in the high-availability namespace '{namespace}', set the '{}' annotation to 'true' on workloads that cannot run two versions at the same time",
+        settings.exemption_annotation
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::GetResourceRequest;
+
+        #[allow(dead_code)]
+        pub fn get_resource<T: 'static>(_req: &GetResourceRequest) -> anyhow::Result<T> {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    fn namespace(high_availability: bool) -> Namespace {
+        Namespace {
+            metadata: ObjectMeta {
+                name: Some("payments".to_owned()),
+                labels: Some(BTreeMap::from([(
+                    "kubewarden.io/high-availability".to_owned(),
+                    high_availability.to_string(),
+                )])),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::recreate_in_ha_namespace("test_data/deployment_recreate.json", true, 1, false)]
+    #[case::recreate_in_other_namespace("test_data/deployment_recreate.json", false, 1, true)]
+    #[case::recreate_exempted("test_data/deployment_recreate_exempted.json", true, 0, true)]
+    #[case::rolling_update("test_data/deployment_rolling_update.json", true, 0, true)]
+    #[serial]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] high_availability: bool,
+        #[case] lookups: usize,
+        #[case] expected_validation_result: bool,
+    ) {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<Namespace>()
+            .times(lookups)
+            .returning(move |req| {
+                if req.name != "payments" || req.namespace.is_some() {
+                    return Err(anyhow::anyhow!("it's not searching the expected Namespace"));
+                }
+                Ok(namespace(high_availability))
+            });
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    #[serial]
+    fn rejection_message() {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<Namespace>()
+            .returning(|_| Ok(namespace(true)));
+
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/deployment_recreate.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Deployment 'ledger': the Recreate strategy is not allowed in the high-availability namespace 'payments', set the 'kubewarden.io/allow-recreate-strategy' annotation to 'true' on workloads that cannot run two versions at the same time"
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn missing_namespace() {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<Namespace>()
+            .times(1)
+            .returning(|_| Err(anyhow::anyhow!("not found")));
+
+        let test_case = Testcase {
+            name: "missing namespace".to_owned(),
+            fixture_file: "test_data/deployment_recreate.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some("Deployment 'ledger': cannot read the namespace 'payments': not found")
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Labels of the high-availability namespaces. A namespace must have all
+    /// of them.
+    pub namespace_labels: BTreeMap<String, String>,
+    /// Annotation that, when set to `true` on the Deployment, allows the
+    /// Recreate strategy
+    pub exemption_annotation: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            namespace_labels: BTreeMap::from([(
+                "kubewarden.io/high-availability".to_owned(),
+                "true".to_owned(),
+            )]),
+            exemption_annotation: "kubewarden.io/allow-recreate-strategy".to_owned(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the labels identify a high-availability namespace
+    pub fn is_high_availability(&self, labels: Option<&BTreeMap<String, String>>) -> bool {
+        labels.is_some_and(|labels| {
+            self.namespace_labels
+                .iter()
+                .all(|(key, value)| labels.get(key) == Some(value))
+        })
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.namespace_labels.is_empty() {
+            return Err("namespaceLabels cannot be empty".to_owned());
+        }
+        if self.exemption_annotation.is_empty() {
+            return Err("exemptionAnnotation cannot be empty".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::high_availability(&[("kubewarden.io/high-availability", "true")], true)]
+    #[case::other_labels(
+        &[("kubewarden.io/high-availability", "true"), ("team", "payments")],
+        true
+    )]
+    #[case::other_value(&[("kubewarden.io/high-availability", "false")], false)]
+    #[case::no_label(&[("team", "payments")], false)]
+    fn high_availability(#[case] labels: &[(&str, &str)], #[case] expected: bool) {
+        let labels: BTreeMap<String, String> = labels
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        assert_eq!(
+            Settings::default().is_high_availability(Some(&labels)),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::namespace_labels(r#"{"namespaceLabels": {"tier": "critical"}}"#, true)]
+    #[case::no_namespace_labels(r#"{"namespaceLabels": {}}"#, false)]
+    #[case::empty_exemption_annotation(r#"{"exemptionAnnotation": ""}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,44 @@
+#!/usr/bin/env bats
+
+SETTINGS='{"namespacedRules": [{"kind": "ConfigMap", "allowedPattern": "[a-z0-9]+-[a-z0-9-]+"}, {"group": "", "kind": "Service", "deniedPattern": ".*-svc-svc"}], "clusterRules": [{"kind": "*", "allowedPattern": "acme-.*"}]}'
+
+@test "Accept a ConfigMap following the naming conventions" {
+	run kwctl run --request-path test_data/configmap_web_config.json --settings-json "$SETTINGS" annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Service matching the denied pattern" {
+	run kwctl run --request-path test_data/service_web_svc_svc.json --settings-json "$SETTINGS" annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Service 'web-svc-svc' does not follow the naming conventions: the name must not match '.\*-svc-svc'.*") -ne 0 ]
+}
+
+@test "Reject a cluster-scoped resource not matching the allowed pattern" {
+	run kwctl run --request-path test_data/clusterrole_secrets_reader.json --settings-json "$SETTINGS" annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*ClusterRole 'secrets-reader' does not follow the naming conventions: the name must match 'acme-.\*'.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/configmap_web_config.json --settings-json '{"namespacedRules": [{"kind": "ConfigMap"}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
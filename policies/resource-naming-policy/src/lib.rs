@@ -0,0 +1,136 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let metadata: ObjectMeta = match request.object.get("metadata") {
+        Some(metadata) => serde_json::from_value(metadata.clone())?,
+        None => ObjectMeta::default(),
+    };
+    // The names generated by the API server are not known yet
+    let Some(name) = metadata.name.filter(|name| !name.is_empty()) else {
+        return kubewarden::accept_request();
+    };
+
+    let violations: Vec<String> = settings
+        .rules_for(&request.kind, !request.namespace.is_empty())
+        .flat_map(|rule| rule.violations(&name))
+        .collect();
+    if violations.is_empty() {
+        return kubewarden::accept_request();
+    }
+    kubewarden::reject_request(
+        Some(format!(
+            "{} '{name}' does not follow the naming conventions: {}",
+            request.kind.kind,
+            violations.join(", ")
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    use settings::Rule;
+
+    fn settings() -> Settings {
+        Settings {
+            namespaced_rules: vec![
+                Rule {
+                    kind: "ConfigMap".to_owned(),
+                    allowed_pattern: Some("[a-z0-9]+-[a-z0-9-]+".to_owned()),
+                    ..Default::default()
+                },
+                Rule {
+                    group: Some("".to_owned()),
+                    kind: "Service".to_owned(),
+                    denied_pattern: Some(".*-svc-svc".to_owned()),
+                    ..Default::default()
+                },
+            ],
+            cluster_rules: vec![Rule {
+                kind: "*".to_owned(),
+                allowed_pattern: Some("acme-.*".to_owned()),
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[rstest]
+    #[case::configmap("test_data/configmap_web_config.json", settings(), true)]
+    #[case::service("test_data/service_web_svc_svc.json", settings(), false)]
+    #[case::cluster_role("test_data/clusterrole_secrets_reader.json", settings(), false)]
+    #[case::no_cluster_rules(
+        "test_data/clusterrole_secrets_reader.json",
+        Settings {
+            cluster_rules: vec![],
+            ..settings()
+        },
+        true
+    )]
+    #[case::namespaced_rules_only_for_namespaced_resources(
+        "test_data/configmap_web_config.json",
+        Settings {
+            namespaced_rules: vec![],
+            ..settings()
+        },
+        true
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/service_web_svc_svc.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Service 'web-svc-svc' does not follow the naming conventions: the name must not match '.*-svc-svc'"
+            )
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use kubewarden::request::GroupVersionKind;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Naming convention of the resources of a kind
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct Rule {
+    /// API group of the resources. When not set, the rule applies to the
+    /// resources of all the groups.
+    pub group: Option<String>,
+    /// Kind of the resources, `*` matches all the kinds
+    pub kind: String,
+    /// Regular expression the names must match
+    pub allowed_pattern: Option<String>,
+    /// Regular expression the names must not match
+    pub denied_pattern: Option<String>,
+}
+
+impl Rule {
+    /// Returns true when the rule applies to the resources of the kind
+    pub fn matches(&self, gvk: &GroupVersionKind) -> bool {
+        self.group.as_ref().is_none_or(|group| *group == gvk.group)
+            && (self.kind == "*" || self.kind == gvk.kind)
+    }
+
+    /// Returns the reasons why the name does not follow the rule
+    pub fn violations(&self, name: &str) -> Vec<String> {
+        let mut violations = Vec::new();
+        if let Some(pattern) = &self.allowed_pattern
+            && !name_regex(pattern).is_ok_and(|regex| regex.is_match(name))
+        {
+            violations.push(format!("the name must match '{pattern}'"));
+        }
+        if let Some(pattern) = &self.denied_pattern
+            && name_regex(pattern).is_ok_and(|regex| regex.is_match(name))
+        {
+            violations.push(format!("the name must not match '{pattern}'"));
+        }
+        violations
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.kind.is_empty() {
+            return Err("the kind of the rules cannot be empty".to_owned());
+        }
+        if self.group.as_ref().is_some_and(|group| group == "*") {
+            return Err(format!(
+                "rule of kind '{}': omit the group to match all the groups",
+                self.kind
+            ));
+        }
+        if self.allowed_pattern.is_none() && self.denied_pattern.is_none() {
+            return Err(format!(
+                "rule of kind '{}': allowedPattern or deniedPattern must be provided",
+                self.kind
+            ));
+        }
+        for pattern in self.allowed_pattern.iter().chain(&self.denied_pattern) {
+            name_regex(pattern).map_err(|e| {
+                format!(
+                    "rule of kind '{}': invalid pattern '{pattern}': {e}",
+                    self.kind
+                )
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Compiles the pattern, which must match the whole name
+fn name_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{pattern})$"))
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Rules applied to the namespaced resources
+    pub namespaced_rules: Vec<Rule>,
+    /// Rules applied to the cluster-scoped resources
+    pub cluster_rules: Vec<Rule>,
+}
+
+impl Settings {
+    /// Returns the rules applying to the resources of the kind. The rules of
+    /// the namespaced resources are used when `namespaced` is true, the ones
+    /// of the cluster-scoped resources otherwise.
+    pub fn rules_for<'a>(
+        &'a self,
+        gvk: &'a GroupVersionKind,
+        namespaced: bool,
+    ) -> impl Iterator<Item = &'a Rule> {
+        let rules = if namespaced {
+            &self.namespaced_rules
+        } else {
+            &self.cluster_rules
+        };
+        rules.iter().filter(|rule| rule.matches(gvk))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.namespaced_rules.is_empty() && self.cluster_rules.is_empty() {
+            return Err("at least one rule must be provided".to_owned());
+        }
+        for rule in &self.namespaced_rules {
+            rule.validate()
+                .map_err(|e| format!("invalid namespacedRules: {e}"))?;
+        }
+        for rule in &self.cluster_rules {
+            rule.validate()
+                .map_err(|e| format!("invalid clusterRules: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    fn gvk(group: &str, kind: &str) -> GroupVersionKind {
+        GroupVersionKind {
+            group: group.to_owned(),
+            version: "v1".to_owned(),
+            kind: kind.to_owned(),
+        }
+    }
+
+    #[rstest]
+    #[case::same_kind(None, "ConfigMap", gvk("", "ConfigMap"), true)]
+    #[case::other_kind(None, "ConfigMap", gvk("", "Secret"), false)]
+    #[case::all_kinds(None, "*", gvk("apps", "Deployment"), true)]
+    #[case::same_group(Some("apps"), "*", gvk("apps", "Deployment"), true)]
+    #[case::other_group(Some("apps"), "Deployment", gvk("example.com", "Deployment"), false)]
+    #[case::core_group(Some(""), "*", gvk("", "Service"), true)]
+    fn rule_matches(
+        #[case] group: Option<&str>,
+        #[case] kind: &str,
+        #[case] gvk: GroupVersionKind,
+        #[case] expected: bool,
+    ) {
+        let rule = Rule {
+            group: group.map(str::to_owned),
+            kind: kind.to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(rule.matches(&gvk), expected);
+    }
+
+    #[rstest]
+    #[case::allowed("web-config", vec![])]
+    #[case::not_allowed("config", vec!["the name must match '[a-z0-9]+-[a-z0-9-]+'"])]
+    #[case::denied("web-svc-svc", vec!["the name must not match '.*-svc-svc'"])]
+    fn rule_violations(#[case] name: &str, #[case] expected: Vec<&str>) {
+        let rule = Rule {
+            kind: "*".to_owned(),
+            allowed_pattern: Some("[a-z0-9]+-[a-z0-9-]+".to_owned()),
+            denied_pattern: Some(".*-svc-svc".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(rule.violations(name), expected);
+    }
+
+    #[rstest]
+    #[case::no_rules(r#"{}"#, false)]
+    #[case::namespaced_rule(
+        r#"{"namespacedRules": [{"kind": "ConfigMap", "allowedPattern": "[a-z0-9]+-[a-z0-9-]+"}]}"#,
+        true
+    )]
+    #[case::cluster_rule(
+        r#"{"clusterRules": [{"group": "rbac.authorization.k8s.io", "kind": "*", "deniedPattern": "system:.*"}]}"#,
+        true
+    )]
+    #[case::without_kind(r#"{"namespacedRules": [{"allowedPattern": "[a-z]+"}]}"#, false)]
+    #[case::wildcard_group(
+        r#"{"namespacedRules": [{"group": "*", "kind": "*", "allowedPattern": "[a-z]+"}]}"#,
+        false
+    )]
+    #[case::without_patterns(r#"{"namespacedRules": [{"kind": "ConfigMap"}]}"#, false)]
+    #[case::invalid_pattern(
+        r#"{"clusterRules": [{"kind": "ClusterRole", "deniedPattern": "system:("}]}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,91 @@
+#!/usr/bin/env bats
+
+@test "Accept a standalone Pod with an allowed restart policy" {
+	run kwctl run \
+		--request-path test_data/pod_standalone.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a standalone Pod restarting always" {
+	run kwctl run \
+		--request-path test_data/pod_standalone_always.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*restartPolicy Always is not allowed.*') -ne 0 ]
+}
+
+@test "Reject a standalone Pod in a forbidden namespace" {
+	run kwctl run \
+		--request-path test_data/pod_standalone_prod.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*standalone Pods are not allowed in the 'prod' namespace.*") -ne 0 ]
+}
+
+@test "Accept a Pod managed by a controller in a forbidden namespace" {
+	run kwctl run \
+		--request-path test_data/pod_controlled_prod.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Job with a restart policy not allowed" {
+	run kwctl run \
+		--request-path test_data/job_on_failure.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*restartPolicy OnFailure is not allowed, use one of: Never.*') -ne 0 ]
+}
+
+@test "Reject a CronJob with a restart policy not allowed" {
+	run kwctl run \
+		--request-path test_data/cronjob_on_failure.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/job_never.json --settings-json '{"jobs": {"allowedRestartPolicies": ["Always"]}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
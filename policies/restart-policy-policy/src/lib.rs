@@ -0,0 +1,183 @@
+use std::collections::BTreeSet;
+
+use guest::prelude::*;
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{Pod, PodSpec};
+use kubewarden_policy_sdk::wapc_guest as guest;
+use workloads::is_controlled;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let result = match (request.kind.group.as_str(), request.kind.kind.as_str()) {
+        ("", "Pod") => serde_json::from_value::<Pod>(request.object)
+            .map(|pod| validate_pod(&pod, &request.namespace, settings)),
+        ("batch", "Job") => serde_json::from_value::<Job>(request.object).map(|job| {
+            let template = job.spec.unwrap_or_default().template;
+            validate_restart_policy(
+                template.spec.as_ref(),
+                &settings.jobs.allowed_restart_policies,
+            )
+        }),
+        ("batch", "CronJob") => serde_json::from_value::<CronJob>(request.object).map(|cronjob| {
+            let template = cronjob
+                .spec
+                .unwrap_or_default()
+                .job_template
+                .spec
+                .unwrap_or_default()
+                .template;
+            validate_restart_policy(
+                template.spec.as_ref(),
+                &settings.jobs.allowed_restart_policies,
+            )
+        }),
+        _ => return kubewarden::accept_request(),
+    };
+    match result {
+        Ok(Ok(())) => kubewarden::accept_request(),
+        Ok(Err(error)) => kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        ),
+        Err(_) => kubewarden::reject_request(
+            Some("Cannot parse validation request".to_owned()),
+            None,
+            None,
+            None,
+        ),
+    }
+}
+
+// Only the standalone Pods are validated: the Pods managed by a controller
+// are validated through their controller, and the restart policy of their
+// template.
+fn validate_pod(pod: &Pod, namespace: &str, settings: &Settings) -> Result<(), String> {
+    if is_controlled(&pod.metadata) {
+        return Ok(());
+    }
+    if settings.pods.forbidden_namespaces.contains(namespace) {
+        return Err(format!(
+            "standalone Pods are not allowed in the '{namespace}' namespace, use a workload resource like a Deployment or a Job"
+        ));
+    }
+    validate_restart_policy(pod.spec.as_ref(), &settings.pods.allowed_restart_policies)
+}
+
+// Returns an error when the restart policy is not allowed. Kubernetes uses
+// Always when it is not set.
+fn validate_restart_policy(
+    spec: Option<&PodSpec>,
+    allowed: &BTreeSet<String>,
+) -> Result<(), String> {
+    let restart_policy = spec
+        .and_then(|spec| spec.restart_policy.as_deref())
+        .unwrap_or("Always");
+    if allowed.is_empty() || allowed.contains(restart_policy) {
+        return Ok(());
+    }
+    Err(format!(
+        "restartPolicy {restart_policy} is not allowed, use one of: {}",
+        allowed.iter().cloned().collect::<Vec<String>>().join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::settings::{JobSettings, PodSettings};
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            jobs: JobSettings {
+                allowed_restart_policies: BTreeSet::from(["Never".to_owned()]),
+            },
+            pods: PodSettings {
+                allowed_restart_policies: BTreeSet::from([
+                    "Never".to_owned(),
+                    "OnFailure".to_owned(),
+                ]),
+                forbidden_namespaces: BTreeSet::from(["prod".to_owned()]),
+            },
+        }
+    }
+
+    #[rstest]
+    #[case::standalone_pod("test_data/pod_standalone.json", true)]
+    #[case::standalone_pod_always("test_data/pod_standalone_always.json", false)]
+    #[case::standalone_pod_in_prod("test_data/pod_standalone_prod.json", false)]
+    #[case::controlled_pod_in_prod("test_data/pod_controlled_prod.json", true)]
+    #[case::job_never("test_data/job_never.json", true)]
+    #[case::job_on_failure("test_data/job_on_failure.json", false)]
+    #[case::cronjob_on_failure("test_data/cronjob_on_failure.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::standalone_pod_always("test_data/pod_standalone_always.json", true)]
+    #[case::standalone_pod_in_prod("test_data/pod_standalone_prod.json", true)]
+    #[case::job_on_failure("test_data/job_on_failure.json", true)]
+    fn default_settings(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::standalone_pod_always(
+        "test_data/pod_standalone_always.json",
+        "Pod 'debug': restartPolicy Always is not allowed, use one of: Never, OnFailure"
+    )]
+    #[case::standalone_pod_in_prod(
+        "test_data/pod_standalone_prod.json",
+        "Pod 'debug': standalone Pods are not allowed in the 'prod' namespace, use a workload resource like a Deployment or a Job"
+    )]
+    #[case::cronjob_on_failure(
+        "test_data/cronjob_on_failure.json",
+        "CronJob 'cleanup': restartPolicy OnFailure is not allowed, use one of: Never"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+const POD_RESTART_POLICIES: &[&str] = &["Always", "OnFailure", "Never"];
+const JOB_RESTART_POLICIES: &[&str] = &["OnFailure", "Never"];
+
+/// Restart policies of the Pods created by the Jobs and the CronJobs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct JobSettings {
+    /// Allowed values of the `restartPolicy` of the Pod templates
+    pub allowed_restart_policies: BTreeSet<String>,
+}
+
+impl Default for JobSettings {
+    fn default() -> Self {
+        JobSettings {
+            allowed_restart_policies: JOB_RESTART_POLICIES
+                .iter()
+                .map(|policy| policy.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Restart policies of the Pods that are not managed by a controller
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct PodSettings {
+    /// Allowed values of `restartPolicy`. All the values are allowed when
+    /// empty.
+    pub allowed_restart_policies: BTreeSet<String>,
+    /// Namespaces where the standalone Pods are not allowed at all
+    pub forbidden_namespaces: BTreeSet<String>,
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    pub jobs: JobSettings,
+    pub pods: PodSettings,
+}
+
+// Returns an error when one of the restart policies is unknown
+fn validate_restart_policies(
+    name: &str,
+    allowed: &BTreeSet<String>,
+    known: &[&str],
+) -> Result<(), String> {
+    match allowed
+        .iter()
+        .find(|policy| !known.contains(&policy.as_str()))
+    {
+        Some(unknown) => Err(format!(
+            "{name}: unknown value '{unknown}', use one of: {}",
+            known.join(", ")
+        )),
+        None => Ok(()),
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.jobs.allowed_restart_policies.is_empty() {
+            return Err("jobs.allowedRestartPolicies cannot be empty".to_owned());
+        }
+        validate_restart_policies(
+            "jobs.allowedRestartPolicies",
+            &self.jobs.allowed_restart_policies,
+            JOB_RESTART_POLICIES,
+        )?;
+        validate_restart_policies(
+            "pods.allowedRestartPolicies",
+            &self.pods.allowed_restart_policies,
+            POD_RESTART_POLICIES,
+        )?;
+        if self.pods.forbidden_namespaces.iter().any(String::is_empty) {
+            return Err("pods.forbiddenNamespaces cannot contain empty names".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "jobs": {"allowedRestartPolicies": ["Never"]},
+            "pods": {"allowedRestartPolicies": ["Never", "OnFailure"], "forbiddenNamespaces": ["prod"]}
+        }"#,
+        true
+    )]
+    #[case::empty_job_restart_policies(r#"{"jobs": {"allowedRestartPolicies": []}}"#, false)]
+    #[case::always_for_jobs(r#"{"jobs": {"allowedRestartPolicies": ["Always"]}}"#, false)]
+    #[case::unknown_pod_restart_policy(
+        r#"{"pods": {"allowedRestartPolicies": ["Sometimes"]}}"#,
+        false
+    )]
+    #[case::empty_namespace(r#"{"pods": {"forbiddenNamespaces": [""]}}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn unknown_setting() {
+        let settings = r#"{"pods": {"forbiddenNamespace": ["prod"]}}"#;
+        assert!(serde_json::from_str::<Settings>(settings).is_err());
+    }
+}
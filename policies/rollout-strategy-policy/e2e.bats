@@ -0,0 +1,65 @@
+#!/usr/bin/env bats
+
+@test "Accept a Deployment within bounds" {
+	run kwctl run \
+		--request-path test_data/deployment_rolling_update.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patch".*') -eq 0 ]
+}
+
+@test "Reject a Deployment above the limit" {
+	run kwctl run \
+		--request-path test_data/deployment_rolling_update_above_limit.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*maxUnavailable 50% is above the limit of 25% for 4 replicas.*') -ne 0 ]
+}
+
+@test "Reject a Deployment using the Recreate strategy" {
+	run kwctl run \
+		--request-path test_data/deployment_recreate.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*the Recreate strategy is not allowed, use RollingUpdate.*') -ne 0 ]
+}
+
+@test "Accept a Deployment without strategy within the bounds" {
+	run kwctl run \
+		--request-path test_data/deployment_without_strategy.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/deployment_rolling_update.json --settings-json '{"maxSurge": {"minimum": 3, "limit": 1}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
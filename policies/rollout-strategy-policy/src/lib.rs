@@ -0,0 +1,279 @@
+use guest::prelude::*;
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+use intstr::{format_value, scaled_value};
+
+mod settings;
+use settings::{Bounds, Settings};
+
+/// Value used by Kubernetes for the rolling update parameters that are not
+/// set. The API server sets it before calling the admission policies.
+const DEFAULT_ROLLING_UPDATE_VALUE: &str = "25%";
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let settings = validation_request.settings;
+
+    let deployment = match serde_json::from_value::<Deployment>(validation_request.request.object) {
+        Ok(deployment) => deployment,
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    if let Err(error) = validate_strategy(&deployment, &settings) {
+        return kubewarden::reject_request(
+            Some(format!(
+                "Deployment '{}': {error}",
+                deployment.metadata.name.unwrap_or_default()
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+
+    kubewarden::accept_request()
+}
+
+fn validate_strategy(deployment: &Deployment, settings: &Settings) -> Result<(), String> {
+    let spec = deployment.spec.clone().unwrap_or_default();
+    let replicas = spec.replicas.unwrap_or(1);
+    let strategy = spec.strategy.unwrap_or_default();
+
+    match strategy.type_.as_deref().unwrap_or("RollingUpdate") {
+        "RollingUpdate" => {}
+        "Recreate" if settings.allow_recreate => return Ok(()),
+        strategy_type => {
+            return Err(format!(
+                "the {strategy_type} strategy is not allowed, use RollingUpdate"
+            ));
+        }
+    }
+
+    let rolling_update = strategy.rolling_update.unwrap_or_default();
+    let default_value = || IntOrString::String(DEFAULT_ROLLING_UPDATE_VALUE.to_owned());
+    let errors: Vec<String> = [
+        (
+            "maxUnavailable",
+            rolling_update.max_unavailable.unwrap_or_else(default_value),
+            &settings.max_unavailable,
+            false,
+        ),
+        (
+            "maxSurge",
+            rolling_update.max_surge.unwrap_or_else(default_value),
+            &settings.max_surge,
+            true,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, value, bounds, round_up)| {
+        validate_bounds(name, &value, bounds, replicas, round_up).err()
+    })
+    .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join(", "))
+}
+
+// Compares the value with its bounds once all of them are scaled on the
+// replicas of the Deployment
+fn validate_bounds(
+    name: &str,
+    value: &IntOrString,
+    bounds: &Bounds,
+    replicas: i32,
+    round_up: bool,
+) -> Result<(), String> {
+    let pods = scaled_value(value, replicas, round_up).map_err(|e| format!("{name}: {e}"))?;
+    if let Some(minimum) = &bounds.minimum
+        && pods < scaled_value(minimum, replicas, round_up).map_err(|e| format!("{name}: {e}"))?
+    {
+        return Err(format!(
+            "{name} {} is below the minimum of {} for {replicas} replicas",
+            format_value(value),
+            format_value(minimum)
+        ));
+    }
+    if let Some(limit) = &bounds.limit
+        && pods > scaled_value(limit, replicas, round_up).map_err(|e| format!("{name}: {e}"))?
+    {
+        return Err(format!(
+            "{name} {} is above the limit of {} for {replicas} replicas",
+            format_value(value),
+            format_value(limit)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn bounded_settings() -> Settings {
+        Settings {
+            max_unavailable: Bounds {
+                minimum: None,
+                limit: Some(IntOrString::String("25%".to_owned())),
+            },
+            max_surge: Bounds {
+                minimum: Some(IntOrString::Int(1)),
+                limit: Some(IntOrString::String("50%".to_owned())),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::within_bounds(IntOrString::Int(1), 4, Ok(()))]
+    #[case::percentage_within_bounds(IntOrString::String("30%".to_owned()), 4, Ok(()))]
+    #[case::above_limit(
+        IntOrString::Int(2),
+        4,
+        Err("maxUnavailable 2 is above the limit of 25% for 4 replicas".to_owned())
+    )]
+    #[case::percentage_above_limit(
+        IntOrString::String("50%".to_owned()),
+        4,
+        Err("maxUnavailable 50% is above the limit of 25% for 4 replicas".to_owned())
+    )]
+    fn max_unavailable_bounds(
+        #[case] value: IntOrString,
+        #[case] replicas: i32,
+        #[case] expected: Result<(), String>,
+    ) {
+        let settings = bounded_settings();
+        assert_eq!(
+            validate_bounds(
+                "maxUnavailable",
+                &value,
+                &settings.max_unavailable,
+                replicas,
+                false
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn invalid_bound() {
+        let bounds = Bounds {
+            minimum: None,
+            limit: Some(IntOrString::String("half".to_owned())),
+        };
+        assert_eq!(
+            validate_bounds("maxSurge", &IntOrString::Int(1), &bounds, 4, true),
+            Err("maxSurge: invalid percentage 'half'".to_owned())
+        );
+    }
+
+    #[rstest]
+    #[case::within_bounds("test_data/deployment_rolling_update.json", bounded_settings(), true)]
+    #[case::defaults_within_bounds(
+        "test_data/deployment_without_strategy.json",
+        bounded_settings(),
+        true
+    )]
+    #[case::above_limit(
+        "test_data/deployment_rolling_update_above_limit.json",
+        bounded_settings(),
+        false
+    )]
+    #[case::recreate("test_data/deployment_recreate.json", Settings::default(), false)]
+    #[case::recreate_allowed(
+        "test_data/deployment_recreate.json",
+        Settings {
+            allow_recreate: true,
+            ..Default::default()
+        },
+        true
+    )]
+    fn validate_deployment(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert!(response.mutated_object.is_none());
+    }
+
+    #[rstest]
+    #[case::above_limit(
+        "test_data/deployment_rolling_update_above_limit.json",
+        bounded_settings(),
+        "Deployment 'web': maxUnavailable 50% is above the limit of 25% for 4 replicas, maxSurge 0 is below the minimum of 1 for 4 replicas"
+    )]
+    #[case::recreate(
+        "test_data/deployment_recreate.json",
+        Settings::default(),
+        "Deployment 'web': the Recreate strategy is not allowed, use RollingUpdate"
+    )]
+    fn rejection_message(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_message: &str,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings,
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn reject_deployment_without_strategy() {
+        // Kubernetes applies a surge of 25% to the Deployments without
+        // strategy: one Pod out of four replicas
+        let test_case = Testcase {
+            name: "reject deployment without strategy".to_owned(),
+            fixture_file: "test_data/deployment_without_strategy.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings {
+                max_surge: Bounds {
+                    minimum: Some(IntOrString::Int(2)),
+                    limit: None,
+                },
+                ..Default::default()
+            },
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some("Deployment 'web': maxSurge 25% is below the minimum of 2 for 4 replicas")
+        );
+    }
+}
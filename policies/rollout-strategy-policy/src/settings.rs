@@ -0,0 +1,86 @@
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use serde::{Deserialize, Serialize};
+
+use intstr::{is_valid, parse_percentage};
+
+/// Bounds of a rolling update parameter. The values are either numbers of
+/// Pods or percentages of the replicas.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Bounds {
+    pub minimum: Option<IntOrString>,
+    pub limit: Option<IntOrString>,
+}
+
+impl Bounds {
+    fn validate(&self, name: &str) -> Result<(), String> {
+        for value in self.minimum.iter().chain(self.limit.iter()) {
+            if !is_valid(value) {
+                return Err(format!(
+                    "{name}: the bounds must be non-negative numbers or percentages"
+                ));
+            }
+        }
+        // Numbers of Pods and percentages can be compared only once scaled
+        // on the replicas of a Deployment
+        let out_of_order = match (&self.minimum, &self.limit) {
+            (Some(IntOrString::Int(minimum)), Some(IntOrString::Int(limit))) => minimum > limit,
+            (Some(IntOrString::String(minimum)), Some(IntOrString::String(limit))) => {
+                parse_percentage(minimum) > parse_percentage(limit)
+            }
+            _ => false,
+        };
+        if out_of_order {
+            return Err(format!("{name}: minimum cannot be greater than limit"));
+        }
+        Ok(())
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Bounds of the Pods that can be unavailable during the rollouts
+    pub max_unavailable: Bounds,
+    /// Bounds of the Pods that can be created above the replicas during the
+    /// rollouts
+    pub max_surge: Bounds,
+    /// Accept the Deployments using the Recreate strategy
+    pub allow_recreate: bool,
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        self.max_unavailable.validate("maxUnavailable")?;
+        self.max_surge.validate("maxSurge")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::bounds(
+        r#"{"maxUnavailable": {"limit": "25%"}, "maxSurge": {"minimum": 1, "limit": "50%"}}"#,
+        true
+    )]
+    #[case::negative_bound(r#"{"maxSurge": {"limit": -1}}"#, false)]
+    #[case::invalid_percentage(r#"{"maxUnavailable": {"limit": "quarter"}}"#, false)]
+    #[case::minimum_above_limit(r#"{"maxSurge": {"minimum": 3, "limit": 1}}"#, false)]
+    #[case::minimum_above_limit_percentage(
+        r#"{"maxSurge": {"minimum": "50%", "limit": "25%"}}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
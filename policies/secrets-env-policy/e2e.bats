@@ -0,0 +1,32 @@
+#!/usr/bin/env bats
+
+@test "Reject a Deployment consuming Secrets through environment variables" {
+	run kwctl run --request-path test_data/deployment_env_secrets.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*container 'web' uses Secret 'db-credentials' in env 'DB_PASSWORD', Secret 'api-tokens' in envFrom.*") -ne 0 ]
+}
+
+@test "Accept a Deployment consuming allowed Secrets through environment variables" {
+	run kwctl run --request-path test_data/deployment_env_secrets.json --settings-json '{"allowedSecrets": ["db-credentials", "api-tokens"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Pod mounting Secrets as volumes" {
+	run kwctl run --request-path test_data/pod_mounted_secrets.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
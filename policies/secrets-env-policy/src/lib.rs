@@ -0,0 +1,231 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::core::v1::{EnvFromSource, EnvVar, PodSpec};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        Ok(None) => return kubewarden::accept_request(),
+        Err(e) => {
+            return kubewarden::reject_request(
+                Some(format!("Cannot parse validation request: {e}")),
+                Some(400),
+                None,
+                None,
+            );
+        }
+    };
+
+    let errors = validate_pod_spec(&pod_spec, &validation_request.settings);
+    if !errors.is_empty() {
+        return kubewarden::reject_request(
+            Some(format!(
+                "Secrets cannot be consumed through environment variables, mount them as volumes instead: {}",
+                errors.join("; ")
+            )),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_pod_spec(pod_spec: &PodSpec, settings: &Settings) -> Vec<String> {
+    let containers = pod_spec
+        .init_containers
+        .iter()
+        .flatten()
+        .chain(pod_spec.containers.iter())
+        .map(|container| (&container.name, &container.env, &container.env_from));
+    let ephemeral_containers = pod_spec
+        .ephemeral_containers
+        .iter()
+        .flatten()
+        .map(|container| (&container.name, &container.env, &container.env_from));
+
+    containers
+        .chain(ephemeral_containers)
+        .filter_map(|(name, env, env_from)| {
+            let secrets = find_env_secrets(env.as_deref(), env_from.as_deref(), settings);
+            if secrets.is_empty() {
+                None
+            } else {
+                Some(format!("container '{name}' uses {}", secrets.join(", ")))
+            }
+        })
+        .collect()
+}
+
+// Returns a description of the Secrets, not allowed, consumed through the
+// environment variables of a container
+fn find_env_secrets(
+    env: Option<&[EnvVar]>,
+    env_from: Option<&[EnvFromSource]>,
+    settings: &Settings,
+) -> Vec<String> {
+    let env_secrets = env.unwrap_or_default().iter().filter_map(|env_var| {
+        env_var
+            .value_from
+            .as_ref()
+            .and_then(|value_from| value_from.secret_key_ref.as_ref())
+            .filter(|secret| !settings.allowed_secrets.contains(&secret.name))
+            .map(|secret| format!("Secret '{}' in env '{}'", secret.name, env_var.name))
+    });
+    let env_from_secrets = env_from
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|source| source.secret_ref.as_ref())
+        .filter(|secret| !settings.allowed_secrets.contains(&secret.name))
+        .map(|secret| format!("Secret '{}' in envFrom", secret.name));
+
+    env_secrets.chain(env_from_secrets).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use k8s_openapi::api::core::v1::{
+        ConfigMapEnvSource, Container, EnvVarSource, SecretEnvSource, SecretKeySelector,
+    };
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn env_secret(name: &str, secret: &str) -> EnvVar {
+        EnvVar {
+            name: name.to_owned(),
+            value_from: Some(EnvVarSource {
+                secret_key_ref: Some(SecretKeySelector {
+                    name: secret.to_owned(),
+                    key: "value".to_owned(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn env_from_secret(secret: &str) -> EnvFromSource {
+        EnvFromSource {
+            secret_ref: Some(SecretEnvSource {
+                name: secret.to_owned(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn container(name: &str, env: Vec<EnvVar>, env_from: Vec<EnvFromSource>) -> Container {
+        Container {
+            name: name.to_owned(),
+            env: Some(env),
+            env_from: Some(env_from),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::no_secrets(
+        vec![container(
+            "web",
+            vec![EnvVar {
+                name: "LOG_LEVEL".to_owned(),
+                value: Some("info".to_owned()),
+                ..Default::default()
+            }],
+            vec![EnvFromSource {
+                config_map_ref: Some(ConfigMapEnvSource {
+                    name: "web-config".to_owned(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+        )],
+        vec![]
+    )]
+    #[case::secret_key_ref(
+        vec![container("web", vec![env_secret("DB_PASSWORD", "db-credentials")], vec![])],
+        vec!["container 'web' uses Secret 'db-credentials' in env 'DB_PASSWORD'"]
+    )]
+    #[case::secret_ref(
+        vec![
+            container("web", vec![], vec![]),
+            container("sidecar", vec![], vec![env_from_secret("api-tokens")]),
+        ],
+        vec!["container 'sidecar' uses Secret 'api-tokens' in envFrom"]
+    )]
+    #[case::allowed_secret(
+        vec![container("web", vec![env_secret("FEATURE_FLAGS", "public-config")], vec![])],
+        vec![]
+    )]
+    fn env_secrets(#[case] containers: Vec<Container>, #[case] expected: Vec<&str>) {
+        let settings = Settings {
+            allowed_secrets: ["public-config".to_owned()].into(),
+        };
+        let pod_spec = PodSpec {
+            containers,
+            ..Default::default()
+        };
+        assert_eq!(validate_pod_spec(&pod_spec, &settings), expected);
+    }
+
+    #[test]
+    fn init_containers() {
+        let pod_spec = PodSpec {
+            init_containers: Some(vec![container(
+                "migrations",
+                vec![env_secret("DB_PASSWORD", "db-credentials")],
+                vec![],
+            )]),
+            containers: vec![container("web", vec![], vec![])],
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_pod_spec(&pod_spec, &Settings::default()),
+            vec!["container 'migrations' uses Secret 'db-credentials' in env 'DB_PASSWORD'"]
+        );
+    }
+
+    #[rstest]
+    #[case::env_secrets("test_data/deployment_env_secrets.json", Settings::default(), false)]
+    #[case::allowed_env_secrets(
+        "test_data/deployment_env_secrets.json",
+        Settings {
+            allowed_secrets: ["db-credentials".to_owned(), "api-tokens".to_owned()].into(),
+        },
+        true
+    )]
+    #[case::mounted_secrets("test_data/pod_mounted_secrets.json", Settings::default(), true)]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -1,8 +1,10 @@
-use anyhow::{Result, anyhow};
+use anyhow::Result;
 use std::convert::TryInto;
 
 use guest::prelude::*;
 use kubewarden_policy_sdk::wapc_guest as guest;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 use k8s_openapi::api::core::v1 as apicore;
 
@@ -12,6 +14,38 @@ use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_se
 mod settings;
 use settings::{ExternalSettings, SELinuxLevel, SELinuxOptions, Settings};
 
+/// Fields of the Pod spec used by the policy
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    #[serde(default)]
+    containers: Vec<ContainerFields>,
+    init_containers: Option<Vec<ContainerFields>>,
+    ephemeral_containers: Option<Vec<ContainerFields>>,
+    security_context: Option<SecurityContextFields>,
+}
+
+/// Container with its securityContext. The other fields are kept in `other`,
+/// so that the mutation does not drop the fields unknown to k8s-openapi.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct ContainerFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    security_context: Option<SecurityContextFields>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+/// SELinux options of the Pod or container securityContext
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct SecurityContextFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    se_linux_options: Option<apicore::SELinuxOptions>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn wapc_init() {
     register_function("validate", validate);
@@ -23,7 +57,7 @@ pub extern "C" fn wapc_init() {
 enum PolicyResponse {
     Accept,
     Reject(String),
-    Mutate(serde_json::Value),
+    Mutate(Value),
 }
 
 fn validate(payload: &[u8]) -> CallResult {
@@ -32,13 +66,9 @@ fn validate(payload: &[u8]) -> CallResult {
     // It is safe to unwrap here, because the validate_settings function already made sure that
     // ExternalSettings can be converted to Settings.
     let settings: Settings = validation_request.settings.try_into().unwrap();
+    let request = &validation_request.request;
 
-    let pod = match serde_json::from_value::<apicore::Pod>(validation_request.request.object) {
-        Ok(pod) => pod,
-        Err(_) => return kubewarden::accept_request(),
-    };
-
-    match do_validate(pod, settings)? {
+    match do_validate(&request.kind.kind, &request.object, settings)? {
         PolicyResponse::Accept => kubewarden::accept_request(),
         PolicyResponse::Reject(message) => {
             kubewarden::reject_request(Some(message), None, None, None)
@@ -47,99 +77,46 @@ fn validate(payload: &[u8]) -> CallResult {
     }
 }
 
-fn do_validate(pod: apicore::Pod, settings: settings::Settings) -> Result<PolicyResponse> {
-    let orig_pod = pod.clone();
+fn do_validate(kind: &str, object: &Value, settings: settings::Settings) -> Result<PolicyResponse> {
     match settings {
         Settings::MustRunAs(expected_selinux_options) => {
-            let mut pod_spec = pod.spec.ok_or_else(|| anyhow!("invalid pod spec"))?;
-
-            if let Some(ref security_context) = pod_spec.security_context {
-                if let Some(ref selinux_options) = security_context.se_linux_options {
-                    if !is_selinux_compliant(selinux_options, &expected_selinux_options) {
-                        return Ok(PolicyResponse::Reject(
-                            "SELinux validation failed".to_string(),
-                        ));
-                    }
-                } else {
-                    pod_spec.security_context = Some(apicore::PodSecurityContext {
-                        se_linux_options: Some(expected_selinux_options.clone().into()),
-                        ..security_context.clone()
-                    })
-                }
-            } else {
-                pod_spec.security_context = Some(apicore::PodSecurityContext {
-                    se_linux_options: Some(expected_selinux_options.clone().into()),
-                    ..apicore::PodSecurityContext::default()
-                })
-            }
+            let Ok(Some(mut pod_spec)) = partial_pod_spec::extract::<PodSpecFields>(kind, object)
+            else {
+                return Ok(PolicyResponse::Accept);
+            };
 
-            let default_container = |container: &mut apicore::Container| {
-                if let Some(ref security_context) = container.security_context {
-                    if let Some(ref selinux_options) = security_context.se_linux_options {
+            let security_contexts = std::iter::once(&mut pod_spec.security_context).chain(
+                pod_spec
+                    .containers
+                    .iter_mut()
+                    .chain(pod_spec.init_containers.iter_mut().flatten())
+                    .chain(pod_spec.ephemeral_containers.iter_mut().flatten())
+                    .map(|container| &mut container.security_context),
+            );
+
+            let mut mutated = false;
+            for security_context in security_contexts {
+                let security_context =
+                    security_context.get_or_insert_with(SecurityContextFields::default);
+                match security_context.se_linux_options {
+                    Some(ref selinux_options) => {
                         if !is_selinux_compliant(selinux_options, &expected_selinux_options) {
-                            return Some(PolicyResponse::Reject(
+                            return Ok(PolicyResponse::Reject(
                                 "SELinux validation failed".to_string(),
                             ));
                         }
-                    } else {
-                        container.security_context = Some(apicore::SecurityContext {
-                            se_linux_options: Some(expected_selinux_options.clone().into()),
-                            ..security_context.clone()
-                        });
                     }
-                } else {
-                    container.security_context = Some(apicore::SecurityContext {
-                        se_linux_options: Some(expected_selinux_options.clone().into()),
-                        ..apicore::SecurityContext::default()
-                    });
-                };
-                None
-            };
-
-            for container in pod_spec.containers.iter_mut() {
-                if let Some(early_response) = default_container(container) {
-                    return Ok(early_response);
-                }
-            }
-
-            if let Some(ref mut init_containers) = pod_spec.init_containers {
-                for container in init_containers.iter_mut() {
-                    if let Some(early_response) = default_container(container) {
-                        return Ok(early_response);
+                    None => {
+                        security_context.se_linux_options =
+                            Some(expected_selinux_options.clone().into());
+                        mutated = true;
                     }
                 }
             }
 
-            if let Some(ref mut ephemeral_containers) = pod_spec.ephemeral_containers {
-                for container in ephemeral_containers.iter_mut() {
-                    if let Some(ref security_context) = container.security_context {
-                        if let Some(ref selinux_options) = security_context.se_linux_options {
-                            if !is_selinux_compliant(selinux_options, &expected_selinux_options) {
-                                return Ok(PolicyResponse::Reject(
-                                    "SELinux validation failed".to_string(),
-                                ));
-                            }
-                        } else {
-                            container.security_context = Some(apicore::SecurityContext {
-                                se_linux_options: Some(expected_selinux_options.clone().into()),
-                                ..security_context.clone()
-                            });
-                        }
-                    } else {
-                        container.security_context = Some(apicore::SecurityContext {
-                            se_linux_options: Some(expected_selinux_options.clone().into()),
-                            ..apicore::SecurityContext::default()
-                        });
-                    };
-                }
-            }
-
-            if orig_pod.spec != Some(pod_spec.clone()) {
-                return Ok(PolicyResponse::Mutate(serde_json::to_value(
-                    apicore::Pod {
-                        spec: Some(pod_spec),
-                        ..orig_pod
-                    },
+            if mutated {
+                return Ok(PolicyResponse::Mutate(partial_pod_spec::patch(
+                    kind, object, &pod_spec,
                 )?));
             }
 
@@ -173,10 +150,16 @@ fn is_selinux_compliant(
 mod tests {
     use super::*;
 
+    use serde_json::json;
+
+    fn do_validate_pod(pod: apicore::Pod, settings: Settings) -> Result<PolicyResponse> {
+        do_validate("Pod", &serde_json::to_value(pod)?, settings)
+    }
+
     #[test]
     fn run_as_any_always_accepts() -> Result<()> {
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some(apicore::PodSpec::default()),
                     ..apicore::Pod::default()
@@ -200,7 +183,7 @@ mod tests {
 
         // Bad role
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -224,7 +207,7 @@ mod tests {
 
         // Bad user
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -248,7 +231,7 @@ mod tests {
 
         // Bad type
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -284,7 +267,7 @@ mod tests {
 
         // Bad role
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -308,7 +291,7 @@ mod tests {
 
         // Bad user
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -332,7 +315,7 @@ mod tests {
 
         // Bad type
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -368,7 +351,7 @@ mod tests {
 
         // Bad role
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -394,7 +377,7 @@ mod tests {
 
         // Bad user
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -420,7 +403,7 @@ mod tests {
 
         // Bad type
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -458,7 +441,7 @@ mod tests {
 
         // Bad level
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -494,7 +477,7 @@ mod tests {
 
         // Unmatching sensitivity
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -530,7 +513,7 @@ mod tests {
 
         // Unmatching categories
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -566,7 +549,7 @@ mod tests {
 
         // Matching rule
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -608,7 +591,7 @@ mod tests {
 
         // Matching rule with different category order
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -649,7 +632,7 @@ mod tests {
         };
 
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -707,7 +690,7 @@ mod tests {
         };
 
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -769,7 +752,7 @@ mod tests {
         };
 
         assert_eq!(
-            do_validate(
+            do_validate_pod(
                 apicore::Pod {
                     spec: Some({
                         apicore::PodSpec {
@@ -802,4 +785,48 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn must_run_as_keeps_unknown_fields() -> Result<()> {
+        let pod = json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "spec": {
+                "containers": [{"name": "nginx", "image": "nginx", "fieldFromTheFuture": 1}],
+                "securityContext": {"runAsUser": 1000, "fieldFromTheFuture": true},
+                "fieldFromTheFuture": {"enabled": true}
+            }
+        });
+        let settings = Settings::MustRunAs(SELinuxOptions {
+            user: Some("user".to_string()),
+            role: Some("role".to_string()),
+            level: None,
+            type_: Some("type".to_string()),
+        });
+        let expected_selinux_options = json!({"user": "user", "role": "role", "type": "type"});
+
+        assert_eq!(
+            do_validate("Pod", &pod, settings)?,
+            PolicyResponse::Mutate(json!({
+                "apiVersion": "v1",
+                "kind": "Pod",
+                "spec": {
+                    "containers": [{
+                        "name": "nginx",
+                        "image": "nginx",
+                        "fieldFromTheFuture": 1,
+                        "securityContext": {"seLinuxOptions": expected_selinux_options}
+                    }],
+                    "securityContext": {
+                        "runAsUser": 1000,
+                        "fieldFromTheFuture": true,
+                        "seLinuxOptions": expected_selinux_options
+                    },
+                    "fieldFromTheFuture": {"enabled": true}
+                }
+            }))
+        );
+
+        Ok(())
+    }
 }
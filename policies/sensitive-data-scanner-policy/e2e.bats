@@ -0,0 +1,63 @@
+#!/usr/bin/env bats
+
+@test "Reject a ConfigMap storing credentials" {
+	run kwctl run --request-path test_data/configmap_with_credentials.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*ConfigMap 'billing' contains sensitive data: key 'application.properties' (Connection string with password).*") -ne 0 ]
+}
+
+@test "Warn about a ConfigMap storing credentials" {
+	run kwctl run --request-path test_data/configmap_with_credentials.json --settings-json '{"mode": "warn"}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"warnings":\[".*contains sensitive data.*') -ne 0 ]
+}
+
+@test "Accept a ConfigMap without credentials" {
+	run kwctl run --request-path test_data/configmap_without_credentials.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Secret when Secrets are not scanned" {
+	run kwctl run --request-path test_data/secret_with_private_key.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Secret storing a private key when Secrets are scanned" {
+	run kwctl run --request-path test_data/secret_with_private_key.json --settings-json '{"scanSecrets": true}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Secret 'deploy-key' contains sensitive data: key 'id_ed25519' (Private key).*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/configmap_without_credentials.json --settings-json '{"customRules": {"broken": "[a-"}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,142 @@
+use std::collections::{BTreeMap, HashSet};
+
+use enforcement_mode::Mode;
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Rules looking for sensitive data, available out of the box
+pub(crate) const BUILTIN_RULES: [(&str, &str); 8] = [
+    (
+        "Private key",
+        r"-----BEGIN ((RSA|DSA|EC|OPENSSH|ENCRYPTED|PGP) )?PRIVATE KEY( BLOCK)?-----",
+    ),
+    ("AWS access key ID", r"\b(AKIA|ASIA)[0-9A-Z]{16}\b"),
+    (
+        "AWS secret access key",
+        r#"(?i)aws_?secret_?access_?key["']?\s*[:=]\s*["']?[A-Za-z0-9/+=]{40}"#,
+    ),
+    (
+        "Google Cloud service account key",
+        r#""type"\s*:\s*"service_account""#,
+    ),
+    (
+        "Azure storage account key",
+        r"AccountKey=[A-Za-z0-9+/=]{86,88}",
+    ),
+    ("GitHub token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+    ("Slack token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+    (
+        "Connection string with password",
+        r"\b[a-zA-Z][a-zA-Z0-9+.-]*://[^/\s:@]+:[^/\s:@]+@",
+    ),
+];
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Names of the built-in rules that are not applied
+    pub disabled_rules: HashSet<String>,
+    /// Additional rules. The keys are the names of the rules, reported when
+    /// they match, the values are regular expressions.
+    pub custom_rules: BTreeMap<String, String>,
+    /// Maximum number of bytes of each value that are scanned
+    pub max_scan_size: usize,
+    /// Scan the Secrets too. Secrets are meant to hold credentials, hence
+    /// they are not scanned by default.
+    pub scan_secrets: bool,
+    /// What to do when sensitive data is found
+    pub mode: Mode,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            disabled_rules: HashSet::new(),
+            custom_rules: BTreeMap::new(),
+            max_scan_size: 1024 * 1024,
+            scan_secrets: false,
+            mode: Mode::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the enabled built-in rules and the custom rules, compiled
+    pub fn rules(&self) -> Result<Vec<(String, Regex)>, String> {
+        BUILTIN_RULES
+            .iter()
+            .filter(|(name, _)| !self.disabled_rules.contains(*name))
+            .map(|(name, pattern)| (name.to_string(), pattern.to_string()))
+            .chain(self.custom_rules.clone())
+            .map(|(name, pattern)| {
+                Regex::new(&pattern)
+                    .map(|regex| (name.clone(), regex))
+                    .map_err(|e| format!("invalid regular expression of rule '{name}': {e}"))
+            })
+            .collect()
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(name) = self
+            .disabled_rules
+            .iter()
+            .find(|name| !BUILTIN_RULES.iter().any(|(builtin, _)| builtin == name))
+        {
+            return Err(format!("disabledRules contains unknown rule '{name}'"));
+        }
+        if let Some(name) = self.custom_rules.keys().find(|name| {
+            name.is_empty() || BUILTIN_RULES.iter().any(|(builtin, _)| builtin == name)
+        }) {
+            return Err(format!(
+                "customRules cannot contain rules without a name or with the name of a built-in rule: '{name}'"
+            ));
+        }
+        if self.max_scan_size == 0 {
+            return Err("maxScanSize must be greater than 0".to_owned());
+        }
+        let rules = self.rules()?;
+        if rules.is_empty() {
+            return Err("at least one rule must be enabled".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[test]
+    fn builtin_rules_compile() {
+        assert_eq!(
+            Settings::default().rules().unwrap().len(),
+            BUILTIN_RULES.len()
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::disabled_rules(r#"{"disabledRules": ["Slack token"]}"#, true)]
+    #[case::unknown_disabled_rule(r#"{"disabledRules": ["Slack"]}"#, false)]
+    #[case::custom_rules(r#"{"customRules": {"Acme API key": "acme_[a-f0-9]{32}"}}"#, true)]
+    #[case::invalid_custom_rule(r#"{"customRules": {"Acme API key": "acme_[a-f"}}"#, false)]
+    #[case::unnamed_custom_rule(r#"{"customRules": {"": "acme_[a-f0-9]{32}"}}"#, false)]
+    #[case::custom_rule_named_as_builtin(r#"{"customRules": {"Private key": "PRIVATE"}}"#, false)]
+    #[case::zero_max_scan_size(r#"{"maxScanSize": 0}"#, false)]
+    #[case::no_rules(
+        r#"{"disabledRules": ["Private key", "AWS access key ID", "AWS secret access key", "Google Cloud service account key", "Azure storage account key", "GitHub token", "Slack token", "Connection string with password"]}"#,
+        false
+    )]
+    #[case::warn_mode(r#"{"mode": "warn", "scanSecrets": true}"#, true)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
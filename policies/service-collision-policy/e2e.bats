@@ -0,0 +1,67 @@
+#!/usr/bin/env bats
+
+@test "Reject a Service requesting an allocated nodePort" {
+	run kwctl run \
+		--request-path test_data/service_node_port.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-node-port-allocated.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*nodePort 30080 is already claimed by 'team-b/api'.*") -ne 0 ]
+}
+
+@test "Reject a Service requesting a claimed loadBalancerIP" {
+	run kwctl run \
+		--request-path test_data/service_load_balancer_ip.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-load-balancer-ip-claimed.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*loadBalancerIP 203.0.113.10 is already claimed by 'team-b/api'.*") -ne 0 ]
+}
+
+@test "Accept a Service sharing a loadBalancerIP" {
+	run kwctl run \
+		--request-path test_data/service_load_balancer_ip.json \
+		--settings-path test_data/settings.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-load-balancer-ip-shared.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Service without explicit nodePorts or loadBalancerIP" {
+	run kwctl run \
+		--request-path test_data/service_cluster_ip.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/service_cluster_ip.json --settings-json '{"sharedIpAnnotation": ""}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
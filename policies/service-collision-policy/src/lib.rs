@@ -0,0 +1,423 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use guest::prelude::*;
+use k8s_openapi::Resource;
+use k8s_openapi::api::core::v1::Service;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::ListAllResourcesRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::list_all_resources;
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::list_all_resources;
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+// Resource of the cluster a Service can claim
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Claim {
+    NodePort(i32),
+    LoadBalancerIp { class: Option<String>, ip: String },
+}
+
+impl fmt::Display for Claim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Claim::NodePort(port) => write!(f, "nodePort {port}"),
+            Claim::LoadBalancerIp { class: None, ip } => write!(f, "loadBalancerIP {ip}"),
+            Claim::LoadBalancerIp {
+                class: Some(class),
+                ip,
+            } => write!(f, "loadBalancerIP {ip} of the loadBalancerClass '{class}'"),
+        }
+    }
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = validation_request.settings;
+
+    let service = match serde_json::from_value::<Service>(request.object) {
+        Ok(service) => service,
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+    let requested = requested_claims(&service, &settings);
+    if requested.is_empty() {
+        return kubewarden::accept_request();
+    }
+
+    let services = match list_all_resources::<Service>(&ListAllResourcesRequest {
+        api_version: Service::API_VERSION.to_owned(),
+        kind: Service::KIND.to_owned(),
+        label_selector: None,
+        field_selector: None,
+        field_masks: None,
+    }) {
+        Ok(services) => services.items,
+        Err(error) => {
+            return kubewarden::reject_request(
+                Some(format!(
+                    "Service '{}': cannot list the Services: {error}",
+                    request.name
+                )),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    // The Service being updated is part of the list
+    let others: Vec<&Service> = services
+        .iter()
+        .filter(|other| {
+            other.metadata.namespace.as_deref() != Some(request.namespace.as_str())
+                || other.metadata.name.as_deref() != Some(request.name.as_str())
+        })
+        .collect();
+    let conflicts: Vec<String> = requested
+        .iter()
+        .filter_map(|claim| {
+            others
+                .iter()
+                .find(|other| {
+                    allocated_claims(other, &settings).contains(claim)
+                        && !(matches!(claim, Claim::LoadBalancerIp { .. })
+                            && settings.is_ip_shared(&service.metadata, &other.metadata))
+                })
+                .map(|other| {
+                    format!(
+                        "{claim} is already claimed by '{}/{}'",
+                        other.metadata.namespace.as_deref().unwrap_or_default(),
+                        other.metadata.name.as_deref().unwrap_or_default()
+                    )
+                })
+        })
+        .collect();
+    if conflicts.is_empty() {
+        return kubewarden::accept_request();
+    }
+
+    kubewarden::reject_request(
+        Some(format!(
+            "Service '{}': {}",
+            request.name,
+            conflicts.join("; ")
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+// Returns the nodePorts and the loadBalancerIP explicitly requested by the
+// Service. The ones allocated by Kubernetes or the cloud provider cannot
+// collide.
+fn requested_claims(service: &Service, settings: &Settings) -> BTreeSet<Claim> {
+    let Some(spec) = &service.spec else {
+        return BTreeSet::new();
+    };
+    let service_type = spec.type_.as_deref().unwrap_or("ClusterIP");
+    let mut claims = BTreeSet::new();
+
+    if settings.check_node_ports && matches!(service_type, "NodePort" | "LoadBalancer") {
+        claims.extend(
+            spec.ports
+                .iter()
+                .flatten()
+                .filter_map(|port| port.node_port)
+                .filter(|node_port| *node_port != 0)
+                .map(Claim::NodePort),
+        );
+    }
+    if settings.check_load_balancer_ips
+        && service_type == "LoadBalancer"
+        && let Some(ip) = spec.load_balancer_ip.as_deref().filter(|ip| !ip.is_empty())
+    {
+        claims.insert(Claim::LoadBalancerIp {
+            class: spec.load_balancer_class.clone(),
+            ip: ip.to_owned(),
+        });
+    }
+    claims
+}
+
+// Returns the nodePorts and the load balancer IPs held by an existing
+// Service: the requested ones and the ones assigned by the cloud provider
+fn allocated_claims(service: &Service, settings: &Settings) -> BTreeSet<Claim> {
+    let mut claims = requested_claims(service, settings);
+    let Some(spec) = &service.spec else {
+        return claims;
+    };
+    if settings.check_load_balancer_ips && spec.type_.as_deref() == Some("LoadBalancer") {
+        claims.extend(
+            service
+                .status
+                .iter()
+                .filter_map(|status| status.load_balancer.as_ref())
+                .flat_map(|load_balancer| load_balancer.ingress.iter().flatten())
+                .filter_map(|ingress| ingress.ip.clone())
+                .map(|ip| Claim::LoadBalancerIp {
+                    class: spec.load_balancer_class.clone(),
+                    ip,
+                }),
+        );
+    }
+    claims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::api::core::v1::{
+        LoadBalancerIngress, LoadBalancerStatus, ServicePort, ServiceSpec, ServiceStatus,
+    };
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::ListAllResourcesRequest;
+
+        #[allow(dead_code)]
+        pub fn list_all_resources<T>(
+            _req: &ListAllResourcesRequest,
+        ) -> anyhow::Result<k8s_openapi::List<T>>
+        where
+            T: k8s_openapi::ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+        {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    fn node_port_service(namespace: &str, name: &str, node_port: i32) -> Service {
+        Service {
+            metadata: ObjectMeta {
+                name: Some(name.to_owned()),
+                namespace: Some(namespace.to_owned()),
+                ..Default::default()
+            },
+            spec: Some(ServiceSpec {
+                type_: Some("NodePort".to_owned()),
+                ports: Some(vec![ServicePort {
+                    port: 80,
+                    node_port: Some(node_port),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn load_balancer_service(
+        namespace: &str,
+        name: &str,
+        class: Option<&str>,
+        assigned_ip: &str,
+        sharing_key: Option<&str>,
+    ) -> Service {
+        Service {
+            metadata: ObjectMeta {
+                name: Some(name.to_owned()),
+                namespace: Some(namespace.to_owned()),
+                annotations: sharing_key.map(|key| {
+                    BTreeMap::from([(
+                        "metallb.universe.tf/allow-shared-ip".to_owned(),
+                        key.to_owned(),
+                    )])
+                }),
+                ..Default::default()
+            },
+            spec: Some(ServiceSpec {
+                type_: Some("LoadBalancer".to_owned()),
+                load_balancer_class: class.map(str::to_owned),
+                ..Default::default()
+            }),
+            status: Some(ServiceStatus {
+                load_balancer: Some(LoadBalancerStatus {
+                    ingress: Some(vec![LoadBalancerIngress {
+                        ip: Some(assigned_ip.to_owned()),
+                        ..Default::default()
+                    }]),
+                }),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            shared_ip_annotation: Some("metallb.universe.tf/allow-shared-ip".to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::no_other_services(vec![], true)]
+    #[case::other_node_port(vec![node_port_service("team-b", "api", 30081)], true)]
+    #[case::same_service(vec![node_port_service("team-a", "web", 30080)], true)]
+    #[case::node_port_allocated(vec![node_port_service("team-b", "api", 30080)], false)]
+    #[serial]
+    fn validate_node_port(
+        #[case] services: Vec<Service>,
+        #[case] expected_validation_result: bool,
+    ) {
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Service>().times(1).returning(move |req| {
+            assert_eq!(req.kind, "Service");
+            Ok(k8s_openapi::List::<Service> {
+                items: services.clone(),
+                ..Default::default()
+            })
+        });
+
+        let test_case = Testcase {
+            name: "validate nodePort".to_owned(),
+            fixture_file: "test_data/service_node_port.json".to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::other_ip(
+        vec![load_balancer_service("team-b", "api", None, "203.0.113.20", None)],
+        true
+    )]
+    #[case::other_class(
+        vec![load_balancer_service("team-b", "api", Some("example.com/internal"), "203.0.113.10", None)],
+        true
+    )]
+    #[case::shared_ip(
+        vec![load_balancer_service("team-b", "api", None, "203.0.113.10", Some("web"))],
+        true
+    )]
+    #[case::ip_assigned(
+        vec![load_balancer_service("team-b", "api", None, "203.0.113.10", None)],
+        false
+    )]
+    #[case::ip_assigned_other_sharing_key(
+        vec![load_balancer_service("team-b", "api", None, "203.0.113.10", Some("api"))],
+        false
+    )]
+    #[serial]
+    fn validate_load_balancer_ip(
+        #[case] services: Vec<Service>,
+        #[case] expected_validation_result: bool,
+    ) {
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Service>().times(1).returning(move |_| {
+            Ok(k8s_openapi::List::<Service> {
+                items: services.clone(),
+                ..Default::default()
+            })
+        });
+
+        let test_case = Testcase {
+            name: "validate loadBalancerIP".to_owned(),
+            fixture_file: "test_data/service_load_balancer_ip.json".to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    #[serial]
+    fn no_claims() {
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Service>().times(0);
+
+        let test_case = Testcase {
+            name: "no claims".to_owned(),
+            fixture_file: "test_data/service_cluster_ip.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    #[serial]
+    fn rejection_message() {
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Service>().times(1).returning(|_| {
+            Ok(k8s_openapi::List::<Service> {
+                items: vec![
+                    node_port_service("team-b", "api", 30443),
+                    load_balancer_service("team-b", "api", None, "203.0.113.10", None),
+                ],
+                ..Default::default()
+            })
+        });
+
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/service_load_balancer_ip.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Service 'web': nodePort 30443 is already claimed by 'team-b/api'; loadBalancerIP 203.0.113.10 is already claimed by 'team-b/api'"
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn checks_disabled() {
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Service>().times(0);
+
+        let test_case = Testcase {
+            name: "checks disabled".to_owned(),
+            fixture_file: "test_data/service_load_balancer_ip.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings {
+                check_node_ports: false,
+                check_load_balancer_ips: false,
+                shared_ip_annotation: None,
+            },
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,114 @@
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Reject the Services requesting a nodePort allocated to another
+    /// Service
+    pub check_node_ports: bool,
+    /// Reject the LoadBalancer Services requesting a loadBalancerIP, of the
+    /// same loadBalancerClass, claimed by another Service
+    pub check_load_balancer_ips: bool,
+    /// Annotation allowing the Services to share their loadBalancerIP when
+    /// they set it to the same value, like
+    /// `metallb.universe.tf/allow-shared-ip`
+    pub shared_ip_annotation: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            check_node_ports: true,
+            check_load_balancer_ips: true,
+            shared_ip_annotation: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the Services are allowed to share their
+    /// loadBalancerIP
+    pub fn is_ip_shared(&self, metadata: &ObjectMeta, other: &ObjectMeta) -> bool {
+        let Some(annotation) = &self.shared_ip_annotation else {
+            return false;
+        };
+        let value_of = |metadata: &ObjectMeta| {
+            metadata
+                .annotations
+                .as_ref()
+                .and_then(|annotations| annotations.get(annotation))
+                .cloned()
+        };
+        value_of(metadata).is_some_and(|value| Some(value) == value_of(other))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.shared_ip_annotation.as_deref() == Some("") {
+            return Err("sharedIpAnnotation cannot be empty".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "checkNodePorts": false,
+            "checkLoadBalancerIps": true,
+            "sharedIpAnnotation": "metallb.universe.tf/allow-shared-ip"
+        }"#,
+        true
+    )]
+    #[case::empty_annotation(r#"{"sharedIpAnnotation": ""}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    fn metadata(sharing_key: Option<&str>) -> ObjectMeta {
+        ObjectMeta {
+            annotations: sharing_key.map(|key| {
+                BTreeMap::from([(
+                    "metallb.universe.tf/allow-shared-ip".to_owned(),
+                    key.to_owned(),
+                )])
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::same_key(Some("web"), Some("web"), true)]
+    #[case::other_key(Some("web"), Some("api"), false)]
+    #[case::missing_key(Some("web"), None, false)]
+    #[case::no_keys(None, None, false)]
+    fn ip_shared(
+        #[case] key: Option<&str>,
+        #[case] other_key: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        let settings = Settings {
+            shared_ip_annotation: Some("metallb.universe.tf/allow-shared-ip".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.is_ip_shared(&metadata(key), &metadata(other_key)),
+            expected
+        );
+    }
+}
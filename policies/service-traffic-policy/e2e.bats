@@ -0,0 +1,52 @@
+#!/usr/bin/env bats
+
+@test "Accept a LoadBalancer Service using the Local externalTrafficPolicy" {
+	run kwctl run --request-path test_data/service_loadbalancer_local.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a NodePort Service using the Cluster externalTrafficPolicy" {
+	run kwctl run --request-path test_data/service_nodeport_cluster.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*NodePort Services must use the 'Local' externalTrafficPolicy.*") -ne 0 ]
+}
+
+@test "Reject a Service not using the required sessionAffinity" {
+	run kwctl run --request-path test_data/service_loadbalancer_local.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Services must use the 'ClientIP' sessionAffinity, found 'None'.*") -ne 0 ]
+}
+
+@test "Accept an exempted Service" {
+	run kwctl run --request-path test_data/service_nodeport_cluster.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/service_loadbalancer_local.json --settings-json '{"sessionAffinity": "Sticky"}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,162 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::core::v1::Service;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    if validation_request.request.kind.kind != "Service" {
+        return kubewarden::accept_request();
+    }
+    let service = serde_json::from_value::<Service>(validation_request.request.object)?;
+
+    let errors = validate_service(&service, &validation_request.settings);
+    if !errors.is_empty() {
+        return kubewarden::reject_request(Some(errors.join(", ")), None, None, None);
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_service(service: &Service, settings: &Settings) -> Vec<String> {
+    if is_exempted(service, settings) {
+        return vec![];
+    }
+    let spec = service.spec.clone().unwrap_or_default();
+    let service_type = spec.type_.as_deref().unwrap_or("ClusterIP");
+    let mut errors = vec![];
+
+    if settings.require_local_external_traffic_policy
+        && matches!(service_type, "LoadBalancer" | "NodePort")
+    {
+        // Kubernetes defaults the externalTrafficPolicy to `Cluster`
+        let external_traffic_policy = spec.external_traffic_policy.as_deref().unwrap_or("Cluster");
+        if external_traffic_policy != "Local" {
+            errors.push(format!(
+                "{service_type} Services must use the 'Local' externalTrafficPolicy, found '{external_traffic_policy}'"
+            ));
+        }
+    }
+
+    if let Some(required) = settings.required_session_affinity()
+        && service_type != "ExternalName"
+    {
+        // Kubernetes defaults the sessionAffinity to `None`
+        let session_affinity = spec.session_affinity.as_deref().unwrap_or("None");
+        if session_affinity != required {
+            errors.push(format!(
+                "Services must use the '{required}' sessionAffinity, found '{session_affinity}'"
+            ));
+        }
+    }
+
+    errors
+}
+
+fn is_exempted(service: &Service, settings: &Settings) -> bool {
+    let labels = service.metadata.labels.clone().unwrap_or_default();
+    settings
+        .exemption_labels
+        .iter()
+        .any(|(key, value)| labels.get(key) == Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::api::core::v1::ServiceSpec;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn service(
+        service_type: Option<&str>,
+        external_traffic_policy: Option<&str>,
+        session_affinity: Option<&str>,
+    ) -> Service {
+        Service {
+            metadata: ObjectMeta {
+                name: Some("web".to_owned()),
+                labels: Some(BTreeMap::from([("app".to_owned(), "web".to_owned())])),
+                ..Default::default()
+            },
+            spec: Some(ServiceSpec {
+                type_: service_type.map(String::from),
+                external_traffic_policy: external_traffic_policy.map(String::from),
+                session_affinity: session_affinity.map(String::from),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::cluster_ip(service(None, None, None), true)]
+    #[case::local_load_balancer(service(Some("LoadBalancer"), Some("Local"), None), true)]
+    #[case::cluster_load_balancer(service(Some("LoadBalancer"), Some("Cluster"), None), false)]
+    #[case::default_node_port(service(Some("NodePort"), None, None), false)]
+    #[case::local_node_port(service(Some("NodePort"), Some("Local"), None), true)]
+    fn external_traffic_policy(#[case] service: Service, #[case] is_valid: bool) {
+        let errors = validate_service(&service, &Settings::default());
+        assert_eq!(errors.is_empty(), is_valid, "errors: {errors:?}");
+    }
+
+    #[rstest]
+    #[case::default_affinity(service(None, None, None), false)]
+    #[case::client_ip(service(None, None, Some("ClientIP")), true)]
+    #[case::external_name(service(Some("ExternalName"), None, None), true)]
+    fn session_affinity(#[case] service: Service, #[case] is_valid: bool) {
+        let settings = Settings {
+            session_affinity: Some("ClientIP".to_owned()),
+            ..Default::default()
+        };
+        let errors = validate_service(&service, &settings);
+        assert_eq!(errors.is_empty(), is_valid, "errors: {errors:?}");
+    }
+
+    #[rstest]
+    #[case::matching_label("app", "web", true)]
+    #[case::other_value("app", "api", false)]
+    #[case::other_key("tier", "web", false)]
+    fn exemption_labels(#[case] key: &str, #[case] value: &str, #[case] is_exempted: bool) {
+        let settings = Settings {
+            exemption_labels: BTreeMap::from([(key.to_owned(), value.to_owned())]),
+            ..Default::default()
+        };
+        let errors = validate_service(
+            &service(Some("LoadBalancer"), Some("Cluster"), None),
+            &settings,
+        );
+        assert_eq!(errors.is_empty(), is_exempted, "errors: {errors:?}");
+    }
+
+    #[rstest]
+    #[case::local_load_balancer("test_data/service_loadbalancer_local.json", true)]
+    #[case::cluster_node_port("test_data/service_nodeport_cluster.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
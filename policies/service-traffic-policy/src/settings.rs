@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+const SESSION_AFFINITIES: [&str; 2] = ["None", "ClientIP"];
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// LoadBalancer and NodePort Services must use the `Local`
+    /// externalTrafficPolicy
+    pub require_local_external_traffic_policy: bool,
+    /// The sessionAffinity Services must use. When not set, or empty, any
+    /// value is accepted.
+    pub session_affinity: Option<String>,
+    /// Services having any of these labels are not validated
+    pub exemption_labels: BTreeMap<String, String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            require_local_external_traffic_policy: true,
+            session_affinity: None,
+            exemption_labels: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn required_session_affinity(&self) -> Option<&str> {
+        self.session_affinity
+            .as_deref()
+            .filter(|session_affinity| !session_affinity.is_empty())
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(session_affinity) = self.required_session_affinity()
+            && !SESSION_AFFINITIES.contains(&session_affinity)
+        {
+            return Err(format!(
+                "sessionAffinity '{session_affinity}' is not valid. Allowed values: {}",
+                SESSION_AFFINITIES.join(", ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::not_set(None, true)]
+    #[case::empty(Some(""), true)]
+    #[case::none(Some("None"), true)]
+    #[case::client_ip(Some("ClientIP"), true)]
+    #[case::invalid(Some("Sticky"), false)]
+    fn validate_settings(#[case] session_affinity: Option<&str>, #[case] is_valid: bool) {
+        let settings = Settings {
+            session_affinity: session_affinity.map(String::from),
+            ..Default::default()
+        };
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,31 @@
+#!/usr/bin/env bats
+
+@test "Reject a ClusterIP Service turned into a LoadBalancer" {
+	run kwctl run --request-path test_data/service_cluster_ip_to_load_balancer.json --settings-path test_data/settings.yaml annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*changing the Service type from ClusterIP to LoadBalancer requires the 'kubewarden.io/approve-service-type-change' annotation.*") -ne 0 ]
+}
+
+@test "Accept the type change when done by an allowed group" {
+	run kwctl run --request-path test_data/service_cluster_ip_to_load_balancer.json --settings-json '{"allowedGroups": ["network-admins"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/service_cluster_ip_to_load_balancer.json --settings-json '{"approvalAnnotation": ""}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
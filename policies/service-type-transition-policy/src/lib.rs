@@ -0,0 +1,172 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::core::v1::Service;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+
+    if request.kind.kind != "Service" || request.operation != "UPDATE" {
+        return kubewarden::accept_request();
+    }
+    let settings = &validation_request.settings;
+    if request
+        .user_info
+        .groups
+        .unwrap_or_default()
+        .iter()
+        .any(|group| settings.allowed_groups.contains(group))
+    {
+        return kubewarden::accept_request();
+    }
+
+    let old_service = serde_json::from_value::<Service>(request.old_object)?;
+    let service = serde_json::from_value::<Service>(request.object)?;
+
+    if let Err(e) = validate_transition(&old_service, &service, settings) {
+        return kubewarden::reject_request(Some(e), None, None, None);
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_transition(
+    old_service: &Service,
+    service: &Service,
+    settings: &Settings,
+) -> Result<(), String> {
+    let old_type = service_type(old_service);
+    let new_type = service_type(service);
+    if exposure_level(new_type) <= exposure_level(old_type) {
+        return Ok(());
+    }
+
+    let approved = service
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get(&settings.approval_annotation))
+        .is_some_and(|value| value == "true");
+    if approved {
+        return Ok(());
+    }
+    Err(format!(
+        "changing the Service type from {old_type} to {new_type} requires the '{}' annotation to be set to 'true'",
+        settings.approval_annotation
+    ))
+}
+
+fn service_type(service: &Service) -> &str {
+    service
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.type_.as_deref())
+        .unwrap_or("ClusterIP")
+}
+
+// How much a Service type exposes the workloads outside of the cluster
+fn exposure_level(service_type: &str) -> u8 {
+    match service_type {
+        "NodePort" => 1,
+        "LoadBalancer" => 2,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::api::core::v1::ServiceSpec;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn service(service_type: Option<&str>, approved: Option<&str>) -> Service {
+        Service {
+            metadata: ObjectMeta {
+                name: Some("web".to_owned()),
+                annotations: approved.map(|value| {
+                    BTreeMap::from([(
+                        "kubewarden.io/approve-service-type-change".to_owned(),
+                        value.to_owned(),
+                    )])
+                }),
+                ..Default::default()
+            },
+            spec: Some(ServiceSpec {
+                type_: service_type.map(String::from),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::unchanged(service(None, None), service(Some("ClusterIP"), None), true)]
+    #[case::cluster_ip_to_node_port(service(None, None), service(Some("NodePort"), None), false)]
+    #[case::cluster_ip_to_load_balancer(
+        service(Some("ClusterIP"), None),
+        service(Some("LoadBalancer"), None),
+        false
+    )]
+    #[case::node_port_to_load_balancer(
+        service(Some("NodePort"), None),
+        service(Some("LoadBalancer"), None),
+        false
+    )]
+    #[case::external_name_to_node_port(
+        service(Some("ExternalName"), None),
+        service(Some("NodePort"), None),
+        false
+    )]
+    #[case::load_balancer_to_cluster_ip(
+        service(Some("LoadBalancer"), None),
+        service(Some("ClusterIP"), None),
+        true
+    )]
+    #[case::approved(service(None, None), service(Some("LoadBalancer"), Some("true")), true)]
+    #[case::not_approved(
+        service(None, None),
+        service(Some("LoadBalancer"), Some("false")),
+        false
+    )]
+    fn transitions(#[case] old_service: Service, #[case] service: Service, #[case] is_valid: bool) {
+        assert_eq!(
+            validate_transition(&old_service, &service, &Settings::default()).is_ok(),
+            is_valid
+        );
+    }
+
+    #[rstest]
+    #[case::escalation(Settings::default(), false)]
+    #[case::allowed_group(Settings {
+        allowed_groups: ["network-admins".to_owned()].into(),
+        ..Default::default()
+    }, true)]
+    fn validate_request(#[case] settings: Settings, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: "ClusterIP to LoadBalancer".to_owned(),
+            fixture_file: "test_data/service_cluster_ip_to_load_balancer.json".to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
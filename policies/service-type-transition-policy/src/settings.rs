@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Annotation that, when set to `true` on the Service, approves the
+    /// change of its type
+    pub approval_annotation: String,
+    /// Groups allowed to change the type of any Service
+    pub allowed_groups: HashSet<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            approval_annotation: "kubewarden.io/approve-service-type-change".to_owned(),
+            allowed_groups: HashSet::new(),
+        }
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.approval_annotation.is_empty() {
+            return Err("approvalAnnotation cannot be empty".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults("kubewarden.io/approve-service-type-change", true)]
+    #[case::custom_annotation("example.com/approved", true)]
+    #[case::empty_annotation("", false)]
+    fn validate_settings(#[case] approval_annotation: &str, #[case] is_valid: bool) {
+        let settings = Settings {
+            approval_annotation: approval_annotation.to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
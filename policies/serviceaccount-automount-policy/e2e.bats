@@ -0,0 +1,46 @@
+#!/usr/bin/env bats
+
+@test "Disable the automount of the token when it is not defined" {
+  # Need to run the command inside of `bash -c` because of a bats
+  # limitation: https://bats-core.readthedocs.io/en/stable/gotchas.html?highlight=pipe#my-piped-command-does-not-work-under-run
+  run bash -c 'kwctl run \
+    --request-path test_data/serviceaccount_automount_unset.json \
+    annotated-policy.wasm 2>/dev/null | jq -er ".patch | @base64d"'
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*{"op":"add","path":"/automountServiceAccountToken","value":false}.*') -ne 0 ]
+}
+
+@test "Reject a ServiceAccount without automountServiceAccountToken when mutation is disabled" {
+  run kwctl run --request-path test_data/serviceaccount_automount_unset.json --settings-json '{"mutate": false}' annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+  [ $(expr "$output" : ".*ServiceAccount 'team-a/web' must set automountServiceAccountToken to false.*") -ne 0 ]
+}
+
+@test "Reject a ServiceAccount automounting its token" {
+  run kwctl run --request-path test_data/serviceaccount_automount_enabled.json annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Accept an exempt ServiceAccount automounting its token" {
+  run kwctl run --request-path test_data/serviceaccount_automount_enabled.json --settings-json '{"exemptServiceAccounts": [{"namespace": "argocd", "name": "argocd-application-controller"}]}' annotated-policy.wasm
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
@@ -0,0 +1,135 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::Resource;
+use k8s_openapi::api::core::v1 as apicore;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    if request.kind.kind != apicore::ServiceAccount::KIND {
+        return kubewarden::accept_request();
+    }
+    let service_account = serde_json::from_value::<apicore::ServiceAccount>(request.object)?;
+
+    let name = service_account.metadata.name.clone().unwrap_or_default();
+    let namespace = service_account
+        .metadata
+        .namespace
+        .clone()
+        .unwrap_or(request.namespace);
+    if settings.is_exempt(&namespace, &name) {
+        return kubewarden::accept_request();
+    }
+
+    match service_account.automount_service_account_token {
+        Some(false) => kubewarden::accept_request(),
+        None if settings.mutate => {
+            let patched_service_account = apicore::ServiceAccount {
+                automount_service_account_token: Some(false),
+                ..service_account
+            };
+            kubewarden::mutate_request(serde_json::to_value(&patched_service_account)?)
+        }
+        _ => kubewarden::reject_request(
+            Some(format!(
+                "ServiceAccount '{namespace}/{name}' must set automountServiceAccountToken to false"
+            )),
+            None,
+            None,
+            None,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use settings::ServiceAccount;
+
+    #[rstest]
+    #[case::automount_disabled(
+        "test_data/serviceaccount_automount_disabled.json",
+        Settings::default(),
+        true,
+        false
+    )]
+    #[case::automount_unset(
+        "test_data/serviceaccount_automount_unset.json",
+        Settings::default(),
+        true,
+        true
+    )]
+    #[case::automount_unset_without_mutation(
+        "test_data/serviceaccount_automount_unset.json",
+        Settings {
+            mutate: false,
+            ..Default::default()
+        },
+        false,
+        false
+    )]
+    #[case::automount_enabled(
+        "test_data/serviceaccount_automount_enabled.json",
+        Settings::default(),
+        false,
+        false
+    )]
+    #[case::exempt_service_account(
+        "test_data/serviceaccount_automount_enabled.json",
+        Settings {
+            exempt_service_accounts: [ServiceAccount {
+                namespace: "argocd".to_owned(),
+                name: "argocd-application-controller".to_owned(),
+            }]
+            .into(),
+            ..Default::default()
+        },
+        true,
+        false
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+        #[case] expect_mutated_object: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        let validation_response = test_case.eval(validate).expect("validation failed");
+        if expect_mutated_object {
+            let service_account = serde_json::from_value::<apicore::ServiceAccount>(
+                validation_response
+                    .mutated_object
+                    .expect("missing mutated object"),
+            )
+            .expect("failed to parse mutated object");
+            assert_eq!(service_account.automount_service_account_token, Some(false));
+        } else {
+            assert!(validation_response.mutated_object.is_none());
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+/// ServiceAccount that can automount its token
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ServiceAccount {
+    pub namespace: String,
+    pub name: String,
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// ServiceAccounts, usually the ones of the controllers, that can
+    /// automount their token
+    pub exempt_service_accounts: HashSet<ServiceAccount>,
+    /// Namespaces whose ServiceAccounts can automount their token
+    pub exempt_namespaces: HashSet<String>,
+    /// Set `automountServiceAccountToken` to false when it is not defined,
+    /// instead of rejecting the ServiceAccount
+    pub mutate: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            exempt_service_accounts: HashSet::new(),
+            exempt_namespaces: HashSet::new(),
+            mutate: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns true when the ServiceAccount can automount its token
+    pub fn is_exempt(&self, namespace: &str, name: &str) -> bool {
+        self.exempt_namespaces.contains(namespace)
+            || self.exempt_service_accounts.iter().any(|service_account| {
+                service_account.namespace == namespace && service_account.name == name
+            })
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.exempt_service_accounts.iter().any(|service_account| {
+            service_account.namespace.is_empty() || service_account.name.is_empty()
+        }) {
+            return Err(
+                "exemptServiceAccounts entries must have both a namespace and a name".to_owned(),
+            );
+        }
+        if self.exempt_namespaces.contains("") {
+            return Err("exemptNamespaces cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::exempt_service_account("argocd", "argocd-application-controller", true)]
+    #[case::other_namespace("default", "argocd-application-controller", false)]
+    #[case::exempt_namespace("kube-system", "coredns", true)]
+    #[case::not_exempt("team-a", "default", false)]
+    fn exempt(#[case] namespace: &str, #[case] name: &str, #[case] expected: bool) {
+        let settings = Settings {
+            exempt_service_accounts: [ServiceAccount {
+                namespace: "argocd".to_owned(),
+                name: "argocd-application-controller".to_owned(),
+            }]
+            .into(),
+            exempt_namespaces: ["kube-system".to_owned()].into(),
+            ..Default::default()
+        };
+        assert_eq!(settings.is_exempt(namespace, name), expected);
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::exempt_service_account(
+        r#"{"exemptServiceAccounts": [{"namespace": "argocd", "name": "argocd-application-controller"}]}"#,
+        true
+    )]
+    #[case::service_account_without_name(
+        r#"{"exemptServiceAccounts": [{"namespace": "argocd", "name": ""}]}"#,
+        false
+    )]
+    #[case::empty_namespace(r#"{"exemptNamespaces": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,55 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod using an allowed and existing ServiceAccount" {
+	run kwctl run \
+		--request-path test_data/pod_with_service_account.json \
+		--settings-path test_data/settings.yaml \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-service-account-found.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a Pod using a missing ServiceAccount" {
+	run kwctl run \
+		--request-path test_data/pod_with_service_account.json \
+		--settings-path test_data/settings.yaml \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-service-account-not-found.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*ServiceAccount 'team-a/worker-emails' cannot be found.*") -ne 0 ]
+}
+
+@test "Reject a Pod using a ServiceAccount not allowed" {
+	run kwctl run \
+		--request-path test_data/pod_with_service_account.json \
+		--settings-json '{"allowedServiceAccounts": {"team-a": ["web"]}}' \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*ServiceAccount 'team-a/worker-emails' is not allowed.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_with_service_account.json --settings-json '{"allowedServiceAccounts": {"team-a": ["worker-("]}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
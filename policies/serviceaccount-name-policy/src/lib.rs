@@ -0,0 +1,303 @@
+use std::collections::BTreeSet;
+
+use guest::prelude::*;
+use k8s_openapi::Resource;
+use k8s_openapi::api::core::v1::ServiceAccount;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::ListResourcesByNamespaceRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::list_resources_by_namespace;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::list_resources_by_namespace;
+
+mod settings;
+use settings::Settings;
+
+// ServiceAccount used by the Pods that do not specify one
+const DEFAULT_SERVICE_ACCOUNT: &str = "default";
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    let pod_spec = match validation_request.extract_pod_spec_from_object() {
+        Ok(Some(pod_spec)) => pod_spec,
+        Ok(None) => return kubewarden::accept_request(),
+        Err(e) => {
+            return kubewarden::reject_request(
+                Some(format!("Cannot parse validation request: {e}")),
+                Some(400),
+                None,
+                None,
+            );
+        }
+    };
+    let service_account = pod_spec
+        .service_account_name
+        .or(pod_spec.service_account)
+        .filter(|service_account| !service_account.is_empty())
+        .unwrap_or_else(|| DEFAULT_SERVICE_ACCOUNT.to_owned());
+
+    if let Err(error) = validate_service_account(
+        &service_account,
+        &validation_request.request.namespace,
+        &validation_request.settings,
+    ) {
+        return kubewarden::reject_request(Some(error), None, None, None);
+    }
+    kubewarden::accept_request()
+}
+
+fn validate_service_account(
+    service_account: &str,
+    namespace: &str,
+    settings: &Settings,
+) -> Result<(), String> {
+    if !settings.is_service_account_allowed(namespace, service_account) {
+        return Err(format!(
+            "ServiceAccount '{namespace}/{service_account}' is not allowed"
+        ));
+    }
+
+    // The ServiceAccount is listed by name: an empty list means it does not
+    // exist, while an error is a failure of the host
+    let kube_request = ListResourcesByNamespaceRequest {
+        api_version: ServiceAccount::API_VERSION.to_owned(),
+        kind: ServiceAccount::KIND.to_owned(),
+        namespace: namespace.to_owned(),
+        label_selector: None,
+        field_selector: Some(format!("metadata.name={service_account}")),
+        field_masks: Some(BTreeSet::from(["metadata.name".to_owned()])),
+    };
+    let service_accounts =
+        list_resources_by_namespace::<ServiceAccount>(&kube_request).map_err(|err| {
+            format!("cannot read the ServiceAccount '{namespace}/{service_account}': {err}")
+        })?;
+    if service_accounts.items.is_empty() {
+        return Err(format!(
+            "ServiceAccount '{namespace}/{service_account}' cannot be found"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::{BTreeMap, HashSet};
+
+    use k8s_openapi::api::apps::v1::{Deployment, DeploymentSpec};
+    use k8s_openapi::api::core::v1::{Pod, PodSpec, PodTemplateSpec};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden::{
+        request::{GroupVersionKind, KubernetesAdmissionRequest},
+        response::ValidationResponse,
+    };
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::ListResourcesByNamespaceRequest;
+
+        #[allow(dead_code)]
+        pub fn list_resources_by_namespace<T>(
+            _req: &ListResourcesByNamespaceRequest,
+        ) -> anyhow::Result<k8s_openapi::List<T>>
+        where
+            T: k8s_openapi::ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+        {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    // Returns the ServiceAccount selected by the field selector of the request
+    fn service_account_list(
+        req: &ListResourcesByNamespaceRequest,
+    ) -> anyhow::Result<k8s_openapi::List<ServiceAccount>> {
+        let name = req
+            .field_selector
+            .as_deref()
+            .and_then(|selector| selector.strip_prefix("metadata.name="))
+            .expect("the ServiceAccount is not listed by name");
+        Ok(k8s_openapi::List {
+            items: vec![ServiceAccount {
+                metadata: ObjectMeta {
+                    name: Some(name.to_owned()),
+                    namespace: Some(req.namespace.clone()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        })
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            allowed_service_accounts: BTreeMap::from([(
+                "team-a".to_owned(),
+                HashSet::from(["default".to_owned(), "worker-.*".to_owned()]),
+            )]),
+        }
+    }
+
+    fn pod_spec(service_account: Option<&str>) -> PodSpec {
+        PodSpec {
+            service_account_name: service_account.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    fn validate_object(kind: GroupVersionKind, object: serde_json::Value) -> ValidationResponse {
+        let request = KubernetesAdmissionRequest {
+            kind,
+            namespace: "team-a".to_owned(),
+            object,
+            ..Default::default()
+        };
+        let validation_request = ValidationRequest::<Settings> {
+            settings: settings(),
+            request,
+        };
+        let payload = serde_json::to_string(&validation_request)
+            .expect("cannot serialize validation request");
+
+        let response = validate(payload.as_bytes()).expect("validation failed");
+        serde_json::from_slice(&response).expect("cannot deserialize validation response")
+    }
+
+    fn validate_pod(service_account: Option<&str>) -> ValidationResponse {
+        let pod = Pod {
+            metadata: ObjectMeta {
+                name: Some("web".to_owned()),
+                namespace: Some("team-a".to_owned()),
+                ..Default::default()
+            },
+            spec: Some(pod_spec(service_account)),
+            ..Default::default()
+        };
+        validate_object(
+            GroupVersionKind {
+                group: "".to_owned(),
+                version: "v1".to_owned(),
+                kind: "Pod".to_owned(),
+            },
+            serde_json::to_value(pod).expect("cannot serialize Pod"),
+        )
+    }
+
+    #[rstest]
+    #[case::default_service_account(None, 1, true)]
+    #[case::allowed_service_account(Some("worker-emails"), 1, true)]
+    #[case::not_allowed_service_account(Some("admin"), 0, false)]
+    #[serial]
+    fn allowed_and_existing_service_accounts(
+        #[case] service_account: Option<&str>,
+        #[case] lookups: usize,
+        #[case] accepted: bool,
+    ) {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<ServiceAccount>()
+            .times(lookups)
+            .returning(service_account_list);
+
+        assert_eq!(validate_pod(service_account).accepted, accepted);
+    }
+
+    #[test]
+    #[serial]
+    fn deployment() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<ServiceAccount>()
+            .times(1)
+            .returning(service_account_list);
+
+        let deployment = Deployment {
+            metadata: ObjectMeta {
+                name: Some("emails".to_owned()),
+                namespace: Some("team-a".to_owned()),
+                ..Default::default()
+            },
+            spec: Some(DeploymentSpec {
+                template: PodTemplateSpec {
+                    spec: Some(pod_spec(Some("worker-emails"))),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let response = validate_object(
+            GroupVersionKind {
+                group: "apps".to_owned(),
+                version: "v1".to_owned(),
+                kind: "Deployment".to_owned(),
+            },
+            serde_json::to_value(deployment).expect("cannot serialize Deployment"),
+        );
+        assert!(response.accepted);
+    }
+
+    #[test]
+    #[serial]
+    fn missing_service_account() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<ServiceAccount>()
+            .times(1)
+            .returning(|req| {
+                if req.namespace != "team-a"
+                    || req.field_selector.as_deref() != Some("metadata.name=worker-emial")
+                {
+                    return Err(anyhow::anyhow!(
+                        "it's not searching the expected ServiceAccount"
+                    ));
+                }
+                Ok(k8s_openapi::List::default())
+            });
+
+        let response = validate_pod(Some("worker-emial"));
+        assert!(!response.accepted);
+        assert_eq!(
+            response.message,
+            Some("ServiceAccount 'team-a/worker-emial' cannot be found".to_owned())
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn service_account_lookup_failure() {
+        let ctx_list_resources = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx_list_resources
+            .expect::<ServiceAccount>()
+            .times(1)
+            .returning(|_req| Err(anyhow::anyhow!("serviceaccounts is forbidden")));
+
+        let response = validate_pod(Some("worker-emails"));
+        assert!(!response.accepted);
+        assert_eq!(
+            response.message,
+            Some(
+                "cannot read the ServiceAccount 'team-a/worker-emails': serviceaccounts is forbidden"
+                    .to_owned()
+            )
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::{BTreeMap, HashSet};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// ServiceAccounts the Pods of each namespace can run as. Entries are
+    /// regular expressions that must match the whole name of the
+    /// ServiceAccount. The `*` key applies to all the namespaces that are not
+    /// explicitly listed.
+    pub allowed_service_accounts: BTreeMap<String, HashSet<String>>,
+}
+
+// Builds the regular expression matching the whole name of the ServiceAccount
+fn build_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{pattern})$"))
+}
+
+impl Settings {
+    /// Returns true when the Pods of the given namespace can run as the
+    /// ServiceAccount. When no allowlist is defined, all the ServiceAccounts
+    /// are allowed.
+    pub fn is_service_account_allowed(&self, namespace: &str, service_account: &str) -> bool {
+        if self.allowed_service_accounts.is_empty() {
+            return true;
+        }
+        let Some(allowed) = self
+            .allowed_service_accounts
+            .get(namespace)
+            .or_else(|| self.allowed_service_accounts.get("*"))
+        else {
+            return false;
+        };
+
+        allowed
+            .iter()
+            .any(|pattern| build_regex(pattern).is_ok_and(|regex| regex.is_match(service_account)))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        for (namespace, patterns) in &self.allowed_service_accounts {
+            for pattern in patterns {
+                if pattern.is_empty() {
+                    return Err(format!(
+                        "allowedServiceAccounts of namespace '{namespace}' cannot contain empty values"
+                    ));
+                }
+                if let Err(e) = build_regex(pattern) {
+                    return Err(format!(
+                        "allowedServiceAccounts of namespace '{namespace}' contains the invalid regular expression '{pattern}': {e}"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            allowed_service_accounts: BTreeMap::from([
+                (
+                    "team-a".to_owned(),
+                    HashSet::from(["web".to_owned(), "worker-.*".to_owned()]),
+                ),
+                ("*".to_owned(), HashSet::from(["default".to_owned()])),
+            ]),
+        }
+    }
+
+    #[rstest]
+    #[case::exact_match("team-a", "web", true)]
+    #[case::regex_match("team-a", "worker-emails", true)]
+    #[case::partial_match("team-a", "web-admin", false)]
+    #[case::not_allowed("team-a", "default", false)]
+    #[case::fallback("team-b", "default", true)]
+    #[case::fallback_not_allowed("team-b", "web", false)]
+    fn allowed_service_accounts(
+        #[case] namespace: &str,
+        #[case] service_account: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            settings().is_service_account_allowed(namespace, service_account),
+            expected
+        );
+    }
+
+    #[test]
+    fn no_allowlist() {
+        assert!(Settings::default().is_service_account_allowed("team-a", "web"));
+    }
+
+    #[rstest]
+    #[case::valid(settings(), true)]
+    #[case::empty_pattern(Settings {
+        allowed_service_accounts: BTreeMap::from([("team-a".to_owned(), HashSet::from([String::new()]))]),
+    }, false)]
+    #[case::invalid_regex(Settings {
+        allowed_service_accounts: BTreeMap::from([("team-a".to_owned(), HashSet::from(["worker-(".to_owned()]))]),
+    }, false)]
+    fn validate_settings(#[case] settings: Settings, #[case] is_valid: bool) {
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
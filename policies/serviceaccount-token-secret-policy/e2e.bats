@@ -0,0 +1,51 @@
+#!/usr/bin/env bats
+
+@test "Reject a ServiceAccount token Secret" {
+	run kwctl run --request-path test_data/secret_service_account_token.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Secret 'jenkins-token' for ServiceAccount 'jenkins' cannot be of type 'kubernetes.io/service-account-token'.*") -ne 0 ]
+}
+
+@test "Accept an Opaque Secret" {
+	run kwctl run --request-path test_data/secret_opaque.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a ServiceAccount token Secret created by an allowed group" {
+	run kwctl run --request-path test_data/secret_service_account_token.json --settings-json '{"allowedGroups": ["ci-admins"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a ServiceAccount token Secret in an allowed namespace" {
+	run kwctl run --request-path test_data/secret_service_account_token.json --settings-json '{"allowedNamespaces": ["ci"]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/secret_opaque.json --settings-json '{"allowedUsers": [""]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,119 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::api::core::v1::Secret;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+// Type of the Secrets holding legacy, non-expiring, ServiceAccount tokens
+const SERVICE_ACCOUNT_TOKEN_TYPE: &str = "kubernetes.io/service-account-token";
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    if request.kind.kind != "Secret" {
+        return kubewarden::accept_request();
+    }
+    let secret = serde_json::from_value::<Secret>(request.object)?;
+    if secret.type_.as_deref() != Some(SERVICE_ACCOUNT_TOKEN_TYPE)
+        || settings.allowed_namespaces.contains(&request.namespace)
+        || settings.is_user_allowed(
+            request.user_info.username.as_deref(),
+            &request.user_info.groups.unwrap_or_default(),
+        )
+    {
+        return kubewarden::accept_request();
+    }
+
+    let service_account = secret
+        .metadata
+        .annotations
+        .as_ref()
+        .and_then(|annotations| annotations.get("kubernetes.io/service-account.name"))
+        .map(|name| format!(" for ServiceAccount '{name}'"))
+        .unwrap_or_default();
+    kubewarden::reject_request(
+        Some(format!(
+            "Secret '{}'{service_account} cannot be of type '{SERVICE_ACCOUNT_TOKEN_TYPE}': long-lived ServiceAccount tokens are not allowed, use bound tokens from the TokenRequest API or projected volumes instead",
+            secret.metadata.name.unwrap_or_default(),
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::service_account_token(
+        "test_data/secret_service_account_token.json",
+        Settings::default(),
+        false
+    )]
+    #[case::opaque_secret("test_data/secret_opaque.json", Settings::default(), true)]
+    #[case::allowed_namespace(
+        "test_data/secret_service_account_token.json",
+        Settings {
+            allowed_namespaces: ["ci".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::allowed_user(
+        "test_data/secret_service_account_token.json",
+        Settings {
+            allowed_users: ["jenkins-admin".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::allowed_group(
+        "test_data/secret_service_account_token.json",
+        Settings {
+            allowed_groups: ["ci-admins".to_owned()].into(),
+            ..Default::default()
+        },
+        true
+    )]
+    #[case::other_namespace_allowed(
+        "test_data/secret_service_account_token.json",
+        Settings {
+            allowed_namespaces: ["kube-system".to_owned()].into(),
+            ..Default::default()
+        },
+        false
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Users allowed to create ServiceAccount token Secrets
+    pub allowed_users: HashSet<String>,
+    /// Groups whose members are allowed to create ServiceAccount token Secrets
+    pub allowed_groups: HashSet<String>,
+    /// Namespaces where ServiceAccount token Secrets can be created
+    pub allowed_namespaces: HashSet<String>,
+}
+
+impl Settings {
+    /// Returns true when the user, or one of its groups, is allowed to create
+    /// ServiceAccount token Secrets
+    pub fn is_user_allowed(&self, username: Option<&str>, groups: &[String]) -> bool {
+        username.is_some_and(|username| self.allowed_users.contains(username))
+            || groups
+                .iter()
+                .any(|group| self.allowed_groups.contains(group))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_users.contains("") {
+            return Err("allowedUsers cannot contain empty values".to_owned());
+        }
+        if self.allowed_groups.contains("") {
+            return Err("allowedGroups cannot contain empty values".to_owned());
+        }
+        if self.allowed_namespaces.contains("") {
+            return Err("allowedNamespaces cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::allowed_user(Some("admin"), vec![], true)]
+    #[case::allowed_group(Some("alice"), vec!["cluster-operators"], true)]
+    #[case::not_allowed(Some("alice"), vec!["system:authenticated"], false)]
+    #[case::anonymous(None, vec![], false)]
+    fn allowed_users(
+        #[case] username: Option<&str>,
+        #[case] groups: Vec<&str>,
+        #[case] expected: bool,
+    ) {
+        let settings = Settings {
+            allowed_users: ["admin".to_owned()].into(),
+            allowed_groups: ["cluster-operators".to_owned()].into(),
+            ..Default::default()
+        };
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(settings.is_user_allowed(username, &groups), expected);
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::allowlists(
+        r#"{"allowedUsers": ["admin"], "allowedGroups": ["cluster-operators"], "allowedNamespaces": ["ci"]}"#,
+        true
+    )]
+    #[case::empty_user(r#"{"allowedUsers": [""]}"#, false)]
+    #[case::empty_group(r#"{"allowedGroups": [""]}"#, false)]
+    #[case::empty_namespace(r#"{"allowedNamespaces": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
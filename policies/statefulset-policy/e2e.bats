@@ -0,0 +1,54 @@
+#!/usr/bin/env bats
+
+@test "Reject volumes deleted on scale down in a prod namespace" {
+	run kwctl run \
+		--request-path test_data/statefulset_delete_when_scaled.json \
+		--settings-path test_data/settings.yaml \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-namespace-prod.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*StatefulSet 'postgres': persistentVolumeClaimRetentionPolicy.whenScaled Delete is not allowed, use one of: Retain.*") -ne 0 ]
+}
+
+@test "Accept volumes deleted on scale down in a dev namespace" {
+	run kwctl run \
+		--request-path test_data/statefulset_delete_when_scaled.json \
+		--settings-path test_data/settings.yaml \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-namespace-dev.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject OnDelete update strategy" {
+	run kwctl run \
+		--request-path test_data/statefulset_on_delete.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*the OnDelete update strategy is not allowed, use RollingUpdate.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/statefulset_defaults.json --settings-json '{"classes": {"prod": {"allowedWhenScaled": ["Keep"]}}}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,294 @@
+use std::collections::BTreeSet;
+
+use guest::prelude::*;
+use k8s_openapi::Resource;
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::core::v1::Namespace;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::GetResourceRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::get_resource;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::get_resource;
+
+mod settings;
+use settings::{Constraints, Settings};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = validation_request.settings;
+
+    let statefulset = match serde_json::from_value::<StatefulSet>(request.object) {
+        Ok(statefulset) => statefulset,
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    let result = namespace_class(&request.namespace, &settings).and_then(|class| {
+        validate_statefulset(&statefulset, settings.constraints_of(class.as_deref()))
+    });
+    if let Err(error) = result {
+        return kubewarden::reject_request(
+            Some(format!("StatefulSet '{}': {error}", request.name)),
+            None,
+            None,
+            None,
+        );
+    }
+    kubewarden::accept_request()
+}
+
+// Returns the class of the namespace. The namespace is read only when some
+// classes have their own constraints.
+fn namespace_class(namespace: &str, settings: &Settings) -> Result<Option<String>, String> {
+    if settings.classes.is_empty() {
+        return Ok(None);
+    }
+    let kube_request = GetResourceRequest {
+        name: namespace.to_owned(),
+        api_version: Namespace::API_VERSION.to_owned(),
+        kind: Namespace::KIND.to_owned(),
+        field_masks: None,
+        namespace: None,
+        disable_cache: false,
+    };
+    let namespace_resource = get_resource::<Namespace>(&kube_request)
+        .map_err(|e| format!("cannot read the namespace '{namespace}': {e}"))?;
+    Ok(namespace_resource
+        .metadata
+        .labels
+        .and_then(|mut labels| labels.remove(&settings.namespace_class_label)))
+}
+
+// Returns an error when the value is not allowed
+fn validate_value(name: &str, value: &str, allowed: &BTreeSet<String>) -> Result<(), String> {
+    if allowed.is_empty() || allowed.contains(value) {
+        return Ok(());
+    }
+    Err(format!(
+        "{name} {value} is not allowed, use one of: {}",
+        allowed.iter().cloned().collect::<Vec<String>>().join(", ")
+    ))
+}
+
+fn validate_statefulset(
+    statefulset: &StatefulSet,
+    constraints: &Constraints,
+) -> Result<(), String> {
+    let spec = statefulset.spec.clone().unwrap_or_default();
+    let retention_policy = spec
+        .persistent_volume_claim_retention_policy
+        .unwrap_or_default();
+
+    // The values that are not set are the ones defaulted by Kubernetes
+    let update_strategy = spec
+        .update_strategy
+        .and_then(|update_strategy| update_strategy.type_)
+        .unwrap_or_else(|| "RollingUpdate".to_owned());
+    let mut errors = vec![];
+    if constraints.require_rolling_update && update_strategy != "RollingUpdate" {
+        errors.push(format!(
+            "the {update_strategy} update strategy is not allowed, use RollingUpdate"
+        ));
+    }
+    let checks = [
+        (
+            "persistentVolumeClaimRetentionPolicy.whenDeleted",
+            retention_policy.when_deleted.as_deref().unwrap_or("Retain"),
+            &constraints.allowed_when_deleted,
+        ),
+        (
+            "persistentVolumeClaimRetentionPolicy.whenScaled",
+            retention_policy.when_scaled.as_deref().unwrap_or("Retain"),
+            &constraints.allowed_when_scaled,
+        ),
+        (
+            "podManagementPolicy",
+            spec.pod_management_policy
+                .as_deref()
+                .unwrap_or("OrderedReady"),
+            &constraints.allowed_pod_management_policies,
+        ),
+    ];
+    errors.extend(
+        checks
+            .into_iter()
+            .filter_map(|(name, value, allowed)| validate_value(name, value, allowed).err()),
+    );
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+    use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::GetResourceRequest;
+
+        #[allow(dead_code)]
+        pub fn get_resource<T: 'static>(_req: &GetResourceRequest) -> anyhow::Result<T> {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    fn settings() -> Settings {
+        serde_json::from_str(
+            r#"{
+                "classes": {
+                    "prod": {
+                        "allowedWhenScaled": ["Retain"],
+                        "allowedWhenDeleted": ["Retain"],
+                        "allowedPodManagementPolicies": ["OrderedReady"]
+                    }
+                }
+            }"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    fn namespace(class: &str) -> Namespace {
+        Namespace {
+            metadata: ObjectMeta {
+                name: Some("databases".to_owned()),
+                labels: Some(BTreeMap::from([(
+                    "kubewarden.io/namespace-class".to_owned(),
+                    class.to_owned(),
+                )])),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::defaults_in_prod("test_data/statefulset_defaults.json", "prod", true)]
+    #[case::delete_when_scaled_in_prod(
+        "test_data/statefulset_delete_when_scaled.json",
+        "prod",
+        false
+    )]
+    #[case::delete_when_scaled_in_dev("test_data/statefulset_delete_when_scaled.json", "dev", true)]
+    #[case::on_delete_in_dev("test_data/statefulset_on_delete.json", "dev", false)]
+    #[serial]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] class: &'static str,
+        #[case] expected_validation_result: bool,
+    ) {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<Namespace>()
+            .times(1)
+            .returning(move |req| {
+                if req.name != "databases" || req.namespace.is_some() {
+                    return Err(anyhow::anyhow!("it's not searching the expected Namespace"));
+                }
+                Ok(namespace(class))
+            });
+
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    #[serial]
+    fn no_classes() {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource.expect::<Namespace>().times(0);
+
+        let test_case = Testcase {
+            name: "no classes".to_owned(),
+            fixture_file: "test_data/statefulset_delete_when_scaled.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    #[serial]
+    fn rejection_message() {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<Namespace>()
+            .returning(|_| Ok(namespace("prod")));
+
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/statefulset_delete_when_scaled.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "StatefulSet 'postgres': persistentVolumeClaimRetentionPolicy.whenScaled Delete is not allowed, use one of: Retain; podManagementPolicy Parallel is not allowed, use one of: OrderedReady"
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn missing_namespace() {
+        let ctx_get_resource = mock_kubernetes_sdk::get_resource_context();
+        ctx_get_resource
+            .expect::<Namespace>()
+            .times(1)
+            .returning(|_| Err(anyhow::anyhow!("not found")));
+
+        let test_case = Testcase {
+            name: "missing namespace".to_owned(),
+            fixture_file: "test_data/statefulset_defaults.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some("StatefulSet 'postgres': cannot read the namespace 'databases': not found")
+        );
+    }
+}
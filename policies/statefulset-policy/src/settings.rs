@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+const RETENTION_POLICIES: &[&str] = &["Retain", "Delete"];
+const POD_MANAGEMENT_POLICIES: &[&str] = &["OrderedReady", "Parallel"];
+
+/// Constraints of the StatefulSets of a class of namespaces. All the values
+/// are accepted when the list of the allowed ones is empty.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct Constraints {
+    /// Reject the StatefulSets using the OnDelete update strategy
+    pub require_rolling_update: bool,
+    /// Allowed values of `persistentVolumeClaimRetentionPolicy.whenDeleted`
+    pub allowed_when_deleted: BTreeSet<String>,
+    /// Allowed values of `persistentVolumeClaimRetentionPolicy.whenScaled`
+    pub allowed_when_scaled: BTreeSet<String>,
+    /// Allowed values of `podManagementPolicy`
+    pub allowed_pod_management_policies: BTreeSet<String>,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Constraints {
+            require_rolling_update: true,
+            allowed_when_deleted: BTreeSet::new(),
+            allowed_when_scaled: BTreeSet::new(),
+            allowed_pod_management_policies: BTreeSet::new(),
+        }
+    }
+}
+
+impl Constraints {
+    fn validate(&self) -> Result<(), String> {
+        for (name, allowed, known) in [
+            (
+                "allowedWhenDeleted",
+                &self.allowed_when_deleted,
+                RETENTION_POLICIES,
+            ),
+            (
+                "allowedWhenScaled",
+                &self.allowed_when_scaled,
+                RETENTION_POLICIES,
+            ),
+            (
+                "allowedPodManagementPolicies",
+                &self.allowed_pod_management_policies,
+                POD_MANAGEMENT_POLICIES,
+            ),
+        ] {
+            if let Some(unknown) = allowed
+                .iter()
+                .find(|value| !known.contains(&value.as_str()))
+            {
+                return Err(format!(
+                    "{name}: unknown value '{unknown}', use one of: {}",
+                    known.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Label of the namespaces holding their class
+    pub namespace_class_label: String,
+    /// Constraints of the namespaces whose class has no constraints
+    pub constraints: Constraints,
+    /// Constraints of the namespaces, by class
+    pub classes: BTreeMap<String, Constraints>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            namespace_class_label: "kubewarden.io/namespace-class".to_owned(),
+            constraints: Constraints::default(),
+            classes: BTreeMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the constraints of the namespace class, the default ones when
+    /// the class has none
+    pub fn constraints_of(&self, class: Option<&str>) -> &Constraints {
+        class
+            .and_then(|class| self.classes.get(class))
+            .unwrap_or(&self.constraints)
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if !self.classes.is_empty() && self.namespace_class_label.is_empty() {
+            return Err("namespaceClassLabel cannot be empty".to_owned());
+        }
+        self.constraints.validate()?;
+        for (class, constraints) in &self.classes {
+            constraints
+                .validate()
+                .map_err(|e| format!("class '{class}': {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::classes(
+        r#"{"classes": {"prod": {"allowedWhenScaled": ["Retain"], "allowedPodManagementPolicies": ["OrderedReady"]}}}"#,
+        true
+    )]
+    #[case::constraints(
+        r#"{"constraints": {"requireRollingUpdate": false, "allowedWhenDeleted": ["Retain", "Delete"]}}"#,
+        true
+    )]
+    #[case::empty_allowed_values(r#"{"constraints": {"allowedWhenScaled": []}}"#, true)]
+    #[case::unknown_retention_policy(
+        r#"{"classes": {"prod": {"allowedWhenDeleted": ["Keep"]}}}"#,
+        false
+    )]
+    #[case::unknown_pod_management_policy(
+        r#"{"constraints": {"allowedPodManagementPolicies": ["Ordered"]}}"#,
+        false
+    )]
+    #[case::empty_class_label(r#"{"namespaceClassLabel": "", "classes": {"prod": {}}}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn unknown_constraint() {
+        let settings = r#"{"constraints": {"allowedWhenScaledDown": ["Retain"]}}"#;
+        assert!(serde_json::from_str::<Settings>(settings).is_err());
+    }
+
+    #[test]
+    fn class_constraints() {
+        let settings: Settings =
+            serde_json::from_str(r#"{"classes": {"prod": {"allowedWhenScaled": ["Retain"]}}}"#)
+                .expect("cannot parse settings");
+        assert_eq!(
+            settings.constraints_of(Some("prod")).allowed_when_scaled,
+            BTreeSet::from(["Retain".to_owned()])
+        );
+        assert_eq!(
+            settings.constraints_of(Some("dev")),
+            &Constraints::default()
+        );
+        assert_eq!(settings.constraints_of(None), &Constraints::default());
+    }
+}
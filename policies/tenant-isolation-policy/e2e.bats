@@ -0,0 +1,101 @@
+#!/usr/bin/env bats
+
+@test "Accept an Ingress using a granted TLS Secret" {
+	run kwctl run \
+		--request-path test_data/ingress_granted_tls_secret.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject an Ingress using the TLS Secret of another namespace" {
+	run kwctl run \
+		--request-path test_data/ingress_cross_namespace_tls_secret.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the Secret 'production/web-tls' of another namespace cannot be referenced from the 'team-a' namespace.*") -ne 0 ]
+}
+
+@test "Reject a RoleBinding binding the ServiceAccount of another namespace" {
+	run kwctl run \
+		--request-path test_data/rolebinding_cross_namespace_service_account.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*subjects\[1\]: the ServiceAccount 'team-b/deployer' of another namespace.*") -ne 0 ]
+}
+
+@test "Accept a PersistentVolume using the Secret of a namespace granted to all" {
+	run kwctl run \
+		--request-path test_data/persistentvolume_granted_secret.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject an HTTPRoute with a backend of another namespace" {
+	run kwctl run \
+		--request-path test_data/httproute_cross_namespace_backend.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the Service 'team-b/api' of another namespace.*") -ne 0 ]
+}
+
+@test "Accept an HTTPRoute with a backend of another namespace when the backends are not checked" {
+	run kwctl run \
+		--request-path test_data/httproute_cross_namespace_backend.json \
+		--settings-json '{"referencePoints": ["ingressTlsSecrets"]}' \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a PersistentVolumeClaim restored from a VolumeSnapshot of another namespace" {
+	run kwctl run \
+		--request-path test_data/pvc_cross_namespace_data_source.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/ingress_granted_tls_secret.json --settings-json '{"grants": [{"namespace": "shared-certs"}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
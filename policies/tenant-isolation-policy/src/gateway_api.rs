@@ -0,0 +1,64 @@
+// Minimal representation of the Gateway API HTTPRoute. Only the backend
+// references are defined.
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpRoute {
+    #[serde(default)]
+    pub spec: HttpRouteSpec,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpRouteSpec {
+    #[serde(default)]
+    pub rules: Vec<HttpRouteRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpRouteRule {
+    #[serde(default)]
+    pub filters: Vec<HttpRouteFilter>,
+    #[serde(default)]
+    pub backend_refs: Vec<BackendRef>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpRouteFilter {
+    pub request_mirror: Option<HttpRequestMirrorFilter>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HttpRequestMirrorFilter {
+    pub backend_ref: BackendRef,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackendRef {
+    pub group: Option<String>,
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    pub namespace: Option<String>,
+    /// Filters applied to the requests sent to the backend. Only the
+    /// backends of the rules have them.
+    #[serde(default)]
+    pub filters: Vec<HttpRouteFilter>,
+}
+
+impl BackendRef {
+    /// Returns the kind written as `Kind` for the core resources and
+    /// `Kind.group` for the others. The backends are Services by default.
+    pub fn qualified_kind(&self) -> String {
+        let kind = self.kind.as_deref().unwrap_or("Service");
+        match self.group.as_deref() {
+            None | Some("") => kind.to_owned(),
+            Some(group) => format!("{kind}.{group}"),
+        }
+    }
+}
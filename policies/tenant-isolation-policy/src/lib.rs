@@ -0,0 +1,230 @@
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use k8s_openapi::api::networking::v1::Ingress;
+use k8s_openapi::api::rbac::v1::RoleBinding;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use serde_json::Value;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod gateway_api;
+use gateway_api::HttpRoute;
+
+mod references;
+use references::ObjectReference;
+
+mod settings;
+use settings::{ReferencePoint, Settings};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let group_kind = (request.kind.group.as_str(), request.kind.kind.as_str());
+    // Namespace of the resource, and the objects it references
+    let references = match group_kind {
+        ("networking.k8s.io", "Ingress") if settings.checks(ReferencePoint::IngressTlsSecrets) => {
+            serde_json::from_value::<Ingress>(request.object).map(|ingress| {
+                (
+                    Some(request.namespace.clone()),
+                    references::ingress_tls_secrets(&ingress),
+                )
+            })
+        }
+        ("rbac.authorization.k8s.io", "RoleBinding")
+            if settings.checks(ReferencePoint::RoleBindingSubjects) =>
+        {
+            serde_json::from_value::<RoleBinding>(request.object).map(|role_binding| {
+                (
+                    Some(request.namespace.clone()),
+                    references::role_binding_subjects(&role_binding),
+                )
+            })
+        }
+        // The PersistentVolumes are not namespaced: they belong to the
+        // namespace of their claim, and are not checked until they are bound
+        // or reserved to a claim
+        ("", "PersistentVolume") if settings.checks(ReferencePoint::VolumeSourceSecrets) => Ok((
+            request
+                .object
+                .pointer("/spec/claimRef/namespace")
+                .and_then(Value::as_str)
+                .map(str::to_owned),
+            references::volume_source_secrets(&request.object),
+        )),
+        ("gateway.networking.k8s.io", "HTTPRoute")
+            if settings.checks(ReferencePoint::HttpRouteBackendRefs) =>
+        {
+            serde_json::from_value::<HttpRoute>(request.object).map(|http_route| {
+                (
+                    Some(request.namespace.clone()),
+                    references::http_route_backend_refs(&http_route),
+                )
+            })
+        }
+        ("", "PersistentVolumeClaim")
+            if settings.checks(ReferencePoint::PersistentVolumeClaimDataSources) =>
+        {
+            serde_json::from_value::<PersistentVolumeClaim>(request.object).map(|pvc| {
+                (
+                    Some(request.namespace.clone()),
+                    references::data_source_ref(&pvc),
+                )
+            })
+        }
+        _ => return kubewarden::accept_request(),
+    };
+
+    let (namespace, references) = match references {
+        Ok((Some(namespace), references)) => (namespace, references),
+        Ok((None, _)) => return kubewarden::accept_request(),
+        Err(_) => {
+            return kubewarden::reject_request(
+                Some("Cannot parse validation request".to_owned()),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    let errors: Vec<String> = references
+        .iter()
+        .filter_map(|reference| validate_reference(reference, &namespace, settings).err())
+        .collect();
+    if errors.is_empty() {
+        return kubewarden::accept_request();
+    }
+    kubewarden::reject_request(
+        Some(format!(
+            "{} '{}': {}",
+            request.kind.kind,
+            request.name,
+            errors.join("; ")
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+fn validate_reference(
+    reference: &ObjectReference,
+    namespace: &str,
+    settings: &Settings,
+) -> Result<(), String> {
+    let target_namespace = reference.namespace.as_deref().unwrap_or(namespace);
+    if settings.is_granted(namespace, &reference.kind, target_namespace) {
+        return Ok(());
+    }
+    Err(format!(
+        "{}: the {} '{target_namespace}/{}' of another namespace cannot be referenced from the '{namespace}' namespace",
+        reference.field, reference.kind, reference.name
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        serde_json::from_str(
+            r#"{"grants": [
+                {"namespace": "shared-certs", "kinds": ["Secret"], "from": ["team-a"]},
+                {"namespace": "storage", "kinds": ["Secret"], "from": ["*"]}
+            ]}"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    #[rstest]
+    #[case::ingress_granted_tls_secret("test_data/ingress_granted_tls_secret.json", true)]
+    #[case::ingress_cross_namespace_tls_secret(
+        "test_data/ingress_cross_namespace_tls_secret.json",
+        false
+    )]
+    #[case::rolebinding_same_namespace("test_data/rolebinding_same_namespace.json", true)]
+    #[case::rolebinding_cross_namespace(
+        "test_data/rolebinding_cross_namespace_service_account.json",
+        false
+    )]
+    #[case::persistentvolume_granted_secret("test_data/persistentvolume_granted_secret.json", true)]
+    #[case::persistentvolume_cross_namespace_secret(
+        "test_data/persistentvolume_cross_namespace_secret.json",
+        false
+    )]
+    #[case::persistentvolume_unbound("test_data/persistentvolume_unbound.json", true)]
+    #[case::httproute_same_namespace("test_data/httproute_same_namespace.json", true)]
+    #[case::httproute_cross_namespace("test_data/httproute_cross_namespace_backend.json", false)]
+    #[case::pvc_cross_namespace("test_data/pvc_cross_namespace_data_source.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::ingress(
+        "test_data/ingress_cross_namespace_tls_secret.json",
+        "Ingress 'web': spec.tls[0].secretName: the Secret 'production/web-tls' of another namespace cannot be referenced from the 'team-a' namespace"
+    )]
+    #[case::rolebinding(
+        "test_data/rolebinding_cross_namespace_service_account.json",
+        "RoleBinding 'deployers': subjects[1]: the ServiceAccount 'team-b/deployer' of another namespace cannot be referenced from the 'team-a' namespace"
+    )]
+    #[case::persistentvolume(
+        "test_data/persistentvolume_cross_namespace_secret.json",
+        "PersistentVolume 'team-a-data': spec.csi.nodeStageSecretRef: the Secret 'team-b/ceph-credentials' of another namespace cannot be referenced from the 'team-a' namespace"
+    )]
+    #[case::httproute(
+        "test_data/httproute_cross_namespace_backend.json",
+        "HTTPRoute 'web': spec.rules[0].backendRefs[1]: the Service 'team-b/api' of another namespace cannot be referenced from the 'team-a' namespace"
+    )]
+    #[case::pvc(
+        "test_data/pvc_cross_namespace_data_source.json",
+        "PersistentVolumeClaim 'postgres-data': spec.dataSourceRef: the VolumeSnapshot.snapshot.storage.k8s.io 'production/postgres-data' of another namespace cannot be referenced from the 'team-a' namespace"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn reference_point_not_checked() {
+        let test_case = Testcase {
+            name: "reference point not checked".to_owned(),
+            fixture_file: "test_data/httproute_cross_namespace_backend.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings {
+                reference_points: [ReferencePoint::IngressTlsSecrets].into(),
+                ..settings()
+            },
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
@@ -0,0 +1,361 @@
+use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use k8s_openapi::api::networking::v1::Ingress;
+use k8s_openapi::api::rbac::v1::RoleBinding;
+use serde_json::Value;
+
+use crate::gateway_api::{BackendRef, HttpRoute, HttpRouteFilter};
+
+const SERVICE_ACCOUNT_USER_PREFIX: &str = "system:serviceaccount:";
+const SERVICE_ACCOUNTS_GROUP: &str = "system:serviceaccounts";
+
+/// Object referenced by a field of the resource being validated
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ObjectReference {
+    /// Path of the field holding the reference
+    pub field: String,
+    /// Kind of the object, written as `Kind` for the core resources and
+    /// `Kind.group` for the others
+    pub kind: String,
+    pub name: String,
+    /// Namespace of the object, the one of the resource when not set
+    pub namespace: Option<String>,
+}
+
+impl ObjectReference {
+    fn new(field: String, kind: &str, name: &str, namespace: Option<&str>) -> Self {
+        ObjectReference {
+            field,
+            kind: kind.to_owned(),
+            name: name.to_owned(),
+            namespace: namespace.map(str::to_owned),
+        }
+    }
+}
+
+/// The TLS Secrets written as `namespace/name`. Ingress controllers like
+/// Contour and HAProxy use this syntax to load the Secrets of other
+/// namespaces.
+pub(crate) fn ingress_tls_secrets(ingress: &Ingress) -> Vec<ObjectReference> {
+    ingress
+        .spec
+        .iter()
+        .flat_map(|spec| spec.tls.iter().flatten())
+        .enumerate()
+        .filter_map(|(index, tls)| {
+            let (namespace, name) = tls.secret_name.as_deref()?.split_once('/')?;
+            Some(ObjectReference::new(
+                format!("spec.tls[{index}].secretName"),
+                "Secret",
+                name,
+                Some(namespace),
+            ))
+        })
+        .collect()
+}
+
+/// The ServiceAccounts bound by the RoleBinding, including the ones bound
+/// with their username and with the group of the ServiceAccounts of their
+/// namespace. Binding the group of all the ServiceAccounts is reported as a
+/// reference to the `*` namespace.
+pub(crate) fn role_binding_subjects(role_binding: &RoleBinding) -> Vec<ObjectReference> {
+    role_binding
+        .subjects
+        .iter()
+        .flatten()
+        .enumerate()
+        .filter_map(|(index, subject)| {
+            let field = format!("subjects[{index}]");
+            match subject.kind.as_str() {
+                "ServiceAccount" => Some(ObjectReference::new(
+                    field,
+                    "ServiceAccount",
+                    &subject.name,
+                    subject.namespace.as_deref(),
+                )),
+                "User" => {
+                    let (namespace, name) = subject
+                        .name
+                        .strip_prefix(SERVICE_ACCOUNT_USER_PREFIX)?
+                        .split_once(':')?;
+                    Some(ObjectReference::new(
+                        field,
+                        "ServiceAccount",
+                        name,
+                        Some(namespace),
+                    ))
+                }
+                "Group" => {
+                    let namespace = subject.name.strip_prefix(SERVICE_ACCOUNTS_GROUP)?;
+                    let namespace = match namespace.strip_prefix(':') {
+                        Some(namespace) => namespace,
+                        None if namespace.is_empty() => "*",
+                        None => return None,
+                    };
+                    Some(ObjectReference::new(
+                        field,
+                        "ServiceAccount",
+                        "*",
+                        Some(namespace),
+                    ))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// The Secrets used by the volume source of the PersistentVolume. The sources
+/// are not parsed: all their `*SecretRef` fields are taken into account,
+/// together with the `secretNamespace` of the Azure File volumes.
+pub(crate) fn volume_source_secrets(persistent_volume: &Value) -> Vec<ObjectReference> {
+    let Some(spec) = persistent_volume.get("spec").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let mut references = Vec::new();
+    for (source_name, source) in spec {
+        let Some(source) = source.as_object() else {
+            continue;
+        };
+        for (field, value) in source {
+            if field == "secretRef" || field.ends_with("SecretRef") {
+                references.push(ObjectReference::new(
+                    format!("spec.{source_name}.{field}"),
+                    "Secret",
+                    value["name"].as_str().unwrap_or_default(),
+                    value["namespace"].as_str(),
+                ));
+            } else if field == "secretNamespace" {
+                references.push(ObjectReference::new(
+                    format!("spec.{source_name}.{field}"),
+                    "Secret",
+                    source
+                        .get("secretName")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default(),
+                    value.as_str(),
+                ));
+            }
+        }
+    }
+    references
+}
+
+/// The backends of the HTTPRoute, including the ones receiving the mirrored
+/// requests
+pub(crate) fn http_route_backend_refs(http_route: &HttpRoute) -> Vec<ObjectReference> {
+    let reference = |field: String, backend_ref: &BackendRef| {
+        ObjectReference::new(
+            field,
+            &backend_ref.qualified_kind(),
+            &backend_ref.name,
+            backend_ref.namespace.as_deref(),
+        )
+    };
+
+    // The backends receiving the requests mirrored by the filters
+    let mirrors = |field: &str, filters: &[HttpRouteFilter]| -> Vec<ObjectReference> {
+        filters
+            .iter()
+            .enumerate()
+            .filter_map(|(index, filter)| {
+                filter.request_mirror.as_ref().map(|request_mirror| {
+                    reference(
+                        format!("{field}.filters[{index}].requestMirror.backendRef"),
+                        &request_mirror.backend_ref,
+                    )
+                })
+            })
+            .collect()
+    };
+
+    let mut references = Vec::new();
+    for (rule_index, rule) in http_route.spec.rules.iter().enumerate() {
+        let rule_field = format!("spec.rules[{rule_index}]");
+        for (index, backend_ref) in rule.backend_refs.iter().enumerate() {
+            let field = format!("{rule_field}.backendRefs[{index}]");
+            let mirrored = mirrors(&field, &backend_ref.filters);
+            references.push(reference(field, backend_ref));
+            references.extend(mirrored);
+        }
+        references.extend(mirrors(&rule_field, &rule.filters));
+    }
+    references
+}
+
+/// The data source of the PersistentVolumeClaim. Only `dataSourceRef` can
+/// reference the objects of other namespaces.
+pub(crate) fn data_source_ref(pvc: &PersistentVolumeClaim) -> Vec<ObjectReference> {
+    pvc.spec
+        .as_ref()
+        .and_then(|spec| spec.data_source_ref.as_ref())
+        .map(|data_source_ref| {
+            let kind = match data_source_ref.api_group.as_deref() {
+                None | Some("") => data_source_ref.kind.clone(),
+                Some(group) => format!("{}.{group}", data_source_ref.kind),
+            };
+            ObjectReference::new(
+                "spec.dataSourceRef".to_owned(),
+                &kind,
+                &data_source_ref.name,
+                data_source_ref.namespace.as_deref(),
+            )
+        })
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn reference(field: &str, kind: &str, name: &str, namespace: &str) -> ObjectReference {
+        ObjectReference::new(field.to_owned(), kind, name, Some(namespace))
+    }
+
+    #[test]
+    fn ingress_references() {
+        let ingress: Ingress = serde_json::from_value(json!({
+            "spec": {"tls": [
+                {"hosts": ["a.example.com"], "secretName": "a-example-com"},
+                {"hosts": ["*.example.com"], "secretName": "shared-certs/wildcard"}
+            ]}
+        }))
+        .expect("cannot parse the Ingress");
+        assert_eq!(
+            ingress_tls_secrets(&ingress),
+            vec![reference(
+                "spec.tls[1].secretName",
+                "Secret",
+                "wildcard",
+                "shared-certs"
+            )]
+        );
+    }
+
+    #[rstest]
+    #[case::service_account(
+        json!({"kind": "ServiceAccount", "name": "deployer", "namespace": "ci"}),
+        Some(reference("subjects[0]", "ServiceAccount", "deployer", "ci"))
+    )]
+    #[case::service_account_user(
+        json!({"kind": "User", "apiGroup": "rbac.authorization.k8s.io", "name": "system:serviceaccount:ci:deployer"}),
+        Some(reference("subjects[0]", "ServiceAccount", "deployer", "ci"))
+    )]
+    #[case::service_accounts_group(
+        json!({"kind": "Group", "apiGroup": "rbac.authorization.k8s.io", "name": "system:serviceaccounts:ci"}),
+        Some(reference("subjects[0]", "ServiceAccount", "*", "ci"))
+    )]
+    #[case::all_service_accounts(
+        json!({"kind": "Group", "apiGroup": "rbac.authorization.k8s.io", "name": "system:serviceaccounts"}),
+        Some(reference("subjects[0]", "ServiceAccount", "*", "*"))
+    )]
+    #[case::user(
+        json!({"kind": "User", "apiGroup": "rbac.authorization.k8s.io", "name": "jane"}),
+        None
+    )]
+    fn role_binding_references(#[case] subject: Value, #[case] expected: Option<ObjectReference>) {
+        let role_binding: RoleBinding = serde_json::from_value(json!({
+            "metadata": {"name": "deployers", "namespace": "team-a"},
+            "roleRef": {"apiGroup": "rbac.authorization.k8s.io", "kind": "ClusterRole", "name": "edit"},
+            "subjects": [subject]
+        }))
+        .expect("cannot parse the RoleBinding");
+        assert_eq!(
+            role_binding_subjects(&role_binding),
+            expected.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn volume_source_references() {
+        let persistent_volume = json!({
+            "spec": {
+                "capacity": {"storage": "10Gi"},
+                "claimRef": {"name": "data", "namespace": "team-a"},
+                "csi": {
+                    "driver": "cephfs.csi.ceph.com",
+                    "volumeHandle": "data",
+                    "nodeStageSecretRef": {"name": "ceph-credentials", "namespace": "storage"}
+                },
+                "azureFile": {"secretName": "azure-credentials", "secretNamespace": "team-a", "shareName": "data"}
+            }
+        });
+        // the order of the sources depends on the features of serde_json
+        let references = volume_source_secrets(&persistent_volume);
+        assert_eq!(references.len(), 2);
+        assert!(references.contains(&reference(
+            "spec.azureFile.secretNamespace",
+            "Secret",
+            "azure-credentials",
+            "team-a"
+        )));
+        assert!(references.contains(&reference(
+            "spec.csi.nodeStageSecretRef",
+            "Secret",
+            "ceph-credentials",
+            "storage"
+        )));
+    }
+
+    #[test]
+    fn http_route_references() {
+        let http_route: HttpRoute = serde_json::from_value(json!({
+            "spec": {"rules": [{
+                "backendRefs": [
+                    {"name": "web", "port": 8080},
+                    {"group": "example.com", "kind": "Backend", "name": "api", "namespace": "shared"},
+                    {
+                        "name": "canary",
+                        "filters": [{
+                            "type": "RequestMirror",
+                            "requestMirror": {"backendRef": {"name": "capture", "namespace": "debug", "port": 80}}
+                        }]
+                    }
+                ],
+                "filters": [{
+                    "type": "RequestMirror",
+                    "requestMirror": {"backendRef": {"name": "audit", "namespace": "security", "port": 80}}
+                }]
+            }]}
+        }))
+        .expect("cannot parse the HTTPRoute");
+        assert_eq!(
+            http_route_backend_refs(&http_route),
+            vec![
+                ObjectReference::new(
+                    "spec.rules[0].backendRefs[0]".to_owned(),
+                    "Service",
+                    "web",
+                    None
+                ),
+                reference(
+                    "spec.rules[0].backendRefs[1]",
+                    "Backend.example.com",
+                    "api",
+                    "shared"
+                ),
+                ObjectReference::new(
+                    "spec.rules[0].backendRefs[2]".to_owned(),
+                    "Service",
+                    "canary",
+                    None
+                ),
+                reference(
+                    "spec.rules[0].backendRefs[2].filters[0].requestMirror.backendRef",
+                    "Service",
+                    "capture",
+                    "debug"
+                ),
+                reference(
+                    "spec.rules[0].filters[0].requestMirror.backendRef",
+                    "Service",
+                    "audit",
+                    "security"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+/// Fields of the resources that can reference the objects of other
+/// namespaces
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum ReferencePoint {
+    /// `spec.tls[].secretName` of the Ingresses, using the `namespace/name`
+    /// syntax supported by some ingress controllers
+    IngressTlsSecrets,
+    /// ServiceAccounts bound by the RoleBindings
+    RoleBindingSubjects,
+    /// Secrets used by the volume sources of the PersistentVolumes, checked
+    /// against the namespace of their claim
+    VolumeSourceSecrets,
+    /// Backends of the Gateway API HTTPRoutes
+    HttpRouteBackendRefs,
+    /// `spec.dataSourceRef` of the PersistentVolumeClaims
+    PersistentVolumeClaimDataSources,
+}
+
+/// Objects of a namespace that can be referenced by the resources of other
+/// namespaces
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Grant {
+    /// Namespace of the referenced objects
+    pub namespace: String,
+    /// Kinds of the referenced objects, written as `Kind` for the core
+    /// resources and `Kind.group` for the others. All kinds when empty.
+    pub kinds: BTreeSet<String>,
+    /// Namespaces of the resources that can reference the objects. `*` grants
+    /// the objects to all the namespaces.
+    pub from: BTreeSet<String>,
+}
+
+impl Grant {
+    fn allows(&self, namespace: &str, kind: &str, target_namespace: &str) -> bool {
+        self.namespace == target_namespace
+            && (self.kinds.is_empty() || self.kinds.contains(kind))
+            && (self.from.contains("*") || self.from.contains(namespace))
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Fields checked by the policy
+    pub reference_points: BTreeSet<ReferencePoint>,
+    /// Cross-namespace references allowed
+    pub grants: Vec<Grant>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            reference_points: BTreeSet::from([
+                ReferencePoint::IngressTlsSecrets,
+                ReferencePoint::RoleBindingSubjects,
+                ReferencePoint::VolumeSourceSecrets,
+                ReferencePoint::HttpRouteBackendRefs,
+                ReferencePoint::PersistentVolumeClaimDataSources,
+            ]),
+            grants: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn checks(&self, reference_point: ReferencePoint) -> bool {
+        self.reference_points.contains(&reference_point)
+    }
+
+    /// Returns true when the resources of the namespace can reference the
+    /// objects of the given kind living inside of the target namespace
+    pub fn is_granted(&self, namespace: &str, kind: &str, target_namespace: &str) -> bool {
+        namespace == target_namespace
+            || self
+                .grants
+                .iter()
+                .any(|grant| grant.allows(namespace, kind, target_namespace))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        for grant in &self.grants {
+            if grant.namespace.is_empty() {
+                return Err("grants: the namespace cannot be empty".to_owned());
+            }
+            if grant.kinds.contains("") {
+                return Err(format!(
+                    "grants: the kinds of the '{}' namespace cannot contain empty values",
+                    grant.namespace
+                ));
+            }
+            if grant.from.is_empty() || grant.from.contains("") {
+                return Err(format!(
+                    "grants: the namespaces allowed to reference the objects of '{}' cannot be empty",
+                    grant.namespace
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "referencePoints": ["ingressTlsSecrets", "httpRouteBackendRefs"],
+            "grants": [{"namespace": "shared-certs", "kinds": ["Secret"], "from": ["team-a"]}]
+        }"#,
+        true
+    )]
+    #[case::unknown_reference_point(r#"{"referencePoints": ["podVolumes"]}"#, false)]
+    #[case::empty_namespace(r#"{"grants": [{"from": ["team-a"]}]}"#, false)]
+    #[case::empty_kind(
+        r#"{"grants": [{"namespace": "shared-certs", "kinds": [""], "from": ["team-a"]}]}"#,
+        false
+    )]
+    #[case::no_namespace_granted(r#"{"grants": [{"namespace": "shared-certs"}]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings = serde_json::from_str::<Settings>(settings);
+        assert_eq!(
+            settings.is_ok_and(|settings| settings.validate().is_ok()),
+            is_valid
+        );
+    }
+
+    #[rstest]
+    #[case::same_namespace("team-a", "Secret", "team-a", true)]
+    #[case::granted_kind("team-a", "Secret", "shared-certs", true)]
+    #[case::not_granted_kind("team-a", "ConfigMap", "shared-certs", false)]
+    #[case::not_granted_namespace("team-b", "Secret", "shared-certs", false)]
+    #[case::granted_to_all("team-b", "Service", "shared-services", true)]
+    #[case::other_namespace("team-a", "Secret", "production", false)]
+    fn granted(
+        #[case] namespace: &str,
+        #[case] kind: &str,
+        #[case] target_namespace: &str,
+        #[case] expected: bool,
+    ) {
+        let settings: Settings = serde_json::from_str(
+            r#"{"grants": [
+                {"namespace": "shared-certs", "kinds": ["Secret"], "from": ["team-a"]},
+                {"namespace": "shared-services", "from": ["*"]}
+            ]}"#,
+        )
+        .expect("cannot parse settings");
+        assert_eq!(
+            settings.is_granted(namespace, kind, target_namespace),
+            expected
+        );
+    }
+}
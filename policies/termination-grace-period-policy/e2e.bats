@@ -0,0 +1,66 @@
+#!/usr/bin/env bats
+
+@test "Accept a Pod within bounds" {
+	run kwctl run \
+		--request-path test_data/pod_within_bounds.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patch".*') -eq 0 ]
+}
+
+@test "Reject a Pod below the minimum" {
+	run kwctl run \
+		--request-path test_data/pod_below_minimum.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*terminationGracePeriodSeconds 0 is below the minimum of 10.*') -ne 0 ]
+}
+
+@test "Reject a Deployment above the maximum" {
+	run kwctl run \
+		--request-path test_data/deployment_above_maximum.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*terminationGracePeriodSeconds 3600 is above the maximum of 300.*') -ne 0 ]
+}
+
+@test "Mutate a Deployment above the maximum" {
+	run kwctl run \
+		--request-path test_data/deployment_above_maximum.json \
+		--settings-json '{"minimumSeconds": 10, "maximumSeconds": 300, "mutate": true}' \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"patchType":"JSONPatch".*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/pod_within_bounds.json --settings-json '{"minimumSeconds": 300, "maximumSeconds": 10}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
@@ -0,0 +1,187 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+use serde::{Deserialize, Serialize};
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+// Value used by Kubernetes when `terminationGracePeriodSeconds` is not set
+const DEFAULT_TERMINATION_GRACE_PERIOD_SECONDS: i64 = 30;
+
+/// Field of the Pod spec checked and mutated by the policy
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    termination_grace_period_seconds: Option<i64>,
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+
+    let request = &validation_request.request;
+    let mut pod_spec =
+        match partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object) {
+            Ok(Some(pod_spec)) => pod_spec,
+            // The resources without Pod spec are not validated
+            Ok(None) => return kubewarden::accept_request(),
+            Err(_) => {
+                return kubewarden::reject_request(
+                    Some("Cannot parse validation request".to_owned()),
+                    None,
+                    None,
+                    None,
+                );
+            }
+        };
+
+    let settings = &validation_request.settings;
+    let seconds = pod_spec
+        .termination_grace_period_seconds
+        .unwrap_or(DEFAULT_TERMINATION_GRACE_PERIOD_SECONDS);
+    let Some(bound) = settings.nearest_bound(seconds) else {
+        return kubewarden::accept_request();
+    };
+    if settings.mutate {
+        pod_spec.termination_grace_period_seconds = Some(bound);
+        return kubewarden::mutate_request(partial_pod_spec::patch(
+            &request.kind.kind,
+            &request.object,
+            &pod_spec,
+        )?);
+    }
+
+    let error = if bound > seconds {
+        format!("terminationGracePeriodSeconds {seconds} is below the minimum of {bound}")
+    } else {
+        format!("terminationGracePeriodSeconds {seconds} is above the maximum of {bound}")
+    };
+    kubewarden::reject_request(
+        Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+        None,
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn settings(mutate: bool) -> Settings {
+        Settings {
+            minimum_seconds: Some(10),
+            maximum_seconds: Some(300),
+            mutate,
+        }
+    }
+
+    #[rstest]
+    #[case::within_bounds("test_data/pod_within_bounds.json", true)]
+    #[case::default_value("test_data/pod_without_grace_period.json", true)]
+    #[case::below_minimum("test_data/pod_below_minimum.json", false)]
+    #[case::above_maximum("test_data/deployment_above_maximum.json", false)]
+    #[case::without_pod_spec("test_data/configmap.json", true)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(false),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert!(response.mutated_object.is_none());
+    }
+
+    #[rstest]
+    #[case::below_minimum(
+        "test_data/pod_below_minimum.json",
+        "Pod 'web': terminationGracePeriodSeconds 0 is below the minimum of 10"
+    )]
+    #[case::above_maximum(
+        "test_data/deployment_above_maximum.json",
+        "Deployment 'web': terminationGracePeriodSeconds 3600 is above the maximum of 300"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(false),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn mutate_pod() {
+        let test_case = Testcase {
+            name: "mutate pod".to_owned(),
+            fixture_file: "test_data/pod_below_minimum.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response.mutated_object.expect("the pod should be mutated");
+        assert_eq!(
+            mutated_object["spec"]["terminationGracePeriodSeconds"],
+            json!(10)
+        );
+    }
+
+    #[test]
+    fn mutate_deployment() {
+        let test_case = Testcase {
+            name: "mutate deployment".to_owned(),
+            fixture_file: "test_data/deployment_above_maximum.json".to_owned(),
+            expected_validation_result: true,
+            settings: settings(true),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response
+            .mutated_object
+            .expect("the deployment should be mutated");
+        assert_eq!(
+            mutated_object["spec"]["template"]["spec"]["terminationGracePeriodSeconds"],
+            json!(300)
+        );
+    }
+
+    #[test]
+    fn mutate_default_value() {
+        let test_case = Testcase {
+            name: "mutate default value".to_owned(),
+            fixture_file: "test_data/pod_without_grace_period.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings {
+                minimum_seconds: Some(60),
+                maximum_seconds: None,
+                mutate: true,
+            },
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        let mutated_object = response.mutated_object.expect("the pod should be mutated");
+        assert_eq!(
+            mutated_object["spec"]["terminationGracePeriodSeconds"],
+            json!(60)
+        );
+    }
+}
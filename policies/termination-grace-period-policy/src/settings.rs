@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Minimum value of `terminationGracePeriodSeconds`
+    pub minimum_seconds: Option<i64>,
+    /// Maximum value of `terminationGracePeriodSeconds`
+    pub maximum_seconds: Option<i64>,
+    /// Set the values out of range to the nearest bound, instead of rejecting
+    /// the request
+    pub mutate: bool,
+}
+
+impl Settings {
+    /// Returns the nearest bound when the value is out of range
+    pub fn nearest_bound(&self, seconds: i64) -> Option<i64> {
+        if let Some(minimum) = self.minimum_seconds
+            && seconds < minimum
+        {
+            return Some(minimum);
+        }
+        if let Some(maximum) = self.maximum_seconds
+            && seconds > maximum
+        {
+            return Some(maximum);
+        }
+        None
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.minimum_seconds.is_none() && self.maximum_seconds.is_none() {
+            return Err("at least one of minimumSeconds and maximumSeconds must be set".to_owned());
+        }
+        if self.minimum_seconds.is_some_and(|minimum| minimum < 0)
+            || self.maximum_seconds.is_some_and(|maximum| maximum < 0)
+        {
+            return Err("minimumSeconds and maximumSeconds cannot be negative".to_owned());
+        }
+        if let (Some(minimum), Some(maximum)) = (self.minimum_seconds, self.maximum_seconds)
+            && minimum > maximum
+        {
+            return Err(format!(
+                "minimumSeconds {minimum} cannot be greater than maximumSeconds {maximum}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::bounds(r#"{"minimumSeconds": 10, "maximumSeconds": 300}"#, true)]
+    #[case::minimum_only(r#"{"minimumSeconds": 10, "mutate": true}"#, true)]
+    #[case::maximum_only(r#"{"maximumSeconds": 300}"#, true)]
+    #[case::same_bounds(r#"{"minimumSeconds": 30, "maximumSeconds": 30}"#, true)]
+    #[case::no_bounds(r#"{}"#, false)]
+    #[case::negative_bound(r#"{"minimumSeconds": -1}"#, false)]
+    #[case::reversed_bounds(r#"{"minimumSeconds": 300, "maximumSeconds": 10}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::below_minimum(5, Some(10))]
+    #[case::minimum(10, None)]
+    #[case::within_bounds(30, None)]
+    #[case::maximum(300, None)]
+    #[case::above_maximum(600, Some(300))]
+    fn nearest_bound(#[case] seconds: i64, #[case] expected: Option<i64>) {
+        let settings = Settings {
+            minimum_seconds: Some(10),
+            maximum_seconds: Some(300),
+            mutate: false,
+        };
+        assert_eq!(settings.nearest_bound(seconds), expected);
+    }
+}
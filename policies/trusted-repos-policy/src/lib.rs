@@ -5,7 +5,8 @@ use k8s_openapi::api::{
     core::v1::{Pod, ReplicationController},
 };
 use kubewarden_policy_sdk::{
-    accept_request, logging, protocol_version_guest, request::ValidationRequest, validate_settings,
+    accept_request, logging, protocol_version_guest, reject_request, request::ValidationRequest,
+    validate_settings,
 };
 use kubewarden_policy_sdk::{response::ValidationResponse, wapc_guest as guest};
 use lazy_static::lazy_static;
@@ -23,7 +24,7 @@ use validating_resource::ValidatingResource;
 pub(crate) mod matchers;
 
 mod settings;
-use settings::Settings;
+use settings::{BundledRules, Settings};
 
 lazy_static! {
     static ref LOG_DRAIN: Logger = Logger::root(
@@ -87,11 +88,32 @@ fn validate_resource<T: ValidatingResource + DeserializeOwned>(
         }
     };
 
-    let validation_response: ValidationResponse =
-        validate_pod_spec(&spec, &validation_request.settings).into();
+    let settings = match with_bundled_rules(validation_request.settings) {
+        Ok(settings) => settings,
+        Err(e) => {
+            return reject_request(
+                Some(format!("Cannot load the rule bundle: {e}")),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    let validation_response: ValidationResponse = validate_pod_spec(&spec, &settings).into();
     Ok(serde_json::to_vec(&validation_response)?)
 }
 
+// The rule bundle is fetched only for the resources having a Pod spec, the
+// other ones are accepted without contacting the registry
+fn with_bundled_rules(settings: Settings) -> anyhow::Result<Settings> {
+    let Some(rule_bundle) = &settings.rule_bundle else {
+        return Ok(settings);
+    };
+    let bundled_rules: BundledRules = rule_bundle.fetch()?;
+    Ok(settings.with_bundled_rules(bundled_rules))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
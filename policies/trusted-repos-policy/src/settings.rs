@@ -2,6 +2,7 @@ use std::{collections::HashSet, str::FromStr};
 
 use kubewarden_policy_sdk::settings::Validatable;
 use oci_spec::distribution::Reference;
+use rule_bundle::RuleBundle;
 use serde::{Deserialize, Serialize};
 
 use crate::matchers::{image::ImageMatcher, registry::RegistryMatcher, tag::TagMatcher};
@@ -79,6 +80,22 @@ pub(crate) struct Settings {
     pub registries: Registries,
     pub tags: Tags,
     pub images: Images,
+    /// Signed OCI artifact with more registries, tags and images to reject
+    #[serde(rename = "ruleBundle")]
+    pub rule_bundle: Option<RuleBundle>,
+}
+
+impl Settings {
+    /// Adds the registries, tags and images rejected by the rule bundle to
+    /// the ones rejected by the settings
+    pub(crate) fn with_bundled_rules(mut self, bundled_rules: BundledRules) -> Self {
+        self.registries
+            .reject
+            .extend(bundled_rules.registries.reject);
+        self.tags.reject.extend(bundled_rules.tags.reject);
+        self.images.reject.extend(bundled_rules.images.reject);
+        self
+    }
 }
 
 impl Validatable for Settings {
@@ -87,6 +104,9 @@ impl Validatable for Settings {
             self.registries.validate(),
             self.images.validate(),
             self.tags.validate(),
+            self.rule_bundle
+                .as_ref()
+                .map_or(Ok(()), RuleBundle::validate),
         ]
         .into_iter()
         .filter_map(Result::err)
@@ -100,6 +120,29 @@ impl Validatable for Settings {
     }
 }
 
+/// Rules distributed with the rule bundle, using the format of the settings.
+/// A bundle can only add to the reject lists: an allow list would reject all
+/// the other images on every cluster sharing the bundle.
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+pub(crate) struct BundledRules {
+    pub registries: Registries,
+    pub tags: Tags,
+    pub images: Images,
+}
+
+impl Validatable for BundledRules {
+    fn validate(&self) -> Result<(), String> {
+        if !self.registries.allow.is_empty() {
+            return Err("the rule bundle cannot allow registries".to_string());
+        }
+        if !self.images.allow.is_empty() {
+            return Err("the rule bundle cannot allow images".to_string());
+        }
+        self.tags.validate()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,6 +174,7 @@ mod tests {
                 reject: vec!["busybox".to_string()].into_iter().map(|image| Reference::from_str(&image).unwrap().into()).collect(),
                 ..Images::default()
             },
+            rule_bundle: None,
         },
         true
     )]
@@ -147,6 +191,7 @@ mod tests {
                 reject: vec!["busybox".to_string()].into_iter().map(|image| Reference::from_str(&image).unwrap().into()).collect(),
                 ..Images::default()
             },
+            rule_bundle: None,
         },
         false
     )]
@@ -174,4 +219,54 @@ mod tests {
         let result = settings.validate();
         assert!(result.is_ok(), "{result:?}");
     }
+
+    #[test]
+    fn validate_rule_bundle() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"ruleBundle": {"image": "ghcr.io/acme/rules:v1", "keys": []}}"#,
+        )
+        .unwrap();
+        assert!(settings.validate().is_err(), "the keys are missing");
+    }
+
+    #[rstest]
+    #[case::reject_lists(
+        r#"{"registries": {"reject": ["*.evil.com"]}, "tags": {"reject": ["latest"]}, "images": {"reject": ["nginx"]}}"#,
+        true
+    )]
+    #[case::registries_allow_list(r#"{"registries": {"allow": ["registry.com"]}}"#, false)]
+    #[case::images_allow_list(r#"{"images": {"allow": ["nginx"]}}"#, false)]
+    #[case::invalid_tag(r#"{"tags": {"reject": ["in valid"]}}"#, false)]
+    fn validate_bundled_rules(#[case] rules: &str, #[case] is_valid: bool) {
+        let bundled_rules: BundledRules = serde_json::from_str(rules).unwrap();
+        assert_eq!(bundled_rules.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn merge_bundled_rules() {
+        let settings = Settings {
+            registries: Registries {
+                allow: vec![exact_registry("registry.com"), exact_registry("evil.com")]
+                    .into_iter()
+                    .collect(),
+                ..Registries::default()
+            },
+            ..Settings::default()
+        };
+        let bundled_rules: BundledRules = serde_json::from_str(
+            r#"{"registries": {"reject": ["evil.com"]}, "tags": {"reject": ["latest"]}}"#,
+        )
+        .unwrap();
+
+        let settings = settings.with_bundled_rules(bundled_rules);
+        assert_eq!(settings.registries.allow.len(), 2);
+        assert_eq!(
+            settings.registries.reject,
+            vec![exact_registry("evil.com")].into_iter().collect()
+        );
+        assert_eq!(
+            settings.tags.reject,
+            vec![exact_tag("latest")].into_iter().collect()
+        );
+    }
 }
@@ -0,0 +1,51 @@
+#!/usr/bin/env bats
+
+@test "Reject a Service claiming a hostname of another namespace" {
+	run kwctl run \
+		--request-path test_data/service_hostname.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-hostname-claimed.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*Service 'web': the value 'www.example.com' of the annotation 'external-dns.alpha.kubernetes.io/hostname' is already claimed by 'team-b/web'.*") -ne 0 ]
+}
+
+@test "Accept a Service sharing a hostname with its namespace" {
+	run kwctl run \
+		--request-path test_data/service_hostname.json \
+		--allow-context-aware \
+		--replay-host-capabilities-interactions test_data/session-hostname-same-namespace.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a Service without hostname" {
+	run kwctl run \
+		--request-path test_data/service_without_hostname.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/service_without_hostname.json --settings-json '{"annotations": [], "labels": []}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
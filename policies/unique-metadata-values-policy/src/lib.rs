@@ -0,0 +1,270 @@
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::Service;
+use k8s_openapi::api::networking::v1::Ingress;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use k8s_openapi::{ListableResource, Metadata};
+use kubewarden_policy_sdk::wapc_guest as guest;
+use serde::de::DeserializeOwned;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{
+    host_capabilities::kubernetes::ListAllResourcesRequest, protocol_version_guest,
+    request::ValidationRequest, validate_settings,
+};
+
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::list_all_resources;
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::list_all_resources;
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = validation_request.settings;
+
+    let metadata: ObjectMeta = match request.object.get("metadata") {
+        Some(metadata) => serde_json::from_value(metadata.clone())?,
+        None => return kubewarden::accept_request(),
+    };
+    let claims = settings.claims(&metadata);
+    if claims.is_empty() {
+        return kubewarden::accept_request();
+    }
+
+    let others = match list_metadata(&request.kind.group, &request.kind.kind) {
+        Ok(others) => others,
+        Err(error) => {
+            return kubewarden::reject_request(
+                Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+                None,
+                None,
+                None,
+            );
+        }
+    };
+
+    // The values can be shared by the resources of the same namespace: only
+    // the claims of the other namespaces are conflicts
+    let others: Vec<&ObjectMeta> = others
+        .iter()
+        .filter(|other| other.namespace.as_deref() != Some(request.namespace.as_str()))
+        .collect();
+    let conflicts: Vec<String> = claims
+        .iter()
+        .filter_map(|claim| {
+            others
+                .iter()
+                .find(|other| settings.claims(other).contains(claim))
+                .map(|other| {
+                    format!(
+                        "{claim} is already claimed by '{}/{}'",
+                        other.namespace.as_deref().unwrap_or_default(),
+                        other.name.as_deref().unwrap_or_default()
+                    )
+                })
+        })
+        .collect();
+    if conflicts.is_empty() {
+        return kubewarden::accept_request();
+    }
+
+    kubewarden::reject_request(
+        Some(format!(
+            "{} '{}': {}",
+            request.kind.kind,
+            request.name,
+            conflicts.join(", ")
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+// Returns the metadata of all the resources of the kind
+fn list_metadata(group: &str, kind: &str) -> Result<Vec<ObjectMeta>, String> {
+    match (group, kind) {
+        ("", "Service") => list_all_metadata::<Service>(),
+        ("networking.k8s.io", "Ingress") => list_all_metadata::<Ingress>(),
+        _ => Err(format!("the {kind} resources are not supported")),
+    }
+}
+
+fn list_all_metadata<T>() -> Result<Vec<ObjectMeta>, String>
+where
+    T: ListableResource + Metadata<Ty = ObjectMeta> + DeserializeOwned + Clone + 'static,
+{
+    let resources = list_all_resources::<T>(&ListAllResourcesRequest {
+        api_version: T::API_VERSION.to_owned(),
+        kind: T::KIND.to_owned(),
+        label_selector: None,
+        field_selector: None,
+        field_masks: None,
+    })
+    .map_err(|e| format!("cannot list the {} resources: {e}", T::KIND))?;
+
+    Ok(resources
+        .items
+        .iter()
+        .map(|resource| resource.metadata().clone())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use rstest::rstest;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::ListAllResourcesRequest;
+
+        #[allow(dead_code)]
+        pub fn list_all_resources<T>(
+            _req: &ListAllResourcesRequest,
+        ) -> anyhow::Result<k8s_openapi::List<T>>
+        where
+            T: k8s_openapi::ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+        {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    fn service(namespace: &str, name: &str, hostname: &str) -> Service {
+        Service {
+            metadata: ObjectMeta {
+                name: Some(name.to_owned()),
+                namespace: Some(namespace.to_owned()),
+                annotations: Some(BTreeMap::from([(
+                    "external-dns.alpha.kubernetes.io/hostname".to_owned(),
+                    hostname.to_owned(),
+                )])),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::no_other_services(vec![], true)]
+    #[case::other_hostname(vec![service("team-b", "api", "api.example.com")], true)]
+    #[case::same_namespace(vec![service("team-a", "web-canary", "www.example.com")], true)]
+    #[case::other_namespace(vec![service("team-b", "web", "www.example.com")], false)]
+    #[case::other_namespace_list(
+        vec![service("team-b", "web", "api.example.com,shop.example.com")],
+        false
+    )]
+    #[serial]
+    fn validate_service(#[case] services: Vec<Service>, #[case] expected_validation_result: bool) {
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Service>().times(1).returning(move |req| {
+            assert_eq!(req.kind, "Service");
+            Ok(k8s_openapi::List::<Service> {
+                items: services.clone(),
+                ..Default::default()
+            })
+        });
+
+        let test_case = Testcase {
+            name: "validate service".to_owned(),
+            fixture_file: "test_data/service_hostname.json".to_owned(),
+            expected_validation_result,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    #[serial]
+    fn no_claims() {
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Service>().times(0);
+
+        let test_case = Testcase {
+            name: "no claims".to_owned(),
+            fixture_file: "test_data/service_without_hostname.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    #[serial]
+    fn rejection_message() {
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Service>().times(1).returning(|_| {
+            Ok(k8s_openapi::List::<Service> {
+                items: vec![
+                    service("team-b", "web", "www.example.com"),
+                    service("team-c", "shop", "shop.example.com"),
+                ],
+                ..Default::default()
+            })
+        });
+
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/service_hostname.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "Service 'web': the value 'shop.example.com' of the annotation 'external-dns.alpha.kubernetes.io/hostname' is already claimed by 'team-c/shop', the value 'www.example.com' of the annotation 'external-dns.alpha.kubernetes.io/hostname' is already claimed by 'team-b/web'"
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn resources_not_listed() {
+        let ctx = mock_kubernetes_sdk::list_all_resources_context();
+        ctx.expect::<Service>()
+            .times(1)
+            .returning(|_| Err(anyhow::anyhow!("forbidden")));
+
+        let test_case = Testcase {
+            name: "resources not listed".to_owned(),
+            fixture_file: "test_data/service_hostname.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some("Service 'web': cannot list the Service resources: forbidden")
+        );
+    }
+
+    #[test]
+    fn unsupported_kind() {
+        assert_eq!(
+            list_metadata("", "ConfigMap"),
+            Err("the ConfigMap resources are not supported".to_owned())
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+/// Annotation used by external-dns to claim DNS names
+const EXTERNAL_DNS_HOSTNAME: &str = "external-dns.alpha.kubernetes.io/hostname";
+
+/// Annotation, or label, whose values must be unique across the cluster
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct UniqueKey {
+    /// Key of the annotation, or of the label
+    pub key: String,
+    /// Separator of the values, when a single annotation or label holds a
+    /// list of values
+    #[serde(default)]
+    pub separator: Option<String>,
+}
+
+impl UniqueKey {
+    /// Returns the values held by the key, if any
+    pub fn values(&self, metadata: Option<&BTreeMap<String, String>>) -> Vec<String> {
+        let Some(value) = metadata.and_then(|metadata| metadata.get(&self.key)) else {
+            return vec![];
+        };
+        let values: Vec<&str> = match &self.separator {
+            Some(separator) => value.split(separator.as_str()).collect(),
+            None => vec![value],
+        };
+        values
+            .into_iter()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+}
+
+/// Value claimed by a resource through one of the unique keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Claim<'a> {
+    /// Either `annotation` or `label`
+    pub source: &'static str,
+    pub key: &'a str,
+    pub value: String,
+}
+
+impl fmt::Display for Claim<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the value '{}' of the {} '{}'",
+            self.value, self.source, self.key
+        )
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Annotations whose values must be unique across the namespaces
+    pub annotations: Vec<UniqueKey>,
+    /// Labels whose values must be unique across the namespaces
+    pub labels: Vec<UniqueKey>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            annotations: vec![UniqueKey {
+                key: EXTERNAL_DNS_HOSTNAME.to_owned(),
+                separator: Some(",".to_owned()),
+            }],
+            labels: vec![],
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the values claimed by the resource
+    pub fn claims(&self, metadata: &ObjectMeta) -> Vec<Claim<'_>> {
+        let annotations = self.annotations.iter().flat_map(|unique_key| {
+            unique_key
+                .values(metadata.annotations.as_ref())
+                .into_iter()
+                .map(|value| Claim {
+                    source: "annotation",
+                    key: &unique_key.key,
+                    value,
+                })
+        });
+        let labels = self.labels.iter().flat_map(|unique_key| {
+            unique_key
+                .values(metadata.labels.as_ref())
+                .into_iter()
+                .map(|value| Claim {
+                    source: "label",
+                    key: &unique_key.key,
+                    value,
+                })
+        });
+        annotations.chain(labels).collect()
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.annotations.is_empty() && self.labels.is_empty() {
+            return Err("at least one annotation or label must be provided".to_owned());
+        }
+        let unique_keys = self.annotations.iter().chain(self.labels.iter());
+        for unique_key in unique_keys {
+            if unique_key.key.is_empty() {
+                return Err(
+                    "the keys of the annotations and of the labels cannot be empty".to_owned(),
+                );
+            }
+            if unique_key.separator.as_deref() == Some("") {
+                return Err(format!(
+                    "the separator of '{}' cannot be empty",
+                    unique_key.key
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::not_set(None, vec![])]
+    #[case::single_value(Some("shop.example.com"), vec!["shop.example.com"])]
+    #[case::list_of_values(
+        Some("shop.example.com, www.example.com,,"),
+        vec!["shop.example.com", "www.example.com"]
+    )]
+    fn external_dns_hostnames(#[case] hostname: Option<&str>, #[case] expected: Vec<&str>) {
+        let metadata = ObjectMeta {
+            annotations: hostname.map(|hostname| {
+                BTreeMap::from([(EXTERNAL_DNS_HOSTNAME.to_owned(), hostname.to_owned())])
+            }),
+            ..Default::default()
+        };
+        let values: Vec<String> = Settings::default()
+            .claims(&metadata)
+            .into_iter()
+            .map(|claim| claim.value)
+            .collect();
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn label_claims() {
+        let settings = Settings {
+            annotations: vec![],
+            labels: vec![UniqueKey {
+                key: "example.com/tenant-id".to_owned(),
+                separator: None,
+            }],
+        };
+        let metadata = ObjectMeta {
+            labels: Some(BTreeMap::from([(
+                "example.com/tenant-id".to_owned(),
+                "tenant-1".to_owned(),
+            )])),
+            annotations: Some(BTreeMap::from([(
+                "example.com/tenant-id".to_owned(),
+                "tenant-2".to_owned(),
+            )])),
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.claims(&metadata),
+            vec![Claim {
+                source: "label",
+                key: "example.com/tenant-id",
+                value: "tenant-1".to_owned(),
+            }]
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::labels(
+        r#"{"annotations": [], "labels": [{"key": "example.com/tenant-id"}]}"#,
+        true
+    )]
+    #[case::no_keys(r#"{"annotations": [], "labels": []}"#, false)]
+    #[case::empty_key(r#"{"labels": [{"key": ""}]}"#, false)]
+    #[case::empty_separator(
+        r#"{"annotations": [{"key": "example.com/hosts", "separator": ""}]}"#,
+        false
+    )]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn unknown_key_field() {
+        let settings = r#"{"labels": [{"name": "example.com/tenant-id"}]}"#;
+        assert!(serde_json::from_str::<Settings>(settings).is_err());
+    }
+}
@@ -24,3 +24,44 @@
 	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
 	[ $(expr "$output" : '.*"message":"service is using selector(s) already defined by these services.*') -ne 0 ]
 }
+
+@test "Warn about service with duplicated service selector" {
+	run kwctl run --allow-context-aware \
+		-r test_data/service-admission-request.json \
+		--settings-json '{"duplicateSelectorAction": "warn"}' \
+		--replay-host-capabilities-interactions test_data/replay-session-service-with-app-selector.yml \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+	[ $(expr "$output" : '.*"warnings":\["service is using selector(s) already defined by these services.*') -ne 0 ]
+}
+
+@test "Accept service without selector by default" {
+	run kwctl run \
+		-r test_data/selectorless-service-admission-request.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject service without selector created by a user that is not allowed" {
+	run kwctl run \
+		-r test_data/selectorless-service-admission-request.json \
+		--settings-json '{"rejectSelectorlessServices": true, "selectorlessAllowedGroups": ["network-admins"]}' \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*"message":"Services without a selector can be created only by the allowed users and groups".*') -ne 0 ]
+}
@@ -5,10 +5,13 @@ use kubewarden_policy_sdk::host_capabilities::kubernetes::{
 };
 
 extern crate kubewarden_policy_sdk as kubewarden;
-use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+use kubewarden::{
+    protocol_version_guest, request::ValidationRequest, response::ValidationResponse,
+    validate_settings,
+};
 
 mod settings;
-use settings::Settings;
+use settings::{DuplicateSelectorAction, Settings};
 
 use guest::prelude::*;
 use kubewarden_policy_sdk::wapc_guest as guest;
@@ -76,22 +79,70 @@ fn find_services_with_duplicate_selectors(
         .collect()
 }
 
-fn validate_service(service: &Service) -> CallResult {
+// Returns true when the Service does not select any Pod
+fn is_selectorless(service: &Service) -> bool {
+    service
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.selector.as_ref())
+        .is_none_or(|selector| selector.is_empty())
+}
+
+// ExternalName Services are just DNS aliases, their selector is ignored
+fn is_external_name(service: &Service) -> bool {
+    service.spec.as_ref().and_then(|spec| spec.type_.as_deref()) == Some("ExternalName")
+}
+
+// Checks if the Service can be defined without a selector. Services that
+// were already defined without a selector can still be updated.
+fn validate_selectorless_service(
+    old_service: Option<&Service>,
+    username: Option<&str>,
+    groups: &[String],
+    settings: &Settings,
+) -> Result<(), String> {
+    if old_service.is_some_and(is_selectorless)
+        || settings.is_selectorless_allowed(username, groups)
+    {
+        return Ok(());
+    }
+    Err(
+        "Services without a selector can be created only by the allowed users and groups"
+            .to_owned(),
+    )
+}
+
+fn validate_service(service: &Service, settings: &Settings) -> CallResult {
     match list_services(service.metadata.namespace.clone().unwrap_or_default()) {
         Ok(current_services) => {
+            // the Service being updated is part of the list
+            let current_services = current_services
+                .into_iter()
+                .filter(|svc| {
+                    svc.metadata.name.is_none() || svc.metadata.name != service.metadata.name
+                })
+                .collect();
             let duplicate_services =
                 find_services_with_duplicate_selectors(service, current_services);
-            if !duplicate_services.is_empty() {
-                return kubewarden::reject_request(
-                    Some(format!(
-                        "service is using selector(s) already defined by these services: {duplicate_services:?}"
-                    )),
-                    None,
-                    None,
-                    None,
-                );
+            if duplicate_services.is_empty() {
+                return kubewarden::accept_request();
+            }
+            let message = format!(
+                "service is using selector(s) already defined by these services: {duplicate_services:?}"
+            );
+            match settings.duplicate_selector_action {
+                DuplicateSelectorAction::Reject => {
+                    kubewarden::reject_request(Some(message), None, None, None)
+                }
+                DuplicateSelectorAction::Warn => Ok(serde_json::to_vec(&ValidationResponse {
+                    accepted: true,
+                    message: None,
+                    code: None,
+                    mutated_object: None,
+                    audit_annotations: None,
+                    warnings: Some(vec![message]),
+                })?),
             }
-            kubewarden::accept_request()
         }
         Err(error) => kubewarden::reject_request(Some(error), None, None, None),
     }
@@ -99,9 +150,33 @@ fn validate_service(service: &Service) -> CallResult {
 
 fn validate(payload: &[u8]) -> CallResult {
     let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let settings = &validation_request.settings;
 
     match serde_json::from_value::<apicore::Service>(validation_request.request.object) {
-        Ok(service) => validate_service(&service),
+        Ok(service) => {
+            if is_external_name(&service) {
+                return kubewarden::accept_request();
+            }
+            if !is_selectorless(&service) {
+                return validate_service(&service, settings);
+            }
+
+            let old_service = if validation_request.request.operation == "UPDATE" {
+                serde_json::from_value::<Service>(validation_request.request.old_object).ok()
+            } else {
+                None
+            };
+            let user_info = &validation_request.request.user_info;
+            match validate_selectorless_service(
+                old_service.as_ref(),
+                user_info.username.as_deref(),
+                user_info.groups.as_deref().unwrap_or_default(),
+                settings,
+            ) {
+                Ok(()) => kubewarden::accept_request(),
+                Err(error) => kubewarden::reject_request(Some(error), None, None, None),
+            }
+        }
         Err(_) => {
             // we didn't get a Service object as expected, let's just accept this request
             kubewarden::accept_request()
@@ -191,4 +266,40 @@ mod tests {
             find_services_with_duplicate_selectors(&service, current_services);
         assert_eq!(found_duplicate_services, duplicate_service_names)
     }
+
+    fn selectorless_settings() -> Settings {
+        Settings {
+            reject_selectorless_services: true,
+            selectorless_allowed_users: ["alice".to_owned()].into(),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::allowed_user(None, "alice", true)]
+    #[case::not_allowed_user(None, "bob", false)]
+    #[case::removing_selector(Some(build_service(vec![("app.kubernetes.io/name".to_owned(),"myapp".to_owned())], false)), "bob", false)]
+    #[case::already_selectorless(Some(build_service(vec![], false)), "bob", true)]
+    #[case::already_empty_selector(Some(build_service(vec![], true)), "bob", true)]
+    fn selectorless_services(
+        #[case] old_service: Option<Service>,
+        #[case] username: &str,
+        #[case] is_valid: bool,
+    ) {
+        let result = validate_selectorless_service(
+            old_service.as_ref(),
+            Some(username),
+            &["system:authenticated".to_owned()],
+            &selectorless_settings(),
+        );
+        assert_eq!(result.is_ok(), is_valid);
+    }
+
+    #[rstest]
+    #[case::missing_selector(build_service(vec![], false), true)]
+    #[case::empty_selector(build_service(vec![], true), true)]
+    #[case::with_selector(build_service(vec![("app.kubernetes.io/name".to_owned(),"myapp".to_owned())], false), false)]
+    fn selectorless(#[case] service: Service, #[case] expected: bool) {
+        assert_eq!(is_selectorless(&service), expected);
+    }
 }
@@ -1,13 +1,127 @@
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
 
+/// What to do when a Service uses the same selector of another Service
+/// defined inside of the same namespace
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum DuplicateSelectorAction {
+    /// Reject the request
+    #[default]
+    Reject,
+    /// Accept the request, but warn the user about the duplicated selector
+    Warn,
+}
+
 // Describe the settings your policy expects when
 // loaded by the policy server.
 #[derive(Serialize, Deserialize, Default, Debug)]
-#[serde(default)]
-pub(crate) struct Settings {}
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// What to do when the selector of the Service duplicates the one of
+    /// another Service of the same namespace
+    pub duplicate_selector_action: DuplicateSelectorAction,
+    /// Reject the Services without a selector, unless they are created by
+    /// the allowed users and groups
+    pub reject_selectorless_services: bool,
+    /// Users allowed to create Services without a selector
+    pub selectorless_allowed_users: HashSet<String>,
+    /// Groups allowed to create Services without a selector
+    pub selectorless_allowed_groups: HashSet<String>,
+}
+
+impl Settings {
+    /// Returns true when the user, or one of its groups, can create Services
+    /// without a selector
+    pub fn is_selectorless_allowed(&self, username: Option<&str>, groups: &[String]) -> bool {
+        !self.reject_selectorless_services
+            || username.is_some_and(|username| self.selectorless_allowed_users.contains(username))
+            || groups
+                .iter()
+                .any(|group| self.selectorless_allowed_groups.contains(group))
+    }
+}
 
 impl kubewarden::settings::Validatable for Settings {
     fn validate(&self) -> Result<(), String> {
+        if self.selectorless_allowed_users.contains("") {
+            return Err("selectorlessAllowedUsers cannot contain empty values".to_owned());
+        }
+        if self.selectorless_allowed_groups.contains("") {
+            return Err("selectorlessAllowedGroups cannot contain empty values".to_owned());
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        Settings {
+            reject_selectorless_services: true,
+            selectorless_allowed_users: ["alice".to_owned()].into(),
+            selectorless_allowed_groups: ["network-admins".to_owned()].into(),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::allowed_user(settings(), Some("alice"), vec![], true)]
+    #[case::allowed_group(settings(), Some("bob"), vec!["system:authenticated", "network-admins"], true)]
+    #[case::not_allowed(settings(), Some("bob"), vec!["system:authenticated"], false)]
+    #[case::anonymous(settings(), None, vec![], false)]
+    #[case::check_disabled(Settings::default(), Some("bob"), vec![], true)]
+    fn selectorless_allowed(
+        #[case] settings: Settings,
+        #[case] username: Option<&str>,
+        #[case] groups: Vec<&str>,
+        #[case] expected: bool,
+    ) {
+        let groups: Vec<String> = groups.into_iter().map(String::from).collect();
+        assert_eq!(
+            settings.is_selectorless_allowed(username, &groups),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, DuplicateSelectorAction::Reject)]
+    #[case::warn(
+        r#"{"duplicateSelectorAction": "warn"}"#,
+        DuplicateSelectorAction::Warn
+    )]
+    fn parse_duplicate_selector_action(
+        #[case] settings: &str,
+        #[case] expected: DuplicateSelectorAction,
+    ) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.duplicate_selector_action, expected);
+    }
+
+    #[test]
+    fn unknown_duplicate_selector_action() {
+        assert!(
+            serde_json::from_str::<Settings>(r#"{"duplicateSelectorAction": "ignore"}"#).is_err()
+        );
+    }
+
+    #[rstest]
+    #[case::valid(settings(), true)]
+    #[case::empty_user(Settings {
+        selectorless_allowed_users: [String::new()].into(),
+        ..Default::default()
+    }, false)]
+    #[case::empty_group(Settings {
+        selectorless_allowed_groups: [String::new()].into(),
+        ..Default::default()
+    }, false)]
+    fn validate_settings(#[case] settings: Settings, #[case] is_valid: bool) {
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
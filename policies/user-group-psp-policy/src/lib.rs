@@ -5,7 +5,8 @@ use kubewarden_policy_sdk::host_capabilities::oci::get_manifest_and_config;
 use kubewarden_policy_sdk::wapc_guest as guest;
 
 use anyhow::Result;
-use k8s_openapi::api::core::v1 as apicore;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 extern crate kubewarden_policy_sdk as kubewarden;
 use kubewarden::{logging, protocol_version_guest, request::ValidationRequest, validate_settings};
@@ -31,6 +32,58 @@ pub extern "C" fn wapc_init() {
     register_function("protocol_version", protocol_version_guest);
 }
 
+/// Fields of the Pod spec used by the policy
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodSpecFields {
+    #[serde(default)]
+    containers: Vec<ContainerFields>,
+    init_containers: Option<Vec<ContainerFields>>,
+    security_context: Option<PodSecurityContextFields>,
+}
+
+/// Container with its image and securityContext. The other fields are kept in
+/// `other`, so that the mutation does not drop the fields unknown to
+/// k8s-openapi.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct ContainerFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    security_context: Option<SecurityContextFields>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct SecurityContextFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_as_user: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_as_group: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_as_non_root: Option<bool>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct PodSecurityContextFields {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_as_user: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_as_group: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_as_non_root: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    supplemental_groups: Option<Vec<i64>>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
 trait GenericSecurityContext {
     fn run_as_user(&self) -> Option<i64>;
     fn set_run_as_user(&mut self, run_as_user: Option<i64>);
@@ -40,7 +93,7 @@ trait GenericSecurityContext {
     fn set_run_as_group(&mut self, run_as_group: Option<i64>);
 }
 
-impl GenericSecurityContext for apicore::SecurityContext {
+impl GenericSecurityContext for SecurityContextFields {
     fn run_as_user(&self) -> Option<i64> {
         self.run_as_user
     }
@@ -66,7 +119,7 @@ impl GenericSecurityContext for apicore::SecurityContext {
     }
 }
 
-impl GenericSecurityContext for apicore::PodSecurityContext {
+impl GenericSecurityContext for PodSecurityContextFields {
     fn run_as_user(&self) -> Option<i64> {
         self.run_as_user
     }
@@ -248,9 +301,9 @@ where
 }
 
 fn enforce_supplemental_groups(
-    security_context_option: Option<apicore::PodSecurityContext>,
+    security_context_option: Option<PodSecurityContextFields>,
     validation_request: &ValidationRequest<Settings>,
-) -> Result<Option<apicore::PodSecurityContext>, ValidationError> {
+) -> Result<Option<PodSecurityContextFields>, ValidationError> {
     let mut security_context = security_context_option.unwrap_or_default();
     match validation_request.settings.supplemental_groups.rule {
         Rule::MustRunAs => {
@@ -297,7 +350,7 @@ fn enforce_supplemental_groups(
 }
 
 fn enforce_container_security_policies(
-    container: &mut apicore::Container,
+    container: &mut ContainerFields,
     validation_request: &ValidationRequest<Settings>,
 ) -> Result<bool> {
     let container_image_config = if validation_request
@@ -339,7 +392,7 @@ fn enforce_container_security_policies(
 }
 
 fn enforce_pod_spec_security_policies(
-    podspec: &mut apicore::PodSpec,
+    podspec: &mut PodSpecFields,
     validation_request: &ValidationRequest<Settings>,
 ) -> Result<bool> {
     let mut mutated: bool = false;
@@ -360,7 +413,8 @@ fn enforce_pod_spec_security_policies(
 
 fn validate(payload: &[u8]) -> CallResult {
     let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
-    match validation_request.extract_pod_spec_from_object() {
+    let request = &validation_request.request;
+    match partial_pod_spec::extract::<PodSpecFields>(&request.kind.kind, &request.object) {
         Ok(pod_spec) => {
             if let Some(mut pod_spec) = pod_spec {
                 let mut mutated: bool = false;
@@ -431,7 +485,11 @@ fn validate(payload: &[u8]) -> CallResult {
                 }
 
                 if mutated {
-                    return kubewarden::mutate_pod_spec_from_request(validation_request, pod_spec);
+                    return kubewarden::mutate_request(partial_pod_spec::patch(
+                        &request.kind.kind,
+                        &request.object,
+                        &pod_spec,
+                    )?);
                 } else {
                     return kubewarden::accept_request();
                 }
@@ -454,7 +512,6 @@ mod tests {
     use super::*;
 
     use jsonpath_lib as jsonpath;
-    use k8s_openapi::api::core::v1::{PodSecurityContext, SecurityContext};
     use kubewarden::{
         request::KubernetesAdmissionRequest, response::ValidationResponse, settings::Validatable,
     };
@@ -507,14 +564,14 @@ mod tests {
         }
     }
 
-    fn get_pod_security_context_expected_mutation() -> PodSecurityContext {
+    fn get_pod_security_context_expected_mutation() -> PodSecurityContextFields {
         get_pod_security_context(Some(vec![1500]))
     }
 
     fn get_pod_security_context(
         supplemental_groups: Option<std::vec::Vec<i64>>,
-    ) -> PodSecurityContext {
-        PodSecurityContext {
+    ) -> PodSecurityContextFields {
+        PodSecurityContextFields {
             supplemental_groups,
             ..Default::default()
         }
@@ -523,33 +580,33 @@ mod tests {
     fn get_security_context(
         run_as_user: Option<i64>,
         run_as_non_root: Option<bool>,
-    ) -> SecurityContext {
-        SecurityContext {
+    ) -> SecurityContextFields {
+        SecurityContextFields {
             run_as_user,
             run_as_non_root,
             ..Default::default()
         }
     }
 
-    fn get_security_context_with_no_user(run_as_group: Option<i64>) -> SecurityContext {
-        SecurityContext {
+    fn get_security_context_with_no_user(run_as_group: Option<i64>) -> SecurityContextFields {
+        SecurityContextFields {
             run_as_group,
             ..Default::default()
         }
     }
 
-    fn get_security_context_expected_mutation_for_group_must_run_as() -> SecurityContext {
-        SecurityContext {
+    fn get_security_context_expected_mutation_for_group_must_run_as() -> SecurityContextFields {
+        SecurityContextFields {
             run_as_group: Some(1500),
             ..Default::default()
         }
     }
 
-    fn get_security_context_expected_mutation_must_run_as() -> SecurityContext {
+    fn get_security_context_expected_mutation_must_run_as() -> SecurityContextFields {
         get_security_context(Some(1500), None)
     }
 
-    fn get_security_context_expected_mutation_must_run_as_non_root() -> SecurityContext {
+    fn get_security_context_expected_mutation_must_run_as_non_root() -> SecurityContextFields {
         get_security_context(None, Some(true))
     }
 
@@ -651,7 +708,7 @@ mod tests {
         #[case] supplemental_groups: Option<std::vec::Vec<i64>>,
         #[case] supplemental_groups_strategy: settings::RuleStrategy,
         #[case] expected_error: Option<ValidationError>,
-        #[case] expected_mutation: Option<PodSecurityContext>,
+        #[case] expected_mutation: Option<PodSecurityContextFields>,
         #[case] validate_only: bool,
     ) {
         let security_context = Some(get_pod_security_context(supplemental_groups));
@@ -742,7 +799,7 @@ mod tests {
         #[case] run_as_group: Option<i64>,
         #[case] run_as_group_strategy: settings::RuleStrategy,
         #[case] expected_error: Option<ValidationError>,
-        #[case] expected_mutation: Option<SecurityContext>,
+        #[case] expected_mutation: Option<SecurityContextFields>,
         #[case] validate_only: bool,
     ) {
         let security_context = Some(get_security_context_with_no_user(run_as_group));
@@ -850,7 +907,7 @@ mod tests {
         #[case] run_as_non_root: Option<bool>,
         #[case] run_as_user_strategy: settings::RuleStrategy,
         #[case] expected_error: Option<ValidationError>,
-        #[case] expected_mutation: Option<SecurityContext>,
+        #[case] expected_mutation: Option<SecurityContextFields>,
         #[case] validate_only: bool,
     ) {
         let security_context = Some(get_security_context(run_as_user, run_as_non_root));
@@ -952,6 +1009,17 @@ mod tests {
             "MustRunAs should mutate object when invalid group ID is set"
         );
 
+        let names_json = jsonpath::select(
+            res.mutated_object.as_ref().unwrap(),
+            "$.spec.containers[*].name",
+        )
+        .unwrap();
+        assert_eq!(
+            names_json,
+            vec!["nginx"],
+            "The mutation should keep the other container fields"
+        );
+
         Ok(())
     }
 
@@ -1317,7 +1385,7 @@ mod tests {
                 ..Default::default()
             },
         };
-        let security_context = Some(SecurityContext {
+        let security_context = Some(SecurityContextFields {
             run_as_user: Some(min),
             ..Default::default()
         });
@@ -1405,7 +1473,7 @@ mod tests {
                 ..Default::default()
             },
         };
-        let security_context = Some(SecurityContext {
+        let security_context = Some(SecurityContextFields {
             run_as_group: Some(min),
             ..Default::default()
         });
@@ -0,0 +1,51 @@
+#!/usr/bin/env bats
+
+@test "Reject the deletion of a Node by a user not granted access" {
+	run kwctl run --request-path test_data/node_delete.json --settings-json '{"restrictedKinds": [{"kind": "Node"}], "rules": [{"groups": ["system:nodes"], "kinds": [{"kind": "Node"}]}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*user 'alice' is not allowed to DELETE Node 'worker-1'.*") -ne 0 ]
+}
+
+@test "Accept the deletion of a Node by a group granted access" {
+	run kwctl run --request-path test_data/node_delete.json --settings-json '{"restrictedKinds": [{"kind": "Node"}], "rules": [{"groups": ["cluster-operators"], "kinds": [{"kind": "Node"}], "operations": ["DELETE"]}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a webhook configuration created by a ServiceAccount granted access" {
+	run kwctl run --request-path test_data/validatingwebhookconfiguration_create.json --settings-json '{"restrictedKinds": [{"group": "admissionregistration.k8s.io", "kind": "*"}], "rules": [{"serviceAccounts": [{"namespace": "cert-manager", "name": "cert-manager-webhook"}], "kinds": [{"group": "admissionregistration.k8s.io", "kind": "ValidatingWebhookConfiguration"}]}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Accept a kind not restricted" {
+	run kwctl run --request-path test_data/persistentvolume_create.json --settings-json '{"restrictedKinds": [{"kind": "Node"}]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/persistentvolume_create.json --settings-json '{}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
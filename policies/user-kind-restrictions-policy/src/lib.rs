@@ -0,0 +1,170 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let group = request.kind.group.as_str();
+    let kind = request.kind.kind.as_str();
+    if !settings.is_kind_restricted(group, kind) {
+        return kubewarden::accept_request();
+    }
+
+    let username = request.user_info.username.as_deref();
+    let groups = request.user_info.groups.unwrap_or_default();
+    let allowed = settings.rules.iter().any(|rule| {
+        rule.matches_requester(username, &groups)
+            && rule.matches_request(group, kind, &request.namespace, &request.operation)
+    });
+    if allowed {
+        return kubewarden::accept_request();
+    }
+
+    let namespace = if request.namespace.is_empty() {
+        String::new()
+    } else {
+        format!(" in namespace '{}'", request.namespace)
+    };
+    kubewarden::reject_request(
+        Some(format!(
+            "user '{}' is not allowed to {} {kind} '{}'{namespace}",
+            username.unwrap_or_default(),
+            request.operation,
+            request.name,
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+    use settings::{Kind, Rule, ServiceAccount};
+
+    fn settings(rules: Vec<Rule>) -> Settings {
+        Settings {
+            restricted_kinds: vec![
+                Kind {
+                    group: String::new(),
+                    kind: "Node".to_owned(),
+                },
+                Kind {
+                    group: "admissionregistration.k8s.io".to_owned(),
+                    kind: "*".to_owned(),
+                },
+            ],
+            rules,
+        }
+    }
+
+    #[rstest]
+    #[case::no_rules(
+        "test_data/node_delete.json",
+        settings(vec![]),
+        false
+    )]
+    #[case::allowed_group(
+        "test_data/node_delete.json",
+        settings(vec![Rule {
+            groups: ["cluster-operators".to_owned()].into(),
+            kinds: vec![Kind { group: String::new(), kind: "Node".to_owned() }],
+            ..Default::default()
+        }]),
+        true
+    )]
+    #[case::operation_not_allowed(
+        "test_data/node_delete.json",
+        settings(vec![Rule {
+            groups: ["cluster-operators".to_owned()].into(),
+            kinds: vec![Kind { group: String::new(), kind: "Node".to_owned() }],
+            operations: ["UPDATE".to_owned()].into(),
+            ..Default::default()
+        }]),
+        false
+    )]
+    #[case::allowed_service_account(
+        "test_data/validatingwebhookconfiguration_create.json",
+        settings(vec![Rule {
+            service_accounts: vec![ServiceAccount {
+                namespace: "cert-manager".to_owned(),
+                name: "cert-manager-webhook".to_owned(),
+            }],
+            kinds: vec![Kind {
+                group: "admissionregistration.k8s.io".to_owned(),
+                kind: "ValidatingWebhookConfiguration".to_owned(),
+            }],
+            ..Default::default()
+        }]),
+        true
+    )]
+    #[case::kind_not_allowed(
+        "test_data/validatingwebhookconfiguration_create.json",
+        settings(vec![Rule {
+            service_accounts: vec![ServiceAccount {
+                namespace: "cert-manager".to_owned(),
+                name: "cert-manager-webhook".to_owned(),
+            }],
+            kinds: vec![Kind {
+                group: "admissionregistration.k8s.io".to_owned(),
+                kind: "MutatingWebhookConfiguration".to_owned(),
+            }],
+            ..Default::default()
+        }]),
+        false
+    )]
+    #[case::kind_not_restricted(
+        "test_data/persistentvolume_create.json",
+        settings(vec![]),
+        true
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/node_delete.json".to_owned(),
+            expected_validation_result: false,
+            settings: settings(vec![]),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some("user 'alice' is not allowed to DELETE Node 'worker-1'")
+        );
+    }
+}
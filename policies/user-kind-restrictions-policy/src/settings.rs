@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+/// Operations that can be granted by the rules
+const OPERATIONS: [&str; 4] = ["CREATE", "UPDATE", "DELETE", "CONNECT"];
+
+/// Kind of a Kubernetes resource
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Kind {
+    /// API group of the kind, empty for the core group
+    #[serde(default)]
+    pub group: String,
+    /// Name of the kind. `*` matches all the kinds of the group.
+    pub kind: String,
+}
+
+impl Kind {
+    /// Returns true when the given kind is matched
+    pub fn matches(&self, group: &str, kind: &str) -> bool {
+        self.group == group && (self.kind == "*" || self.kind == kind)
+    }
+}
+
+/// ServiceAccount granted access by a rule
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ServiceAccount {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl ServiceAccount {
+    /// Returns the username used by the ServiceAccount
+    pub fn username(&self) -> String {
+        format!("system:serviceaccount:{}:{}", self.namespace, self.name)
+    }
+}
+
+/// Access to some of the restricted kinds granted to users, groups and
+/// ServiceAccounts
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Rule {
+    pub users: HashSet<String>,
+    pub groups: HashSet<String>,
+    pub service_accounts: Vec<ServiceAccount>,
+    /// Kinds the requesters can operate on
+    pub kinds: Vec<Kind>,
+    /// Namespaces of the resources the requesters can operate on. When
+    /// empty, all the namespaces are allowed. Cluster-wide resources are
+    /// matched only by rules without namespaces.
+    pub namespaces: HashSet<String>,
+    /// Operations the requesters can perform. When empty, all the operations
+    /// are allowed.
+    pub operations: HashSet<String>,
+}
+
+impl Rule {
+    /// Returns true when the rule grants access to the requester
+    pub fn matches_requester(&self, username: Option<&str>, groups: &[String]) -> bool {
+        username.is_some_and(|username| {
+            self.users.contains(username)
+                || self
+                    .service_accounts
+                    .iter()
+                    .any(|service_account| service_account.username() == username)
+        }) || groups.iter().any(|group| self.groups.contains(group))
+    }
+
+    /// Returns true when the rule grants the operation on the kind inside of
+    /// the namespace. Cluster-wide resources have an empty namespace.
+    pub fn matches_request(
+        &self,
+        group: &str,
+        kind: &str,
+        namespace: &str,
+        operation: &str,
+    ) -> bool {
+        self.kinds.iter().any(|k| k.matches(group, kind))
+            && (self.namespaces.is_empty() || self.namespaces.contains(namespace))
+            && (self.operations.is_empty() || self.operations.contains(operation))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.users.is_empty() && self.groups.is_empty() && self.service_accounts.is_empty() {
+            return Err("rules must contain at least one user, group or ServiceAccount".to_owned());
+        }
+        if self.users.contains("") || self.groups.contains("") {
+            return Err("users and groups cannot contain empty values".to_owned());
+        }
+        if self.service_accounts.iter().any(|service_account| {
+            service_account.namespace.is_empty() || service_account.name.is_empty()
+        }) {
+            return Err("serviceAccounts must have both a namespace and a name".to_owned());
+        }
+        if self.kinds.is_empty() {
+            return Err("rules must contain at least one kind".to_owned());
+        }
+        if self.namespaces.contains("") {
+            return Err("namespaces cannot contain empty values".to_owned());
+        }
+        if let Some(operation) = self
+            .operations
+            .iter()
+            .find(|operation| !OPERATIONS.contains(&operation.as_str()))
+        {
+            return Err(format!(
+                "invalid operation '{operation}', allowed values are: {}",
+                OPERATIONS.join(", ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// Kinds that can be operated on only by the requesters granted access
+    /// by one of the rules
+    pub restricted_kinds: Vec<Kind>,
+    pub rules: Vec<Rule>,
+}
+
+impl Settings {
+    /// Returns true when the kind is restricted by the policy
+    pub fn is_kind_restricted(&self, group: &str, kind: &str) -> bool {
+        self.restricted_kinds.iter().any(|k| k.matches(group, kind))
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.restricted_kinds.is_empty() {
+            return Err("restrictedKinds cannot be empty".to_owned());
+        }
+        if self
+            .restricted_kinds
+            .iter()
+            .chain(self.rules.iter().flat_map(|rule| rule.kinds.iter()))
+            .any(|kind| kind.kind.is_empty())
+        {
+            return Err("kinds must have a name".to_owned());
+        }
+        self.rules.iter().try_for_each(Rule::validate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    fn rule() -> Rule {
+        Rule {
+            users: ["admin".to_owned()].into(),
+            groups: ["storage-admins".to_owned()].into(),
+            service_accounts: vec![ServiceAccount {
+                namespace: "csi".to_owned(),
+                name: "provisioner".to_owned(),
+            }],
+            kinds: vec![
+                Kind {
+                    group: String::new(),
+                    kind: "PersistentVolume".to_owned(),
+                },
+                Kind {
+                    group: "storage.k8s.io".to_owned(),
+                    kind: "*".to_owned(),
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::user(Some("admin"), vec![], true)]
+    #[case::group(Some("alice"), vec!["storage-admins"], true)]
+    #[case::service_account(Some("system:serviceaccount:csi:provisioner"), vec![], true)]
+    #[case::other_service_account(Some("system:serviceaccount:csi:attacher"), vec![], false)]
+    #[case::other_user(Some("alice"), vec!["system:authenticated"], false)]
+    #[case::anonymous(None, vec![], false)]
+    fn requesters(
+        #[case] username: Option<&str>,
+        #[case] groups: Vec<&str>,
+        #[case] expected: bool,
+    ) {
+        let groups: Vec<String> = groups.into_iter().map(str::to_owned).collect();
+        assert_eq!(rule().matches_requester(username, &groups), expected);
+    }
+
+    #[rstest]
+    #[case::kind(rule(), "", "PersistentVolume", "", "DELETE", true)]
+    #[case::wildcard_kind(rule(), "storage.k8s.io", "StorageClass", "", "CREATE", true)]
+    #[case::other_group(rule(), "apps", "PersistentVolume", "", "CREATE", false)]
+    #[case::other_kind(rule(), "", "Node", "", "UPDATE", false)]
+    #[case::operation(
+        Rule { operations: ["CREATE".to_owned()].into(), ..rule() },
+        "", "PersistentVolume", "", "CREATE", true
+    )]
+    #[case::other_operation(
+        Rule { operations: ["CREATE".to_owned()].into(), ..rule() },
+        "", "PersistentVolume", "", "DELETE", false
+    )]
+    #[case::namespace(
+        Rule { namespaces: ["team-a".to_owned()].into(), ..rule() },
+        "storage.k8s.io", "CSIStorageCapacity", "team-a", "CREATE", true
+    )]
+    #[case::other_namespace(
+        Rule { namespaces: ["team-a".to_owned()].into(), ..rule() },
+        "storage.k8s.io", "CSIStorageCapacity", "team-b", "CREATE", false
+    )]
+    #[case::cluster_wide_resource(
+        Rule { namespaces: ["team-a".to_owned()].into(), ..rule() },
+        "", "PersistentVolume", "", "CREATE", false
+    )]
+    fn requests(
+        #[case] rule: Rule,
+        #[case] group: &str,
+        #[case] kind: &str,
+        #[case] namespace: &str,
+        #[case] operation: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            rule.matches_request(group, kind, namespace, operation),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::valid(r#"{"restrictedKinds": [{"kind": "Node"}], "rules": [{"groups": ["system:nodes"], "kinds": [{"kind": "Node"}]}]}"#, true)]
+    #[case::no_rules(
+        r#"{"restrictedKinds": [{"group": "admissionregistration.k8s.io", "kind": "*"}]}"#,
+        true
+    )]
+    #[case::no_restricted_kinds(
+        r#"{"rules": [{"users": ["admin"], "kinds": [{"kind": "Node"}]}]}"#,
+        false
+    )]
+    #[case::empty_kind(r#"{"restrictedKinds": [{"group": "apps", "kind": ""}]}"#, false)]
+    #[case::rule_without_requesters(
+        r#"{"restrictedKinds": [{"kind": "Node"}], "rules": [{"kinds": [{"kind": "Node"}]}]}"#,
+        false
+    )]
+    #[case::rule_without_kinds(
+        r#"{"restrictedKinds": [{"kind": "Node"}], "rules": [{"users": ["admin"]}]}"#,
+        false
+    )]
+    #[case::incomplete_service_account(r#"{"restrictedKinds": [{"kind": "Node"}], "rules": [{"serviceAccounts": [{"namespace": "", "name": "admin"}], "kinds": [{"kind": "Node"}]}]}"#, false)]
+    #[case::invalid_operation(r#"{"restrictedKinds": [{"kind": "Node"}], "rules": [{"users": ["admin"], "kinds": [{"kind": "Node"}], "operations": ["PATCH"]}]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}
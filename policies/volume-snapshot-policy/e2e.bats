@@ -0,0 +1,77 @@
+#!/usr/bin/env bats
+
+@test "Accept a VolumeSnapshot using an allowed class" {
+	run kwctl run \
+		--request-path test_data/volumesnapshot_allowed_class.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a VolumeSnapshot using a class not allowed in its namespace" {
+	run kwctl run \
+		--request-path test_data/volumesnapshot_production_class.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*the VolumeSnapshotClass 'csi-production' is not allowed in the 'team-a' namespace.*") -ne 0 ]
+}
+
+@test "Reject a production VolumeSnapshotClass deleting the snapshots" {
+	run kwctl run \
+		--request-path test_data/volumesnapshotclass_delete.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*must be Retain, not Delete.*') -ne 0 ]
+}
+
+@test "Accept a PersistentVolumeClaim restoring a shared VolumeSnapshot" {
+	run kwctl run \
+		--request-path test_data/pvc_shared_namespace_snapshot.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a PersistentVolumeClaim restoring a VolumeSnapshot of another namespace" {
+	run kwctl run \
+		--request-path test_data/pvc_other_namespace_snapshot.json \
+		--settings-path test_data/settings.json \
+		annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/volumesnapshot_allowed_class.json --settings-json '{"productionClasses": [""]}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
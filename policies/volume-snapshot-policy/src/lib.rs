@@ -0,0 +1,218 @@
+use guest::prelude::*;
+use k8s_openapi::api::core::v1::PersistentVolumeClaim;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::Settings;
+
+mod snapshot;
+use snapshot::{
+    SNAPSHOT_API_GROUP, VOLUME_SNAPSHOT_CLASS_KIND, VOLUME_SNAPSHOT_KIND, VolumeSnapshot,
+    VolumeSnapshotClass,
+};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let result = match (request.kind.group.as_str(), request.kind.kind.as_str()) {
+        (SNAPSHOT_API_GROUP, VOLUME_SNAPSHOT_KIND) => {
+            serde_json::from_value::<VolumeSnapshot>(request.object)
+                .map(|snapshot| validate_volume_snapshot(&snapshot, &request.namespace, settings))
+        }
+        (SNAPSHOT_API_GROUP, VOLUME_SNAPSHOT_CLASS_KIND) => {
+            serde_json::from_value::<VolumeSnapshotClass>(request.object)
+                .map(|class| validate_volume_snapshot_class(&class, settings))
+        }
+        ("", "PersistentVolumeClaim") => {
+            serde_json::from_value::<PersistentVolumeClaim>(request.object)
+                .map(|pvc| validate_data_source(&pvc, &request.namespace, settings))
+        }
+        _ => return kubewarden::accept_request(),
+    };
+    match result {
+        Ok(Ok(())) => kubewarden::accept_request(),
+        Ok(Err(error)) => kubewarden::reject_request(
+            Some(format!("{} '{}': {error}", request.kind.kind, request.name)),
+            None,
+            None,
+            None,
+        ),
+        Err(_) => kubewarden::reject_request(
+            Some("Cannot parse validation request".to_owned()),
+            None,
+            None,
+            None,
+        ),
+    }
+}
+
+fn validate_volume_snapshot(
+    snapshot: &VolumeSnapshot,
+    namespace: &str,
+    settings: &Settings,
+) -> Result<(), String> {
+    let allowed_classes = settings.allowed_classes_of(namespace);
+    if allowed_classes.is_empty() {
+        return Ok(());
+    }
+    // The default VolumeSnapshotClass is chosen after the admission: the
+    // policy cannot tell whether it is allowed
+    let Some(class) = snapshot.spec.volume_snapshot_class_name.as_deref() else {
+        return Err(format!(
+            "volumeSnapshotClassName must be set in the '{namespace}' namespace"
+        ));
+    };
+    if !allowed_classes.contains(class) {
+        return Err(format!(
+            "the VolumeSnapshotClass '{class}' is not allowed in the '{namespace}' namespace, use one of: {}",
+            allowed_classes
+                .iter()
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+    Ok(())
+}
+
+fn validate_volume_snapshot_class(
+    class: &VolumeSnapshotClass,
+    settings: &Settings,
+) -> Result<(), String> {
+    let name = class.metadata.name.as_deref().unwrap_or_default();
+    if settings.production_classes.contains(name) && class.deletion_policy != "Retain" {
+        return Err(format!(
+            "the deletionPolicy of a production VolumeSnapshotClass must be Retain, not {}",
+            class.deletion_policy
+        ));
+    }
+    Ok(())
+}
+
+// Validates the VolumeSnapshots of the other namespaces used as data source,
+// which requires the `CrossNamespaceVolumeDataSource` feature gate
+fn validate_data_source(
+    pvc: &PersistentVolumeClaim,
+    namespace: &str,
+    settings: &Settings,
+) -> Result<(), String> {
+    let Some(data_source_ref) = pvc
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.data_source_ref.as_ref())
+    else {
+        return Ok(());
+    };
+    if data_source_ref.api_group.as_deref() != Some(SNAPSHOT_API_GROUP)
+        || data_source_ref.kind != VOLUME_SNAPSHOT_KIND
+    {
+        return Ok(());
+    }
+    match data_source_ref.namespace.as_deref() {
+        Some(source_namespace)
+            if source_namespace != namespace
+                && !settings
+                    .shared_snapshot_namespaces
+                    .contains(source_namespace) =>
+        {
+            Err(format!(
+                "the VolumeSnapshot '{source_namespace}/{}' of another namespace cannot be used as data source",
+                data_source_ref.name
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn settings() -> Settings {
+        serde_json::from_str(
+            r#"{
+                "allowedClasses": ["csi-standard"],
+                "namespaces": {"production": {"allowedClasses": ["csi-production"]}},
+                "productionClasses": ["csi-production"],
+                "sharedSnapshotNamespaces": ["golden-images"]
+            }"#,
+        )
+        .expect("cannot parse settings")
+    }
+
+    #[rstest]
+    #[case::allowed_class("test_data/volumesnapshot_allowed_class.json", true)]
+    #[case::class_of_other_namespace("test_data/volumesnapshot_production_class.json", false)]
+    #[case::without_class("test_data/volumesnapshot_without_class.json", false)]
+    #[case::production_class_retain("test_data/volumesnapshotclass_retain.json", true)]
+    #[case::production_class_delete("test_data/volumesnapshotclass_delete.json", false)]
+    #[case::same_namespace_source("test_data/pvc_same_namespace_snapshot.json", true)]
+    #[case::shared_namespace_source("test_data/pvc_shared_namespace_snapshot.json", true)]
+    #[case::other_namespace_source("test_data/pvc_other_namespace_snapshot.json", false)]
+    fn validate_request(#[case] fixture: &str, #[case] expected_validation_result: bool) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings: settings(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[rstest]
+    #[case::class_of_other_namespace(
+        "test_data/volumesnapshot_production_class.json",
+        "VolumeSnapshot 'postgres-data': the VolumeSnapshotClass 'csi-production' is not allowed in the 'team-a' namespace, use one of: csi-standard"
+    )]
+    #[case::without_class(
+        "test_data/volumesnapshot_without_class.json",
+        "VolumeSnapshot 'postgres-data': volumeSnapshotClassName must be set in the 'team-a' namespace"
+    )]
+    #[case::production_class_delete(
+        "test_data/volumesnapshotclass_delete.json",
+        "VolumeSnapshotClass 'csi-production': the deletionPolicy of a production VolumeSnapshotClass must be Retain, not Delete"
+    )]
+    #[case::other_namespace_source(
+        "test_data/pvc_other_namespace_snapshot.json",
+        "PersistentVolumeClaim 'postgres-data': the VolumeSnapshot 'production/postgres-data' of another namespace cannot be used as data source"
+    )]
+    fn rejection_message(#[case] fixture: &str, #[case] expected_message: &str) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result: false,
+            settings: settings(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(response.message.as_deref(), Some(expected_message));
+    }
+
+    #[test]
+    fn all_classes_allowed_by_default() {
+        let test_case = Testcase {
+            name: "all classes allowed by default".to_owned(),
+            fixture_file: "test_data/volumesnapshot_without_class.json".to_owned(),
+            expected_validation_result: true,
+            settings: Settings::default(),
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+}
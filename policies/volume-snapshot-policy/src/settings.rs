@@ -0,0 +1,120 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+/// VolumeSnapshotClasses allowed in a namespace
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct NamespaceSettings {
+    /// VolumeSnapshotClasses replacing the global ones in the namespace
+    pub allowed_classes: BTreeSet<String>,
+}
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// VolumeSnapshotClasses the VolumeSnapshots can use. All the classes
+    /// are allowed when empty.
+    pub allowed_classes: BTreeSet<String>,
+    /// VolumeSnapshotClasses allowed in the namespaces that do not use the
+    /// global ones
+    pub namespaces: BTreeMap<String, NamespaceSettings>,
+    /// VolumeSnapshotClasses of the production data, which must retain the
+    /// snapshots when their VolumeSnapshots are deleted
+    pub production_classes: BTreeSet<String>,
+    /// Namespaces whose VolumeSnapshots can be used as data source by the
+    /// PersistentVolumeClaims of the other namespaces
+    pub shared_snapshot_namespaces: BTreeSet<String>,
+}
+
+impl Settings {
+    /// Returns the VolumeSnapshotClasses allowed in the namespace, the
+    /// global ones when the namespace has none. All the classes are allowed
+    /// when empty.
+    pub fn allowed_classes_of(&self, namespace: &str) -> &BTreeSet<String> {
+        self.namespaces
+            .get(namespace)
+            .map(|namespace| &namespace.allowed_classes)
+            .unwrap_or(&self.allowed_classes)
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if self.allowed_classes.contains("") {
+            return Err("allowedClasses cannot contain empty values".to_owned());
+        }
+        for (namespace, settings) in &self.namespaces {
+            if namespace.is_empty() {
+                return Err("namespaces cannot contain empty names".to_owned());
+            }
+            if settings.allowed_classes.contains("") {
+                return Err(format!(
+                    "namespace '{namespace}': allowedClasses cannot contain empty values"
+                ));
+            }
+        }
+        if self.production_classes.contains("") {
+            return Err("productionClasses cannot contain empty values".to_owned());
+        }
+        if self.shared_snapshot_namespaces.contains("") {
+            return Err("sharedSnapshotNamespaces cannot contain empty values".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::all_settings(
+        r#"{
+            "allowedClasses": ["csi-standard"],
+            "namespaces": {"production": {"allowedClasses": ["csi-production"]}},
+            "productionClasses": ["csi-production"],
+            "sharedSnapshotNamespaces": ["golden-images"]
+        }"#,
+        true
+    )]
+    #[case::empty_class(r#"{"allowedClasses": [""]}"#, false)]
+    #[case::empty_namespace_class(
+        r#"{"namespaces": {"production": {"allowedClasses": [""]}}}"#,
+        false
+    )]
+    #[case::empty_production_class(r#"{"productionClasses": [""]}"#, false)]
+    #[case::empty_shared_namespace(r#"{"sharedSnapshotNamespaces": [""]}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+
+    #[test]
+    fn unknown_namespace_setting() {
+        let settings = r#"{"namespaces": {"production": {"allowedClass": ["csi-production"]}}}"#;
+        assert!(serde_json::from_str::<Settings>(settings).is_err());
+    }
+
+    #[rstest]
+    #[case::global_classes("team-a", vec!["csi-standard"])]
+    #[case::namespace_classes("production", vec!["csi-production"])]
+    #[case::all_classes("sandbox", vec![])]
+    fn allowed_classes(#[case] namespace: &str, #[case] expected: Vec<&str>) {
+        let settings: Settings = serde_json::from_str(
+            r#"{
+                "allowedClasses": ["csi-standard"],
+                "namespaces": {"production": {"allowedClasses": ["csi-production"]}, "sandbox": {}}
+            }"#,
+        )
+        .expect("cannot parse settings");
+        let expected: BTreeSet<String> = expected.into_iter().map(str::to_owned).collect();
+        assert_eq!(settings.allowed_classes_of(namespace), &expected);
+    }
+}
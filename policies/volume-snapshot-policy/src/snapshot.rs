@@ -0,0 +1,36 @@
+// Minimal representation of the resources of the CSI external snapshotter.
+// Only the fields inspected by the policy are defined.
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
+use serde::{Deserialize, Serialize};
+
+pub(crate) const SNAPSHOT_API_GROUP: &str = "snapshot.storage.k8s.io";
+pub(crate) const VOLUME_SNAPSHOT_KIND: &str = "VolumeSnapshot";
+pub(crate) const VOLUME_SNAPSHOT_CLASS_KIND: &str = "VolumeSnapshotClass";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VolumeSnapshot {
+    #[serde(default)]
+    pub metadata: ObjectMeta,
+    #[serde(default)]
+    pub spec: VolumeSnapshotSpec,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VolumeSnapshotSpec {
+    /// The default VolumeSnapshotClass of the driver is used when not set
+    pub volume_snapshot_class_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VolumeSnapshotClass {
+    #[serde(default)]
+    pub metadata: ObjectMeta,
+    #[serde(default)]
+    pub driver: String,
+    /// Either `Delete` or `Retain`
+    #[serde(default)]
+    pub deletion_policy: String,
+}
@@ -14,3 +14,18 @@
 	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
 	[ $(expr "$output" : '.*"message":.*test-var.*test-data.*') -ne 0 ]
  }
+
+@test "Reject pod with forbidden subPath" {
+	run kwctl run  --request-path test_data/pod_sub_path.json --settings-json '{"forbidSubPath": true}' annotated-policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : '.*"message":.*cannot use subPath.*') -ne 0 ]
+ }
+
+@test "Accept the Bidirectional mountPropagation of an allowed DaemonSet" {
+	run kwctl run  --request-path test_data/daemonset_bidirectional.json --settings-json '{"restrictMountPropagation": true, "bidirectionalPropagationDaemonSets": [{"namespace": "kube-system", "name": "ebs-csi-node"}]}' annotated-policy.wasm
+	[ "$status" -eq 0 ]
+	echo "$output"
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+ }
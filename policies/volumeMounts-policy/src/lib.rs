@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
 use anyhow::{Result, anyhow};
 use lazy_static::lazy_static;
@@ -6,13 +6,26 @@ use lazy_static::lazy_static;
 use guest::prelude::*;
 use kubewarden_policy_sdk::wapc_guest as guest;
 
+use k8s_openapi::Resource;
+use k8s_openapi::api::apps::v1::DaemonSet;
 use k8s_openapi::api::core::v1::{self as apicore, VolumeMount};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference};
 
 extern crate kubewarden_policy_sdk as kubewarden;
-use kubewarden::{logging, protocol_version_guest, request::ValidationRequest, validate_settings};
+use kubewarden::{
+    host_capabilities::kubernetes::ListResourcesByNamespaceRequest,
+    logging, protocol_version_guest,
+    request::{KubernetesAdmissionRequest, ValidationRequest},
+    validate_settings,
+};
+
+#[cfg(test)]
+use crate::tests::mock_kubernetes_sdk::list_resources_by_namespace;
+#[cfg(not(test))]
+use kubewarden::host_capabilities::kubernetes::list_resources_by_namespace;
 
 mod settings;
-use settings::Settings;
+use settings::{Settings, sub_path_regex};
 
 use slog::{Logger, o};
 
@@ -32,10 +45,23 @@ pub extern "C" fn wapc_init() {
 
 fn validate(payload: &[u8]) -> CallResult {
     let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let bidirectional_propagation_allowed = match allows_bidirectional_propagation(
+        &validation_request.request,
+        &validation_request.settings,
+    ) {
+        Ok(allowed) => allowed,
+        Err(err) => {
+            return kubewarden::reject_request(Some(err.to_string()), None, None, None);
+        }
+    };
     match validation_request.extract_pod_spec_from_object() {
         Ok(pod_spec) => {
             if let Some(pod_spec) = pod_spec {
-                return match validate_pod(&pod_spec, &validation_request.settings) {
+                return match validate_pod(
+                    &pod_spec,
+                    &validation_request.settings,
+                    bidirectional_propagation_allowed,
+                ) {
                     Ok(_) => kubewarden::accept_request(),
                     Err(err) => kubewarden::reject_request(Some(err.to_string()), None, None, None),
                 };
@@ -53,10 +79,77 @@ fn validate(payload: &[u8]) -> CallResult {
     }
 }
 
-fn validate_pod(pod: &apicore::PodSpec, settings: &settings::Settings) -> Result<()> {
+// Returns true when the resource can use the Bidirectional mountPropagation:
+// it is one of the allowed DaemonSets, or a Pod created by one of them
+fn allows_bidirectional_propagation(
+    request: &KubernetesAdmissionRequest,
+    settings: &Settings,
+) -> Result<bool> {
+    match request.kind.kind.as_str() {
+        DaemonSet::KIND => {
+            Ok(settings.allows_bidirectional_propagation(&request.namespace, &request.name))
+        }
+        apicore::Pod::KIND => {
+            let Some(owner) = daemon_set_owner(&request.object) else {
+                return Ok(false);
+            };
+            if !settings.allows_bidirectional_propagation(&request.namespace, &owner.name) {
+                return Ok(false);
+            }
+            // The owner references can be set by any user creating Pods: the
+            // DaemonSet must exist and be the owner of the Pod
+            is_owner_daemon_set(&request.namespace, &owner)
+        }
+        _ => Ok(false),
+    }
+}
+
+// Returns the DaemonSet controlling the Pod
+fn daemon_set_owner(object: &serde_json::Value) -> Option<OwnerReference> {
+    object
+        .get("metadata")
+        .and_then(|metadata| serde_json::from_value::<ObjectMeta>(metadata.clone()).ok())
+        .and_then(|metadata| metadata.owner_references)
+        .into_iter()
+        .flatten()
+        .find(|owner| owner.controller == Some(true) && owner.kind == DaemonSet::KIND)
+}
+
+// Returns true when the DaemonSet referenced by the owner exists, with the
+// same uid
+fn is_owner_daemon_set(namespace: &str, owner: &OwnerReference) -> Result<bool> {
+    let daemon_sets = list_resources_by_namespace::<DaemonSet>(&ListResourcesByNamespaceRequest {
+        api_version: DaemonSet::API_VERSION.to_owned(),
+        kind: DaemonSet::KIND.to_owned(),
+        namespace: namespace.to_owned(),
+        label_selector: None,
+        field_selector: Some(format!("metadata.name={}", owner.name)),
+        field_masks: Some(BTreeSet::from([
+            "metadata.name".to_owned(),
+            "metadata.uid".to_owned(),
+        ])),
+    })
+    .map_err(|err| {
+        anyhow!(
+            "cannot read the DaemonSet '{namespace}/{}': {err}",
+            owner.name
+        )
+    })?;
+    Ok(daemon_sets
+        .items
+        .iter()
+        .any(|daemon_set| daemon_set.metadata.uid.as_deref() == Some(owner.uid.as_str())))
+}
+
+fn validate_pod(
+    pod: &apicore::PodSpec,
+    settings: &settings::Settings,
+    bidirectional_propagation_allowed: bool,
+) -> Result<()> {
     let mut err_message = String::new();
     for container in &pod.containers {
-        let container_valid = validate_container(container, settings);
+        let container_valid =
+            validate_container(container, settings, bidirectional_propagation_allowed);
         if let Err(e) = container_valid {
             err_message =
                 err_message + &format!("container {} is invalid: {}\n", container.name, e);
@@ -64,7 +157,8 @@ fn validate_pod(pod: &apicore::PodSpec, settings: &settings::Settings) -> Result
     }
     if let Some(init_containers) = &pod.init_containers {
         for container in init_containers {
-            let container_valid = validate_container(container, settings);
+            let container_valid =
+                validate_container(container, settings, bidirectional_propagation_allowed);
             if let Err(e) = container_valid {
                 err_message =
                     err_message + &format!("container {} is invalid: {}\n", container.name, e);
@@ -73,7 +167,11 @@ fn validate_pod(pod: &apicore::PodSpec, settings: &settings::Settings) -> Result
     }
     if let Some(ephemeral_containers) = &pod.ephemeral_containers {
         for container in ephemeral_containers {
-            let container_valid = validate_ephemeral_container(container, settings);
+            let container_valid = validate_ephemeral_container(
+                container,
+                settings,
+                bidirectional_propagation_allowed,
+            );
             if let Err(e) = container_valid {
                 err_message =
                     err_message + &format!("container {} is invalid: {}\n", container.name, e);
@@ -89,16 +187,21 @@ fn validate_pod(pod: &apicore::PodSpec, settings: &settings::Settings) -> Result
 fn validate_ephemeral_container(
     container: &apicore::EphemeralContainer,
     settings: &settings::Settings,
+    bidirectional_propagation_allowed: bool,
 ) -> Result<()> {
     if let Some(volume_mounts) = &container.volume_mounts {
-        return validate_volume_mounts(volume_mounts, settings);
+        return validate_volume_mounts(volume_mounts, settings, bidirectional_propagation_allowed);
     }
     Ok(())
 }
 
-fn validate_container(container: &apicore::Container, settings: &settings::Settings) -> Result<()> {
+fn validate_container(
+    container: &apicore::Container,
+    settings: &settings::Settings,
+    bidirectional_propagation_allowed: bool,
+) -> Result<()> {
     if let Some(volume_mounts) = &container.volume_mounts {
-        return validate_volume_mounts(volume_mounts, settings);
+        return validate_volume_mounts(volume_mounts, settings, bidirectional_propagation_allowed);
     }
     Ok(())
 }
@@ -106,6 +209,78 @@ fn validate_container(container: &apicore::Container, settings: &settings::Setti
 fn validate_volume_mounts(
     volume_mounts: &[VolumeMount],
     settings: &settings::Settings,
+    bidirectional_propagation_allowed: bool,
+) -> Result<()> {
+    let mut errors = vec![];
+    if settings.checks_names()
+        && let Err(e) = validate_volume_mounts_names(volume_mounts, settings)
+    {
+        errors.push(e.to_string());
+    }
+    for mount in volume_mounts {
+        errors.extend(validate_sub_path(mount, settings));
+        if settings.restrict_mount_propagation {
+            errors.extend(validate_mount_propagation(
+                mount,
+                bidirectional_propagation_allowed,
+            ));
+        }
+    }
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!(errors.join(", ")))
+}
+
+fn validate_sub_path(mount: &VolumeMount, settings: &settings::Settings) -> Vec<String> {
+    let sub_paths = [
+        ("subPath", mount.sub_path.as_deref()),
+        ("subPathExpr", mount.sub_path_expr.as_deref()),
+    ];
+    let mut errors = vec![];
+    for (field, sub_path) in sub_paths {
+        let Some(sub_path) = sub_path.filter(|sub_path| !sub_path.is_empty()) else {
+            continue;
+        };
+        if settings.forbid_sub_path {
+            errors.push(format!("volumeMount {} cannot use {field}", mount.name));
+            continue;
+        }
+        // The variables of `subPathExpr` are expanded by the kubelet: the
+        // patterns match the expression itself. The patterns have been
+        // checked when validating the settings.
+        if !settings.allowed_sub_path_patterns.is_empty()
+            && !settings
+                .allowed_sub_path_patterns
+                .iter()
+                .any(|pattern| sub_path_regex(pattern).is_ok_and(|regex| regex.is_match(sub_path)))
+        {
+            errors.push(format!(
+                "volumeMount {} {field} '{sub_path}' does not match the allowed patterns",
+                mount.name
+            ));
+        }
+    }
+    errors
+}
+
+fn validate_mount_propagation(
+    mount: &VolumeMount,
+    bidirectional_propagation_allowed: bool,
+) -> Option<String> {
+    match mount.mount_propagation.as_deref().unwrap_or("None") {
+        "None" | "HostToContainer" => None,
+        "Bidirectional" if bidirectional_propagation_allowed => None,
+        propagation => Some(format!(
+            "volumeMount {} cannot use the {propagation} mountPropagation, use None or HostToContainer",
+            mount.name
+        )),
+    }
+}
+
+fn validate_volume_mounts_names(
+    volume_mounts: &[VolumeMount],
+    settings: &settings::Settings,
 ) -> Result<()> {
     let mut volume_mounts_names: HashSet<String> = HashSet::new();
     for mount in volume_mounts {
@@ -181,6 +356,38 @@ mod tests {
     use super::*;
 
     use kubewarden_policy_sdk::test::Testcase;
+    use mockall::automock;
+    use serial_test::serial;
+
+    #[automock]
+    pub mod kubernetes_sdk {
+        use kubewarden::host_capabilities::kubernetes::ListResourcesByNamespaceRequest;
+
+        #[allow(dead_code)]
+        pub fn list_resources_by_namespace<T>(
+            _req: &ListResourcesByNamespaceRequest,
+        ) -> anyhow::Result<k8s_openapi::List<T>>
+        where
+            T: k8s_openapi::ListableResource + serde::de::DeserializeOwned + Clone + 'static,
+        {
+            Err(anyhow::anyhow!("not mocked"))
+        }
+    }
+
+    /// uid of the DaemonSet owning the Pod of the fixture
+    const DAEMON_SET_UID: &str = "5f0c1e42-8d1b-4a8e-9b51-2c6a3f0d9e11";
+
+    fn daemon_set(uid: &str) -> DaemonSet {
+        DaemonSet {
+            metadata: ObjectMeta {
+                name: Some("ebs-csi-node".to_owned()),
+                namespace: Some("kube-system".to_owned()),
+                uid: Some(uid.to_owned()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
 
     #[test]
     fn accept_pod_no_volume_mounts_default_settings() -> Result<(), ()> {
@@ -206,6 +413,7 @@ mod tests {
             settings: Settings {
                 operator: settings::Reject::AnyIn,
                 volume_mounts_names: HashSet::from([String::from("test1")]),
+                ..Default::default()
             },
         };
 
@@ -226,6 +434,7 @@ mod tests {
                     String::from("test-var"),
                     String::from("test-data"),
                 ]),
+                ..Default::default()
             },
         };
 
@@ -253,6 +462,7 @@ container init-myservice2 is invalid: volumeMount names not allowed: [\"test-var
                     String::from("test-var-local-aaa"),
                     String::from("kube-api-access-kplj9"),
                 ]),
+                ..Default::default()
             },
         };
 
@@ -270,6 +480,7 @@ container init-myservice2 is invalid: volumeMount names not allowed: [\"test-var
             settings: Settings {
                 operator: settings::Reject::AnyNotIn,
                 volume_mounts_names: HashSet::from([String::from("unexistent")]),
+                ..Default::default()
             },
         };
 
@@ -297,6 +508,7 @@ container init-myservice2 is invalid: volumeMount names not allowed: [\"test-var
                     String::from("test-var"),
                     String::from("unexistent"),
                 ]),
+                ..Default::default()
             },
         };
 
@@ -317,6 +529,7 @@ container init-myservice2 is invalid: volumeMount names not allowed: [\"test-var
                     String::from("test-var"),
                     String::from("test-var-local-aaa"),
                 ]),
+                ..Default::default()
             },
         };
 
@@ -340,6 +553,7 @@ container init-myservice2 is invalid: volumeMount names not allowed: [\"test-var
                     String::from("test-var"),
                     String::from("test-var-local-aaa"),
                 ]),
+                ..Default::default()
             },
         };
 
@@ -360,6 +574,7 @@ container init-myservice2 is invalid: volumeMount names not allowed: [\"test-var
                     String::from("test-var"),
                     String::from("nonexistent"),
                 ]),
+                ..Default::default()
             },
         };
 
@@ -370,4 +585,181 @@ container init-myservice2 is invalid: volumeMount names not allowed: [\"test-var
         assert_eq!(expected, result.unwrap().message.unwrap());
         Ok(())
     }
+
+    fn sub_path_settings(forbid_sub_path: bool, allowed_sub_path_patterns: &[&str]) -> Settings {
+        Settings {
+            forbid_sub_path,
+            allowed_sub_path_patterns: allowed_sub_path_patterns
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn propagation_settings(daemon_sets: &[(&str, &str)]) -> Settings {
+        Settings {
+            restrict_mount_propagation: true,
+            bidirectional_propagation_daemon_sets: daemon_sets
+                .iter()
+                .map(|(namespace, name)| settings::DaemonSet {
+                    namespace: namespace.to_string(),
+                    name: name.to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reject_pod_with_forbidden_sub_path() -> Result<(), ()> {
+        let tc = Testcase {
+            name: String::from("forbidden subPath"),
+            fixture_file: String::from("test_data/pod_sub_path.json"),
+            expected_validation_result: false,
+            settings: sub_path_settings(true, &[]),
+        };
+
+        let result = tc.eval(validate);
+        let expected = "container app is invalid: volumeMount config cannot use subPath, volumeMount logs cannot use subPathExpr\n";
+        assert_eq!(expected, result.unwrap().message.unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_pod_with_sub_path_not_matching_patterns() -> Result<(), ()> {
+        let tc = Testcase {
+            name: String::from("subPath patterns reject"),
+            fixture_file: String::from("test_data/pod_sub_path.json"),
+            expected_validation_result: false,
+            settings: sub_path_settings(false, &["config/.*"]),
+        };
+
+        let result = tc.eval(validate);
+        let expected = "container app is invalid: volumeMount logs subPathExpr 'logs/$(POD_NAME)' does not match the allowed patterns\n";
+        assert_eq!(expected, result.unwrap().message.unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_with_sub_path_matching_patterns() -> Result<(), ()> {
+        let tc = Testcase {
+            name: String::from("subPath patterns"),
+            fixture_file: String::from("test_data/pod_sub_path.json"),
+            expected_validation_result: true,
+            settings: sub_path_settings(false, &["config/.*", r"logs/\$\(POD_NAME\)"]),
+        };
+
+        tc.eval(validate).unwrap();
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn accept_allowed_daemonset_with_bidirectional_propagation() -> Result<(), ()> {
+        let ctx = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx.expect::<DaemonSet>().times(1).returning(|req| {
+            if req.namespace != "kube-system"
+                || req.field_selector.as_deref() != Some("metadata.name=ebs-csi-node")
+            {
+                return Err(anyhow::anyhow!("it's not looking up the DaemonSet"));
+            }
+            Ok(k8s_openapi::List {
+                items: vec![daemon_set(DAEMON_SET_UID)],
+                ..Default::default()
+            })
+        });
+
+        for fixture in [
+            "test_data/daemonset_bidirectional.json",
+            "test_data/pod_daemonset_bidirectional.json",
+        ] {
+            let tc = Testcase {
+                name: String::from("allowed bidirectional propagation"),
+                fixture_file: String::from(fixture),
+                expected_validation_result: true,
+                settings: propagation_settings(&[("kube-system", "ebs-csi-node")]),
+            };
+
+            tc.eval(validate).unwrap();
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn reject_pod_with_bidirectional_propagation_not_owned_by_daemonset() -> Result<(), ()> {
+        for daemon_sets in [vec![], vec![daemon_set("another-uid")]] {
+            let ctx = mock_kubernetes_sdk::list_resources_by_namespace_context();
+            ctx.expect::<DaemonSet>().times(1).returning(move |_| {
+                Ok(k8s_openapi::List {
+                    items: daemon_sets.clone(),
+                    ..Default::default()
+                })
+            });
+
+            let tc = Testcase {
+                name: String::from("bidirectional propagation of a Pod not owned by the DaemonSet"),
+                fixture_file: String::from("test_data/pod_daemonset_bidirectional.json"),
+                expected_validation_result: false,
+                settings: propagation_settings(&[("kube-system", "ebs-csi-node")]),
+            };
+
+            tc.eval(validate).unwrap();
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn reject_pod_when_daemonset_lookup_fails() -> Result<(), ()> {
+        let ctx = mock_kubernetes_sdk::list_resources_by_namespace_context();
+        ctx.expect::<DaemonSet>()
+            .times(1)
+            .returning(|_| Err(anyhow::anyhow!("connection refused")));
+
+        let tc = Testcase {
+            name: String::from("DaemonSet lookup failure"),
+            fixture_file: String::from("test_data/pod_daemonset_bidirectional.json"),
+            expected_validation_result: false,
+            settings: propagation_settings(&[("kube-system", "ebs-csi-node")]),
+        };
+
+        let result = tc.eval(validate);
+        let expected = "cannot read the DaemonSet 'kube-system/ebs-csi-node': connection refused";
+        assert_eq!(expected, result.unwrap().message.unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_daemonset_with_bidirectional_propagation() -> Result<(), ()> {
+        let tc = Testcase {
+            name: String::from("bidirectional propagation reject"),
+            fixture_file: String::from("test_data/daemonset_bidirectional.json"),
+            expected_validation_result: false,
+            settings: propagation_settings(&[("kube-system", "csi-node")]),
+        };
+
+        let result = tc.eval(validate);
+        let expected = "container ebs-plugin is invalid: volumeMount kubelet-dir cannot use the Bidirectional mountPropagation, use None or HostToContainer\n";
+        assert_eq!(expected, result.unwrap().message.unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn accept_pod_without_sub_path_and_propagation() -> Result<(), ()> {
+        let tc = Testcase {
+            name: String::from("no subPath nor propagation"),
+            fixture_file: String::from("test_data/pod_creation_volume_mounts.json"),
+            expected_validation_result: true,
+            settings: Settings {
+                forbid_sub_path: true,
+                restrict_mount_propagation: true,
+                ..Default::default()
+            },
+        };
+
+        tc.eval(validate).unwrap();
+        Ok(())
+    }
 }
@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Default, Debug)]
@@ -8,6 +9,45 @@ use serde::{Deserialize, Serialize};
 pub(crate) struct Settings {
     pub operator: Reject,
     pub volume_mounts_names: HashSet<String>,
+    /// Reject the volumeMounts using `subPath` or `subPathExpr`
+    pub forbid_sub_path: bool,
+    /// Regular expressions the whole `subPath` and `subPathExpr` of the
+    /// volumeMounts must match. Ignored when empty.
+    pub allowed_sub_path_patterns: Vec<String>,
+    /// Restrict the `mountPropagation` of the volumeMounts to `None` and
+    /// `HostToContainer`
+    pub restrict_mount_propagation: bool,
+    /// DaemonSets, and their Pods, allowed to use the `Bidirectional`
+    /// mountPropagation when it is restricted
+    pub bidirectional_propagation_daemon_sets: HashSet<DaemonSet>,
+}
+
+/// DaemonSet allowed to use the `Bidirectional` mountPropagation, usually a
+/// CSI node plugin
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct DaemonSet {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl Settings {
+    /// Returns true when the name checks are configured
+    pub fn checks_names(&self) -> bool {
+        !self.volume_mounts_names.is_empty()
+    }
+
+    /// Returns true when the DaemonSet can use the `Bidirectional`
+    /// mountPropagation
+    pub fn allows_bidirectional_propagation(&self, namespace: &str, name: &str) -> bool {
+        self.bidirectional_propagation_daemon_sets
+            .iter()
+            .any(|daemon_set| daemon_set.namespace == namespace && daemon_set.name == name)
+    }
+}
+
+/// Compiles the pattern, which must match the whole path
+pub(crate) fn sub_path_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{pattern})$"))
 }
 
 #[derive(Serialize, Deserialize, Default, Debug)]
@@ -23,8 +63,41 @@ pub enum Reject {
 
 impl kubewarden::settings::Validatable for Settings {
     fn validate(&self) -> Result<(), String> {
-        if self.volume_mounts_names.is_empty() {
-            return Err("volumeMountsNames is empty".to_string());
+        if !self.checks_names()
+            && !self.forbid_sub_path
+            && self.allowed_sub_path_patterns.is_empty()
+            && !self.restrict_mount_propagation
+        {
+            return Err(
+                "one of volumeMountsNames, forbidSubPath, allowedSubPathPatterns or restrictMountPropagation must be set"
+                    .to_string(),
+            );
+        }
+        if self.forbid_sub_path && !self.allowed_sub_path_patterns.is_empty() {
+            return Err(
+                "forbidSubPath and allowedSubPathPatterns cannot be set at the same time"
+                    .to_string(),
+            );
+        }
+        for pattern in &self.allowed_sub_path_patterns {
+            sub_path_regex(pattern).map_err(|e| format!("invalid pattern '{pattern}': {e}"))?;
+        }
+        if !self.bidirectional_propagation_daemon_sets.is_empty()
+            && !self.restrict_mount_propagation
+        {
+            return Err(
+                "bidirectionalPropagationDaemonSets requires restrictMountPropagation".to_string(),
+            );
+        }
+        if self
+            .bidirectional_propagation_daemon_sets
+            .iter()
+            .any(|daemon_set| daemon_set.namespace.is_empty() || daemon_set.name.is_empty())
+        {
+            return Err(
+                "bidirectionalPropagationDaemonSets entries must have both a namespace and a name"
+                    .to_string(),
+            );
         }
         Ok(())
     }
@@ -75,4 +148,47 @@ volumeMountsNames:
         );
         Ok(())
     }
+
+    #[test]
+    fn test_policy_with_sub_path_and_propagation_settings() {
+        for payload in [
+            "forbidSubPath: true",
+            "allowedSubPathPatterns: ['config/.*']",
+            "restrictMountPropagation: true",
+            "
+restrictMountPropagation: true
+bidirectionalPropagationDaemonSets:
+  - namespace: kube-system
+    name: ebs-csi-node
+",
+        ] {
+            let settings = serde_yaml::from_str::<Settings>(payload).unwrap();
+            assert!(settings.validate().is_ok(), "{payload} should be valid");
+        }
+    }
+
+    #[test]
+    fn test_policy_with_invalid_sub_path_and_propagation_settings() {
+        for payload in [
+            "
+forbidSubPath: true
+allowedSubPathPatterns: ['config/.*']
+",
+            "allowedSubPathPatterns: ['config/[a-z']",
+            "
+bidirectionalPropagationDaemonSets:
+  - namespace: kube-system
+    name: ebs-csi-node
+",
+            "
+restrictMountPropagation: true
+bidirectionalPropagationDaemonSets:
+  - namespace: ''
+    name: ebs-csi-node
+",
+        ] {
+            let settings = serde_yaml::from_str::<Settings>(payload).unwrap();
+            assert!(settings.validate().is_err(), "{payload} should be invalid");
+        }
+    }
 }
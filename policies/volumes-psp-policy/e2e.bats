@@ -105,3 +105,44 @@
   [ "$status" -eq 0 ]
   [ $(expr "$output" : '.*allowed.*true') -ne 0 ]
 }
+
+@test "accept legacy volumes with allowed parameters" {
+  run kwctl run annotated-policy.wasm -r test_data/request-pod-legacy-volumes.json \
+    --settings-json \
+    '{ "allowedTypes": ["nfs", "iscsi", "cephfs", "glusterfs"], "volumeParameters": { "nfs": { "allowedServers": ["nfs.example.com"], "requireReadOnly": true }, "iscsi": { "allowedPortalCIDRs": ["10.0.0.0/24", "192.168.1.0/24"] } } }'
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  # request accepted
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*allowed.*true') -ne 0 ]
+}
+
+@test "reject legacy volumes with parameters not allowed" {
+  run kwctl run annotated-policy.wasm -r test_data/request-pod-legacy-volumes.json \
+    --settings-json \
+    '{ "allowedTypes": ["*"], "volumeParameters": { "iscsi": { "allowedPortalCIDRs": ["10.0.0.0/24"] }, "glusterfs": { "requireReadOnly": true } } }'
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  # request rejected
+  [ "$status" -eq 0 ]
+  [ $(expr "$output" : '.*allowed.*false') -ne 0 ]
+  [ $(expr "$output" : ".*volume 'iscsi-data' of type 'iscsi': the portal '192.168.1.10' is not in the allowed CIDRs.*") -ne 0 ]
+  [ $(expr "$output" : ".*volume 'glusterfs-data' of type 'glusterfs': readOnly must be set to true.*") -ne 0 ]
+}
+
+@test "reject invalid iSCSI portal CIDRs" {
+  run kwctl run annotated-policy.wasm -r test_data/request-pod-legacy-volumes.json \
+    --settings-json \
+    '{ "allowedTypes": ["iscsi"], "volumeParameters": { "iscsi": { "allowedPortalCIDRs": ["10.0.0.0/33"] } } }'
+
+  # this prints the output when one the checks below fails
+  echo "output = ${output}"
+
+  # request rejected by settings error
+  [ "$status" -eq 1 ]
+  [ $(expr "$output" : ".*Provided settings are not valid.*") -ne 0 ]
+}
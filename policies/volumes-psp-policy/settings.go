@@ -11,6 +11,7 @@ import (
 type Settings struct {
 	AllowedTypes                mapset.Set[string] `json:"allowedTypes"`
 	IgnoreInitContainersVolumes bool               `json:"ignoreInitContainersVolumes,omitempty"`
+	VolumeParameters            VolumeParameters   `json:"volumeParameters,omitempty"`
 }
 
 // Builds a new Settings instance starting from a validation
@@ -69,15 +70,16 @@ func (s *Settings) Valid() bool {
 	if s.AllowedTypes.Contains("*") && (s.AllowedTypes.Cardinality() != 1) {
 		return false
 	}
-	return true
+	return s.VolumeParameters.Valid()
 }
 
 func (s *Settings) UnmarshalJSON(data []byte) error {
 	// This is needed becaus golang-set v2.3.0 has a bug that prevents
 	// the correct unmarshalling of ThreadUnsafeSet types.
 	rawSettings := struct {
-		AllowedTypes                []string `json:"allowedTypes"`
-		IgnoreInitContainersVolumes bool     `json:"ignoreInitContainersVolumes,omitempty"`
+		AllowedTypes                []string         `json:"allowedTypes"`
+		IgnoreInitContainersVolumes bool             `json:"ignoreInitContainersVolumes,omitempty"`
+		VolumeParameters            VolumeParameters `json:"volumeParameters,omitempty"`
 	}{}
 
 	err := json.Unmarshal(data, &rawSettings)
@@ -87,6 +89,7 @@ func (s *Settings) UnmarshalJSON(data []byte) error {
 
 	s.AllowedTypes = mapset.NewThreadUnsafeSet[string](rawSettings.AllowedTypes...)
 	s.IgnoreInitContainersVolumes = rawSettings.IgnoreInitContainersVolumes
+	s.VolumeParameters = rawSettings.VolumeParameters
 
 	return nil
 }
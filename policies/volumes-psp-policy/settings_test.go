@@ -146,3 +146,71 @@ func TestParsingSettingsWithIgnoreInitContainersVolumesDefault(t *testing.T) {
 		t.Errorf("Expected IgnoreInitContainersVolumes to be false by default")
 	}
 }
+
+func TestParsingSettingsWithVolumeParameters(t *testing.T) {
+	request := `
+	{
+		"request": "doesn't matter here",
+		"settings": {
+			"allowedTypes": ["nfs", "iscsi"],
+			"volumeParameters": {
+				"nfs": {
+					"allowedServers": ["nfs.example.com"],
+					"requireReadOnly": true
+				},
+				"iscsi": {
+					"allowedPortalCIDRs": ["10.0.0.0/24"]
+				}
+			}
+		}
+	}
+	`
+	rawRequest := []byte(request)
+
+	settings, err := NewSettingsFromValidationReq(rawRequest)
+	if err != nil {
+		t.Errorf("Unexpected error %+v", err)
+	}
+
+	if settings.VolumeParameters.NFS == nil ||
+		len(settings.VolumeParameters.NFS.AllowedServers) != 1 ||
+		!settings.VolumeParameters.NFS.RequireReadOnly {
+		t.Errorf("Missing nfs parameters")
+	}
+
+	if settings.VolumeParameters.ISCSI == nil ||
+		len(settings.VolumeParameters.ISCSI.AllowedPortalCIDRs) != 1 {
+		t.Errorf("Missing iscsi parameters")
+	}
+
+	if settings.VolumeParameters.CephFS != nil || settings.VolumeParameters.Glusterfs != nil {
+		t.Errorf("Expected cephfs and glusterfs parameters to be unset")
+	}
+
+	if !settings.Valid() {
+		t.Errorf("Settings are reported as not valid")
+	}
+}
+
+func TestSettingsWithInvalidPortalCIDR(t *testing.T) {
+	request := `
+	{
+		"allowedTypes": ["iscsi"],
+		"volumeParameters": {
+			"iscsi": {
+				"allowedPortalCIDRs": ["10.0.0.0/33"]
+			}
+		}
+	}
+	`
+	rawRequest := []byte(request)
+
+	settings, err := NewSettingsFromValidateSettingsPayload(rawRequest)
+	if err != nil {
+		t.Error("Expected no error, got one")
+	}
+
+	if settings.Valid() {
+		t.Errorf("Expected Settings reported as not valid")
+	}
+}
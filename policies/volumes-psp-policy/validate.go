@@ -24,8 +24,9 @@ func validate(payload []byte) ([]byte, error) {
 			kubewarden.NoCode)
 	}
 
-	if (settings.AllowedTypes.Cardinality() == 1) &&
-		settings.AllowedTypes.Contains("*") {
+	allTypesAllowed := (settings.AllowedTypes.Cardinality() == 1) &&
+		settings.AllowedTypes.Contains("*")
+	if allTypesAllowed && settings.VolumeParameters.IsEmpty() {
 		// all volume types accepted
 		return kubewarden.AcceptRequest()
 	}
@@ -58,14 +59,16 @@ func validate(payload []byte) ([]byte, error) {
 	})
 
 	for _, volume := range volumes.Array() {
-		// obtain volumeName, volumeType:
+		// obtain volumeName, volumeType and volumeSource:
 		var volumeName, volumeType string
+		var volumeSource gjson.Result
 		volume.ForEach(func(key, value gjson.Result) bool {
 			if key.String() == "name" {
 				volumeName = value.String()
 			} else {
 				// must be the type
 				volumeType = key.String()
+				volumeSource = value
 			}
 			return true // keep iterating
 		})
@@ -80,9 +83,19 @@ func validate(payload []byte) ([]byte, error) {
 			}
 		}
 
-		if !settings.AllowedTypes.Contains(volumeType) {
-			errMsg := fmt.Sprintf("volume '%s' of type '%s' is not in the AllowedTypes list",
-				volumeName, volumeType)
+		errMsgs := []string{}
+		if !allTypesAllowed && !settings.AllowedTypes.Contains(volumeType) {
+			errMsgs = append(errMsgs,
+				fmt.Sprintf("volume '%s' of type '%s' is not in the AllowedTypes list",
+					volumeName, volumeType))
+		} else {
+			for _, violation := range settings.VolumeParameters.Violations(volumeType, volumeSource) {
+				errMsgs = append(errMsgs,
+					fmt.Sprintf("volume '%s' of type '%s': %s", volumeName, volumeType, violation))
+			}
+		}
+
+		for _, errMsg := range errMsgs {
 			if err == nil {
 				err = errors.New(errMsg)
 			} else {
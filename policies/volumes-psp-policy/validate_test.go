@@ -80,6 +80,34 @@ func TestApproval(t *testing.T) {
 				),
 			},
 		},
+		{
+			name:     "legacy volumes with allowed parameters",
+			testData: "test_data/request-pod-legacy-volumes.json",
+			settings: Settings{
+				AllowedTypes: mapset.NewThreadUnsafeSet(
+					"nfs",
+					"iscsi",
+					"cephfs",
+					"glusterfs",
+				),
+				VolumeParameters: VolumeParameters{
+					NFS: &NFSParameters{
+						AllowedServers:  []string{"nfs.example.com"},
+						RequireReadOnly: true,
+					},
+					ISCSI: &ISCSIParameters{
+						AllowedPortalCIDRs: []string{"10.0.0.0/24", "192.168.1.0/24"},
+						RequireReadOnly:    true,
+					},
+					CephFS: &CephFSParameters{
+						AllowedMonitors: []string{"10.16.154.78:6789", "10.16.154.82:6789"},
+					},
+					Glusterfs: &GlusterfsParameters{
+						AllowedEndpoints: []string{"glusterfs-cluster"},
+					},
+				},
+			},
+		},
 	} {
 		payload, err := kubewarden_testing.BuildValidationRequestFromFixture(
 			tcase.testData,
@@ -129,6 +157,44 @@ func TestRejection(t *testing.T) {
 				" volume 'test-var-local-aaa' of type 'hostPath' is not in the AllowedTypes list;" +
 				" volume 'kube-api-access-kplj9' of type 'projected' is not in the AllowedTypes list",
 		},
+		{
+			name:     "legacy volumes with parameters not allowed",
+			testData: "test_data/request-pod-legacy-volumes.json",
+			settings: Settings{
+				AllowedTypes: mapset.NewThreadUnsafeSet[string]("*"),
+				VolumeParameters: VolumeParameters{
+					NFS: &NFSParameters{
+						AllowedServers: []string{"nfs.internal"},
+					},
+					ISCSI: &ISCSIParameters{
+						AllowedPortalCIDRs: []string{"10.0.0.0/24"},
+					},
+					CephFS: &CephFSParameters{
+						AllowedMonitors: []string{"10.16.154.78:6789"},
+					},
+					Glusterfs: &GlusterfsParameters{
+						RequireReadOnly: true,
+					},
+				},
+			},
+			error: "volume 'nfs-data' of type 'nfs': the server 'nfs.example.com' is not allowed;" +
+				" volume 'iscsi-data' of type 'iscsi': the portal '192.168.1.10' is not in the allowed CIDRs;" +
+				" volume 'cephfs-data' of type 'cephfs': the monitor '10.16.154.82:6789' is not allowed;" +
+				" volume 'glusterfs-data' of type 'glusterfs': readOnly must be set to true",
+		},
+		{
+			name:     "legacy volume type not allowed",
+			testData: "test_data/request-pod-legacy-volumes.json",
+			settings: Settings{
+				AllowedTypes: mapset.NewThreadUnsafeSet[string]("nfs", "iscsi", "cephfs"),
+				VolumeParameters: VolumeParameters{
+					Glusterfs: &GlusterfsParameters{
+						AllowedEndpoints: []string{"glusterfs-cluster"},
+					},
+				},
+			},
+			error: "volume 'glusterfs-data' of type 'glusterfs' is not in the AllowedTypes list",
+		},
 	} {
 		payload, err := kubewarden_testing.BuildValidationRequestFromFixture(
 			tcase.testData,
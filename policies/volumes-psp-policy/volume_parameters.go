@@ -0,0 +1,149 @@
+package main
+
+import (
+	"fmt"
+	"net/netip"
+	"slices"
+
+	"github.com/kubewarden/gjson"
+)
+
+// Parameters allowed for the legacy in-tree volume types. A type without
+// parameters is only checked against the AllowedTypes list.
+type VolumeParameters struct {
+	NFS       *NFSParameters       `json:"nfs,omitempty"`
+	ISCSI     *ISCSIParameters     `json:"iscsi,omitempty"`
+	CephFS    *CephFSParameters    `json:"cephfs,omitempty"`
+	Glusterfs *GlusterfsParameters `json:"glusterfs,omitempty"`
+}
+
+type NFSParameters struct {
+	// Servers the volumes can use. Any server is allowed when empty.
+	AllowedServers  []string `json:"allowedServers,omitempty"`
+	RequireReadOnly bool     `json:"requireReadOnly,omitempty"`
+}
+
+type ISCSIParameters struct {
+	// CIDRs the target portals of the volumes must be part of. Any portal
+	// is allowed when empty.
+	AllowedPortalCIDRs []string `json:"allowedPortalCIDRs,omitempty"`
+	RequireReadOnly    bool     `json:"requireReadOnly,omitempty"`
+}
+
+type CephFSParameters struct {
+	// Monitors the volumes can use, written like in the volumes. Any
+	// monitor is allowed when empty.
+	AllowedMonitors []string `json:"allowedMonitors,omitempty"`
+	RequireReadOnly bool     `json:"requireReadOnly,omitempty"`
+}
+
+type GlusterfsParameters struct {
+	// Endpoints objects the volumes can use. Any Endpoints object is allowed
+	// when empty.
+	AllowedEndpoints []string `json:"allowedEndpoints,omitempty"`
+	RequireReadOnly  bool     `json:"requireReadOnly,omitempty"`
+}
+
+func (p *VolumeParameters) IsEmpty() bool {
+	return p.NFS == nil && p.ISCSI == nil && p.CephFS == nil && p.Glusterfs == nil
+}
+
+func (p *VolumeParameters) Valid() bool {
+	if p.ISCSI == nil {
+		return true
+	}
+	for _, cidr := range p.ISCSI.AllowedPortalCIDRs {
+		if _, err := netip.ParsePrefix(cidr); err != nil {
+			return false
+		}
+	}
+	return true
+}
+
+// Returns the reasons why the parameters of the volume, of the given type,
+// are not allowed
+func (p *VolumeParameters) Violations(volumeType string, source gjson.Result) []string {
+	violations := []string{}
+	requireReadOnly := false
+
+	switch volumeType {
+	case "nfs":
+		if p.NFS == nil {
+			return violations
+		}
+		requireReadOnly = p.NFS.RequireReadOnly
+		server := source.Get("server").String()
+		if len(p.NFS.AllowedServers) > 0 && !slices.Contains(p.NFS.AllowedServers, server) {
+			violations = append(violations,
+				fmt.Sprintf("the server '%s' is not allowed", server))
+		}
+	case "iscsi":
+		if p.ISCSI == nil {
+			return violations
+		}
+		requireReadOnly = p.ISCSI.RequireReadOnly
+		if len(p.ISCSI.AllowedPortalCIDRs) > 0 {
+			portals := []string{source.Get("targetPortal").String()}
+			for _, portal := range source.Get("portals").Array() {
+				portals = append(portals, portal.String())
+			}
+			for _, portal := range portals {
+				if !p.ISCSI.isPortalAllowed(portal) {
+					violations = append(violations,
+						fmt.Sprintf("the portal '%s' is not in the allowed CIDRs", portal))
+				}
+			}
+		}
+	case "cephfs":
+		if p.CephFS == nil {
+			return violations
+		}
+		requireReadOnly = p.CephFS.RequireReadOnly
+		if len(p.CephFS.AllowedMonitors) > 0 {
+			for _, monitor := range source.Get("monitors").Array() {
+				if !slices.Contains(p.CephFS.AllowedMonitors, monitor.String()) {
+					violations = append(violations,
+						fmt.Sprintf("the monitor '%s' is not allowed", monitor.String()))
+				}
+			}
+		}
+	case "glusterfs":
+		if p.Glusterfs == nil {
+			return violations
+		}
+		requireReadOnly = p.Glusterfs.RequireReadOnly
+		endpoints := source.Get("endpoints").String()
+		if len(p.Glusterfs.AllowedEndpoints) > 0 &&
+			!slices.Contains(p.Glusterfs.AllowedEndpoints, endpoints) {
+			violations = append(violations,
+				fmt.Sprintf("the endpoints '%s' are not allowed", endpoints))
+		}
+	default:
+		return violations
+	}
+
+	if requireReadOnly && !source.Get("readOnly").Bool() {
+		violations = append(violations, "readOnly must be set to true")
+	}
+	return violations
+}
+
+// The portals are IP addresses, with an optional port
+func (p *ISCSIParameters) isPortalAllowed(portal string) bool {
+	addr, err := netip.ParseAddr(portal)
+	if err != nil {
+		addrPort, err := netip.ParseAddrPort(portal)
+		if err != nil {
+			// host names cannot be matched against CIDRs
+			return false
+		}
+		addr = addrPort.Addr()
+	}
+	for _, cidr := range p.AllowedPortalCIDRs {
+		prefix, err := netip.ParsePrefix(cidr)
+		if err == nil && prefix.Contains(addr) {
+			return true
+		}
+	}
+	return false
+}
@@ -0,0 +1,53 @@
+#!/usr/bin/env bats
+
+@test "Accept a hardened ValidatingWebhookConfiguration" {
+	run kwctl run --request-path test_data/validating_webhook_hardened.json annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject an insecure MutatingWebhookConfiguration" {
+	run kwctl run --request-path test_data/mutating_webhook_insecure.json annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*webhook 'inject.sidecar.example.com': failurePolicy must be Fail, got 'Ignore'.*") -ne 0 ]
+	[ $(expr "$output" : ".*namespaceSelector must exclude the kube-system namespace.*") -ne 0 ]
+}
+
+@test "Accept an insecure MutatingWebhookConfiguration when the checks are disabled" {
+	run kwctl run --request-path test_data/mutating_webhook_insecure.json --settings-json '{"requireFailClosed": false, "requireNoSideEffects": false, "maxTimeoutSeconds": null, "requireCaBundle": false, "requireKubeSystemExcluded": false}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":true.*') -ne 0 ]
+}
+
+@test "Reject a webhook with a timeout above the maximum" {
+	run kwctl run --request-path test_data/validating_webhook_hardened.json --settings-json '{"maxTimeoutSeconds": 3}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -eq 0 ]
+	[ $(expr "$output" : '.*"allowed":false.*') -ne 0 ]
+	[ $(expr "$output" : ".*timeoutSeconds 5 is greater than the maximum of 3.*") -ne 0 ]
+}
+
+@test "Reject invalid settings" {
+	run kwctl run --request-path test_data/validating_webhook_hardened.json --settings-json '{"maxTimeoutSeconds": 60}' annotated-policy.wasm
+
+	# this prints the output when one the checks below fails
+	echo "output = ${output}"
+
+	[ "$status" -ne 0 ]
+}
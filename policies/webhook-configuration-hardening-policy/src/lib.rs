@@ -0,0 +1,312 @@
+use guest::prelude::*;
+use kubewarden_policy_sdk::wapc_guest as guest;
+
+use k8s_openapi::Resource;
+use k8s_openapi::api::admissionregistration::v1::{
+    MutatingWebhook, MutatingWebhookConfiguration, ValidatingWebhook,
+    ValidatingWebhookConfiguration, WebhookClientConfig,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+
+extern crate kubewarden_policy_sdk as kubewarden;
+use kubewarden::{protocol_version_guest, request::ValidationRequest, validate_settings};
+
+mod settings;
+use settings::{DEFAULT_TIMEOUT_SECONDS, Settings};
+
+/// Label set by Kubernetes on every namespace, holding its name
+const NAMESPACE_NAME_LABEL: &str = "kubernetes.io/metadata.name";
+const KUBE_SYSTEM_NAMESPACE: &str = "kube-system";
+
+#[unsafe(no_mangle)]
+pub extern "C" fn wapc_init() {
+    register_function("validate", validate);
+    register_function("validate_settings", validate_settings::<Settings>);
+    register_function("protocol_version", protocol_version_guest);
+}
+
+// Fields shared by the validating and the mutating webhooks
+struct Webhook<'a> {
+    name: &'a str,
+    failure_policy: Option<&'a str>,
+    side_effects: &'a str,
+    timeout_seconds: Option<i32>,
+    client_config: &'a WebhookClientConfig,
+    namespace_selector: Option<&'a LabelSelector>,
+}
+
+impl<'a> From<&'a ValidatingWebhook> for Webhook<'a> {
+    fn from(webhook: &'a ValidatingWebhook) -> Self {
+        Webhook {
+            name: &webhook.name,
+            failure_policy: webhook.failure_policy.as_deref(),
+            side_effects: &webhook.side_effects,
+            timeout_seconds: webhook.timeout_seconds,
+            client_config: &webhook.client_config,
+            namespace_selector: webhook.namespace_selector.as_ref(),
+        }
+    }
+}
+
+impl<'a> From<&'a MutatingWebhook> for Webhook<'a> {
+    fn from(webhook: &'a MutatingWebhook) -> Self {
+        Webhook {
+            name: &webhook.name,
+            failure_policy: webhook.failure_policy.as_deref(),
+            side_effects: &webhook.side_effects,
+            timeout_seconds: webhook.timeout_seconds,
+            client_config: &webhook.client_config,
+            namespace_selector: webhook.namespace_selector.as_ref(),
+        }
+    }
+}
+
+fn validate(payload: &[u8]) -> CallResult {
+    let validation_request: ValidationRequest<Settings> = ValidationRequest::new(payload)?;
+    let request = validation_request.request;
+    let settings = &validation_request.settings;
+
+    let (name, errors) = match request.kind.kind.as_str() {
+        ValidatingWebhookConfiguration::KIND => {
+            let cfg: ValidatingWebhookConfiguration = serde_json::from_value(request.object)?;
+            let errors =
+                validate_webhooks(cfg.webhooks.iter().flatten().map(Webhook::from), settings);
+            (cfg.metadata.name, errors)
+        }
+        MutatingWebhookConfiguration::KIND => {
+            let cfg: MutatingWebhookConfiguration = serde_json::from_value(request.object)?;
+            let errors =
+                validate_webhooks(cfg.webhooks.iter().flatten().map(Webhook::from), settings);
+            (cfg.metadata.name, errors)
+        }
+        _ => return kubewarden::accept_request(),
+    };
+
+    if errors.is_empty() {
+        return kubewarden::accept_request();
+    }
+    kubewarden::reject_request(
+        Some(format!(
+            "{} '{}' is not allowed: {}",
+            request.kind.kind,
+            name.unwrap_or_default(),
+            errors.join("; ")
+        )),
+        None,
+        None,
+        None,
+    )
+}
+
+// Returns, for each webhook not allowed, the reasons why
+fn validate_webhooks<'a>(
+    webhooks: impl Iterator<Item = Webhook<'a>>,
+    settings: &Settings,
+) -> Vec<String> {
+    webhooks
+        .filter_map(|webhook| {
+            let errors = validate_webhook(&webhook, settings);
+            (!errors.is_empty())
+                .then(|| format!("webhook '{}': {}", webhook.name, errors.join(", ")))
+        })
+        .collect()
+}
+
+// Returns the reasons why the webhook is not allowed
+fn validate_webhook(webhook: &Webhook, settings: &Settings) -> Vec<String> {
+    let mut errors = Vec::new();
+    // Kubernetes defaults failurePolicy to Fail
+    if settings.require_fail_closed && webhook.failure_policy == Some("Ignore") {
+        errors.push("failurePolicy must be Fail, got 'Ignore'".to_owned());
+    }
+    if settings.require_no_side_effects && !matches!(webhook.side_effects, "None" | "NoneOnDryRun")
+    {
+        errors.push(format!(
+            "sideEffects must be None or NoneOnDryRun, got '{}'",
+            webhook.side_effects
+        ));
+    }
+    if let Some(max_timeout_seconds) = settings.max_timeout_seconds {
+        let timeout_seconds = webhook.timeout_seconds.unwrap_or(DEFAULT_TIMEOUT_SECONDS);
+        if timeout_seconds > max_timeout_seconds {
+            errors.push(format!(
+                "timeoutSeconds {timeout_seconds} is greater than the maximum of {max_timeout_seconds}"
+            ));
+        }
+    }
+    if settings.require_ca_bundle
+        && webhook
+            .client_config
+            .ca_bundle
+            .as_ref()
+            .is_none_or(|ca_bundle| ca_bundle.0.is_empty())
+    {
+        errors.push("clientConfig must contain a caBundle".to_owned());
+    }
+    if settings.require_kube_system_excluded
+        && selects_namespace(webhook.namespace_selector, KUBE_SYSTEM_NAMESPACE)
+    {
+        errors.push(format!(
+            "namespaceSelector must exclude the {KUBE_SYSTEM_NAMESPACE} namespace"
+        ));
+    }
+    errors
+}
+
+// Returns true when the selector matches the namespace, relying on the name
+// label Kubernetes sets on every namespace. A missing selector matches all
+// the namespaces.
+fn selects_namespace(selector: Option<&LabelSelector>, namespace: &str) -> bool {
+    let Some(selector) = selector else {
+        return true;
+    };
+    let label = |key: &str| (key == NAMESPACE_NAME_LABEL).then_some(namespace);
+
+    let labels_match = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .all(|(key, value)| label(key) == Some(value.as_str()));
+    let expressions_match = selector.match_expressions.iter().flatten().all(|expr| {
+        let values = expr.values.as_deref().unwrap_or_default();
+        let value = label(&expr.key);
+        match expr.operator.as_str() {
+            "In" => value.is_some_and(|value| values.iter().any(|v| v == value)),
+            "NotIn" => value.is_none_or(|value| values.iter().all(|v| v != value)),
+            "Exists" => value.is_some(),
+            "DoesNotExist" => value.is_none(),
+            _ => true,
+        }
+    });
+    labels_match && expressions_match
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelectorRequirement;
+    use kubewarden_policy_sdk::test::Testcase;
+    use rstest::rstest;
+
+    fn expression(key: &str, operator: &str, values: &[&str]) -> LabelSelectorRequirement {
+        LabelSelectorRequirement {
+            key: key.to_owned(),
+            operator: operator.to_owned(),
+            values: Some(values.iter().map(|v| v.to_string()).collect()),
+        }
+    }
+
+    #[rstest]
+    #[case::no_selector(None, true)]
+    #[case::empty_selector(Some(LabelSelector::default()), true)]
+    #[case::not_in(
+        Some(LabelSelector {
+            match_expressions: Some(vec![expression(NAMESPACE_NAME_LABEL, "NotIn", &["kube-system", "kube-public"])]),
+            ..Default::default()
+        }),
+        false
+    )]
+    #[case::not_in_other_namespaces(
+        Some(LabelSelector {
+            match_expressions: Some(vec![expression(NAMESPACE_NAME_LABEL, "NotIn", &["kube-public"])]),
+            ..Default::default()
+        }),
+        true
+    )]
+    #[case::in_other_namespaces(
+        Some(LabelSelector {
+            match_expressions: Some(vec![expression(NAMESPACE_NAME_LABEL, "In", &["default"])]),
+            ..Default::default()
+        }),
+        false
+    )]
+    #[case::opt_in_label(
+        Some(LabelSelector {
+            match_expressions: Some(vec![expression("example.com/webhook", "Exists", &[])]),
+            ..Default::default()
+        }),
+        false
+    )]
+    #[case::opt_out_label(
+        Some(LabelSelector {
+            match_expressions: Some(vec![expression("example.com/webhook", "DoesNotExist", &[])]),
+            ..Default::default()
+        }),
+        true
+    )]
+    #[case::match_labels(
+        Some(LabelSelector {
+            match_labels: Some(BTreeMap::from([(NAMESPACE_NAME_LABEL.to_owned(), "kube-system".to_owned())])),
+            ..Default::default()
+        }),
+        true
+    )]
+    fn kube_system_selected(#[case] selector: Option<LabelSelector>, #[case] expected: bool) {
+        assert_eq!(
+            selects_namespace(selector.as_ref(), KUBE_SYSTEM_NAMESPACE),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::hardened(
+        "test_data/validating_webhook_hardened.json",
+        Settings::default(),
+        true
+    )]
+    #[case::insecure("test_data/mutating_webhook_insecure.json", Settings::default(), false)]
+    #[case::insecure_checks_disabled(
+        "test_data/mutating_webhook_insecure.json",
+        Settings {
+            require_fail_closed: false,
+            require_no_side_effects: false,
+            max_timeout_seconds: None,
+            require_ca_bundle: false,
+            require_kube_system_excluded: false,
+        },
+        true
+    )]
+    #[case::timeout_above_maximum(
+        "test_data/validating_webhook_hardened.json",
+        Settings {
+            max_timeout_seconds: Some(3),
+            ..Default::default()
+        },
+        false
+    )]
+    fn validate_request(
+        #[case] fixture: &str,
+        #[case] settings: Settings,
+        #[case] expected_validation_result: bool,
+    ) {
+        let test_case = Testcase {
+            name: fixture.to_owned(),
+            fixture_file: fixture.to_owned(),
+            expected_validation_result,
+            settings,
+        };
+
+        test_case.eval(validate).expect("validation failed");
+    }
+
+    #[test]
+    fn rejection_message() {
+        let test_case = Testcase {
+            name: "rejection message".to_owned(),
+            fixture_file: "test_data/mutating_webhook_insecure.json".to_owned(),
+            expected_validation_result: false,
+            settings: Settings::default(),
+        };
+
+        let response = test_case.eval(validate).expect("validation failed");
+        assert_eq!(
+            response.message.as_deref(),
+            Some(
+                "MutatingWebhookConfiguration 'sidecar-injector' is not allowed: webhook 'inject.sidecar.example.com': failurePolicy must be Fail, got 'Ignore', sideEffects must be None or NoneOnDryRun, got 'Some', timeoutSeconds 30 is greater than the maximum of 10, clientConfig must contain a caBundle, namespaceSelector must exclude the kube-system namespace"
+            )
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+/// Timeout used by Kubernetes when `timeoutSeconds` is not set
+pub(crate) const DEFAULT_TIMEOUT_SECONDS: i32 = 10;
+
+/// Maximum timeout accepted by Kubernetes
+const MAX_TIMEOUT_SECONDS: i32 = 30;
+
+// Describe the settings your policy expects when
+// loaded by the policy server.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Settings {
+    /// The webhooks must not set `failurePolicy` to `Ignore`, which admits
+    /// the requests unchecked when the webhook is not reachable
+    pub require_fail_closed: bool,
+    /// The webhooks must declare `sideEffects` as `None` or `NoneOnDryRun`
+    pub require_no_side_effects: bool,
+    /// Maximum `timeoutSeconds` of the webhooks. When not set, the timeout
+    /// is not checked.
+    pub max_timeout_seconds: Option<i32>,
+    /// The webhooks must provide a `caBundle` to verify the TLS certificate
+    /// of the server
+    pub require_ca_bundle: bool,
+    /// The `namespaceSelector` of the webhooks must exclude the `kube-system`
+    /// namespace
+    pub require_kube_system_excluded: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            require_fail_closed: true,
+            require_no_side_effects: true,
+            max_timeout_seconds: Some(DEFAULT_TIMEOUT_SECONDS),
+            require_ca_bundle: true,
+            require_kube_system_excluded: true,
+        }
+    }
+}
+
+impl kubewarden::settings::Validatable for Settings {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(max_timeout_seconds) = self.max_timeout_seconds
+            && !(1..=MAX_TIMEOUT_SECONDS).contains(&max_timeout_seconds)
+        {
+            return Err(format!(
+                "maxTimeoutSeconds must be between 1 and {MAX_TIMEOUT_SECONDS}, got {max_timeout_seconds}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use kubewarden_policy_sdk::settings::Validatable;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::defaults(r#"{}"#, true)]
+    #[case::toggles(
+        r#"{"requireFailClosed": false, "requireNoSideEffects": false, "requireCaBundle": false, "requireKubeSystemExcluded": false}"#,
+        true
+    )]
+    #[case::max_timeout(r#"{"maxTimeoutSeconds": 30}"#, true)]
+    #[case::timeout_not_checked(r#"{"maxTimeoutSeconds": null}"#, true)]
+    #[case::zero_max_timeout(r#"{"maxTimeoutSeconds": 0}"#, false)]
+    #[case::max_timeout_too_long(r#"{"maxTimeoutSeconds": 60}"#, false)]
+    fn validate_settings(#[case] settings: &str, #[case] is_valid: bool) {
+        let settings: Settings = serde_json::from_str(settings).expect("cannot parse settings");
+        assert_eq!(settings.validate().is_ok(), is_valid);
+    }
+}